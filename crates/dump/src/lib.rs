@@ -6,7 +6,9 @@ use meilisearch_types::error::ResponseError;
 use meilisearch_types::keys::Key;
 use meilisearch_types::milli::update::IndexDocumentsMethod;
 use meilisearch_types::settings::Unchecked;
-use meilisearch_types::tasks::{Details, IndexSwap, KindWithContent, Status, Task, TaskId};
+use meilisearch_types::tasks::{
+    Details, IndexSwap, KindWithContent, Priority, Status, Task, TaskId, TransactionOperation,
+};
 use meilisearch_types::InstanceUid;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
@@ -64,6 +66,9 @@ pub struct TaskDump {
     #[serde(default)]
     pub index_uid: Option<String>,
     pub status: Status,
+    // The tasks dumped before the introduction of the priorities are all `Normal`.
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
     #[serde(rename = "type")]
     pub kind: KindDump,
 
@@ -89,6 +94,18 @@ pub struct TaskDump {
         default
     )]
     pub finished_at: Option<OffsetDateTime>,
+    // The tasks dumped before the introduction of the scheduled tasks can run at any time.
+    #[serde(
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub run_after: Option<OffsetDateTime>,
+    // The tasks dumped before the introduction of the custom metadata and idempotency keys have none.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub custom_metadata: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub idempotency_key: Option<String>,
 }
 
 // A `Kind` specific version made for the dump. If modified you may break the dump.
@@ -144,6 +161,11 @@ pub enum KindDump {
     UpgradeDatabase {
         from: (u32, u32, u32),
     },
+    // The documents of every document addition of the transaction are dumped one after the
+    // other in the content file of the task.
+    Transaction {
+        operations: Vec<TransactionOperation>,
+    },
 }
 
 impl From<Task> for TaskDump {
@@ -153,6 +175,7 @@ impl From<Task> for TaskDump {
             batch_uid: task.batch_uid,
             index_uid: task.index_uid().map(|uid| uid.to_string()),
             status: task.status,
+            priority: task.priority,
             kind: task.kind.into(),
             canceled_by: task.canceled_by,
             details: task.details,
@@ -160,6 +183,9 @@ impl From<Task> for TaskDump {
             enqueued_at: task.enqueued_at,
             started_at: task.started_at,
             finished_at: task.finished_at,
+            run_after: task.run_after,
+            custom_metadata: task.custom_metadata,
+            idempotency_key: task.idempotency_key,
         }
     }
 }
@@ -216,6 +242,7 @@ impl From<KindWithContent> for KindDump {
             KindWithContent::UpgradeDatabase { from: version } => {
                 KindDump::UpgradeDatabase { from: version }
             }
+            KindWithContent::Transaction { operations } => KindDump::Transaction { operations },
         }
     }
 }
@@ -290,6 +317,7 @@ pub(crate) mod test {
             separator_tokens: Setting::NotSet,
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_sets: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
@@ -305,6 +333,8 @@ pub(crate) mod test {
             localized_attributes: Setting::NotSet,
             facet_search: Setting::NotSet,
             prefix_search: Setting::NotSet,
+            pinning: Setting::NotSet,
+            reranker: Setting::NotSet,
             _kind: std::marker::PhantomData,
         };
         settings.check()
@@ -344,6 +374,7 @@ pub(crate) mod test {
                     batch_uid: Some(0),
                     index_uid: Some(S("doggo")),
                     status: Status::Succeeded,
+                    priority: Priority::Normal,
                     kind: KindDump::DocumentImport {
                         method: milli::update::IndexDocumentsMethod::UpdateDocuments,
                         allow_index_creation: true,
@@ -359,6 +390,9 @@ pub(crate) mod test {
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
                     started_at: Some(datetime!(2022-11-20 0:00 UTC)),
                     finished_at: Some(datetime!(2022-11-21 0:00 UTC)),
+                    run_after: None,
+                    custom_metadata: None,
+                    idempotency_key: None,
                 },
                 None,
             ),
@@ -368,6 +402,7 @@ pub(crate) mod test {
                     batch_uid: None,
                     index_uid: Some(S("doggo")),
                     status: Status::Enqueued,
+                    priority: Priority::Normal,
                     kind: KindDump::DocumentImport {
                         method: milli::update::IndexDocumentsMethod::UpdateDocuments,
                         allow_index_creation: true,
@@ -383,6 +418,9 @@ pub(crate) mod test {
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
                     started_at: None,
                     finished_at: None,
+                    run_after: None,
+                    custom_metadata: None,
+                    idempotency_key: None,
                 },
                 Some(vec![
                     json!({ "id": 4, "race": "leonberg" }).as_object().unwrap().clone(),
@@ -395,6 +433,7 @@ pub(crate) mod test {
                     batch_uid: None,
                     index_uid: Some(S("catto")),
                     status: Status::Enqueued,
+                    priority: Priority::Normal,
                     kind: KindDump::IndexDeletion,
                     canceled_by: None,
                     details: None,
@@ -402,6 +441,9 @@ pub(crate) mod test {
                     enqueued_at: datetime!(2022-11-15 0:00 UTC),
                     started_at: None,
                     finished_at: None,
+                    run_after: None,
+                    custom_metadata: None,
+                    idempotency_key: None,
                 },
                 None,
             ),
@@ -78,6 +78,7 @@ impl CompatV5ToV6 {
                         v5::Status::Succeeded => v6::Status::Succeeded,
                         v5::Status::Failed => v6::Status::Failed,
                     },
+                    priority: v6::Priority::Normal,
                     kind: match task.content {
                         v5::tasks::TaskContent::IndexCreation { primary_key, .. } => {
                             v6::Kind::IndexCreation { primary_key }
@@ -159,6 +160,9 @@ impl CompatV5ToV6 {
                     enqueued_at: task_view.enqueued_at,
                     started_at: task_view.started_at,
                     finished_at: task_view.finished_at,
+                    run_after: None,
+                    custom_metadata: None,
+                    idempotency_key: None,
                 };
 
                 (task, content_file)
@@ -358,6 +362,7 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             separator_tokens: v6::Setting::NotSet,
             dictionary: v6::Setting::NotSet,
             synonyms: settings.synonyms.into(),
+            synonym_sets: v6::Setting::NotSet,
             distinct_attribute: settings.distinct_attribute.into(),
             proximity_precision: v6::Setting::NotSet,
             typo_tolerance: match settings.typo_tolerance {
@@ -397,6 +402,8 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             search_cutoff_ms: v6::Setting::NotSet,
             facet_search: v6::Setting::NotSet,
             prefix_search: v6::Setting::NotSet,
+            pinning: v6::Setting::NotSet,
+            reranker: v6::Setting::NotSet,
             _kind: std::marker::PhantomData,
         }
     }
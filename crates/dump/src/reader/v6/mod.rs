@@ -27,6 +27,7 @@ pub type Network = meilisearch_types::features::Network;
 // ===== Other types to clarify the code of the compat module
 // everything related to the tasks
 pub type Status = meilisearch_types::tasks::Status;
+pub type Priority = meilisearch_types::tasks::Priority;
 pub type Kind = crate::KindDump;
 pub type Details = meilisearch_types::tasks::Details;
 
@@ -28,6 +28,8 @@ pub enum Condition<'a> {
     Between { from: Token<'a>, to: Token<'a> },
     Contains { keyword: Token<'a>, word: Token<'a> },
     StartsWith { keyword: Token<'a>, word: Token<'a> },
+    Matches { keyword: Token<'a>, pattern: Token<'a> },
+    Like { keyword: Token<'a>, pattern: Token<'a> },
 }
 
 impl Condition<'_> {
@@ -45,6 +47,8 @@ impl Condition<'_> {
             Condition::Between { .. } => "TO",
             Condition::Contains { .. } => "CONTAINS",
             Condition::StartsWith { .. } => "STARTS WITH",
+            Condition::Matches { .. } => "MATCHES",
+            Condition::Like { .. } => "LIKE",
         }
     }
 }
@@ -169,6 +173,61 @@ pub fn parse_not_starts_with(input: Span) -> IResult<FilterCondition> {
     ))
 }
 
+/// matches        = value "MATCHES" value
+pub fn parse_matches(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, matches, value)) =
+        tuple((parse_value, tag("MATCHES"), cut(parse_value)))(input)?;
+    Ok((
+        input,
+        FilterCondition::Condition {
+            fid,
+            op: Matches { keyword: Token { span: matches, value: None }, pattern: value },
+        },
+    ))
+}
+
+/// matches        = value "NOT" WS+ "MATCHES" value
+pub fn parse_not_matches(input: Span) -> IResult<FilterCondition> {
+    let keyword = tuple((tag("NOT"), multispace1, tag("MATCHES")));
+    let (input, (fid, (_not, _spaces, matches), value)) =
+        tuple((parse_value, keyword, cut(parse_value)))(input)?;
+
+    Ok((
+        input,
+        FilterCondition::Not(Box::new(FilterCondition::Condition {
+            fid,
+            op: Matches { keyword: Token { span: matches, value: None }, pattern: value },
+        })),
+    ))
+}
+
+/// like           = value "LIKE" value
+pub fn parse_like(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, like, value)) = tuple((parse_value, tag("LIKE"), cut(parse_value)))(input)?;
+    Ok((
+        input,
+        FilterCondition::Condition {
+            fid,
+            op: Like { keyword: Token { span: like, value: None }, pattern: value },
+        },
+    ))
+}
+
+/// like           = value "NOT" WS+ "LIKE" value
+pub fn parse_not_like(input: Span) -> IResult<FilterCondition> {
+    let keyword = tuple((tag("NOT"), multispace1, tag("LIKE")));
+    let (input, (fid, (_not, _spaces, like), value)) =
+        tuple((parse_value, keyword, cut(parse_value)))(input)?;
+
+    Ok((
+        input,
+        FilterCondition::Not(Box::new(FilterCondition::Condition {
+            fid,
+            op: Like { keyword: Token { span: like, value: None }, pattern: value },
+        })),
+    ))
+}
+
 /// to             = value value "TO" WS+ value
 pub fn parse_to(input: Span) -> IResult<FilterCondition> {
     let (input, (key, from, _, _, to)) =
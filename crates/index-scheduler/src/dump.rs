@@ -5,7 +5,9 @@ use dump::{KindDump, TaskDump, UpdateFile};
 use meilisearch_types::batches::{Batch, BatchId};
 use meilisearch_types::heed::RwTxn;
 use meilisearch_types::milli;
-use meilisearch_types::tasks::{Kind, KindWithContent, Status, Task};
+use meilisearch_types::tasks::{
+    Kind, KindWithContent, Priority, Status, Task, TransactionOperation,
+};
 use roaring::RoaringBitmap;
 use uuid::Uuid;
 
@@ -20,6 +22,8 @@ pub struct Dump<'a> {
     indexes: HashMap<String, RoaringBitmap>,
     statuses: HashMap<Status, RoaringBitmap>,
     kinds: HashMap<Kind, RoaringBitmap>,
+    priorities: HashMap<Priority, RoaringBitmap>,
+    custom_metadata: HashMap<String, RoaringBitmap>,
 
     batch_indexes: HashMap<String, RoaringBitmap>,
     batch_statuses: HashMap<Status, RoaringBitmap>,
@@ -38,6 +42,8 @@ impl<'a> Dump<'a> {
             indexes: HashMap::new(),
             statuses: HashMap::new(),
             kinds: HashMap::new(),
+            priorities: HashMap::new(),
+            custom_metadata: HashMap::new(),
             batch_indexes: HashMap::new(),
             batch_statuses: HashMap::new(),
             batch_kinds: HashMap::new(),
@@ -105,8 +111,13 @@ impl<'a> Dump<'a> {
     pub fn register_dumped_task(
         &mut self,
         task: TaskDump,
-        content_file: Option<Box<UpdateFile>>,
+        mut content_file: Option<Box<UpdateFile>>,
     ) -> Result<Task> {
+        // The documents of a transaction are split between its document additions below.
+        let transaction_documents = match task.kind {
+            KindDump::Transaction { .. } => content_file.take(),
+            _ => None,
+        };
         let task_has_no_docs = matches!(task.kind, KindDump::DocumentImport { documents_count, .. } if documents_count == 0);
 
         let content_uuid = match content_file {
@@ -142,10 +153,14 @@ impl<'a> Dump<'a> {
             enqueued_at: task.enqueued_at,
             started_at: task.started_at,
             finished_at: task.finished_at,
+            run_after: task.run_after,
             error: task.error,
             canceled_by: task.canceled_by,
             details: task.details,
             status: task.status,
+            priority: task.priority,
+            custom_metadata: task.custom_metadata,
+            idempotency_key: task.idempotency_key,
             kind: match task.kind {
                 KindDump::DocumentImport {
                     primary_key,
@@ -212,6 +227,37 @@ impl<'a> Dump<'a> {
                 }
                 KindDump::SnapshotCreation => KindWithContent::SnapshotCreation,
                 KindDump::UpgradeDatabase { from } => KindWithContent::UpgradeDatabase { from },
+                KindDump::Transaction { mut operations } => {
+                    if task.status == Status::Enqueued {
+                        let mut documents = transaction_documents.into_iter().flatten();
+                        for operation in &mut operations {
+                            let TransactionOperation::DocumentAdditionOrUpdate {
+                                content_file,
+                                documents_count,
+                                ..
+                            } = operation
+                            else {
+                                continue;
+                            };
+                            let (uuid, file) =
+                                self.index_scheduler.queue.create_update_file(false)?;
+                            let mut writer = io::BufWriter::new(file);
+                            for doc in documents.by_ref().take(*documents_count as usize) {
+                                let doc = doc?;
+                                serde_json::to_writer(&mut writer, &doc).map_err(|e| {
+                                    Error::from_milli(
+                                        milli::InternalError::SerdeJson(e).into(),
+                                        None,
+                                    )
+                                })?;
+                            }
+                            let file = writer.into_inner().map_err(|e| e.into_error())?;
+                            file.persist()?;
+                            *content_file = uuid;
+                        }
+                    }
+                    KindWithContent::Transaction { operations }
+                }
             },
         };
 
@@ -239,6 +285,14 @@ impl<'a> Dump<'a> {
             task.enqueued_at,
             task.uid,
         )?;
+        if let Some(run_after) = task.run_after {
+            utils::insert_task_datetime(
+                &mut self.wtxn,
+                self.index_scheduler.queue.tasks.run_after,
+                run_after,
+                task.uid,
+            )?;
+        }
 
         // we can't override the started_at & finished_at, so we must only set it if the tasks is finished and won't change
         if matches!(task.status, Status::Succeeded | Status::Failed | Status::Canceled) {
@@ -262,6 +316,14 @@ impl<'a> Dump<'a> {
 
         self.statuses.entry(task.status).or_default().insert(task.uid);
         self.kinds.entry(task.kind.as_kind()).or_default().insert(task.uid);
+        if !task.priority.is_normal() {
+            self.priorities.entry(task.priority).or_default().insert(task.uid);
+        }
+        if let Some(custom_metadata) = &task.custom_metadata {
+            self.custom_metadata.entry(custom_metadata.clone()).or_default().insert(task.uid);
+        }
+        // The API key a task was registered with is not dumped, the imported keys are only scoped by index.
+        self.index_scheduler.queue.tasks.put_idempotency_key(&mut self.wtxn, &task, None)?;
 
         Ok(task)
     }
@@ -285,6 +347,16 @@ impl<'a> Dump<'a> {
         for (kind, bitmap) in self.kinds {
             self.index_scheduler.queue.tasks.put_kind(&mut self.wtxn, kind, &bitmap)?;
         }
+        for (priority, bitmap) in self.priorities {
+            self.index_scheduler.queue.tasks.put_priority(&mut self.wtxn, priority, &bitmap)?;
+        }
+        for (custom_metadata, bitmap) in self.custom_metadata {
+            self.index_scheduler.queue.tasks.custom_metadata.put(
+                &mut self.wtxn,
+                &custom_metadata,
+                &bitmap,
+            )?;
+        }
 
         for (index, bitmap) in self.batch_indexes {
             self.index_scheduler.queue.batches.index_tasks.put(&mut self.wtxn, &index, &bitmap)?;
@@ -51,6 +51,8 @@ pub enum Error {
     WithCustomErrorCode(Code, Box<Self>),
     #[error("Received bad task id: {received} should be >= to {expected}.")]
     BadTaskId { received: TaskId, expected: TaskId },
+    #[error("The idempotency key `{idempotency_key}` was already used to register the task `{task_uid}` of another type or on other indexes.")]
+    IdempotencyKeyAlreadyUsed { idempotency_key: String, task_uid: TaskId },
     #[error("Index `{0}` not found.")]
     IndexNotFound(String),
     #[error("Index `{0}` already exists.")]
@@ -113,9 +115,9 @@ pub enum Error {
     TaskFileNotFound(TaskId),
     #[error("Batch `{0}` not found.")]
     BatchNotFound(BatchId),
-    #[error("Query parameters to filter the tasks to delete are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`.")]
+    #[error("Query parameters to filter the tasks to delete are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`, `beforeRunAfter`, `afterRunAfter`.")]
     TaskDeletionWithEmptyQuery,
-    #[error("Query parameters to filter the tasks to cancel are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`.")]
+    #[error("Query parameters to filter the tasks to cancel are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`, `beforeRunAfter`, `afterRunAfter`.")]
     TaskCancelationWithEmptyQuery,
     #[error("Aborted task")]
     AbortedTask,
@@ -176,6 +178,7 @@ impl Error {
             Error::IndexNotFound(_)
             | Error::WithCustomErrorCode(_, _)
             | Error::BadTaskId { .. }
+            | Error::IdempotencyKeyAlreadyUsed { .. }
             | Error::IndexAlreadyExists(_)
             | Error::SwapDuplicateIndexFound(_)
             | Error::SwapDuplicateIndexesFound(_)
@@ -239,6 +242,7 @@ impl ErrorCode for Error {
         match self {
             Error::WithCustomErrorCode(code, _) => *code,
             Error::BadTaskId { .. } => Code::BadRequest,
+            Error::IdempotencyKeyAlreadyUsed { .. } => Code::IdempotencyKeyAlreadyUsed,
             Error::IndexNotFound(_) => Code::IndexNotFound,
             Error::IndexAlreadyExists(_) => Code::IndexAlreadyExists,
             Error::SwapDuplicateIndexesFound(_) => Code::InvalidSwapDuplicateIndexFound,
@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
@@ -77,9 +78,11 @@ pub struct IndexMapper {
     enable_mdb_writemap: bool,
     pub indexer_config: Arc<IndexerConfig>,
 
-    /// A few types of long running batches of tasks that act on a single index set this field
+    /// A few types of long running batches of tasks that act on a single index insert it in this map
     /// so that a handle to the index is available from other threads (search) in an optimized manner.
-    currently_updating_index: Arc<RwLock<Option<(String, Index)>>>,
+    ///
+    /// There are several of them when batches targeting different indexes are processed concurrently.
+    currently_updating_indexes: Arc<RwLock<HashMap<String, Index>>>,
 }
 
 /// Whether the index is available for use or is forbidden to be inserted back in the index map
@@ -118,6 +121,10 @@ pub struct IndexStats {
     /// Number of embedded documents in the index.
     /// Option: retrocompatible with the stats of the pre-v1.13.0 versions of meilisearch
     pub number_of_embedded_documents: Option<u64>,
+    /// Size taken by the embeddings of each embedder, in bytes.
+    /// Option: retrocompatible with the stats of the previous versions of meilisearch
+    #[serde(default)]
+    pub embedder_storage_sizes: Option<BTreeMap<String, u64>>,
     /// Size taken by the used pages of the index' DB, in bytes.
     ///
     /// As the DB backend does not return to the disk the pages that are not currently used by the DB,
@@ -146,6 +153,7 @@ impl IndexStats {
         Ok(IndexStats {
             number_of_embeddings: Some(arroy_stats.number_of_embeddings),
             number_of_embedded_documents: Some(arroy_stats.documents.len()),
+            embedder_storage_sizes: Some(index.embedder_storage_sizes(rtxn)?),
             documents_database_stats: index.documents_stats(rtxn)?.unwrap_or_default(),
             number_of_documents: None,
             database_size: index.on_disk_size()?,
@@ -178,7 +186,7 @@ impl IndexMapper {
             index_growth_amount: options.index_growth_amount,
             enable_mdb_writemap: options.enable_mdb_writemap,
             indexer_config: options.indexer_config.clone(),
-            currently_updating_index: Default::default(),
+            currently_updating_indexes: Default::default(),
         })
     }
 
@@ -342,12 +350,8 @@ impl IndexMapper {
 
     /// Return an index, may open it if it wasn't already opened.
     pub fn index(&self, rtxn: &RoTxn, name: &str) -> Result<Index> {
-        if let Some((current_name, current_index)) =
-            self.currently_updating_index.read().unwrap().as_ref()
-        {
-            if current_name == name {
-                return Ok(current_index.clone());
-            }
+        if let Some(current_index) = self.currently_updating_indexes.read().unwrap().get(name) {
+            return Ok(current_index.clone());
         }
 
         let uuid = self
@@ -528,7 +532,11 @@ impl IndexMapper {
         &self.indexer_config
     }
 
-    pub fn set_currently_updating_index(&self, index: Option<(String, Index)>) {
-        *self.currently_updating_index.write().unwrap() = index;
+    pub fn insert_currently_updating_index(&self, name: String, index: Index) {
+        self.currently_updating_indexes.write().unwrap().insert(name, index);
+    }
+
+    pub fn remove_currently_updating_index(&self, name: &str) {
+        self.currently_updating_indexes.write().unwrap().remove(name);
     }
 }
@@ -52,14 +52,11 @@ pub fn snapshot_index_scheduler(scheduler: &IndexScheduler) -> String {
 
     let processing = processing_tasks.read().unwrap().clone();
     snap.push_str(&format!("### Autobatching Enabled = {}\n", scheduler.autobatching_enabled));
-    snap.push_str(&format!(
-        "### Processing batch {:?}:\n",
-        processing.batch.as_ref().map(|batch| batch.uid)
-    ));
+    snap.push_str(&format!("### Processing batch {:?}:\n", processing.batches.keys().next()));
     snap.push_str(&snapshot_bitmap(&processing.processing));
-    if let Some(ref batch) = processing.batch {
+    for current in processing.batches.values() {
         snap.push('\n');
-        snap.push_str(&snapshot_batch(&batch.to_batch()));
+        snap.push_str(&snapshot_batch(&current.batch.to_batch()));
     }
     snap.push_str("\n----------------------------------------------------------------------\n");
 
@@ -209,10 +206,14 @@ pub fn snapshot_task(task: &Task) -> String {
         enqueued_at: _,
         started_at: _,
         finished_at: _,
+        run_after,
         error,
         canceled_by,
         details,
         status,
+        priority,
+        custom_metadata,
+        idempotency_key,
         kind,
     } = task;
     snap.push('{');
@@ -221,6 +222,18 @@ pub fn snapshot_task(task: &Task) -> String {
         snap.push_str(&format!("batch_uid: {batch_uid}, "));
     }
     snap.push_str(&format!("status: {status}, "));
+    if !priority.is_normal() {
+        snap.push_str(&format!("priority: {priority}, "));
+    }
+    if let Some(run_after) = run_after {
+        snap.push_str(&format!("run_after: {run_after}, "));
+    }
+    if let Some(custom_metadata) = custom_metadata {
+        snap.push_str(&format!("custom_metadata: {custom_metadata:?}, "));
+    }
+    if let Some(idempotency_key) = idempotency_key {
+        snap.push_str(&format!("idempotency_key: {idempotency_key:?}, "));
+    }
     if let Some(canceled_by) = canceled_by {
         snap.push_str(&format!("canceled_by: {canceled_by}, "));
     }
@@ -294,6 +307,20 @@ fn snapshot_details(d: &Details) -> String {
         Details::UpgradeDatabase { from, to } => {
             format!("{{ from: {from:?}, to: {to:?} }}")
         }
+        Details::Transaction { operations } => {
+            let operations: Vec<_> = operations
+                .iter()
+                .map(|op| {
+                    format!(
+                        "{{ index_uid: {:?}, kind: {:?}, details: {} }}",
+                        op.index_uid,
+                        op.kind,
+                        snapshot_details(&op.details)
+                    )
+                })
+                .collect();
+            format!("{{ operations: [{}] }}", operations.join(", "))
+        }
     }
 }
 
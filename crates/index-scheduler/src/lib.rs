@@ -11,6 +11,7 @@ scheduler waits to be woken up to process new tasks. It wakes up when:
 1. it is launched for the first time
 2. a new task is registered
 3. a batch of tasks has been processed
+4. a task scheduled to run later becomes eligible
 
 It is only within this thread that the scheduler is allowed to process tasks.
 On the other hand, the publicly accessible methods of the scheduler can be
@@ -38,7 +39,7 @@ pub mod versioning;
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 pub type TaskId = u32;
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::{self, BufReader, Read};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
@@ -57,20 +58,22 @@ use meilisearch_types::heed::types::I128;
 use meilisearch_types::heed::{self, Env, RoTxn, WithoutTls};
 use meilisearch_types::milli::index::IndexEmbeddingConfig;
 use meilisearch_types::milli::update::IndexerConfig;
+use meilisearch_types::milli::vector::persistent_cache::PersistentEmbeddingCache;
 use meilisearch_types::milli::vector::{Embedder, EmbedderOptions, EmbeddingConfigs};
 use meilisearch_types::milli::{self, Index};
 use meilisearch_types::task_view::TaskView;
-use meilisearch_types::tasks::{KindWithContent, Task};
+use meilisearch_types::tasks::{KindWithContent, Priority, Task};
 use processing::ProcessingTasks;
 pub use queue::Query;
 use queue::Queue;
 use roaring::RoaringBitmap;
 use scheduler::Scheduler;
 use time::OffsetDateTime;
+use uuid::Uuid;
 use versioning::Versioning;
 
 use crate::index_mapper::IndexMapper;
-use crate::utils::clamp_to_page_size;
+pub use crate::utils::clamp_to_page_size;
 
 pub(crate) type BEI128 = I128<BE>;
 
@@ -121,6 +124,10 @@ pub struct IndexSchedulerOptions {
     /// If the autobatcher is allowed to automatically batch tasks
     /// it will only batch this defined maximum size (in bytes) of tasks at once.
     pub batched_tasks_size_limit: u64,
+    /// The maximum number of batches processed at the same time.
+    ///
+    /// Only the batches targeting different indexes are processed concurrently.
+    pub max_concurrent_batches: usize,
     /// The experimental features enabled for this instance.
     pub instance_features: InstanceTogglableFeatures,
     /// The experimental features enabled for this instance.
@@ -131,6 +138,31 @@ pub struct IndexSchedulerOptions {
     pub embedding_cache_cap: usize,
 }
 
+/// The maximum length of an idempotency key, in bytes.
+///
+/// An idempotency key is stored in an LMDB key of at most 511 bytes, followed by a `\0`
+/// separator and the 64 hexadecimal characters of the hash of its scope.
+pub const MAX_IDEMPOTENCY_KEY_BYTES: usize = 511 - 1 - 64;
+
+/// The options a task can be registered with.
+#[derive(Debug, Default, Clone)]
+pub struct RegisterOptions {
+    /// The uid to give to the task, it must be greater than the uid of every registered task.
+    pub task_id: Option<TaskId>,
+    /// The priority with which the task will be batched.
+    pub priority: Priority,
+    /// The date before which the task must not be processed.
+    pub run_after: Option<OffsetDateTime>,
+    /// An arbitrary string stored along with the task.
+    pub custom_metadata: Option<String>,
+    /// The key identifying the request, the task already registered with it is returned instead.
+    pub idempotency_key: Option<String>,
+    /// The API key used to register the task, the idempotency keys of different API keys don't conflict.
+    pub api_key_uid: Option<Uuid>,
+    /// Whether the task must only be validated and not registered.
+    pub dry_run: bool,
+}
+
 /// Structure which holds meilisearch's indexes and schedules the tasks
 /// to be performed on them.
 pub struct IndexScheduler {
@@ -392,7 +424,17 @@ impl IndexScheduler {
                     let ret = catch_unwind(AssertUnwindSafe(|| run.tick()));
                     match ret {
                         Ok(Ok(TickOutcome::TickAgain(_))) => (),
-                        Ok(Ok(TickOutcome::WaitForSignal)) => run.scheduler.wake_up.wait(),
+                        Ok(Ok(TickOutcome::WaitForSignal)) => match run.next_scheduled_task_delay() {
+                            // Wake up by ourselves when the next scheduled task becomes eligible.
+                            Ok(Some(delay)) => {
+                                run.scheduler.wake_up.wait_timeout(delay);
+                            }
+                            Ok(None) => run.scheduler.wake_up.wait(),
+                            Err(e) => {
+                                tracing::error!("{e}");
+                                run.scheduler.wake_up.wait();
+                            }
+                        },
                         Ok(Ok(TickOutcome::StopProcessingForever)) => break,
                         Ok(Err(e)) => {
                             tracing::error!("{e}");
@@ -411,10 +453,23 @@ impl IndexScheduler {
             .unwrap();
     }
 
+    /// Returns how long we can wait before the next task scheduled to run later becomes eligible.
+    fn next_scheduled_task_delay(&self) -> Result<Option<Duration>> {
+        let rtxn = self.read_txn()?;
+        let now = OffsetDateTime::now_utc();
+        let run_after = self.queue.tasks.next_run_after(&rtxn, now)?;
+        Ok(run_after.map(|run_after| (run_after - now).unsigned_abs()))
+    }
+
     pub fn indexer_config(&self) -> &IndexerConfig {
         &self.index_mapper.indexer_config
     }
 
+    /// The cache of the embeddings stored on disk, if enabled.
+    pub fn embedding_cache(&self) -> Option<&PersistentEmbeddingCache> {
+        self.indexer_config().embedding_cache.as_ref()
+    }
+
     /// Return the real database size (i.e.: The size **with** the free pages)
     pub fn size(&self) -> Result<u64> {
         Ok(self.env.real_disk_size()?)
@@ -624,6 +679,17 @@ impl IndexScheduler {
         kind: KindWithContent,
         task_id: Option<TaskId>,
         dry_run: bool,
+    ) -> Result<Task> {
+        self.register_with_options(kind, RegisterOptions { task_id, dry_run, ..Default::default() })
+    }
+
+    /// Register a new task in the scheduler with the given options.
+    ///
+    /// If it fails and data was associated with the task, it tries to delete the associated data.
+    pub fn register_with_options(
+        &self,
+        kind: KindWithContent,
+        options: RegisterOptions,
     ) -> Result<Task> {
         // if the task doesn't delete anything and 50% of the task queue is full, we must refuse to enqueue the incomming task
         if !matches!(&kind, KindWithContent::TaskDeletion { tasks, .. } if !tasks.is_empty())
@@ -633,15 +699,45 @@ impl IndexScheduler {
         }
 
         let mut wtxn = self.env.write_txn()?;
-        let task = self.queue.register(&mut wtxn, &kind, task_id, dry_run)?;
 
-        // If the registered task is a task cancelation
-        // we inform the processing tasks to stop (if necessary).
+        // A request submitted again with the same idempotency key gets its original task back.
+        if let Some(idempotency_key) = &options.idempotency_key {
+            if let Some(task_id) = self.queue.tasks.task_with_idempotency_key(
+                &wtxn,
+                idempotency_key,
+                &kind,
+                options.api_key_uid,
+            )? {
+                let task =
+                    self.queue.tasks.get_task(&wtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
+                // The key identifies a request, it can't be reused for a different operation.
+                // The indexes of a swap or a transaction are not listed in a stable order.
+                let same_indexes = task.indexes().into_iter().collect::<BTreeSet<_>>()
+                    == kind.indexes().into_iter().collect::<BTreeSet<_>>();
+                if task.kind.as_kind() != kind.as_kind() || !same_indexes {
+                    return Err(Error::IdempotencyKeyAlreadyUsed {
+                        idempotency_key: idempotency_key.clone(),
+                        task_uid: task.uid,
+                    });
+                }
+                // The content sent along with the duplicated request will never be used.
+                if !options.dry_run {
+                    for content_file in kind.content_uuids() {
+                        self.queue.delete_update_file(content_file)?;
+                    }
+                }
+                return Ok(task);
+            }
+        }
+
+        let task = self.queue.register(&mut wtxn, &kind, &options)?;
+
+        // If the registered task is a task cancelation that can run right away
+        // we inform the batches processing the canceled tasks to stop (if necessary).
         if let KindWithContent::TaskCancelation { tasks, .. } = kind {
-            let tasks_to_cancel = RoaringBitmap::from_iter(tasks);
-            if self.processing_tasks.read().unwrap().must_cancel_processing_tasks(&tasks_to_cancel)
-            {
-                self.scheduler.must_stop_processing.must_stop();
+            if task.run_after.is_none_or(|run_after| run_after <= OffsetDateTime::now_utc()) {
+                let tasks_to_cancel = RoaringBitmap::from_iter(tasks);
+                self.processing_tasks.read().unwrap().cancel_processing_tasks(&tasks_to_cancel);
             }
         }
 
@@ -805,39 +901,56 @@ impl IndexScheduler {
             .map(
                 |IndexEmbeddingConfig {
                      name,
-                     config: milli::vector::EmbeddingConfig { embedder_options, prompt, quantized },
+                     config:
+                         milli::vector::EmbeddingConfig {
+                             embedder_options,
+                             prompt,
+                             quantized,
+                             quantization,
+                             distance,
+                         },
                      ..
                  }| {
-                    let prompt = Arc::new(
-                        prompt
-                            .try_into()
-                            .map_err(meilisearch_types::milli::Error::from)
-                            .map_err(|err| Error::from_milli(err, Some(index_uid.clone())))?,
-                    );
+                    let prompt: milli::prompt::Prompt = prompt
+                        .try_into()
+                        .map_err(meilisearch_types::milli::Error::from)
+                        .map_err(|err| Error::from_milli(err, Some(index_uid.clone())))?;
+                    let media = embedder_options
+                        .document_media(self.indexer_config().embedding_media_path.as_deref());
+                    let prompt = Arc::new(prompt.with_media(media));
+                    let quantization =
+                        milli::vector::VectorQuantization::from_options(quantization, quantized);
                     // optimistically return existing embedder
                     {
                         let embedders = self.embedders.read().unwrap();
                         if let Some(embedder) = embedders.get(&embedder_options) {
                             return Ok((
                                 name,
-                                (embedder.clone(), prompt, quantized.unwrap_or_default()),
+                                (
+                                    embedder.clone(),
+                                    prompt,
+                                    quantization,
+                                    distance.unwrap_or_default(),
+                                ),
                             ));
                         }
                     }
 
                     // add missing embedder
                     let embedder = Arc::new(
-                        Embedder::new(embedder_options.clone(), self.scheduler.embedding_cache_cap)
-                            .map_err(meilisearch_types::milli::vector::Error::from)
-                            .map_err(|err| {
-                                Error::from_milli(err.into(), Some(index_uid.clone()))
-                            })?,
+                        Embedder::new(
+                            embedder_options.clone(),
+                            self.scheduler.embedding_cache_cap,
+                            self.embedding_cache(),
+                        )
+                        .map_err(meilisearch_types::milli::vector::Error::from)
+                        .map_err(|err| Error::from_milli(err.into(), Some(index_uid.clone())))?,
                     );
                     {
                         let mut embedders = self.embedders.write().unwrap();
                         embedders.insert(embedder_options, embedder.clone());
                     }
-                    Ok((name, (embedder, prompt, quantized.unwrap_or_default())))
+                    Ok((name, (embedder, prompt, quantization, distance.unwrap_or_default())))
                 },
             )
             .collect();
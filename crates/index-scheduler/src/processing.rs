@@ -1,18 +1,36 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use meilisearch_types::batches::BatchId;
 use meilisearch_types::milli::progress::{AtomicSubStep, NamedStep, Progress, ProgressView};
 use meilisearch_types::milli::{make_atomic_progress, make_enum_progress};
-use roaring::RoaringBitmap;
+use roaring::{MultiOps, RoaringBitmap};
 
+use crate::scheduler::MustStopProcessing;
 use crate::utils::ProcessingBatch;
 
 #[derive(Clone, Default)]
 pub struct ProcessingTasks {
-    pub batch: Option<Arc<ProcessingBatch>>,
-    /// The list of tasks ids that are currently running.
+    /// The batches that are currently processing, by batch uid.
+    pub batches: BTreeMap<BatchId, CurrentBatch>,
+    /// The list of tasks ids that are currently running, in all the batches.
     pub processing: Arc<RoaringBitmap>,
-    /// The progress on processing tasks
-    pub progress: Option<Progress>,
+}
+
+/// A batch that is currently processing.
+#[derive(Clone)]
+pub struct CurrentBatch {
+    pub batch: Arc<ProcessingBatch>,
+    /// The list of tasks ids of this batch.
+    pub processing: Arc<RoaringBitmap>,
+    /// The progress on the tasks of this batch.
+    pub progress: Progress,
+    /// Set to stop the processing of this batch only.
+    must_stop_processing: MustStopProcessing,
+    /// Whether the processing of this batch was aborted.
+    ///
+    /// An aborted batch is still displayed as processing until the next batch starts.
+    aborted: bool,
 }
 
 impl ProcessingTasks {
@@ -21,39 +39,86 @@ impl ProcessingTasks {
         ProcessingTasks::default()
     }
 
-    pub fn get_progress_view(&self) -> Option<ProgressView> {
-        Some(self.progress.as_ref()?.as_progress_view())
+    /// Returns the batch with the given uid if it is currently processing.
+    pub fn batch(&self, batch_uid: BatchId) -> Option<&ProcessingBatch> {
+        self.batches.get(&batch_uid).map(|current| &*current.batch)
+    }
+
+    /// Returns the batch containing the given task if it is currently processing.
+    pub fn batch_of_task(&self, task_uid: u32) -> Option<&ProcessingBatch> {
+        self.batches
+            .values()
+            .find(|current| current.processing.contains(task_uid))
+            .map(|current| &*current.batch)
+    }
+
+    /// The uids of the batches that are currently processing.
+    pub fn batch_uids(&self) -> RoaringBitmap {
+        self.batches.keys().copied().collect()
+    }
+
+    pub fn get_progress_view(&self, batch_uid: BatchId) -> Option<ProgressView> {
+        Some(self.batches.get(&batch_uid)?.progress.as_progress_view())
     }
 
-    /// Stores the currently processing tasks, and the date time at which it started.
+    /// The batches that are currently running, ignoring the aborted ones.
+    pub fn running_batches(&self) -> impl Iterator<Item = &CurrentBatch> {
+        self.batches.values().filter(|current| !current.aborted)
+    }
+
+    /// Stores the currently processing tasks of a batch, and the date time at which it started.
+    ///
+    /// The batches that were aborted are removed.
     pub fn start_processing(
         &mut self,
         processing_batch: ProcessingBatch,
         processing: RoaringBitmap,
+        must_stop_processing: MustStopProcessing,
     ) -> Progress {
-        self.batch = Some(Arc::new(processing_batch));
-        self.processing = Arc::new(processing);
+        self.batches.retain(|_, current| !current.aborted);
         let progress = Progress::default();
         progress.update_progress(BatchProgress::ProcessingTasks);
-        self.progress = Some(progress.clone());
+        self.batches.insert(
+            processing_batch.uid,
+            CurrentBatch {
+                batch: Arc::new(processing_batch),
+                processing: Arc::new(processing),
+                progress: progress.clone(),
+                must_stop_processing,
+                aborted: false,
+            },
+        );
+        self.update_processing();
 
         progress
     }
 
-    /// Set the processing tasks to an empty list
-    pub fn stop_processing(&mut self) -> Self {
-        self.progress = None;
+    /// Removes the batch from the processing ones.
+    pub fn stop_processing(&mut self, batch_uid: BatchId) -> Option<CurrentBatch> {
+        let current = self.batches.remove(&batch_uid);
+        self.update_processing();
+        current
+    }
+
+    /// Marks the batch as aborted, it will be removed when the next batch starts processing.
+    pub fn abort_processing(&mut self, batch_uid: BatchId) {
+        if let Some(current) = self.batches.get_mut(&batch_uid) {
+            current.aborted = true;
+        }
+    }
 
-        Self {
-            batch: std::mem::take(&mut self.batch),
-            processing: std::mem::take(&mut self.processing),
-            progress: None,
+    /// Stops the batches containing at least one of the canceled tasks.
+    pub fn cancel_processing_tasks(&self, canceled_tasks: &RoaringBitmap) {
+        for current in self.batches.values() {
+            if !current.processing.is_disjoint(canceled_tasks) {
+                current.must_stop_processing.must_stop();
+            }
         }
     }
 
-    /// Returns `true` if there, at least, is one task that is currently processing that we must stop.
-    pub fn must_cancel_processing_tasks(&self, canceled_tasks: &RoaringBitmap) -> bool {
-        !self.processing.is_disjoint(canceled_tasks)
+    fn update_processing(&mut self) {
+        let processing = self.batches.values().map(|current| &*current.processing).union();
+        self.processing = Arc::new(processing);
     }
 }
 
@@ -136,6 +201,15 @@ make_enum_progress! {
     }
 }
 
+make_enum_progress! {
+    pub enum TransactionProgress {
+        EnsuringTheIndexesExist,
+        ApplyingTheOperations,
+        SwappingTheIndexes,
+        CommittingTheChanges,
+    }
+}
+
 make_enum_progress! {
     pub enum DocumentOperationProgress {
         RetrievingConfig,
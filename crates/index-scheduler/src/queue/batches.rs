@@ -7,7 +7,6 @@ use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn, WithoutTls};
 use meilisearch_types::milli::{CboRoaringBitmapCodec, RoaringBitmapCodec, BEU32};
 use meilisearch_types::tasks::{Kind, Status};
 use roaring::{MultiOps, RoaringBitmap};
-use time::OffsetDateTime;
 
 use super::{Query, Queue};
 use crate::processing::ProcessingTasks;
@@ -279,9 +278,9 @@ impl BatchQueue {
         tasks
             .into_iter()
             .map(|batch_id| {
-                if Some(batch_id) == processing.batch.as_ref().map(|batch| batch.uid) {
-                    let mut batch = processing.batch.as_ref().unwrap().to_batch();
-                    batch.progress = processing.get_progress_view();
+                if let Some(processing_batch) = processing.batch(batch_id) {
+                    let mut batch = processing_batch.to_batch();
+                    batch.progress = processing.get_progress_view(batch_id);
                     Ok(batch)
                 } else {
                     self.get_batch(rtxn, batch_id)
@@ -308,20 +307,27 @@ impl Queue {
             batch_uids,
             statuses,
             types,
+            // Batches are not indexed by priority.
+            priorities: _,
             index_uids,
             canceled_by,
+            // Batches are not indexed by custom metadata nor idempotency key.
+            custom_metadata: _,
+            idempotency_keys: _,
             before_enqueued_at,
             after_enqueued_at,
             before_started_at,
             after_started_at,
             before_finished_at,
             after_finished_at,
+            // Batches are not scheduled, only their tasks are.
+            before_run_after: _,
+            after_run_after: _,
         } = query;
 
+        let processing_batches = processing.batch_uids();
         let mut batches = self.batches.all_batch_ids(rtxn)?;
-        if let Some(batch_id) = processing.batch.as_ref().map(|batch| batch.uid) {
-            batches.insert(batch_id);
-        }
+        batches |= &processing_batches;
 
         if let Some(from) = from {
             let range = if reverse.unwrap_or_default() {
@@ -342,20 +348,14 @@ impl Queue {
             for status in status {
                 match status {
                     // special case for Processing batches
-                    Status::Processing => {
-                        if let Some(batch_id) = processing.batch.as_ref().map(|batch| batch.uid) {
-                            status_batches.insert(batch_id);
-                        }
-                    }
+                    Status::Processing => status_batches |= &processing_batches,
                     // Enqueued tasks are not stored in batches
                     Status::Enqueued => (),
                     status => status_batches |= &self.batches.get_status(rtxn, *status)?,
                 };
             }
             if !status.contains(&Status::Processing) {
-                if let Some(ref batch) = processing.batch {
-                    batches.remove(batch.uid);
-                }
+                batches -= &processing_batches;
             }
             batches &= status_batches;
         }
@@ -400,12 +400,10 @@ impl Queue {
             let mut kind_batches = RoaringBitmap::new();
             for kind in kind {
                 kind_batches |= self.batches.get_kind(rtxn, *kind)?;
-                if let Some(uid) = processing
-                    .batch
-                    .as_ref()
-                    .and_then(|batch| batch.kinds.contains(kind).then_some(batch.uid))
-                {
-                    kind_batches.insert(uid);
+                for current in processing.batches.values() {
+                    if current.batch.kinds.contains(kind) {
+                        kind_batches.insert(current.batch.uid);
+                    }
                 }
             }
             batches &= &kind_batches;
@@ -415,12 +413,10 @@ impl Queue {
             let mut index_batches = RoaringBitmap::new();
             for index in index {
                 index_batches |= self.batches.index_batches(rtxn, index)?;
-                if let Some(uid) = processing
-                    .batch
-                    .as_ref()
-                    .and_then(|batch| batch.indexes.contains(index).then_some(batch.uid))
-                {
-                    index_batches.insert(uid);
+                for current in processing.batches.values() {
+                    if current.batch.indexes.contains(index) {
+                        index_batches.insert(current.batch.uid);
+                    }
                 }
             }
             batches &= &index_batches;
@@ -432,39 +428,26 @@ impl Queue {
         // Once we have filtered the two subsets, we put them back together and assign it back to `batches`.
         batches = {
             let (mut filtered_non_processing_batches, mut filtered_processing_batches) =
-                (&batches - &*processing.processing, &batches & &*processing.processing);
+                (&batches - &processing_batches, RoaringBitmap::new());
 
             // special case for Processing batches
-            // A closure that clears the filtered_processing_batches if their started_at date falls outside the given bounds
-            let mut clear_filtered_processing_batches =
-                |start: Bound<OffsetDateTime>, end: Bound<OffsetDateTime>| {
-                    let start = map_bound(start, |b| b.unix_timestamp_nanos());
-                    let end = map_bound(end, |b| b.unix_timestamp_nanos());
-                    let is_within_dates = RangeBounds::contains(
-                        &(start, end),
-                        &processing
-                            .batch
-                            .as_ref()
-                            .map_or_else(OffsetDateTime::now_utc, |batch| batch.started_at)
-                            .unix_timestamp_nanos(),
-                    );
-                    if !is_within_dates {
-                        filtered_processing_batches.clear();
-                    }
-                };
-            match (after_started_at, before_started_at) {
-                (None, None) => (),
-                (None, Some(before)) => {
-                    clear_filtered_processing_batches(Bound::Unbounded, Bound::Excluded(*before))
-                }
-                (Some(after), None) => {
-                    clear_filtered_processing_batches(Bound::Excluded(*after), Bound::Unbounded)
-                }
-                (Some(after), Some(before)) => clear_filtered_processing_batches(
-                    Bound::Excluded(*after),
-                    Bound::Excluded(*before),
-                ),
+            // A processing batch is kept only if its in-memory started_at date falls within the given bounds
+            let (start, end) = match (after_started_at, before_started_at) {
+                (None, None) => (Bound::Unbounded, Bound::Unbounded),
+                (None, Some(before)) => (Bound::Unbounded, Bound::Excluded(*before)),
+                (Some(after), None) => (Bound::Excluded(*after), Bound::Unbounded),
+                (Some(after), Some(before)) => (Bound::Excluded(*after), Bound::Excluded(*before)),
             };
+            let start = map_bound(start, |b| b.unix_timestamp_nanos());
+            let end = map_bound(end, |b| b.unix_timestamp_nanos());
+            for current in processing.batches.values() {
+                let started_at = current.batch.started_at.unix_timestamp_nanos();
+                if batches.contains(current.batch.uid)
+                    && RangeBounds::contains(&(start, end), &started_at)
+                {
+                    filtered_processing_batches.insert(current.batch.uid);
+                }
+            }
 
             keep_ids_within_datetimes(
                 rtxn,
@@ -535,9 +518,9 @@ impl Queue {
         if query.index_uids.is_some() || !filters.all_indexes_authorized() {
             for kind in enum_iterator::all::<Kind>().filter(|kind| !kind.related_to_one_index()) {
                 batches -= self.tasks.get_kind(rtxn, kind)?;
-                if let Some(batch) = processing.batch.as_ref() {
-                    if batch.kinds.contains(&kind) {
-                        batches.remove(batch.uid);
+                for current in processing.batches.values() {
+                    if current.batch.kinds.contains(&kind) {
+                        batches.remove(current.batch.uid);
                     }
                 }
             }
@@ -558,12 +541,12 @@ impl Queue {
                     forbidden_indexes |= index_tasks;
                 }
             }
-            if let Some(batch) = processing.batch.as_ref() {
-                for index in &batch.indexes {
+            for current in processing.batches.values() {
+                for index in &current.batch.indexes {
                     if filters.is_index_authorized(index) {
-                        valid_indexes.insert(batch.uid);
+                        valid_indexes.insert(current.batch.uid);
                     } else {
-                        forbidden_indexes.insert(batch.uid);
+                        forbidden_indexes.insert(current.batch.uid);
                     }
                 }
             }
@@ -15,7 +15,7 @@ use file_store::FileStore;
 use meilisearch_types::batches::BatchId;
 use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn, WithoutTls};
 use meilisearch_types::milli::{CboRoaringBitmapCodec, BEU32};
-use meilisearch_types::tasks::{Kind, KindWithContent, Status, Task};
+use meilisearch_types::tasks::{Kind, KindWithContent, Priority, Status, Task};
 use roaring::RoaringBitmap;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
@@ -27,7 +27,7 @@ use crate::processing::ProcessingTasks;
 use crate::utils::{
     check_index_swap_validity, filter_out_references_to_newer_tasks, ProcessingBatch,
 };
-use crate::{Error, IndexSchedulerOptions, Result, TaskId};
+use crate::{Error, IndexSchedulerOptions, RegisterOptions, Result, TaskId};
 
 /// The number of database used by queue itself
 const NUMBER_OF_DATABASES: u32 = 1;
@@ -64,11 +64,17 @@ pub struct Query {
     /// # }
     /// ```
     pub types: Option<Vec<Kind>>,
+    /// The allowed [priorities](meilisearch_types::tasks::Task::priority) of the matched tasks
+    pub priorities: Option<Vec<Priority>>,
     /// The allowed [index ids](meilisearch_types::tasks::Task::index_uid) of the matched tasks
     pub index_uids: Option<Vec<String>>,
     /// The [task ids](`meilisearch_types::tasks::Task::uid`) of the [`TaskCancelation`](meilisearch_types::tasks::Task::Kind::TaskCancelation) tasks
     /// that canceled the matched tasks.
     pub canceled_by: Option<Vec<TaskId>>,
+    /// The allowed [custom metadata](meilisearch_types::tasks::Task::custom_metadata) of the matched tasks
+    pub custom_metadata: Option<Vec<String>>,
+    /// The allowed [idempotency keys](meilisearch_types::tasks::Task::idempotency_key) of the matched tasks
+    pub idempotency_keys: Option<Vec<String>>,
     /// Exclusive upper bound of the matched tasks' [`enqueued_at`](meilisearch_types::tasks::Task::enqueued_at) field.
     pub before_enqueued_at: Option<OffsetDateTime>,
     /// Exclusive lower bound of the matched tasks' [`enqueued_at`](meilisearch_types::tasks::Task::enqueued_at) field.
@@ -81,6 +87,10 @@ pub struct Query {
     pub before_finished_at: Option<OffsetDateTime>,
     /// Exclusive lower bound of the matched tasks' [`finished_at`](meilisearch_types::tasks::Task::finished_at) field.
     pub after_finished_at: Option<OffsetDateTime>,
+    /// Exclusive upper bound of the matched tasks' [`run_after`](meilisearch_types::tasks::Task::run_after) field.
+    pub before_run_after: Option<OffsetDateTime>,
+    /// Exclusive lower bound of the matched tasks' [`run_after`](meilisearch_types::tasks::Task::run_after) field.
+    pub after_run_after: Option<OffsetDateTime>,
 }
 
 impl Query {
@@ -97,14 +107,19 @@ impl Query {
                 batch_uids: None,
                 statuses: None,
                 types: None,
+                priorities: None,
                 index_uids: None,
                 canceled_by: None,
+                custom_metadata: None,
+                idempotency_keys: None,
                 before_enqueued_at: None,
                 after_enqueued_at: None,
                 before_started_at: None,
                 after_started_at: None,
                 before_finished_at: None,
                 after_finished_at: None,
+                before_run_after: None,
+                after_run_after: None,
             }
         )
     }
@@ -211,10 +226,10 @@ impl Queue {
     }
 
     pub(crate) fn delete_persisted_task_data(&self, task: &Task) -> Result<()> {
-        match task.content_uuid() {
-            Some(content_file) => self.delete_update_file(content_file),
-            None => Ok(()),
+        for content_file in task.content_uuids() {
+            self.delete_update_file(content_file)?;
         }
+        Ok(())
     }
 
     /// Open and returns the task's content File.
@@ -256,9 +271,17 @@ impl Queue {
         &self,
         wtxn: &mut RwTxn,
         kind: &KindWithContent,
-        task_id: Option<TaskId>,
-        dry_run: bool,
+        options: &RegisterOptions,
     ) -> Result<Task> {
+        let RegisterOptions {
+            task_id,
+            priority,
+            run_after,
+            ref custom_metadata,
+            ref idempotency_key,
+            api_key_uid,
+            dry_run,
+        } = *options;
         let next_task_id = self.tasks.next_task_id(wtxn)?;
 
         if let Some(uid) = task_id {
@@ -274,10 +297,14 @@ impl Queue {
             enqueued_at: OffsetDateTime::now_utc(),
             started_at: None,
             finished_at: None,
+            run_after,
             error: None,
             canceled_by: None,
             details: kind.default_details(),
             status: Status::Enqueued,
+            priority,
+            custom_metadata: custom_metadata.clone(),
+            idempotency_key: idempotency_key.clone(),
             kind: kind.clone(),
         };
         // For deletion and cancelation tasks, we want to make extra sure that they
@@ -294,7 +321,7 @@ impl Queue {
 
         // Get rid of the mutability.
         let task = task;
-        self.tasks.register(wtxn, &task)?;
+        self.tasks.register(wtxn, &task, api_key_uid)?;
 
         Ok(task)
     }
@@ -343,8 +370,7 @@ impl Queue {
                 ),
                 tasks: to_delete,
             },
-            None,
-            false,
+            &RegisterOptions::default(),
         )?;
 
         Ok(())
@@ -3,9 +3,11 @@ use std::ops::{Bound, RangeBounds};
 use meilisearch_types::heed::types::{DecodeIgnore, SerdeBincode, SerdeJson, Str};
 use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn, WithoutTls};
 use meilisearch_types::milli::{CboRoaringBitmapCodec, RoaringBitmapCodec, BEU32};
-use meilisearch_types::tasks::{Kind, Status, Task};
+use meilisearch_types::tasks::{Kind, KindWithContent, Priority, Status, Task};
 use roaring::{MultiOps, RoaringBitmap};
+use sha2::{Digest, Sha256};
 use time::OffsetDateTime;
+use uuid::Uuid;
 
 use super::{Query, Queue};
 use crate::processing::ProcessingTasks;
@@ -15,18 +17,22 @@ use crate::utils::{
 use crate::{Error, Result, TaskId, BEI128};
 
 /// The number of database used by the task queue
-const NUMBER_OF_DATABASES: u32 = 8;
+const NUMBER_OF_DATABASES: u32 = 12;
 /// Database const names for the `IndexScheduler`.
 mod db_name {
     pub const ALL_TASKS: &str = "all-tasks";
 
     pub const STATUS: &str = "status";
     pub const KIND: &str = "kind";
+    pub const PRIORITY: &str = "priority";
     pub const INDEX_TASKS: &str = "index-tasks";
     pub const CANCELED_BY: &str = "canceled_by";
     pub const ENQUEUED_AT: &str = "enqueued-at";
     pub const STARTED_AT: &str = "started-at";
     pub const FINISHED_AT: &str = "finished-at";
+    pub const RUN_AFTER: &str = "run-after";
+    pub const CUSTOM_METADATA: &str = "custom-metadata";
+    pub const IDEMPOTENCY_KEYS: &str = "idempotency-keys";
 }
 
 pub struct TaskQueue {
@@ -38,6 +44,8 @@ pub struct TaskQueue {
     pub(crate) status: Database<SerdeBincode<Status>, RoaringBitmapCodec>,
     /// All the tasks ids grouped by their kind.
     pub(crate) kind: Database<SerdeBincode<Kind>, RoaringBitmapCodec>,
+    /// All the tasks ids grouped by their priority, the tasks with the `Normal` priority are not stored.
+    pub(crate) priority: Database<SerdeBincode<Priority>, RoaringBitmapCodec>,
     /// Store the tasks associated to an index.
     pub(crate) index_tasks: Database<Str, RoaringBitmapCodec>,
     /// Store the tasks that were canceled by a task uid
@@ -48,6 +56,12 @@ pub struct TaskQueue {
     pub(crate) started_at: Database<BEI128, CboRoaringBitmapCodec>,
     /// Store the task ids of tasks which finished at a specific date
     pub(crate) finished_at: Database<BEI128, CboRoaringBitmapCodec>,
+    /// Store the task ids of tasks which must not be processed before a specific date
+    pub(crate) run_after: Database<BEI128, CboRoaringBitmapCodec>,
+    /// All the tasks ids grouped by their custom metadata.
+    pub(crate) custom_metadata: Database<Str, RoaringBitmapCodec>,
+    /// The task registered with each idempotency key, scoped by index and API key.
+    pub(crate) idempotency_keys: Database<Str, BEU32>,
 }
 
 impl TaskQueue {
@@ -56,11 +70,15 @@ impl TaskQueue {
             all_tasks: self.all_tasks,
             status: self.status,
             kind: self.kind,
+            priority: self.priority,
             index_tasks: self.index_tasks,
             canceled_by: self.canceled_by,
             enqueued_at: self.enqueued_at,
             started_at: self.started_at,
             finished_at: self.finished_at,
+            run_after: self.run_after,
+            custom_metadata: self.custom_metadata,
+            idempotency_keys: self.idempotency_keys,
         }
     }
 
@@ -73,11 +91,15 @@ impl TaskQueue {
             all_tasks: env.create_database(wtxn, Some(db_name::ALL_TASKS))?,
             status: env.create_database(wtxn, Some(db_name::STATUS))?,
             kind: env.create_database(wtxn, Some(db_name::KIND))?,
+            priority: env.create_database(wtxn, Some(db_name::PRIORITY))?,
             index_tasks: env.create_database(wtxn, Some(db_name::INDEX_TASKS))?,
             canceled_by: env.create_database(wtxn, Some(db_name::CANCELED_BY))?,
             enqueued_at: env.create_database(wtxn, Some(db_name::ENQUEUED_AT))?,
             started_at: env.create_database(wtxn, Some(db_name::STARTED_AT))?,
             finished_at: env.create_database(wtxn, Some(db_name::FINISHED_AT))?,
+            run_after: env.create_database(wtxn, Some(db_name::RUN_AFTER))?,
+            custom_metadata: env.create_database(wtxn, Some(db_name::CUSTOM_METADATA))?,
+            idempotency_keys: env.create_database(wtxn, Some(db_name::IDEMPOTENCY_KEYS))?,
         })
     }
 
@@ -235,6 +257,131 @@ impl TaskQueue {
         Ok(())
     }
 
+    pub(crate) fn get_priority(&self, rtxn: &RoTxn, priority: Priority) -> Result<RoaringBitmap> {
+        match priority {
+            // The tasks with the normal priority are all the tasks that don't have another priority.
+            Priority::Normal => {
+                let mut tasks = self.all_task_ids(rtxn)?;
+                for priority in enum_iterator::all::<Priority>().filter(|p| !p.is_normal()) {
+                    tasks -= self.get_priority(rtxn, priority)?;
+                }
+                Ok(tasks)
+            }
+            priority => Ok(self.priority.get(rtxn, &priority)?.unwrap_or_default()),
+        }
+    }
+
+    pub(crate) fn put_priority(
+        &self,
+        wtxn: &mut RwTxn,
+        priority: Priority,
+        bitmap: &RoaringBitmap,
+    ) -> Result<()> {
+        debug_assert!(!priority.is_normal(), "The normal priority is not stored");
+        if bitmap.is_empty() {
+            self.priority.delete(wtxn, &priority)?;
+        } else {
+            self.priority.put(wtxn, &priority, bitmap)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn update_priority(
+        &self,
+        wtxn: &mut RwTxn,
+        priority: Priority,
+        f: impl Fn(&mut RoaringBitmap),
+    ) -> Result<()> {
+        let mut tasks = self.get_priority(wtxn, priority)?;
+        f(&mut tasks);
+        self.put_priority(wtxn, priority, &tasks)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn custom_metadata_tasks(
+        &self,
+        rtxn: &RoTxn,
+        custom_metadata: &str,
+    ) -> Result<RoaringBitmap> {
+        Ok(self.custom_metadata.get(rtxn, custom_metadata)?.unwrap_or_default())
+    }
+
+    pub(crate) fn update_custom_metadata(
+        &self,
+        wtxn: &mut RwTxn,
+        custom_metadata: &str,
+        f: impl Fn(&mut RoaringBitmap),
+    ) -> Result<()> {
+        let mut tasks = self.custom_metadata_tasks(wtxn, custom_metadata)?;
+        f(&mut tasks);
+        if tasks.is_empty() {
+            self.custom_metadata.delete(wtxn, custom_metadata)?;
+        } else {
+            self.custom_metadata.put(wtxn, custom_metadata, &tasks)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the uid of the task registered with the given idempotency key
+    /// on the indexes of `kind` and with the given API key, if any.
+    pub(crate) fn task_with_idempotency_key(
+        &self,
+        rtxn: &RoTxn,
+        idempotency_key: &str,
+        kind: &KindWithContent,
+        api_key_uid: Option<Uuid>,
+    ) -> Result<Option<TaskId>> {
+        let key = idempotency_db_key(idempotency_key, kind, api_key_uid);
+        Ok(self.idempotency_keys.get(rtxn, &key)?)
+    }
+
+    /// Returns the uids of all the tasks registered with the given idempotency key, whatever their scope.
+    pub(crate) fn idempotency_key_tasks(
+        &self,
+        rtxn: &RoTxn,
+        idempotency_key: &str,
+    ) -> Result<RoaringBitmap> {
+        let prefix = format!("{idempotency_key}\0");
+        let mut tasks = RoaringBitmap::new();
+        for entry in self.idempotency_keys.prefix_iter(rtxn, &prefix)? {
+            let (_, task_id) = entry?;
+            tasks.insert(task_id);
+        }
+        Ok(tasks)
+    }
+
+    /// Stores the task under its idempotency key.
+    pub(crate) fn put_idempotency_key(
+        &self,
+        wtxn: &mut RwTxn,
+        task: &Task,
+        api_key_uid: Option<Uuid>,
+    ) -> Result<()> {
+        if let Some(idempotency_key) = &task.idempotency_key {
+            let key = idempotency_db_key(idempotency_key, &task.kind, api_key_uid);
+            self.idempotency_keys.put(wtxn, &key, &task.uid)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the task from its idempotency key, so that the key can be used again.
+    pub(crate) fn delete_idempotency_key(&self, wtxn: &mut RwTxn, task: &Task) -> Result<()> {
+        if let Some(idempotency_key) = &task.idempotency_key {
+            // The API key is not stored in the task, the key is looked up in every scope.
+            let prefix = format!("{idempotency_key}\0");
+            let mut iter = self.idempotency_keys.prefix_iter_mut(wtxn, &prefix)?;
+            while let Some((_, task_id)) = iter.next().transpose()? {
+                if task_id == task.uid {
+                    // safety: We don't keep references to the database
+                    unsafe { iter.del_current()? };
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Convert an iterator to a `Vec` of tasks. The tasks MUST exist or a
     /// `CorruptedTaskQueue` error will be thrown.
     pub(crate) fn get_existing_tasks(
@@ -250,7 +397,12 @@ impl TaskQueue {
             .collect::<Result<_>>()
     }
 
-    pub(crate) fn register(&self, wtxn: &mut RwTxn, task: &Task) -> Result<()> {
+    pub(crate) fn register(
+        &self,
+        wtxn: &mut RwTxn,
+        task: &Task,
+        api_key_uid: Option<Uuid>,
+    ) -> Result<()> {
         self.all_tasks.put(wtxn, &task.uid, task)?;
 
         for index in task.indexes() {
@@ -267,10 +419,67 @@ impl TaskQueue {
             bitmap.insert(task.uid);
         })?;
 
+        if !task.priority.is_normal() {
+            self.update_priority(wtxn, task.priority, |bitmap| {
+                bitmap.insert(task.uid);
+            })?;
+        }
+
         utils::insert_task_datetime(wtxn, self.enqueued_at, task.enqueued_at, task.uid)?;
+        if let Some(run_after) = task.run_after {
+            utils::insert_task_datetime(wtxn, self.run_after, run_after, task.uid)?;
+        }
+        if let Some(custom_metadata) = &task.custom_metadata {
+            self.update_custom_metadata(wtxn, custom_metadata, |bitmap| {
+                bitmap.insert(task.uid);
+            })?;
+        }
+        self.put_idempotency_key(wtxn, task, api_key_uid)?;
 
         Ok(())
     }
+
+    /// Returns the earliest date, strictly after `now`, at which an enqueued task becomes eligible.
+    pub(crate) fn next_run_after(
+        &self,
+        rtxn: &RoTxn,
+        now: OffsetDateTime,
+    ) -> Result<Option<OffsetDateTime>> {
+        let enqueued = self.get_status(rtxn, Status::Enqueued)?;
+        let range = (Bound::Excluded(now.unix_timestamp_nanos()), Bound::Unbounded);
+        for entry in self.run_after.range(rtxn, &range)? {
+            let (timestamp, task_ids) = entry?;
+            if !task_ids.is_disjoint(&enqueued) {
+                let date = OffsetDateTime::from_unix_timestamp_nanos(timestamp)
+                    .map_err(|_| Error::CorruptedTaskQueue)?;
+                return Ok(Some(date));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The key of a task in the `idempotency_keys` database.
+///
+/// The idempotency key is scoped by the index of the task, when it targets a single one, and by
+/// the API key used to register it. It comes first so that all the tasks registered with it can
+/// be found with a prefix search, a header can't contain the `\0` separator. The scope is hashed
+/// to keep the key under the LMDB limit, see [`crate::MAX_IDEMPOTENCY_KEY_BYTES`].
+fn idempotency_db_key(
+    idempotency_key: &str,
+    kind: &KindWithContent,
+    api_key_uid: Option<Uuid>,
+) -> String {
+    let index_uid = match kind.indexes().as_slice() {
+        [index_uid] => *index_uid,
+        _ => "",
+    };
+    let api_key_uid = api_key_uid.map(|uid| uid.to_string()).unwrap_or_default();
+    let mut scope = Sha256::new();
+    scope.update(index_uid);
+    scope.update([0]);
+    scope.update(api_key_uid);
+    format!("{idempotency_key}\0{:x}", scope.finalize())
 }
 
 impl Queue {
@@ -281,7 +490,7 @@ impl Queue {
         query: &Query,
         processing_tasks: &ProcessingTasks,
     ) -> Result<RoaringBitmap> {
-        let ProcessingTasks { batch: processing_batch, processing: processing_tasks, progress: _ } =
+        let ProcessingTasks { batches: processing_batches, processing: processing_tasks } =
             processing_tasks;
         let Query {
             limit,
@@ -291,14 +500,19 @@ impl Queue {
             batch_uids,
             statuses,
             types,
+            priorities,
             index_uids,
             canceled_by,
+            custom_metadata,
+            idempotency_keys,
             before_enqueued_at,
             after_enqueued_at,
             before_started_at,
             after_started_at,
             before_finished_at,
             after_finished_at,
+            before_run_after,
+            after_run_after,
         } = query;
 
         let mut tasks = self.tasks.all_task_ids(rtxn)?;
@@ -315,8 +529,8 @@ impl Queue {
         if let Some(batch_uids) = batch_uids {
             let mut batch_tasks = RoaringBitmap::new();
             for batch_uid in batch_uids {
-                if processing_batch.as_ref().is_some_and(|batch| batch.uid == *batch_uid) {
-                    batch_tasks |= &**processing_tasks;
+                if let Some(current) = processing_batches.get(batch_uid) {
+                    batch_tasks |= &*current.processing;
                 } else {
                     batch_tasks |= self.tasks_in_batch(rtxn, *batch_uid)?;
                 }
@@ -371,6 +585,14 @@ impl Queue {
             tasks &= &kind_tasks;
         }
 
+        if let Some(priorities) = priorities {
+            let mut priority_tasks = RoaringBitmap::new();
+            for priority in priorities {
+                priority_tasks |= self.tasks.get_priority(rtxn, *priority)?;
+            }
+            tasks &= &priority_tasks;
+        }
+
         if let Some(index) = index_uids {
             let mut index_tasks = RoaringBitmap::new();
             for index in index {
@@ -379,44 +601,46 @@ impl Queue {
             tasks &= &index_tasks;
         }
 
+        if let Some(custom_metadata) = custom_metadata {
+            let mut custom_metadata_tasks = RoaringBitmap::new();
+            for custom_metadata in custom_metadata {
+                custom_metadata_tasks |= self.tasks.custom_metadata_tasks(rtxn, custom_metadata)?;
+            }
+            tasks &= &custom_metadata_tasks;
+        }
+
+        if let Some(idempotency_keys) = idempotency_keys {
+            let mut idempotency_key_tasks = RoaringBitmap::new();
+            for idempotency_key in idempotency_keys {
+                idempotency_key_tasks |= self.tasks.idempotency_key_tasks(rtxn, idempotency_key)?;
+            }
+            tasks &= &idempotency_key_tasks;
+        }
+
         // For the started_at filter, we need to treat the part of the tasks that are processing from the part of the
         // tasks that are not processing. The non-processing ones are filtered normally while the processing ones
         // are entirely removed unless the in-memory startedAt variable falls within the date filter.
         // Once we have filtered the two subsets, we put them back together and assign it back to `tasks`.
         tasks = {
             let (mut filtered_non_processing_tasks, mut filtered_processing_tasks) =
-                (&tasks - &**processing_tasks, &tasks & &**processing_tasks);
+                (&tasks - &**processing_tasks, RoaringBitmap::new());
 
             // special case for Processing tasks
-            // A closure that clears the filtered_processing_tasks if their started_at date falls outside the given bounds
-            let mut clear_filtered_processing_tasks =
-                |start: Bound<OffsetDateTime>, end: Bound<OffsetDateTime>| {
-                    let start = map_bound(start, |b| b.unix_timestamp_nanos());
-                    let end = map_bound(end, |b| b.unix_timestamp_nanos());
-                    let is_within_dates = RangeBounds::contains(
-                        &(start, end),
-                        &processing_batch
-                            .as_ref()
-                            .map_or_else(OffsetDateTime::now_utc, |batch| batch.started_at)
-                            .unix_timestamp_nanos(),
-                    );
-                    if !is_within_dates {
-                        filtered_processing_tasks.clear();
-                    }
-                };
-            match (after_started_at, before_started_at) {
-                (None, None) => (),
-                (None, Some(before)) => {
-                    clear_filtered_processing_tasks(Bound::Unbounded, Bound::Excluded(*before))
-                }
-                (Some(after), None) => {
-                    clear_filtered_processing_tasks(Bound::Excluded(*after), Bound::Unbounded)
-                }
-                (Some(after), Some(before)) => clear_filtered_processing_tasks(
-                    Bound::Excluded(*after),
-                    Bound::Excluded(*before),
-                ),
+            // The processing tasks of a batch are kept only if the in-memory started_at date of their batch falls within the given bounds
+            let (start, end) = match (after_started_at, before_started_at) {
+                (None, None) => (Bound::Unbounded, Bound::Unbounded),
+                (None, Some(before)) => (Bound::Unbounded, Bound::Excluded(*before)),
+                (Some(after), None) => (Bound::Excluded(*after), Bound::Unbounded),
+                (Some(after), Some(before)) => (Bound::Excluded(*after), Bound::Excluded(*before)),
             };
+            let start = map_bound(start, |b| b.unix_timestamp_nanos());
+            let end = map_bound(end, |b| b.unix_timestamp_nanos());
+            for current in processing_batches.values() {
+                let started_at = current.batch.started_at.unix_timestamp_nanos();
+                if RangeBounds::contains(&(start, end), &started_at) {
+                    filtered_processing_tasks |= &tasks & &*current.processing;
+                }
+            }
 
             keep_ids_within_datetimes(
                 rtxn,
@@ -444,6 +668,14 @@ impl Queue {
             *before_finished_at,
         )?;
 
+        keep_ids_within_datetimes(
+            rtxn,
+            &mut tasks,
+            self.tasks.run_after,
+            *after_run_after,
+            *before_run_after,
+        )?;
+
         if let Some(limit) = limit {
             tasks = if query.reverse.unwrap_or_default() {
                 tasks.into_iter().take(*limit as usize).collect()
@@ -512,30 +744,18 @@ impl Queue {
             .tasks
             .get_existing_tasks(rtxn, tasks.take(query.limit.unwrap_or(u32::MAX) as usize))?;
 
-        let ProcessingTasks { batch, processing, progress: _ } = processing_tasks;
-
-        let ret = tasks.into_iter();
-        if processing.is_empty() || batch.is_none() {
-            Ok((ret.collect(), total))
-        } else {
-            // Safe because we ensured there was a batch in the previous branch
-            let batch = batch.as_ref().unwrap();
-            Ok((
-                ret.map(|task| {
-                    if processing.contains(task.uid) {
-                        Task {
-                            status: Status::Processing,
-                            batch_uid: Some(batch.uid),
-                            started_at: Some(batch.started_at),
-                            ..task
-                        }
-                    } else {
-                        task
-                    }
-                })
-                .collect(),
-                total,
-            ))
-        }
+        let ret = tasks
+            .into_iter()
+            .map(|task| match processing_tasks.batch_of_task(task.uid) {
+                Some(batch) => Task {
+                    status: Status::Processing,
+                    batch_uid: Some(batch.uid),
+                    started_at: Some(batch.started_at),
+                    ..task
+                },
+                None => task,
+            })
+            .collect();
+        Ok((ret, total))
     }
 }
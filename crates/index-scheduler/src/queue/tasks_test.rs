@@ -1,13 +1,13 @@
 use meili_snap::snapshot;
 use meilisearch_auth::AuthFilter;
 use meilisearch_types::index_uid_pattern::IndexUidPattern;
-use meilisearch_types::tasks::{IndexSwap, KindWithContent, Status};
+use meilisearch_types::tasks::{IndexSwap, KindWithContent, Priority, Status};
 use time::{Duration, OffsetDateTime};
 
 use crate::insta_snapshot::{snapshot_bitmap, snapshot_index_scheduler};
 use crate::test_utils::Breakpoint::*;
 use crate::test_utils::{index_creation_task, FailureLocation};
-use crate::{IndexScheduler, Query};
+use crate::{IndexScheduler, Query, RegisterOptions};
 
 #[test]
 fn query_tasks_from_and_limit() {
@@ -294,6 +294,151 @@ fn query_tasks_simple() {
     snapshot!(snapshot_bitmap(&tasks), @"[2,]");
 }
 
+#[test]
+fn query_tasks_priorities() {
+    let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
+
+    let kind = index_creation_task("catto", "mouse");
+    let _task = index_scheduler.register(kind, None, false).unwrap();
+    let kind = index_creation_task("doggo", "sheep");
+    let options = RegisterOptions { priority: Priority::High, ..Default::default() };
+    let _task = index_scheduler.register_with_options(kind, options).unwrap();
+    let kind = index_creation_task("whalo", "fish");
+    let options = RegisterOptions { priority: Priority::Low, ..Default::default() };
+    let _task = index_scheduler.register_with_options(kind, options).unwrap();
+
+    let rtxn = index_scheduler.env.read_txn().unwrap();
+    let processing = index_scheduler.processing_tasks.read().unwrap();
+    let query = Query { priorities: Some(vec![Priority::Normal]), ..Default::default() };
+    let (tasks, _) = index_scheduler
+        .queue
+        .get_task_ids_from_authorized_indexes(&rtxn, &query, &AuthFilter::default(), &processing)
+        .unwrap();
+    snapshot!(snapshot_bitmap(&tasks), @"[0,]");
+
+    let query =
+        Query { priorities: Some(vec![Priority::High, Priority::Low]), ..Default::default() };
+    let (tasks, _) = index_scheduler
+        .queue
+        .get_task_ids_from_authorized_indexes(&rtxn, &query, &AuthFilter::default(), &processing)
+        .unwrap();
+    snapshot!(snapshot_bitmap(&tasks), @"[1,2,]");
+}
+
+#[test]
+fn register_tasks_with_idempotency_keys() {
+    let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
+
+    let options = RegisterOptions {
+        custom_metadata: Some(String::from("import")),
+        idempotency_key: Some(String::from("doggo-key")),
+        ..Default::default()
+    };
+    let kind = index_creation_task("doggo", "bone");
+    let task = index_scheduler.register_with_options(kind, options.clone()).unwrap();
+    snapshot!(task.uid, @"0");
+    let kind = index_creation_task("catto", "mouse");
+    let options = RegisterOptions { idempotency_key: Some(String::from("catto-key")), ..options };
+    let task = index_scheduler.register_with_options(kind, options.clone()).unwrap();
+    snapshot!(task.uid, @"1");
+
+    // Submitting the same request again returns the task that was already registered.
+    let kind = index_creation_task("catto", "mouse");
+    let task = index_scheduler.register_with_options(kind, options).unwrap();
+    snapshot!(task.uid, @"1");
+    let kind = index_creation_task("whalo", "fish");
+    let _task = index_scheduler.register(kind, None, false).unwrap();
+
+    let rtxn = index_scheduler.env.read_txn().unwrap();
+    let processing = index_scheduler.processing_tasks.read().unwrap();
+    let query = Query { custom_metadata: Some(vec![String::from("import")]), ..Default::default() };
+    let (tasks, _) = index_scheduler
+        .queue
+        .get_task_ids_from_authorized_indexes(&rtxn, &query, &AuthFilter::default(), &processing)
+        .unwrap();
+    snapshot!(snapshot_bitmap(&tasks), @"[0,1,]");
+
+    let query = Query {
+        idempotency_keys: Some(vec![String::from("catto-key"), String::from("unknown")]),
+        ..Default::default()
+    };
+    let (tasks, _) = index_scheduler
+        .queue
+        .get_task_ids_from_authorized_indexes(&rtxn, &query, &AuthFilter::default(), &processing)
+        .unwrap();
+    snapshot!(snapshot_bitmap(&tasks), @"[1,]");
+}
+
+#[test]
+fn idempotency_keys_are_scoped_by_index_and_api_key() {
+    let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
+
+    let options =
+        RegisterOptions { idempotency_key: Some(String::from("import")), ..Default::default() };
+    let kind = index_creation_task("doggo", "bone");
+    let task = index_scheduler.register_with_options(kind, options.clone()).unwrap();
+    snapshot!(task.uid, @"0");
+
+    // The same key used on another index or with another API key registers a new task.
+    let kind = index_creation_task("catto", "mouse");
+    let task = index_scheduler.register_with_options(kind, options.clone()).unwrap();
+    snapshot!(task.uid, @"1");
+    let other_api_key =
+        RegisterOptions { api_key_uid: Some(uuid::Uuid::from_u128(1)), ..options.clone() };
+    let kind = index_creation_task("doggo", "bone");
+    let task = index_scheduler.register_with_options(kind, other_api_key.clone()).unwrap();
+    snapshot!(task.uid, @"2");
+    let kind = index_creation_task("doggo", "bone");
+    let task = index_scheduler.register_with_options(kind, other_api_key).unwrap();
+    snapshot!(task.uid, @"2");
+
+    // But it can't be reused for another operation in the same scope.
+    let kind = KindWithContent::IndexDeletion { index_uid: String::from("doggo") };
+    let error = index_scheduler.register_with_options(kind, options.clone()).unwrap_err();
+    snapshot!(error, @"The idempotency key `import` was already used to register the task `0` of another type or on other indexes.");
+    let swap = IndexSwap { indexes: (String::from("doggo"), String::from("catto")) };
+    let kind = KindWithContent::IndexSwap { swaps: vec![swap] };
+    let task = index_scheduler.register_with_options(kind, options.clone()).unwrap();
+    snapshot!(task.uid, @"3");
+    let swap = IndexSwap { indexes: (String::from("doggo"), String::from("whalo")) };
+    let kind = KindWithContent::IndexSwap { swaps: vec![swap] };
+    let error = index_scheduler.register_with_options(kind, options).unwrap_err();
+    snapshot!(error, @"The idempotency key `import` was already used to register the task `3` of another type or on other indexes.");
+
+    // Filtering by idempotency key returns the tasks of every scope.
+    let rtxn = index_scheduler.env.read_txn().unwrap();
+    let processing = index_scheduler.processing_tasks.read().unwrap();
+    let query =
+        Query { idempotency_keys: Some(vec![String::from("import")]), ..Default::default() };
+    let (tasks, _) = index_scheduler
+        .queue
+        .get_task_ids_from_authorized_indexes(&rtxn, &query, &AuthFilter::default(), &processing)
+        .unwrap();
+    snapshot!(snapshot_bitmap(&tasks), @"[0,1,2,3,]");
+}
+
+#[test]
+fn idempotency_key_retry_of_a_swap() {
+    let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
+
+    let options =
+        RegisterOptions { idempotency_key: Some(String::from("swap")), ..Default::default() };
+    let swaps = || {
+        let swaps = [("doggo", "catto"), ("whalo", "birdo"), ("horso", "fisho")];
+        let swaps =
+            swaps.map(|(lhs, rhs)| IndexSwap { indexes: (lhs.to_string(), rhs.to_string()) });
+        KindWithContent::IndexSwap { swaps: swaps.to_vec() }
+    };
+    let task = index_scheduler.register_with_options(swaps(), options.clone()).unwrap();
+    snapshot!(task.uid, @"0");
+
+    // The indexes of a swap are not listed in a stable order, every retry must still match.
+    for _ in 0..20 {
+        let task = index_scheduler.register_with_options(swaps(), options.clone()).unwrap();
+        assert_eq!(task.uid, 0);
+    }
+}
+
 #[test]
 fn query_tasks_special_rules() {
     let (index_scheduler, mut handle) =
@@ -1,14 +1,17 @@
+use std::collections::HashSet;
 use std::fmt;
 
+use meilisearch_types::batches::BatchId;
 use meilisearch_types::heed::RoTxn;
 use meilisearch_types::milli::update::IndexDocumentsMethod;
 use meilisearch_types::settings::{Settings, Unchecked};
-use meilisearch_types::tasks::{Kind, KindWithContent, Status, Task};
+use meilisearch_types::tasks::{Kind, KindWithContent, Priority, Status, Task};
 use roaring::RoaringBitmap;
+use time::OffsetDateTime;
 use uuid::Uuid;
 
 use super::autobatcher::{self, BatchKind};
-use crate::utils::ProcessingBatch;
+use crate::utils::{keep_ids_within_datetimes, ProcessingBatch};
 use crate::{Error, IndexScheduler, Result};
 
 /// Represents a combination of tasks that can all be processed at the same time.
@@ -47,6 +50,9 @@ pub(crate) enum Batch {
     IndexSwap {
         task: Task,
     },
+    Transaction {
+        task: Task,
+    },
     UpgradeDatabase {
         tasks: Vec<Task>,
     },
@@ -128,7 +134,7 @@ impl Batch {
                     ..
                 } => RoaringBitmap::from_iter(tasks.iter().chain(other).map(|task| task.uid)),
             },
-            Batch::IndexSwap { task } => {
+            Batch::IndexSwap { task } | Batch::Transaction { task } => {
                 RoaringBitmap::from_sorted_iter(std::iter::once(task.uid)).unwrap()
             }
         }
@@ -143,7 +149,8 @@ impl Batch {
             | SnapshotCreation(_)
             | Dump(_)
             | UpgradeDatabase { .. }
-            | IndexSwap { .. } => None,
+            | IndexSwap { .. }
+            | Transaction { .. } => None,
             IndexOperation { op, .. } => Some(op.index_uid()),
             IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
@@ -167,6 +174,7 @@ impl fmt::Display for Batch {
             Batch::IndexUpdate { .. } => f.write_str("IndexUpdate")?,
             Batch::IndexDeletion { .. } => f.write_str("IndexDeletion")?,
             Batch::IndexSwap { .. } => f.write_str("IndexSwap")?,
+            Batch::Transaction { .. } => f.write_str("Transaction")?,
             Batch::UpgradeDatabase { .. } => f.write_str("UpgradeDatabase")?,
         };
         match index_uid {
@@ -419,6 +427,12 @@ impl IndexScheduler {
                 current_batch.processing(Some(&mut task));
                 Ok(Some(Batch::IndexSwap { task }))
             }
+            BatchKind::Transaction { id } => {
+                let mut task =
+                    self.queue.tasks.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                current_batch.processing(Some(&mut task));
+                Ok(Some(Batch::Transaction { task }))
+            }
         }
     }
 
@@ -428,6 +442,11 @@ impl IndexScheduler {
     /// 3. We get the *next* snapshot to process.
     /// 4. We get the *next* dump to process.
     /// 5. We get the *next* tasks to process for a specific index.
+    ///
+    /// When other batches are already processing, only the tasks of an index that is not
+    /// used by these batches can be batched, the other operations must wait for them.
+    /// The tasks that must run after a date that is not reached yet are ignored, and so are the
+    /// tasks enqueued after them on the same indexes, to keep the order of the operations on an index.
     #[tracing::instrument(level = "trace", skip(self, rtxn), target = "indexing::scheduler")]
     pub(crate) fn create_next_batch(
         &self,
@@ -436,10 +455,46 @@ impl IndexScheduler {
         #[cfg(test)]
         self.maybe_fail(crate::test_utils::FailureLocation::InsideCreateBatch)?;
 
-        let batch_id = self.queue.batches.next_batch_id(rtxn)?;
+        let processing = self.processing_tasks.read().unwrap().clone();
+        let running_batches: Vec<_> = processing.running_batches().collect();
+
+        // The running batches are not written to disk yet, we must not reuse their ids.
+        let batch_id = running_batches
+            .iter()
+            .map(|current| current.batch.uid + 1)
+            .fold(self.queue.batches.next_batch_id(rtxn)?, BatchId::max);
         let mut current_batch = ProcessingBatch::new(batch_id);
 
-        let enqueued = &self.queue.tasks.get_status(rtxn, Status::Enqueued)?;
+        let mut enqueued = self.queue.tasks.get_status(rtxn, Status::Enqueued)?;
+        // The tasks of the running batches are still enqueued on disk.
+        for current in &running_batches {
+            enqueued -= &*current.processing;
+        }
+        // The tasks scheduled to run later are not eligible yet.
+        let mut scheduled = enqueued.clone();
+        keep_ids_within_datetimes(
+            rtxn,
+            &mut scheduled,
+            self.queue.tasks.run_after,
+            Some(OffsetDateTime::now_utc()),
+            None,
+        )?;
+        enqueued -= &scheduled;
+        // The tasks enqueued after a scheduled task on the same index wait for it,
+        // only the oldest scheduled task of each index needs to be looked at.
+        let mut blocked_indexes = HashSet::new();
+        for task_id in &scheduled {
+            let task =
+                self.queue.tasks.get_task(rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
+            for index in task.indexes() {
+                if blocked_indexes.insert(index.to_string()) {
+                    let mut blocked = self.queue.tasks.index_tasks(rtxn, index)?;
+                    blocked.remove_range(..task_id);
+                    enqueued -= blocked;
+                }
+            }
+        }
+        let enqueued = &enqueued;
         let failed = &self.queue.tasks.get_status(rtxn, Status::Failed)?;
 
         // 0. The priority over everything is to upgrade the instance
@@ -456,6 +511,22 @@ impl IndexScheduler {
             return Ok(Some((Batch::UpgradeDatabase { tasks }, current_batch)));
         }
 
+        if !running_batches.is_empty() {
+            let mut global_tasks = RoaringBitmap::new();
+            for kind in [
+                Kind::TaskCancelation,
+                Kind::TaskDeletion,
+                Kind::SnapshotCreation,
+                Kind::DumpCreation,
+            ] {
+                global_tasks |= self.queue.tasks.get_kind(rtxn, kind)?;
+            }
+            // The operations that are not specific to an index wait for the running batches to finish.
+            if !global_tasks.is_disjoint(enqueued) {
+                return Ok(None);
+            }
+        }
+
         // 1. we get the last task to cancel.
         let to_cancel = self.queue.tasks.get_kind(rtxn, Kind::TaskCancelation)? & enqueued;
         if let Some(task_id) = to_cancel.max() {
@@ -490,10 +561,49 @@ impl IndexScheduler {
             return Ok(Some((Batch::Dump(task), current_batch)));
         }
 
-        // 5. We make a batch from the unprioritised tasks. Start by taking the next enqueued task.
-        let task_id = if let Some(task_id) = enqueued.min() { task_id } else { return Ok(None) };
+        // 5. We make a batch from the unprioritised tasks. Start by taking the next enqueued task
+        //    that doesn't target an index of a running batch.
+        let mut candidates = enqueued.clone();
+        for current in &running_batches {
+            for index in &current.batch.indexes {
+                candidates -= self.queue.tasks.index_tasks(rtxn, index)?;
+            }
+        }
+        // An index swap or a transaction also waits for the running batches, and the tasks
+        // enqueued after it wait for it.
+        if !running_batches.is_empty() {
+            let swaps = (self.queue.tasks.get_kind(rtxn, Kind::IndexSwap)?
+                | self.queue.tasks.get_kind(rtxn, Kind::Transaction)?)
+                & enqueued;
+            if let Some(first_swap) = swaps.min() {
+                candidates.remove_range(first_swap..);
+            }
+        }
+
+        // The index to batch is the one of the oldest candidate with the highest priority,
+        // but its tasks are still batched from the oldest one to keep their order on the index.
+        let high = self.queue.tasks.get_priority(rtxn, Priority::High)? & &candidates;
+        let low = self.queue.tasks.get_priority(rtxn, Priority::Low)? & &candidates;
+        let normal = &candidates - &high - &low;
+        let task_id = match high.min().or_else(|| normal.min()).or_else(|| low.min()) {
+            Some(task_id) => task_id,
+            None => return Ok(None),
+        };
+        let task = self.queue.tasks.get_task(rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
+        let task_id = match task.indexes().first() {
+            Some(index) => {
+                (self.queue.tasks.index_tasks(rtxn, index)? & &candidates).min().unwrap_or(task_id)
+            }
+            None => task_id,
+        };
         let mut task =
             self.queue.tasks.get_task(rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
+        // An index swap or a transaction must also wait for the older tasks of the other indexes it touches.
+        if let Some(oldest) =
+            candidates.min().filter(|&oldest| oldest != task_id && task.indexes().len() > 1)
+        {
+            task = self.queue.tasks.get_task(rtxn, oldest)?.ok_or(Error::CorruptedTaskQueue)?;
+        }
 
         // If the task is not associated with any index, verify that it is an index swap and
         // create the batch directly. Otherwise, get the index name associated with the task
@@ -515,7 +625,7 @@ impl IndexScheduler {
             primary_key = index.primary_key(&rtxn)?.map(|pk| pk.to_string());
         }
 
-        let index_tasks = self.queue.tasks.index_tasks(rtxn, index_name)? & enqueued;
+        let index_tasks = self.queue.tasks.index_tasks(rtxn, index_name)? & &candidates;
 
         // If autobatching is disabled we only take one task at a time.
         // Otherwise, we take only a maximum of tasks to create batches.
@@ -534,7 +644,7 @@ impl IndexScheduler {
                 .get_task(rtxn, task_id)
                 .and_then(|task| task.ok_or(Error::CorruptedTaskQueue))?;
 
-            if let Some(uuid) = task.content_uuid() {
+            for uuid in task.content_uuids() {
                 let content_size = self.queue.file_store.compute_size(uuid)?;
                 total_size = total_size.saturating_add(content_size);
             }
@@ -6,6 +6,7 @@ mod process_batch;
 mod process_dump_creation;
 mod process_index_operation;
 mod process_snapshot_creation;
+mod process_transaction;
 mod process_upgrade;
 #[cfg(test)]
 mod test;
@@ -16,20 +17,26 @@ mod test_embedders;
 #[cfg(test)]
 mod test_failure;
 
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
+use meilisearch_types::batches::BatchId;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::heed::{Env, WithoutTls};
 use meilisearch_types::milli;
+use meilisearch_types::milli::progress::Progress;
 use meilisearch_types::tasks::Status;
 use rayon::current_num_threads;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use roaring::RoaringBitmap;
 use synchronoise::SignalEvent;
 
-use crate::processing::{AtomicTaskStep, BatchProgress};
+use self::create_batch::Batch;
+use crate::processing::{AtomicTaskStep, BatchProgress, ProcessingTasks};
+use crate::utils::ProcessingBatch;
 use crate::{Error, IndexScheduler, IndexSchedulerOptions, Result, TickOutcome};
 
 #[derive(Default, Clone, Debug)]
@@ -43,14 +50,12 @@ impl MustStopProcessing {
     pub fn must_stop(&self) {
         self.0.store(true, Ordering::Relaxed);
     }
-
-    pub fn reset(&self) {
-        self.0.store(false, Ordering::Relaxed);
-    }
 }
 
 pub struct Scheduler {
-    /// A boolean that can be set to true to stop the currently processing tasks.
+    /// A boolean that can be set to true to stop the tasks of the batch processed by this scheduler.
+    ///
+    /// Every batch gets its own flag when it starts processing.
     pub must_stop_processing: MustStopProcessing,
 
     /// Get a signal when a batch needs to be processed.
@@ -65,6 +70,9 @@ pub struct Scheduler {
     /// The maximum size, in bytes, of tasks in a batch.
     pub(crate) batched_tasks_size_limit: u64,
 
+    /// The maximum number of batches targeting different indexes that are processed at the same time.
+    pub(crate) max_concurrent_batches: usize,
+
     /// The path used to create the dumps.
     pub(crate) dumps_path: PathBuf,
 
@@ -91,6 +99,7 @@ impl Scheduler {
             autobatching_enabled: self.autobatching_enabled,
             max_number_of_batched_tasks: self.max_number_of_batched_tasks,
             batched_tasks_size_limit: self.batched_tasks_size_limit,
+            max_concurrent_batches: self.max_concurrent_batches,
             dumps_path: self.dumps_path.clone(),
             snapshots_path: self.snapshots_path.clone(),
             auth_env: self.auth_env.clone(),
@@ -107,6 +116,7 @@ impl Scheduler {
             autobatching_enabled: options.autobatching_enabled,
             max_number_of_batched_tasks: options.max_number_of_batched_tasks,
             batched_tasks_size_limit: options.batched_tasks_size_limit,
+            max_concurrent_batches: options.max_concurrent_batches.max(1),
             dumps_path: options.dumps_path.clone(),
             snapshots_path: options.snapshots_path.clone(),
             auth_env,
@@ -137,6 +147,12 @@ impl IndexScheduler {
             self.breakpoint(crate::test_utils::Breakpoint::Start);
         }
 
+        // We don't create a new batch while the maximum number of batches is already processing.
+        let running_batches = self.processing_tasks.read().unwrap().running_batches().count();
+        if running_batches >= self.scheduler.max_concurrent_batches {
+            return Ok(TickOutcome::WaitForSignal);
+        }
+
         if self.cleanup_enabled {
             let mut wtxn = self.env.write_txn()?;
             self.queue.cleanup_task_queue(&mut wtxn)?;
@@ -144,30 +160,77 @@ impl IndexScheduler {
         }
 
         let rtxn = self.env.read_txn().map_err(Error::HeedTransaction)?;
-        let (batch, mut processing_batch) =
+        let (batch, processing_batch) =
             match self.create_next_batch(&rtxn).map_err(|e| Error::CreateBatch(Box::new(e)))? {
                 Some(batch) => batch,
                 None => return Ok(TickOutcome::WaitForSignal),
             };
-        let index_uid = batch.index_uid().map(ToOwned::to_owned);
         drop(rtxn);
 
         // 1. store the starting date with the bitmap of processing tasks.
-        let mut ids = batch.ids();
-        let processed_tasks = ids.len();
+        let ids = batch.ids();
 
-        // We reset the must_stop flag to be sure that we don't stop processing tasks
-        self.scheduler.must_stop_processing.reset();
+        // Each batch gets its own must_stop flag so that canceling its tasks doesn't stop the other batches
+        let must_stop_processing = MustStopProcessing::default();
         let progress = self
             .processing_tasks
             .write()
             .unwrap()
             // We can clone the processing batch here because we don't want its modification to affect the view of the processing batches
-            .start_processing(processing_batch.clone(), ids.clone());
+            .start_processing(processing_batch.clone(), ids.clone(), must_stop_processing.clone());
+        let mut processor = self.private_clone();
+        processor.scheduler.must_stop_processing = must_stop_processing;
 
         #[cfg(test)]
         self.breakpoint(crate::test_utils::Breakpoint::BatchCreated);
 
+        // The batches targeting a single index are processed in the background so that
+        // we can start processing the tasks of another index in the meantime.
+        if self.scheduler.max_concurrent_batches > 1 && batch.index_uid().is_some() {
+            std::thread::Builder::new()
+                .name(String::from("concurrent-batch"))
+                .spawn(move || {
+                    let ret = catch_unwind(AssertUnwindSafe(|| {
+                        processor.process_and_commit_batch(batch, processing_batch, ids, progress)
+                    }));
+                    match ret {
+                        Ok(Ok(_)) => (),
+                        Ok(Err(e)) => {
+                            tracing::error!("{e}");
+                            // Wait one second when an irrecoverable error occurs.
+                            if !e.is_recoverable() {
+                                std::thread::sleep(Duration::from_secs(1));
+                            }
+                        }
+                        Err(_panic) => {
+                            tracing::error!("Internal error: Unexpected panic while processing a concurrent batch.");
+                        }
+                    }
+                    // The index is available again, the scheduler may be waiting for it.
+                    processor.scheduler.wake_up.signal();
+                })?;
+            return Ok(TickOutcome::TickAgain(0));
+        }
+
+        processor.process_and_commit_batch(batch, processing_batch, ids, progress)
+    }
+
+    /// Process the batch and write the outcome of its tasks to disk.
+    ///
+    /// The batch must already be registered in the processing tasks, it is removed from them
+    /// once its tasks are committed and marked as aborted in any other case.
+    fn process_and_commit_batch(
+        &self,
+        batch: Batch,
+        mut processing_batch: ProcessingBatch,
+        mut ids: RoaringBitmap,
+        progress: Progress,
+    ) -> Result<TickOutcome> {
+        let batch_uid = processing_batch.uid;
+        let _abort_guard = AbortOnDrop { processing_tasks: &self.processing_tasks, batch_uid };
+        let index_uid = batch.index_uid().map(ToOwned::to_owned);
+        let processed_tasks = ids.len();
+
         // 2. Process the tasks
         let res = {
             let cloned_index_scheduler = self.private_clone();
@@ -212,8 +275,10 @@ impl IndexScheduler {
             })
         };
 
-        // Reset the currently updating index to relinquish the index handle
-        self.index_mapper.set_currently_updating_index(None);
+        // Remove the currently updating index to relinquish the index handle
+        if let Some(index_uid) = &index_uid {
+            self.index_mapper.remove_currently_updating_index(index_uid);
+        }
 
         #[cfg(test)]
         self.maybe_fail(crate::test_utils::FailureLocation::AcquiringWtxn)?;
@@ -280,8 +345,9 @@ impl IndexScheduler {
 
                 tracing::info!("A batch of tasks was aborted.");
                 // We make sure that we don't call `stop_processing` on the `processing_tasks`,
-                // this is because we want to let the next tick call `create_next_batch` and keep
-                // the `started_at` date times and `processings` of the current processing tasks.
+                // the batch is only marked as aborted, this is because we want to let the next tick
+                // call `create_next_batch` and keep the `started_at` date times and `processings`
+                // of the current processing tasks.
                 // This date time is used by the task cancelation to store the right `started_at`
                 // date in the task on disk.
                 return Ok(TickOutcome::TickAgain(0));
@@ -380,7 +446,7 @@ impl IndexScheduler {
 
         // We should stop processing AFTER everything is processed and written to disk otherwise, a batch (which only lives in RAM) may appear in the processing task
         // and then become « not found » for some time until the commit everything is written and the final commit is made.
-        self.processing_tasks.write().unwrap().stop_processing();
+        self.processing_tasks.write().unwrap().stop_processing(batch_uid);
 
         // Once the tasks are committed, we should delete all the update files associated ASAP to avoid leaking files in case of a restart
         tracing::debug!("Deleting the update files");
@@ -419,3 +485,19 @@ impl IndexScheduler {
         }
     }
 }
+
+/// Marks a batch as aborted if it is still processing when dropped.
+///
+/// It makes sure the index of a batch that failed to be committed is released.
+struct AbortOnDrop<'a> {
+    processing_tasks: &'a RwLock<ProcessingTasks>,
+    batch_uid: BatchId,
+}
+
+impl Drop for AbortOnDrop<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut processing_tasks) = self.processing_tasks.write() {
+            processing_tasks.abort_processing(self.batch_uid);
+        }
+    }
+}
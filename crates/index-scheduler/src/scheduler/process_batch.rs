@@ -135,8 +135,7 @@ impl IndexScheduler {
                 };
 
                 // the index operation can take a long time, so save this handle to make it available to the search for the duration of the tick
-                self.index_mapper
-                    .set_currently_updating_index(Some((index_uid.clone(), index.clone())));
+                self.index_mapper.insert_currently_updating_index(index_uid.clone(), index.clone());
 
                 let mut index_wtxn = index.write_txn()?;
                 let (tasks, congestion) =
@@ -323,6 +322,7 @@ impl IndexScheduler {
                 task.status = Status::Succeeded;
                 Ok((vec![task], None))
             }
+            Batch::Transaction { task } => self.process_transaction(progress, task),
             Batch::UpgradeDatabase { mut tasks } => {
                 let KindWithContent::UpgradeDatabase { from } = tasks.last().unwrap().kind else {
                     unreachable!();
@@ -357,7 +357,7 @@ impl IndexScheduler {
     }
 
     /// Swap the index `lhs` with the index `rhs`.
-    fn apply_index_swap(
+    pub(super) fn apply_index_swap(
         &self,
         wtxn: &mut RwTxn,
         progress: &Progress,
@@ -438,6 +438,8 @@ impl IndexScheduler {
         let mut affected_indexes = HashSet::new();
         let mut affected_statuses = HashSet::new();
         let mut affected_kinds = HashSet::new();
+        let mut affected_priorities = HashSet::new();
+        let mut affected_custom_metadata = HashSet::new();
         let mut affected_canceled_by = RoaringBitmap::new();
         // The tasks that have been removed *per batches*.
         let mut affected_batches: HashMap<BatchId, RoaringBitmap> = HashMap::new();
@@ -451,6 +453,14 @@ impl IndexScheduler {
             affected_indexes.extend(task.indexes().into_iter().map(|x| x.to_owned()));
             affected_statuses.insert(task.status);
             affected_kinds.insert(task.kind.as_kind());
+            if !task.priority.is_normal() {
+                affected_priorities.insert(task.priority);
+            }
+            if let Some(custom_metadata) = &task.custom_metadata {
+                affected_custom_metadata.insert(custom_metadata.clone());
+            }
+            // The idempotency key of a deleted task can be used again.
+            self.queue.tasks.delete_idempotency_key(wtxn, &task)?;
             // Note: don't delete the persisted task data since
             // we can only delete succeeded, failed, and canceled tasks.
             // In each of those cases, the persisted data is supposed to
@@ -477,6 +487,9 @@ impl IndexScheduler {
                     task.uid,
                 )?;
             }
+            if let Some(run_after) = task.run_after {
+                utils::remove_task_datetime(wtxn, self.queue.tasks.run_after, run_after, task.uid)?;
+            }
             if let Some(canceled_by) = task.canceled_by {
                 affected_canceled_by.insert(canceled_by);
             }
@@ -488,7 +501,11 @@ impl IndexScheduler {
 
         progress.update_progress(TaskDeletionProgress::DeletingTasksMetadata);
         let (atomic_progress, task_progress) = AtomicTaskStep::new(
-            (affected_indexes.len() + affected_statuses.len() + affected_kinds.len()) as u32,
+            (affected_indexes.len()
+                + affected_statuses.len()
+                + affected_kinds.len()
+                + affected_priorities.len()
+                + affected_custom_metadata.len()) as u32,
         );
         progress.update_progress(task_progress);
         for index in affected_indexes.iter() {
@@ -506,6 +523,20 @@ impl IndexScheduler {
             atomic_progress.fetch_add(1, Ordering::Relaxed);
         }
 
+        for priority in affected_priorities.iter() {
+            self.queue
+                .tasks
+                .update_priority(wtxn, *priority, |bitmap| *bitmap -= &to_delete_tasks)?;
+            atomic_progress.fetch_add(1, Ordering::Relaxed);
+        }
+
+        for custom_metadata in affected_custom_metadata.iter() {
+            self.queue.tasks.update_custom_metadata(wtxn, custom_metadata, |bitmap| {
+                *bitmap -= &to_delete_tasks
+            })?;
+            atomic_progress.fetch_add(1, Ordering::Relaxed);
+        }
+
         progress.update_progress(TaskDeletionProgress::DeletingTasks);
         let (atomic_progress, task_progress) = AtomicTaskStep::new(to_delete_tasks.len() as u32);
         progress.update_progress(task_progress);
@@ -58,7 +58,7 @@ impl IndexScheduler {
 
             let (_, mut t) = ret?;
             let status = t.status;
-            let content_file = t.content_uuid();
+            let content_files = t.content_uuids();
 
             // In the case we're dumping ourselves we want to be marked as finished
             // to not loop over ourselves indefinitely.
@@ -82,20 +82,23 @@ impl IndexScheduler {
             let mut dump_content_file = dump_tasks.push_task(&t.into())?;
 
             // 2.1. Dump the `content_file` associated with the task if there is one and the task is not finished yet.
-            if let Some(content_file) = content_file {
+            //      The documents of all the content files of a transaction are dumped one after the other.
+            if !content_files.is_empty() {
                 if self.scheduler.must_stop_processing.get() {
                     return Err(Error::AbortedTask);
                 }
                 if status == Status::Enqueued {
-                    let content_file = self.queue.file_store.get_update(content_file)?;
-
-                    for document in
-                        serde_json::de::Deserializer::from_reader(content_file).into_iter()
-                    {
-                        let document = document.map_err(|e| {
-                            Error::from_milli(milli::InternalError::SerdeJson(e).into(), None)
-                        })?;
-                        dump_content_file.push_document(&document)?;
+                    for content_file in content_files {
+                        let content_file = self.queue.file_store.get_update(content_file)?;
+
+                        for document in
+                            serde_json::de::Deserializer::from_reader(content_file).into_iter()
+                        {
+                            let document = document.map_err(|e| {
+                                Error::from_milli(milli::InternalError::SerdeJson(e).into(), None)
+                            })?;
+                            dump_content_file.push_document(&document)?;
+                        }
                     }
 
                     dump_content_file.flush()?;
@@ -222,13 +225,14 @@ impl IndexScheduler {
                     };
 
                     for (embedder_name, embeddings) in embeddings {
-                        let user_provided = embedding_configs
-                            .iter()
-                            .find(|conf| conf.name == embedder_name)
-                            .is_some_and(|conf| conf.user_provided.contains(id));
+                        let config =
+                            embedding_configs.iter().find(|conf| conf.name == embedder_name);
+                        let user_provided =
+                            config.is_some_and(|conf| conf.user_provided.contains(id));
+                        let sparse = config.is_some_and(|conf| conf.config.is_sparse());
                         let embeddings = ExplicitVectors {
-                            embeddings: Some(VectorOrArrayOfVectors::from_array_of_vectors(
-                                embeddings,
+                            embeddings: Some(VectorOrArrayOfVectors::from_stored(
+                                embeddings, sparse,
                             )),
                             regenerate: !user_provided,
                         };
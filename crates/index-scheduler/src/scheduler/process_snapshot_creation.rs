@@ -58,7 +58,7 @@ impl IndexScheduler {
         for task_id in enqueued {
             let task =
                 self.queue.tasks.get_task(&rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
-            if let Some(content_uuid) = task.content_uuid() {
+            for content_uuid in task.content_uuids() {
                 let src = self.queue.file_store.get_update_path(content_uuid);
                 let dst = update_files_dir.join(content_uuid.to_string());
                 fs::copy(src, dst)?;
@@ -0,0 +1,333 @@
+use std::collections::{BTreeSet, HashMap};
+
+use meilisearch_types::milli::progress::{Progress, VariableNameStep};
+use meilisearch_types::milli::update::IndexDocumentsMethod;
+use meilisearch_types::milli::{self, ChannelCongestion};
+use meilisearch_types::settings::{Settings, Unchecked};
+use meilisearch_types::tasks::{
+    Details, IndexSwap, KindWithContent, Status, Task, TransactionOperation,
+};
+
+use super::create_batch::{DocumentOperation, IndexOperation};
+use crate::processing::TransactionProgress;
+use crate::{Error, IndexScheduler, Result};
+
+/// The operations of a transaction that target the same index.
+#[derive(Default)]
+struct IndexTransaction {
+    /// The name of the index before the swaps of the transaction are applied.
+    index_uid: String,
+    allow_index_creation: bool,
+    primary_key: Option<String>,
+    /// The position of each operation in the transaction along with its own task.
+    documents: Vec<(usize, DocumentOperation, Task)>,
+    settings: Vec<(usize, (bool, Settings<Unchecked>), Task)>,
+}
+
+impl IndexScheduler {
+    /// Apply all the operations of a transaction and only commit them if they all succeed.
+    ///
+    /// The document operations of an index are indexed together, before the settings updates
+    /// of this index, which leads to the same final state as applying them one by one.
+    /// The index swaps are applied last, but the operations given after a swap are sent
+    /// to the swapped indexes.
+    pub(super) fn process_transaction(
+        &self,
+        progress: Progress,
+        mut task: Task,
+    ) -> Result<(Vec<Task>, Option<ChannelCongestion>)> {
+        let mut created_indexes = Vec::new();
+        let ret = self.apply_transaction(&progress, &mut task, &mut created_indexes);
+
+        // Nothing must remain of a failed transaction, not even the indexes it created.
+        if ret.is_err() || task.status == Status::Failed {
+            for index_uid in created_indexes {
+                let res = self
+                    .env
+                    .write_txn()
+                    .map_err(Error::from)
+                    .and_then(|wtxn| self.index_mapper.delete_index(wtxn, &index_uid));
+                if let Err(e) = res {
+                    tracing::error!(
+                        error = &e as &dyn std::error::Error,
+                        "Could not delete the index created by a failed transaction"
+                    );
+                }
+            }
+        }
+
+        ret.map(|congestion| (vec![task], congestion))
+    }
+
+    /// Apply the operations of the transaction, the indexes it creates are pushed in
+    /// `created_indexes` and only removed from it once every change is committed.
+    fn apply_transaction(
+        &self,
+        progress: &Progress,
+        task: &mut Task,
+        created_indexes: &mut Vec<String>,
+    ) -> Result<Option<ChannelCongestion>> {
+        progress.update_progress(TransactionProgress::EnsuringTheIndexesExist);
+        let operations = match &task.kind {
+            KindWithContent::Transaction { operations } => operations,
+            _ => unreachable!(),
+        };
+
+        // 1. Group the operations by the index they target once the previous swaps are applied.
+        let mut swapped = HashMap::<&str, &str>::new();
+        let mut swaps = Vec::<&IndexSwap>::new();
+        let mut index_transactions = Vec::<IndexTransaction>::new();
+        for (position, operation) in operations.iter().enumerate() {
+            if let TransactionOperation::IndexSwap { swaps: operation_swaps } = operation {
+                for swap in operation_swaps {
+                    let (lhs, rhs) = (swap.indexes.0.as_str(), swap.indexes.1.as_str());
+                    let original_lhs = swapped.get(lhs).copied().unwrap_or(lhs);
+                    let original_rhs = swapped.get(rhs).copied().unwrap_or(rhs);
+                    swapped.insert(lhs, original_rhs);
+                    swapped.insert(rhs, original_lhs);
+                    swaps.push(swap);
+                }
+                continue;
+            }
+
+            let index_uid = operation.index_uid().unwrap();
+            let index_uid = swapped.get(index_uid).copied().unwrap_or(index_uid);
+            let index_transaction =
+                match index_transactions.iter().position(|t| t.index_uid == index_uid) {
+                    Some(i) => &mut index_transactions[i],
+                    None => {
+                        index_transactions.push(IndexTransaction {
+                            index_uid: index_uid.to_string(),
+                            ..Default::default()
+                        });
+                        index_transactions.last_mut().unwrap()
+                    }
+                };
+
+            let kind = operation.to_kind();
+            let operation_task = Task {
+                details: kind.default_details(),
+                kind,
+                status: Status::Enqueued,
+                error: None,
+                ..task.clone()
+            };
+            match operation {
+                TransactionOperation::DocumentAdditionOrUpdate {
+                    primary_key,
+                    method,
+                    content_file,
+                    allow_index_creation,
+                    ..
+                } => {
+                    index_transaction.allow_index_creation |= *allow_index_creation;
+                    match (&index_transaction.primary_key, primary_key) {
+                        (Some(pk), Some(primary_key)) if pk != primary_key => {
+                            return Err(Error::from_milli(
+                                milli::UserError::PrimaryKeyCannotBeChanged(pk.clone()).into(),
+                                Some(index_uid.to_string()),
+                            ));
+                        }
+                        (None, primary_key) => index_transaction.primary_key = primary_key.clone(),
+                        _ => (),
+                    }
+                    let document_operation = match method {
+                        IndexDocumentsMethod::ReplaceDocuments => {
+                            DocumentOperation::Replace(*content_file)
+                        }
+                        IndexDocumentsMethod::UpdateDocuments => {
+                            DocumentOperation::Update(*content_file)
+                        }
+                    };
+                    index_transaction.documents.push((
+                        position,
+                        document_operation,
+                        operation_task,
+                    ));
+                }
+                TransactionOperation::DocumentDeletion { documents_ids, .. } => {
+                    let document_operation = DocumentOperation::Delete(documents_ids.clone());
+                    index_transaction.documents.push((
+                        position,
+                        document_operation,
+                        operation_task,
+                    ));
+                }
+                TransactionOperation::SettingsUpdate {
+                    new_settings,
+                    is_deletion,
+                    allow_index_creation,
+                    ..
+                } => {
+                    index_transaction.allow_index_creation |=
+                        *allow_index_creation && !*is_deletion;
+                    let settings = (*is_deletion, *new_settings.clone());
+                    index_transaction.settings.push((position, settings, operation_task));
+                }
+                TransactionOperation::IndexSwap { .. } => unreachable!(),
+            }
+        }
+
+        // 2. Retrieve the indexes and create the missing ones if it is allowed.
+        let mut indexes = Vec::with_capacity(index_transactions.len());
+        for index_transaction in &index_transactions {
+            let index_uid = &index_transaction.index_uid;
+            let rtxn = self.env.read_txn()?;
+            let index = match self.index_mapper.index(&rtxn, index_uid) {
+                Ok(index) => index,
+                Err(Error::IndexNotFound(_)) if index_transaction.allow_index_creation => {
+                    drop(rtxn);
+                    let wtxn = self.env.write_txn()?;
+                    let index = self.index_mapper.create_index(wtxn, index_uid, None)?;
+                    created_indexes.push(index_uid.clone());
+                    index
+                }
+                Err(e) => return Err(e),
+            };
+            indexes.push((index_uid.clone(), index));
+        }
+
+        // 3. Apply the operations on their index without committing anything.
+        progress.update_progress(TransactionProgress::ApplyingTheOperations);
+        let mut operations_details: Vec<_> =
+            operations.iter().map(TransactionOperation::default_details).collect();
+        let mut index_wtxns = Vec::with_capacity(indexes.len());
+        let mut congestion = None;
+        let mut failure = None;
+        let total = index_transactions.len() as u32;
+        for (step, (index_transaction, (_, index))) in
+            index_transactions.into_iter().zip(&indexes).enumerate()
+        {
+            let IndexTransaction { index_uid, primary_key, documents, settings, .. } =
+                index_transaction;
+            progress.update_progress(VariableNameStep::<TransactionProgress>::new(
+                format!("applying the operations of index {index_uid}"),
+                step as u32,
+                total,
+            ));
+            let mut index_wtxn = index.write_txn()?;
+            let mut positions = Vec::new();
+            let mut processed_tasks = Vec::new();
+
+            if !documents.is_empty() {
+                let mut operations = Vec::with_capacity(documents.len());
+                let mut tasks = Vec::with_capacity(documents.len());
+                for (position, operation, task) in documents {
+                    positions.push(position);
+                    operations.push(operation);
+                    tasks.push(task);
+                }
+                let op = IndexOperation::DocumentOperation {
+                    index_uid: index_uid.clone(),
+                    primary_key,
+                    operations,
+                    tasks,
+                };
+                let (mut tasks, index_congestion) =
+                    self.apply_index_operation(&mut index_wtxn, index, op, progress.clone())?;
+                congestion = congestion.or(index_congestion);
+                processed_tasks.append(&mut tasks);
+            }
+
+            if !settings.is_empty() {
+                let mut index_settings = Vec::with_capacity(settings.len());
+                let mut tasks = Vec::with_capacity(settings.len());
+                for (position, settings, task) in settings {
+                    positions.push(position);
+                    index_settings.push(settings);
+                    tasks.push(task);
+                }
+                let op = IndexOperation::Settings { index_uid, settings: index_settings, tasks };
+                let (mut tasks, _congestion) =
+                    self.apply_index_operation(&mut index_wtxn, index, op, progress.clone())?;
+                processed_tasks.append(&mut tasks);
+            }
+
+            for (position, processed_task) in positions.into_iter().zip(processed_tasks) {
+                if let Some(details) = processed_task.details {
+                    *operations_details[position].details = details;
+                }
+                if let Some(error) = processed_task.error {
+                    // The error of the first operation that failed is reported.
+                    if failure.as_ref().is_none_or(|(first, _)| position < *first) {
+                        failure = Some((position, error));
+                    }
+                }
+            }
+            index_wtxns.push(index_wtxn);
+        }
+
+        if let Some((_, error)) = failure {
+            task.status = Status::Failed;
+            task.error = Some(error);
+            task.details =
+                Some(Details::Transaction { operations: operations_details }.to_failed());
+            return Ok(None);
+        }
+
+        // 4. Swap the indexes in a single write transaction of the scheduler, committed after
+        //    the indexes. The tasks are updated afterward, like for any other batch.
+        progress.update_progress(TransactionProgress::SwappingTheIndexes);
+        let mut wtxn = self.env.write_txn()?;
+        let mut not_found_indexes = BTreeSet::new();
+        for IndexSwap { indexes: (lhs, rhs) } in &swaps {
+            for index in [lhs, rhs] {
+                if !self.index_mapper.index_exists(&wtxn, index)? {
+                    not_found_indexes.insert(index);
+                }
+            }
+        }
+        if !not_found_indexes.is_empty() {
+            if not_found_indexes.len() == 1 {
+                return Err(Error::SwapIndexNotFound(
+                    not_found_indexes.into_iter().next().unwrap().clone(),
+                ));
+            } else {
+                return Err(Error::SwapIndexesNotFound(
+                    not_found_indexes.into_iter().cloned().collect(),
+                ));
+            }
+        }
+        for swap in &swaps {
+            self.apply_index_swap(&mut wtxn, progress, task.uid, &swap.indexes.0, &swap.indexes.1)?;
+        }
+
+        // 5. Commit everything. Each index is its own environment, so their commits are not atomic:
+        //    if one fails, the indexes committed before it keep their changes, the swaps are not
+        //    applied, and the indexes created by the transaction are deleted.
+        progress.update_progress(TransactionProgress::CommittingTheChanges);
+        for index_wtxn in index_wtxns {
+            index_wtxn.commit()?;
+        }
+        wtxn.commit()?;
+        // The created indexes are now part of the committed transaction.
+        created_indexes.clear();
+
+        // Storing the new stats of the indexes is not critical and must not fail the transaction.
+        for (original_uid, index) in &indexes {
+            let index_uid = swapped
+                .iter()
+                .find(|(_, &original)| original == original_uid)
+                .map_or(original_uid.as_str(), |(&index_uid, _)| index_uid);
+            let res = || -> Result<()> {
+                let index_rtxn = index.read_txn()?;
+                let stats = crate::index_mapper::IndexStats::new(index, &index_rtxn)
+                    .map_err(|e| Error::from_milli(e, Some(index_uid.to_string())))?;
+                let mut wtxn = self.env.write_txn()?;
+                self.index_mapper.store_stats_of(&mut wtxn, index_uid, &stats)?;
+                wtxn.commit()?;
+                Ok(())
+            }();
+            if let Err(e) = res {
+                tracing::error!(
+                    error = &e as &dyn std::error::Error,
+                    "Could not write the stats of the index"
+                );
+            }
+        }
+
+        task.status = Status::Succeeded;
+        task.details = Some(Details::Transaction { operations: operations_details });
+        Ok(congestion)
+    }
+}
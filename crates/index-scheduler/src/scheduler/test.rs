@@ -7,15 +7,18 @@ use meilisearch_types::milli::index::IndexEmbeddingConfig;
 use meilisearch_types::milli::update::IndexDocumentsMethod::*;
 use meilisearch_types::milli::{self};
 use meilisearch_types::settings::SettingEmbeddingSettings;
-use meilisearch_types::tasks::{IndexSwap, KindWithContent};
+use meilisearch_types::tasks::{
+    IndexSwap, KindWithContent, Priority, Status, TransactionOperation,
+};
 use roaring::RoaringBitmap;
+use time::{Duration, OffsetDateTime};
 
 use crate::insta_snapshot::snapshot_index_scheduler;
 use crate::test_utils::Breakpoint::*;
 use crate::test_utils::{
     index_creation_task, read_json, replace_document_import_task, sample_documents,
 };
-use crate::IndexScheduler;
+use crate::{IndexScheduler, RegisterOptions};
 
 #[test]
 fn insert_task_while_another_task_is_processing() {
@@ -48,6 +51,244 @@ fn test_task_is_processing() {
     assert!(index_scheduler.is_task_processing().unwrap());
 }
 
+#[test]
+fn create_batch_while_another_batch_is_processing() {
+    let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+    index_scheduler.register(index_creation_task("index_a", "id"), None, false).unwrap();
+    handle.advance_till([Start, BatchCreated]);
+
+    index_scheduler
+        .register(KindWithContent::IndexDeletion { index_uid: S("index_a") }, None, false)
+        .unwrap();
+    index_scheduler.register(index_creation_task("index_b", "id"), None, false).unwrap();
+
+    // The tasks of index_a must wait for the processing batch, but index_b is free.
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let (batch, processing_batch) = index_scheduler.create_next_batch(&rtxn).unwrap().unwrap();
+    assert_eq!(batch.index_uid(), Some("index_b"));
+    assert_eq!(batch.ids(), RoaringBitmap::from_iter([2]));
+    // The processing batch is not written to disk yet but its uid is already taken.
+    assert_eq!(processing_batch.uid, 1);
+    drop(rtxn);
+
+    // The dumps wait for every processing batch to finish.
+    index_scheduler
+        .register(KindWithContent::DumpCreation { keys: vec![], instance_uid: None }, None, false)
+        .unwrap();
+    let rtxn = index_scheduler.read_txn().unwrap();
+    assert!(index_scheduler.create_next_batch(&rtxn).unwrap().is_none());
+}
+
+#[test]
+fn create_batch_by_priority() {
+    let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
+    let low = RegisterOptions { priority: Priority::Low, ..Default::default() };
+    let high = RegisterOptions { priority: Priority::High, ..Default::default() };
+
+    index_scheduler.register(index_creation_task("index_a", "id"), None, false).unwrap();
+    index_scheduler.register_with_options(index_creation_task("index_b", "id"), low).unwrap();
+    index_scheduler
+        .register_with_options(KindWithContent::IndexDeletion { index_uid: S("index_b") }, high)
+        .unwrap();
+
+    // The high priority task goes first, but after the older tasks of its index.
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let (batch, _) = index_scheduler.create_next_batch(&rtxn).unwrap().unwrap();
+    assert_eq!(batch.index_uid(), Some("index_b"));
+    assert!(batch.ids().contains(1));
+    assert!(!batch.ids().contains(0));
+}
+
+#[test]
+fn create_batch_ignores_scheduled_tasks() {
+    let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
+    let now = OffsetDateTime::now_utc();
+    let later = RegisterOptions { run_after: Some(now + Duration::days(1)), ..Default::default() };
+    let earlier =
+        RegisterOptions { run_after: Some(now - Duration::days(1)), ..Default::default() };
+
+    index_scheduler.register_with_options(index_creation_task("index_a", "id"), later).unwrap();
+    index_scheduler.register_with_options(index_creation_task("index_b", "id"), earlier).unwrap();
+
+    // The task of index_a must wait for its date, but the date of the task of index_b is reached.
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let (batch, _) = index_scheduler.create_next_batch(&rtxn).unwrap().unwrap();
+    assert_eq!(batch.index_uid(), Some("index_b"));
+    assert_eq!(batch.ids(), RoaringBitmap::from_iter([1]));
+
+    let next = index_scheduler.queue.tasks.next_run_after(&rtxn, now).unwrap();
+    assert_eq!(next, Some(now + Duration::days(1)));
+}
+
+#[test]
+fn create_batch_keeps_later_tasks_behind_scheduled_tasks() {
+    let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
+    let later = RegisterOptions {
+        run_after: Some(OffsetDateTime::now_utc() + Duration::days(1)),
+        ..Default::default()
+    };
+
+    index_scheduler.register_with_options(index_creation_task("index_a", "id"), later).unwrap();
+    index_scheduler.register(index_creation_task("index_a", "id"), None, false).unwrap();
+    index_scheduler.register(index_creation_task("index_b", "id"), None, false).unwrap();
+
+    // The second task of index_a must not run before the scheduled one, but index_b isn't blocked.
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let (batch, _) = index_scheduler.create_next_batch(&rtxn).unwrap().unwrap();
+    assert_eq!(batch.index_uid(), Some("index_b"));
+    assert_eq!(batch.ids(), RoaringBitmap::from_iter([2]));
+}
+
+#[test]
+fn process_transaction_on_several_indexes() {
+    let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+    let mut operations = Vec::new();
+    for (file_uuid, index) in [(0, "index_a"), (1, "index_b")] {
+        let (file, documents_count) = sample_documents(&index_scheduler, file_uuid, 0);
+        file.persist().unwrap();
+        operations.push(TransactionOperation::DocumentAdditionOrUpdate {
+            index_uid: S(index),
+            primary_key: Some(S("id")),
+            method: ReplaceDocuments,
+            content_file: uuid::Uuid::from_u128(file_uuid),
+            documents_count,
+            allow_index_creation: true,
+        });
+    }
+    operations.push(TransactionOperation::IndexSwap {
+        swaps: vec![IndexSwap { indexes: (S("index_a"), S("index_b")) }],
+    });
+    index_scheduler.register(KindWithContent::Transaction { operations }, None, false).unwrap();
+    handle.advance_one_successful_batch();
+
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let task = index_scheduler.queue.tasks.get_task(&rtxn, 0).unwrap().unwrap();
+    snapshot!(task.status, @"succeeded");
+    for index_uid in ["index_a", "index_b"] {
+        let index = index_scheduler.index(index_uid).unwrap();
+        let index_rtxn = index.read_txn().unwrap();
+        snapshot!(index.number_of_documents(&index_rtxn).unwrap(), @"1");
+    }
+}
+
+#[test]
+fn failed_transaction_changes_nothing() {
+    let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+    let (file, documents_count) = sample_documents(&index_scheduler, 0, 0);
+    file.persist().unwrap();
+    let operations = vec![
+        TransactionOperation::DocumentAdditionOrUpdate {
+            index_uid: S("index_a"),
+            primary_key: Some(S("id")),
+            method: ReplaceDocuments,
+            content_file: uuid::Uuid::from_u128(0),
+            documents_count,
+            allow_index_creation: true,
+        },
+        // The index doesn't exist and deleting documents can't create it.
+        TransactionOperation::DocumentDeletion {
+            index_uid: S("index_b"),
+            documents_ids: vec![S("0")],
+        },
+    ];
+    index_scheduler.register(KindWithContent::Transaction { operations }, None, false).unwrap();
+    handle.advance_one_failed_batch();
+
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let task = index_scheduler.queue.tasks.get_task(&rtxn, 0).unwrap().unwrap();
+    assert_eq!(task.status, Status::Failed);
+    snapshot!(task.error.unwrap().message, @"Index `index_b` not found.");
+    // The index created by the transaction has been removed.
+    snapshot!(index_scheduler.index_exists("index_a").unwrap(), @"false");
+}
+
+#[test]
+fn cancel_a_batch_while_another_batch_is_processing() {
+    let (index_scheduler, mut handle) = IndexScheduler::test_with_concurrent_batches(2);
+
+    let status = |index_scheduler: &IndexScheduler, task_uid| {
+        let rtxn = index_scheduler.read_txn().unwrap();
+        index_scheduler.queue.tasks.get_task(&rtxn, task_uid).unwrap().unwrap().status
+    };
+    let running_batches = |index_scheduler: &IndexScheduler| {
+        let processing_tasks = index_scheduler.processing_tasks.read().unwrap();
+        processing_tasks
+            .running_batches()
+            .map(|current| (*current.processing).clone())
+            .collect::<Vec<_>>()
+    };
+
+    index_scheduler.register(index_creation_task("catto", "id"), None, false).unwrap();
+    index_scheduler.register(index_creation_task("doggo", "id"), None, false).unwrap();
+    handle.advance_until(|s| status(s, 0) != Status::Enqueued && status(s, 1) != Status::Enqueued);
+    assert_eq!(status(&index_scheduler, 0), Status::Succeeded);
+    assert_eq!(status(&index_scheduler, 1), Status::Succeeded);
+
+    // Holding the write transactions of the indexes keeps their batches processing.
+    let catto = index_scheduler.index("catto").unwrap();
+    let doggo = index_scheduler.index("doggo").unwrap();
+    let catto_wtxn = catto.write_txn().unwrap();
+    let doggo_wtxn = doggo.write_txn().unwrap();
+
+    for (file_uuid, index) in [(0, "catto"), (1, "doggo")] {
+        let (file, documents_count) =
+            sample_documents(&index_scheduler, file_uuid, file_uuid as usize);
+        file.persist().unwrap();
+        index_scheduler
+            .register(
+                replace_document_import_task(index, None, file_uuid, documents_count),
+                None,
+                false,
+            )
+            .unwrap();
+    }
+
+    handle.advance_until(|s| running_batches(s).len() == 2);
+    assert_eq!(
+        running_batches(&index_scheduler),
+        [RoaringBitmap::from_iter([2]), RoaringBitmap::from_iter([3])]
+    );
+
+    // Canceling the catto task only stops its batch.
+    index_scheduler
+        .register(
+            KindWithContent::TaskCancelation {
+                query: "test_query".to_owned(),
+                tasks: RoaringBitmap::from_iter([2]),
+            },
+            None,
+            false,
+        )
+        .unwrap();
+    drop(catto_wtxn);
+
+    handle.advance_until(|s| running_batches(s).len() == 1);
+    assert_eq!(running_batches(&index_scheduler), [RoaringBitmap::from_iter([3])]);
+    // The cancelation waits for the doggo batch to finish.
+    assert_eq!(status(&index_scheduler, 2), Status::Enqueued);
+    assert_eq!(status(&index_scheduler, 4), Status::Enqueued);
+
+    drop(doggo_wtxn);
+    handle.advance_until(|s| status(s, 3) != Status::Enqueued && status(s, 4) != Status::Enqueued);
+    assert_eq!(status(&index_scheduler, 2), Status::Canceled);
+    assert_eq!(status(&index_scheduler, 3), Status::Succeeded);
+    assert_eq!(status(&index_scheduler, 4), Status::Succeeded);
+
+    // The catto index was released and its next tasks are processed.
+    let (file, documents_count) = sample_documents(&index_scheduler, 2, 2);
+    file.persist().unwrap();
+    index_scheduler
+        .register(replace_document_import_task("catto", None, 2, documents_count), None, false)
+        .unwrap();
+    handle.advance_until(|s| status(s, 5) != Status::Enqueued);
+    assert_eq!(status(&index_scheduler, 5), Status::Succeeded);
+    let rtxn = catto.read_txn().unwrap();
+    assert_eq!(catto.number_of_documents(&rtxn).unwrap(), 1);
+}
+
 /// We send a lot of tasks but notify the tasks scheduler only once as
 /// we send them very fast, we must make sure that they are all processed.
 #[test]
@@ -740,6 +981,7 @@ fn basic_get_stats() {
         "snapshotCreation": 0,
         "taskCancelation": 0,
         "taskDeletion": 0,
+        "transaction": 0,
         "upgradeDatabase": 0
       }
     }
@@ -773,6 +1015,7 @@ fn basic_get_stats() {
         "snapshotCreation": 0,
         "taskCancelation": 0,
         "taskDeletion": 0,
+        "transaction": 0,
         "upgradeDatabase": 0
       }
     }
@@ -813,6 +1056,7 @@ fn basic_get_stats() {
         "snapshotCreation": 0,
         "taskCancelation": 0,
         "taskDeletion": 0,
+        "transaction": 0,
         "upgradeDatabase": 0
       }
     }
@@ -854,6 +1098,7 @@ fn basic_get_stats() {
         "snapshotCreation": 0,
         "taskCancelation": 0,
         "taskDeletion": 0,
+        "transaction": 0,
         "upgradeDatabase": 0
       }
     }
@@ -918,6 +1163,7 @@ fn create_and_list_index() {
             "database_size": "[bytes]",
             "number_of_embeddings": 0,
             "number_of_embedded_documents": 0,
+            "embedder_storage_sizes": {},
             "used_database_size": "[bytes]",
             "primary_key": null,
             "field_distribution": {},
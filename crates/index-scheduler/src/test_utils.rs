@@ -80,6 +80,19 @@ impl IndexScheduler {
         })
     }
 
+    /// Creates a scheduler processing up to `max_concurrent_batches` batches at the same time.
+    ///
+    /// The breakpoints of the concurrent batches are sent from several threads in any order, the
+    /// test must progress with [`IndexSchedulerHandle::advance_until`].
+    pub(crate) fn test_with_concurrent_batches(
+        max_concurrent_batches: usize,
+    ) -> (Self, IndexSchedulerHandle) {
+        Self::test_with_custom_config(vec![], |config| {
+            config.max_concurrent_batches = max_concurrent_batches;
+            None
+        })
+    }
+
     pub(crate) fn test_with_custom_config(
         planned_failures: Vec<(usize, FailureLocation)>,
         configuration: impl Fn(&mut IndexSchedulerOptions) -> Option<(u32, u32, u32)>,
@@ -110,6 +123,7 @@ impl IndexScheduler {
             max_number_of_tasks: 1_000_000,
             max_number_of_batched_tasks: usize::MAX,
             batched_tasks_size_limit: u64::MAX,
+            max_concurrent_batches: 1,
             instance_features: Default::default(),
             auto_upgrade: true, // Don't cost much and will ensure the happy path works
             embedding_cache_cap: 10,
@@ -338,6 +352,29 @@ impl IndexSchedulerHandle {
         breakpoint_2
     }
 
+    /// Let the scheduler run, whatever the breakpoints it goes through, until the condition holds.
+    ///
+    /// The batches processed concurrently send their breakpoints from different threads and in
+    /// any order, which prevents advancing through them one by one.
+    #[track_caller]
+    pub(crate) fn advance_until(&mut self, condition: impl Fn(&IndexScheduler) -> bool) {
+        let start = std::time::Instant::now();
+        while !condition(&self.index_scheduler) {
+            if start.elapsed() > Duration::from_secs(50) {
+                let state = snapshot_index_scheduler(&self.index_scheduler);
+                panic!("The scheduler never reached the expected state.\n{state}")
+            }
+            // The scheduler may be processing without going through any breakpoint.
+            match self.test_breakpoint_rcv.recv_timeout(Duration::from_millis(10)) {
+                Ok(_) | Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => {
+                    let state = snapshot_index_scheduler(&self.index_scheduler);
+                    panic!("The scheduler crashed.\n{state}")
+                }
+            }
+        }
+    }
+
     /// Advance the scheduler until all the provided breakpoints are reached in order.
     #[track_caller]
     pub(crate) fn advance_till(&mut self, breakpoints: impl IntoIterator<Item = Breakpoint>) {
@@ -1,6 +1,6 @@
 use anyhow::bail;
 use meilisearch_types::heed::{Env, RwTxn, WithoutTls};
-use meilisearch_types::tasks::{Details, KindWithContent, Status, Task};
+use meilisearch_types::tasks::{Details, KindWithContent, Priority, Status, Task};
 use meilisearch_types::versioning::{VERSION_MAJOR, VERSION_MINOR, VERSION_PATCH};
 use time::OffsetDateTime;
 use tracing::info;
@@ -78,10 +78,14 @@ pub fn upgrade_index_scheduler(
             enqueued_at: OffsetDateTime::now_utc(),
             started_at: None,
             finished_at: None,
+            run_after: None,
             error: None,
             canceled_by: None,
             details: Some(Details::UpgradeDatabase { from, to }),
             status: Status::Enqueued,
+            priority: Priority::Normal,
+            custom_metadata: None,
+            idempotency_key: None,
             kind: KindWithContent::UpgradeDatabase { from },
         },
     )?;
@@ -7,7 +7,9 @@ use meilisearch_types::batches::{Batch, BatchEnqueuedAt, BatchId, BatchStats};
 use meilisearch_types::heed::{Database, RoTxn, RwTxn};
 use meilisearch_types::milli::CboRoaringBitmapCodec;
 use meilisearch_types::task_view::DetailsView;
-use meilisearch_types::tasks::{Details, IndexSwap, Kind, KindWithContent, Status};
+use meilisearch_types::tasks::{
+    Details, IndexSwap, Kind, KindWithContent, Status, TransactionOperation,
+};
 use roaring::RoaringBitmap;
 use time::OffsetDateTime;
 
@@ -261,21 +263,52 @@ pub fn swap_index_uid_in_task(task: &mut Task, swap: (&str, &str)) {
                 }
             }
         }
+        K::Transaction { operations } => {
+            for operation in operations.iter_mut() {
+                match operation {
+                    TransactionOperation::DocumentAdditionOrUpdate { index_uid, .. }
+                    | TransactionOperation::DocumentDeletion { index_uid, .. }
+                    | TransactionOperation::SettingsUpdate { index_uid, .. } => {
+                        index_uids.push(index_uid)
+                    }
+                    TransactionOperation::IndexSwap { swaps } => {
+                        for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
+                            index_uids.push(lhs);
+                            index_uids.push(rhs);
+                        }
+                    }
+                }
+            }
+        }
         K::TaskCancelation { .. }
         | K::TaskDeletion { .. }
         | K::DumpCreation { .. }
         | K::UpgradeDatabase { .. }
         | K::SnapshotCreation => (),
     };
-    if let Some(Details::IndexSwap { swaps }) = &mut task.details {
-        for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
-            if lhs == swap.0 || lhs == swap.1 {
-                index_uids.push(lhs);
+    match &mut task.details {
+        Some(Details::IndexSwap { swaps }) => {
+            for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
+                if lhs == swap.0 || lhs == swap.1 {
+                    index_uids.push(lhs);
+                }
+                if rhs == swap.0 || rhs == swap.1 {
+                    index_uids.push(rhs);
+                }
             }
-            if rhs == swap.0 || rhs == swap.1 {
-                index_uids.push(rhs);
+        }
+        Some(Details::Transaction { operations }) => {
+            for operation in operations.iter_mut() {
+                index_uids.extend(operation.index_uid.as_mut());
+                if let Details::IndexSwap { swaps } = &mut *operation.details {
+                    for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
+                        index_uids.push(lhs);
+                        index_uids.push(rhs);
+                    }
+                }
             }
         }
+        _ => (),
     }
     for index_uid in index_uids {
         if index_uid == swap.0 {
@@ -306,8 +339,18 @@ pub(crate) fn filter_out_references_to_newer_tasks(task: &mut Task) {
 }
 
 pub(crate) fn check_index_swap_validity(task: &Task) -> Result<()> {
-    let swaps =
-        if let KindWithContent::IndexSwap { swaps } = &task.kind { swaps } else { return Ok(()) };
+    let swaps: Vec<&IndexSwap> = match &task.kind {
+        KindWithContent::IndexSwap { swaps } => swaps.iter().collect(),
+        // The swaps of a transaction are applied together and must not overlap either.
+        KindWithContent::Transaction { operations } => operations
+            .iter()
+            .flat_map(|operation| match operation {
+                TransactionOperation::IndexSwap { swaps } => swaps.as_slice(),
+                _ => &[],
+            })
+            .collect(),
+        _ => return Ok(()),
+    };
     let mut all_indexes = HashSet::new();
     let mut duplicate_indexes = BTreeSet::new();
     for IndexSwap { indexes: (lhs, rhs) } in swaps {
@@ -345,6 +388,7 @@ impl crate::IndexScheduler {
         for task in self.queue.tasks.all_tasks.iter(&rtxn).unwrap() {
             let (task_id, task) = task.unwrap();
             let task_index_uid = task.index_uid().map(ToOwned::to_owned);
+            let content_uuids = task.content_uuids();
 
             let Task {
                 uid,
@@ -352,10 +396,14 @@ impl crate::IndexScheduler {
                 enqueued_at,
                 started_at,
                 finished_at,
+                run_after,
                 error: _,
                 canceled_by,
                 details,
                 status,
+                priority,
+                custom_metadata,
+                idempotency_key,
                 kind,
             } = task;
             assert_eq!(uid, task.uid);
@@ -391,6 +439,23 @@ impl crate::IndexScheduler {
                     .unwrap()
                     .contains(task.uid));
             }
+            assert!(self.queue.tasks.get_priority(&rtxn, priority).unwrap().contains(uid));
+            if let Some(custom_metadata) = custom_metadata {
+                assert!(self
+                    .queue
+                    .tasks
+                    .custom_metadata_tasks(&rtxn, &custom_metadata)
+                    .unwrap()
+                    .contains(uid));
+            }
+            if let Some(idempotency_key) = idempotency_key {
+                assert!(self
+                    .queue
+                    .tasks
+                    .idempotency_key_tasks(&rtxn, &idempotency_key)
+                    .unwrap()
+                    .contains(uid));
+            }
             let db_enqueued_at = self
                 .queue
                 .tasks
@@ -419,6 +484,16 @@ impl crate::IndexScheduler {
                     .unwrap();
                 assert!(db_finished_at.contains(task_id));
             }
+            if let Some(run_after) = run_after {
+                let db_run_after = self
+                    .queue
+                    .tasks
+                    .run_after
+                    .get(&rtxn, &run_after.unix_timestamp_nanos())
+                    .unwrap()
+                    .unwrap();
+                assert!(db_run_after.contains(task_id));
+            }
             if let Some(canceled_by) = canceled_by {
                 let db_canceled_tasks =
                     self.queue.tasks.get_status(&rtxn, Status::Canceled).unwrap();
@@ -594,13 +669,19 @@ impl crate::IndexScheduler {
                     Details::UpgradeDatabase { from: _, to: _ } => {
                         assert_eq!(kind.as_kind(), Kind::UpgradeDatabase);
                     }
+                    Details::Transaction { operations: details } => match &kind {
+                        KindWithContent::Transaction { operations } => {
+                            assert_eq!(details.len(), operations.len());
+                        }
+                        _ => panic!(),
+                    },
                 }
             }
 
             assert!(self.queue.tasks.get_status(&rtxn, status).unwrap().contains(uid));
             assert!(self.queue.tasks.get_kind(&rtxn, kind.as_kind()).unwrap().contains(uid));
 
-            if let KindWithContent::DocumentAdditionOrUpdate { content_file, .. } = kind {
+            for content_file in content_uuids {
                 match status {
                     Status::Enqueued | Status::Processing => {
                         assert!(self
@@ -101,7 +101,12 @@ impl AuthController {
 
         let allow_index_creation = self.is_key_authorized(uid, Action::IndexesAdd, None)?;
 
-        Ok(AuthFilter { search_rules, key_authorized_indexes, allow_index_creation })
+        Ok(AuthFilter {
+            search_rules,
+            key_authorized_indexes,
+            allow_index_creation,
+            key_uid: Some(uid),
+        })
     }
 
     pub fn list_keys(&self) -> Result<Vec<Key>> {
@@ -169,6 +174,7 @@ pub struct AuthFilter {
     search_rules: Option<SearchRules>,
     key_authorized_indexes: SearchRules,
     allow_index_creation: bool,
+    key_uid: Option<Uuid>,
 }
 
 impl Default for AuthFilter {
@@ -177,6 +183,7 @@ impl Default for AuthFilter {
             search_rules: None,
             key_authorized_indexes: SearchRules::default(),
             allow_index_creation: true,
+            key_uid: None,
         }
     }
 }
@@ -187,6 +194,12 @@ impl AuthFilter {
         self.allow_index_creation && self.is_index_authorized(index)
     }
 
+    /// Return the uid of the API key used to authenticate, or of the API key that signed the tenant token.
+    /// Returns `None` when the master key is used or when there is no master key.
+    pub fn key_uid(&self) -> Option<Uuid> {
+        self.key_uid
+    }
+
     #[inline]
     /// Return true if a tenant token was used to generate the search rules.
     pub fn is_tenant_token(&self) -> bool {
@@ -198,6 +211,7 @@ impl AuthFilter {
             search_rules: None,
             key_authorized_indexes: SearchRules::Set(allowed_indexes),
             allow_index_creation: false,
+            key_uid: None,
         }
     }
 
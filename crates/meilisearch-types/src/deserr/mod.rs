@@ -12,7 +12,7 @@ use crate::error::{
     ParseOffsetDateTimeError,
 };
 use crate::index_uid::IndexUidFormatError;
-use crate::tasks::{ParseTaskKindError, ParseTaskStatusError};
+use crate::tasks::{ParseTaskKindError, ParseTaskPriorityError, ParseTaskStatusError};
 
 pub mod query_params;
 
@@ -150,6 +150,14 @@ make_missing_field_convenience_builder!(MissingApiKeyActions, missing_api_key_ac
 make_missing_field_convenience_builder!(MissingApiKeyExpiresAt, missing_api_key_expires_at);
 make_missing_field_convenience_builder!(MissingApiKeyIndexes, missing_api_key_indexes);
 make_missing_field_convenience_builder!(MissingSwapIndexes, missing_swap_indexes);
+make_missing_field_convenience_builder!(
+    MissingTransactionOperations,
+    missing_transaction_operations
+);
+make_missing_field_convenience_builder!(
+    MissingTransactionOperationType,
+    missing_transaction_operation_type
+);
 make_missing_field_convenience_builder!(MissingDocumentFilter, missing_document_filter);
 make_missing_field_convenience_builder!(
     MissingFacetSearchFacetName,
@@ -191,11 +199,13 @@ merge_with_error_impl_take_error_message!(InvalidTaskDateError);
 merge_with_error_impl_take_error_message!(ParseOffsetDateTimeError);
 merge_with_error_impl_take_error_message!(ParseTaskKindError);
 merge_with_error_impl_take_error_message!(ParseTaskStatusError);
+merge_with_error_impl_take_error_message!(ParseTaskPriorityError);
 merge_with_error_impl_take_error_message!(IndexUidFormatError);
 merge_with_error_impl_take_error_message!(InvalidMultiSearchWeight);
 merge_with_error_impl_take_error_message!(InvalidNetworkUrl);
 merge_with_error_impl_take_error_message!(InvalidNetworkSearchApiKey);
 merge_with_error_impl_take_error_message!(InvalidSearchSemanticRatio);
 merge_with_error_impl_take_error_message!(InvalidSearchRankingScoreThreshold);
+merge_with_error_impl_take_error_message!(InvalidSearchFacetHistogram);
 merge_with_error_impl_take_error_message!(InvalidSimilarRankingScoreThreshold);
 merge_with_error_impl_take_error_message!(InvalidSimilarId);
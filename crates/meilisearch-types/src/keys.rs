@@ -308,6 +308,12 @@ pub enum Action {
     #[serde(rename = "network.update")]
     #[deserr(rename = "network.update")]
     NetworkUpdate,
+    #[serde(rename = "embeddingCache.delete")]
+    #[deserr(rename = "embeddingCache.delete")]
+    EmbeddingCacheDelete,
+    #[serde(rename = "transactions.create")]
+    #[deserr(rename = "transactions.create")]
+    TransactionsCreate,
 }
 
 impl Action {
@@ -349,6 +355,8 @@ impl Action {
             EXPERIMENTAL_FEATURES_UPDATE => Some(Self::ExperimentalFeaturesUpdate),
             NETWORK_GET => Some(Self::NetworkGet),
             NETWORK_UPDATE => Some(Self::NetworkUpdate),
+            EMBEDDING_CACHE_DELETE => Some(Self::EmbeddingCacheDelete),
+            TRANSACTIONS_CREATE => Some(Self::TransactionsCreate),
             _otherwise => None,
         }
     }
@@ -397,4 +405,8 @@ pub mod actions {
 
     pub const NETWORK_GET: u8 = NetworkGet.repr();
     pub const NETWORK_UPDATE: u8 = NetworkUpdate.repr();
+
+    pub const EMBEDDING_CACHE_DELETE: u8 = EmbeddingCacheDelete.repr();
+
+    pub const TRANSACTIONS_CREATE: u8 = TransactionsCreate.repr();
 }
@@ -10,15 +10,19 @@ use deserr::{DeserializeError, Deserr, ErrorKind, MergeWithError, ValuePointerRe
 use fst::IntoStreamer;
 use milli::index::{IndexEmbeddingConfig, PrefixSearch};
 use milli::proximity::ProximityPrecision;
+use milli::synonyms::SynonymRule;
 use milli::update::Setting;
-use milli::{Criterion, CriterionError, FilterableAttributesRule, Index, DEFAULT_VALUES_PER_FACET};
+use milli::{
+    Criterion, CriterionError, FilterableAttributesRule, Index, PinningRule, RerankerSettings,
+    SynonymSet, DEFAULT_VALUES_PER_FACET,
+};
 use serde::{Deserialize, Serialize, Serializer};
 use utoipa::ToSchema;
 
 use crate::deserr::DeserrJsonError;
 use crate::error::deserr_codes::*;
 use crate::facet_values_sort::FacetValuesSort;
-use crate::locales::LocalizedAttributesRuleView;
+use crate::locales::{Locale, LocalizedAttributesRuleView};
 
 /// The maximum number of results that the engine
 /// will be able to return in one search call.
@@ -177,6 +181,56 @@ impl<E: DeserializeError> Deserr<E> for SettingEmbeddingSettings {
     }
 }
 
+/// A set of synonym rules, see [`milli::SynonymSet`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(deny_unknown_fields, rename_all = camelCase, validate = validate_synonym_set -> DeserrJsonError<InvalidSettingsSynonymSets>)]
+pub struct SynonymSetView {
+    /// Rules written as `a, b, c` equivalence groups or `a, b => c, d` one-way rules.
+    #[schema(example = json!(["tv, television", "phone => smartphone"]))]
+    pub rules: Vec<String>,
+    /// The locales of the queries the rules apply to, the rules apply to every query when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    #[schema(value_type = Option<Vec<Locale>>, example = json!(["fra"]))]
+    pub locales: Option<Vec<Locale>>,
+}
+
+fn validate_synonym_set<E: DeserializeError>(
+    set: SynonymSetView,
+    location: ValuePointerRef,
+) -> Result<SynonymSetView, E> {
+    for rule in &set.rules {
+        if let Err(error) = SynonymRule::parse(rule) {
+            return Err(deserr::take_cf_content(E::error::<Infallible>(
+                None,
+                ErrorKind::Unexpected {
+                    msg: format!("The synonym rule `{rule}` is invalid: {error}"),
+                },
+                location,
+            )));
+        }
+    }
+    Ok(set)
+}
+
+impl From<SynonymSetView> for SynonymSet {
+    fn from(view: SynonymSetView) -> Self {
+        SynonymSet {
+            rules: view.rules,
+            locales: view.locales.unwrap_or_default().into_iter().map(|l| l.into()).collect(),
+        }
+    }
+}
+
+impl From<SynonymSet> for SynonymSetView {
+    fn from(set: SynonymSet) -> Self {
+        let locales =
+            (!set.locales.is_empty()).then(|| set.locales.into_iter().map(|l| l.into()).collect());
+        SynonymSetView { rules: set.rules, locales }
+    }
+}
+
 /// Holds all the settings for an index. `T` can either be `Checked` if they represents settings
 /// whose validity is guaranteed, or `Unchecked` if they need to be validated. In the later case, a
 /// call to `check` will return a `Settings<Checked>` from a `Settings<Unchecked>`.
@@ -240,6 +294,12 @@ pub struct Settings<T> {
     #[deserr(default, error = DeserrJsonError<InvalidSettingsSynonyms>)]
     #[schema(value_type = Option<BTreeMap<String, Vec<String>>>, example = json!({ "he": ["she", "they", "them"], "phone": ["iPhone", "android"]}))]
     pub synonyms: Setting<BTreeMap<String, Vec<String>>>,
+    /// Sets of synonym rules, each set can be restricted to the queries in some locales.
+    /// A rule is either an equivalence group `a, b, c` or a one-way rule `a => b, c`.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsSynonymSets>)]
+    #[schema(value_type = Option<Vec<SynonymSetView>>, example = json!([{ "rules": ["tv, television", "phone => smartphone"] }, { "rules": ["portable, téléphone"], "locales": ["fra"] }]))]
+    pub synonym_sets: Setting<Vec<SynonymSetView>>,
     /// Search returns documents with distinct (different) values of the given field.
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsDistinctAttribute>)]
@@ -288,6 +348,16 @@ pub struct Settings<T> {
     #[deserr(default, error = DeserrJsonError<InvalidSettingsPrefixSearch>)]
     #[schema(value_type = Option<PrefixSearchSettings>, example = json!("Hemlo"))]
     pub prefix_search: Setting<PrefixSearchSettings>,
+    /// Merchandising rules pinning documents to fixed positions and hiding others for given queries.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsPinning>)]
+    #[schema(value_type = Option<Vec<PinningRule>>, example = json!([{ "query": "running shoes", "pin": [{ "id": "42", "position": 0 }], "hide": ["7"] }]))]
+    pub pinning: Setting<Vec<PinningRule>>,
+    /// Remote service reranking the first hits of the searches enabling the `rerank` stage.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsReranker>)]
+    #[schema(value_type = Option<RerankerSettings>, example = json!({ "url": "http://localhost:8000/rerank", "request": { "query": "{{query}}", "documents": ["{{text}}", "{{..}}"] }, "response": { "scores": ["{{score}}", "{{..}}"] } }))]
+    pub reranker: Setting<RerankerSettings>,
 
     #[serde(skip)]
     #[deserr(skip)]
@@ -296,6 +366,10 @@ pub struct Settings<T> {
 
 impl<T> Settings<T> {
     pub fn hide_secrets(&mut self) {
+        if let Setting::Set(RerankerSettings { api_key: Some(api_key), .. }) = &mut self.reranker {
+            Self::hide_secret(api_key);
+        }
+
         let Setting::Set(embedders) = &mut self.embedders else {
             return;
         };
@@ -341,6 +415,7 @@ impl Settings<Checked> {
             ranking_rules: Setting::Reset,
             stop_words: Setting::Reset,
             synonyms: Setting::Reset,
+            synonym_sets: Setting::Reset,
             non_separator_tokens: Setting::Reset,
             separator_tokens: Setting::Reset,
             dictionary: Setting::Reset,
@@ -354,6 +429,8 @@ impl Settings<Checked> {
             localized_attributes: Setting::Reset,
             facet_search: Setting::Reset,
             prefix_search: Setting::Reset,
+            pinning: Setting::Reset,
+            reranker: Setting::Reset,
             _kind: PhantomData,
         }
     }
@@ -370,6 +447,7 @@ impl Settings<Checked> {
             separator_tokens,
             dictionary,
             synonyms,
+            synonym_sets,
             distinct_attribute,
             proximity_precision,
             typo_tolerance,
@@ -380,6 +458,8 @@ impl Settings<Checked> {
             localized_attributes: localized_attributes_rules,
             facet_search,
             prefix_search,
+            pinning,
+            reranker,
             _kind,
         } = self;
 
@@ -394,6 +474,7 @@ impl Settings<Checked> {
             separator_tokens,
             dictionary,
             synonyms,
+            synonym_sets,
             distinct_attribute,
             proximity_precision,
             typo_tolerance,
@@ -404,6 +485,8 @@ impl Settings<Checked> {
             localized_attributes: localized_attributes_rules,
             facet_search,
             prefix_search,
+            pinning,
+            reranker,
             _kind: PhantomData,
         }
     }
@@ -441,6 +524,7 @@ impl Settings<Unchecked> {
             ranking_rules: self.ranking_rules,
             stop_words: self.stop_words,
             synonyms: self.synonyms,
+            synonym_sets: self.synonym_sets,
             non_separator_tokens: self.non_separator_tokens,
             separator_tokens: self.separator_tokens,
             dictionary: self.dictionary,
@@ -454,6 +538,8 @@ impl Settings<Unchecked> {
             localized_attributes: self.localized_attributes,
             facet_search: self.facet_search,
             prefix_search: self.prefix_search,
+            pinning: self.pinning,
+            reranker: self.reranker,
             _kind: PhantomData,
         }
     }
@@ -502,6 +588,7 @@ impl Settings<Unchecked> {
             separator_tokens: other.separator_tokens.clone().or(self.separator_tokens.clone()),
             dictionary: other.dictionary.clone().or(self.dictionary.clone()),
             synonyms: other.synonyms.clone().or(self.synonyms.clone()),
+            synonym_sets: other.synonym_sets.clone().or(self.synonym_sets.clone()),
             distinct_attribute: other
                 .distinct_attribute
                 .clone()
@@ -530,6 +617,8 @@ impl Settings<Unchecked> {
             },
             prefix_search: other.prefix_search.or(self.prefix_search),
             facet_search: other.facet_search.or(self.facet_search),
+            pinning: other.pinning.clone().or(self.pinning.clone()),
+            reranker: other.reranker.clone().or(self.reranker.clone()),
             _kind: PhantomData,
         }
     }
@@ -558,6 +647,7 @@ pub fn apply_settings_to_builder(
         separator_tokens,
         dictionary,
         synonyms,
+        synonym_sets,
         distinct_attribute,
         proximity_precision,
         typo_tolerance,
@@ -568,6 +658,8 @@ pub fn apply_settings_to_builder(
         localized_attributes: localized_attributes_rules,
         facet_search,
         prefix_search,
+        pinning,
+        reranker,
         _kind,
     } = settings;
 
@@ -639,6 +731,14 @@ pub fn apply_settings_to_builder(
         Setting::NotSet => (),
     }
 
+    match synonym_sets {
+        Setting::Set(ref sets) => {
+            builder.set_synonym_sets(sets.iter().cloned().map(|set| set.into()).collect())
+        }
+        Setting::Reset => builder.reset_synonym_sets(),
+        Setting::NotSet => (),
+    }
+
     match distinct_attribute {
         Setting::Set(ref attr) => builder.set_distinct_field(attr.clone()),
         Setting::Reset => builder.reset_distinct_field(),
@@ -772,6 +872,18 @@ pub fn apply_settings_to_builder(
         Setting::Reset => builder.reset_facet_search(),
         Setting::NotSet => (),
     }
+
+    match pinning {
+        Setting::Set(rules) => builder.set_pinning(rules.clone()),
+        Setting::Reset => builder.reset_pinning(),
+        Setting::NotSet => (),
+    }
+
+    match reranker {
+        Setting::Set(reranker) => builder.set_reranker(reranker.clone()),
+        Setting::Reset => builder.reset_reranker(),
+        Setting::NotSet => (),
+    }
 }
 
 pub enum SecretPolicy {
@@ -814,6 +926,7 @@ pub fn settings(
     let proximity_precision = index.proximity_precision(rtxn)?.map(ProximityPrecisionView::from);
 
     let synonyms = index.user_defined_synonyms(rtxn)?;
+    let synonym_sets = index.user_defined_synonym_sets(rtxn)?;
 
     let min_typo_word_len = MinWordSizeTyposSetting {
         one_typo: Setting::Set(index.min_word_len_one_typo(rtxn)?),
@@ -876,6 +989,10 @@ pub fn settings(
 
     let facet_search = index.facet_search(rtxn)?;
 
+    let pinning = index.pinning_rules(rtxn)?;
+
+    let reranker = index.reranker(rtxn)?;
+
     let mut settings = Settings {
         displayed_attributes: match displayed_attributes {
             Some(attrs) => Setting::Set(attrs),
@@ -900,6 +1017,7 @@ pub fn settings(
         },
         proximity_precision: Setting::Set(proximity_precision.unwrap_or_default()),
         synonyms: Setting::Set(synonyms),
+        synonym_sets: Setting::Set(synonym_sets.into_iter().map(|set| set.into()).collect()),
         typo_tolerance: Setting::Set(typo_tolerance),
         faceting: Setting::Set(faceting),
         pagination: Setting::Set(pagination),
@@ -914,6 +1032,11 @@ pub fn settings(
         },
         prefix_search: Setting::Set(prefix_search.unwrap_or_default()),
         facet_search: Setting::Set(facet_search),
+        pinning: Setting::Set(pinning),
+        reranker: match reranker {
+            Some(reranker) => Setting::Set(reranker),
+            None => Setting::Reset,
+        },
         _kind: PhantomData,
     };
 
@@ -945,6 +1068,8 @@ pub enum RankingRuleView {
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
     Desc(String),
+    /// Sorted by the decreasing value of the numeric expression specified.
+    Expression(String),
 }
 impl Serialize for RankingRuleView {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -1001,6 +1126,7 @@ impl From<Criterion> for RankingRuleView {
             Criterion::Exactness => RankingRuleView::Exactness,
             Criterion::Asc(x) => RankingRuleView::Asc(x),
             Criterion::Desc(x) => RankingRuleView::Desc(x),
+            Criterion::Expression(x) => RankingRuleView::Expression(x),
         }
     }
 }
@@ -1015,6 +1141,7 @@ impl From<RankingRuleView> for Criterion {
             RankingRuleView::Exactness => Criterion::Exactness,
             RankingRuleView::Asc(x) => Criterion::Asc(x),
             RankingRuleView::Desc(x) => Criterion::Desc(x),
+            RankingRuleView::Expression(x) => Criterion::Expression(x),
         }
     }
 }
@@ -1131,6 +1258,7 @@ pub(crate) mod test {
             separator_tokens: Setting::NotSet,
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_sets: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
@@ -1141,6 +1269,8 @@ pub(crate) mod test {
             search_cutoff_ms: Setting::NotSet,
             facet_search: Setting::NotSet,
             prefix_search: Setting::NotSet,
+            pinning: Setting::NotSet,
+            reranker: Setting::NotSet,
             _kind: PhantomData::<Unchecked>,
         };
 
@@ -1162,6 +1292,7 @@ pub(crate) mod test {
             separator_tokens: Setting::NotSet,
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_sets: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
@@ -1172,6 +1303,8 @@ pub(crate) mod test {
             search_cutoff_ms: Setting::NotSet,
             facet_search: Setting::NotSet,
             prefix_search: Setting::NotSet,
+            pinning: Setting::NotSet,
+            reranker: Setting::NotSet,
             _kind: PhantomData::<Unchecked>,
         };
 
@@ -6,7 +6,10 @@ use utoipa::ToSchema;
 use crate::batches::BatchId;
 use crate::error::ResponseError;
 use crate::settings::{Settings, Unchecked};
-use crate::tasks::{serialize_duration, Details, IndexSwap, Kind, Status, Task, TaskId};
+use crate::tasks::{
+    serialize_duration, Details, IndexSwap, Kind, Priority, Status, Task, TaskId,
+    TransactionOperationDetails,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -24,6 +27,9 @@ pub struct TaskView {
     /// The type of the task.
     #[serde(rename = "type")]
     pub kind: Kind,
+    /// The priority given to the task when it was enqueued, omitted for the `normal` priority.
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
     /// The uid of the task that performed the taskCancelation if the task has been canceled.
     #[schema(value_type = Option<u32>, example = json!(4326))]
     pub canceled_by: Option<TaskId>,
@@ -46,6 +52,23 @@ pub struct TaskView {
     #[schema(value_type = String, example = json!("2024-08-08_14:12:09.393Z"))]
     #[serde(with = "time::serde::rfc3339::option", default)]
     pub finished_at: Option<OffsetDateTime>,
+    /// The date before which the task is not processed, omitted if it was enqueued without one.
+    /// The tasks enqueued after it on the same indexes wait for it to be processed.
+    #[schema(value_type = Option<String>, example = json!("2024-08-09_00:00:00.000Z"))]
+    #[serde(
+        with = "time::serde::rfc3339::option",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub run_after: Option<OffsetDateTime>,
+    /// The custom metadata given to the task when it was enqueued, omitted if there was none.
+    #[schema(value_type = Option<String>, example = json!("import-2024-08-08"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_metadata: Option<String>,
+    /// The `Idempotency-Key` the task was enqueued with, omitted if there was none.
+    #[schema(value_type = Option<String>, example = json!("5c1a0b0e-2d57-4cbb-9f43-6c2e4c1d8a4f"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
 impl TaskView {
@@ -56,6 +79,7 @@ impl TaskView {
             index_uid: task.index_uid().map(ToOwned::to_owned),
             status: task.status,
             kind: task.kind.as_kind(),
+            priority: task.priority,
             canceled_by: task.canceled_by,
             details: task.details.clone().map(DetailsView::from),
             error: task.error.clone(),
@@ -63,6 +87,9 @@ impl TaskView {
             enqueued_at: task.enqueued_at,
             started_at: task.started_at,
             finished_at: task.finished_at,
+            run_after: task.run_after,
+            custom_metadata: task.custom_metadata.clone(),
+            idempotency_key: task.idempotency_key.clone(),
         }
     }
 }
@@ -118,6 +145,32 @@ pub struct DetailsView {
     pub upgrade_from: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub upgrade_to: Option<String>,
+    /// The details of each operation of a transaction, in the order in which they were given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operations: Option<Vec<TransactionOperationView>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct TransactionOperationView {
+    /// The index updated by the operation, `null` for the index swaps.
+    pub index_uid: Option<String>,
+    /// The type of the operation.
+    #[serde(rename = "type")]
+    pub kind: Kind,
+    #[schema(no_recursion)]
+    pub details: DetailsView,
+}
+
+impl From<TransactionOperationDetails> for TransactionOperationView {
+    fn from(operation: TransactionOperationDetails) -> Self {
+        TransactionOperationView {
+            index_uid: operation.index_uid,
+            kind: operation.kind,
+            details: DetailsView::from(*operation.details),
+        }
+    }
 }
 
 impl DetailsView {
@@ -238,6 +291,14 @@ impl DetailsView {
                     Some(left)
                 }
             },
+            operations: match (self.operations.clone(), other.operations.clone()) {
+                (None, None) => None,
+                (None, Some(operations)) | (Some(operations), None) => Some(operations),
+                (Some(mut left), Some(mut right)) => {
+                    left.append(&mut right);
+                    Some(left)
+                }
+            },
             // We want the earliest version
             upgrade_from: match (self.upgrade_from.clone(), other.upgrade_from.clone()) {
                 (None, None) => None,
@@ -332,6 +393,10 @@ impl From<Details> for DetailsView {
                 upgrade_to: Some(format!("v{}.{}.{}", to.0, to.1, to.2)),
                 ..Default::default()
             },
+            Details::Transaction { operations } => DetailsView {
+                operations: Some(operations.into_iter().map(Into::into).collect()),
+                ..Default::default()
+            },
         }
     }
 }
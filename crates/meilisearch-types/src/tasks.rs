@@ -32,12 +32,27 @@ pub struct Task {
     pub started_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339::option")]
     pub finished_at: Option<OffsetDateTime>,
+    /// The task is not eligible for processing before this date.
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub run_after: Option<OffsetDateTime>,
 
     pub error: Option<ResponseError>,
     pub canceled_by: Option<TaskId>,
     pub details: Option<Details>,
 
     pub status: Status,
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
+    /// An arbitrary string given by the user when the task was enqueued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_metadata: Option<String>,
+    /// The key identifying the request that enqueued the task, re-submitting it returns this task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
     pub kind: KindWithContent,
 }
 
@@ -51,7 +66,8 @@ impl Task {
             | TaskCancelation { .. }
             | TaskDeletion { .. }
             | UpgradeDatabase { .. }
-            | IndexSwap { .. } => None,
+            | IndexSwap { .. }
+            | Transaction { .. } => None,
             DocumentAdditionOrUpdate { index_uid, .. }
             | DocumentEdition { index_uid, .. }
             | DocumentDeletion { index_uid, .. }
@@ -69,7 +85,8 @@ impl Task {
         self.kind.indexes()
     }
 
-    /// Return the content-uuid if there is one
+    /// Return the content-uuid if there is one, the transactions can have several of them
+    /// and are only covered by [`Task::content_uuids`].
     pub fn content_uuid(&self) -> Option<Uuid> {
         match self.kind {
             KindWithContent::DocumentAdditionOrUpdate { content_file, .. } => Some(content_file),
@@ -82,6 +99,7 @@ impl Task {
             | KindWithContent::IndexCreation { .. }
             | KindWithContent::IndexUpdate { .. }
             | KindWithContent::IndexSwap { .. }
+            | KindWithContent::Transaction { .. }
             | KindWithContent::TaskCancelation { .. }
             | KindWithContent::TaskDeletion { .. }
             | KindWithContent::DumpCreation { .. }
@@ -89,6 +107,11 @@ impl Task {
             | KindWithContent::UpgradeDatabase { .. } => None,
         }
     }
+
+    /// Return all the content-uuids of the task.
+    pub fn content_uuids(&self) -> Vec<Uuid> {
+        self.kind.content_uuids()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -139,6 +162,9 @@ pub enum KindWithContent {
     IndexSwap {
         swaps: Vec<IndexSwap>,
     },
+    Transaction {
+        operations: Vec<TransactionOperation>,
+    },
     TaskCancelation {
         query: String,
         tasks: RoaringBitmap,
@@ -163,6 +189,91 @@ pub struct IndexSwap {
     pub indexes: (String, String),
 }
 
+/// An operation of a transaction, all of them are committed together or not at all.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionOperation {
+    DocumentAdditionOrUpdate {
+        index_uid: String,
+        primary_key: Option<String>,
+        method: IndexDocumentsMethod,
+        content_file: Uuid,
+        documents_count: u64,
+        allow_index_creation: bool,
+    },
+    DocumentDeletion {
+        index_uid: String,
+        documents_ids: Vec<String>,
+    },
+    SettingsUpdate {
+        index_uid: String,
+        new_settings: Box<Settings<Unchecked>>,
+        is_deletion: bool,
+        allow_index_creation: bool,
+    },
+    IndexSwap {
+        swaps: Vec<IndexSwap>,
+    },
+}
+
+impl TransactionOperation {
+    /// Return the index updated by this operation, the index swaps are not associated with any.
+    pub fn index_uid(&self) -> Option<&str> {
+        match self {
+            TransactionOperation::DocumentAdditionOrUpdate { index_uid, .. }
+            | TransactionOperation::DocumentDeletion { index_uid, .. }
+            | TransactionOperation::SettingsUpdate { index_uid, .. } => Some(index_uid),
+            TransactionOperation::IndexSwap { .. } => None,
+        }
+    }
+
+    /// Return the kind of the standalone task doing the same operation.
+    pub fn to_kind(&self) -> KindWithContent {
+        match self.clone() {
+            TransactionOperation::DocumentAdditionOrUpdate {
+                index_uid,
+                primary_key,
+                method,
+                content_file,
+                documents_count,
+                allow_index_creation,
+            } => KindWithContent::DocumentAdditionOrUpdate {
+                index_uid,
+                primary_key,
+                method,
+                content_file,
+                documents_count,
+                allow_index_creation,
+            },
+            TransactionOperation::DocumentDeletion { index_uid, documents_ids } => {
+                KindWithContent::DocumentDeletion { index_uid, documents_ids }
+            }
+            TransactionOperation::SettingsUpdate {
+                index_uid,
+                new_settings,
+                is_deletion,
+                allow_index_creation,
+            } => KindWithContent::SettingsUpdate {
+                index_uid,
+                new_settings,
+                is_deletion,
+                allow_index_creation,
+            },
+            TransactionOperation::IndexSwap { swaps } => KindWithContent::IndexSwap { swaps },
+        }
+    }
+
+    /// Returns the details of the operation before it is processed.
+    pub fn default_details(&self) -> TransactionOperationDetails {
+        TransactionOperationDetails {
+            index_uid: self.index_uid().map(ToOwned::to_owned),
+            kind: self.to_kind().as_kind(),
+            // All the operations of a transaction have default details.
+            details: Box::new(self.to_kind().default_details().unwrap()),
+        }
+    }
+}
+
 impl KindWithContent {
     pub fn as_kind(&self) -> Kind {
         match self {
@@ -176,6 +287,7 @@ impl KindWithContent {
             KindWithContent::IndexDeletion { .. } => Kind::IndexDeletion,
             KindWithContent::IndexUpdate { .. } => Kind::IndexUpdate,
             KindWithContent::IndexSwap { .. } => Kind::IndexSwap,
+            KindWithContent::Transaction { .. } => Kind::Transaction,
             KindWithContent::TaskCancelation { .. } => Kind::TaskCancelation,
             KindWithContent::TaskDeletion { .. } => Kind::TaskDeletion,
             KindWithContent::DumpCreation { .. } => Kind::DumpCreation,
@@ -184,6 +296,23 @@ impl KindWithContent {
         }
     }
 
+    /// Return all the content-uuids associated with this kind of task.
+    pub fn content_uuids(&self) -> Vec<Uuid> {
+        match self {
+            KindWithContent::DocumentAdditionOrUpdate { content_file, .. } => vec![*content_file],
+            KindWithContent::Transaction { operations } => operations
+                .iter()
+                .filter_map(|operation| match operation {
+                    TransactionOperation::DocumentAdditionOrUpdate { content_file, .. } => {
+                        Some(*content_file)
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn indexes(&self) -> Vec<&str> {
         use KindWithContent::*;
 
@@ -210,6 +339,21 @@ impl KindWithContent {
                 }
                 indexes.into_iter().collect()
             }
+            Transaction { operations } => {
+                let mut indexes = HashSet::<&str>::default();
+                for operation in operations {
+                    match operation {
+                        TransactionOperation::IndexSwap { swaps } => {
+                            for swap in swaps {
+                                indexes.insert(swap.indexes.0.as_str());
+                                indexes.insert(swap.indexes.1.as_str());
+                            }
+                        }
+                        operation => indexes.extend(operation.index_uid()),
+                    }
+                }
+                indexes.into_iter().collect()
+            }
         }
     }
 
@@ -257,6 +401,9 @@ impl KindWithContent {
             KindWithContent::IndexSwap { swaps } => {
                 Some(Details::IndexSwap { swaps: swaps.clone() })
             }
+            KindWithContent::Transaction { operations } => Some(Details::Transaction {
+                operations: operations.iter().map(TransactionOperation::default_details).collect(),
+            }),
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: None,
@@ -320,7 +467,7 @@ impl KindWithContent {
             | KindWithContent::IndexUpdate { primary_key, .. } => {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
             }
-            KindWithContent::IndexSwap { .. } => {
+            KindWithContent::IndexSwap { .. } | KindWithContent::Transaction { .. } => {
                 todo!()
             }
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
@@ -371,6 +518,7 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
             }
             KindWithContent::IndexSwap { .. } => None,
+            KindWithContent::Transaction { .. } => None,
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: None,
@@ -469,6 +617,83 @@ impl fmt::Display for ParseTaskStatusError {
 }
 impl std::error::Error for ParseTaskStatusError {}
 
+/// The priority of a task, given when it is enqueued.
+///
+/// The tasks with the highest priority are batched first, but never before
+/// the tasks that were enqueued earlier on the same index.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Sequence,
+    PartialOrd,
+    Ord,
+    ToSchema,
+)]
+#[schema(example = json!(Priority::High))]
+#[serde(rename_all = "camelCase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl Priority {
+    pub fn is_normal(&self) -> bool {
+        *self == Priority::Normal
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::High => write!(f, "high"),
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = ParseTaskPriorityError;
+
+    fn from_str(priority: &str) -> Result<Self, Self::Err> {
+        if priority.eq_ignore_ascii_case("low") {
+            Ok(Priority::Low)
+        } else if priority.eq_ignore_ascii_case("normal") {
+            Ok(Priority::Normal)
+        } else if priority.eq_ignore_ascii_case("high") {
+            Ok(Priority::High)
+        } else {
+            Err(ParseTaskPriorityError(priority.to_owned()))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseTaskPriorityError(pub String);
+impl fmt::Display for ParseTaskPriorityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` is not a valid task priority. Available priorities are {}.",
+            self.0,
+            enum_iterator::all::<Priority>()
+                .map(|p| format!("`{p}`"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+impl std::error::Error for ParseTaskPriorityError {}
+
 /// The type of the task.
 #[derive(
     Debug,
@@ -500,6 +725,7 @@ pub enum Kind {
     DumpCreation,
     SnapshotCreation,
     UpgradeDatabase,
+    Transaction,
 }
 
 impl Kind {
@@ -513,6 +739,7 @@ impl Kind {
             | Kind::IndexDeletion
             | Kind::IndexUpdate => true,
             Kind::IndexSwap
+            | Kind::Transaction
             | Kind::TaskCancelation
             | Kind::TaskDeletion
             | Kind::DumpCreation
@@ -537,6 +764,7 @@ impl Display for Kind {
             Kind::DumpCreation => write!(f, "dumpCreation"),
             Kind::SnapshotCreation => write!(f, "snapshotCreation"),
             Kind::UpgradeDatabase => write!(f, "upgradeDatabase"),
+            Kind::Transaction => write!(f, "transaction"),
         }
     }
 }
@@ -570,6 +798,8 @@ impl FromStr for Kind {
             Ok(Kind::SnapshotCreation)
         } else if kind.eq_ignore_ascii_case("upgradeDatabase") {
             Ok(Kind::UpgradeDatabase)
+        } else if kind.eq_ignore_ascii_case("transaction") {
+            Ok(Kind::Transaction)
         } else {
             Err(ParseTaskKindError(kind.to_owned()))
         }
@@ -647,6 +877,17 @@ pub enum Details {
         from: (u32, u32, u32),
         to: (u32, u32, u32),
     },
+    Transaction {
+        operations: Vec<TransactionOperationDetails>,
+    },
+}
+
+/// The details of an operation of a transaction.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct TransactionOperationDetails {
+    pub index_uid: Option<String>,
+    pub kind: Kind,
+    pub details: Box<Details>,
 }
 
 impl Details {
@@ -669,6 +910,11 @@ impl Details {
             | Self::Dump { .. }
             | Self::UpgradeDatabase { .. }
             | Self::IndexSwap { .. } => (),
+            Self::Transaction { operations } => {
+                for operation in operations {
+                    *operation.details = operation.details.to_failed();
+                }
+            }
         }
 
         details
@@ -196,10 +196,14 @@ struct Infos {
     experimental_reduce_indexing_memory_usage: bool,
     experimental_max_number_of_batched_tasks: usize,
     experimental_limit_batched_tasks_total_size: u64,
+    experimental_max_concurrent_batches: usize,
     experimental_network: bool,
     experimental_get_task_documents_route: bool,
     experimental_composite_embedders: bool,
     experimental_embedding_cache_entries: usize,
+    experimental_persistent_embedding_cache_entries: usize,
+    experimental_persistent_embedding_cache_size: Byte,
+    experimental_embedding_media_path: bool,
     gpu_enabled: bool,
     db_path: bool,
     import_dump: bool,
@@ -247,7 +251,11 @@ impl Infos {
             experimental_reduce_indexing_memory_usage,
             experimental_max_number_of_batched_tasks,
             experimental_limit_batched_tasks_total_size,
+            experimental_max_concurrent_batches,
             experimental_embedding_cache_entries,
+            experimental_persistent_embedding_cache_entries,
+            experimental_persistent_embedding_cache_size,
+            experimental_embedding_media_path,
             http_addr,
             master_key: _,
             env,
@@ -315,6 +323,9 @@ impl Infos {
             experimental_get_task_documents_route: get_task_documents_route,
             experimental_composite_embedders: composite_embedders,
             experimental_embedding_cache_entries,
+            experimental_persistent_embedding_cache_entries,
+            experimental_persistent_embedding_cache_size,
+            experimental_embedding_media_path: experimental_embedding_media_path.is_some(),
             gpu_enabled: meilisearch_types::milli::vector::is_cuda_enabled(),
             db_path: db_path != PathBuf::from("./data.ms"),
             import_dump: import_dump.is_some(),
@@ -330,6 +341,7 @@ impl Infos {
             http_payload_size_limit,
             experimental_max_number_of_batched_tasks,
             experimental_limit_batched_tasks_total_size,
+            experimental_max_concurrent_batches: experimental_max_concurrent_batches.get(),
             task_queue_webhook: task_webhook_url.is_some(),
             task_webhook_authorization_header: task_webhook_authorization_header.is_some(),
             log_level: log_level.to_string(),
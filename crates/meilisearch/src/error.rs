@@ -35,6 +35,8 @@ pub enum MeilisearchHttpError {
     PaginationInFederatedQuery(usize, &'static str),
     #[error("Inside `.queries[{0}]`: Using facet options is not allowed in federated queries.\n - Hint: remove `facets` from query #{0} or remove `federation` from the request\n - Hint: pass `federation.facetsByIndex.{1}: {2:?}` for facets in federated search")]
     FacetsInFederatedQuery(usize, String, Vec<String>),
+    #[error("Inside `.queries[{0}]`: Using `{1}` is not allowed in federated queries.\n - Hint: remove `{1}` from query #{0} or remove `federation` from the request")]
+    FacetRangesInFederatedQuery(usize, &'static str),
     #[error("Inconsistent order for values in facet `{facet}`: index `{previous_uid}` orders {previous_facet_order}, but index `{current_uid}` orders {index_facet_order}.\n - Hint: Remove `federation.mergeFacets` or change `faceting.sortFacetValuesBy` to be consistent in settings.")]
     InconsistentFacetOrder {
         facet: String,
@@ -55,6 +57,14 @@ pub enum MeilisearchHttpError {
         .0.iter().map(|uid| format!("\"{uid}\"")).collect::<Vec<_>>().join(", "), .0.len()
     )]
     SwapIndexPayloadWrongLength(Vec<IndexUid>),
+    #[error("A transaction must contain at least one operation.")]
+    EmptyTransaction,
+    #[error("Missing field `{field}` in the operation {position} of type `{kind}`.")]
+    TransactionOperationMissingField { position: usize, kind: &'static str, field: &'static str },
+    #[error("The field `{field}` is not accepted by the operation {position} of type `{kind}`.")]
+    TransactionOperationUnexpectedField { position: usize, kind: &'static str, field: &'static str },
+    #[error("The documents of the operation {0} must all be JSON objects.")]
+    TransactionDocumentNotAnObject(usize),
     #[error(transparent)]
     IndexUid(#[from] IndexUidFormatError),
     #[error(transparent)]
@@ -101,6 +111,12 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::TooManySearchRequests(_) => Code::TooManySearchRequests,
             MeilisearchHttpError::SearchLimiterIsDown => Code::Internal,
             MeilisearchHttpError::SwapIndexPayloadWrongLength(_) => Code::InvalidSwapIndexes,
+            MeilisearchHttpError::EmptyTransaction
+            | MeilisearchHttpError::TransactionOperationMissingField { .. }
+            | MeilisearchHttpError::TransactionOperationUnexpectedField { .. }
+            | MeilisearchHttpError::TransactionDocumentNotAnObject(_) => {
+                Code::InvalidTransactionOperations
+            }
             MeilisearchHttpError::IndexUid(e) => e.error_code(),
             MeilisearchHttpError::SerdeJson(_) => Code::Internal,
             MeilisearchHttpError::HeedError(_) => Code::Internal,
@@ -117,7 +133,10 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::PaginationInFederatedQuery(_, _) => {
                 Code::InvalidMultiSearchQueryPagination
             }
-            MeilisearchHttpError::FacetsInFederatedQuery(..) => Code::InvalidMultiSearchQueryFacets,
+            MeilisearchHttpError::FacetsInFederatedQuery(..)
+            | MeilisearchHttpError::FacetRangesInFederatedQuery(..) => {
+                Code::InvalidMultiSearchQueryFacets
+            }
             MeilisearchHttpError::InconsistentFacetOrder { .. } => {
                 Code::InvalidMultiSearchFacetOrder
             }
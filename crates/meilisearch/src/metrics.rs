@@ -63,6 +63,21 @@ lazy_static! {
             "Meilisearch Searches Being Processed"
         ))
         .expect("Can't create a metric");
+    pub static ref MEILISEARCH_EMBEDDING_CACHE_HITS: IntGauge = register_int_gauge!(opts!(
+        "meilisearch_embedding_cache_hits",
+        "Meilisearch Embedding Cache Hits"
+    ))
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_EMBEDDING_CACHE_MISSES: IntGauge = register_int_gauge!(opts!(
+        "meilisearch_embedding_cache_misses",
+        "Meilisearch Embedding Cache Misses"
+    ))
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_EMBEDDING_CACHE_ENTRIES: IntGauge = register_int_gauge!(opts!(
+        "meilisearch_embedding_cache_entries",
+        "Meilisearch Embedding Cache Entries"
+    ))
+    .expect("Can't create a metric");
     pub static ref MEILISEARCH_TASK_QUEUE_LATENCY_SECONDS: Gauge = register_gauge!(
         "meilisearch_task_queue_latency_seconds",
         "Meilisearch Task Queue Latency in Seconds",
@@ -11,8 +11,10 @@ use std::{env, fmt, fs};
 
 use byte_unit::{Byte, ParseError, UnitType};
 use clap::Parser;
+use index_scheduler::clamp_to_page_size;
 use meilisearch_types::features::InstanceTogglableFeatures;
 use meilisearch_types::milli::update::IndexerConfig;
+use meilisearch_types::milli::vector::persistent_cache::PersistentEmbeddingCache;
 use meilisearch_types::milli::ThreadPoolNoAbortBuilder;
 use rustls::server::{ServerSessionMemoryCache, WebPkiClientVerifier};
 use rustls::RootCertStore;
@@ -63,8 +65,14 @@ const MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS: &str =
     "MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS";
 const MEILI_EXPERIMENTAL_LIMIT_BATCHED_TASKS_TOTAL_SIZE: &str =
     "MEILI_EXPERIMENTAL_LIMIT_BATCHED_TASKS_SIZE";
+const MEILI_EXPERIMENTAL_MAX_CONCURRENT_BATCHES: &str = "MEILI_EXPERIMENTAL_MAX_CONCURRENT_BATCHES";
 const MEILI_EXPERIMENTAL_EMBEDDING_CACHE_ENTRIES: &str =
     "MEILI_EXPERIMENTAL_EMBEDDING_CACHE_ENTRIES";
+const MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_ENTRIES: &str =
+    "MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_ENTRIES";
+const MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_SIZE: &str =
+    "MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_SIZE";
+const MEILI_EXPERIMENTAL_EMBEDDING_MEDIA_PATH: &str = "MEILI_EXPERIMENTAL_EMBEDDING_MEDIA_PATH";
 const DEFAULT_CONFIG_FILE_PATH: &str = "./config.toml";
 const DEFAULT_DB_PATH: &str = "./data.ms";
 const DEFAULT_HTTP_ADDR: &str = "localhost:7700";
@@ -74,6 +82,7 @@ const DEFAULT_SNAPSHOT_DIR: &str = "snapshots/";
 const DEFAULT_SNAPSHOT_INTERVAL_SEC: u64 = 86400;
 const DEFAULT_SNAPSHOT_INTERVAL_SEC_STR: &str = "86400";
 const DEFAULT_DUMP_DIR: &str = "dumps/";
+const DEFAULT_PERSISTENT_EMBEDDING_CACHE_SIZE: &str = "10GiB";
 
 const MEILI_MAX_INDEXING_MEMORY: &str = "MEILI_MAX_INDEXING_MEMORY";
 const MEILI_MAX_INDEXING_THREADS: &str = "MEILI_MAX_INDEXING_THREADS";
@@ -447,6 +456,14 @@ pub struct Opt {
     #[serde(default = "default_limit_batched_tasks_total_size")]
     pub experimental_limit_batched_tasks_total_size: u64,
 
+    /// Experimentally processes up to this number of batches at the same time, as long as they target
+    /// different indexes. Batches that are not targeting a single index are always processed alone.
+    ///
+    /// The default of 1 processes the batches one after the other.
+    #[clap(long, env = MEILI_EXPERIMENTAL_MAX_CONCURRENT_BATCHES, default_value_t = default_max_concurrent_batches())]
+    #[serde(default = "default_max_concurrent_batches")]
+    pub experimental_max_concurrent_batches: NonZeroUsize,
+
     /// Enables experimental caching of search query embeddings. The value represents the maximal number of entries in the cache of each
     /// distinct embedder.
     ///
@@ -455,6 +472,28 @@ pub struct Opt {
     #[serde(default = "default_embedding_cache_entries")]
     pub experimental_embedding_cache_entries: usize,
 
+    /// Enables experimental caching on disk of the embeddings generated when indexing documents and
+    /// searching. The value represents the maximal number of entries in the cache, shared by all the embedders.
+    ///
+    /// The oldest entries are evicted first. 0 disables the cache.
+    #[clap(long, env = MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_ENTRIES, default_value_t = default_persistent_embedding_cache_entries())]
+    #[serde(default = "default_persistent_embedding_cache_entries")]
+    pub experimental_persistent_embedding_cache_entries: usize,
+
+    /// Sets the maximum size of the embedding cache on disk. Value must be given in bytes or explicitly
+    /// stating a base unit (for instance: 107374182400, '107.7Gb', or '107374 Mb').
+    #[clap(long, env = MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_SIZE, default_value_t = default_persistent_embedding_cache_size())]
+    #[serde(default = "default_persistent_embedding_cache_size")]
+    pub experimental_persistent_embedding_cache_size: Byte,
+
+    /// Experimental directory from which the multimodal `rest` embedders read the media files referenced by the
+    /// documents, such as images.
+    ///
+    /// Documents can only reference files inside of this directory. Without it, the media must be sent in base64.
+    #[clap(long, env = MEILI_EXPERIMENTAL_EMBEDDING_MEDIA_PATH)]
+    #[serde(default)]
+    pub experimental_embedding_media_path: Option<PathBuf>,
+
     #[serde(flatten)]
     #[clap(flatten)]
     pub indexer_options: IndexerOpts,
@@ -558,7 +597,11 @@ impl Opt {
             experimental_reduce_indexing_memory_usage,
             experimental_max_number_of_batched_tasks,
             experimental_limit_batched_tasks_total_size,
+            experimental_max_concurrent_batches,
             experimental_embedding_cache_entries,
+            experimental_persistent_embedding_cache_entries,
+            experimental_persistent_embedding_cache_size,
+            experimental_embedding_media_path,
         } = self;
         export_to_env_if_not_present(MEILI_DB_PATH, db_path);
         export_to_env_if_not_present(MEILI_HTTP_ADDR, http_addr);
@@ -651,10 +694,28 @@ impl Opt {
             MEILI_EXPERIMENTAL_LIMIT_BATCHED_TASKS_TOTAL_SIZE,
             experimental_limit_batched_tasks_total_size.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_MAX_CONCURRENT_BATCHES,
+            experimental_max_concurrent_batches.to_string(),
+        );
         export_to_env_if_not_present(
             MEILI_EXPERIMENTAL_EMBEDDING_CACHE_ENTRIES,
             experimental_embedding_cache_entries.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_ENTRIES,
+            experimental_persistent_embedding_cache_entries.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_SIZE,
+            experimental_persistent_embedding_cache_size.to_string(),
+        );
+        if let Some(experimental_embedding_media_path) = experimental_embedding_media_path {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_EMBEDDING_MEDIA_PATH,
+                experimental_embedding_media_path,
+            );
+        }
         indexer_options.export_to_env();
     }
 
@@ -702,6 +763,18 @@ impl Opt {
         }
     }
 
+    /// The cache of the embeddings stored on disk, `None` if disabled.
+    pub(crate) fn persistent_embedding_cache(&self) -> Option<PersistentEmbeddingCache> {
+        let max_entries = NonZeroUsize::new(self.experimental_persistent_embedding_cache_entries)?;
+        let map_size =
+            clamp_to_page_size(self.experimental_persistent_embedding_cache_size.as_u64() as usize);
+        Some(PersistentEmbeddingCache::new(
+            self.db_path.join("embedding_cache"),
+            map_size,
+            max_entries,
+        ))
+    }
+
     pub(crate) fn to_instance_features(&self) -> InstanceTogglableFeatures {
         InstanceTogglableFeatures {
             metrics: self.experimental_enable_metrics,
@@ -972,10 +1045,22 @@ fn default_limit_batched_tasks_total_size() -> u64 {
     u64::MAX
 }
 
+fn default_max_concurrent_batches() -> NonZeroUsize {
+    NonZeroUsize::MIN
+}
+
 fn default_embedding_cache_entries() -> usize {
     0
 }
 
+fn default_persistent_embedding_cache_entries() -> usize {
+    0
+}
+
+fn default_persistent_embedding_cache_size() -> Byte {
+    Byte::from_str(DEFAULT_PERSISTENT_EMBEDDING_CACHE_SIZE).unwrap()
+}
+
 fn default_snapshot_dir() -> PathBuf {
     PathBuf::from(DEFAULT_SNAPSHOT_DIR)
 }
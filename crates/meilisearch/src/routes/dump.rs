@@ -11,7 +11,7 @@ use crate::analytics::Analytics;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
-use crate::routes::{get_task_id, is_dry_run, SummarizedTaskView};
+use crate::routes::{register_options, SummarizedTaskView};
 use crate::Opt;
 
 #[derive(OpenApi)]
@@ -81,10 +81,9 @@ pub async fn create_dump(
         keys: auth_controller.list_keys()?,
         instance_uid: analytics.instance_uid().cloned(),
     };
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
 
@@ -0,0 +1,82 @@
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use index_scheduler::IndexScheduler;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::keys::actions;
+use serde::Serialize;
+use tracing::debug;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::analytics::Analytics;
+use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(purge_embedding_cache),
+    tags((
+        name = "Embedding cache",
+        description = "The embedding cache route allows removing the embeddings cached on disk, when the persistent embedding cache is enabled.",
+    )),
+)]
+pub struct EmbeddingCacheApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::delete().to(SeqHandler(purge_embedding_cache))));
+}
+
+crate::empty_analytics!(EmbeddingCachePurgeAnalytics, "Embedding Cache Purged");
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct PurgedEmbeddingCache {
+    /// The number of embeddings removed from the cache.
+    deleted_entries: u64,
+}
+
+/// Purge the embedding cache
+///
+/// Removes all the embeddings cached on disk. The embeddings will be generated again by the embedders
+/// the next time they are needed. Nothing is removed if the persistent embedding cache is disabled.
+#[utoipa::path(
+    delete,
+    path = "",
+    tag = "Embedding cache",
+    security(("Bearer" = ["embeddingCache.delete", "*"])),
+    responses(
+        (status = 200, description = "The embedding cache was purged", body = PurgedEmbeddingCache, content_type = "application/json", example = json!(
+            {
+                "deletedEntries": 1024
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn purge_embedding_cache(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::EMBEDDING_CACHE_DELETE }>,
+        Data<IndexScheduler>,
+    >,
+    req: HttpRequest,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    analytics.publish(EmbeddingCachePurgeAnalytics::default(), &req);
+
+    let deleted_entries = tokio::task::spawn_blocking(move || {
+        index_scheduler.embedding_cache().map_or(Ok(0), |cache| cache.clear())
+    })
+    .await??;
+
+    let response = PurgedEmbeddingCache { deleted_entries };
+    debug!(returns = ?response, "Purge embedding cache");
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -9,7 +9,7 @@ use bstr::ByteSlice as _;
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
 use deserr::Deserr;
 use futures::StreamExt;
-use index_scheduler::{IndexScheduler, RoFeatures, TaskId};
+use index_scheduler::{IndexScheduler, RegisterOptions, RoFeatures};
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
 use meilisearch_types::document_formats::{read_csv, read_json, read_ndjson, PayloadType};
@@ -18,7 +18,7 @@ use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli::update::IndexDocumentsMethod;
-use meilisearch_types::milli::vector::parsed_vectors::ExplicitVectors;
+use meilisearch_types::milli::vector::parsed_vectors::{ExplicitVectors, VectorOrArrayOfVectors};
 use meilisearch_types::milli::DocumentId;
 use meilisearch_types::serde_cs::vec::CS;
 use meilisearch_types::star_or::OptionStarOrList;
@@ -43,7 +43,7 @@ use crate::extractors::authentication::GuardedData;
 use crate::extractors::payload::Payload;
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::routes::{
-    get_task_id, is_dry_run, PaginationView, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT,
+    register_options, PaginationView, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT,
 };
 use crate::search::{parse_filter, ExternalDocumentId, RetrieveVectors};
 use crate::{aggregate_methods, Opt};
@@ -374,10 +374,9 @@ pub async fn delete_document(
         index_uid: index_uid.to_string(),
         documents_ids: vec![document_id],
     };
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
     debug!("returns: {:?}", task);
@@ -795,8 +794,7 @@ pub async fn replace_documents(
     );
 
     let allow_index_creation = index_scheduler.filters().allow_index_creation(&index_uid);
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task = document_addition(
         extract_mime_type(&req)?,
         index_scheduler,
@@ -805,8 +803,7 @@ pub async fn replace_documents(
         params.csv_delimiter,
         body,
         IndexDocumentsMethod::ReplaceDocuments,
-        uid,
-        dry_run,
+        options,
         allow_index_creation,
     )
     .await?;
@@ -896,8 +893,7 @@ pub async fn update_documents(
     );
 
     let allow_index_creation = index_scheduler.filters().allow_index_creation(&index_uid);
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task = document_addition(
         extract_mime_type(&req)?,
         index_scheduler,
@@ -906,8 +902,7 @@ pub async fn update_documents(
         params.csv_delimiter,
         body,
         IndexDocumentsMethod::UpdateDocuments,
-        uid,
-        dry_run,
+        options,
         allow_index_creation,
     )
     .await?;
@@ -925,8 +920,7 @@ async fn document_addition(
     csv_delimiter: Option<u8>,
     body: Payload,
     method: IndexDocumentsMethod,
-    task_id: Option<TaskId>,
-    dry_run: bool,
+    options: RegisterOptions,
     allow_index_creation: bool,
 ) -> Result<SummarizedTaskView, MeilisearchHttpError> {
     let format = match (
@@ -959,7 +953,7 @@ async fn document_addition(
         }
     };
 
-    let (uuid, mut update_file) = index_scheduler.queue.create_update_file(dry_run)?;
+    let (uuid, mut update_file) = index_scheduler.queue.create_update_file(options.dry_run)?;
     let documents_count = match format {
         PayloadType::Ndjson => {
             let (path, file) = update_file.into_parts();
@@ -1047,15 +1041,16 @@ async fn document_addition(
     };
 
     let scheduler = index_scheduler.clone();
-    let task = match tokio::task::spawn_blocking(move || scheduler.register(task, task_id, dry_run))
-        .await?
-    {
-        Ok(task) => task,
-        Err(e) => {
-            index_scheduler.queue.delete_update_file(uuid)?;
-            return Err(e.into());
-        }
-    };
+    let task =
+        match tokio::task::spawn_blocking(move || scheduler.register_with_options(task, options))
+            .await?
+        {
+            Ok(task) => task,
+            Err(e) => {
+                index_scheduler.queue.delete_update_file(uuid)?;
+                return Err(e.into());
+            }
+        };
 
     Ok(task.into())
 }
@@ -1153,10 +1148,9 @@ pub async fn delete_documents_batch(
 
     let task =
         KindWithContent::DocumentDeletion { index_uid: index_uid.to_string(), documents_ids: ids };
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
 
@@ -1231,10 +1225,9 @@ pub async fn delete_documents_by_filter(
 
     let task = KindWithContent::DocumentDeletionByFilter { index_uid, filter_expr: filter };
 
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
 
@@ -1374,10 +1367,9 @@ pub async fn edit_documents_by_function(
         function,
     };
 
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
 
@@ -1433,10 +1425,9 @@ pub async fn clear_all_documents(
     );
 
     let task = KindWithContent::DocumentClear { index_uid: index_uid.to_string() };
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
 
@@ -1469,12 +1460,12 @@ fn some_documents<'a, 't: 'a>(
                         _ => Default::default(),
                     };
                     for (name, vector) in index.embeddings(rtxn, key)? {
-                        let user_provided = embedding_configs
-                            .iter()
-                            .find(|conf| conf.name == name)
-                            .is_some_and(|conf| conf.user_provided.contains(key));
+                        let config = embedding_configs.iter().find(|conf| conf.name == name);
+                        let user_provided =
+                            config.is_some_and(|conf| conf.user_provided.contains(key));
+                        let sparse = config.is_some_and(|conf| conf.config.is_sparse());
                         let embeddings = ExplicitVectors {
-                            embeddings: Some(vector.into()),
+                            embeddings: Some(VectorOrArrayOfVectors::from_stored(vector, sparse)),
                             regenerate: !user_provided,
                         };
                         vectors.insert(
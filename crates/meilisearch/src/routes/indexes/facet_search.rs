@@ -325,14 +325,19 @@ impl From<FacetSearchQuery> for SearchQuery {
             show_ranking_score: false,
             show_ranking_score_details: false,
             filter,
+            boost: None,
+            rerank: None,
             sort: None,
             distinct: None,
             facets: None,
+            facet_ranges: None,
+            facet_histogram: None,
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
             crop_marker: DEFAULT_CROP_MARKER(),
             matching_strategy,
             vector,
+            media: None,
             attributes_to_search_on,
             hybrid,
             ranking_score_threshold,
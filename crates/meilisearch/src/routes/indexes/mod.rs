@@ -1,4 +1,4 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::Infallible;
 
 use actix_web::web::Data;
@@ -19,13 +19,12 @@ use tracing::debug;
 use utoipa::{IntoParams, OpenApi, ToSchema};
 
 use super::{
-    get_task_id, Pagination, PaginationView, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT,
+    register_options, Pagination, PaginationView, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT,
 };
 use crate::analytics::{Aggregate, Analytics};
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::{AuthenticationError, GuardedData};
 use crate::extractors::sequential_extractor::SeqHandler;
-use crate::routes::is_dry_run;
 use crate::Opt;
 
 pub mod documents;
@@ -268,12 +267,12 @@ pub async fn create_index(
         );
 
         let task = KindWithContent::IndexCreation { index_uid: uid.to_string(), primary_key };
-        let uid = get_task_id(&req, &opt)?;
-        let dry_run = is_dry_run(&req, &opt)?;
-        let task: SummarizedTaskView =
-            tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
-                .await??
-                .into();
+        let options = register_options(&req, &opt, index_scheduler.filters())?;
+        let task: SummarizedTaskView = tokio::task::spawn_blocking(move || {
+            index_scheduler.register_with_options(task, options)
+        })
+        .await??
+        .into();
         debug!(returns = ?task, "Create index");
 
         Ok(HttpResponse::Accepted().json(task))
@@ -429,10 +428,9 @@ pub async fn update_index(
         primary_key: body.primary_key,
     };
 
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
 
@@ -477,10 +475,9 @@ pub async fn delete_index(
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
     let task = KindWithContent::IndexDeletion { index_uid: index_uid.into_inner() };
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
     debug!(returns = ?task, "Delete index");
@@ -506,6 +503,10 @@ pub struct IndexStats {
     /// Number of embedded documents in the index
     #[serde(skip_serializing_if = "Option::is_none")]
     pub number_of_embedded_documents: Option<u64>,
+    /// Size taken by the embeddings of each embedder, in bytes
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[schema(value_type = HashMap<String, u64>)]
+    pub embedder_storage_sizes: BTreeMap<String, u64>,
     /// Association of every field name with the number of times it occurs in the documents.
     #[schema(value_type = HashMap<String, u64>)]
     pub field_distribution: FieldDistribution,
@@ -523,6 +524,7 @@ impl From<index_scheduler::IndexStats> for IndexStats {
             is_indexing: stats.is_indexing,
             number_of_embeddings: stats.inner_stats.number_of_embeddings,
             number_of_embedded_documents: stats.inner_stats.number_of_embedded_documents,
+            embedder_storage_sizes: stats.inner_stats.embedder_storage_sizes.unwrap_or_default(),
             field_distribution: stats.inner_stats.field_distribution,
         }
     }
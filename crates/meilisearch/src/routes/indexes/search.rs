@@ -188,12 +188,16 @@ impl TryFrom<SearchQueryGet> for SearchQuery {
                     meilisearch_types::error::Code::InvalidSearchHybridQuery,
                 ));
             }
-            (Some(embedder), None) => {
-                Some(HybridQuery { semantic_ratio: DEFAULT_SEMANTIC_RATIO(), embedder })
-            }
-            (Some(embedder), Some(semantic_ratio)) => {
-                Some(HybridQuery { semantic_ratio: *semantic_ratio, embedder })
-            }
+            (Some(embedder), None) => Some(HybridQuery {
+                semantic_ratio: DEFAULT_SEMANTIC_RATIO(),
+                embedder,
+                ..Default::default()
+            }),
+            (Some(embedder), Some(semantic_ratio)) => Some(HybridQuery {
+                semantic_ratio: *semantic_ratio,
+                embedder,
+                ..Default::default()
+            }),
         };
 
         if other.vector.is_some() && hybrid.is_none() {
@@ -206,6 +210,7 @@ impl TryFrom<SearchQueryGet> for SearchQuery {
         Ok(Self {
             q: other.q,
             vector: other.vector.map(CS::into_inner),
+            media: None,
             offset: other.offset.0,
             limit: other.limit.0,
             page: other.page.as_deref().copied(),
@@ -216,12 +221,16 @@ impl TryFrom<SearchQueryGet> for SearchQuery {
             crop_length: other.crop_length.0,
             attributes_to_highlight: other.attributes_to_highlight.map(|o| o.into_iter().collect()),
             filter,
+            boost: None,
+            rerank: None,
             sort: other.sort.map(|attr| fix_sort_query_parameters(&attr)),
             distinct: other.distinct,
             show_matches_position: other.show_matches_position.0,
             show_ranking_score: other.show_ranking_score.0,
             show_ranking_score_details: other.show_ranking_score_details.0,
             facets: other.facets.map(|o| o.into_iter().collect()),
+            facet_ranges: None,
+            facet_histogram: None,
             highlight_pre_tag: other.highlight_pre_tag,
             highlight_post_tag: other.highlight_post_tag,
             crop_marker: other.crop_marker,
@@ -481,6 +490,40 @@ pub fn search_kind(
     index_uid: String,
     index: &milli::Index,
 ) -> Result<SearchKind, ResponseError> {
+    // media are embedded along with the query, they are the semantic counterpart of the query like a vector
+    if query.media.is_some() {
+        let Some(hybrid) = &query.hybrid else {
+            return Err(ResponseError::from_msg(
+                "Invalid request: missing `hybrid` parameter when `media` is present.".into(),
+                meilisearch_types::error::Code::MissingSearchHybrid,
+            ));
+        };
+        if query.vector.is_some() {
+            return Err(ResponseError::from_msg(
+                "Invalid request: `media` and `vector` cannot be used together.".into(),
+                meilisearch_types::error::Code::InvalidSearchMedia,
+            ));
+        }
+        let semantic_ratio = *hybrid.semantic_ratio;
+        return match query.q.as_deref() {
+            // hybrid.semantic_ratio == 0.0 => keyword
+            _ if semantic_ratio == 0.0 => Ok(SearchKind::KeywordOnly),
+            // query, hybrid.semantic_ratio < 1.0 => hybrid
+            Some(q) if !q.trim().is_empty() && semantic_ratio != 1.0 => SearchKind::hybrid(
+                index_scheduler,
+                index_uid,
+                index,
+                &hybrid.embedder,
+                semantic_ratio,
+                hybrid.fusion(),
+                hybrid.sparse_embedder.as_deref(),
+                None,
+            ),
+            // no query or hybrid.semantic_ratio == 1.0 => semantic
+            _ => SearchKind::semantic(index_scheduler, index_uid, index, &hybrid.embedder, None),
+        };
+    }
+
     // handle with care, the order of cases matters, the semantics is subtle
     match (query.q.as_deref(), &query.hybrid, query.vector.as_deref()) {
         // empty query, no vector => placeholder search
@@ -488,26 +531,28 @@ pub fn search_kind(
         // no query, no vector => placeholder search
         (None, _, None) => Ok(SearchKind::KeywordOnly),
         // hybrid.semantic_ratio == 1.0 => vector
-        (_, Some(HybridQuery { semantic_ratio, embedder }), v) if **semantic_ratio == 1.0 => {
+        (_, Some(HybridQuery { semantic_ratio, embedder, .. }), v) if **semantic_ratio == 1.0 => {
             SearchKind::semantic(index_scheduler, index_uid, index, embedder, v.map(|v| v.len()))
         }
         // hybrid.semantic_ratio == 0.0 => keyword
-        (_, Some(HybridQuery { semantic_ratio, embedder: _ }), _) if **semantic_ratio == 0.0 => {
+        (_, Some(HybridQuery { semantic_ratio, .. }), _) if **semantic_ratio == 0.0 => {
             Ok(SearchKind::KeywordOnly)
         }
         // no query, hybrid, vector => semantic
-        (None, Some(HybridQuery { semantic_ratio: _, embedder }), Some(v)) => {
+        (None, Some(HybridQuery { embedder, .. }), Some(v)) => {
             SearchKind::semantic(index_scheduler, index_uid, index, embedder, Some(v.len()))
         }
         // query, no hybrid, no vector => keyword
         (Some(_), None, None) => Ok(SearchKind::KeywordOnly),
         // query, hybrid, maybe vector => hybrid
-        (Some(_), Some(HybridQuery { semantic_ratio, embedder }), v) => SearchKind::hybrid(
+        (Some(_), Some(hybrid), v) => SearchKind::hybrid(
             index_scheduler,
             index_uid,
             index,
-            embedder,
-            **semantic_ratio,
+            &hybrid.embedder,
+            *hybrid.semantic_ratio,
+            hybrid.fusion(),
+            hybrid.sparse_embedder.as_deref(),
             v.map(|v| v.len()),
         ),
 
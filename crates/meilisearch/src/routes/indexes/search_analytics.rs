@@ -8,7 +8,7 @@ use serde_json::{json, Value};
 use crate::aggregate_methods;
 use crate::analytics::{Aggregate, AggregateMethod};
 use crate::search::{
-    SearchQuery, SearchResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    FusionMethod, SearchQuery, SearchResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
     DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
     DEFAULT_SEMANTIC_RATIO,
 };
@@ -46,6 +46,18 @@ pub struct SearchAggregator<Method: AggregateMethod> {
     filter_total_number_of_criteria: usize,
     used_syntax: HashMap<String, usize>,
 
+    // boost
+    // every time a request has boosts, this field must be incremented by one
+    boost_total_number_of_uses: usize,
+    // The maximum number of boosts in a request
+    max_boost_number: usize,
+
+    // rerank
+    // every time a request enables the reranking, this field must be incremented by one
+    rerank_total_number_of_uses: usize,
+    // The maximum number of hits sent to the reranker in a request
+    max_rerank_limit: usize,
+
     // attributes_to_search_on
     // every time a search is done using attributes_to_search_on
     attributes_to_search_on_total_number_of_uses: usize,
@@ -57,8 +69,14 @@ pub struct SearchAggregator<Method: AggregateMethod> {
     // vector
     // The maximum number of floats in a vector request
     max_vector_size: usize,
+    // every time a request contains media, this field must be incremented by one
+    media_total_number_of_uses: usize,
     // Whether the semantic ratio passed to a hybrid search equals the default ratio.
     semantic_ratio: bool,
+    // Whether a hybrid search used the Reciprocal Rank Fusion.
+    rrf_fusion: bool,
+    // Whether a hybrid search used a sparse embedder.
+    sparse_embedder: bool,
     hybrid: bool,
     retrieve_vectors: bool,
 
@@ -101,6 +119,7 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
         let SearchQuery {
             q,
             vector,
+            media,
             offset,
             limit,
             page,
@@ -114,9 +133,13 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
             show_ranking_score,
             show_ranking_score_details,
             filter,
+            boost,
+            rerank,
             sort,
             distinct,
             facets: _,
+            facet_ranges: _,
+            facet_histogram: _,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -163,6 +186,16 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
             ret.filter_sum_of_criteria_terms = RE.split(&stringified_filters).count();
         }
 
+        if let Some(ref boost) = boost {
+            ret.boost_total_number_of_uses = 1;
+            ret.max_boost_number = boost.len();
+        }
+
+        if let Some(rerank) = rerank {
+            ret.rerank_total_number_of_uses = 1;
+            ret.max_rerank_limit = rerank.limit;
+        }
+
         // attributes_to_search_on
         if attributes_to_search_on.is_some() {
             ret.attributes_to_search_on_total_number_of_uses = 1;
@@ -175,6 +208,9 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
         if let Some(ref vector) = vector {
             ret.max_vector_size = vector.len();
         }
+        if media.is_some() {
+            ret.media_total_number_of_uses = 1;
+        }
         ret.retrieve_vectors |= retrieve_vectors;
 
         if query.is_finite_pagination() {
@@ -206,6 +242,8 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
 
         if let Some(hybrid) = hybrid {
             ret.semantic_ratio = hybrid.semantic_ratio != DEFAULT_SEMANTIC_RATIO();
+            ret.rrf_fusion = hybrid.fusion == FusionMethod::Rrf;
+            ret.sparse_embedder = hybrid.sparse_embedder.is_some();
             ret.hybrid = true;
         }
 
@@ -221,6 +259,8 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
             semantic_hit_count: _,
             facet_distribution: _,
             facet_stats: _,
+            facet_ranges: _,
+            facet_histogram: _,
             degraded,
             used_negative_operator,
         } = result;
@@ -255,9 +295,14 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
             filter_sum_of_criteria_terms,
             filter_total_number_of_criteria,
             used_syntax,
+            boost_total_number_of_uses,
+            max_boost_number,
+            rerank_total_number_of_uses,
+            max_rerank_limit,
             attributes_to_search_on_total_number_of_uses,
             max_terms_number,
             max_vector_size,
+            media_total_number_of_uses,
             retrieve_vectors,
             matching_strategy,
             max_limit,
@@ -276,6 +321,8 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
             show_ranking_score,
             show_ranking_score_details,
             semantic_ratio,
+            rrf_fusion,
+            sparse_embedder,
             hybrid,
             total_degraded,
             total_used_negative_operator,
@@ -314,6 +361,16 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
             *used_syntax = used_syntax.saturating_add(value);
         }
 
+        // boost
+        self.boost_total_number_of_uses =
+            self.boost_total_number_of_uses.saturating_add(boost_total_number_of_uses);
+        self.max_boost_number = self.max_boost_number.max(max_boost_number);
+
+        // rerank
+        self.rerank_total_number_of_uses =
+            self.rerank_total_number_of_uses.saturating_add(rerank_total_number_of_uses);
+        self.max_rerank_limit = self.max_rerank_limit.max(max_rerank_limit);
+
         // attributes_to_search_on
         self.attributes_to_search_on_total_number_of_uses = self
             .attributes_to_search_on_total_number_of_uses
@@ -324,8 +381,12 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
 
         // vector
         self.max_vector_size = self.max_vector_size.max(max_vector_size);
+        self.media_total_number_of_uses =
+            self.media_total_number_of_uses.saturating_add(media_total_number_of_uses);
         self.retrieve_vectors |= retrieve_vectors;
         self.semantic_ratio |= semantic_ratio;
+        self.rrf_fusion |= rrf_fusion;
+        self.sparse_embedder |= sparse_embedder;
         self.hybrid |= hybrid;
 
         // pagination
@@ -381,9 +442,14 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
             filter_sum_of_criteria_terms,
             filter_total_number_of_criteria,
             used_syntax,
+            boost_total_number_of_uses,
+            max_boost_number,
+            rerank_total_number_of_uses,
+            max_rerank_limit,
             attributes_to_search_on_total_number_of_uses,
             max_terms_number,
             max_vector_size,
+            media_total_number_of_uses,
             retrieve_vectors,
             matching_strategy,
             max_limit,
@@ -402,6 +468,8 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
             show_ranking_score,
             show_ranking_score_details,
             semantic_ratio,
+            rrf_fusion,
+            sparse_embedder,
             hybrid,
             total_degraded,
             total_used_negative_operator,
@@ -437,6 +505,14 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
                "avg_criteria_number": format!("{:.2}", filter_sum_of_criteria_terms as f64 / filter_total_number_of_criteria as f64),
                "most_used_syntax": used_syntax.iter().max_by_key(|(_, v)| *v).map(|(k, _)| json!(k)).unwrap_or_else(|| json!(null)),
             },
+            "boost": {
+               "total_number_of_uses": boost_total_number_of_uses,
+               "max_boost_number": max_boost_number,
+            },
+            "rerank": {
+               "total_number_of_uses": rerank_total_number_of_uses,
+               "max_limit": max_rerank_limit,
+            },
             "attributes_to_search_on": {
                "total_number_of_uses": attributes_to_search_on_total_number_of_uses,
             },
@@ -447,9 +523,14 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
                 "max_vector_size": max_vector_size,
                 "retrieve_vectors": retrieve_vectors,
             },
+            "media": {
+                "total_number_of_uses": media_total_number_of_uses,
+            },
             "hybrid": {
                 "enabled": hybrid,
                 "semantic_ratio": semantic_ratio,
+                "rrf_fusion": rrf_fusion,
+                "sparse_embedder": sparse_embedder,
             },
             "pagination": {
                "max_limit": max_limit,
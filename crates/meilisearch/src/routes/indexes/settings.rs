@@ -16,7 +16,7 @@ use super::settings_analytics::*;
 use crate::analytics::Analytics;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
-use crate::routes::{get_task_id, is_dry_run, SummarizedTaskView};
+use crate::routes::{register_options, SummarizedTaskView};
 use crate::Opt;
 
 /// This macro generates the routes for the settings.
@@ -82,7 +82,7 @@ macro_rules! make_setting_route {
             use $crate::extractors::authentication::GuardedData;
             use $crate::extractors::sequential_extractor::SeqHandler;
             use $crate::Opt;
-            use $crate::routes::{is_dry_run, get_task_id, SummarizedTaskView};
+            use $crate::routes::{register_options, SummarizedTaskView};
             #[allow(unused_imports)]
             use super::*;
 
@@ -138,10 +138,9 @@ macro_rules! make_setting_route {
                     is_deletion: true,
                     allow_index_creation,
                 };
-                let uid = get_task_id(&req, &opt)?;
-                let dry_run = is_dry_run(&req, &opt)?;
+                let options = register_options(&req, &opt, index_scheduler.filters())?;
                 let task: SummarizedTaskView =
-                    tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+                    tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
                         .await??
                         .into();
 
@@ -224,10 +223,9 @@ macro_rules! make_setting_route {
                     is_deletion: false,
                     allow_index_creation,
                 };
-                let uid = get_task_id(&req, &opt)?;
-                let dry_run = is_dry_run(&req, &opt)?;
+                let options = register_options(&req, &opt, index_scheduler.filters())?;
                 let task: SummarizedTaskView =
-                    tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+                    tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
                         .await??
                         .into();
 
@@ -398,6 +396,17 @@ make_setting_routes!(
         camelcase_attr: "synonyms",
         analytics: SynonymsAnalytics
     },
+    {
+        route: "/synonym-sets",
+        update_verb: put,
+        value_type: Vec<meilisearch_types::settings::SynonymSetView>,
+        err_type: meilisearch_types::deserr::DeserrJsonError<
+            meilisearch_types::error::deserr_codes::InvalidSettingsSynonymSets,
+        >,
+        attr: synonym_sets,
+        camelcase_attr: "synonymSets",
+        analytics: SynonymSetsAnalytics
+    },
     {
         route: "/distinct-attribute",
         update_verb: put,
@@ -508,6 +517,28 @@ make_setting_routes!(
         camelcase_attr: "prefixSearch",
         analytics: PrefixSearchAnalytics
     },
+    {
+        route: "/pinning",
+        update_verb: put,
+        value_type: Vec<meilisearch_types::milli::PinningRule>,
+        err_type: meilisearch_types::deserr::DeserrJsonError<
+            meilisearch_types::error::deserr_codes::InvalidSettingsPinning,
+        >,
+        attr: pinning,
+        camelcase_attr: "pinning",
+        analytics: PinningAnalytics
+    },
+    {
+        route: "/reranker",
+        update_verb: put,
+        value_type: meilisearch_types::milli::RerankerSettings,
+        err_type: meilisearch_types::deserr::DeserrJsonError<
+            meilisearch_types::error::deserr_codes::InvalidSettingsReranker,
+        >,
+        attr: reranker,
+        camelcase_attr: "reranker",
+        analytics: RerankerAnalytics
+    },
 );
 
 #[utoipa::path(
@@ -583,6 +614,7 @@ pub async fn update_all(
             pagination: PaginationAnalytics::new(new_settings.pagination.as_ref().set()),
             stop_words: StopWordsAnalytics::new(new_settings.stop_words.as_ref().set()),
             synonyms: SynonymsAnalytics::new(new_settings.synonyms.as_ref().set()),
+            synonym_sets: SynonymSetsAnalytics::new(new_settings.synonym_sets.as_ref().set()),
             embedders: EmbeddersAnalytics::new(new_settings.embedders.as_ref().set()),
             search_cutoff_ms: SearchCutoffMsAnalytics::new(
                 new_settings.search_cutoff_ms.as_ref().set(),
@@ -597,6 +629,8 @@ pub async fn update_all(
             ),
             facet_search: FacetSearchAnalytics::new(new_settings.facet_search.as_ref().set()),
             prefix_search: PrefixSearchAnalytics::new(new_settings.prefix_search.as_ref().set()),
+            pinning: PinningAnalytics::new(new_settings.pinning.as_ref().set()),
+            reranker: RerankerAnalytics::new(new_settings.reranker.as_ref().set()),
         },
         &req,
     );
@@ -609,10 +643,9 @@ pub async fn update_all(
         is_deletion: false,
         allow_index_creation,
     };
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
 
@@ -703,10 +736,9 @@ pub async fn delete_all(
         is_deletion: true,
         allow_index_creation,
     };
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
 
@@ -714,7 +746,7 @@ pub async fn delete_all(
     Ok(HttpResponse::Accepted().json(task))
 }
 
-fn validate_settings(
+pub(crate) fn validate_settings(
     settings: Settings<Unchecked>,
     index_scheduler: &IndexScheduler,
 ) -> Result<Settings<Unchecked>, ResponseError> {
@@ -8,10 +8,10 @@ use std::collections::{BTreeMap, BTreeSet, HashSet};
 use meilisearch_types::facet_values_sort::FacetValuesSort;
 use meilisearch_types::locales::{Locale, LocalizedAttributesRuleView};
 use meilisearch_types::milli::update::Setting;
-use meilisearch_types::milli::FilterableAttributesRule;
+use meilisearch_types::milli::{FilterableAttributesRule, PinningRule, RerankerSettings};
 use meilisearch_types::settings::{
     FacetingSettings, PaginationSettings, PrefixSearchSettings, ProximityPrecisionView,
-    RankingRuleView, SettingEmbeddingSettings, TypoSettings,
+    RankingRuleView, SettingEmbeddingSettings, SynonymSetView, TypoSettings,
 };
 use serde::Serialize;
 
@@ -31,6 +31,7 @@ pub struct SettingsAnalytics {
     pub pagination: PaginationAnalytics,
     pub stop_words: StopWordsAnalytics,
     pub synonyms: SynonymsAnalytics,
+    pub synonym_sets: SynonymSetsAnalytics,
     pub embedders: EmbeddersAnalytics,
     pub search_cutoff_ms: SearchCutoffMsAnalytics,
     pub locales: LocalesAnalytics,
@@ -39,6 +40,8 @@ pub struct SettingsAnalytics {
     pub non_separator_tokens: NonSeparatorTokensAnalytics,
     pub facet_search: FacetSearchAnalytics,
     pub prefix_search: PrefixSearchAnalytics,
+    pub pinning: PinningAnalytics,
+    pub reranker: RerankerAnalytics,
 }
 
 impl Aggregate for SettingsAnalytics {
@@ -142,6 +145,10 @@ impl Aggregate for SettingsAnalytics {
                 total: new.stop_words.total.or(self.stop_words.total),
             },
             synonyms: SynonymsAnalytics { total: new.synonyms.total.or(self.synonyms.total) },
+            synonym_sets: SynonymSetsAnalytics {
+                total_rules: new.synonym_sets.total_rules.or(self.synonym_sets.total_rules),
+                locales: new.synonym_sets.locales.or(self.synonym_sets.locales),
+            },
             embedders: EmbeddersAnalytics {
                 total: new.embedders.total.or(self.embedders.total),
                 sources: match (self.embedders.sources, new.embedders.sources) {
@@ -165,6 +172,24 @@ impl Aggregate for SettingsAnalytics {
                     (Some(bytes), None) | (None, Some(bytes)) => Some(bytes),
                     (Some(this), Some(other)) => Some(this.max(other)),
                 },
+                chunking_used: match (self.embedders.chunking_used, new.embedders.chunking_used) {
+                    (None, None) => None,
+                    (Some(used), None) | (None, Some(used)) => Some(used),
+                    (Some(this), Some(other)) => Some(this | other),
+                },
+                media_used: match (self.embedders.media_used, new.embedders.media_used) {
+                    (None, None) => None,
+                    (Some(used), None) | (None, Some(used)) => Some(used),
+                    (Some(this), Some(other)) => Some(this | other),
+                },
+                late_interaction_used: match (
+                    self.embedders.late_interaction_used,
+                    new.embedders.late_interaction_used,
+                ) {
+                    (None, None) => None,
+                    (Some(used), None) | (None, Some(used)) => Some(used),
+                    (Some(this), Some(other)) => Some(this | other),
+                },
                 binary_quantization_used: match (
                     self.embedders.binary_quantization_used,
                     new.embedders.binary_quantization_used,
@@ -173,6 +198,18 @@ impl Aggregate for SettingsAnalytics {
                     (Some(bq), None) | (None, Some(bq)) => Some(bq),
                     (Some(this), Some(other)) => Some(this | other),
                 },
+                quantizations: match (self.embedders.quantizations, new.embedders.quantizations) {
+                    (None, None) => None,
+                    (Some(quantizations), None) | (None, Some(quantizations)) => {
+                        Some(quantizations)
+                    }
+                    (Some(this), Some(other)) => Some(this.union(&other).cloned().collect()),
+                },
+                distances: match (self.embedders.distances, new.embedders.distances) {
+                    (None, None) => None,
+                    (Some(distances), None) | (None, Some(distances)) => Some(distances),
+                    (Some(this), Some(other)) => Some(this.union(&other).cloned().collect()),
+                },
             },
             search_cutoff_ms: SearchCutoffMsAnalytics {
                 search_cutoff_ms: new
@@ -198,6 +235,18 @@ impl Aggregate for SettingsAnalytics {
                 set: new.prefix_search.set | self.prefix_search.set,
                 value: new.prefix_search.value.or(self.prefix_search.value),
             },
+            pinning: PinningAnalytics {
+                total_rules: new.pinning.total_rules.or(self.pinning.total_rules),
+                total_pinned: new.pinning.total_pinned.or(self.pinning.total_pinned),
+                total_hidden: new.pinning.total_hidden.or(self.pinning.total_hidden),
+            },
+            reranker: RerankerAnalytics {
+                set: new.reranker.set | self.reranker.set,
+                document_template_used: new
+                    .reranker
+                    .document_template_used
+                    .or(self.reranker.document_template_used),
+            },
         })
     }
 
@@ -254,6 +303,7 @@ impl RankingRulesAnalytics {
                             s,
                             meilisearch_types::settings::RankingRuleView::Asc(_)
                                 | meilisearch_types::settings::RankingRuleView::Desc(_)
+                                | meilisearch_types::settings::RankingRuleView::Expression(_)
                         )
                     })
                     .map(|x| x.to_string())
@@ -492,6 +542,27 @@ impl SynonymsAnalytics {
     }
 }
 
+#[derive(Serialize, Default)]
+pub struct SynonymSetsAnalytics {
+    pub total_rules: Option<usize>,
+    pub locales: Option<BTreeSet<Locale>>,
+}
+
+impl SynonymSetsAnalytics {
+    pub fn new(sets: Option<&Vec<SynonymSetView>>) -> Self {
+        Self {
+            total_rules: sets.map(|sets| sets.iter().map(|set| set.rules.len()).sum()),
+            locales: sets.map(|sets| {
+                sets.iter().flat_map(|set| set.locales.iter().flatten().cloned()).collect()
+            }),
+        }
+    }
+
+    pub fn into_settings(self) -> SettingsAnalytics {
+        SettingsAnalytics { synonym_sets: self, ..Default::default() }
+    }
+}
+
 #[derive(Serialize, Default)]
 pub struct EmbeddersAnalytics {
     // last
@@ -503,7 +574,17 @@ pub struct EmbeddersAnalytics {
     // max
     pub document_template_max_bytes: Option<usize>,
     // |=
+    pub chunking_used: Option<bool>,
+    // |=
+    pub media_used: Option<bool>,
+    // |=
+    pub late_interaction_used: Option<bool>,
+    // |=
     pub binary_quantization_used: Option<bool>,
+    // Merge the quantizations
+    pub quantizations: Option<HashSet<String>>,
+    // Merge the distances
+    pub distances: Option<HashSet<String>>,
 }
 
 impl EmbeddersAnalytics {
@@ -524,6 +605,8 @@ impl EmbeddersAnalytics {
                     EmbedderSource::Ollama => sources.insert("ollama".to_string()),
                     EmbedderSource::Rest => sources.insert("rest".to_string()),
                     EmbedderSource::Composite => sources.insert("composite".to_string()),
+                    EmbedderSource::Sparse => sources.insert("sparse".to_string()),
+                    EmbedderSource::Onnx => sources.insert("onnx".to_string()),
                 };
             }
         };
@@ -542,11 +625,40 @@ impl EmbeddersAnalytics {
                     .filter_map(|config| config.document_template_max_bytes.set())
                     .max()
             }),
+            chunking_used: setting.as_ref().map(|map| {
+                map.values()
+                    .filter_map(|config| config.inner.clone().set())
+                    .any(|config| config.chunking.set().is_some())
+            }),
+            media_used: setting.as_ref().map(|map| {
+                map.values()
+                    .filter_map(|config| config.inner.clone().set())
+                    .any(|config| config.media.set().is_some())
+            }),
+            late_interaction_used: setting.as_ref().map(|map| {
+                map.values()
+                    .filter_map(|config| config.inner.clone().set())
+                    .any(|config| config.late_interaction.set().unwrap_or_default())
+            }),
             binary_quantization_used: setting.as_ref().map(|map| {
                 map.values()
                     .filter_map(|config| config.inner.clone().set())
                     .any(|config| config.binary_quantized.set().is_some())
             }),
+            quantizations: setting.as_ref().map(|map| {
+                map.values()
+                    .filter_map(|config| config.inner.clone().set())
+                    .filter_map(|config| config.quantization.set())
+                    .map(|quantization| quantization.to_string())
+                    .collect()
+            }),
+            distances: setting.as_ref().map(|map| {
+                map.values()
+                    .filter_map(|config| config.inner.clone().set())
+                    .filter_map(|config| config.distance.set())
+                    .map(|distance| distance.to_string())
+                    .collect()
+            }),
         }
     }
 
@@ -674,3 +786,43 @@ impl PrefixSearchAnalytics {
         SettingsAnalytics { prefix_search: self, ..Default::default() }
     }
 }
+
+#[derive(Serialize, Default)]
+pub struct PinningAnalytics {
+    pub total_rules: Option<usize>,
+    pub total_pinned: Option<usize>,
+    pub total_hidden: Option<usize>,
+}
+
+impl PinningAnalytics {
+    pub fn new(rules: Option<&Vec<PinningRule>>) -> Self {
+        Self {
+            total_rules: rules.map(|rules| rules.len()),
+            total_pinned: rules.map(|rules| rules.iter().map(|rule| rule.pin.len()).sum()),
+            total_hidden: rules.map(|rules| rules.iter().map(|rule| rule.hide.len()).sum()),
+        }
+    }
+
+    pub fn into_settings(self) -> SettingsAnalytics {
+        SettingsAnalytics { pinning: self, ..Default::default() }
+    }
+}
+
+#[derive(Serialize, Default)]
+pub struct RerankerAnalytics {
+    pub set: bool,
+    pub document_template_used: Option<bool>,
+}
+
+impl RerankerAnalytics {
+    pub fn new(reranker: Option<&RerankerSettings>) -> Self {
+        Self {
+            set: reranker.is_some(),
+            document_template_used: reranker.map(|reranker| reranker.document_template.is_some()),
+        }
+    }
+
+    pub fn into_settings(self) -> SettingsAnalytics {
+        SettingsAnalytics { reranker: self, ..Default::default() }
+    }
+}
@@ -225,7 +225,7 @@ async fn similar(
 
     let index = index_scheduler.index(&index_uid)?;
 
-    let (embedder_name, embedder, quantized) = SearchKind::embedder(
+    let (embedder_name, embedder, quantization, distance) = SearchKind::embedder(
         &index_scheduler,
         index_uid.to_string(),
         &index,
@@ -240,7 +240,8 @@ async fn similar(
             query,
             embedder_name,
             embedder,
-            quantized,
+            quantization,
+            distance,
             retrieve_vectors,
             index_scheduler.features(),
         )
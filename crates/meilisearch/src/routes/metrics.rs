@@ -154,6 +154,12 @@ pub async fn get_metrics(
     }
     crate::metrics::MEILISEARCH_IS_INDEXING.set(index_scheduler.is_task_processing()? as i64);
 
+    if let Some(embedding_cache) = index_scheduler.embedding_cache() {
+        crate::metrics::MEILISEARCH_EMBEDDING_CACHE_HITS.set(embedding_cache.hits() as i64);
+        crate::metrics::MEILISEARCH_EMBEDDING_CACHE_MISSES.set(embedding_cache.misses() as i64);
+        crate::metrics::MEILISEARCH_EMBEDDING_CACHE_ENTRIES.set(embedding_cache.entries()? as i64);
+    }
+
     let task_queue_latency_seconds = index_scheduler
         .get_tasks_from_authorized_indexes(
             &Query {
@@ -2,24 +2,26 @@ use std::collections::BTreeMap;
 
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
-use index_scheduler::IndexScheduler;
-use meilisearch_auth::AuthController;
+use index_scheduler::{IndexScheduler, RegisterOptions, MAX_IDEMPOTENCY_KEY_BYTES};
+use meilisearch_auth::{AuthController, AuthFilter};
 use meilisearch_types::batch_view::BatchView;
 use meilisearch_types::batches::BatchStats;
 use meilisearch_types::error::{Code, ErrorType, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::keys::CreateApiKey;
+use meilisearch_types::milli::facet::DateBucket;
 use meilisearch_types::milli::{
-    AttributePatterns, FilterFeatures, FilterableAttributesFeatures, FilterableAttributesPatterns,
-    FilterableAttributesRule,
+    AttributePatterns, DateFeatures, FilterFeatures, FilterableAttributesFeatures,
+    FilterableAttributesPatterns, FilterableAttributesRule,
 };
 use meilisearch_types::settings::{
     Checked, FacetingSettings, MinWordSizeTyposSetting, PaginationSettings, Settings, TypoSettings,
     Unchecked,
 };
 use meilisearch_types::task_view::{DetailsView, TaskView};
-use meilisearch_types::tasks::{Kind, Status, Task, TaskId};
+use meilisearch_types::tasks::{Kind, Priority, Status, Task, TaskId};
 use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 use tracing::debug;
 use utoipa::{OpenApi, ToSchema};
@@ -40,6 +42,7 @@ use crate::routes::indexes::IndexView;
 use crate::routes::multi_search::SearchResults;
 use crate::routes::network::{Network, Remote};
 use crate::routes::swap_indexes::SwapIndexesPayload;
+use crate::routes::transactions::TransactionPayload;
 use crate::search::{
     FederatedSearch, FederatedSearchResult, Federation, FederationOptions, MergeFacets,
     SearchQueryWithIndex, SearchResultWithIndex, SimilarQuery, SimilarResult,
@@ -53,6 +56,7 @@ const PAGINATION_DEFAULT_LIMIT_FN: fn() -> usize = || 20;
 mod api_key;
 pub mod batches;
 mod dump;
+mod embedding_cache;
 pub mod features;
 pub mod indexes;
 mod logs;
@@ -66,6 +70,7 @@ mod swap_indexes;
 pub mod tasks;
 #[cfg(test)]
 mod tasks_test;
+mod transactions;
 
 #[derive(OpenApi)]
 #[openapi(
@@ -84,6 +89,8 @@ mod tasks_test;
         (path = "/swap-indexes", api = swap_indexes::SwapIndexesApi),
         (path = "/experimental-features", api = features::ExperimentalFeaturesApi),
         (path = "/network", api = network::NetworkApi),
+        (path = "/embedding-cache", api = embedding_cache::EmbeddingCacheApi),
+        (path = "/transactions", api = transactions::TransactionsApi),
     ),
     paths(get_health, get_version, get_stats),
     tags(
@@ -94,7 +101,7 @@ mod tasks_test;
         url = "/",
         description = "Local server",
     )),
-    components(schemas(PaginationView<KeyView>, PaginationView<IndexView>, IndexView, DocumentDeletionByFilter, AllBatches, BatchStats, ProgressStepView, ProgressView, BatchView, RuntimeTogglableFeatures, SwapIndexesPayload, DocumentEditionByFunction, MergeFacets, FederationOptions, SearchQueryWithIndex, Federation, FederatedSearch, FederatedSearchResult, SearchResults, SearchResultWithIndex, SimilarQuery, SimilarResult, PaginationView<serde_json::Value>, BrowseQuery, UpdateIndexRequest, IndexUid, IndexCreateRequest, KeyView, Action, CreateApiKey, UpdateStderrLogs, LogMode, GetLogs, IndexStats, Stats, HealthStatus, HealthResponse, VersionResponse, Code, ErrorType, AllTasks, TaskView, Status, DetailsView, ResponseError, Settings<Unchecked>, Settings<Checked>, TypoSettings, MinWordSizeTyposSetting, FacetingSettings, PaginationSettings, SummarizedTaskView, Kind, Network, Remote, FilterableAttributesRule, FilterableAttributesPatterns, AttributePatterns, FilterableAttributesFeatures, FilterFeatures))
+    components(schemas(PaginationView<KeyView>, PaginationView<IndexView>, IndexView, DocumentDeletionByFilter, AllBatches, BatchStats, ProgressStepView, ProgressView, BatchView, RuntimeTogglableFeatures, SwapIndexesPayload, TransactionPayload, DocumentEditionByFunction, MergeFacets, FederationOptions, SearchQueryWithIndex, Federation, FederatedSearch, FederatedSearchResult, SearchResults, SearchResultWithIndex, SimilarQuery, SimilarResult, PaginationView<serde_json::Value>, BrowseQuery, UpdateIndexRequest, IndexUid, IndexCreateRequest, KeyView, Action, CreateApiKey, UpdateStderrLogs, LogMode, GetLogs, IndexStats, Stats, HealthStatus, HealthResponse, VersionResponse, Code, ErrorType, AllTasks, TaskView, Status, Priority, DetailsView, ResponseError, Settings<Unchecked>, Settings<Checked>, TypoSettings, MinWordSizeTyposSetting, FacetingSettings, PaginationSettings, SummarizedTaskView, Kind, Network, Remote, FilterableAttributesRule, FilterableAttributesPatterns, AttributePatterns, FilterableAttributesFeatures, FilterFeatures, DateFeatures, DateBucket))
 )]
 pub struct MeilisearchApi;
 
@@ -113,7 +120,9 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(web::scope("/swap-indexes").configure(swap_indexes::configure))
         .service(web::scope("/metrics").configure(metrics::configure))
         .service(web::scope("/experimental-features").configure(features::configure))
-        .service(web::scope("/network").configure(network::configure));
+        .service(web::scope("/network").configure(network::configure))
+        .service(web::scope("/embedding-cache").configure(embedding_cache::configure))
+        .service(web::scope("/transactions").configure(transactions::configure));
 
     #[cfg(feature = "swagger")]
     {
@@ -173,6 +182,112 @@ pub fn is_dry_run(req: &HttpRequest, opt: &Opt) -> Result<bool, ResponseError> {
         .is_some_and(|s| s.to_lowercase() == "true"))
 }
 
+pub fn get_task_priority(req: &HttpRequest) -> Result<Priority, ResponseError> {
+    let priority = req
+        .headers()
+        .get("X-Meili-Task-Priority")
+        .map(|header| {
+            header.to_str().map_err(|e| {
+                ResponseError::from_msg(
+                    format!("X-Meili-Task-Priority is not a valid utf-8 string: {e}"),
+                    Code::BadRequest,
+                )
+            })
+        })
+        .transpose()?
+        .map(|s| {
+            s.parse::<Priority>()
+                .map_err(|e| ResponseError::from_msg(e.to_string(), Code::BadRequest))
+        })
+        .transpose()?;
+    Ok(priority.unwrap_or_default())
+}
+
+/// Reads the `X-Meili-Task-Run-After` header, the date before which the task must not be processed.
+///
+/// Until that date, the tasks enqueued after this one on the same indexes are not processed either,
+/// so that they are still applied in their enqueue order.
+pub fn get_task_run_after(req: &HttpRequest) -> Result<Option<OffsetDateTime>, ResponseError> {
+    req.headers()
+        .get("X-Meili-Task-Run-After")
+        .map(|header| {
+            header.to_str().map_err(|e| {
+                ResponseError::from_msg(
+                    format!("X-Meili-Task-Run-After is not a valid utf-8 string: {e}"),
+                    Code::BadRequest,
+                )
+            })
+        })
+        .transpose()?
+        .map(|s| {
+            OffsetDateTime::parse(s, &Rfc3339).map_err(|e| {
+                ResponseError::from_msg(
+                    format!("Could not parse X-Meili-Task-Run-After as an RFC 3339 date: {e}"),
+                    Code::BadRequest,
+                )
+            })
+        })
+        .transpose()
+}
+
+/// Retrieve a header whose value is used as a key in the task queue, such keys are limited
+/// to 511 bytes.
+fn get_task_key_header(
+    req: &HttpRequest,
+    name: &str,
+    max_bytes: usize,
+) -> Result<Option<String>, ResponseError> {
+    req.headers()
+        .get(name)
+        .map(|header| {
+            let value = header.to_str().map_err(|e| {
+                ResponseError::from_msg(
+                    format!("{name} is not a valid utf-8 string: {e}"),
+                    Code::BadRequest,
+                )
+            })?;
+            if value.is_empty() || value.len() > max_bytes {
+                return Err(ResponseError::from_msg(
+                    format!(
+                        "{name} must contain between 1 and {max_bytes} bytes but contains {} bytes",
+                        value.len()
+                    ),
+                    Code::BadRequest,
+                ));
+            }
+            Ok(value.to_string())
+        })
+        .transpose()
+}
+
+pub fn get_task_custom_metadata(req: &HttpRequest) -> Result<Option<String>, ResponseError> {
+    // The custom metadata is stored as is in an LMDB key.
+    get_task_key_header(req, "X-Meili-Task-Custom-Metadata", 511)
+}
+
+pub fn get_task_idempotency_key(req: &HttpRequest) -> Result<Option<String>, ResponseError> {
+    get_task_key_header(req, "Idempotency-Key", MAX_IDEMPOTENCY_KEY_BYTES)
+}
+
+/// Retrieve the options with which the task created by the request must be registered.
+///
+/// The idempotency key of the request is scoped to the API key it was authenticated with.
+pub fn register_options(
+    req: &HttpRequest,
+    opt: &Opt,
+    filters: &AuthFilter,
+) -> Result<RegisterOptions, ResponseError> {
+    Ok(RegisterOptions {
+        task_id: get_task_id(req, opt)?,
+        priority: get_task_priority(req)?,
+        run_after: get_task_run_after(req)?,
+        custom_metadata: get_task_custom_metadata(req)?,
+        idempotency_key: get_task_idempotency_key(req)?,
+        api_key_uid: filters.key_uid(),
+        dry_run: is_dry_run(req, opt)?,
+    })
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SummarizedTaskView {
@@ -42,6 +42,7 @@ impl MultiSearchAggregator {
             federation_options,
             q: _,
             vector: _,
+            media: _,
             offset: _,
             limit: _,
             page: _,
@@ -55,9 +56,13 @@ impl MultiSearchAggregator {
             show_ranking_score_details: _,
             show_matches_position: _,
             filter: _,
+            boost: _,
+            rerank: _,
             sort: _,
             distinct: _,
             facets: _,
+            facet_ranges: _,
+            facet_histogram: _,
             highlight_pre_tag: _,
             highlight_post_tag: _,
             crop_marker: _,
@@ -11,7 +11,7 @@ use meilisearch_types::tasks::{IndexSwap, KindWithContent};
 use serde::Serialize;
 use utoipa::{OpenApi, ToSchema};
 
-use super::{get_task_id, is_dry_run, SummarizedTaskView};
+use super::{register_options, SummarizedTaskView};
 use crate::analytics::{Aggregate, Analytics};
 use crate::error::MeilisearchHttpError;
 use crate::extractors::authentication::policies::*;
@@ -114,10 +114,9 @@ pub async fn swap_indexes(
     }
 
     let task = KindWithContent::IndexSwap { swaps };
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
     let task: SummarizedTaskView =
-        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+        tokio::task::spawn_blocking(move || index_scheduler.register_with_options(task, options))
             .await??
             .into();
     Ok(HttpResponse::Accepted().json(task))
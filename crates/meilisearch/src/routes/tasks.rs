@@ -13,7 +13,7 @@ use meilisearch_types::error::{InvalidTaskDateError, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::star_or::{OptionStarOr, OptionStarOrList};
 use meilisearch_types::task_view::TaskView;
-use meilisearch_types::tasks::{Kind, KindWithContent, Status};
+use meilisearch_types::tasks::{Kind, KindWithContent, Priority, Status};
 use serde::Serialize;
 use time::format_description::well_known::Rfc3339;
 use time::macros::format_description;
@@ -22,7 +22,7 @@ use tokio::io::AsyncReadExt;
 use tokio::task;
 use utoipa::{IntoParams, OpenApi, ToSchema};
 
-use super::{get_task_id, is_dry_run, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT};
+use super::{register_options, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT};
 use crate::analytics::{Aggregate, AggregateMethod, Analytics};
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
@@ -92,10 +92,22 @@ pub struct TasksFilterQuery {
     #[deserr(default, error = DeserrQueryParamError<InvalidTaskStatuses>)]
     #[param(required = false, value_type = Option<Vec<Status>>, example = json!([Status::Succeeded, Status::Failed, Status::Canceled, Status::Enqueued, Status::Processing, "*"]))]
     pub statuses: OptionStarOrList<Status>,
+    /// Permits to filter tasks by the priority they were enqueued with. By default, when `priorities` query parameter is not set, the tasks of all the priorities are returned. It's possible to specify several priorities by separating them with the `,` character.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTaskPriorities>)]
+    #[param(required = false, value_type = Option<Vec<Priority>>, example = json!([Priority::High, "*"]))]
+    pub priorities: OptionStarOrList<Priority>,
     /// Permits to filter tasks by their related index. By default, when `indexUids` query parameter is not set, the tasks of all the indexes are returned. It is possible to specify several indexes by separating them with the `,` character.
     #[deserr(default, error = DeserrQueryParamError<InvalidIndexUid>)]
     #[param(required = false, value_type = Option<Vec<String>>, example = json!(["movies", "theater", "*"]))]
     pub index_uids: OptionStarOrList<IndexUid>,
+    /// Permits to filter tasks by the custom metadata they were enqueued with. By default, when `customMetadata` query parameter is not set, all the tasks are returned. It's possible to specify several values by separating them with the `,` character.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTaskCustomMetadata>)]
+    #[param(required = false, value_type = Option<Vec<String>>, example = json!(["import-2024-08-08", "*"]))]
+    pub custom_metadata: OptionStarOrList<String>,
+    /// Permits to filter tasks by the `Idempotency-Key` they were enqueued with. By default, when `idempotencyKeys` query parameter is not set, all the tasks are returned. It's possible to specify several keys by separating them with the `,` character.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTaskIdempotencyKeys>)]
+    #[param(required = false, value_type = Option<Vec<String>>, example = json!(["5c1a0b0e-2d57-4cbb-9f43-6c2e4c1d8a4f", "*"]))]
+    pub idempotency_keys: OptionStarOrList<String>,
 
     /// Permits to filter tasks based on their enqueuedAt time. Matches tasks enqueued after the given date. Supports RFC 3339 date format.
     #[deserr(default, error = DeserrQueryParamError<InvalidTaskAfterEnqueuedAt>, try_from(OptionStarOr<String>) = deserialize_date_after -> InvalidTaskDateError)]
@@ -121,6 +133,14 @@ pub struct TasksFilterQuery {
     #[deserr(default, error = DeserrQueryParamError<InvalidTaskBeforeFinishedAt>, try_from(OptionStarOr<String>) = deserialize_date_before -> InvalidTaskDateError)]
     #[param(required = false, value_type = Option<String>, example = json!(["2024-08-08T16:37:09.971Z", "*"]))]
     pub before_finished_at: OptionStarOr<OffsetDateTime>,
+    /// Permits to filter tasks based on their runAfter time. Matches tasks that must run after the given date. Supports RFC 3339 date format.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTaskAfterRunAfter>, try_from(OptionStarOr<String>) = deserialize_date_after -> InvalidTaskDateError)]
+    #[param(required = false, value_type = Option<String>, example = json!(["2024-08-08T16:37:09.971Z", "*"]))]
+    pub after_run_after: OptionStarOr<OffsetDateTime>,
+    /// Permits to filter tasks based on their runAfter time. Matches tasks that must run before the given date. Supports RFC 3339 date format.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTaskBeforeRunAfter>, try_from(OptionStarOr<String>) = deserialize_date_before -> InvalidTaskDateError)]
+    #[param(required = false, value_type = Option<String>, example = json!(["2024-08-08T16:37:09.971Z", "*"]))]
+    pub before_run_after: OptionStarOr<OffsetDateTime>,
 }
 
 impl TasksFilterQuery {
@@ -132,15 +152,20 @@ impl TasksFilterQuery {
             batch_uids: self.batch_uids.merge_star_and_none(),
             statuses: self.statuses.merge_star_and_none(),
             types: self.types.merge_star_and_none(),
+            priorities: self.priorities.merge_star_and_none(),
             index_uids: self.index_uids.map(|x| x.to_string()).merge_star_and_none(),
             uids: self.uids.merge_star_and_none(),
             canceled_by: self.canceled_by.merge_star_and_none(),
+            custom_metadata: self.custom_metadata.merge_star_and_none(),
+            idempotency_keys: self.idempotency_keys.merge_star_and_none(),
             before_enqueued_at: self.before_enqueued_at.merge_star_and_none(),
             after_enqueued_at: self.after_enqueued_at.merge_star_and_none(),
             before_started_at: self.before_started_at.merge_star_and_none(),
             after_started_at: self.after_started_at.merge_star_and_none(),
             before_finished_at: self.before_finished_at.merge_star_and_none(),
             after_finished_at: self.after_finished_at.merge_star_and_none(),
+            before_run_after: self.before_run_after.merge_star_and_none(),
+            after_run_after: self.after_run_after.merge_star_and_none(),
         }
     }
 }
@@ -161,7 +186,9 @@ impl TaskDeletionOrCancelationQuery {
                 after_started_at: OptionStarOr::None,
                 before_started_at: OptionStarOr::None,
                 after_finished_at: OptionStarOr::None,
-                before_finished_at: OptionStarOr::None
+                before_finished_at: OptionStarOr::None,
+                after_run_after: OptionStarOr::None,
+                before_run_after: OptionStarOr::None
             }
         )
     }
@@ -220,6 +247,14 @@ pub struct TaskDeletionOrCancelationQuery {
     #[deserr(default, error = DeserrQueryParamError<InvalidTaskBeforeFinishedAt>, try_from(OptionStarOr<String>) = deserialize_date_before -> InvalidTaskDateError)]
     #[param(required = false, value_type = Option<String>, example = json!(["2024-08-08T16:37:09.971Z", "*"]))]
     pub before_finished_at: OptionStarOr<OffsetDateTime>,
+    /// Permits to filter tasks based on their runAfter time. Matches tasks that must run after the given date. Supports RFC 3339 date format.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTaskAfterRunAfter>, try_from(OptionStarOr<String>) = deserialize_date_after -> InvalidTaskDateError)]
+    #[param(required = false, value_type = Option<String>, example = json!(["2024-08-08T16:37:09.971Z", "*"]))]
+    pub after_run_after: OptionStarOr<OffsetDateTime>,
+    /// Permits to filter tasks based on their runAfter time. Matches tasks that must run before the given date. Supports RFC 3339 date format.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTaskBeforeRunAfter>, try_from(OptionStarOr<String>) = deserialize_date_before -> InvalidTaskDateError)]
+    #[param(required = false, value_type = Option<String>, example = json!(["2024-08-08T16:37:09.971Z", "*"]))]
+    pub before_run_after: OptionStarOr<OffsetDateTime>,
 }
 
 impl TaskDeletionOrCancelationQuery {
@@ -231,15 +266,20 @@ impl TaskDeletionOrCancelationQuery {
             batch_uids: self.batch_uids.merge_star_and_none(),
             statuses: self.statuses.merge_star_and_none(),
             types: self.types.merge_star_and_none(),
+            priorities: None,
             index_uids: self.index_uids.map(|x| x.to_string()).merge_star_and_none(),
             uids: self.uids.merge_star_and_none(),
             canceled_by: self.canceled_by.merge_star_and_none(),
+            custom_metadata: None,
+            idempotency_keys: None,
             before_enqueued_at: self.before_enqueued_at.merge_star_and_none(),
             after_enqueued_at: self.after_enqueued_at.merge_star_and_none(),
             before_started_at: self.before_started_at.merge_star_and_none(),
             after_started_at: self.after_started_at.merge_star_and_none(),
             before_finished_at: self.before_finished_at.merge_star_and_none(),
             after_finished_at: self.after_finished_at.merge_star_and_none(),
+            before_run_after: self.before_run_after.merge_star_and_none(),
+            after_run_after: self.after_run_after.merge_star_and_none(),
         }
     }
 }
@@ -262,6 +302,8 @@ struct TaskFilterAnalytics<Method: AggregateMethod> {
     filtered_by_after_started_at: bool,
     filtered_by_before_finished_at: bool,
     filtered_by_after_finished_at: bool,
+    filtered_by_before_run_after: bool,
+    filtered_by_after_run_after: bool,
 
     #[serde(skip)]
     marker: std::marker::PhantomData<Method>,
@@ -291,6 +333,10 @@ impl<Method: AggregateMethod + 'static> Aggregate for TaskFilterAnalytics<Method
                 | new.filtered_by_before_finished_at,
             filtered_by_after_finished_at: self.filtered_by_after_finished_at
                 | new.filtered_by_after_finished_at,
+            filtered_by_before_run_after: self.filtered_by_before_run_after
+                | new.filtered_by_before_run_after,
+            filtered_by_after_run_after: self.filtered_by_after_run_after
+                | new.filtered_by_after_run_after,
 
             marker: std::marker::PhantomData,
         })
@@ -322,7 +368,7 @@ impl<Method: AggregateMethod + 'static> Aggregate for TaskFilterAnalytics<Method
         )),
         (status = 400, description = "A filter is missing", body = ResponseError, content_type = "application/json", example = json!(
             {
-                "message": "Query parameters to filter the tasks to cancel are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`.",
+                "message": "Query parameters to filter the tasks to cancel are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`, `beforeRunAfter`, `afterRunAfter`.",
                 "code": "missing_task_filters",
                 "type": "invalid_request",
                 "link": "https://docs.meilisearch.com/errors#missing_task_filters"
@@ -372,6 +418,8 @@ async fn cancel_tasks(
             filtered_by_after_started_at: params.after_started_at.is_some(),
             filtered_by_before_finished_at: params.before_finished_at.is_some(),
             filtered_by_after_finished_at: params.after_finished_at.is_some(),
+            filtered_by_before_run_after: params.before_run_after.is_some(),
+            filtered_by_after_run_after: params.after_run_after.is_some(),
 
             marker: std::marker::PhantomData,
         },
@@ -385,11 +433,11 @@ async fn cancel_tasks(
     let task_cancelation =
         KindWithContent::TaskCancelation { query: format!("?{}", req.query_string()), tasks };
 
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
-    let task =
-        task::spawn_blocking(move || index_scheduler.register(task_cancelation, uid, dry_run))
-            .await??;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
+    let task = task::spawn_blocking(move || {
+        index_scheduler.register_with_options(task_cancelation, options)
+    })
+    .await??;
     let task: SummarizedTaskView = task.into();
 
     Ok(HttpResponse::Ok().json(task))
@@ -416,7 +464,7 @@ async fn cancel_tasks(
         )),
         (status = 400, description = "A filter is missing", body = ResponseError, content_type = "application/json", example = json!(
             {
-                "message": "Query parameters to filter the tasks to delete are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`.",
+                "message": "Query parameters to filter the tasks to delete are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`, `beforeRunAfter`, `afterRunAfter`.",
                 "code": "missing_task_filters",
                 "type": "invalid_request",
                 "link": "https://docs.meilisearch.com/errors#missing_task_filters"
@@ -466,6 +514,8 @@ async fn delete_tasks(
             filtered_by_after_started_at: params.after_started_at.is_some(),
             filtered_by_before_finished_at: params.before_finished_at.is_some(),
             filtered_by_after_finished_at: params.after_finished_at.is_some(),
+            filtered_by_before_run_after: params.before_run_after.is_some(),
+            filtered_by_after_run_after: params.after_run_after.is_some(),
 
             marker: std::marker::PhantomData,
         },
@@ -479,10 +529,10 @@ async fn delete_tasks(
     let task_deletion =
         KindWithContent::TaskDeletion { query: format!("?{}", req.query_string()), tasks };
 
-    let uid = get_task_id(&req, &opt)?;
-    let dry_run = is_dry_run(&req, &opt)?;
-    let task = task::spawn_blocking(move || index_scheduler.register(task_deletion, uid, dry_run))
-        .await??;
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
+    let task =
+        task::spawn_blocking(move || index_scheduler.register_with_options(task_deletion, options))
+            .await??;
     let task: SummarizedTaskView = task.into();
 
     Ok(HttpResponse::Ok().json(task))
@@ -0,0 +1,421 @@
+use std::io::{BufWriter, Write};
+
+use actix_web::web::Data;
+use actix_web::{web, HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_auth::{AuthController, AuthFilter};
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::{
+    InvalidDocumentId, InvalidIndexPrimaryKey, InvalidIndexUid, InvalidSwapIndexes,
+    InvalidTransactionOperationType, InvalidTransactionOperations,
+};
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::keys::Action;
+use meilisearch_types::milli::update::IndexDocumentsMethod;
+use meilisearch_types::settings::{Settings, Unchecked};
+use meilisearch_types::tasks::{IndexSwap, KindWithContent, TransactionOperation};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::{OpenApi, ToSchema};
+
+use super::indexes::settings::validate_settings;
+use super::{register_options, SummarizedTaskView};
+use crate::analytics::{Aggregate, Analytics};
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::{AuthenticationError, GuardedData};
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::Opt;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(create_transaction),
+    tags((
+        name = "Transactions",
+        description = "The `/transactions` route allows applying document, settings, and index swap operations on several indexes atomically.",
+    )),
+)]
+pub struct TransactionsApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(create_transaction))));
+}
+
+#[derive(Deserr, Debug, ToSchema)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+#[schema(rename_all = "camelCase")]
+pub struct TransactionPayload {
+    /// The operations of the transaction, applied in the given order.
+    #[deserr(missing_field_error = DeserrJsonError::missing_transaction_operations)]
+    operations: Vec<TransactionOperationPayload>,
+}
+
+#[derive(Deserr, Debug, ToSchema)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+#[schema(rename_all = "camelCase")]
+pub struct TransactionOperationPayload {
+    /// The type of the operation.
+    #[deserr(rename = "type", error = DeserrJsonError<InvalidTransactionOperationType>, missing_field_error = DeserrJsonError::missing_transaction_operation_type)]
+    #[schema(rename = "type")]
+    kind: TransactionOperationType,
+    /// The index updated by a document or settings operation.
+    #[deserr(default, error = DeserrJsonError<InvalidIndexUid>)]
+    index_uid: Option<IndexUid>,
+    /// The documents to add or update.
+    #[deserr(default, error = DeserrJsonError<InvalidTransactionOperations>)]
+    documents: Option<Vec<Value>>,
+    /// The primary key of the index when adding documents.
+    #[deserr(default, error = DeserrJsonError<InvalidIndexPrimaryKey>)]
+    primary_key: Option<String>,
+    /// Whether the given documents replace or update the existing ones, defaults to `replace`.
+    #[deserr(default, error = DeserrJsonError<InvalidTransactionOperations>)]
+    method: Option<TransactionDocumentsMethod>,
+    /// The ids of the documents to delete.
+    #[deserr(default, error = DeserrJsonError<InvalidDocumentId>)]
+    document_ids: Option<Vec<Value>>,
+    /// The new settings of the index.
+    #[deserr(default)]
+    settings: Option<Settings<Unchecked>>,
+    /// The two indexes to swap.
+    #[deserr(default, error = DeserrJsonError<InvalidSwapIndexes>)]
+    indexes: Option<Vec<IndexUid>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserr, PartialEq, Eq, ToSchema)]
+#[deserr(rename_all = camelCase)]
+#[schema(rename_all = "camelCase")]
+pub enum TransactionOperationType {
+    DocumentAdditionOrUpdate,
+    DocumentDeletion,
+    SettingsUpdate,
+    IndexSwap,
+}
+
+impl TransactionOperationType {
+    fn name(&self) -> &'static str {
+        match self {
+            TransactionOperationType::DocumentAdditionOrUpdate => "documentAdditionOrUpdate",
+            TransactionOperationType::DocumentDeletion => "documentDeletion",
+            TransactionOperationType::SettingsUpdate => "settingsUpdate",
+            TransactionOperationType::IndexSwap => "indexSwap",
+        }
+    }
+
+    /// The fields an operation of this type accepts, besides its type.
+    fn accepted_fields(&self) -> &'static [&'static str] {
+        match self {
+            TransactionOperationType::DocumentAdditionOrUpdate => {
+                &["indexUid", "documents", "primaryKey", "method"]
+            }
+            TransactionOperationType::DocumentDeletion => &["indexUid", "documentIds"],
+            TransactionOperationType::SettingsUpdate => &["indexUid", "settings"],
+            TransactionOperationType::IndexSwap => &["indexes"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserr, PartialEq, Eq, ToSchema)]
+#[deserr(rename_all = camelCase)]
+#[schema(rename_all = "camelCase")]
+pub enum TransactionDocumentsMethod {
+    #[default]
+    Replace,
+    Update,
+}
+
+#[derive(Serialize, Default)]
+struct TransactionAnalytics {
+    total_received: usize,
+    max_operations_per_transaction: usize,
+    document_operations: usize,
+    settings_updates: usize,
+    index_swaps: usize,
+}
+
+impl Aggregate for TransactionAnalytics {
+    fn event_name(&self) -> &'static str {
+        "Transaction Created"
+    }
+
+    fn aggregate(self: Box<Self>, new: Box<Self>) -> Box<Self> {
+        Box::new(Self {
+            total_received: self.total_received.saturating_add(new.total_received),
+            max_operations_per_transaction: self
+                .max_operations_per_transaction
+                .max(new.max_operations_per_transaction),
+            document_operations: self.document_operations.saturating_add(new.document_operations),
+            settings_updates: self.settings_updates.saturating_add(new.settings_updates),
+            index_swaps: self.index_swaps.saturating_add(new.index_swaps),
+        })
+    }
+
+    fn into_event(self: Box<Self>) -> serde_json::Value {
+        serde_json::to_value(*self).unwrap_or_default()
+    }
+}
+
+/// Create a transaction
+///
+/// Enqueue a group of document, settings and index swap operations, possibly targeting several indexes, in a single task.
+/// The transaction is atomic: either every operation succeeds and all the changes are committed, or nothing is changed.
+///
+/// On top of `transactions.create`, the API key must hold the action of each operation on the indexes it targets:
+/// `documents.add`, `documents.delete`, `settings.update` or `indexes.swap`.
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "Transactions",
+    security(("Bearer" = ["transactions.create", "*"])),
+    request_body = TransactionPayload,
+    responses(
+        (status = 202, description = "Task successfully enqueued", body = SummarizedTaskView, content_type = "application/json", example = json!(
+            {
+                "taskUid": 147,
+                "indexUid": null,
+                "status": "enqueued",
+                "type": "transaction",
+                "enqueuedAt": "2024-08-08T17:05:55.791772Z"
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn create_transaction(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::TRANSACTIONS_CREATE }>,
+        Data<IndexScheduler>,
+    >,
+    auth_controller: Data<AuthController>,
+    params: AwebJson<TransactionPayload, DeserrJsonError>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let TransactionPayload { operations: payloads } = params.into_inner();
+    if payloads.is_empty() {
+        return Err(MeilisearchHttpError::EmptyTransaction.into());
+    }
+
+    let mut analytics_aggregate = TransactionAnalytics {
+        total_received: 1,
+        max_operations_per_transaction: payloads.len(),
+        ..Default::default()
+    };
+    let filters = index_scheduler.filters();
+
+    // The documents of each addition are kept aside until they are written in their update file.
+    let mut operations = Vec::with_capacity(payloads.len());
+    for (position, payload) in payloads.into_iter().enumerate() {
+        let kind = payload.kind;
+        let given_fields = [
+            ("indexUid", payload.index_uid.is_some()),
+            ("documents", payload.documents.is_some()),
+            ("primaryKey", payload.primary_key.is_some()),
+            ("method", payload.method.is_some()),
+            ("documentIds", payload.document_ids.is_some()),
+            ("settings", payload.settings.is_some()),
+            ("indexes", payload.indexes.is_some()),
+        ];
+        if let Some((field, _)) = given_fields
+            .into_iter()
+            .find(|(field, given)| *given && !kind.accepted_fields().contains(field))
+        {
+            return Err(MeilisearchHttpError::TransactionOperationUnexpectedField {
+                position,
+                kind: kind.name(),
+                field,
+            }
+            .into());
+        }
+        let missing = |field| MeilisearchHttpError::TransactionOperationMissingField {
+            position,
+            kind: kind.name(),
+            field,
+        };
+
+        let operation = match kind {
+            TransactionOperationType::DocumentAdditionOrUpdate => {
+                let index_uid = payload.index_uid.ok_or_else(|| missing("indexUid"))?;
+                let documents = payload.documents.ok_or_else(|| missing("documents"))?;
+                ensure_authorized(&auth_controller, filters, Action::DocumentsAdd, &index_uid)?;
+                if documents.iter().any(|document| !document.is_object()) {
+                    return Err(
+                        MeilisearchHttpError::TransactionDocumentNotAnObject(position).into()
+                    );
+                }
+                analytics_aggregate.document_operations += 1;
+                let method = match payload.method.unwrap_or_default() {
+                    TransactionDocumentsMethod::Replace => IndexDocumentsMethod::ReplaceDocuments,
+                    TransactionDocumentsMethod::Update => IndexDocumentsMethod::UpdateDocuments,
+                };
+                let operation = TransactionOperation::DocumentAdditionOrUpdate {
+                    allow_index_creation: filters.allow_index_creation(&index_uid),
+                    index_uid: index_uid.into_inner(),
+                    primary_key: payload.primary_key,
+                    method,
+                    // The content file is created once every operation has been validated.
+                    content_file: Default::default(),
+                    documents_count: documents.len() as u64,
+                };
+                (operation, documents)
+            }
+            TransactionOperationType::DocumentDeletion => {
+                let index_uid = payload.index_uid.ok_or_else(|| missing("indexUid"))?;
+                let document_ids = payload.document_ids.ok_or_else(|| missing("documentIds"))?;
+                ensure_authorized(&auth_controller, filters, Action::DocumentsDelete, &index_uid)?;
+                analytics_aggregate.document_operations += 1;
+                let documents_ids = document_ids
+                    .iter()
+                    .map(|v| v.as_str().map(String::from).unwrap_or_else(|| v.to_string()))
+                    .collect();
+                let operation = TransactionOperation::DocumentDeletion {
+                    index_uid: index_uid.into_inner(),
+                    documents_ids,
+                };
+                (operation, Vec::new())
+            }
+            TransactionOperationType::SettingsUpdate => {
+                let index_uid = payload.index_uid.ok_or_else(|| missing("indexUid"))?;
+                let settings = payload.settings.ok_or_else(|| missing("settings"))?;
+                ensure_authorized(&auth_controller, filters, Action::SettingsUpdate, &index_uid)?;
+                analytics_aggregate.settings_updates += 1;
+                let new_settings = validate_settings(settings, &index_scheduler)?;
+                let operation = TransactionOperation::SettingsUpdate {
+                    allow_index_creation: filters.allow_index_creation(&index_uid),
+                    index_uid: index_uid.into_inner(),
+                    new_settings: Box::new(new_settings),
+                    is_deletion: false,
+                };
+                (operation, Vec::new())
+            }
+            TransactionOperationType::IndexSwap => {
+                let indexes = payload.indexes.ok_or_else(|| missing("indexes"))?;
+                let (lhs, rhs) = match indexes.as_slice() {
+                    [lhs, rhs] => (lhs, rhs),
+                    _ => {
+                        return Err(
+                            MeilisearchHttpError::SwapIndexPayloadWrongLength(indexes).into()
+                        )
+                    }
+                };
+                ensure_authorized(&auth_controller, filters, Action::IndexesSwap, lhs)?;
+                ensure_authorized(&auth_controller, filters, Action::IndexesSwap, rhs)?;
+                analytics_aggregate.index_swaps += 1;
+                let swaps = vec![IndexSwap { indexes: (lhs.to_string(), rhs.to_string()) }];
+                (TransactionOperation::IndexSwap { swaps }, Vec::new())
+            }
+        };
+        operations.push(operation);
+    }
+    analytics.publish(analytics_aggregate, &req);
+
+    let options = register_options(&req, &opt, index_scheduler.filters())?;
+    let scheduler = index_scheduler.clone();
+    let operations = tokio::task::spawn_blocking(move || {
+        write_update_files(&scheduler, options.dry_run, operations)
+    })
+    .await??;
+    let content_files: Vec<_> = operations
+        .iter()
+        .filter_map(|operation| match operation {
+            TransactionOperation::DocumentAdditionOrUpdate { content_file, .. } => {
+                Some(*content_file)
+            }
+            _ => None,
+        })
+        .collect();
+
+    let task = KindWithContent::Transaction { operations };
+    let scheduler = index_scheduler.clone();
+    let task =
+        match tokio::task::spawn_blocking(move || scheduler.register_with_options(task, options))
+            .await?
+        {
+            Ok(task) => task,
+            Err(e) => {
+                for uuid in content_files {
+                    index_scheduler.queue.delete_update_file(uuid)?;
+                }
+                return Err(e.into());
+            }
+        };
+
+    let task: SummarizedTaskView = task.into();
+    Ok(HttpResponse::Accepted().json(task))
+}
+
+/// Ensure the API key, or the tenant token, is allowed to apply the action of an operation to the index.
+fn ensure_authorized(
+    auth_controller: &AuthController,
+    filters: &AuthFilter,
+    action: Action,
+    index: &str,
+) -> Result<(), ResponseError> {
+    if !filters.is_index_authorized(index) {
+        return Err(AuthenticationError::InvalidToken.into());
+    }
+    // Without a key uid, the master key is used or there is no master key: everything is allowed.
+    if let Some(key_uid) = filters.key_uid() {
+        if !auth_controller.is_key_authorized(key_uid, action, Some(index))? {
+            return Err(AuthenticationError::InvalidToken.into());
+        }
+    }
+    Ok(())
+}
+
+/// Write the documents of each addition in its own update file.
+fn write_update_files(
+    index_scheduler: &IndexScheduler,
+    dry_run: bool,
+    operations: Vec<(TransactionOperation, Vec<Value>)>,
+) -> Result<Vec<TransactionOperation>, MeilisearchHttpError> {
+    let mut written = Vec::with_capacity(operations.len());
+    for (mut operation, documents) in operations {
+        if let TransactionOperation::DocumentAdditionOrUpdate { content_file, .. } = &mut operation
+        {
+            match write_update_file(index_scheduler, dry_run, &documents) {
+                Ok(uuid) => *content_file = uuid,
+                Err(e) => {
+                    // Do not leave the files of the previous additions behind.
+                    for operation in written {
+                        if let TransactionOperation::DocumentAdditionOrUpdate {
+                            content_file, ..
+                        } = operation
+                        {
+                            index_scheduler.queue.delete_update_file(content_file)?;
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        written.push(operation);
+    }
+    Ok(written)
+}
+
+fn write_update_file(
+    index_scheduler: &IndexScheduler,
+    dry_run: bool,
+    documents: &[Value],
+) -> Result<uuid::Uuid, MeilisearchHttpError> {
+    let (uuid, mut update_file) = index_scheduler.queue.create_update_file(dry_run)?;
+    let mut writer = BufWriter::new(&mut update_file);
+    for document in documents {
+        serde_json::to_writer(&mut writer, document)?;
+    }
+    writer.flush().map_err(|e| index_scheduler::Error::FileStore(file_store::Error::IoError(e)))?;
+    drop(writer);
+    update_file.persist()?;
+    Ok(uuid)
+}
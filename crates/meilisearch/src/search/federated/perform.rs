@@ -474,6 +474,14 @@ impl PartitionedQueries {
             .into());
         }
 
+        if let Some(facet_ranges_field) = federated_query.has_facet_ranges() {
+            return Err(MeilisearchHttpError::FacetRangesInFederatedQuery(
+                query_index,
+                facet_ranges_field,
+            )
+            .into());
+        }
+
         let (index_uid, query, federation_options) = federated_query.into_index_query_federation();
 
         let federation_options = federation_options.unwrap_or_default();
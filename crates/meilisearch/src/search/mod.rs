@@ -1,11 +1,12 @@
 use core::fmt;
 use std::cmp::min;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::convert::Infallible;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use deserr::Deserr;
+use deserr::{DeserializeError, Deserr, ErrorKind, ValuePointerRef};
 use either::Either;
 use index_scheduler::RoFeatures;
 use indexmap::IndexMap;
@@ -16,18 +17,23 @@ use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::locales::Locale;
+use meilisearch_types::milli::geojson::GeoJsonGeometry;
 use meilisearch_types::milli::score_details::{ScoreDetails, ScoringStrategy};
-use meilisearch_types::milli::vector::parsed_vectors::ExplicitVectors;
-use meilisearch_types::milli::vector::Embedder;
+use meilisearch_types::milli::vector::media::MediaInput;
+use meilisearch_types::milli::vector::parsed_vectors::{ExplicitVectors, VectorOrArrayOfVectors};
+use meilisearch_types::milli::vector::sparse::SparseVector;
+use meilisearch_types::milli::vector::{Embedder, VectorDistance, VectorQuantization};
 use meilisearch_types::milli::{
-    FacetValueHit, InternalError, OrderBy, PatternMatch, SearchForFacetValues, TimeBudget,
+    FacetRange, FacetValueHit, InternalError, OrderBy, PatternMatch, SearchForFacetValues,
+    TimeBudget,
 };
 use meilisearch_types::settings::DEFAULT_PAGINATION_MAX_TOTAL_HITS;
 use meilisearch_types::{milli, Document};
 use milli::tokenizer::{Language, TokenizerBuilder};
 use milli::{
-    AscDesc, FieldId, FieldsIdsMap, Filter, FormatOptions, Index, LocalizedAttributesRule,
-    MatchBounds, MatcherBuilder, SortError, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    AscDesc, FieldId, FieldsIdsMap, Filter, FormatOptions, HybridFusion, Index,
+    LocalizedAttributesRule, MatchBounds, MatcherBuilder, SortError, TermsMatchingStrategy,
+    DEFAULT_VALUES_PER_FACET,
 };
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -55,6 +61,7 @@ pub const DEFAULT_CROP_MARKER: fn() -> String = || "…".to_string();
 pub const DEFAULT_HIGHLIGHT_PRE_TAG: fn() -> String = || "<em>".to_string();
 pub const DEFAULT_HIGHLIGHT_POST_TAG: fn() -> String = || "</em>".to_string();
 pub const DEFAULT_SEMANTIC_RATIO: fn() -> SemanticRatio = || SemanticRatio(0.5);
+pub const DEFAULT_RANK_CONSTANT: u32 = 60;
 
 #[derive(Clone, Default, PartialEq, Deserr, ToSchema)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
@@ -63,6 +70,8 @@ pub struct SearchQuery {
     pub q: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchVector>)]
     pub vector: Option<Vec<f32>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchMedia>)]
+    pub media: Option<Value>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
     #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
@@ -94,12 +103,20 @@ pub struct SearchQuery {
     pub show_ranking_score_details: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
     pub filter: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchBoost>)]
+    pub boost: Option<Vec<SearchBoost>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRerank>)]
+    pub rerank: Option<RerankQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchSort>)]
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
     pub distinct: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetRanges>)]
+    pub facet_ranges: Option<BTreeMap<String, Vec<FacetRange>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetHistogram>)]
+    pub facet_histogram: Option<BTreeMap<String, FacetHistogram>>,
     #[deserr(error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     #[schema(default = DEFAULT_HIGHLIGHT_PRE_TAG)]
     pub highlight_pre_tag: String,
@@ -136,6 +153,59 @@ impl std::convert::TryFrom<f64> for RankingScoreThreshold {
     }
 }
 
+/// Splits the values of a numeric facet in buckets of `interval` width.
+#[derive(Debug, Clone, Copy, PartialEq, Deserr, ToSchema, Serialize)]
+#[deserr(error = DeserrJsonError<InvalidSearchFacetHistogram>, rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetHistogram {
+    #[schema(value_type = f64)]
+    pub interval: FacetHistogramInterval,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserr, Serialize)]
+#[deserr(try_from(f64) = TryFrom::try_from -> InvalidSearchFacetHistogram)]
+pub struct FacetHistogramInterval(f64);
+
+impl std::convert::TryFrom<f64> for FacetHistogramInterval {
+    type Error = InvalidSearchFacetHistogram;
+
+    fn try_from(f: f64) -> Result<Self, Self::Error> {
+        if f.is_finite() && f > 0.0 {
+            Ok(Self(f))
+        } else {
+            Err(InvalidSearchFacetHistogram)
+        }
+    }
+}
+
+impl std::ops::Deref for FacetHistogramInterval {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Documents matching `filter` are ranked before the other documents.
+///
+/// The documents matching several boosts are ranked by the sum of their weights.
+#[derive(Debug, Clone, PartialEq, Deserr, ToSchema, Serialize)]
+#[deserr(error = DeserrJsonError<InvalidSearchBoost>, rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchBoost {
+    #[schema(value_type = Value)]
+    pub filter: Value,
+    pub weight: f64,
+}
+
+/// Reorders the `limit` first hits by the scores of the reranker of the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr, ToSchema, Serialize)]
+#[deserr(error = DeserrJsonError<InvalidSearchRerank>, rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RerankQuery {
+    pub limit: usize,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserr)]
 #[deserr(try_from(f64) = TryFrom::try_from -> InvalidSimilarRankingScoreThreshold)]
 pub struct RankingScoreThresholdSimilar(f64);
@@ -162,6 +232,7 @@ impl fmt::Debug for SearchQuery {
         let Self {
             q,
             vector,
+            media,
             hybrid,
             offset,
             limit,
@@ -176,9 +247,13 @@ impl fmt::Debug for SearchQuery {
             show_ranking_score,
             show_ranking_score_details,
             filter,
+            boost,
+            rerank,
             sort,
             distinct,
             facets,
+            facet_ranges,
+            facet_histogram,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -216,6 +291,10 @@ impl fmt::Debug for SearchQuery {
                 );
             }
         }
+        if let Some(media) = media {
+            // media are usually large base64 strings
+            debug.field("media", &format!("{} bytes", media.to_string().len()));
+        }
         if let Some(hybrid) = hybrid {
             debug.field("hybrid", &hybrid);
         }
@@ -225,6 +304,12 @@ impl fmt::Debug for SearchQuery {
         if let Some(filter) = filter {
             debug.field("filter", &filter);
         }
+        if let Some(boost) = boost {
+            debug.field("boost", &boost);
+        }
+        if let Some(rerank) = rerank {
+            debug.field("rerank", &rerank);
+        }
         if let Some(sort) = sort {
             debug.field("sort", &sort);
         }
@@ -234,6 +319,12 @@ impl fmt::Debug for SearchQuery {
         if let Some(facets) = facets {
             debug.field("facets", &facets);
         }
+        if let Some(facet_ranges) = facet_ranges {
+            debug.field("facet_ranges", &facet_ranges);
+        }
+        if let Some(facet_histogram) = facet_histogram {
+            debug.field("facet_histogram", &facet_histogram);
+        }
         debug.field("matching_strategy", &matching_strategy);
 
         // Then everything related to the formatting
@@ -276,7 +367,7 @@ impl fmt::Debug for SearchQuery {
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Deserr, ToSchema, Serialize)]
-#[deserr(error = DeserrJsonError<InvalidSearchHybridQuery>, rename_all = camelCase, deny_unknown_fields)]
+#[deserr(error = DeserrJsonError<InvalidSearchHybridQuery>, rename_all = camelCase, deny_unknown_fields, validate = validate_hybrid_query -> DeserrJsonError<InvalidSearchRankConstant>)]
 #[serde(rename_all = "camelCase")]
 pub struct HybridQuery {
     #[deserr(default, error = DeserrJsonError<InvalidSearchSemanticRatio>, default)]
@@ -285,13 +376,104 @@ pub struct HybridQuery {
     pub semantic_ratio: SemanticRatio,
     #[deserr(error = DeserrJsonError<InvalidSearchEmbedder>)]
     pub embedder: String,
+    /// How the keyword and semantic results are merged together.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFusion>)]
+    #[schema(default)]
+    #[serde(default, skip_serializing_if = "FusionMethod::is_ratio")]
+    pub fusion: FusionMethod,
+    /// The constant added to the ranks of the documents by the `rrf` fusion.
+    ///
+    /// The higher the constant, the less the top ranked documents weigh. Defaults to `60`.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankConstant>)]
+    #[schema(value_type = Option<u32>, default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rank_constant: Option<u32>,
+    /// The name of a `sparse` embedder whose results are merged with the keyword and semantic results.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSparseEmbedder>)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_embedder: Option<String>,
+    /// The sparse embedding of the query, as a map of token ids to weights.
+    ///
+    /// When absent, the query is embedded by the `sparseEmbedder`.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSparseVector>)]
+    #[schema(value_type = Option<BTreeMap<String, f32>>)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vector: Option<SparseVector>,
+}
+
+impl HybridQuery {
+    pub fn fusion(&self) -> HybridFusion {
+        match self.fusion {
+            FusionMethod::Ratio => HybridFusion::Ratio,
+            FusionMethod::Rrf => HybridFusion::ReciprocalRank {
+                rank_constant: self.rank_constant.unwrap_or(DEFAULT_RANK_CONSTANT),
+            },
+        }
+    }
+}
+
+fn validate_hybrid_query<E: DeserializeError>(
+    query: HybridQuery,
+    location: ValuePointerRef,
+) -> Result<HybridQuery, E> {
+    if query.rank_constant.is_some() && query.fusion != FusionMethod::Rrf {
+        return Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected {
+                msg: "`rankConstant` can only be used with the `rrf` fusion".to_string(),
+            },
+            location,
+        )));
+    }
+    if query.sparse_vector.is_some() && query.sparse_embedder.is_none() {
+        return Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected {
+                msg: "`sparseVector` can only be used with a `sparseEmbedder`".to_string(),
+            },
+            location,
+        )));
+    }
+    Ok(query)
+}
+
+/// The method used to merge the keyword and semantic results of a hybrid search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserr, ToSchema, Serialize)]
+#[deserr(rename_all = camelCase)]
+#[serde(rename_all = "camelCase")]
+pub enum FusionMethod {
+    /// Compare the scores of the documents, weighted by the `semanticRatio`.
+    #[default]
+    Ratio,
+    /// Reciprocal Rank Fusion: rank the documents by the reciprocals of their ranks
+    /// in the keyword and semantic results, weighted by the `semanticRatio`.
+    Rrf,
+}
+
+impl FusionMethod {
+    fn is_ratio(&self) -> bool {
+        matches!(self, FusionMethod::Ratio)
+    }
 }
 
 #[derive(Clone)]
 pub enum SearchKind {
     KeywordOnly,
-    SemanticOnly { embedder_name: String, embedder: Arc<Embedder>, quantized: bool },
-    Hybrid { embedder_name: String, embedder: Arc<Embedder>, quantized: bool, semantic_ratio: f32 },
+    SemanticOnly {
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        quantization: VectorQuantization,
+        distance: VectorDistance,
+    },
+    Hybrid {
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        quantization: VectorQuantization,
+        distance: VectorDistance,
+        semantic_ratio: f32,
+        fusion: HybridFusion,
+        sparse: Option<(String, Arc<Embedder>)>,
+    },
 }
 
 impl SearchKind {
@@ -302,7 +484,7 @@ impl SearchKind {
         embedder_name: &str,
         vector_len: Option<usize>,
     ) -> Result<Self, ResponseError> {
-        let (embedder_name, embedder, quantized) = Self::embedder(
+        let (embedder_name, embedder, quantization, distance) = Self::embedder(
             index_scheduler,
             index_uid,
             index,
@@ -310,7 +492,7 @@ impl SearchKind {
             vector_len,
             Route::Search,
         )?;
-        Ok(Self::SemanticOnly { embedder_name, embedder, quantized })
+        Ok(Self::SemanticOnly { embedder_name, embedder, quantization, distance })
     }
 
     pub(crate) fn hybrid(
@@ -319,9 +501,20 @@ impl SearchKind {
         index: &Index,
         embedder_name: &str,
         semantic_ratio: f32,
+        fusion: HybridFusion,
+        sparse_embedder_name: Option<&str>,
         vector_len: Option<usize>,
     ) -> Result<Self, ResponseError> {
-        let (embedder_name, embedder, quantized) = Self::embedder(
+        let sparse = match sparse_embedder_name {
+            Some(sparse_embedder_name) => Some(Self::sparse_embedder(
+                index_scheduler,
+                index_uid.clone(),
+                index,
+                sparse_embedder_name,
+            )?),
+            None => None,
+        };
+        let (embedder_name, embedder, quantization, distance) = Self::embedder(
             index_scheduler,
             index_uid,
             index,
@@ -329,7 +522,35 @@ impl SearchKind {
             vector_len,
             Route::Search,
         )?;
-        Ok(Self::Hybrid { embedder_name, embedder, quantized, semantic_ratio })
+        Ok(Self::Hybrid {
+            embedder_name,
+            embedder,
+            quantization,
+            distance,
+            semantic_ratio,
+            fusion,
+            sparse,
+        })
+    }
+
+    fn sparse_embedder(
+        index_scheduler: &index_scheduler::IndexScheduler,
+        index_uid: String,
+        index: &Index,
+        embedder_name: &str,
+    ) -> Result<(String, Arc<Embedder>), ResponseError> {
+        let rtxn = index.read_txn()?;
+        let embedder_configs = index.embedding_configs(&rtxn)?;
+        let embedders = index_scheduler.embedders(index_uid, embedder_configs)?;
+
+        let embedder = embedders
+            .get(embedder_name)
+            .map(|(embedder, ..)| embedder)
+            .filter(|embedder| embedder.is_sparse())
+            .ok_or_else(|| milli::UserError::InvalidSearchSparseEmbedder(embedder_name.to_owned()))
+            .map_err(milli::Error::from)?;
+
+        Ok((embedder_name.to_owned(), embedder))
     }
 
     pub(crate) fn embedder(
@@ -339,12 +560,12 @@ impl SearchKind {
         embedder_name: &str,
         vector_len: Option<usize>,
         route: Route,
-    ) -> Result<(String, Arc<Embedder>, bool), ResponseError> {
+    ) -> Result<(String, Arc<Embedder>, VectorQuantization, VectorDistance), ResponseError> {
         let rtxn = index.read_txn()?;
         let embedder_configs = index.embedding_configs(&rtxn)?;
         let embedders = index_scheduler.embedders(index_uid, embedder_configs)?;
 
-        let (embedder, _, quantized) = embedders
+        let (embedder, _, quantization, distance) = embedders
             .get(embedder_name)
             .ok_or(match route {
                 Route::Search | Route::MultiSearch => {
@@ -357,7 +578,13 @@ impl SearchKind {
             .map_err(milli::Error::from)?;
 
         if let Some(vector_len) = vector_len {
-            if vector_len != embedder.dimensions() {
+            // the token embeddings of a late interaction query are packed together
+            let valid_len = if embedder.is_late_interaction() {
+                vector_len != 0 && vector_len % embedder.dimensions() == 0
+            } else {
+                vector_len == embedder.dimensions()
+            };
+            if !valid_len {
                 return Err(meilisearch_types::milli::Error::UserError(
                     meilisearch_types::milli::UserError::InvalidVectorDimensions {
                         expected: embedder.dimensions(),
@@ -368,7 +595,7 @@ impl SearchKind {
             }
         }
 
-        Ok((embedder_name.to_owned(), embedder, quantized))
+        Ok((embedder_name.to_owned(), embedder, quantization, distance))
     }
 }
 
@@ -425,6 +652,9 @@ pub struct SearchQueryWithIndex {
     pub q: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
     pub vector: Option<Vec<f32>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchMedia>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<Value>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchOffset>)]
@@ -453,12 +683,24 @@ pub struct SearchQueryWithIndex {
     pub show_matches_position: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
     pub filter: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchBoost>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boost: Option<Vec<SearchBoost>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRerank>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<RerankQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchSort>)]
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
     pub distinct: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetRanges>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_ranges: Option<BTreeMap<String, Vec<FacetRange>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetHistogram>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_histogram: Option<BTreeMap<String, FacetHistogram>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
@@ -497,6 +739,16 @@ impl SearchQueryWithIndex {
         self.facets.as_deref().filter(|v| !v.is_empty())
     }
 
+    pub fn has_facet_ranges(&self) -> Option<&'static str> {
+        if self.facet_ranges.is_some() {
+            Some("facetRanges")
+        } else if self.facet_histogram.is_some() {
+            Some("facetHistogram")
+        } else {
+            None
+        }
+    }
+
     pub fn from_index_query_federation(
         index_uid: IndexUid,
         query: SearchQuery,
@@ -505,6 +757,7 @@ impl SearchQueryWithIndex {
         let SearchQuery {
             q,
             vector,
+            media,
             hybrid,
             offset,
             limit,
@@ -519,9 +772,13 @@ impl SearchQueryWithIndex {
             show_ranking_score,
             show_ranking_score_details,
             filter,
+            boost,
+            rerank,
             sort,
             distinct,
             facets,
+            facet_ranges,
+            facet_histogram,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -535,6 +792,7 @@ impl SearchQueryWithIndex {
             index_uid,
             q,
             vector,
+            media,
             hybrid,
             offset: if offset == DEFAULT_SEARCH_OFFSET() { None } else { Some(offset) },
             limit: if limit == DEFAULT_SEARCH_LIMIT() { None } else { Some(limit) },
@@ -549,9 +807,13 @@ impl SearchQueryWithIndex {
             show_ranking_score_details,
             show_matches_position,
             filter,
+            boost,
+            rerank,
             sort,
             distinct,
             facets,
+            facet_ranges,
+            facet_histogram,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -569,6 +831,7 @@ impl SearchQueryWithIndex {
             federation_options,
             q,
             vector,
+            media,
             offset,
             limit,
             page,
@@ -582,9 +845,13 @@ impl SearchQueryWithIndex {
             show_ranking_score_details,
             show_matches_position,
             filter,
+            boost,
+            rerank,
             sort,
             distinct,
             facets,
+            facet_ranges,
+            facet_histogram,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -599,6 +866,7 @@ impl SearchQueryWithIndex {
             SearchQuery {
                 q,
                 vector,
+                media,
                 offset: offset.unwrap_or(DEFAULT_SEARCH_OFFSET()),
                 limit: limit.unwrap_or(DEFAULT_SEARCH_LIMIT()),
                 page,
@@ -612,9 +880,13 @@ impl SearchQueryWithIndex {
                 show_ranking_score_details,
                 show_matches_position,
                 filter,
+                boost,
+                rerank,
                 sort,
                 distinct,
                 facets,
+                facet_ranges,
+                facet_histogram,
                 highlight_pre_tag,
                 highlight_post_tag,
                 crop_marker,
@@ -767,6 +1039,10 @@ pub struct SearchResult {
     pub facet_distribution: Option<BTreeMap<String, IndexMap<String, u64>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub facet_stats: Option<BTreeMap<String, FacetStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_ranges: Option<BTreeMap<String, Vec<FacetRangeCount>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_histogram: Option<BTreeMap<String, Vec<FacetRangeCount>>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_hit_count: Option<u32>,
@@ -787,6 +1063,8 @@ impl fmt::Debug for SearchResult {
             hits_info,
             facet_distribution,
             facet_stats,
+            facet_ranges,
+            facet_histogram,
             semantic_hit_count,
             degraded,
             used_negative_operator,
@@ -810,6 +1088,12 @@ impl fmt::Debug for SearchResult {
         if let Some(facet_stats) = facet_stats {
             debug.field("facet_stats", &facet_stats);
         }
+        if let Some(facet_ranges) = facet_ranges {
+            debug.field("facet_ranges", &facet_ranges);
+        }
+        if let Some(facet_histogram) = facet_histogram {
+            debug.field("facet_histogram", &facet_histogram);
+        }
         if let Some(semantic_hit_count) = semantic_hit_count {
             debug.field("semantic_hit_count", &semantic_hit_count);
         }
@@ -854,6 +1138,16 @@ pub struct FacetStats {
     pub max: f64,
 }
 
+/// The number of documents whose facet value is in `from..to`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct FacetRangeCount {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<f64>,
+    pub count: u64,
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FacetSearchResult {
@@ -882,6 +1176,31 @@ pub fn add_search_rules(filter: &mut Option<Value>, rules: IndexSearchRules) {
     }
 }
 
+/// Embeds the query along with its media.
+fn embed_media(
+    embedder: &Embedder,
+    q: Option<&str>,
+    media: &Value,
+) -> Result<Vec<f32>, ResponseError> {
+    if !embedder.supports_media() {
+        return Err(ResponseError::from_msg(
+            "Invalid request: `media` can only be used with an embedder that defines `media`."
+                .to_string(),
+            Code::InvalidSearchMedia,
+        ));
+    }
+    let span = tracing::trace_span!(target: "search::vector", "embed_media");
+    let _entered = span.enter();
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let input = MediaInput::new(q, Some(media.clone())).encode();
+
+    Ok(embedder
+        .embed_search(&input, Some(deadline))
+        .map_err(milli::vector::Error::from)
+        .map_err(milli::Error::from)?)
+}
+
 fn prepare_search<'t>(
     index: &'t Index,
     rtxn: &'t RoTxn,
@@ -906,10 +1225,11 @@ fn prepare_search<'t>(
                 search.query(q);
             }
         }
-        SearchKind::SemanticOnly { embedder_name, embedder, quantized } => {
-            let vector = match query.vector.clone() {
-                Some(vector) => vector,
-                None => {
+        SearchKind::SemanticOnly { embedder_name, embedder, quantization, distance } => {
+            let vector = match (query.vector.clone(), &query.media) {
+                (Some(vector), _) => vector,
+                (None, Some(media)) => embed_media(embedder, query.q.as_deref(), media)?,
+                (None, None) => {
                     let span = tracing::trace_span!(target: "search::vector", "embed_one");
                     let _entered = span.enter();
 
@@ -922,19 +1242,47 @@ fn prepare_search<'t>(
                 }
             };
 
-            search.semantic(embedder_name.clone(), embedder.clone(), *quantized, Some(vector));
+            search.semantic(
+                embedder_name.clone(),
+                embedder.clone(),
+                *quantization,
+                *distance,
+                Some(vector),
+            );
         }
-        SearchKind::Hybrid { embedder_name, embedder, quantized, semantic_ratio: _ } => {
+        SearchKind::Hybrid {
+            embedder_name,
+            embedder,
+            quantization,
+            distance,
+            semantic_ratio: _,
+            fusion: _,
+            sparse,
+        } => {
             if let Some(q) = &query.q {
                 search.query(q);
             }
-            // will be embedded in hybrid search if necessary
+            // the media cannot be embedded by the hybrid search, that only knows about the query
+            let vector = match &query.media {
+                Some(media) => Some(embed_media(embedder, query.q.as_deref(), media)?),
+                // will be embedded in hybrid search if necessary
+                None => query.vector.clone(),
+            };
             search.semantic(
                 embedder_name.clone(),
                 embedder.clone(),
-                *quantized,
-                query.vector.clone(),
+                *quantization,
+                *distance,
+                vector,
             );
+            if let Some((sparse_embedder_name, sparse_embedder)) = sparse {
+                let sparse_vector = query
+                    .hybrid
+                    .as_ref()
+                    .and_then(|hybrid| hybrid.sparse_vector.as_ref())
+                    .map(SparseVector::pack);
+                search.sparse(sparse_embedder_name.clone(), sparse_embedder.clone(), sparse_vector);
+            }
         }
     }
 
@@ -988,6 +1336,27 @@ fn prepare_search<'t>(
         }
     }
 
+    if let Some(ref boosts) = query.boost {
+        let mut search_boosts = Vec::with_capacity(boosts.len());
+        for SearchBoost { filter, weight } in boosts {
+            if let Some(filter) = parse_filter(filter, Code::InvalidSearchBoost, features)? {
+                search_boosts.push(milli::Boost { filter, weight: *weight });
+            }
+        }
+        search.boosts(search_boosts);
+    }
+
+    if let Some(RerankQuery { limit }) = query.rerank {
+        let Some(reranker) = index.reranker(rtxn).map_err(milli::Error::from)? else {
+            return Err(ResponseError::from_msg(
+                "Cannot rerank the hits as no `reranker` is configured for this index.".to_string(),
+                Code::InvalidSearchRerank,
+            ));
+        };
+        let reranker = milli::Reranker::new(reranker).map_err(milli::Error::from)?;
+        search.rerank(Arc::new(reranker), min(limit, max_total_hits));
+    }
+
     if let Some(ref sort) = query.sort {
         let sort = match sort.iter().map(|s| AscDesc::from_str(s)).collect() {
             Ok(sorts) => sorts,
@@ -1052,18 +1421,23 @@ pub fn perform_search(
         show_ranking_score_details,
         sort,
         facets,
+        facet_ranges,
+        facet_histogram,
         highlight_pre_tag,
         highlight_post_tag,
         crop_marker,
         locales,
         // already used in prepare_search
         vector: _,
+        media: _,
         hybrid: _,
         offset: _,
         ranking_score_threshold: _,
         matching_strategy: _,
         attributes_to_search_on: _,
         filter: _,
+        boost: _,
+        rerank: _,
         distinct: _,
     } = query;
 
@@ -1109,6 +1483,13 @@ pub fn perform_search(
         HitsInfo::OffsetLimit { limit, offset, estimated_total_hits: number_of_hits }
     };
 
+    let facet_ranges = facet_ranges
+        .map(|ranges| compute_facet_ranges(&ranges, index, &rtxn, candidates.clone()))
+        .transpose()?;
+    let facet_histogram = facet_histogram
+        .map(|histogram| compute_facet_histogram(&histogram, index, &rtxn, candidates.clone()))
+        .transpose()?;
+
     let (facet_distribution, facet_stats) = facets
         .map(move |facets| {
             compute_facet_distribution_stats(&facets, index, &rtxn, candidates, Route::Search)
@@ -1124,6 +1505,8 @@ pub fn perform_search(
         processing_time_ms: before_search.elapsed().as_millis(),
         facet_distribution,
         facet_stats,
+        facet_ranges,
+        facet_histogram,
         degraded,
         used_negative_operator,
         semantic_hit_count,
@@ -1188,6 +1571,68 @@ fn compute_facet_distribution_stats<S: AsRef<str>>(
     Ok(ComputedFacets { distribution, stats })
 }
 
+fn compute_facet_ranges(
+    ranges: &BTreeMap<String, Vec<FacetRange>>,
+    index: &Index,
+    rtxn: &RoTxn,
+    candidates: roaring::RoaringBitmap,
+) -> Result<BTreeMap<String, Vec<FacetRangeCount>>, ResponseError> {
+    let mut facet_distribution = index.facets_distribution(rtxn);
+    let counts =
+        facet_distribution.candidates(candidates).compute_ranges(ranges).map_err(|error| {
+            match error {
+                error @ milli::Error::UserError(milli::UserError::InvalidFacetsDistribution {
+                    ..
+                }) => ResponseError::from_msg(error.to_string(), Code::InvalidSearchFacetRanges),
+                error => error.into(),
+            }
+        })?;
+    Ok(into_facet_range_counts(counts))
+}
+
+fn compute_facet_histogram(
+    histogram: &BTreeMap<String, FacetHistogram>,
+    index: &Index,
+    rtxn: &RoTxn,
+    candidates: roaring::RoaringBitmap,
+) -> Result<BTreeMap<String, Vec<FacetRangeCount>>, ResponseError> {
+    let mut facet_distribution = index.facets_distribution(rtxn);
+    let max_values_by_facet = index
+        .max_values_per_facet(rtxn)
+        .map_err(milli::Error::from)?
+        .map(|x| x as usize)
+        .unwrap_or(DEFAULT_VALUES_PER_FACET);
+
+    let intervals =
+        histogram.iter().map(|(name, histogram)| (name.clone(), *histogram.interval)).collect();
+    let counts = facet_distribution
+        .max_values_per_facet(max_values_by_facet)
+        .candidates(candidates)
+        .compute_histogram(&intervals)
+        .map_err(|error| match error {
+            error @ milli::Error::UserError(milli::UserError::InvalidFacetsDistribution {
+                ..
+            }) => ResponseError::from_msg(error.to_string(), Code::InvalidSearchFacetHistogram),
+            error => error.into(),
+        })?;
+    Ok(into_facet_range_counts(counts))
+}
+
+fn into_facet_range_counts(
+    counts: BTreeMap<String, Vec<(FacetRange, u64)>>,
+) -> BTreeMap<String, Vec<FacetRangeCount>> {
+    counts
+        .into_iter()
+        .map(|(name, counts)| {
+            let counts = counts
+                .into_iter()
+                .map(|(FacetRange { from, to }, count)| FacetRangeCount { from, to, count })
+                .collect();
+            (name, counts)
+        })
+        .collect()
+}
+
 pub fn search_from_kind(
     index_uid: String,
     search_kind: SearchKind,
@@ -1207,8 +1652,8 @@ pub fn search_from_kind(
             let semantic_hit_count = results.document_scores.len() as u32;
             (results, Some(semantic_hit_count))
         }
-        SearchKind::Hybrid { semantic_ratio, .. } => search
-            .execute_hybrid(*semantic_ratio)
+        SearchKind::Hybrid { semantic_ratio, fusion, .. } => search
+            .execute_hybrid(*semantic_ratio, *fusion)
             .map_err(|e| MeilisearchHttpError::from_milli(e, Some(index_uid)))?,
     };
     Ok((milli_result, semantic_hit_count))
@@ -1432,13 +1877,13 @@ impl<'a> HitMaker<'a> {
                 _ => Default::default(),
             };
             for (name, vector) in self.index.embeddings(self.rtxn, id)? {
-                let user_provided = self
-                    .embedding_configs
-                    .iter()
-                    .find(|conf| conf.name == name)
-                    .is_some_and(|conf| conf.user_provided.contains(id));
-                let embeddings =
-                    ExplicitVectors { embeddings: Some(vector.into()), regenerate: !user_provided };
+                let config = self.embedding_configs.iter().find(|conf| conf.name == name);
+                let user_provided = config.is_some_and(|conf| conf.user_provided.contains(id));
+                let sparse = config.is_some_and(|conf| conf.config.is_sparse());
+                let embeddings = ExplicitVectors {
+                    embeddings: Some(VectorOrArrayOfVectors::from_stored(vector, sparse)),
+                    regenerate: !user_provided,
+                };
                 vectors.insert(
                     name,
                     serde_json::to_value(embeddings).map_err(InternalError::SerdeJson)?,
@@ -1578,7 +2023,8 @@ pub fn perform_similar(
     query: SimilarQuery,
     embedder_name: String,
     embedder: Arc<Embedder>,
-    quantized: bool,
+    quantization: VectorQuantization,
+    distance: VectorDistance,
     retrieve_vectors: RetrieveVectors,
     features: RoFeatures,
 ) -> Result<SimilarResult, ResponseError> {
@@ -1620,7 +2066,8 @@ pub fn perform_similar(
         &rtxn,
         embedder_name,
         embedder,
-        quantized,
+        quantization,
+        distance,
     );
 
     if let Some(ref filter) = query.filter {
@@ -1697,11 +2144,29 @@ pub fn insert_geo_distance(sorts: &[String], document: &mut Document) {
     if let Some(capture_group) = sorts.iter().find_map(|sort| GEO_REGEX.captures(sort)) {
         // TODO: TAMO: milli encountered an internal error, what do we want to do?
         let base = [capture_group[1].parse().unwrap(), capture_group[2].parse().unwrap()];
-        let geo_point = &document.get("_geo").unwrap_or(&json!(null));
-        if let Some((lat, lng)) =
-            extract_geo_value(&geo_point["lat"]).zip(extract_geo_value(&geo_point["lng"]))
-        {
-            let distance = milli::distance_between_two_points(&base, &[lat, lng]);
+        // a document with several points is at the distance of its nearest point
+        let geo_points = match document.get("_geo") {
+            Some(Value::Array(points)) => points.iter().collect(),
+            Some(point) => vec![point],
+            None => Vec::new(),
+        };
+        let geo_distance = geo_points
+            .into_iter()
+            .filter_map(|point| {
+                extract_geo_value(&point["lat"]).zip(extract_geo_value(&point["lng"]))
+            })
+            .map(|(lat, lng)| milli::distance_between_two_points(&base, &[lat, lng]))
+            .reduce(f64::min);
+        // the distance to a `_geojson` geometry is the distance to its nearest point
+        let geojson_distance = document
+            .get("_geojson")
+            .and_then(|geometry| GeoJsonGeometry::from_value(geometry).ok())
+            .map(|geometry| geometry.distance_to(&base));
+        let distance = match (geo_distance, geojson_distance) {
+            (Some(geo), Some(geojson)) => Some(geo.min(geojson)),
+            (distance, None) | (None, distance) => distance,
+        };
+        if let Some(distance) = distance {
             document.insert("_geoDistance".to_string(), json!(distance.round() as usize));
         }
     }
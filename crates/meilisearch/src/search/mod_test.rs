@@ -1,4 +1,4 @@
-use meilisearch_types::Document;
+use meilisearch_types::{milli, Document};
 use serde_json::json;
 
 use crate::search::insert_geo_distance;
@@ -112,3 +112,71 @@ fn test_insert_geo_distance_with_coords_as_string() {
     insert_geo_distance(sorters, &mut document);
     assert_eq!(document.get("_geoDistance"), Some(&json!(0)));
 }
+
+#[test]
+fn test_insert_geo_distance_with_geojson() {
+    let value: Document = serde_json::from_str(
+        r#"{
+          "_geojson": {
+            "type": "Polygon",
+            "coordinates": [[[3.0, 50.0], [3.1, 50.0], [3.1, 50.1], [3.0, 50.1], [3.0, 50.0]]]
+          },
+          "id": "1"
+        }"#,
+    )
+    .unwrap();
+
+    // the point is inside of the polygon
+    let sorters = &["_geoPoint(50.05, 3.05):asc".to_string()];
+    let mut document = value.clone();
+    insert_geo_distance(sorters, &mut document);
+    assert_eq!(document.get("_geoDistance"), Some(&json!(0)));
+
+    // outside of the polygon the distance is computed to the nearest vertex
+    let sorters = &["_geoPoint(50.0, 2.9):asc".to_string()];
+    let mut document = value.clone();
+    insert_geo_distance(sorters, &mut document);
+    let distance = milli::distance_between_two_points(&[50.0, 2.9], &[50.0, 3.0]);
+    assert_eq!(document.get("_geoDistance"), Some(&json!(distance.round() as usize)));
+
+    let value: Document = serde_json::from_str(
+        r#"{
+          "_geo": { "lat": 10, "lng": 10 },
+          "_geojson": { "type": "MultiPoint", "coordinates": [[20, 20], [3, 50]] },
+          "id": "1"
+        }"#,
+    )
+    .unwrap();
+
+    // the nearest point between the `_geo` and `_geojson` fields is used
+    let sorters = &["_geoPoint(50, 3):asc".to_string()];
+    let mut document = value;
+    insert_geo_distance(sorters, &mut document);
+    assert_eq!(document.get("_geoDistance"), Some(&json!(0)));
+}
+
+#[test]
+fn test_insert_geo_distance_with_multiple_points() {
+    let value: Document = serde_json::from_str(
+        r#"{
+          "_geo": [
+            { "lat": 10, "lng": 10 },
+            { "lat": "50", "lng": 3 },
+            { "lat": 48.85, "lng": 2.35 }
+          ],
+          "id": "1"
+        }"#,
+    )
+    .unwrap();
+
+    // the distance to the nearest point is used, whatever the order
+    let sorters = &["_geoPoint(50, 3):asc".to_string()];
+    let mut document = value.clone();
+    insert_geo_distance(sorters, &mut document);
+    assert_eq!(document.get("_geoDistance"), Some(&json!(0)));
+
+    let sorters = &["_geoPoint(10, 10):desc".to_string()];
+    let mut document = value;
+    insert_geo_distance(sorters, &mut document);
+    assert_eq!(document.get("_geoDistance"), Some(&json!(0)));
+}
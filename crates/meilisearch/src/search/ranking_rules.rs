@@ -236,6 +236,7 @@ impl RankingRules {
                         })
                     }
                 }
+                Criterion::Expression(_) => canonical_criteria.push(criterion.clone()),
                 Criterion::Asc(s) | Criterion::Desc(s) => match sorted_fields.entry(s) {
                     std::collections::hash_map::Entry::Occupied(entry) => canonicalization_actions
                         .push(CanonicalizationAction::RemovedDuplicate {
@@ -316,6 +317,7 @@ impl RankingRules {
                         })
                     }
                 }
+                Criterion::Expression(_) => canonical_criteria.push(criterion.clone()),
                 Criterion::Asc(s) | Criterion::Desc(s) => match sorted_fields.entry(s) {
                     std::collections::hash_map::Entry::Occupied(entry) => canonicalization_actions
                         .push(CanonicalizationAction::RemovedDuplicate {
@@ -450,6 +452,7 @@ impl RankingRules {
                         })
                     }
                 }
+                Criterion::Expression(_) => canonical_criteria.push(criterion),
                 Criterion::Asc(s) | Criterion::Desc(s) => match sorted_fields.entry(s) {
                     std::collections::hash_map::Entry::Occupied(entry) => canonicalization_actions
                         .push(CanonicalizationAction::RemovedDuplicate {
@@ -730,6 +733,8 @@ impl RankingRule {
             Criterion::Desc(s) if s == "_geo" => RankingRuleKind::DescendingGeoSort,
 
             Criterion::Desc(_) => RankingRuleKind::DescendingSort,
+            // the higher the value of the expression, the better
+            Criterion::Expression(_) => RankingRuleKind::DescendingSort,
             Criterion::Sort => {
                 return either::Right(sort.iter().flatten().enumerate().map(
                     move |(rule_index, asc_desc)| {
@@ -70,6 +70,8 @@ pub static AUTHORIZATIONS: Lazy<HashMap<(&'static str, &'static str), HashSet<&'
             ("PATCH",   "/experimental-features") =>                           hashset!{"experimental.update", "*"},
             ("GET",   "/network") =>                                           hashset!{"network.get", "*"},
             ("PATCH",   "/network") =>                                         hashset!{"network.update", "*"},
+            ("DELETE",  "/embedding-cache") =>                                 hashset!{"embeddingCache.delete", "*"},
+            ("POST",    "/transactions") =>                                    hashset!{"transactions.create", "*"},
         };
 
         authorizations
@@ -1040,3 +1042,84 @@ async fn error_creating_index_without_index() {
     let (response, code) = index.create(None).await;
     assert_eq!(403, code, "{:?}", &response);
 }
+
+#[actix_rt::test]
+async fn error_transaction_operation_without_action() {
+    let mut server = Server::new_auth().await;
+
+    let operations = [
+        (
+            "documents.add",
+            json!({ "type": "documentAdditionOrUpdate", "indexUid": "products", "documents": [{ "id": 1 }] }),
+        ),
+        (
+            "documents.delete",
+            json!({ "type": "documentDeletion", "indexUid": "products", "documentIds": [1] }),
+        ),
+        (
+            "settings.update",
+            json!({ "type": "settingsUpdate", "indexUid": "products", "settings": { "distinctAttribute": "id" } }),
+        ),
+        ("indexes.swap", json!({ "type": "indexSwap", "indexes": ["products", "doggos"] })),
+    ];
+    let all_actions = [
+        "transactions.create",
+        "documents.add",
+        "documents.delete",
+        "settings.update",
+        "indexes.swap",
+    ];
+
+    for (action, operation) in operations.iter() {
+        // create a key allowed to create transactions, but not to apply this operation.
+        server.use_api_key(MASTER_KEY);
+        let content = json!({
+            "indexes": ["*"],
+            "actions": all_actions.iter().filter(|a| *a != action).collect::<Vec<_>>(),
+            "expiresAt": (OffsetDateTime::now_utc() + Duration::hours(1)).format(&Rfc3339).unwrap(),
+        });
+        let (response, code) = server.add_api_key(content).await;
+        assert_eq!(201, code, "{:?}", &response);
+
+        server.use_api_key(response["key"].as_str().unwrap());
+        let (mut response, code) =
+            server.create_transaction(json!({ "operations": [operation] })).await;
+        response["message"] = serde_json::json!(null);
+        assert_eq!(response, INVALID_RESPONSE.clone(), "on action: {:?}", action);
+        assert_eq!(403, code, "{:?}", &response);
+    }
+
+    // the operations are not registered when one of them is refused.
+    server.use_api_key(MASTER_KEY);
+    let (response, code) = server.tasks().await;
+    assert_eq!(200, code, "{:?}", &response);
+    assert_eq!(response["total"], 0);
+
+    // an operation targeting an index the key can't access is refused as well.
+    let content = json!({
+        "indexes": ["doggos"],
+        "actions": all_actions,
+        "expiresAt": (OffsetDateTime::now_utc() + Duration::hours(1)).format(&Rfc3339).unwrap(),
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    server.use_api_key(response["key"].as_str().unwrap());
+    for (action, operation) in operations.iter() {
+        let (_, code) = server.create_transaction(json!({ "operations": [operation] })).await;
+        assert_eq!(403, code, "on action: {:?}", action);
+    }
+
+    // with every action on every index, the transaction is accepted.
+    server.use_api_key(MASTER_KEY);
+    let content = json!({
+        "indexes": ["*"],
+        "actions": all_actions,
+        "expiresAt": (OffsetDateTime::now_utc() + Duration::hours(1)).format(&Rfc3339).unwrap(),
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    server.use_api_key(response["key"].as_str().unwrap());
+    let operations: Vec<_> = operations.into_iter().map(|(_, operation)| operation).collect();
+    let (response, code) = server.create_transaction(json!({ "operations": operations })).await;
+    assert_eq!(202, code, "{:?}", &response);
+}
@@ -388,6 +388,10 @@ impl<State> Server<State> {
         self.service.post("/swap-indexes", value).await
     }
 
+    pub async fn create_transaction(&self, value: Value) -> (Value, StatusCode) {
+        self.service.post("/transactions", value).await
+    }
+
     pub async fn cancel_tasks(&self, value: &str) -> (Value, StatusCode) {
         self.service.post(format!("/tasks/cancel?{}", value), json!(null)).await
     }
@@ -75,6 +75,7 @@ async fn import_dump_v1_movie_raw() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -99,7 +100,9 @@ async fn import_dump_v1_movie_raw() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -245,6 +248,7 @@ async fn import_dump_v1_movie_with_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -269,7 +273,9 @@ async fn import_dump_v1_movie_with_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -414,6 +420,7 @@ async fn import_dump_v1_rubygems_with_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -438,7 +445,9 @@ async fn import_dump_v1_rubygems_with_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -569,6 +578,7 @@ async fn import_dump_v2_movie_raw() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -593,7 +603,9 @@ async fn import_dump_v2_movie_raw() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -736,6 +748,7 @@ async fn import_dump_v2_movie_with_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -760,7 +773,9 @@ async fn import_dump_v2_movie_with_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -902,6 +917,7 @@ async fn import_dump_v2_rubygems_with_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -926,7 +942,9 @@ async fn import_dump_v2_rubygems_with_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -1057,6 +1075,7 @@ async fn import_dump_v3_movie_raw() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -1081,7 +1100,9 @@ async fn import_dump_v3_movie_raw() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -1224,6 +1245,7 @@ async fn import_dump_v3_movie_with_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -1248,7 +1270,9 @@ async fn import_dump_v3_movie_with_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -1390,6 +1414,7 @@ async fn import_dump_v3_rubygems_with_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -1414,7 +1439,9 @@ async fn import_dump_v3_rubygems_with_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -1545,6 +1572,7 @@ async fn import_dump_v4_movie_raw() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -1569,7 +1597,9 @@ async fn import_dump_v4_movie_raw() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -1712,6 +1742,7 @@ async fn import_dump_v4_movie_with_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -1736,7 +1767,9 @@ async fn import_dump_v4_movie_with_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -1878,6 +1911,7 @@ async fn import_dump_v4_rubygems_with_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -1902,7 +1936,9 @@ async fn import_dump_v4_rubygems_with_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###
     );
@@ -2161,6 +2197,7 @@ async fn import_dump_v6_containing_experimental_features() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byAttribute",
       "typoTolerance": {
@@ -2185,7 +2222,9 @@ async fn import_dump_v6_containing_experimental_features() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###);
 
@@ -2392,6 +2431,7 @@ async fn generate_and_import_dump_containing_vectors() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -2425,7 +2465,9 @@ async fn generate_and_import_dump_containing_vectors() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "###);
 
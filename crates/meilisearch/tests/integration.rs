@@ -15,6 +15,7 @@ mod snapshot;
 mod stats;
 mod swap_indexes;
 mod tasks;
+mod transactions;
 mod upgrade;
 mod vector;
 
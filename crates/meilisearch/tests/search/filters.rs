@@ -120,6 +120,127 @@ async fn search_with_contains_filter() {
     assert_eq!(response["hits"].as_array().unwrap().len(), 2);
 }
 
+#[actix_rt::test]
+async fn search_with_matches_and_like_filter() {
+    let server = Server::new().await;
+    let index = server.index("movies");
+
+    let (task, _code) = index
+        .update_settings(json!({"filterableAttributes": [
+            {"attributePatterns": ["title"], "features": {"filter": {"pattern": true}}},
+            "color"
+        ]}))
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let documents = DOCUMENTS.clone();
+    let (request, _code) = index.add_documents(documents, None).await;
+    index.wait_task(request.uid()).await.succeeded();
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "title MATCHES '(captain|escape) .*'"
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 2);
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "title LIKE '%dragon%'"
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 1);
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "color LIKE 'gr%'"
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Index `movies`: Filter operator `LIKE` is not allowed for the attribute `color`.\n  - Note: allowed operators: OR, AND, NOT, =, !=, IN, <, >, <=, >=, TO, IS EMPTY, IS NULL, EXISTS.\n  - Note: field `color` matched rule #1 in `filterableAttributes`\n  - Hint: enable pattern in rule #1 by modifying the features.filter object\n  - Hint: prepend another rule matching `color` with appropriate filter features before rule #1",
+      "code": "invalid_search_filter",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_filter"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_with_date_facet() {
+    let server = Server::new().await;
+    let index = server.index("events");
+
+    let (task, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": [
+                {"attributePatterns": ["createdAt"], "features": {"date": {"enabled": true, "bucket": "month"}, "filter": {"comparison": true}}}
+            ],
+            "sortableAttributes": ["createdAt"]
+        }))
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let documents = json!([
+        {"id": 1, "createdAt": "2024-01-15T10:00:00Z"},
+        {"id": 2, "createdAt": "2024-01-31T23:30:00-02:00"},
+        {"id": 3, "createdAt": "2024-03-01T00:00:00Z"},
+        {"id": 4, "createdAt": "unknown"},
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "createdAt >= '2024-01-20T00:00:00Z'",
+            "sort": ["createdAt:desc"],
+            "attributesToRetrieve": ["id"]
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 3
+      },
+      {
+        "id": 2
+      }
+    ]
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "createdAt '2024-01-01T00:00:00Z' TO '2024-01-31T23:59:59Z'",
+            "attributesToRetrieve": ["id"]
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      }
+    ]
+    "###);
+
+    let (response, code) = index.search_post(json!({"facets": ["createdAt"]})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "createdAt": {
+        "2024-01": 1,
+        "2024-02": 1,
+        "2024-03": 1,
+        "unknown": 1
+      }
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn search_with_pattern_filter_settings() {
     // Check if the Equality filter works with patterns
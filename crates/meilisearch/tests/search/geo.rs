@@ -121,6 +121,249 @@ async fn geo_bounding_box_with_string_and_number() {
         .await;
 }
 
+#[actix_rt::test]
+async fn geo_polygon() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["_geo"])).await;
+    let (ret, _code) = index.add_documents(documents, None).await;
+    index.wait_task(ret.uid()).await.succeeded();
+
+    // a triangle covering the north of Italy
+    index
+        .search(
+            json!({
+                "filter": "_geoPolygon([47, 6], [47, 14], [43, 10])",
+                "attributesToRetrieve": ["id", "name"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 2,
+                    "name": "La Bella Italia"
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "filter": "_geoPolygon([0, 0], [10, 10], [0, 10], [10, 0])",
+            }),
+            |response, code| {
+                assert_eq!(code, 400, "{}", response);
+                snapshot!(json_string!(response), @r###"
+                {
+                  "message": "The `_geoPolygon` filter expects a simple polygon, but some of its edges intersect each other.\n1:48 _geoPolygon([0, 0], [10, 10], [0, 10], [10, 0])",
+                  "code": "invalid_search_filter",
+                  "type": "invalid_request",
+                  "link": "https://docs.meilisearch.com/errors#invalid_search_filter"
+                }
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn multiple_geo_points() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        {
+            "id": 1,
+            "name": "Boulangerie chain",
+            "_geo": [
+                { "lat": 48.8566, "lng": 2.3522 },
+                { "lat": 45.7640, "lng": 4.8357 },
+                { "lat": 43.2965, "lng": 5.3698 }
+            ]
+        },
+        {
+            "id": 2,
+            "name": "Lyon bakery",
+            "_geo": { "lat": 45.7578, "lng": 4.8320 }
+        }
+    ]);
+    index.update_settings_filterable_attributes(json!(["_geo"])).await;
+    index.update_settings_sortable_attributes(json!(["_geo"])).await;
+    let (ret, _code) = index.add_documents(documents, None).await;
+    index.wait_task(ret.uid()).await.succeeded();
+
+    // a document matches when any of its points matches
+    index
+        .search(
+            json!({
+                "filter": "_geoRadius(43.2965, 5.3698, 1000)",
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    // the documents are ranked by their nearest point
+    index
+        .search(
+            json!({
+                "sort": ["_geoPoint(48.85, 2.35):asc"],
+                "attributesToRetrieve": ["id"],
+                "showRankingScoreDetails": true,
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"], { "[]._geoDistance" => "[distance]", r#"[]._rankingScoreDetails["_geoPoint(48.85, 2.35):asc"].distance"# => "[distance]" }), @r###"
+                [
+                  {
+                    "id": 1,
+                    "_geoDistance": "[distance]",
+                    "_rankingScoreDetails": {
+                      "_geoPoint(48.85, 2.35):asc": {
+                        "order": 0,
+                        "value": {
+                          "lat": 48.8566,
+                          "lng": 2.3522
+                        },
+                        "distance": "[distance]"
+                      }
+                    }
+                  },
+                  {
+                    "id": 2,
+                    "_geoDistance": "[distance]",
+                    "_rankingScoreDetails": {
+                      "_geoPoint(48.85, 2.35):asc": {
+                        "order": 0,
+                        "value": {
+                          "lat": 45.7578,
+                          "lng": 4.832
+                        },
+                        "distance": "[distance]"
+                      }
+                    }
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn geojson_geometries() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        {
+            "id": 1,
+            "name": "Parc de la Tête d'Or",
+            "_geojson": {
+                "type": "Polygon",
+                "coordinates": [[[4.84, 45.77], [4.86, 45.77], [4.86, 45.78], [4.84, 45.78], [4.84, 45.77]]]
+            }
+        },
+        {
+            "id": 2,
+            "name": "Bakeries",
+            "_geojson": {
+                "type": "MultiPoint",
+                "coordinates": [[2.35, 48.85], [4.83, 45.76]]
+            }
+        },
+        {
+            "id": 3,
+            "name": "Eiffel Tower",
+            "_geo": { "lat": 48.8584, "lng": 2.2945 }
+        }
+    ]);
+    index.update_settings_filterable_attributes(json!(["_geo"])).await;
+    index.update_settings_sortable_attributes(json!(["_geo"])).await;
+    let (ret, _code) = index.add_documents(documents, None).await;
+    index.wait_task(ret.uid()).await.succeeded();
+
+    index
+        .search(
+            json!({
+                "filter": "_geoRadius(45.775, 4.85, 3000)",
+                "sort": ["_geoPoint(45.775, 4.85):asc"],
+                "attributesToRetrieve": ["id", "name", "_geojson"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                let hits: Vec<_> = response["hits"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|hit| (hit["id"].clone(), hit["_geoDistance"].clone()))
+                    .collect();
+                snapshot!(format!("{hits:?}"), @r###"[(Number(1), Number(0)), (Number(2), Number(2278))]"###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "filter": "_geoBoundingBox([49, 3], [48, 2])",
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 2
+                  },
+                  {
+                    "id": 3
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn invalid_geojson() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings_filterable_attributes(json!(["_geo"])).await;
+    let (ret, _code) = index
+        .add_documents(
+            json!([{ "id": 1, "_geojson": { "type": "Point", "coordinates": [12] } }]),
+            None,
+        )
+        .await;
+    let task = index.wait_task(ret.uid()).await.failed();
+    snapshot!(task["error"], @r###"
+    {
+      "message": "Index `test`: The `_geojson` field in the document with the id: `\"1\"` is not a valid GeoJSON geometry: expected a position of the form `[longitude, latitude]` with a longitude between -180 and 180 and a latitude between -90 and 90 but instead got `[12]`.",
+      "code": "invalid_document_geo_field",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_document_geo_field"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn bug_4640() {
     // https://github.com/meilisearch/meilisearch/issues/4640
@@ -368,6 +368,72 @@ async fn invalid_semantic_ratio() {
     "###);
 }
 
+#[actix_rt::test]
+async fn rrf_fusion() {
+    let server = Server::new().await;
+    let index = index_with_documents_user_provided(&server, &SIMPLE_SEARCH_DOCUMENTS_VEC).await;
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"embedder": "default", "fusion": "rrf"}, "showRankingScoreDetails": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    let fusions: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| json!({"id": hit["id"], "fusion": hit["_rankingScoreDetails"]["fusion"]}))
+        .collect();
+    snapshot!(json!(fusions), @r###"[{"id":"2","fusion":{"order":0,"keywordRank":1,"semanticRank":2,"rankConstant":60,"score":0.9919354838709679}},{"id":"3","fusion":{"order":0,"keywordRank":2,"semanticRank":1,"rankConstant":60,"score":0.9919354838709679}},{"id":"1","fusion":{"order":0,"keywordRank":3,"semanticRank":3,"rankConstant":60,"score":0.9682539682539681}}]"###);
+    snapshot!(response["semanticHitCount"], @"1");
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"embedder": "default", "fusion": "rrf", "rankConstant": 0, "semanticRatio": 0.8}, "showRankingScore": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3","_rankingScore":0.9000000059604645},{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_rankingScore":0.5999999940395355},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1","_rankingScore":0.3333333333333333}]"###);
+    snapshot!(response["semanticHitCount"], @"1");
+}
+
+#[actix_rt::test]
+async fn invalid_fusion() {
+    let server = Server::new().await;
+    let index = index_with_documents_user_provided(&server, &SIMPLE_SEARCH_DOCUMENTS_VEC).await;
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"embedder": "default", "fusion": "doggo"}}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Unknown value `doggo` at `.hybrid.fusion`: expected one of `ratio`, `rrf`",
+      "code": "invalid_search_fusion",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_fusion"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"embedder": "default", "rankConstant": 60}}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value at `.hybrid`: `rankConstant` can only be used with the `rrf` fusion",
+      "code": "invalid_search_rank_constant",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rank_constant"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn single_document() {
     let server = Server::new().await;
@@ -1335,3 +1335,93 @@ async fn german_search() {
         )
         .await;
 }
+
+#[actix_rt::test]
+async fn synonym_sets_scoped_by_locale() {
+    let server = Server::new().await;
+
+    let index = server.index("test");
+    let documents = json!([
+      {"id": 1, "title_fra": "Le portable est cassé"},
+      {"id": 2, "title_eng": "My mobile is broken"},
+    ]);
+    index.add_documents(documents, None).await;
+    let (response, _) = index
+        .update_settings(json!({
+            "localizedAttributes": [
+                {"attributePatterns": ["*_fra"], "locales": ["fra"]},
+                {"attributePatterns": ["*_eng"], "locales": ["eng"]}
+            ],
+            "synonymSets": [
+                {"rules": ["téléphone => portable"], "locales": ["fra"]},
+                {"rules": ["phone, cellphone => mobile"], "locales": ["eng"]}
+            ]
+        }))
+        .await;
+    index.wait_task(response.uid()).await.succeeded();
+
+    // the french rules apply to the french queries
+    index
+        .search(
+            json!({"q": "téléphone", "locales": ["fra"], "attributesToRetrieve": ["id"]}),
+            |response, code| {
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  }
+                ]
+                "###);
+                snapshot!(code, @"200 OK");
+            },
+        )
+        .await;
+
+    // but not to the english ones
+    index
+        .search(
+            json!({"q": "téléphone", "locales": ["eng"], "attributesToRetrieve": ["id"]}),
+            |response, code| {
+                snapshot!(json_string!(response["hits"]), @"[]");
+                snapshot!(code, @"200 OK");
+            },
+        )
+        .await;
+
+    // without locales, the rules of the locales of the localized attributes apply
+    index
+        .search(json!({"q": "cellphone", "attributesToRetrieve": ["id"]}), |response, code| {
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 2
+              }
+            ]
+            "###);
+            snapshot!(code, @"200 OK");
+        })
+        .await;
+
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["synonymSets"]), @r###"
+    [
+      {
+        "rules": [
+          "téléphone => portable"
+        ],
+        "locales": [
+          "fra"
+        ]
+      },
+      {
+        "rules": [
+          "phone, cellphone => mobile"
+        ],
+        "locales": [
+          "eng"
+        ]
+      }
+    ]
+    "###);
+}
@@ -720,6 +720,264 @@ async fn search_is_hard_limited() {
         .await;
 }
 
+#[actix_rt::test]
+async fn search_facet_ranges_and_histogram() {
+    let index = shared_index_with_nested_documents().await;
+    index
+        .search(
+            json!({
+                "facetRanges": {"doggos.age": [{"to": 5}, {"from": 5, "to": 7}, {"from": 7}]},
+                "facetHistogram": {"doggos.age": {"interval": 3}}
+            }),
+            |response, code| {
+                snapshot!(code, @"200 OK");
+                snapshot!(json_string!(response["facetRanges"]), @r###"
+                {
+                  "doggos.age": [
+                    {
+                      "to": 5.0,
+                      "count": 1
+                    },
+                    {
+                      "from": 5.0,
+                      "to": 7.0,
+                      "count": 1
+                    },
+                    {
+                      "from": 7.0,
+                      "count": 1
+                    }
+                  ]
+                }
+                "###);
+                snapshot!(json_string!(response["facetHistogram"]), @r###"
+                {
+                  "doggos.age": [
+                    {
+                      "from": 0.0,
+                      "to": 3.0,
+                      "count": 1
+                    },
+                    {
+                      "from": 3.0,
+                      "to": 6.0,
+                      "count": 2
+                    },
+                    {
+                      "from": 6.0,
+                      "to": 9.0,
+                      "count": 2
+                    }
+                  ]
+                }
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(json!({"facetHistogram": {"doggos.age": {"interval": 0}}}), |response, code| {
+            snapshot!(code, @"400 Bad Request");
+            snapshot!(json_string!(response), @r###"
+            {
+              "message": "Invalid value at `.facetHistogram.doggos.age.interval`: the value of `interval` is invalid, expected a strictly positive number.",
+              "code": "invalid_search_facet_histogram",
+              "type": "invalid_request",
+              "link": "https://docs.meilisearch.com/errors#invalid_search_facet_histogram"
+            }
+            "###);
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_boost() {
+    let index = shared_index_with_documents().await;
+    index
+        .search(
+            json!({
+                "boost": [{"filter": "id = 450465", "weight": 2}, {"filter": "id = 522681", "weight": 1}],
+                "attributesToRetrieve": ["id"],
+                "showRankingScoreDetails": true,
+                "limit": 3
+            }),
+            |response, code| {
+                snapshot!(code, @"200 OK");
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": "450465",
+                    "_rankingScoreDetails": {
+                      "boost": {
+                        "order": 0,
+                        "weight": 2.0
+                      }
+                    }
+                  },
+                  {
+                    "id": "522681",
+                    "_rankingScoreDetails": {
+                      "boost": {
+                        "order": 0,
+                        "weight": 1.0
+                      }
+                    }
+                  },
+                  {
+                    "id": "287947",
+                    "_rankingScoreDetails": {
+                      "boost": {
+                        "order": 0,
+                        "weight": 0.0
+                      }
+                    }
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(json!({"boost": [{"filter": "title = ", "weight": 1}]}), |response, code| {
+            snapshot!(code, @"400 Bad Request");
+            snapshot!(json_string!(response["code"]), @r###""invalid_search_boost""###);
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_pinning() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "title": "red shoes", "color": "red" },
+        { "id": 2, "title": "red shoes", "color": "red" },
+        { "id": 3, "title": "blue shoes", "color": "blue" },
+        { "id": 4, "title": "red hat", "color": "red" },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (task, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["color"],
+            "pinning": [{ "query": "red shoes", "pin": [{ "id": "4", "position": 0 }], "hide": ["2"] }]
+        }))
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    index
+        .search(
+            json!({ "q": "Red  Shoes", "attributesToRetrieve": ["id"], "showRankingScoreDetails": true }),
+            |response, code| {
+                snapshot!(code, @"200 OK");
+                snapshot!(json_string!(response["hits"][0]), @r###"
+                {
+                  "id": 4,
+                  "_rankingScoreDetails": {
+                    "pinned": {
+                      "order": 0,
+                      "position": 0
+                    }
+                  }
+                }
+                "###);
+                snapshot!(json_string!(response["hits"][1]["id"]), @"1");
+                snapshot!(json_string!(response["hits"].as_array().unwrap().len()), @"2");
+                snapshot!(json_string!(response["estimatedTotalHits"]), @"2");
+            },
+        )
+        .await;
+
+    // the pinned document must match the filter
+    index
+        .search(
+            json!({ "q": "red shoes", "filter": "color = blue", "attributesToRetrieve": ["id"] }),
+            |response, code| {
+                snapshot!(code, @"200 OK");
+                snapshot!(json_string!(response["hits"]), @"[]");
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn hybrid_search_with_pinning() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _code) = index
+        .update_settings(json!({
+            "embedders": { "default": { "source": "userProvided", "dimensions": 2 } },
+            "pinning": [{ "query": "red shoes", "pin": [{ "id": "4", "position": 0 }], "hide": ["2"] }]
+        }))
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "title": "red shoes", "_vectors": { "default": [0.5, 0.5] } },
+        { "id": 2, "title": "red shoes", "_vectors": { "default": [1.0, 0.0] } },
+        { "id": 3, "title": "blue shoes", "_vectors": { "default": [0.0, 1.0] } },
+        { "id": 4, "title": "red hat", "_vectors": { "default": [-1.0, 0.0] } },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the hidden document is the closest one to the vector, it must not be returned by the semantic search
+    for semantic_ratio in [0.5, 1.0] {
+        index
+            .search(
+                json!({
+                    "q": "red shoes",
+                    "vector": [1.0, 0.0],
+                    "hybrid": { "semanticRatio": semantic_ratio, "embedder": "default" },
+                    "attributesToRetrieve": ["id"],
+                }),
+                |response, code| {
+                    snapshot!(code, @"200 OK");
+                    snapshot!(json_string!(response["hits"]), @r###"
+                    [
+                      {
+                        "id": 4
+                      },
+                      {
+                        "id": 1
+                      },
+                      {
+                        "id": 3
+                      }
+                    ]
+                    "###);
+                },
+            )
+            .await;
+    }
+
+    // the pinned document is inserted before the pagination
+    index
+        .search(
+            json!({
+                "q": "red shoes",
+                "vector": [1.0, 0.0],
+                "hybrid": { "semanticRatio": 1.0, "embedder": "default" },
+                "attributesToRetrieve": ["id"],
+                "offset": 1,
+                "limit": 1,
+            }),
+            |response, code| {
+                snapshot!(code, @"200 OK");
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+}
+
 #[actix_rt::test]
 async fn faceting_max_values_per_facet() {
     let server = Server::new().await;
@@ -4439,6 +4439,57 @@ async fn federation_vector_two_indexes() {
     "###);
 }
 
+#[actix_rt::test]
+async fn federation_rrf_and_keyword_queries() {
+    let server = Server::new().await;
+
+    let index = server.index("movies");
+    let (value, _) = index
+        .update_settings(json!({"embedders": {
+          "default": {
+            "source": "userProvided",
+            "dimensions": 2
+          },
+        }}))
+        .await;
+    index.wait_task(value.uid()).await.succeeded();
+    let (value, code) = index
+        .add_documents(
+            json!([
+              { "id": "1", "title": "Shazam!", "desc": "a Captain Marvel ersatz", "_vectors": { "default": [1.0, 3.0] } },
+              { "id": "2", "title": "Captain Planet", "desc": "He's not part of the Marvel Cinematic Universe", "_vectors": { "default": [1.0, 2.0] } },
+              { "id": "3", "title": "Captain Marvel", "desc": "a Shazam ersatz", "_vectors": { "default": [2.0, 3.0] } },
+            ]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    let index = server.index("titles");
+    let (value, code) = index.add_documents(json!([{ "id": "a", "title": "Captain" }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    // the fused scores of the rrf query are compared with the ranking scores of the keyword query:
+    // 0.9919 for the first two hits, 0.9683 for the last one, against 0.98 for the exact match
+    let (response, code) = server
+        .multi_search(json!({"federation": {}, "queries": [
+        {"indexUid": "movies", "q": "Captain", "vector": [1.0, 1.0], "hybrid": {"embedder": "default", "fusion": "rrf"}},
+        {"indexUid": "titles", "q": "Captain", "federationOptions": {"weight": 0.98}},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| json!({"id": hit["id"], "queriesPosition": hit["_federation"]["queriesPosition"]}))
+        .collect();
+    snapshot!(json!(hits), @r###"[{"id":"2","queriesPosition":0},{"id":"3","queriesPosition":0},{"id":"a","queriesPosition":1},{"id":"1","queriesPosition":0}]"###);
+    snapshot!(response["semanticHitCount"], @"1");
+}
+
 #[actix_rt::test]
 async fn federation_facets_different_indexes_same_facet() {
     let server = Server::new().await;
@@ -141,6 +141,31 @@ async fn settings_bad_ranking_rules() {
       "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
     }
     "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "rankingRules": ["words", "expression:log(1 + sales"] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRules[1]`: `log(1 + sales` is not a valid ranking expression: unexpected end of expression.",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
+
+    let (response, code) =
+        index.update_settings(json!({ "rankingRules": ["expression:rating * sin(sales)"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRules[0]`: `rating * sin(sales)` is not a valid ranking expression: unknown function `sin`. Available functions are log, log10, log2, exp, sqrt, abs, floor, ceil, min, max and pow.",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
 }
 
 #[actix_rt::test]
@@ -199,6 +224,38 @@ async fn settings_bad_synonyms() {
     "###);
 }
 
+#[actix_rt::test]
+async fn settings_bad_synonym_sets() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index
+        .update_settings(json!({ "synonymSets": [{ "rules": ["tv => telly => telly"] }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.synonymSets[0]`: The synonym rule `tv => telly => telly` is invalid: a rule can contain at most one `=>`.",
+      "code": "invalid_settings_synonym_sets",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_synonym_sets"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "synonymSets": [{ "rules": ["tv"], "locales": ["fra"] }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.synonymSets[0]`: The synonym rule `tv` is invalid: an equivalence group must contain at least two terms.",
+      "code": "invalid_settings_synonym_sets",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_synonym_sets"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn settings_bad_distinct_attribute() {
     let server = Server::new_shared();
@@ -365,3 +422,51 @@ async fn settings_bad_search_cutoff_ms() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn settings_bad_pinning() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index.update_settings(json!({ "pinning": [{ "pin": [] }] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `query` inside `.pinning[0]`",
+      "code": "invalid_settings_pinning",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_pinning"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "pinning": [{ "query": "doggo", "pin": [{ "id": "1" }] }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `position` inside `.pinning[0].pin[0]`",
+      "code": "invalid_settings_pinning",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_pinning"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_reranker() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) =
+        index.update_settings(json!({ "reranker": { "request": {}, "response": {} } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `url` inside `.reranker`",
+      "code": "invalid_settings_reranker",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_reranker"
+    }
+    "###);
+}
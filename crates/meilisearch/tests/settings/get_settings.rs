@@ -136,6 +136,11 @@ test_setting_routes!(
         update_verb: put,
         default_value: {}
     },
+    {
+        setting: synonym_sets,
+        update_verb: put,
+        default_value: []
+    },
     {
         setting: pagination,
         update_verb: patch,
@@ -166,6 +171,16 @@ test_setting_routes!(
         update_verb: put,
         default_value: "indexingTime"
     },
+    {
+        setting: pinning,
+        update_verb: put,
+        default_value: []
+    },
+    {
+        setting: reranker,
+        update_verb: put,
+        default_value: null
+    },
     {
         setting: proximity_precision,
         update_verb: put,
@@ -199,7 +214,7 @@ async fn get_settings() {
     let (response, code) = index.settings().await;
     assert_eq!(code, 200);
     let settings = response.as_object().unwrap();
-    assert_eq!(settings.keys().len(), 20);
+    assert_eq!(settings.keys().len(), 23);
     assert_eq!(settings["displayedAttributes"], json!(["*"]));
     assert_eq!(settings["searchableAttributes"], json!(["*"]));
     assert_eq!(settings["filterableAttributes"], json!([]));
@@ -213,6 +228,7 @@ async fn get_settings() {
     assert_eq!(settings["nonSeparatorTokens"], json!([]));
     assert_eq!(settings["separatorTokens"], json!([]));
     assert_eq!(settings["dictionary"], json!([]));
+    assert_eq!(settings["synonymSets"], json!([]));
     assert_eq!(
         settings["faceting"],
         json!({
@@ -232,6 +248,8 @@ async fn get_settings() {
     assert_eq!(settings["searchCutoffMs"], json!(null));
     assert_eq!(settings["prefixSearch"], json!("indexingTime"));
     assert_eq!(settings["facetSearch"], json!(true));
+    assert_eq!(settings["pinning"], json!([]));
+    assert_eq!(settings["reranker"], json!(null));
     assert_eq!(settings["embedders"], json!({}));
 }
 
@@ -299,6 +317,7 @@ async fn secrets_are_hidden_in_settings() {
       "separatorTokens": [],
       "dictionary": [],
       "synonyms": {},
+      "synonymSets": [],
       "distinctAttribute": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
@@ -335,7 +354,9 @@ async fn secrets_are_hidden_in_settings() {
       "searchCutoffMs": null,
       "localizedAttributes": null,
       "facetSearch": true,
-      "prefixSearch": "indexingTime"
+      "prefixSearch": "indexingTime",
+      "pinning": [],
+      "reranker": null
     }
     "#);
 
@@ -545,7 +566,12 @@ async fn granular_filterable_attributes() {
           "facetSearch": true,
           "filter": {
             "equality": true,
-            "comparison": false
+            "comparison": false,
+            "pattern": false
+          },
+          "date": {
+            "enabled": false,
+            "bucket": "day"
           }
         }
       },
@@ -557,7 +583,12 @@ async fn granular_filterable_attributes() {
           "facetSearch": false,
           "filter": {
             "equality": true,
-            "comparison": true
+            "comparison": true,
+            "pattern": false
+          },
+          "date": {
+            "enabled": false,
+            "bucket": "day"
           }
         }
       },
@@ -569,7 +600,12 @@ async fn granular_filterable_attributes() {
           "facetSearch": false,
           "filter": {
             "equality": true,
-            "comparison": false
+            "comparison": false,
+            "pattern": false
+          },
+          "date": {
+            "enabled": false,
+            "bucket": "day"
           }
         }
       },
@@ -581,7 +617,12 @@ async fn granular_filterable_attributes() {
           "facetSearch": true,
           "filter": {
             "equality": true,
-            "comparison": false
+            "comparison": false,
+            "pattern": false
+          },
+          "date": {
+            "enabled": false,
+            "bucket": "day"
           }
         }
       },
@@ -593,7 +634,12 @@ async fn granular_filterable_attributes() {
           "facetSearch": true,
           "filter": {
             "equality": true,
-            "comparison": true
+            "comparison": true,
+            "pattern": false
+          },
+          "date": {
+            "enabled": false,
+            "bucket": "day"
           }
         }
       },
@@ -605,7 +651,12 @@ async fn granular_filterable_attributes() {
           "facetSearch": true,
           "filter": {
             "equality": true,
-            "comparison": false
+            "comparison": false,
+            "pattern": false
+          },
+          "date": {
+            "enabled": false,
+            "bucket": "day"
           }
         }
       },
@@ -617,7 +668,12 @@ async fn granular_filterable_attributes() {
           "facetSearch": true,
           "filter": {
             "equality": true,
-            "comparison": false
+            "comparison": false,
+            "pattern": false
+          },
+          "date": {
+            "enabled": false,
+            "bucket": "day"
           }
         }
       },
@@ -629,7 +685,12 @@ async fn granular_filterable_attributes() {
           "facetSearch": false,
           "filter": {
             "equality": true,
-            "comparison": true
+            "comparison": true,
+            "pattern": false
+          },
+          "date": {
+            "enabled": false,
+            "bucket": "day"
           }
         }
       }
@@ -110,7 +110,7 @@ async fn add_remove_embeddings() {
     index.wait_task(response.uid()).await.succeeded();
 
     let (stats, _code) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".embedderStorageSizes.*" => "[size]" }), @r###"
     {
       "numberOfDocuments": 2,
       "rawDocumentDbSize": 27,
@@ -118,6 +118,10 @@ async fn add_remove_embeddings() {
       "isIndexing": false,
       "numberOfEmbeddings": 5,
       "numberOfEmbeddedDocuments": 2,
+      "embedderStorageSizes": {
+        "handcrafted": "[size]",
+        "manual": "[size]"
+      },
       "fieldDistribution": {
         "id": 2,
         "name": 2
@@ -135,7 +139,7 @@ async fn add_remove_embeddings() {
     index.wait_task(response.uid()).await.succeeded();
 
     let (stats, _code) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".embedderStorageSizes.*" => "[size]" }), @r###"
     {
       "numberOfDocuments": 2,
       "rawDocumentDbSize": 27,
@@ -143,6 +147,10 @@ async fn add_remove_embeddings() {
       "isIndexing": false,
       "numberOfEmbeddings": 3,
       "numberOfEmbeddedDocuments": 2,
+      "embedderStorageSizes": {
+        "handcrafted": "[size]",
+        "manual": "[size]"
+      },
       "fieldDistribution": {
         "id": 2,
         "name": 2
@@ -160,7 +168,7 @@ async fn add_remove_embeddings() {
     index.wait_task(response.uid()).await.succeeded();
 
     let (stats, _code) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".embedderStorageSizes.*" => "[size]" }), @r###"
     {
       "numberOfDocuments": 2,
       "rawDocumentDbSize": 27,
@@ -168,6 +176,10 @@ async fn add_remove_embeddings() {
       "isIndexing": false,
       "numberOfEmbeddings": 2,
       "numberOfEmbeddedDocuments": 2,
+      "embedderStorageSizes": {
+        "handcrafted": "[size]",
+        "manual": "[size]"
+      },
       "fieldDistribution": {
         "id": 2,
         "name": 2
@@ -186,7 +198,7 @@ async fn add_remove_embeddings() {
     index.wait_task(response.uid()).await.succeeded();
 
     let (stats, _code) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".embedderStorageSizes.*" => "[size]" }), @r###"
     {
       "numberOfDocuments": 2,
       "rawDocumentDbSize": 27,
@@ -194,6 +206,10 @@ async fn add_remove_embeddings() {
       "isIndexing": false,
       "numberOfEmbeddings": 2,
       "numberOfEmbeddedDocuments": 1,
+      "embedderStorageSizes": {
+        "handcrafted": "[size]",
+        "manual": "[size]"
+      },
       "fieldDistribution": {
         "id": 2,
         "name": 2
@@ -236,7 +252,7 @@ async fn add_remove_embedded_documents() {
     index.wait_task(response.uid()).await.succeeded();
 
     let (stats, _code) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".embedderStorageSizes.*" => "[size]" }), @r###"
     {
       "numberOfDocuments": 2,
       "rawDocumentDbSize": 27,
@@ -244,6 +260,10 @@ async fn add_remove_embedded_documents() {
       "isIndexing": false,
       "numberOfEmbeddings": 5,
       "numberOfEmbeddedDocuments": 2,
+      "embedderStorageSizes": {
+        "handcrafted": "[size]",
+        "manual": "[size]"
+      },
       "fieldDistribution": {
         "id": 2,
         "name": 2
@@ -257,7 +277,7 @@ async fn add_remove_embedded_documents() {
     index.wait_task(response.uid()).await.succeeded();
 
     let (stats, _code) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".embedderStorageSizes.*" => "[size]" }), @r###"
     {
       "numberOfDocuments": 1,
       "rawDocumentDbSize": 13,
@@ -265,6 +285,10 @@ async fn add_remove_embedded_documents() {
       "isIndexing": false,
       "numberOfEmbeddings": 3,
       "numberOfEmbeddedDocuments": 1,
+      "embedderStorageSizes": {
+        "handcrafted": "[size]",
+        "manual": "[size]"
+      },
       "fieldDistribution": {
         "id": 1,
         "name": 1
@@ -326,7 +350,7 @@ async fn update_embedder_settings() {
     server.wait_task(response.uid()).await.succeeded();
 
     let (stats, _code) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".embedderStorageSizes.*" => "[size]" }), @r###"
     {
       "numberOfDocuments": 2,
       "rawDocumentDbSize": 108,
@@ -334,6 +358,10 @@ async fn update_embedder_settings() {
       "isIndexing": false,
       "numberOfEmbeddings": 3,
       "numberOfEmbeddedDocuments": 2,
+      "embedderStorageSizes": {
+        "handcrafted": "[size]",
+        "manual": "[size]"
+      },
       "fieldDistribution": {
         "id": 2,
         "name": 2
@@ -2,11 +2,11 @@ mod errors;
 mod webhook;
 
 use meili_snap::insta::assert_json_snapshot;
-use meili_snap::snapshot;
+use meili_snap::{json_string, snapshot};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
-use crate::common::Server;
+use crate::common::{Server, Value};
 use crate::json;
 
 #[actix_rt::test]
@@ -1063,3 +1063,140 @@ async fn test_summarized_dump_creation() {
     }
     "###);
 }
+
+#[actix_web::test]
+async fn idempotency_key_and_custom_metadata() {
+    let server = Server::new().await;
+    let add_documents = |documents: Value| {
+        actix_web::test::TestRequest::post()
+            .uri("/indexes/doggos/documents")
+            .insert_header(("Idempotency-Key", "import-doggos"))
+            .insert_header(("X-Meili-Task-Custom-Metadata", "nightly-import"))
+            .set_json(documents)
+    };
+
+    let (response, code) = server.service.request(add_documents(json!([{ "id": 1 }]))).await;
+    snapshot!(code, @"202 Accepted");
+    snapshot!(response["taskUid"], @"0");
+    server.wait_task(response.uid()).await.succeeded();
+
+    // A request submitted again with the same key returns the original task.
+    let (response, code) = server.service.request(add_documents(json!([{ "id": 2 }]))).await;
+    snapshot!(code, @"202 Accepted");
+    snapshot!(response["taskUid"], @"0");
+
+    let (response, code) = server.index("doggos").add_documents(json!([{ "id": 3 }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (task, _) = server.get_task(0).await;
+    snapshot!(task["customMetadata"], @r###""nightly-import""###);
+    snapshot!(task["idempotencyKey"], @r###""import-doggos""###);
+
+    let (response, code) = server.tasks_filter("customMetadata=nightly-import").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["results"].as_array().unwrap().len(), @"1");
+    let (response, code) = server.tasks_filter("idempotencyKeys=import-doggos,unknown").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["results"][0]["uid"], @"0");
+    snapshot!(response["total"], @"1");
+}
+
+#[actix_web::test]
+async fn idempotency_key_reused_for_another_request() {
+    let server = Server::new().await;
+    let add_documents = |index: &str| {
+        actix_web::test::TestRequest::post()
+            .uri(&format!("/indexes/{index}/documents"))
+            .insert_header(("Idempotency-Key", "import"))
+            .set_json(json!([{ "id": 1 }]))
+    };
+
+    let (response, code) = server.service.request(add_documents("doggos")).await;
+    snapshot!(code, @"202 Accepted");
+    snapshot!(response["taskUid"], @"0");
+
+    // The key is scoped by index, it can be used again on another one.
+    let (response, code) = server.service.request(add_documents("catto")).await;
+    snapshot!(code, @"202 Accepted");
+    snapshot!(response["taskUid"], @"1");
+
+    let request = actix_web::test::TestRequest::delete()
+        .uri("/indexes/doggos/documents")
+        .insert_header(("Idempotency-Key", "import"));
+    let (response, code) = server.service.request(request).await;
+    snapshot!(code, @"409 Conflict");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The idempotency key `import` was already used to register the task `0` of another type or on other indexes.",
+      "code": "idempotency_key_already_used",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#idempotency_key_already_used"
+    }
+    "###);
+}
+
+#[actix_web::test]
+async fn idempotency_key_on_long_index_uid() {
+    let server = Server::new().await;
+    let index_uid = "a".repeat(400);
+    let add_documents = |idempotency_key: String| {
+        actix_web::test::TestRequest::post()
+            .uri(&format!("/indexes/{index_uid}/documents"))
+            .insert_header(("Idempotency-Key", idempotency_key))
+            .set_json(json!([{ "id": 1 }]))
+    };
+
+    // The key and its scope must fit in an LMDB key, a 511-byte key is refused upfront.
+    let (response, code) = server.service.request(add_documents("k".repeat(511))).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["message"], @r###""Idempotency-Key must contain between 1 and 446 bytes but contains 511 bytes""###);
+
+    let (response, code) = server.service.request(add_documents("k".repeat(446))).await;
+    snapshot!(code, @"202 Accepted");
+    let uid = response.uid();
+    server.wait_task(uid).await.succeeded();
+
+    let (response, code) = server.service.request(add_documents("k".repeat(446))).await;
+    snapshot!(code, @"202 Accepted");
+    assert_eq!(response.uid(), uid);
+}
+
+#[actix_web::test]
+async fn run_after_delays_the_tasks_of_the_index() {
+    let server = Server::new().await;
+
+    let request = actix_web::test::TestRequest::post()
+        .uri("/indexes/doggos/documents")
+        .insert_header(("X-Meili-Task-Run-After", "2999-01-01T00:00:00Z"))
+        .set_json(json!([{ "id": 1 }]));
+    let (response, code) = server.service.request(request).await;
+    snapshot!(code, @"202 Accepted");
+    let scheduled = response.uid();
+
+    // The tasks enqueued after the scheduled task on its index wait for it, the other indexes don't.
+    let (response, code) = server.index("doggos").add_documents(json!([{ "id": 2 }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    let blocked = response.uid();
+    let (response, code) = server.index("catto").add_documents(json!([{ "id": 1 }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (task, _) = server.get_task(scheduled).await;
+    snapshot!(task["status"], @r###""enqueued""###);
+    snapshot!(task["runAfter"], @r###""2999-01-01T00:00:00Z""###);
+    let (task, _) = server.get_task(blocked).await;
+    snapshot!(task["status"], @r###""enqueued""###);
+
+    let (response, code) = server.tasks_filter("afterRunAfter=2998-12-31T00:00:00Z").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["total"], @"1");
+
+    let request = actix_web::test::TestRequest::post()
+        .uri("/indexes/doggos/documents")
+        .insert_header(("X-Meili-Task-Run-After", "tomorrow"))
+        .set_json(json!([{ "id": 3 }]));
+    let (response, code) = server.service.request(request).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""bad_request""###);
+}
@@ -0,0 +1,100 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{GetAllDocumentsOptions, Server};
+use crate::json;
+
+#[actix_rt::test]
+async fn transaction_on_several_indexes() {
+    let server = Server::new().await;
+
+    let (res, code) = server
+        .create_transaction(json!({
+            "operations": [
+                { "type": "documentAdditionOrUpdate", "indexUid": "a", "documents": [{ "id": 1, "index": "a" }] },
+                { "type": "documentAdditionOrUpdate", "indexUid": "b", "documents": [{ "id": 1, "index": "b" }] },
+                { "type": "settingsUpdate", "indexUid": "b", "settings": { "filterableAttributes": ["index"] } },
+            ]
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(res.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+    snapshot!(task["type"], @r###""transaction""###);
+    snapshot!(json_string!(task["details"]), @r###"
+    {
+      "operations": [
+        {
+          "indexUid": "a",
+          "type": "documentAdditionOrUpdate",
+          "details": {
+            "receivedDocuments": 1,
+            "indexedDocuments": 1
+          }
+        },
+        {
+          "indexUid": "b",
+          "type": "documentAdditionOrUpdate",
+          "details": {
+            "receivedDocuments": 1,
+            "indexedDocuments": 1
+          }
+        },
+        {
+          "indexUid": "b",
+          "type": "settingsUpdate",
+          "details": {
+            "filterableAttributes": [
+              "index"
+            ]
+          }
+        }
+      ]
+    }
+    "###);
+
+    let (documents, code) =
+        server.index("a").get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(documents["total"], @"1");
+    let (documents, code) =
+        server.index("b").get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(documents["total"], @"1");
+}
+
+#[actix_rt::test]
+async fn failed_transaction_changes_nothing() {
+    let server = Server::new().await;
+
+    let (res, code) = server
+        .create_transaction(json!({
+            "operations": [
+                { "type": "documentAdditionOrUpdate", "indexUid": "a", "documents": [{ "id": 1 }] },
+                { "type": "documentDeletion", "indexUid": "b", "documentIds": [1] },
+            ]
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(res.uid()).await;
+    snapshot!(task["status"], @r###""failed""###);
+    snapshot!(task["error"]["code"], @r###""index_not_found""###);
+
+    let (_, code) = server.index("a").get().await;
+    snapshot!(code, @"404 Not Found");
+}
+
+#[actix_rt::test]
+async fn empty_transaction() {
+    let server = Server::new_shared();
+
+    let (response, code) = server.create_transaction(json!({ "operations": [] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "A transaction must contain at least one operation.",
+      "code": "invalid_transaction_operations",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_transaction_operations"
+    }
+    "###);
+}
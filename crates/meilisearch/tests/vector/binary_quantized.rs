@@ -287,6 +287,57 @@ async fn try_to_disable_binary_quantization() {
     "#);
 }
 
+#[actix_rt::test]
+async fn try_to_binary_quantize_dot_product() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+                  "binaryQuantized": true,
+                  "distance": "dotProduct",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret, @r#"
+    {
+      "uid": "[uid]",
+      "batchUid": "[batch_uid]",
+      "indexUid": "doggo",
+      "status": "failed",
+      "type": "settingsUpdate",
+      "canceledBy": null,
+      "details": {
+        "embedders": {
+          "manual": {
+            "source": "userProvided",
+            "dimensions": 3,
+            "binaryQuantized": true,
+            "distance": "dotProduct"
+          }
+        }
+      },
+      "error": {
+        "message": "Index `doggo`: `.embedders.manual`: Cannot use the `binary` quantization with the `dotProduct` distance.\n - Hint: Use the `cosine` or `euclidean` distance, or disable the quantization.",
+        "code": "invalid_settings_embedders",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+      },
+      "duration": "[duration]",
+      "enqueuedAt": "[date]",
+      "startedAt": "[date]",
+      "finishedAt": "[date]"
+    }
+    "#);
+}
+
 #[actix_rt::test]
 async fn binary_quantize_clear_documents() {
     let server = Server::new().await;
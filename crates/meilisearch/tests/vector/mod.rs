@@ -4,6 +4,7 @@ mod ollama;
 mod openai;
 mod rest;
 mod settings;
+mod sparse;
 
 use std::str::FromStr;
 
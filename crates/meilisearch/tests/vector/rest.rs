@@ -1,11 +1,12 @@
 use std::collections::BTreeMap;
 
 use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
 use reqwest::IntoUrl;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, Request, ResponseTemplate};
 
-use crate::common::Value;
+use crate::common::{default_settings, Server, Value};
 use crate::json;
 use crate::vector::{get_server_vector, GetAllDocumentsOptions};
 
@@ -2111,3 +2112,602 @@ async fn searchable_reindex() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn chunking() {
+    let mock_server = MockServer::start().await;
+
+    let text_to_embedding: BTreeMap<_, _> = vec![
+        // text -> embedding
+        ("kefir", [1.0, 0.0, 0.0]),
+        ("intel", [0.0, 1.0, 0.0]),
+    ]
+    .into_iter()
+    .collect();
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(move |req: &Request| {
+            let text: String = req.body_json().unwrap();
+            ResponseTemplate::new(200).set_body_json(
+                json!({ "data": text_to_embedding.get(text.as_str()).unwrap_or(&[0., 0., 1.]) }),
+            )
+        })
+        .mount(&mock_server)
+        .await;
+
+    let server = get_server_vector().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": mock_server.uri(),
+                  "dimensions": 3,
+                  "request": "{{text}}",
+                  "response": { "data": "{{embedding}}" },
+                  "documentTemplate": "{{doc.name}}",
+                  "chunking": { "strategy": "tokens", "size": 1, "overlap": 1 },
+              },
+          },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.rest.chunking.overlap`: `overlap` must be smaller than `size`, got an overlap of 1 for a size of 1",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": mock_server.uri(),
+                  "dimensions": 3,
+                  "request": "{{text}}",
+                  "response": { "data": "{{embedding}}" },
+                  "documentTemplate": "{{doc.name}}",
+                  "chunking": { "strategy": "tokens", "size": 1 },
+              },
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+
+    let documents = json!([
+      {"id": 0, "name": "kefir intel"},
+      {"id": 1, "name": "echo"},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(value.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+
+    // each chunk of the document is embedded
+    let (document, _code) = index.get_document(0, Some(json!({"retrieveVectors": true}))).await;
+    snapshot!(json_string!(document["_vectors"]), @r###"
+    {
+      "rest": {
+        "embeddings": [
+          [
+            1.0,
+            0.0,
+            0.0
+          ],
+          [
+            0.0,
+            1.0,
+            0.0
+          ]
+        ],
+        "regenerate": true
+      }
+    }
+    "###);
+
+    // the document is ranked by its best chunk
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [0.0, 1.0, 0.0],
+            "hybrid": {"embedder": "rest", "semanticRatio": 1.0},
+            "showRankingScoreDetails": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let chunks: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| json!({"id": hit["id"], "chunk": hit["_rankingScoreDetails"]["vectorSort"]["chunk"]}))
+        .collect();
+    snapshot!(json!(chunks), @r###"[{"id":0,"chunk":1},{"id":1,"chunk":0}]"###);
+}
+
+#[actix_rt::test]
+async fn reranker() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/rerank"))
+        .respond_with(move |req: &Request| {
+            let body: Value = req.body_json().unwrap();
+            assert_eq!(body["query"], json!("kefir"));
+            // the longest documents get the best scores
+            let results: Vec<_> = body["documents"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|document| json!({ "score": document.as_str().unwrap().len() as f64 / 100.0 }))
+                .collect();
+            ResponseTemplate::new(200).set_body_json(json!({ "results": results }))
+        })
+        .mount(&mock_server)
+        .await;
+
+    let server = get_server_vector().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index.search_post(json!({"q": "kefir", "rerank": {"limit": 3}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Cannot rerank the hits as no `reranker` is configured for this index.",
+      "code": "invalid_search_rerank",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rerank"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "reranker": {
+              "url": format!("{}/rerank", mock_server.uri()),
+              "request": { "query": "{{query}}", "documents": ["{{text}}", "{{..}}"] },
+              "response": { "results": { "score": "{{score}}" } },
+              "documentTemplate": "{{doc.name}}",
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response.uid()).await;
+    snapshot!(task["status"], @r###""failed""###);
+    snapshot!(task["error"]["message"], @r###""`.reranker.response`: Expected \"{{score}}\" to be repeated with \"{{..}}\", as several documents are scored at once""###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "reranker": {
+              "url": format!("{}/rerank", mock_server.uri()),
+              "request": { "query": "{{query}}", "documents": ["{{text}}", "{{..}}"] },
+              "response": { "results": [{ "score": "{{score}}" }, "{{..}}"] },
+              "documentTemplate": "{{doc.name}}",
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+
+    let documents = json!([
+      {"id": 0, "name": "kefir"},
+      {"id": 1, "name": "intel kefir"},
+      {"id": 2, "name": "echo kefir"},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(value.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir",
+            "rerank": {"limit": 3},
+            "showRankingScoreDetails": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| json!({"id": hit["id"], "rerank": hit["_rankingScoreDetails"]["rerank"]}))
+        .collect();
+    snapshot!(json_string!(hits), @r###"
+    [
+      {
+        "id": 1,
+        "rerank": {
+          "order": 0,
+          "rank": 1,
+          "score": 0.11
+        }
+      },
+      {
+        "id": 2,
+        "rerank": {
+          "order": 0,
+          "rank": 2,
+          "score": 0.1
+        }
+      },
+      {
+        "id": 0,
+        "rerank": {
+          "order": 0,
+          "rank": 3,
+          "score": 0.05
+        }
+      }
+    ]
+    "###);
+
+    // the hits are reranked before the pagination
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir",
+            "rerank": {"limit": 3},
+            "offset": 1,
+            "limit": 1,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2,
+        "name": "echo kefir"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn persistent_embedding_cache() {
+    let (mock, setting) = create_mock().await;
+    let dir = tempfile::tempdir().unwrap();
+    let options = Opt {
+        experimental_persistent_embedding_cache_entries: 100,
+        ..default_settings(dir.path())
+    };
+    let server = Server::new_with_options(options).await.unwrap();
+    let documents = json!([
+      {"id": 0, "name": "kefir"},
+      {"id": 1, "name": "intel"},
+    ]);
+
+    for uid in ["doggo", "doggo2"] {
+        let index = server.index(uid);
+        let (response, code) = index.update_settings(json!({"embedders": {"rest": setting}})).await;
+        snapshot!(code, @"202 Accepted");
+        server.wait_task(response.uid()).await.succeeded();
+        let (response, code) = index.add_documents(documents.clone(), None).await;
+        snapshot!(code, @"202 Accepted");
+        server.wait_task(response.uid()).await.succeeded();
+    }
+
+    // the documents of the second index and the query are embedded from the cache
+    let (response, code) = server
+        .index("doggo2")
+        .search_post(json!({"q": "kefir", "hybrid": {"embedder": "rest", "semanticRatio": 1.0}}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["semanticHitCount"], @"2");
+    snapshot!(mock.received_requests().await.unwrap().len(), @"2");
+
+    let (response, code) = server.service.delete("/embedding-cache").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    {
+      "deletedEntries": 2
+    }
+    "###);
+
+    let index = server.index("doggo3");
+    let (response, code) = index.update_settings(json!({"embedders": {"rest": setting}})).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+    let (response, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+    snapshot!(mock.received_requests().await.unwrap().len(), @"4");
+}
+
+#[actix_rt::test]
+async fn multimodal() {
+    let mock_server = MockServer::start().await;
+
+    let media_to_embedding: BTreeMap<_, _> = vec![
+        // base64 media -> embedding
+        ("aGVsbG8=", [1.0, 0.0, 0.0]),
+        ("d29ybGQ=", [0.0, 1.0, 0.0]),
+    ]
+    .into_iter()
+    .collect();
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(move |req: &Request| {
+            let body: Value = req.body_json().unwrap();
+            let media = body["input"]["image"].as_str().unwrap_or_default();
+            ResponseTemplate::new(200).set_body_json(
+                json!({ "data": media_to_embedding.get(media).unwrap_or(&[0., 0., 1.]) }),
+            )
+        })
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let media_dir = tempfile::tempdir().unwrap();
+    std::fs::write(media_dir.path().join("doggo.png"), "world").unwrap();
+    let options = Opt {
+        experimental_embedding_media_path: Some(media_dir.path().to_owned()),
+        ..default_settings(dir.path())
+    };
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("doggo");
+
+    let mut setting = json!({
+        "source": "rest",
+        "url": mock_server.uri(),
+        "dimensions": 3,
+        "request": { "input": "{{text}}" },
+        "response": { "data": "{{embedding}}" },
+        "documentTemplate": "{{doc.name}}",
+        "media": {
+            "documentField": "image",
+            "indexingFragment": { "text": "{{text}}", "image": "{{media}}" },
+        },
+        "chunking": { "strategy": "tokens", "size": 1 },
+    });
+    let (response, code) = index.update_settings(json!({"embedders": {"rest": setting}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.rest.media`: `media` cannot be used along with `chunking`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    setting.as_object_mut().unwrap().remove("chunking");
+    let (response, code) = index.update_settings(json!({"embedders": {"rest": setting}})).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    // the chunking cannot be added afterward either
+    let chunking =
+        json!({"embedders": {"rest": {"chunking": { "strategy": "tokens", "size": 1 }}}});
+    let (response, code) = index.update_settings(chunking).await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response.uid()).await;
+    snapshot!(task["status"], @r###""failed""###);
+    snapshot!(task["error"]["message"], @r###""`.embedders.rest.media`: `media` cannot be used along with `chunking`""###);
+
+    let documents = json!([
+      {"id": 0, "name": "kefir", "image": "aGVsbG8="},
+      {"id": 1, "name": "intel", "image": {"path": "doggo.png"}},
+      {"id": 2, "name": "echo"},
+    ]);
+    let (response, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    // the rendered template and the media are injected in the fragment
+    let mut inputs: Vec<_> = mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|req| req.body_json::<Value>().unwrap()["input"].clone())
+        .collect();
+    inputs.sort_by_key(|input| input.to_string());
+    snapshot!(json_string!(inputs), @r###"
+    [
+      {
+        "text": "echo",
+        "image": null
+      },
+      {
+        "text": "intel",
+        "image": "d29ybGQ="
+      },
+      {
+        "text": "kefir",
+        "image": "aGVsbG8="
+      }
+    ]
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "media": "d29ybGQ=",
+            "hybrid": {"embedder": "rest", "semanticRatio": 1.0},
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"][0]["id"], @"1");
+
+    let (response, code) = index.search_post(json!({"media": "d29ybGQ="})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid request: missing `hybrid` parameter when `media` is present.",
+      "code": "missing_search_hybrid",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#missing_search_hybrid"
+    }
+    "###);
+
+    // a document referencing a file outside of the media directory fails to be indexed
+    let documents = json!([{"id": 3, "name": "escape", "image": {"path": "../doggo.png"}}]);
+    let (response, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response.uid()).await;
+    snapshot!(task["status"], @r###""failed""###);
+}
+
+#[actix_rt::test]
+async fn media_without_multimodal_embedder() {
+    let (_mock, setting) = create_mock().await;
+    let server = get_server_vector().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index.update_settings(json!({"embedders": {"rest": setting}})).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index
+        .search_post(json!({
+            "media": "aGVsbG8=",
+            "hybrid": {"embedder": "rest", "semanticRatio": 1.0},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid request: `media` can only be used with an embedder that defines `media`.",
+      "code": "invalid_search_media",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_media"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn late_interaction() {
+    let mock_server = MockServer::start().await;
+
+    let token_to_embedding: BTreeMap<_, _> = vec![
+        // token -> embedding
+        ("kefir", [1.0, 0.0, 0.0]),
+        ("intel", [0.0, 1.0, 0.0]),
+        ("echo", [0.0, 0.0, 1.0]),
+    ]
+    .into_iter()
+    .collect();
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(move |req: &Request| {
+            let text: String = req.body_json().unwrap();
+            let embeddings: Vec<_> = text
+                .split_whitespace()
+                .map(|token| token_to_embedding.get(token).unwrap_or(&[1., 1., 1.]))
+                .collect();
+            ResponseTemplate::new(200).set_body_json(json!({ "data": embeddings }))
+        })
+        .mount(&mock_server)
+        .await;
+
+    let server = get_server_vector().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": mock_server.uri(),
+                  "request": "{{text}}",
+                  "response": { "data": "{{embedding}}" },
+                  "documentTemplate": "{{doc.name}}",
+                  "lateInteraction": true,
+              },
+          },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.rest`: Missing field `dimensions`.\n  - note: `dimensions` must be set when `lateInteraction` is `true`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": mock_server.uri(),
+                  "dimensions": 3,
+                  "request": "{{text}}",
+                  "response": { "data": "{{embedding}}" },
+                  "documentTemplate": "{{doc.name}}",
+                  "lateInteraction": true,
+              },
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "kefir intel"},
+      {"id": 1, "name": "echo"},
+      {"id": 2, "name": "kefir echo"},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    // each token of the document is embedded
+    let (document, _code) = index.get_document(0, Some(json!({"retrieveVectors": true}))).await;
+    snapshot!(json_string!(document["_vectors"]), @r###"
+    {
+      "rest": {
+        "embeddings": [
+          [
+            1.0,
+            0.0,
+            0.0
+          ],
+          [
+            0.0,
+            1.0,
+            0.0
+          ]
+        ],
+        "regenerate": true
+      }
+    }
+    "###);
+
+    // each token of the query is matched with the most similar token of the documents
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir intel",
+            "hybrid": {"embedder": "rest", "semanticRatio": 1.0},
+            "showRankingScore": true,
+            "attributesToRetrieve": ["name"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "name": "kefir intel",
+        "_rankingScore": 1.0
+      },
+      {
+        "name": "kefir echo",
+        "_rankingScore": 0.75
+      },
+      {
+        "name": "echo",
+        "_rankingScore": 0.5
+      }
+    ]
+    "###);
+}
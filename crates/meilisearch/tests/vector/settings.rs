@@ -17,7 +17,7 @@ async fn field_unavailable_for_source() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(response, @r###"
     {
-      "message": "`.embedders.manual`: Field `documentTemplate` unavailable for source `userProvided`.\n  - note: `documentTemplate` is available for sources: `openAi`, `huggingFace`, `ollama`, `rest`\n  - note: available fields for source `userProvided`: `source`, `dimensions`, `distribution`, `binaryQuantized`",
+      "message": "`.embedders.manual`: Field `documentTemplate` unavailable for source `userProvided`.\n  - note: `documentTemplate` is available for sources: `openAi`, `huggingFace`, `ollama`, `rest`, `sparse`, `onnx`\n  - note: available fields for source `userProvided`: `source`, `dimensions`, `distribution`, `binaryQuantized`, `quantization`, `distance`",
       "code": "invalid_settings_embedders",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
@@ -32,7 +32,7 @@ async fn field_unavailable_for_source() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(response, @r###"
     {
-      "message": "`.embedders.default`: Field `revision` unavailable for source `openAi`.\n  - note: `revision` is available for sources: `huggingFace`\n  - note: available fields for source `openAi`: `source`, `model`, `apiKey`, `dimensions`, `documentTemplate`, `documentTemplateMaxBytes`, `url`, `distribution`, `binaryQuantized`",
+      "message": "`.embedders.default`: Field `revision` unavailable for source `openAi`.\n  - note: `revision` is available for sources: `huggingFace`\n  - note: available fields for source `openAi`: `source`, `model`, `apiKey`, `dimensions`, `documentTemplate`, `documentTemplateMaxBytes`, `chunking`, `url`, `distribution`, `binaryQuantized`, `quantization`, `distance`",
       "code": "invalid_settings_embedders",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
@@ -91,6 +91,101 @@ async fn update_embedder() {
     "###);
 }
 
+#[actix_rt::test]
+async fn update_embedder_distance() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "manual": { "source": "userProvided", "dimensions": 2 } },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+        { "id": "same", "_vectors": { "manual": [1.0, 0.0] } },
+        { "id": "long", "_vectors": { "manual": [3.0, 0.5] } },
+        { "id": "orthogonal", "_vectors": { "manual": [0.0, 1.0] } },
+    ]);
+    let (response, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let query = json!({
+        "vector": [1.0, 0.0],
+        "hybrid": { "semanticRatio": 1.0, "embedder": "manual" },
+        "attributesToRetrieve": ["id"],
+    });
+
+    let (response, code) = index.search_post(query.clone()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "same"
+      },
+      {
+        "id": "long"
+      },
+      {
+        "id": "orthogonal"
+      }
+    ]
+    "###);
+
+    // the inner product favors the longest embeddings
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "manual": { "source": "userProvided", "dimensions": 2, "distance": "dotProduct" } },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(query.clone()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "long"
+      },
+      {
+        "id": "same"
+      },
+      {
+        "id": "orthogonal"
+      }
+    ]
+    "###);
+
+    // the euclidean distance favors the closest embeddings, regardless of their direction
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "manual": { "source": "userProvided", "dimensions": 2, "distance": "euclidean" } },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(query).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "same"
+      },
+      {
+        "id": "orthogonal"
+      },
+      {
+        "id": "long"
+      }
+    ]
+    "###);
+}
+
 #[actix_rt::test]
 async fn reset_embedder_documents() {
     let server = Server::new().await;
@@ -1066,3 +1161,39 @@ async fn composite_checks() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn onnx_checks() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "onnx": { "source": "onnx" } },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.onnx`: Missing field `model` (note: this field is mandatory for source `onnx`)",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "onnx": { "source": "onnx", "model": "./all-MiniLM-L6-v2", "revision": "main" } },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.onnx`: Field `revision` unavailable for source `onnx`.\n  - note: `revision` is available for sources: `huggingFace`\n  - note: available fields for source `onnx`: `source`, `model`, `pooling`, `documentTemplate`, `documentTemplateMaxBytes`, `chunking`, `distribution`, `binaryQuantized`, `quantization`, `distance`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
@@ -0,0 +1,206 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{GetAllDocumentsOptions, Server};
+use crate::json;
+
+#[actix_rt::test]
+async fn user_provided_sparse_vectors() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+              },
+              "sparse": {
+                  "source": "sparse",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "kefir", "_vectors": { "manual": [0, 1, 0], "sparse": { "1": 1.0, "2": 0.25 } }},
+      {"id": 1, "name": "echo", "_vectors": { "manual": [1, 0, 0], "sparse": { "3": 1.0 } }},
+      {"id": 2, "name": "intel", "_vectors": { "manual": [1, 1, 0], "sparse": { "2": 1.0 } }},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    let (documents, _code) = index
+        .get_all_documents(GetAllDocumentsOptions { retrieve_vectors: true, ..Default::default() })
+        .await;
+    snapshot!(json_string!(documents["results"][0]), @r###"
+    {
+      "id": 0,
+      "name": "kefir",
+      "_vectors": {
+        "manual": {
+          "embeddings": [
+            [
+              0.0,
+              1.0,
+              0.0
+            ]
+          ],
+          "regenerate": false
+        },
+        "sparse": {
+          "embeddings": {
+            "1": 1.0,
+            "2": 0.25
+          },
+          "regenerate": false
+        }
+      }
+    }
+    "###);
+
+    // without the sparse results, `echo` is ranked before `intel` by the semantic results
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir",
+            "vector": [1, 0, 0],
+            "hybrid": {"embedder": "manual", "semanticRatio": 0.5, "fusion": "rrf"},
+            "attributesToRetrieve": ["name"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "name": "kefir"
+      },
+      {
+        "name": "echo"
+      },
+      {
+        "name": "intel"
+      }
+    ]
+    "###);
+
+    // `intel` is the best match of the sparse vector
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir",
+            "vector": [1, 0, 0],
+            "hybrid": {
+                "embedder": "manual",
+                "semanticRatio": 0.5,
+                "fusion": "rrf",
+                "sparseEmbedder": "sparse",
+                "sparseVector": { "2": 1.0 },
+            },
+            "attributesToRetrieve": ["name"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "name": "kefir"
+      },
+      {
+        "name": "intel"
+      },
+      {
+        "name": "echo"
+      }
+    ]
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir",
+            "hybrid": {"embedder": "manual", "sparseEmbedder": "manual"},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Cannot find sparse embedder with name `manual`.",
+      "code": "invalid_search_sparse_embedder",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_sparse_embedder"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn sparse_settings_errors() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "sparse": {
+                  "source": "sparse",
+                  "url": "http://localhost:7700/sparse",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.sparse`: Missing field `request`.\n  - note: `url`, `request` and `response` must be set together for source `sparse`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "sparse": {
+                  "source": "sparse",
+                  "dimensions": 3,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.sparse`: Field `dimensions` unavailable for source `sparse`.\n  - note: `dimensions` is available for sources: `openAi`, `ollama`, `userProvided`, `rest`\n  - note: available fields for source `sparse`: `source`, `apiKey`, `documentTemplate`, `documentTemplateMaxBytes`, `chunking`, `url`, `request`, `response`, `headers`, `distribution`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "sparse": {
+                  "source": "sparse",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "sparse": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response.uid()).await.failed();
+    snapshot!(task["error"]["message"], @r###""`.embedders.sparse`: Cannot change the source from `sparse` to `userProvided`.\n - Hint: Add a new embedder that uses the `userProvided` source and remove this one.""###);
+}
@@ -593,14 +593,15 @@ fn export_documents(
                         };
 
                         for (embedder_name, embeddings) in embeddings {
-                            let user_provided = embedding_configs
-                                .iter()
-                                .find(|conf| conf.name == embedder_name)
-                                .is_some_and(|conf| conf.user_provided.contains(id));
+                            let config =
+                                embedding_configs.iter().find(|conf| conf.name == embedder_name);
+                            let user_provided =
+                                config.is_some_and(|conf| conf.user_provided.contains(id));
+                            let sparse = config.is_some_and(|conf| conf.config.is_sparse());
 
                             let embeddings = ExplicitVectors {
-                                embeddings: Some(VectorOrArrayOfVectors::from_array_of_vectors(
-                                    embeddings,
+                                embeddings: Some(VectorOrArrayOfVectors::from_stored(
+                                    embeddings, sparse,
                                 )),
                                 regenerate: !user_provided,
                             };
@@ -4,6 +4,7 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::ranking_expression::{ExpressionError, RankingExpression};
 use crate::{AscDesc, Member};
 
 #[derive(Error, Debug)]
@@ -22,6 +23,8 @@ pub enum CriterionError {
 `{name}` can only be used for filtering at search time"
     )]
     ReservedNameForFilter { name: String },
+    #[error("`{expression}` is not a valid ranking expression: {error}")]
+    InvalidExpression { expression: String, error: ExpressionError },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -45,6 +48,11 @@ pub enum Criterion {
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
     Desc(String),
+    /// Sorted by the decreasing value of a numeric expression computed from the fields of the documents.
+    ///
+    /// The expression is computed for every document of the buckets returned by the previous
+    /// ranking rules, it is cheaper when placed after the rules that split the candidates the most.
+    Expression(String),
 }
 
 impl Criterion {
@@ -55,6 +63,15 @@ impl Criterion {
             _otherwise => None,
         }
     }
+
+    /// Returns the parsed expression of an expression criterion.
+    pub fn expression(&self) -> Option<RankingExpression> {
+        match self {
+            // the expression has been validated when the criterion was parsed
+            Criterion::Expression(expression) => RankingExpression::parse(expression).ok(),
+            _otherwise => None,
+        }
+    }
 }
 
 impl FromStr for Criterion {
@@ -68,6 +85,16 @@ impl FromStr for Criterion {
             "attribute" => Ok(Criterion::Attribute),
             "sort" => Ok(Criterion::Sort),
             "exactness" => Ok(Criterion::Exactness),
+            text if text.starts_with("expression:") => {
+                let expression = text["expression:".len()..].trim();
+                match RankingExpression::parse(expression) {
+                    Ok(_) => Ok(Criterion::Expression(expression.to_string())),
+                    Err(error) => Err(CriterionError::InvalidExpression {
+                        expression: expression.to_string(),
+                        error,
+                    }),
+                }
+            }
             text => match AscDesc::from_str(text)? {
                 AscDesc::Asc(Member::Field(field)) => Ok(Criterion::Asc(field)),
                 AscDesc::Desc(Member::Field(field)) => Ok(Criterion::Desc(field)),
@@ -103,6 +130,7 @@ impl fmt::Display for Criterion {
             Exactness => f.write_str("exactness"),
             Asc(attr) => write!(f, "{}:asc", attr),
             Desc(attr) => write!(f, "{}:desc", attr),
+            Expression(expression) => write!(f, "expression:{}", expression),
         }
     }
 }
@@ -130,6 +158,11 @@ mod tests {
             ("truc:machin:desc", Criterion::Desc(S("truc:machin"))),
             ("hello-world!:desc", Criterion::Desc(S("hello-world!"))),
             ("it's spacy over there:asc", Criterion::Asc(S("it's spacy over there"))),
+            ("expression:rating", Criterion::Expression(S("rating"))),
+            (
+                "expression: log(1 + sales) * 0.7 + rating * 0.3",
+                Criterion::Expression(S("log(1 + sales) * 0.7 + rating * 0.3")),
+            ),
         ];
 
         for (input, expected) in valid_criteria {
@@ -165,6 +198,21 @@ mod tests {
                 "_geoBoundingBox([42, 75], [75, 59]):asc",
                 ReservedNameForFilter { name: S("_geoBoundingBox") },
             ),
+            ("expression:", InvalidExpression { expression: S(""), error: ExpressionError::Empty }),
+            (
+                "expression:rating *",
+                InvalidExpression {
+                    expression: S("rating *"),
+                    error: ExpressionError::UnexpectedEnd,
+                },
+            ),
+            (
+                "expression:sin(rating)",
+                InvalidExpression {
+                    expression: S("sin(rating)"),
+                    error: ExpressionError::UnknownFunction { name: S("sin") },
+                },
+            ),
         ];
 
         for (input, expected) in invalid_criteria {
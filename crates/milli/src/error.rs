@@ -15,6 +15,7 @@ use crate::constants::RESERVED_GEO_FIELD_NAME;
 use crate::documents::{self, DocumentsBatchCursorError};
 use crate::thread_pool_no_abort::PanicCatched;
 use crate::vector::settings::EmbeddingSettings;
+use crate::vector::{VectorDistance, VectorQuantization};
 use crate::{CriterionError, DocumentId, FieldId, Object, SortError};
 
 pub fn is_reserved_keyword(keyword: &str) -> bool {
@@ -166,6 +167,7 @@ and can not be more than 511 bytes.", .document_id.to_string()
         allowed_operators.join(", "),
         if operator == "=" || operator == "!=" || operator == "IN" {"equality"}
         else if operator == "<" || operator == ">" || operator == "<=" || operator == ">=" || operator == "TO" {"comparison"}
+        else if operator == "MATCHES" || operator == "LIKE" {"pattern"}
         else {"the appropriate filter operators"}
     )]
     FilterOperatorNotAllowed {
@@ -271,6 +273,8 @@ and can not be more than 511 bytes.", .document_id.to_string()
     UnknownInternalDocumentId { document_id: DocumentId },
     #[error("`minWordSizeForTypos` setting is invalid. `oneTypo` and `twoTypos` fields should be between `0` and `255`, and `twoTypos` should be greater or equals to `oneTypo` but found `oneTypo: {0}` and twoTypos: {1}`.")]
     InvalidMinTypoWordLenSetting(u8, u8),
+    #[error("The synonym rule `{rule}` is invalid: {error}")]
+    InvalidSynonymRule { rule: String, error: crate::synonyms::SynonymRuleError },
     #[error(transparent)]
     VectorEmbeddingError(#[from] crate::vector::Error),
     #[error(transparent)]
@@ -283,6 +287,8 @@ and can not be more than 511 bytes.", .document_id.to_string()
     TooManyEmbedders(usize),
     #[error("Cannot find embedder with name `{0}`.")]
     InvalidSearchEmbedder(String),
+    #[error("Cannot find sparse embedder with name `{0}`.")]
+    InvalidSearchSparseEmbedder(String),
     #[error("Cannot find embedder with name `{0}`.")]
     InvalidSimilarEmbedder(String),
     #[error("Too many vectors for document with id {0}: found {1}, but limited to 256.")]
@@ -367,8 +373,32 @@ and can not be more than 511 bytes.", .document_id.to_string()
         "`.embedders.{embedder_name}.binaryQuantized`: Cannot disable the binary quantization.\n - Note: Binary quantization is a lossy operation that cannot be reverted.\n - Hint: Add a new embedder that is non-quantized and regenerate the vectors."
     )]
     InvalidDisableBinaryQuantization { embedder_name: String },
+    #[error(
+        "`.embedders.{embedder_name}.quantization`: Cannot change the quantization from `{old_quantization}` to `{new_quantization}`.\n - Note: Quantization is a lossy operation that cannot be reverted.\n - Hint: Add a new embedder that uses the `{new_quantization}` quantization and regenerate the vectors."
+    )]
+    InvalidQuantizationChange {
+        embedder_name: String,
+        old_quantization: VectorQuantization,
+        new_quantization: VectorQuantization,
+    },
+    #[error(
+        "`.embedders.{embedder_name}`: Cannot use the `{quantization}` quantization with the `{distance}` distance.\n - Hint: Use the `cosine` or `euclidean` distance, or disable the quantization."
+    )]
+    InvalidQuantizationForDistance {
+        embedder_name: String,
+        quantization: VectorQuantization,
+        distance: VectorDistance,
+    },
     #[error("`.embedders.{embedder_name}.documentTemplateMaxBytes`: `documentTemplateMaxBytes` cannot be zero")]
     InvalidSettingsDocumentTemplateMaxBytes { embedder_name: String },
+    #[error("`.embedders.{embedder_name}.chunking.size`: `size` cannot be zero")]
+    InvalidSettingsChunkingSize { embedder_name: String },
+    #[error("`.embedders.{embedder_name}.chunking.overlap`: `overlap` must be smaller than `size`, got an overlap of {overlap} for a size of {size}")]
+    InvalidSettingsChunkingOverlap { embedder_name: String, size: usize, overlap: usize },
+    #[error("`.embedders.{embedder_name}.media`: `media` cannot be used along with `chunking`")]
+    InvalidSettingsMediaWithChunking { embedder_name: String },
+    #[error("{0}")]
+    InvalidReranker(String),
     #[error("`.embedders.{embedder_name}.url`: could not parse `{url}`: {inner_error}")]
     InvalidUrl { embedder_name: String, inner_error: url::ParseError, url: String },
     #[error("Document editions cannot modify a document's primary key")]
@@ -434,6 +464,8 @@ pub enum GeoError {
     BadLatitude { document_id: Value, value: Value },
     #[error("Could not parse longitude in the document with the id: `{document_id}`. Was expecting a finite number but instead got `{value}`.")]
     BadLongitude { document_id: Value, value: Value },
+    #[error("The `_geojson` field in the document with the id: `{document_id}` is not a valid GeoJSON geometry: {reason}.")]
+    InvalidGeoJson { document_id: Value, reason: String },
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,64 @@
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// Parses an RFC 3339 date into the number of seconds since the Unix epoch.
+///
+/// Date facets are stored in the number facet databases under this representation,
+/// which makes range filters, sorting and stats work without any special case.
+pub fn parse_date_facet(value: &str) -> Option<f64> {
+    let date = OffsetDateTime::parse(value.trim(), &Rfc3339).ok()?;
+    Some(date.unix_timestamp_nanos() as f64 / 1_000_000_000.0)
+}
+
+/// The granularity used to group the date values in the facet distribution.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Deserr, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase)]
+pub enum DateBucket {
+    #[default]
+    Day,
+    Month,
+    Year,
+}
+
+impl DateBucket {
+    /// Returns the label of the bucket containing the given Unix timestamp, in UTC.
+    pub fn label(&self, timestamp: f64) -> Option<String> {
+        let nanos = (timestamp * 1_000_000_000.0) as i128;
+        let date = OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()?.date();
+        let label = match self {
+            DateBucket::Day => {
+                format!("{:04}-{:02}-{:02}", date.year(), date.month() as u8, date.day())
+            }
+            DateBucket::Month => format!("{:04}-{:02}", date.year(), date.month() as u8),
+            DateBucket::Year => format!("{:04}", date.year()),
+        };
+        Some(label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_bucket() {
+        let timestamp = parse_date_facet("2024-02-29T23:30:00-01:00").unwrap();
+        assert_eq!(timestamp, 1709253000.0);
+        assert_eq!(DateBucket::Day.label(timestamp).unwrap(), "2024-03-01");
+        assert_eq!(DateBucket::Month.label(timestamp).unwrap(), "2024-03");
+        assert_eq!(DateBucket::Year.label(timestamp).unwrap(), "2024");
+
+        let timestamp = parse_date_facet("1969-12-31T23:59:59.5Z").unwrap();
+        assert_eq!(timestamp, -0.5);
+        assert_eq!(DateBucket::Day.label(timestamp).unwrap(), "1969-12-31");
+
+        assert_eq!(parse_date_facet("2024-01-01"), None);
+        assert_eq!(parse_date_facet("yesterday"), None);
+    }
+}
@@ -1,6 +1,8 @@
+mod facet_date;
 mod facet_type;
 mod facet_value;
 pub mod value_encoding;
 
+pub use self::facet_date::{parse_date_facet, DateBucket};
 pub use self::facet_type::FacetType;
 pub use self::facet_value::FacetValue;
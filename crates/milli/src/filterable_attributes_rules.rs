@@ -6,6 +6,7 @@ use utoipa::ToSchema;
 use crate::{
     attribute_patterns::{match_distinct_field, match_field_legacy, PatternMatch},
     constants::RESERVED_GEO_FIELD_NAME,
+    facet::DateBucket,
     AttributePatterns,
 };
 
@@ -77,6 +78,9 @@ pub struct FilterableAttributesFeatures {
     #[serde(default)]
     #[deserr(default)]
     filter: FilterFeatures,
+    #[serde(default)]
+    #[deserr(default)]
+    date: DateFeatures,
 }
 
 impl FilterableAttributesFeatures {
@@ -85,12 +89,20 @@ impl FilterableAttributesFeatures {
     /// This is the default behavior for `FilterableAttributesRule::Field`.
     /// This will set the facet search to true and activate all the filter operators.
     pub fn legacy_default() -> Self {
-        Self { facet_search: true, filter: FilterFeatures::legacy_default() }
+        Self {
+            facet_search: true,
+            filter: FilterFeatures::legacy_default(),
+            date: DateFeatures::default(),
+        }
     }
 
     /// Create a new `FilterableAttributesFeatures` with no features.
     pub fn no_features() -> Self {
-        Self { facet_search: false, filter: FilterFeatures::no_features() }
+        Self {
+            facet_search: false,
+            filter: FilterFeatures::no_features(),
+            date: DateFeatures::default(),
+        }
     }
 
     pub fn is_filterable(&self) -> bool {
@@ -122,11 +134,26 @@ impl FilterableAttributesFeatures {
         self.filter.is_filterable_comparison()
     }
 
+    /// Check if `MATCHES` or `LIKE` are allowed
+    pub fn is_filterable_pattern(&self) -> bool {
+        self.filter.is_filterable_pattern()
+    }
+
     /// Check if the facet search is allowed
     pub fn is_facet_searchable(&self) -> bool {
         self.facet_search
     }
 
+    /// Check if the string values must be parsed as RFC 3339 dates
+    pub fn is_date(&self) -> bool {
+        self.date.enabled
+    }
+
+    /// Get the granularity of the facet distribution if the attribute is a date
+    pub fn date_bucket(&self) -> Option<DateBucket> {
+        self.date.enabled.then_some(self.date.bucket)
+    }
+
     pub fn allowed_filter_operators(&self) -> Vec<String> {
         self.filter.allowed_operators()
     }
@@ -157,6 +184,25 @@ pub struct FilterFeatures {
     #[serde(default)]
     #[deserr(default)]
     comparison: bool,
+    #[serde(default)]
+    #[deserr(default)]
+    pattern: bool,
+}
+
+/// Parse the string values of an attribute as RFC 3339 dates.
+///
+/// Dates are indexed as numbers so that range filters and sorting apply to them,
+/// and the facet distribution groups them by `bucket`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct DateFeatures {
+    #[serde(default)]
+    #[deserr(default)]
+    enabled: bool,
+    #[serde(default)]
+    #[deserr(default)]
+    bucket: DateBucket,
 }
 
 fn default_true() -> bool {
@@ -177,6 +223,9 @@ impl FilterFeatures {
         if self.is_filterable_comparison() {
             operators.extend_from_slice(&["<", ">", "<=", ">=", "TO"]);
         }
+        if self.is_filterable_pattern() {
+            operators.extend_from_slice(&["MATCHES", "LIKE"]);
+        }
         if self.is_filterable_empty() {
             operators.push("IS EMPTY");
         }
@@ -191,7 +240,7 @@ impl FilterFeatures {
     }
 
     pub fn is_filterable(&self) -> bool {
-        self.equality || self.comparison
+        self.equality || self.comparison || self.pattern
     }
 
     pub fn is_filterable_equality(&self) -> bool {
@@ -203,6 +252,11 @@ impl FilterFeatures {
         self.comparison
     }
 
+    /// Check if `MATCHES` or `LIKE` are allowed
+    pub fn is_filterable_pattern(&self) -> bool {
+        self.pattern
+    }
+
     /// Check if `IS EMPTY` is allowed
     pub fn is_filterable_empty(&self) -> bool {
         self.is_filterable()
@@ -222,19 +276,20 @@ impl FilterFeatures {
     ///
     /// This is the default behavior for `FilterableAttributesRule::Field`.
     /// This will set the equality and comparison to true.
+    /// The pattern operators are more expensive and must be explicitly enabled.
     pub fn legacy_default() -> Self {
-        Self { equality: true, comparison: true }
+        Self { equality: true, comparison: true, pattern: false }
     }
 
     /// Create a new `FilterFeatures` with no features.
     pub fn no_features() -> Self {
-        Self { equality: false, comparison: false }
+        Self { equality: false, comparison: false, pattern: false }
     }
 }
 
 impl Default for FilterFeatures {
     fn default() -> Self {
-        Self { equality: true, comparison: false }
+        Self { equality: true, comparison: false, pattern: false }
     }
 }
 
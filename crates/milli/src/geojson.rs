@@ -0,0 +1,309 @@
+//! Support for the `_geojson` reserved field.
+//!
+//! The geometries are indexed in the same rtree as the `_geo` points: every position of a
+//! geometry is inserted with the document id, which makes the `_geoRadius`, `_geoBoundingBox`
+//! and `_geoPolygon` filters and the geo sort work on them out of the box. Polygons are also
+//! matched when they intersect the area targeted by a filter without any of their vertices being
+//! inside of it: their envelopes are indexed in a second rtree to only check the polygons that
+//! may intersect the area.
+
+use rstar::primitives::{GeomWithData, Rectangle};
+use serde_json::Value;
+
+use crate::{distance_between_two_points, DocumentId};
+
+/// The envelope of a `_geojson` polygon in the latitude and longitude plane, associated with the
+/// document id.
+pub type GeoJsonEnvelope = GeomWithData<Rectangle<[f64; 2]>, DocumentId>;
+
+/// Creates the envelope to index in the polygons rtree from its lower and upper corners.
+pub fn geojson_envelope(docid: DocumentId, [lower, upper]: [[f64; 2]; 2]) -> GeoJsonEnvelope {
+    GeoJsonEnvelope::new(Rectangle::from_corners(lower, upper), docid)
+}
+
+/// A GeoJSON geometry, with its positions converted to `[latitude, longitude]` points.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoJsonGeometry {
+    Point([f64; 2]),
+    MultiPoint(Vec<[f64; 2]>),
+    /// The exterior ring followed by the holes, without their closing positions.
+    Polygon(Vec<Vec<[f64; 2]>>),
+}
+
+impl GeoJsonGeometry {
+    /// Parses a `Point`, `MultiPoint` or `Polygon` GeoJSON geometry.
+    ///
+    /// Returns a description of the problem when the geometry is invalid.
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        let Value::Object(object) = value else {
+            return Err(format!("expected an object but instead got `{value}`"));
+        };
+        let coordinates = object.get("coordinates").ok_or("missing the `coordinates` field")?;
+        match object.get("type").and_then(Value::as_str) {
+            Some("Point") => parse_position(coordinates).map(Self::Point),
+            Some("MultiPoint") => parse_positions(coordinates).map(Self::MultiPoint),
+            Some("Polygon") => {
+                let Value::Array(rings) = coordinates else {
+                    return Err(format!(
+                        "expected an array of rings but instead got `{coordinates}`"
+                    ));
+                };
+                if rings.is_empty() {
+                    return Err("a polygon must contain at least one ring".to_string());
+                }
+                rings.iter().map(parse_ring).collect::<Result<_, _>>().map(Self::Polygon)
+            }
+            Some(other) => Err(format!(
+                "unsupported geometry type `{other}`, expected `Point`, `MultiPoint` or `Polygon`"
+            )),
+            None => Err("missing the `type` field".to_string()),
+        }
+    }
+
+    /// Returns the points to index in the rtree.
+    pub fn points(&self) -> Vec<[f64; 2]> {
+        match self {
+            Self::Point(point) => vec![*point],
+            Self::MultiPoint(points) => points.clone(),
+            Self::Polygon(rings) => rings.concat(),
+        }
+    }
+
+    /// Returns the lower and upper `[latitude, longitude]` corners of the envelope of a polygon.
+    pub fn envelope(&self) -> Option<[[f64; 2]; 2]> {
+        match self {
+            Self::Point(_) | Self::MultiPoint(_) => None,
+            Self::Polygon(rings) => {
+                // the holes are inside of the exterior ring
+                let exterior = &rings[0];
+                let lower = exterior
+                    .iter()
+                    .fold([f64::INFINITY; 2], |[lat, lng], p| [lat.min(p[0]), lng.min(p[1])]);
+                let upper = exterior
+                    .iter()
+                    .fold([f64::NEG_INFINITY; 2], |[lat, lng], p| [lat.max(p[0]), lng.max(p[1])]);
+                Some([lower, upper])
+            }
+        }
+    }
+
+    /// Returns `true` if the geometry is a polygon that contains the point.
+    pub fn contains(&self, point: &[f64; 2]) -> bool {
+        match self {
+            Self::Point(_) | Self::MultiPoint(_) => false,
+            Self::Polygon(rings) => {
+                let (exterior, holes) = rings.split_first().unwrap();
+                polygon_contains(exterior, point)
+                    && !holes.iter().any(|hole| polygon_contains(hole, point))
+            }
+        }
+    }
+
+    /// Returns `true` if the geometry is a polygon with an edge crossing an edge of the other
+    /// polygon.
+    pub fn crosses_polygon(&self, polygon: &[[f64; 2]]) -> bool {
+        self.edges().any(|(a, b)| ring_edges(polygon).any(|(c, d)| segments_intersect(a, b, c, d)))
+    }
+
+    /// Returns `true` if the geometry is a polygon with an edge passing at less than `radius`
+    /// meters from the point.
+    pub fn crosses_circle(&self, center: &[f64; 2], radius: f64) -> bool {
+        self.edges().any(|(a, b)| distance_to_segment(center, a, b) <= radius + f64::EPSILON)
+    }
+
+    /// Returns the edges of the rings of a polygon.
+    fn edges(&self) -> impl Iterator<Item = ([f64; 2], [f64; 2])> + '_ {
+        let rings = match self {
+            Self::Point(_) | Self::MultiPoint(_) => &[][..],
+            Self::Polygon(rings) => rings.as_slice(),
+        };
+        rings.iter().flat_map(|ring| ring_edges(ring))
+    }
+
+    /// Returns the distance in meters between the point and the nearest point of the geometry.
+    ///
+    /// The distance to a polygon is zero when it contains the point and is computed against
+    /// its vertices otherwise.
+    pub fn distance_to(&self, point: &[f64; 2]) -> f64 {
+        if self.contains(point) {
+            return 0.0;
+        }
+        self.points()
+            .iter()
+            .map(|other| distance_between_two_points(point, other))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+/// Returns `true` if the point is inside the polygon, using the ray casting algorithm on the
+/// latitude and longitude of the points.
+///
+/// Polygons crossing the antimeridian are not supported.
+pub(crate) fn polygon_contains(polygon: &[[f64; 2]], point: &[f64; 2]) -> bool {
+    let [lat, lng] = *point;
+    let mut inside = false;
+    for (a, b) in polygon.iter().zip(polygon.iter().cycle().skip(1)) {
+        if (a[0] > lat) != (b[0] > lat) {
+            let crossing_lng = a[1] + (lat - a[0]) * (b[1] - a[1]) / (b[0] - a[0]);
+            if lng < crossing_lng {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Returns the edges of a ring, including the one closing it.
+fn ring_edges(ring: &[[f64; 2]]) -> impl Iterator<Item = ([f64; 2], [f64; 2])> + '_ {
+    ring.iter().copied().zip(ring.iter().copied().cycle().skip(1))
+}
+
+/// Returns `true` if the segments `ab` and `cd` share a point in the latitude and longitude
+/// plane.
+fn segments_intersect(a: [f64; 2], b: [f64; 2], c: [f64; 2], d: [f64; 2]) -> bool {
+    // the sign of the cross product tells on which side of a segment a point is
+    let side = |o: [f64; 2], p: [f64; 2], q: [f64; 2]| {
+        (p[0] - o[0]) * (q[1] - o[1]) - (p[1] - o[1]) * (q[0] - o[0])
+    };
+    // when a point is aligned with a segment, it must be within its bounds
+    let within = |p: [f64; 2], q: [f64; 2], r: [f64; 2]| {
+        (p[0].min(q[0])..=p[0].max(q[0])).contains(&r[0])
+            && (p[1].min(q[1])..=p[1].max(q[1])).contains(&r[1])
+    };
+
+    let (d1, d2) = (side(c, d, a), side(c, d, b));
+    let (d3, d4) = (side(a, b, c), side(a, b, d));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return true;
+    }
+    (d1 == 0.0 && within(c, d, a))
+        || (d2 == 0.0 && within(c, d, b))
+        || (d3 == 0.0 && within(a, b, c))
+        || (d4 == 0.0 && within(a, b, d))
+}
+
+/// Returns the distance in meters between the point and the segment `ab`.
+///
+/// The nearest point of the segment is found in an equirectangular projection centered on the
+/// point, which is precise enough for the distances covered by a radius filter.
+fn distance_to_segment(point: &[f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let scale = point[0].to_radians().cos();
+    let project = |p: [f64; 2]| [p[0] - point[0], (p[1] - point[1]) * scale];
+    let (pa, pb) = (project(a), project(b));
+    let ab = [pb[0] - pa[0], pb[1] - pa[1]];
+    let length = ab[0] * ab[0] + ab[1] * ab[1];
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (-(pa[0] * ab[0] + pa[1] * ab[1]) / length).clamp(0.0, 1.0)
+    };
+    let nearest = [a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])];
+    distance_between_two_points(point, &nearest)
+}
+
+/// Parses a GeoJSON position, which is expressed as `[longitude, latitude]`.
+fn parse_position(value: &Value) -> Result<[f64; 2], String> {
+    let position = match value.as_array().map(Vec::as_slice) {
+        Some([lng, lat, ..]) => lng.as_f64().zip(lat.as_f64()),
+        _ => None,
+    };
+    match position {
+        Some((lng, lat)) if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng) => {
+            Ok([lat, lng])
+        }
+        _ => Err(format!(
+            "expected a position of the form `[longitude, latitude]` with a longitude between -180 and 180 and a latitude between -90 and 90 but instead got `{value}`"
+        )),
+    }
+}
+
+fn parse_positions(value: &Value) -> Result<Vec<[f64; 2]>, String> {
+    match value {
+        Value::Array(positions) => positions.iter().map(parse_position).collect(),
+        value => Err(format!("expected an array of positions but instead got `{value}`")),
+    }
+}
+
+/// Parses a linear ring and removes its closing position.
+fn parse_ring(value: &Value) -> Result<Vec<[f64; 2]>, String> {
+    let mut ring = parse_positions(value)?;
+    if ring.len() < 4 || ring.first() != ring.last() {
+        return Err(format!(
+            "expected a closed ring of at least four positions but instead got `{value}`"
+        ));
+    }
+    ring.pop();
+    Ok(ring)
+}
+
+#[cfg(test)]
+mod tests {
+    use meili_snap::snapshot;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_geometries() {
+        let point = GeoJsonGeometry::from_value(&json!({
+            "type": "Point",
+            "coordinates": [2.35, 48.85]
+        }))
+        .unwrap();
+        assert_eq!(point, GeoJsonGeometry::Point([48.85, 2.35]));
+
+        let multi_point = GeoJsonGeometry::from_value(&json!({
+            "type": "MultiPoint",
+            "coordinates": [[2.35, 48.85], [4.83, 45.76]]
+        }))
+        .unwrap();
+        assert_eq!(multi_point.points(), vec![[48.85, 2.35], [45.76, 4.83]]);
+
+        let polygon = GeoJsonGeometry::from_value(&json!({
+            "type": "Polygon",
+            "coordinates": [
+                [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                [[4, 4], [6, 4], [6, 6], [4, 6], [4, 4]]
+            ]
+        }))
+        .unwrap();
+        assert_eq!(polygon.points().len(), 8);
+        assert!(polygon.contains(&[2.0, 2.0]));
+        assert!(!polygon.contains(&[5.0, 5.0]));
+        assert!(!polygon.contains(&[12.0, 2.0]));
+        assert_eq!(polygon.distance_to(&[2.0, 2.0]), 0.0);
+        assert!(polygon.distance_to(&[5.0, 5.0]) > 0.0);
+        assert_eq!(polygon.envelope(), Some([[0.0, 0.0], [10.0, 10.0]]));
+        assert_eq!(multi_point.envelope(), None);
+
+        // the edges of the polygon and of its hole cross the other geometries
+        assert!(polygon.crosses_polygon(&[[-1.0, 2.0], [1.0, 2.0], [1.0, 3.0], [-1.0, 3.0]]));
+        assert!(polygon.crosses_polygon(&[[5.0, 5.0], [7.0, 5.0], [7.0, 7.0]]));
+        assert!(!polygon.crosses_polygon(&[[1.0, 1.0], [2.0, 1.0], [2.0, 2.0]]));
+        assert!(!polygon.crosses_polygon(&[[20.0, 20.0], [21.0, 20.0], [21.0, 21.0]]));
+        assert!(polygon.crosses_circle(&[-0.001, 5.0], 1000.0));
+        assert!(!polygon.crosses_circle(&[-0.1, 5.0], 1000.0));
+        assert!(!multi_point.crosses_circle(&[48.85, 2.35], 1000.0));
+
+        let error = GeoJsonGeometry::from_value(&json!({
+            "type": "LineString",
+            "coordinates": [[0, 0], [1, 1]]
+        }))
+        .unwrap_err();
+        snapshot!(error, @"unsupported geometry type `LineString`, expected `Point`, `MultiPoint` or `Polygon`");
+
+        let error = GeoJsonGeometry::from_value(&json!({
+            "type": "Point",
+            "coordinates": [12, 95]
+        }))
+        .unwrap_err();
+        snapshot!(error, @"expected a position of the form `[longitude, latitude]` with a longitude between -180 and 180 and a latitude between -90 and 90 but instead got `[12,95]`");
+
+        let error = GeoJsonGeometry::from_value(&json!({
+            "type": "Polygon",
+            "coordinates": [[[0, 0], [10, 0], [10, 10]]]
+        }))
+        .unwrap_err();
+        snapshot!(error, @"expected a closed ring of at least four positions but instead got `[[0,0],[10,0],[10,10]]`");
+    }
+}
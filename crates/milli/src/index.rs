@@ -3,18 +3,22 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::path::Path;
 
+use charabia::Language;
 use heed::{types::*, WithoutTls};
 use heed::{CompactionOption, Database, RoTxn, RwTxn, Unspecified};
 use roaring::RoaringBitmap;
 use rstar::RTree;
 use serde::{Deserialize, Serialize};
 
-use crate::constants::{self, RESERVED_GEO_FIELD_NAME, RESERVED_VECTORS_FIELD_NAME};
+use crate::constants::{
+    self, RESERVED_GEOJSON_FIELD_NAME, RESERVED_GEO_FIELD_NAME, RESERVED_VECTORS_FIELD_NAME,
+};
 use crate::database_stats::DatabaseStats;
 use crate::documents::PrimaryKey;
 use crate::error::{InternalError, UserError};
 use crate::fields_ids_map::metadata::{FieldIdMapWithMetadata, MetadataBuilder};
 use crate::fields_ids_map::FieldsIdsMap;
+use crate::geojson::{GeoJsonEnvelope, GeoJsonGeometry};
 use crate::heed_codec::facet::{
     FacetGroupKeyCodec, FacetGroupValueCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec,
     FieldIdCodec, OrderedF64Codec,
@@ -23,13 +27,18 @@ use crate::heed_codec::version::VersionCodec;
 use crate::heed_codec::{BEU16StrCodec, FstSetCodec, StrBEU16Codec, StrRefCodec};
 use crate::order_by_map::OrderByMap;
 use crate::proximity::ProximityPrecision;
-use crate::vector::{ArroyStats, ArroyWrapper, Embedding, EmbeddingConfig};
+use crate::synonyms::NormalizedSynonymSet;
+use crate::update::index_documents::extract_finite_float_from_value;
+use crate::vector::sparse::SparseVectorDatabase;
+use crate::vector::{
+    ArroyStats, ArroyWrapper, Embedding, EmbeddingConfig, ScalarQuantizedDatabase,
+};
 use crate::{
     default_criteria, CboRoaringBitmapCodec, Criterion, DocumentId, ExternalDocumentsIds,
     FacetDistribution, FieldDistribution, FieldId, FieldIdMapMissingEntry, FieldIdWordCountCodec,
     FieldidsWeightsMap, FilterableAttributesRule, GeoPoint, LocalizedAttributesRule, ObkvCodec,
-    Result, RoaringBitmapCodec, RoaringBitmapLenCodec, Search, U8StrStrCodec, Weight, BEU16, BEU32,
-    BEU64,
+    PinningRule, RerankerSettings, Result, RoaringBitmapCodec, RoaringBitmapLenCodec, Search,
+    SynonymSet, U8StrStrCodec, Weight, BEU16, BEU32, BEU64,
 };
 
 pub const DEFAULT_MIN_WORD_LEN_ONE_TYPO: u8 = 5;
@@ -49,6 +58,8 @@ pub mod main_key {
     pub const FIELDIDS_WEIGHTS_MAP_KEY: &str = "fieldids-weights-map";
     pub const GEO_FACETED_DOCUMENTS_IDS_KEY: &str = "geo-faceted-documents-ids";
     pub const GEO_RTREE_KEY: &str = "geo-rtree";
+    pub const GEOJSON_DOCUMENTS_IDS_KEY: &str = "geojson-documents-ids";
+    pub const GEOJSON_RTREE_KEY: &str = "geojson-rtree";
     pub const PRIMARY_KEY_KEY: &str = "primary-key";
     pub const SEARCHABLE_FIELDS_KEY: &str = "searchable-fields";
     pub const USER_DEFINED_SEARCHABLE_FIELDS_KEY: &str = "user-defined-searchable-fields";
@@ -58,6 +69,8 @@ pub mod main_key {
     pub const DICTIONARY_KEY: &str = "dictionary";
     pub const SYNONYMS_KEY: &str = "synonyms";
     pub const USER_DEFINED_SYNONYMS_KEY: &str = "user-defined-synonyms";
+    pub const SYNONYM_SETS_KEY: &str = "synonym-sets";
+    pub const USER_DEFINED_SYNONYM_SETS_KEY: &str = "user-defined-synonym-sets";
     pub const WORDS_FST_KEY: &str = "words-fst";
     pub const WORDS_PREFIXES_FST_KEY: &str = "words-prefixes-fst";
     pub const CREATED_AT_KEY: &str = "created-at";
@@ -76,6 +89,8 @@ pub mod main_key {
     pub const LOCALIZED_ATTRIBUTES_RULES: &str = "localized_attributes_rules";
     pub const FACET_SEARCH: &str = "facet_search";
     pub const PREFIX_SEARCH: &str = "prefix_search";
+    pub const PINNING_RULES: &str = "pinning_rules";
+    pub const RERANKER: &str = "reranker";
     pub const DOCUMENTS_STATS: &str = "documents_stats";
 }
 
@@ -104,6 +119,8 @@ pub mod db_name {
     pub const FIELD_ID_DOCID_FACET_STRINGS: &str = "field-id-docid-facet-strings";
     pub const VECTOR_EMBEDDER_CATEGORY_ID: &str = "vector-embedder-category-id";
     pub const VECTOR_ARROY: &str = "vector-arroy";
+    pub const VECTOR_SCALAR_QUANTIZED: &str = "vector-scalar-quantized";
+    pub const VECTOR_SPARSE: &str = "vector-sparse";
     pub const DOCUMENTS: &str = "documents";
 }
 
@@ -170,6 +187,10 @@ pub struct Index {
     pub embedder_category_id: Database<Str, U8>,
     /// Vector store based on arroy™.
     pub vector_arroy: arroy::Database<Unspecified>,
+    /// Maps the arroy index and item id to the `int8` quantized embedding.
+    pub vector_scalar_quantized: ScalarQuantizedDatabase,
+    /// Maps the embeddings of the sparse embedders and the posting lists of their tokens.
+    pub vector_sparse: SparseVectorDatabase,
 
     /// Maps the document id to the document as an obkv store.
     pub(crate) documents: Database<BEU32, ObkvCodec>,
@@ -185,7 +206,7 @@ impl Index {
     ) -> Result<Index> {
         use db_name::*;
 
-        options.max_dbs(25);
+        options.max_dbs(26);
 
         let env = unsafe { options.open(path) }?;
         let mut wtxn = env.write_txn()?;
@@ -227,6 +248,9 @@ impl Index {
         let embedder_category_id =
             env.create_database(&mut wtxn, Some(VECTOR_EMBEDDER_CATEGORY_ID))?;
         let vector_arroy = env.create_database(&mut wtxn, Some(VECTOR_ARROY))?;
+        let vector_scalar_quantized =
+            env.create_database(&mut wtxn, Some(VECTOR_SCALAR_QUANTIZED))?;
+        let vector_sparse = env.create_database(&mut wtxn, Some(VECTOR_SPARSE))?;
 
         let documents = env.create_database(&mut wtxn, Some(DOCUMENTS))?;
 
@@ -254,6 +278,8 @@ impl Index {
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_scalar_quantized,
+            vector_sparse,
             embedder_category_id,
             documents,
         };
@@ -664,6 +690,110 @@ impl Index {
             None => Ok(RoaringBitmap::new()),
         }
     }
+
+    /* geojson */
+
+    /// Writes the documents ids that have their points in the rtree coming from a _geojson field.
+    pub(crate) fn put_geojson_documents_ids(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        docids: &RoaringBitmap,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, RoaringBitmapCodec>().put(
+            wtxn,
+            main_key::GEOJSON_DOCUMENTS_IDS_KEY,
+            docids,
+        )
+    }
+
+    /// Delete the documents ids that have a _geojson field.
+    pub(crate) fn delete_geojson_documents_ids(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::GEOJSON_DOCUMENTS_IDS_KEY)
+    }
+
+    /// Retrieve all the documents ids that have their points in the rtree coming from a _geojson field.
+    pub fn geojson_documents_ids(&self, rtxn: &RoTxn<'_>) -> heed::Result<RoaringBitmap> {
+        match self
+            .main
+            .remap_types::<Str, RoaringBitmapCodec>()
+            .get(rtxn, main_key::GEOJSON_DOCUMENTS_IDS_KEY)?
+        {
+            Some(docids) => Ok(docids),
+            None => Ok(RoaringBitmap::new()),
+        }
+    }
+
+    /// Writes the provided `rtree` which associates the envelopes of the _geojson polygons to
+    /// documents ids.
+    pub(crate) fn put_geojson_rtree(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        rtree: &RTree<GeoJsonEnvelope>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<RTree<GeoJsonEnvelope>>>().put(
+            wtxn,
+            main_key::GEOJSON_RTREE_KEY,
+            rtree,
+        )
+    }
+
+    /// Delete the `rtree` which associates the envelopes of the _geojson polygons to documents ids.
+    pub(crate) fn delete_geojson_rtree(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::GEOJSON_RTREE_KEY)
+    }
+
+    /// Returns the `rtree` which associates the envelopes of the _geojson polygons to documents ids.
+    pub fn geojson_rtree(&self, rtxn: &RoTxn<'_>) -> Result<Option<RTree<GeoJsonEnvelope>>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeBincode<RTree<GeoJsonEnvelope>>>()
+            .get(rtxn, main_key::GEOJSON_RTREE_KEY)?)
+    }
+
+    /// Returns the _geojson geometry of the given document, if any.
+    pub fn geojson_geometry(
+        &self,
+        rtxn: &RoTxn<'_>,
+        fields_ids_map: &FieldsIdsMap,
+        docid: DocumentId,
+    ) -> Result<Option<GeoJsonGeometry>> {
+        let Some(fid) = fields_ids_map.id(RESERVED_GEOJSON_FIELD_NAME) else { return Ok(None) };
+        let Some(value) = self.documents.get(rtxn, &docid)?.and_then(|document| document.get(fid))
+        else {
+            return Ok(None);
+        };
+        let value: serde_json::Value =
+            serde_json::from_slice(value).map_err(InternalError::SerdeJson)?;
+        // the geometry has been validated during the indexing
+        Ok(GeoJsonGeometry::from_value(&value).ok())
+    }
+
+    /// Returns the points of the `_geo` field of the given document.
+    pub fn geo_points(
+        &self,
+        rtxn: &RoTxn<'_>,
+        fields_ids_map: &FieldsIdsMap,
+        docid: DocumentId,
+    ) -> Result<Vec<[f64; 2]>> {
+        let Some(fid) = fields_ids_map.id(RESERVED_GEO_FIELD_NAME) else { return Ok(Vec::new()) };
+        let Some(value) = self.documents.get(rtxn, &docid)?.and_then(|document| document.get(fid))
+        else {
+            return Ok(Vec::new());
+        };
+        let points = match serde_json::from_slice(value).map_err(InternalError::SerdeJson)? {
+            serde_json::Value::Array(points) => points,
+            point => vec![point],
+        };
+        // the points have been validated during the indexing
+        Ok(points
+            .into_iter()
+            .filter_map(|point| {
+                let lat = extract_finite_float_from_value(point.get("lat")?.clone()).ok()?;
+                let lng = extract_finite_float_from_value(point.get("lng")?.clone()).ok()?;
+                Some([lat, lng])
+            })
+            .collect())
+    }
     /* field distribution */
 
     /// Writes the field distribution which associates every field name with
@@ -982,9 +1112,14 @@ impl Index {
         let asc_desc_fields = self
             .criteria(rtxn)?
             .into_iter()
-            .filter_map(|criterion| match criterion {
-                Criterion::Asc(field) | Criterion::Desc(field) => Some(field),
-                _otherwise => None,
+            .flat_map(|criterion| match criterion {
+                Criterion::Asc(field) | Criterion::Desc(field) => vec![field],
+                // the fields of an expression must be faceted to be evaluated
+                criterion @ Criterion::Expression(_) => criterion
+                    .expression()
+                    .map(|expression| expression.fields().into_iter().map(String::from).collect())
+                    .unwrap_or_default(),
+                _otherwise => Vec::new(),
             })
             .collect();
 
@@ -1271,6 +1406,76 @@ impl Index {
         Ok(self.synonyms(rtxn)?.remove(&words))
     }
 
+    /* synonym sets */
+
+    pub(crate) fn put_synonym_sets(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        synonym_sets: Vec<NormalizedSynonymSet>,
+        user_defined_synonym_sets: Vec<SynonymSet>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<Vec<NormalizedSynonymSet>>>().put(
+            wtxn,
+            main_key::SYNONYM_SETS_KEY,
+            &synonym_sets,
+        )?;
+        self.main.remap_types::<Str, SerdeJson<Vec<SynonymSet>>>().put(
+            wtxn,
+            main_key::USER_DEFINED_SYNONYM_SETS_KEY,
+            &user_defined_synonym_sets,
+        )
+    }
+
+    pub(crate) fn delete_synonym_sets(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SYNONYM_SETS_KEY)?;
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::USER_DEFINED_SYNONYM_SETS_KEY)
+    }
+
+    pub fn user_defined_synonym_sets(&self, rtxn: &RoTxn<'_>) -> heed::Result<Vec<SynonymSet>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::USER_DEFINED_SYNONYM_SETS_KEY)?
+            .unwrap_or_default())
+    }
+
+    pub fn synonym_sets(&self, rtxn: &RoTxn<'_>) -> heed::Result<Vec<NormalizedSynonymSet>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeBincode<_>>()
+            .get(rtxn, main_key::SYNONYM_SETS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the synonyms that apply to a query in the given locales.
+    ///
+    /// These are the `synonyms` of the index merged with the synonym sets
+    /// that are either scoped to one of the `locales` or not scoped at all.
+    pub fn synonyms_for_locales(
+        &self,
+        rtxn: &RoTxn<'_>,
+        locales: &[Language],
+    ) -> heed::Result<HashMap<Vec<String>, Vec<Vec<String>>>> {
+        let mut synonyms = self.synonyms(rtxn)?;
+        let synonym_sets = self.synonym_sets(rtxn)?;
+        if synonym_sets.is_empty() {
+            return Ok(synonyms);
+        }
+
+        for set in synonym_sets.into_iter().filter(|set| set.matches_locales(locales)) {
+            for (words, set_synonyms) in set.synonyms {
+                synonyms.entry(words).or_default().extend(set_synonyms);
+            }
+        }
+
+        synonyms.values_mut().for_each(|synonyms| {
+            synonyms.sort_unstable();
+            synonyms.dedup();
+        });
+
+        Ok(synonyms)
+    }
+
     /* words prefixes fst */
 
     /// Writes the FST which is the words prefixes dictionary of the engine.
@@ -1722,6 +1927,50 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(wtxn, main_key::SEARCH_CUTOFF)
     }
 
+    pub(crate) fn put_pinning_rules(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        rules: &[PinningRule],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<&[PinningRule]>>().put(
+            wtxn,
+            main_key::PINNING_RULES,
+            &rules,
+        )
+    }
+
+    pub fn pinning_rules(&self, rtxn: &RoTxn<'_>) -> heed::Result<Vec<PinningRule>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::PINNING_RULES)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn delete_pinning_rules(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::PINNING_RULES)
+    }
+
+    pub(crate) fn put_reranker(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        reranker: &RerankerSettings,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<&RerankerSettings>>().put(
+            wtxn,
+            main_key::RERANKER,
+            &reranker,
+        )
+    }
+
+    pub fn reranker(&self, rtxn: &RoTxn<'_>) -> heed::Result<Option<RerankerSettings>> {
+        self.main.remap_types::<Str, SerdeJson<_>>().get(rtxn, main_key::RERANKER)
+    }
+
+    pub(crate) fn delete_reranker(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::RERANKER)
+    }
+
     pub fn embeddings(
         &self,
         rtxn: &RoTxn<'_>,
@@ -1731,8 +1980,14 @@ impl Index {
         let embedding_configs = self.embedding_configs(rtxn)?;
         for config in embedding_configs {
             let embedder_id = self.embedder_category_id.get(rtxn, &config.name)?.unwrap();
-            let reader =
-                ArroyWrapper::new(self.vector_arroy, embedder_id, config.config.quantized());
+            let reader = ArroyWrapper::new(
+                self.vector_arroy,
+                self.vector_scalar_quantized,
+                config.config.is_sparse().then_some(self.vector_sparse),
+                embedder_id,
+                config.config.quantization(),
+                config.config.distance(),
+            );
             let embeddings = reader.item_vectors(rtxn, docid)?;
             res.insert(config.name.to_owned(), embeddings);
         }
@@ -1749,12 +2004,37 @@ impl Index {
         let embedding_configs = self.embedding_configs(rtxn)?;
         for config in embedding_configs {
             let embedder_id = self.embedder_category_id.get(rtxn, &config.name)?.unwrap();
-            let reader =
-                ArroyWrapper::new(self.vector_arroy, embedder_id, config.config.quantized());
+            let reader = ArroyWrapper::new(
+                self.vector_arroy,
+                self.vector_scalar_quantized,
+                config.config.is_sparse().then_some(self.vector_sparse),
+                embedder_id,
+                config.config.quantization(),
+                config.config.distance(),
+            );
             reader.aggregate_stats(rtxn, &mut stats)?;
         }
         Ok(stats)
     }
+
+    /// Returns the size taken by the embeddings of each embedder, in bytes.
+    pub fn embedder_storage_sizes(&self, rtxn: &RoTxn<'_>) -> Result<BTreeMap<String, u64>> {
+        let mut sizes = BTreeMap::new();
+        let embedding_configs = self.embedding_configs(rtxn)?;
+        for config in embedding_configs {
+            let embedder_id = self.embedder_category_id.get(rtxn, &config.name)?.unwrap();
+            let reader = ArroyWrapper::new(
+                self.vector_arroy,
+                self.vector_scalar_quantized,
+                config.config.is_sparse().then_some(self.vector_sparse),
+                embedder_id,
+                config.config.quantization(),
+                config.config.distance(),
+            );
+            sizes.insert(config.name, reader.storage_size(rtxn)?);
+        }
+        Ok(sizes)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -17,14 +17,19 @@ mod external_documents_ids;
 pub mod facet;
 mod fields_ids_map;
 mod filterable_attributes_rules;
+pub mod geojson;
 pub mod heed_codec;
 pub mod index;
 mod localized_attributes_rules;
 pub mod order_by_map;
+pub mod pinning;
 pub mod prompt;
 pub mod proximity;
+pub mod ranking_expression;
+pub mod reranker;
 pub mod score_details;
 mod search;
+pub mod synonyms;
 mod thread_pool_no_abort;
 pub mod update;
 pub mod vector;
@@ -64,7 +69,7 @@ pub use self::external_documents_ids::ExternalDocumentsIds;
 pub use self::fieldids_weights_map::FieldidsWeightsMap;
 pub use self::fields_ids_map::{FieldsIdsMap, GlobalFieldsIdsMap};
 pub use self::filterable_attributes_rules::{
-    FilterFeatures, FilterableAttributesFeatures, FilterableAttributesPatterns,
+    DateFeatures, FilterFeatures, FilterableAttributesFeatures, FilterableAttributesPatterns,
     FilterableAttributesRule,
 };
 pub use self::heed_codec::{
@@ -75,12 +80,17 @@ pub use self::heed_codec::{
 };
 pub use self::index::Index;
 pub use self::localized_attributes_rules::LocalizedAttributesRule;
+pub use self::pinning::PinningRule;
+pub use self::reranker::{Reranker, RerankerSettings};
 pub use self::search::facet::{FacetValueHit, SearchForFacetValues};
+pub use self::search::hybrid::HybridFusion;
 pub use self::search::similar::Similar;
 pub use self::search::{
-    FacetDistribution, Filter, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, OrderBy,
-    Search, SearchResult, SemanticSearch, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    Boost, FacetDistribution, FacetRange, Filter, FormatOptions, MatchBounds, MatcherBuilder,
+    MatchingWords, OrderBy, Search, SearchResult, SemanticSearch, TermsMatchingStrategy,
+    DEFAULT_VALUES_PER_FACET,
 };
+pub use self::synonyms::SynonymSet;
 pub use self::update::ChannelCongestion;
 
 pub use arroy;
@@ -0,0 +1,76 @@
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A merchandising rule applied to the searches whose query matches the query of the rule.
+///
+/// The pinned documents are inserted at their position in the results
+/// and the hidden documents are removed from them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct PinningRule {
+    pub query: String,
+    #[serde(default)]
+    #[deserr(default)]
+    pub pin: Vec<PinnedDocument>,
+    #[serde(default)]
+    #[deserr(default)]
+    pub hide: Vec<String>,
+}
+
+/// A document pinned at a position of the results, the first position being `0`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct PinnedDocument {
+    pub id: String,
+    pub position: usize,
+}
+
+impl PinningRule {
+    /// Returns `true` if the rule applies to the given query.
+    ///
+    /// The queries are compared regardless of their case and of the whitespaces between their words.
+    pub fn matches(&self, query: &str) -> bool {
+        let words = query.split_whitespace().map(str::to_lowercase);
+        words.eq(self.query.split_whitespace().map(str::to_lowercase))
+    }
+}
+
+/// Returns the first rule of the list that applies to the given query.
+pub fn matching_rule<'a>(rules: &'a [PinningRule], query: &str) -> Option<&'a PinningRule> {
+    rules.iter().find(|rule| rule.matches(query))
+}
+
+#[cfg(test)]
+mod test {
+    use big_s::S;
+
+    use super::*;
+
+    fn rule(query: &str) -> PinningRule {
+        PinningRule { query: query.to_string(), pin: vec![], hide: vec![] }
+    }
+
+    #[test]
+    fn matches_query() {
+        assert!(rule("red shoes").matches("red shoes"));
+        assert!(rule("red shoes").matches("  Red   SHOES "));
+        assert!(rule("").matches(""));
+        assert!(rule("").matches("   "));
+        assert!(!rule("red shoes").matches("red"));
+        assert!(!rule("red shoes").matches("red shoes for kids"));
+        assert!(!rule("red").matches(""));
+    }
+
+    #[test]
+    fn first_matching_rule() {
+        let rules = vec![rule("shoes"), rule("red shoes"), rule("Red shoes")];
+        assert_eq!(
+            matching_rule(&rules, "red shoes").map(|rule| &rule.query),
+            Some(&S("red shoes"))
+        );
+        assert!(matching_rule(&rules, "blue shoes").is_none());
+    }
+}
@@ -0,0 +1,187 @@
+//! Splitting of the rendered documents into several chunks that are embedded separately.
+//!
+//! The text is first split into units, paragraphs or tokens depending on the strategy,
+//! then consecutive units are grouped into chunks of `size` units, consecutive chunks sharing `overlap` units.
+
+use std::num::NonZeroUsize;
+
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The maximum number of chunks of a document, as a document can have at most this number of embeddings.
+pub const MAX_CHUNKS: usize = u8::MAX as usize;
+
+const DEFAULT_PARAGRAPH_SIZE: usize = 1;
+const DEFAULT_TOKEN_SIZE: usize = 256;
+
+/// Describes how to split the rendered documents into several chunks, each of them being embedded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct Chunking {
+    /// The unit in which the text is split.
+    pub strategy: ChunkingStrategy,
+    /// The number of units of a chunk, must not be zero.
+    ///
+    /// Defaults to 1 paragraph or 256 tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub size: Option<usize>,
+    /// The number of units shared by two consecutive chunks, must be smaller than the size.
+    ///
+    /// Defaults to 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub overlap: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub enum ChunkingStrategy {
+    /// Paragraphs are separated by at least one empty line.
+    Paragraph,
+    /// Tokens are separated by whitespace.
+    Tokens,
+}
+
+impl Chunking {
+    pub fn size(&self) -> usize {
+        self.size.unwrap_or(match self.strategy {
+            ChunkingStrategy::Paragraph => DEFAULT_PARAGRAPH_SIZE,
+            ChunkingStrategy::Tokens => DEFAULT_TOKEN_SIZE,
+        })
+    }
+
+    pub fn overlap(&self) -> usize {
+        self.overlap.unwrap_or_default()
+    }
+
+    /// Splits the text into at most [`MAX_CHUNKS`] chunks, each of them truncated to `max_bytes`.
+    ///
+    /// A text without any unit is returned as a single chunk.
+    pub fn split<'t>(&self, text: &'t str, max_bytes: Option<NonZeroUsize>) -> Vec<&'t str> {
+        let units = match self.strategy {
+            ChunkingStrategy::Paragraph => paragraphs(text),
+            ChunkingStrategy::Tokens => tokens(text),
+        };
+        if units.is_empty() {
+            return vec![truncate(text, max_bytes)];
+        }
+
+        // a zero size or an overlap greater than the size are refused by the settings, be defensive anyway
+        let size = self.size().max(1);
+        let step = size.saturating_sub(self.overlap()).max(1);
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while chunks.len() < MAX_CHUNKS {
+            let end = (start + size).min(units.len());
+            let chunk = &text[units[start].0..units[end - 1].1];
+            chunks.push(truncate(chunk, max_bytes));
+            if end == units.len() {
+                break;
+            }
+            start += step;
+        }
+        chunks
+    }
+}
+
+/// The byte ranges of the paragraphs of the text, without their surrounding whitespace.
+fn paragraphs(text: &str) -> Vec<(usize, usize)> {
+    let mut paragraphs = Vec::new();
+    let mut offset = 0;
+    for paragraph in text.split("\n\n") {
+        let trimmed = paragraph.trim();
+        if !trimmed.is_empty() {
+            // trimmed is a subslice of the paragraph
+            let start = offset + (trimmed.as_ptr() as usize - paragraph.as_ptr() as usize);
+            paragraphs.push((start, start + trimmed.len()));
+        }
+        offset += paragraph.len() + "\n\n".len();
+    }
+    paragraphs
+}
+
+/// The byte ranges of the whitespace separated tokens of the text.
+fn tokens(text: &str) -> Vec<(usize, usize)> {
+    let mut tokens = Vec::new();
+    let mut token_start = None;
+    for (index, c) in text.char_indices() {
+        match (c.is_whitespace(), token_start) {
+            (true, Some(start)) => {
+                tokens.push((start, index));
+                token_start = None;
+            }
+            (false, None) => token_start = Some(index),
+            _ => (),
+        }
+    }
+    if let Some(start) = token_start {
+        tokens.push((start, text.len()));
+    }
+    tokens
+}
+
+fn truncate(text: &str, max_bytes: Option<NonZeroUsize>) -> &str {
+    let Some(max_bytes) = max_bytes.map(NonZeroUsize::get) else { return text };
+    if max_bytes >= text.len() {
+        return text;
+    }
+    let end = (0..=max_bytes).rev().find(|&i| text.is_char_boundary(i)).unwrap_or_default();
+    &text[..end]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chunking(strategy: ChunkingStrategy, size: usize, overlap: usize) -> Chunking {
+        Chunking { strategy, size: Some(size), overlap: Some(overlap) }
+    }
+
+    #[test]
+    fn split_paragraphs() {
+        let text = "first paragraph\n\n\n  second\nparagraph \n\nthird";
+        let chunks = chunking(ChunkingStrategy::Paragraph, 1, 0).split(text, None);
+        assert_eq!(chunks, vec!["first paragraph", "second\nparagraph", "third"]);
+
+        let chunks = chunking(ChunkingStrategy::Paragraph, 2, 1).split(text, None);
+        assert_eq!(
+            chunks,
+            vec!["first paragraph\n\n\n  second\nparagraph", "second\nparagraph \n\nthird"]
+        );
+    }
+
+    #[test]
+    fn split_tokens() {
+        let text = "a bb  ccc\tdddd e";
+        let chunks = chunking(ChunkingStrategy::Tokens, 2, 0).split(text, None);
+        assert_eq!(chunks, vec!["a bb", "ccc\tdddd", "e"]);
+
+        let chunks = chunking(ChunkingStrategy::Tokens, 3, 2).split(text, None);
+        assert_eq!(chunks, vec!["a bb  ccc", "bb  ccc\tdddd", "ccc\tdddd e"]);
+    }
+
+    #[test]
+    fn split_truncates_chunks() {
+        let text = "インテル ザー ビーグル";
+        let chunks = chunking(ChunkingStrategy::Tokens, 1, 0).split(text, NonZeroUsize::new(7));
+        assert_eq!(chunks, vec!["イン", "ザー", "ビー"]);
+    }
+
+    #[test]
+    fn split_without_units() {
+        let chunks = chunking(ChunkingStrategy::Paragraph, 1, 0).split("  \n\n ", None);
+        assert_eq!(chunks, vec!["  \n\n "]);
+    }
+
+    #[test]
+    fn split_at_most_max_chunks() {
+        let text = "a ".repeat(MAX_CHUNKS + 10);
+        let chunks = chunking(ChunkingStrategy::Tokens, 1, 0).split(&text, None);
+        assert_eq!(chunks.len(), MAX_CHUNKS);
+    }
+}
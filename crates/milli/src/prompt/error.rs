@@ -51,6 +51,20 @@ impl RenderPromptError {
     pub(crate) fn missing_context(inner: liquid::Error) -> RenderPromptError {
         Self { kind: RenderPromptErrorKind::MissingContext(inner), fault: FaultSource::User }
     }
+
+    pub(crate) fn invalid_media_with_external_docid(
+        external_docid: String,
+        message: String,
+    ) -> RenderPromptError {
+        Self {
+            kind: RenderPromptErrorKind::InvalidMediaWithExternalDocid(external_docid, message),
+            fault: FaultSource::User,
+        }
+    }
+
+    pub(crate) fn invalid_media(message: String) -> RenderPromptError {
+        Self { kind: RenderPromptErrorKind::InvalidMedia(message), fault: FaultSource::User }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -59,6 +73,10 @@ pub enum RenderPromptErrorKind {
     MissingContext(liquid::Error),
     #[error("missing field in document `{0}`: {1}")]
     MissingContextWithExternalDocid(String, liquid::Error),
+    #[error("invalid media in document: {0}")]
+    InvalidMedia(String),
+    #[error("invalid media in document `{0}`: {1}")]
+    InvalidMediaWithExternalDocid(String, String),
 }
 
 impl From<RenderPromptError> for crate::Error {
@@ -1,3 +1,4 @@
+pub mod chunking;
 mod context;
 mod document;
 pub(crate) mod error;
@@ -10,6 +11,7 @@ use std::fmt::Debug;
 use std::num::NonZeroUsize;
 
 use bumpalo::Bump;
+use chunking::Chunking;
 use document::ParseableDocument;
 use error::{NewPromptError, RenderPromptError};
 use fields::{BorrowedFields, OwnedFields};
@@ -17,24 +19,29 @@ use fields::{BorrowedFields, OwnedFields};
 use self::context::Context;
 use self::document::Document;
 use crate::fields_ids_map::metadata::FieldIdMapWithMetadata;
-use crate::update::del_add::DelAdd;
+use crate::update::del_add::{DelAdd, KvReaderDelAdd};
+use crate::vector::media::{DocumentMedia, MediaInput};
 use crate::GlobalFieldsIdsMap;
 
 pub struct Prompt {
     template: liquid::Template,
     template_text: String,
     max_bytes: Option<NonZeroUsize>,
+    chunking: Option<Chunking>,
+    media: Option<DocumentMedia>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PromptData {
     pub template: String,
     pub max_bytes: Option<NonZeroUsize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunking: Option<Chunking>,
 }
 
 impl From<Prompt> for PromptData {
     fn from(value: Prompt) -> Self {
-        Self { template: value.template_text, max_bytes: value.max_bytes }
+        Self { template: value.template_text, max_bytes: value.max_bytes, chunking: value.chunking }
     }
 }
 
@@ -42,7 +49,7 @@ impl TryFrom<PromptData> for Prompt {
     type Error = NewPromptError;
 
     fn try_from(value: PromptData) -> Result<Self, Self::Error> {
-        Prompt::new(value.template, value.max_bytes)
+        Ok(Prompt::new(value.template, value.max_bytes)?.with_chunking(value.chunking))
     }
 }
 
@@ -53,6 +60,8 @@ impl Clone for Prompt {
             template: new_template(&template_text).unwrap(),
             template_text,
             max_bytes: self.max_bytes,
+            chunking: self.chunking,
+            media: self.media.clone(),
         }
     }
 }
@@ -83,13 +92,19 @@ impl Default for Prompt {
             template: default_template(),
             template_text: default_template_text().into(),
             max_bytes: Some(default_max_bytes()),
+            chunking: None,
+            media: None,
         }
     }
 }
 
 impl Default for PromptData {
     fn default() -> Self {
-        Self { template: default_template_text().into(), max_bytes: Some(default_max_bytes()) }
+        Self {
+            template: default_template_text().into(),
+            max_bytes: Some(default_max_bytes()),
+            chunking: None,
+        }
     }
 }
 
@@ -103,6 +118,8 @@ impl Prompt {
                 .map_err(NewPromptError::cannot_parse_template)?,
             template_text: template,
             max_bytes,
+            chunking: None,
+            media: None,
         };
 
         // render template with special object that's OK with `doc.*` and `fields.*`
@@ -113,6 +130,34 @@ impl Prompt {
         Ok(this)
     }
 
+    /// Split the rendered documents into chunks that are embedded separately.
+    pub fn with_chunking(mut self, chunking: Option<Chunking>) -> Self {
+        self.chunking = chunking;
+        self
+    }
+
+    /// Send the media of the documents along with the rendered documents.
+    ///
+    /// The rendered documents are then [`MediaInput`]s encoding both the rendered text and the media.
+    pub fn with_media(mut self, media: Option<DocumentMedia>) -> Self {
+        self.media = media;
+        self
+    }
+
+    /// Splits a rendered document into the texts to embed.
+    ///
+    /// Without chunking, the whole rendered document is embedded.
+    /// With chunking, each chunk is truncated to the maximum number of bytes rather than the whole document.
+    ///
+    /// The settings forbid chunking along with media: a document rendered with its media is an encoded
+    /// [`MediaInput`] that must never be split, so it is always embedded whole.
+    pub fn split_into_chunks<'t>(&self, rendered: &'t str) -> Vec<&'t str> {
+        match (&self.chunking, &self.media) {
+            (Some(chunking), None) => chunking.split(rendered, self.max_bytes),
+            _ => vec![rendered],
+        }
+    }
+
     pub fn render_document<
         'a,       // lifetime of the borrow of the document
         'doc: 'a, // lifetime of the allocator, will live for an entire chunk of documents
@@ -123,6 +168,27 @@ impl Prompt {
         field_id_map: &RefCell<GlobalFieldsIdsMap>,
         doc_alloc: &'doc Bump,
     ) -> Result<&'doc str, RenderPromptError> {
+        let media = match &self.media {
+            Some(media) => {
+                let value = document
+                    .top_level_field(media.field())
+                    .map_err(|error| error.to_string())
+                    .and_then(|raw| {
+                        raw.map(|raw| serde_json::from_str(raw.get()))
+                            .transpose()
+                            .map_err(|error| error.to_string())
+                    })
+                    .and_then(|value| media.resolve(value))
+                    .map_err(|error| {
+                        RenderPromptError::invalid_media_with_external_docid(
+                            external_docid.to_owned(),
+                            error,
+                        )
+                    })?;
+                Some(value)
+            }
+            None => None,
+        };
         let document = ParseableDocument::new(document, doc_alloc);
         let fields = BorrowedFields::new(&document, field_id_map, doc_alloc);
         let context = Context::new(&document, &fields);
@@ -136,8 +202,14 @@ impl Prompt {
                 liquid_error,
             )
         })?;
-        Ok(std::str::from_utf8(rendered.into_bump_slice())
-            .expect("render can only write UTF-8 because all inputs and processing preserve utf-8"))
+        let rendered = std::str::from_utf8(rendered.into_bump_slice())
+            .expect("render can only write UTF-8 because all inputs and processing preserve utf-8");
+        Ok(match media {
+            Some(media) => {
+                doc_alloc.alloc_str(&MediaInput::new(Some(rendered), Some(media)).encode())
+            }
+            None => rendered,
+        })
     }
 
     pub fn render_kvdeladd(
@@ -146,20 +218,40 @@ impl Prompt {
         side: DelAdd,
         field_id_map: &FieldIdMapWithMetadata,
     ) -> Result<String, RenderPromptError> {
+        let media = match &self.media {
+            Some(media) => {
+                let value = field_id_map
+                    .as_fields_ids_map()
+                    .id(media.field())
+                    .and_then(|field_id| document.get(field_id))
+                    .and_then(|raw| KvReaderDelAdd::from_slice(raw).get(side))
+                    .map(serde_json::from_slice)
+                    .transpose()
+                    .map_err(|error| error.to_string())
+                    .and_then(|value| media.resolve(value))
+                    .map_err(RenderPromptError::invalid_media)?;
+                Some(value)
+            }
+            None => None,
+        };
         let document = Document::new(document, side, field_id_map.as_fields_ids_map());
         let fields = OwnedFields::new(&document, field_id_map);
         let context = Context::new(&document, &fields);
 
         let mut rendered =
             self.template.render(&context).map_err(RenderPromptError::missing_context)?;
-        if let Some(max_bytes) = self.max_bytes {
+        // when chunking, each chunk is truncated rather than the whole document
+        if let (Some(max_bytes), None) = (self.max_bytes, self.chunking) {
             truncate(&mut rendered, max_bytes.get());
         }
+        if let Some(media) = media {
+            rendered = MediaInput::new(Some(&rendered), Some(media)).encode();
+        }
         Ok(rendered)
     }
 }
 
-fn truncate(s: &mut String, max_bytes: usize) {
+pub(crate) fn truncate(s: &mut String, max_bytes: usize) {
     if max_bytes >= s.len() {
         return;
     }
@@ -0,0 +1,452 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// A numeric expression computed for every document and used as a ranking rule.
+///
+/// An expression is made of numbers, field names, the `+`, `-`, `*`, `/` operators,
+/// parentheses and a set of functions, e.g. `log(1 + sales) * 0.7 + rating * 0.3`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RankingExpression {
+    Number(f64),
+    Field(String),
+    Negate(Box<RankingExpression>),
+    Binary(Box<RankingExpression>, BinaryOperator, Box<RankingExpression>),
+    Function(Function, Vec<RankingExpression>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    Log,
+    Log10,
+    Log2,
+    Exp,
+    Sqrt,
+    Abs,
+    Floor,
+    Ceil,
+    Min,
+    Max,
+    Pow,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "log" => Some(Function::Log),
+            "log10" => Some(Function::Log10),
+            "log2" => Some(Function::Log2),
+            "exp" => Some(Function::Exp),
+            "sqrt" => Some(Function::Sqrt),
+            "abs" => Some(Function::Abs),
+            "floor" => Some(Function::Floor),
+            "ceil" => Some(Function::Ceil),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "pow" => Some(Function::Pow),
+            _ => None,
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Function::Min | Function::Max | Function::Pow => 2,
+            _ => 1,
+        }
+    }
+
+    fn apply(&self, args: &[f64]) -> f64 {
+        match (self, args) {
+            (Function::Log, [x]) => x.ln(),
+            (Function::Log10, [x]) => x.log10(),
+            (Function::Log2, [x]) => x.log2(),
+            (Function::Exp, [x]) => x.exp(),
+            (Function::Sqrt, [x]) => x.sqrt(),
+            (Function::Abs, [x]) => x.abs(),
+            (Function::Floor, [x]) => x.floor(),
+            (Function::Ceil, [x]) => x.ceil(),
+            (Function::Min, [x, y]) => x.min(*y),
+            (Function::Max, [x, y]) => x.max(*y),
+            (Function::Pow, [x, y]) => x.powf(*y),
+            _ => f64::NAN,
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Function::Log => "log",
+            Function::Log10 => "log10",
+            Function::Log2 => "log2",
+            Function::Exp => "exp",
+            Function::Sqrt => "sqrt",
+            Function::Abs => "abs",
+            Function::Floor => "floor",
+            Function::Ceil => "ceil",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Pow => "pow",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ExpressionError {
+    #[error("the expression is empty.")]
+    Empty,
+    #[error("unexpected character `{character}` at position {position}.")]
+    UnexpectedCharacter { character: char, position: usize },
+    #[error("unexpected `{token}` at position {position}.")]
+    UnexpectedToken { token: String, position: usize },
+    #[error("unexpected end of expression.")]
+    UnexpectedEnd,
+    #[error("`{number}` is not a valid number.")]
+    InvalidNumber { number: String },
+    #[error("unknown function `{name}`. Available functions are log, log10, log2, exp, sqrt, abs, floor, ceil, min, max and pow.")]
+    UnknownFunction { name: String },
+    #[error("the function `{function}` takes {expected} argument{} but {found} were given.", plural(.expected))]
+    InvalidArity { function: Function, expected: usize, found: usize },
+}
+
+fn plural(count: &usize) -> &'static str {
+    if *count > 1 {
+        "s"
+    } else {
+        ""
+    }
+}
+
+impl RankingExpression {
+    pub fn parse(text: &str) -> Result<Self, ExpressionError> {
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            return Err(ExpressionError::Empty);
+        }
+        let mut parser = Parser { tokens, position: 0 };
+        let expression = parser.expression()?;
+        match parser.tokens.get(parser.position) {
+            Some((token, position)) => Err(ExpressionError::UnexpectedToken {
+                token: token.to_string(),
+                position: *position,
+            }),
+            None => Ok(expression),
+        }
+    }
+
+    /// Returns the name of the fields used in the expression.
+    pub fn fields(&self) -> BTreeSet<&str> {
+        let mut fields = BTreeSet::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields<'a>(&'a self, fields: &mut BTreeSet<&'a str>) {
+        match self {
+            RankingExpression::Number(_) => (),
+            RankingExpression::Field(name) => {
+                fields.insert(name);
+            }
+            RankingExpression::Negate(expr) => expr.collect_fields(fields),
+            RankingExpression::Binary(left, _, right) => {
+                left.collect_fields(fields);
+                right.collect_fields(fields);
+            }
+            RankingExpression::Function(_, args) => {
+                args.iter().for_each(|arg| arg.collect_fields(fields))
+            }
+        }
+    }
+
+    /// Computes the value of the expression, fetching the value of the fields with `field_value`.
+    ///
+    /// Returns `None` if a field is missing or if the result is not a finite number.
+    pub fn evaluate(&self, field_value: &impl Fn(&str) -> Option<f64>) -> Option<f64> {
+        let value = match self {
+            RankingExpression::Number(number) => *number,
+            RankingExpression::Field(name) => field_value(name)?,
+            RankingExpression::Negate(expr) => -expr.evaluate(field_value)?,
+            RankingExpression::Binary(left, operator, right) => {
+                let left = left.evaluate(field_value)?;
+                let right = right.evaluate(field_value)?;
+                match operator {
+                    BinaryOperator::Add => left + right,
+                    BinaryOperator::Sub => left - right,
+                    BinaryOperator::Mul => left * right,
+                    BinaryOperator::Div => left / right,
+                }
+            }
+            RankingExpression::Function(function, args) => {
+                let args: Option<Vec<f64>> =
+                    args.iter().map(|arg| arg.evaluate(field_value)).collect();
+                function.apply(&args?)
+            }
+        };
+        value.is_finite().then_some(value)
+    }
+}
+
+impl FromStr for RankingExpression {
+    type Err = ExpressionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RankingExpression::parse(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Comma,
+    OpenParen,
+    CloseParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{number}"),
+            Token::Ident(ident) => f.write_str(ident),
+            Token::Plus => f.write_str("+"),
+            Token::Minus => f.write_str("-"),
+            Token::Star => f.write_str("*"),
+            Token::Slash => f.write_str("/"),
+            Token::Comma => f.write_str(","),
+            Token::OpenParen => f.write_str("("),
+            Token::CloseParen => f.write_str(")"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(position, c)) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            ',' => Token::Comma,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut end = position;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let number = &text[position..end];
+                let number = number
+                    .parse()
+                    .map_err(|_| ExpressionError::InvalidNumber { number: number.to_string() })?;
+                tokens.push((Token::Number(number), position));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = position;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push((Token::Ident(text[position..end].to_string()), position));
+                continue;
+            }
+            character => return Err(ExpressionError::UnexpectedCharacter { character, position }),
+        };
+        chars.next();
+        tokens.push((token, position));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<(Token, usize), ExpressionError> {
+        let token =
+            self.tokens.get(self.position).cloned().ok_or(ExpressionError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ExpressionError> {
+        match self.next()? {
+            (token, _) if token == expected => Ok(()),
+            (token, position) => {
+                Err(ExpressionError::UnexpectedToken { token: token.to_string(), position })
+            }
+        }
+    }
+
+    /// `expression := term (('+' | '-') term)*`
+    fn expression(&mut self) -> Result<RankingExpression, ExpressionError> {
+        let mut left = self.term()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Plus) => BinaryOperator::Add,
+                Some(Token::Minus) => BinaryOperator::Sub,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            let right = self.term()?;
+            left = RankingExpression::Binary(Box::new(left), operator, Box::new(right));
+        }
+    }
+
+    /// `term := factor (('*' | '/') factor)*`
+    fn term(&mut self) -> Result<RankingExpression, ExpressionError> {
+        let mut left = self.factor()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Star) => BinaryOperator::Mul,
+                Some(Token::Slash) => BinaryOperator::Div,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            let right = self.factor()?;
+            left = RankingExpression::Binary(Box::new(left), operator, Box::new(right));
+        }
+    }
+
+    /// `factor := '-' factor | number | field | function '(' args ')' | '(' expression ')'`
+    fn factor(&mut self) -> Result<RankingExpression, ExpressionError> {
+        match self.next()? {
+            (Token::Minus, _) => Ok(RankingExpression::Negate(Box::new(self.factor()?))),
+            (Token::Number(number), _) => Ok(RankingExpression::Number(number)),
+            (Token::Ident(name), _) if self.peek() == Some(&Token::OpenParen) => {
+                let function =
+                    Function::from_name(&name).ok_or(ExpressionError::UnknownFunction { name })?;
+                self.position += 1;
+                let mut args = vec![self.expression()?];
+                while self.peek() == Some(&Token::Comma) {
+                    self.position += 1;
+                    args.push(self.expression()?);
+                }
+                self.expect(Token::CloseParen)?;
+                if args.len() != function.arity() {
+                    return Err(ExpressionError::InvalidArity {
+                        function,
+                        expected: function.arity(),
+                        found: args.len(),
+                    });
+                }
+                Ok(RankingExpression::Function(function, args))
+            }
+            (Token::Ident(name), _) => Ok(RankingExpression::Field(name)),
+            (Token::OpenParen, _) => {
+                let expression = self.expression()?;
+                self.expect(Token::CloseParen)?;
+                Ok(expression)
+            }
+            (token, position) => {
+                Err(ExpressionError::UnexpectedToken { token: token.to_string(), position })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn evaluate(expression: &str, values: &[(&str, f64)]) -> Option<f64> {
+        let values: HashMap<_, _> = values.iter().copied().collect();
+        RankingExpression::parse(expression).unwrap().evaluate(&|name| values.get(name).copied())
+    }
+
+    #[test]
+    fn evaluate_expressions() {
+        assert_eq!(evaluate("1 + 2 * 3", &[]), Some(7.0));
+        assert_eq!(evaluate("(1 + 2) * 3", &[]), Some(9.0));
+        assert_eq!(evaluate("10 - 4 - 3", &[]), Some(3.0));
+        assert_eq!(evaluate("-2 * -3", &[]), Some(6.0));
+        assert_eq!(evaluate("rating * 0.5", &[("rating", 4.0)]), Some(2.0));
+        assert_eq!(
+            evaluate(
+                "max(price, 10) + min(2, stock.count)",
+                &[("price", 3.0), ("stock.count", 1.0)]
+            ),
+            Some(11.0)
+        );
+        assert_eq!(evaluate("pow(2, 10)", &[]), Some(1024.0));
+        assert_eq!(evaluate("floor(2.7) + ceil(0.2) + abs(-1)", &[]), Some(5.0));
+        assert_eq!(
+            evaluate(
+                "log(1 + sales) * 0.75 + rating * 0.25 - ageDays * 0.5",
+                &[("sales", 0.0), ("rating", 4.0), ("ageDays", 1.0)]
+            ),
+            Some(0.5)
+        );
+
+        // a missing field or a non-finite result doesn't give any value
+        assert_eq!(evaluate("rating * 2", &[]), None);
+        assert_eq!(evaluate("1 / 0", &[]), None);
+        assert_eq!(evaluate("log(0)", &[]), None);
+        assert_eq!(evaluate("sqrt(-1)", &[]), None);
+    }
+
+    #[test]
+    fn expression_fields() {
+        let expression = RankingExpression::parse("log(1 + sales) * rating - sales").unwrap();
+        assert_eq!(expression.fields().into_iter().collect::<Vec<_>>(), vec!["rating", "sales"]);
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        let invalid = [
+            ("", "the expression is empty."),
+            ("1 +", "unexpected end of expression."),
+            ("rating $ 2", "unexpected character `$` at position 7."),
+            ("(rating", "unexpected end of expression."),
+            ("rating)", "unexpected `)` at position 6."),
+            ("rating rating", "unexpected `rating` at position 7."),
+            ("1.2.3", "`1.2.3` is not a valid number."),
+            ("ln(2)", "unknown function `ln`. Available functions are log, log10, log2, exp, sqrt, abs, floor, ceil, min, max and pow."),
+            ("log(1, 2)", "the function `log` takes 1 argument but 2 were given."),
+            ("pow(2)", "the function `pow` takes 2 arguments but 1 were given."),
+        ];
+
+        for (input, expected) in invalid {
+            let error = RankingExpression::parse(input).unwrap_err();
+            assert_eq!(error.to_string(), expected, "Bad error for input `{input}`");
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! Reranking of the first hits of a search by a remote scoring service, e.g. a cross-encoder.
+//!
+//! The query and the rendered documents are injected in the `request` template,
+//! and the score of each document is extracted from the response with the `response` template.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use bumpalo::Bump;
+use deserr::Deserr;
+use heed::RoTxn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::prompt::{truncate, Prompt, PromptData};
+use crate::update::new::document::DocumentFromDb;
+use crate::vector::json_template::ValueTemplate;
+use crate::{DocumentId, GlobalFieldsIdsMap, Index, UserError};
+
+pub const QUERY_PLACEHOLDER: &str = "{{query}}";
+pub const DOCUMENT_PLACEHOLDER: &str = "{{text}}";
+pub const SCORE_PLACEHOLDER: &str = "{{score}}";
+pub const REPEAT_PLACEHOLDER: &str = "{{..}}";
+
+/// Reranking is done while the search request is pending, don't make the user wait for too long.
+const RERANK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The configuration of the remote service scoring the first hits of a search against its query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct RerankerSettings {
+    /// The URL the requests are sent to.
+    pub url: String,
+    /// Sent as a bearer token in the `Authorization` header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub api_key: Option<String>,
+    /// Template request, `"{{query}}"` is replaced by the query and the repeated `"{{text}}"` by the rendered documents.
+    #[schema(value_type = Object)]
+    pub request: Value,
+    /// Template response, indicating where the repeated `"{{score}}"` of the documents are found, in the order of the documents.
+    #[schema(value_type = Object)]
+    pub response: Value,
+    /// Additional headers to send to the service.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[deserr(default)]
+    pub headers: BTreeMap<String, String>,
+    /// The liquid template rendering the documents sent to the service, defaults to the template of the embedders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub document_template: Option<String>,
+    /// The maximum size of a rendered document, defaults to the maximum size of the embedders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub document_template_max_bytes: Option<usize>,
+}
+
+/// Sends the query and the rendered documents to the configured service and extracts their scores.
+#[derive(Debug)]
+pub struct Reranker {
+    client: ureq::Agent,
+    bearer: Option<String>,
+    headers: BTreeMap<String, String>,
+    url: String,
+    request: ValueTemplate,
+    response: ValueTemplate,
+    prompt: Prompt,
+    max_bytes: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RerankError {
+    #[error("could not reach the reranker: {0}")]
+    Network(Box<ureq::Transport>),
+    #[error("the reranker responded with status code `{0}`{}", .1.as_deref().map(|body| format!(": {body}")).unwrap_or_default())]
+    Status(u16, Option<String>),
+    #[error("could not deserialize the response of the reranker as JSON: {0}")]
+    Deserialization(std::io::Error),
+    #[error("error while extracting the scores from the response of the reranker: {0}")]
+    Extraction(String),
+    #[error("expected {expected} scores from the reranker, but got {got}")]
+    ScoreCount { expected: usize, got: usize },
+}
+
+impl Reranker {
+    pub fn new(settings: RerankerSettings) -> Result<Self, UserError> {
+        let RerankerSettings {
+            url,
+            api_key,
+            request,
+            response,
+            headers,
+            document_template,
+            document_template_max_bytes,
+        } = settings;
+
+        let invalid = |message: String| UserError::InvalidReranker(message);
+
+        if let Err(error) = url::Url::parse(&url) {
+            return Err(invalid(format!("`.reranker.url`: could not parse `{url}`: {error}")));
+        }
+
+        if !contains_placeholder(&request, QUERY_PLACEHOLDER) {
+            return Err(invalid(format!(
+                r#"`.reranker.request`: Expected "{QUERY_PLACEHOLDER}" in the request"#
+            )));
+        }
+        let request = ValueTemplate::new(request, DOCUMENT_PLACEHOLDER, REPEAT_PLACEHOLDER)
+            .map_err(|error| {
+                invalid(format!(
+                    "`.reranker.request`: {}",
+                    error.error_message("request", DOCUMENT_PLACEHOLDER, REPEAT_PLACEHOLDER)
+                ))
+            })?;
+        if !request.has_array_value() {
+            return Err(invalid(format!(
+                r#"`.reranker.request`: Expected "{DOCUMENT_PLACEHOLDER}" to be repeated with "{REPEAT_PLACEHOLDER}", as several documents are sent at once"#
+            )));
+        }
+
+        let response = ValueTemplate::new(response, SCORE_PLACEHOLDER, REPEAT_PLACEHOLDER)
+            .map_err(|error| {
+                invalid(format!(
+                    "`.reranker.response`: {}",
+                    error.error_message("response", SCORE_PLACEHOLDER, REPEAT_PLACEHOLDER)
+                ))
+            })?;
+        if !response.has_array_value() {
+            return Err(invalid(format!(
+                r#"`.reranker.response`: Expected "{SCORE_PLACEHOLDER}" to be repeated with "{REPEAT_PLACEHOLDER}", as several documents are scored at once"#
+            )));
+        }
+
+        let max_bytes = match document_template_max_bytes {
+            Some(max_bytes) => Some(NonZeroUsize::new(max_bytes).ok_or_else(|| {
+                invalid(
+                    "`.reranker.documentTemplateMaxBytes`: `documentTemplateMaxBytes` cannot be zero"
+                        .to_string(),
+                )
+            })?),
+            None => Some(crate::prompt::default_max_bytes()),
+        };
+        let template = document_template.unwrap_or_else(|| PromptData::default().template);
+        let prompt = Prompt::new(template, max_bytes).map_err(|error| {
+            invalid(format!("`.reranker.documentTemplate`: Invalid template: {error}."))
+        })?;
+
+        let client = ureq::AgentBuilder::new().timeout(RERANK_TIMEOUT).build();
+        let bearer = api_key.as_deref().map(|api_key| format!("Bearer {api_key}"));
+
+        Ok(Self { client, bearer, headers, url, request, response, prompt, max_bytes })
+    }
+
+    /// Renders the documents with the document template, in the order of the given ids.
+    pub fn render_documents<'t>(
+        &self,
+        index: &'t Index,
+        rtxn: &'t RoTxn<'t>,
+        docids: &[DocumentId],
+    ) -> crate::Result<Vec<String>> {
+        let db_fields_ids_map = index.fields_ids_map(rtxn)?;
+        let fields_ids_map = RwLock::new(index.fields_ids_map_with_metadata(rtxn)?);
+        let fields_ids_map = RefCell::new(GlobalFieldsIdsMap::new(&fields_ids_map));
+        let external_ids = index.external_id_of(rtxn, docids.iter().copied())?;
+
+        let mut doc_alloc = Bump::new();
+        let mut documents = Vec::with_capacity(docids.len());
+        for (&docid, external_id) in docids.iter().zip(external_ids) {
+            let external_id = external_id?;
+            let document = DocumentFromDb::new(docid, rtxn, index, &db_fields_ids_map)?
+                .ok_or(UserError::UnknownInternalDocumentId { document_id: docid })?;
+            let mut rendered = self
+                .prompt
+                .render_document(&external_id, document, &fields_ids_map, &doc_alloc)?
+                .to_string();
+            if let Some(max_bytes) = self.max_bytes {
+                truncate(&mut rendered, max_bytes.get());
+            }
+            documents.push(rendered);
+            doc_alloc.reset();
+        }
+        Ok(documents)
+    }
+
+    /// Returns the scores of the documents against the query, in the order of the documents.
+    pub fn rerank<S: AsRef<str>>(
+        &self,
+        query: &str,
+        documents: &[S],
+    ) -> Result<Vec<f64>, RerankError> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut body = self
+            .request
+            .inject(documents.iter().map(|document| Value::from(document.as_ref())))
+            // documents is not empty
+            .unwrap();
+        inject_query(&mut body, query);
+
+        let mut request = self.client.post(&self.url).set("Content-Type", "application/json");
+        if let Some(bearer) = &self.bearer {
+            request = request.set("Authorization", bearer);
+        }
+        for (header, value) in &self.headers {
+            request = request.set(header.as_str(), value.as_str());
+        }
+
+        let response = match request.send_json(&body) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                return Err(RerankError::Status(code, response.into_string().ok()))
+            }
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(RerankError::Network(Box::new(transport)))
+            }
+        };
+        let response: Value = response.into_json().map_err(RerankError::Deserialization)?;
+
+        let scores: Vec<f64> = self.response.extract(response).map_err(|error| {
+            RerankError::Extraction(error.error_message("response", SCORE_PLACEHOLDER, "a number"))
+        })?;
+        if scores.len() != documents.len() {
+            return Err(RerankError::ScoreCount { expected: documents.len(), got: scores.len() });
+        }
+        Ok(scores)
+    }
+}
+
+fn contains_placeholder(value: &Value, placeholder: &str) -> bool {
+    match value {
+        Value::String(s) => s == placeholder,
+        Value::Array(values) => values.iter().any(|value| contains_placeholder(value, placeholder)),
+        Value::Object(map) => map.values().any(|value| contains_placeholder(value, placeholder)),
+        Value::Null | Value::Bool(_) | Value::Number(_) => false,
+    }
+}
+
+/// Replaces all the query placeholders of the rendered request by the query.
+fn inject_query(value: &mut Value, query: &str) {
+    match value {
+        Value::String(s) if s == QUERY_PLACEHOLDER => *value = Value::from(query),
+        Value::Array(values) => values.iter_mut().for_each(|value| inject_query(value, query)),
+        Value::Object(map) => map.values_mut().for_each(|value| inject_query(value, query)),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn settings(request: Value, response: Value) -> RerankerSettings {
+        RerankerSettings {
+            url: "http://localhost:7777/rerank".to_string(),
+            api_key: None,
+            request,
+            response,
+            headers: Default::default(),
+            document_template: None,
+            document_template_max_bytes: None,
+        }
+    }
+
+    #[test]
+    fn parse_templates() {
+        Reranker::new(settings(
+            json!({ "query": "{{query}}", "documents": ["{{text}}", "{{..}}"] }),
+            json!({ "results": [{ "score": "{{score}}" }, "{{..}}"] }),
+        ))
+        .unwrap();
+
+        let error = Reranker::new(settings(
+            json!({ "documents": ["{{text}}", "{{..}}"] }),
+            json!(["{{score}}", "{{..}}"]),
+        ))
+        .unwrap_err();
+        assert!(error.to_string().contains(r#"Expected "{{query}}""#), "{error}");
+
+        let error = Reranker::new(settings(
+            json!({ "query": "{{query}}", "document": "{{text}}" }),
+            json!(["{{score}}", "{{..}}"]),
+        ))
+        .unwrap_err();
+        assert!(error.to_string().contains("`.reranker.request`"), "{error}");
+
+        let error = Reranker::new(settings(
+            json!({ "query": "{{query}}", "documents": ["{{text}}", "{{..}}"] }),
+            json!({ "score": "{{score}}" }),
+        ))
+        .unwrap_err();
+        assert!(error.to_string().contains("`.reranker.response`"), "{error}");
+    }
+
+    #[test]
+    fn inject_query_everywhere() {
+        let mut value = json!({ "query": "{{query}}", "nested": [{ "q": "{{query}}" }], "other": "{{query}} " });
+        inject_query(&mut value, "kefir");
+        assert_eq!(
+            value,
+            json!({ "query": "kefir", "nested": [{ "q": "kefir" }], "other": "{{query}} " })
+        );
+    }
+}
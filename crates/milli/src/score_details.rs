@@ -17,6 +17,11 @@ pub enum ScoreDetails {
     Sort(Sort),
     Vector(Vector),
     GeoSort(GeoSort),
+    Expression(Expression),
+    Boost(Boost),
+    Pinned(Pinned),
+    Fusion(Fusion),
+    Rerank(Rerank),
 
     /// Returned when we don't have the time to finish applying all the subsequent ranking-rules
     Skipped,
@@ -27,12 +32,20 @@ pub enum ScoreValue<'a> {
     Score(f64),
     Sort(&'a Sort),
     GeoSort(&'a GeoSort),
+    Expression(&'a Expression),
+    Boost(&'a Boost),
+    Pinned(&'a Pinned),
 }
 
 enum RankOrValue<'a> {
     Rank(Rank),
     Sort(&'a Sort),
     GeoSort(&'a GeoSort),
+    Expression(&'a Expression),
+    Boost(&'a Boost),
+    Pinned(&'a Pinned),
+    Fusion(&'a Fusion),
+    Rerank(&'a Rerank),
     Score(f64),
 }
 
@@ -61,6 +74,11 @@ impl ScoreDetails {
             ScoreDetails::ExactWords(details) => Some(details.rank()),
             ScoreDetails::Sort(_) => None,
             ScoreDetails::GeoSort(_) => None,
+            ScoreDetails::Expression(_) => None,
+            ScoreDetails::Boost(_) => None,
+            ScoreDetails::Pinned(_) => None,
+            ScoreDetails::Fusion(_) => None,
+            ScoreDetails::Rerank(_) => None,
             ScoreDetails::Vector(_) => None,
             ScoreDetails::Skipped => Some(Rank { rank: 0, max_rank: 1 }),
         }
@@ -92,6 +110,11 @@ impl ScoreDetails {
                 RankOrValue::Rank(r) => ScoreValue::Score(r.local_score()),
                 RankOrValue::Sort(s) => ScoreValue::Sort(s),
                 RankOrValue::GeoSort(g) => ScoreValue::GeoSort(g),
+                RankOrValue::Expression(e) => ScoreValue::Expression(e),
+                RankOrValue::Boost(b) => ScoreValue::Boost(b),
+                RankOrValue::Pinned(p) => ScoreValue::Pinned(p),
+                RankOrValue::Fusion(f) => ScoreValue::Score(f.score()),
+                RankOrValue::Rerank(r) => ScoreValue::Score(r.ranking_score()),
                 RankOrValue::Score(s) => ScoreValue::Score(s),
             })
     }
@@ -116,6 +139,22 @@ impl ScoreDetails {
                 RankOrValue::GeoSort(g) => {
                     WeightedScoreValue::GeoSort { asc: g.ascending, distance: g.distance() }
                 }
+                // expressions are compared like a descending sort on their computed value
+                RankOrValue::Expression(e) => {
+                    WeightedScoreValue::Sort { asc: false, value: e.json_value() }
+                }
+                // as are boosts on the sum of the weights of the boosts matched by the document
+                RankOrValue::Boost(b) => {
+                    WeightedScoreValue::Sort { asc: false, value: serde_json::json!(b.weight) }
+                }
+                // and pinned documents like an ascending sort on their position
+                RankOrValue::Pinned(p) => {
+                    WeightedScoreValue::Sort { asc: true, value: serde_json::json!(p.position) }
+                }
+                RankOrValue::Fusion(f) => WeightedScoreValue::WeightedScore(f.score() * weight),
+                RankOrValue::Rerank(r) => {
+                    WeightedScoreValue::WeightedScore(r.ranking_score() * weight)
+                }
                 RankOrValue::Score(s) => WeightedScoreValue::VectorSort(s * weight),
             })
     }
@@ -131,6 +170,11 @@ impl ScoreDetails {
             ScoreDetails::ExactWords(e) => RankOrValue::Rank(e.rank()),
             ScoreDetails::Sort(sort) => RankOrValue::Sort(sort),
             ScoreDetails::GeoSort(geosort) => RankOrValue::GeoSort(geosort),
+            ScoreDetails::Expression(expression) => RankOrValue::Expression(expression),
+            ScoreDetails::Boost(boost) => RankOrValue::Boost(boost),
+            ScoreDetails::Pinned(pinned) => RankOrValue::Pinned(pinned),
+            ScoreDetails::Fusion(fusion) => RankOrValue::Fusion(fusion),
+            ScoreDetails::Rerank(rerank) => RankOrValue::Rerank(rerank),
             ScoreDetails::Vector(vector) => {
                 RankOrValue::Score(vector.similarity.as_ref().map(|s| *s as f64).unwrap_or(0.0f64))
             }
@@ -281,13 +325,64 @@ impl ScoreDetails {
                     details_map.insert(sort, sort_details);
                     order += 1;
                 }
+                ScoreDetails::Expression(details) => {
+                    let expression = format!("expression:{}", details.expression);
+                    let expression_details = serde_json::json!({
+                        "order": order,
+                        "value": details.json_value(),
+                    });
+                    details_map.insert(expression, expression_details);
+                    order += 1;
+                }
+                ScoreDetails::Boost(details) => {
+                    let boost_details = serde_json::json!({
+                        "order": order,
+                        "weight": details.weight,
+                    });
+                    details_map.insert("boost".into(), boost_details);
+                    order += 1;
+                }
+                ScoreDetails::Pinned(details) => {
+                    let pinned_details = serde_json::json!({
+                        "order": order,
+                        "position": details.position,
+                    });
+                    details_map.insert("pinned".into(), pinned_details);
+                    order += 1;
+                }
+                ScoreDetails::Fusion(details) => {
+                    let mut fusion_details = serde_json::json!({
+                        "order": order,
+                        "keywordRank": details.keyword_rank,
+                        "semanticRank": details.semantic_rank,
+                        "rankConstant": details.rank_constant,
+                        "score": details.score(),
+                    });
+                    if details.with_sparse {
+                        fusion_details["sparseRank"] = serde_json::json!(details.sparse_rank);
+                    }
+                    details_map.insert("fusion".into(), fusion_details);
+                    order += 1;
+                }
+                ScoreDetails::Rerank(details) => {
+                    let rerank_details = serde_json::json!({
+                        "order": order,
+                        "rank": details.rank,
+                        "score": details.score,
+                    });
+                    details_map.insert("rerank".into(), rerank_details);
+                    order += 1;
+                }
                 ScoreDetails::Vector(s) => {
                     let similarity = s.similarity.as_ref();
 
-                    let details = serde_json::json!({
+                    let mut details = serde_json::json!({
                         "order": order,
                         "similarity": similarity,
                     });
+                    if let Some(chunk) = s.chunk {
+                        details["chunk"] = chunk.into();
+                    }
                     details_map.insert("vectorSort".into(), details);
                     order += 1;
                 }
@@ -540,9 +635,120 @@ impl PartialOrd for GeoSort {
     }
 }
 
+/// The value computed for a document by an expression ranking rule.
+///
+/// The value is `None` when the expression couldn't be computed for the document,
+/// e.g. because one of its fields was missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub expression: String,
+    pub value: Option<f64>,
+}
+
+impl Expression {
+    pub fn json_value(&self) -> serde_json::Value {
+        self.value
+            .and_then(serde_json::Number::from_f64)
+            .map_or(serde_json::Value::Null, serde_json::Value::Number)
+    }
+}
+
+impl PartialOrd for Expression {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.expression != other.expression {
+            return None;
+        }
+        // the higher the value the better
+        Some(match (self.value, other.value) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(left), Some(right)) => left.partial_cmp(&right)?,
+        })
+    }
+}
+
+/// The sum of the weights of the boosts matched by a document.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Boost {
+    pub weight: f64,
+}
+
+/// A document pinned at a position of the results by a pinning rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pinned {
+    pub position: usize,
+}
+
+impl PartialOrd for Pinned {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // the lower the position the better
+        Some(other.position.cmp(&self.position))
+    }
+}
+
+/// The ranks of a document in the keyword and semantic results of a hybrid search,
+/// fused with the Reciprocal Rank Fusion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fusion {
+    /// The 1-based rank of the document in the keyword results, if it appears in them.
+    pub keyword_rank: Option<u32>,
+    /// The 1-based rank of the document in the semantic results, if it appears in them.
+    pub semantic_rank: Option<u32>,
+    /// The 1-based rank of the document in the sparse results, if it appears in them.
+    pub sparse_rank: Option<u32>,
+    /// Whether a sparse search was fused with the keyword and semantic results.
+    pub with_sparse: bool,
+    pub rank_constant: u32,
+    pub semantic_ratio: f32,
+}
+
+impl Fusion {
+    /// The fused score, normalized so that a document ranked first on all sides has a score of 1.
+    ///
+    /// The semantic and sparse results share the semantic ratio equally.
+    pub fn score(&self) -> f64 {
+        let reciprocal_rank = |rank: Option<u32>| {
+            rank.map_or(0.0, |rank| 1.0 / (self.rank_constant as f64 + rank as f64))
+        };
+        let semantic_ratio = self.semantic_ratio as f64;
+        let semantic = if self.with_sparse {
+            (reciprocal_rank(self.semantic_rank) + reciprocal_rank(self.sparse_rank)) / 2.0
+        } else {
+            reciprocal_rank(self.semantic_rank)
+        };
+        let fused =
+            (1.0 - semantic_ratio) * reciprocal_rank(self.keyword_rank) + semantic_ratio * semantic;
+        fused * (self.rank_constant as f64 + 1.0)
+    }
+}
+
+/// The score given to a document by the reranker of the index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rerank {
+    /// The 1-based rank of the document among the reranked documents.
+    pub rank: u32,
+    /// The score returned by the reranker.
+    pub score: f64,
+}
+
+impl Rerank {
+    /// The score returned by the reranker, expected between 0 and 1 like the other ranking scores.
+    pub fn ranking_score(&self) -> f64 {
+        if self.score.is_nan() {
+            0.0
+        } else {
+            self.score.clamp(0.0, 1.0)
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Vector {
     pub similarity: Option<f32>,
+    /// The position of the embedding of the document that matched,
+    /// e.g. its chunk when the embedder splits documents into chunks.
+    pub chunk: Option<u8>,
 }
 
 impl GeoSort {
@@ -1,24 +1,28 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Display;
-use std::ops::ControlFlow;
+use std::ops::{Bound, ControlFlow};
 use std::{fmt, mem};
 
+use deserr::Deserr;
 use heed::types::Bytes;
 use heed::BytesDecode;
 use indexmap::IndexMap;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::attribute_patterns::match_field_legacy;
-use crate::facet::FacetType;
+use crate::facet::{DateBucket, FacetType};
 use crate::filterable_attributes_rules::{filtered_matching_patterns, matching_features};
 use crate::heed_codec::facet::{
-    FacetGroupKeyCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec,
+    OrderedF64Codec,
 };
 use crate::heed_codec::{BytesRefCodec, StrRefCodec};
 use crate::search::facet::facet_distribution_iter::{
     count_iterate_over_facet_distribution, lexicographically_iterate_over_facet_distribution,
 };
+use crate::search::facet::facet_range_search::find_docids_of_facet_within_bounds;
 use crate::{Error, FieldId, FilterableAttributesRule, Index, PatternMatch, Result, UserError};
 
 /// The default number of values by facets that will
@@ -48,6 +52,20 @@ impl Display for OrderBy {
     }
 }
 
+/// A range of numbers, including `from` and excluding `to`.
+///
+/// A missing bound means that the range is unbounded on this side.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, Deserr, ToSchema)]
+#[deserr(deny_unknown_fields)]
+pub struct FacetRange {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub from: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub to: Option<f64>,
+}
+
 pub struct FacetDistribution<'a> {
     facets: Option<HashMap<String, OrderBy>>,
     candidates: Option<RoaringBitmap>,
@@ -287,6 +305,75 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
+    /// Groups the values of a date facet by bucket.
+    ///
+    /// The dates are stored as timestamps in the number database, in chronological order,
+    /// so the values of a bucket are contiguous. The strings that are not dates are returned as is.
+    fn facet_date_values(
+        &self,
+        field_id: FieldId,
+        bucket: DateBucket,
+        order_by: OrderBy,
+    ) -> heed::Result<IndexMap<String, u64>> {
+        let universe;
+        let candidates = match &self.candidates {
+            Some(cnd) => cnd,
+            None => {
+                universe = self.index.documents_ids(self.rtxn)?;
+                &universe
+            }
+        };
+
+        let mut buckets = Vec::new();
+        let mut current: Option<(String, RoaringBitmap)> = None;
+        let db =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: &[][..] };
+        for result in db.prefix_iter(self.rtxn, &prefix)? {
+            let (key, value) = result?;
+            let timestamp = OrderedF64Codec::bytes_decode(key.left_bound).unwrap();
+            let Some(label) = bucket.label(timestamp) else { continue };
+            let docids = value.bitmap & candidates;
+            if docids.is_empty() {
+                continue;
+            }
+
+            match &mut current {
+                Some((current_label, current_docids)) if *current_label == label => {
+                    *current_docids |= docids;
+                }
+                _ => {
+                    buckets.extend(current.replace((label, docids)));
+                    if order_by == OrderBy::Lexicographic
+                        && buckets.len() == self.max_values_per_facet
+                    {
+                        current = None;
+                        break;
+                    }
+                }
+            }
+        }
+        buckets.extend(current);
+
+        if order_by == OrderBy::Count {
+            buckets.sort_by(|(_, a), (_, b)| b.len().cmp(&a.len()));
+        }
+        buckets.truncate(self.max_values_per_facet);
+
+        let mut distribution: IndexMap<_, _> =
+            buckets.into_iter().map(|(label, docids)| (label, docids.len())).collect();
+        if distribution.len() < self.max_values_per_facet {
+            self.facet_strings_distribution_from_facet_levels(
+                field_id,
+                candidates,
+                order_by,
+                &mut distribution,
+            )?;
+        }
+
+        Ok(distribution)
+    }
+
     pub fn compute_stats(&self) -> Result<BTreeMap<String, (f64, f64)>> {
         let candidates = if let Some(candidates) = self.candidates.clone() {
             candidates
@@ -329,6 +416,124 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
+    /// Count the candidates whose value falls in each of the given ranges.
+    pub fn compute_ranges(
+        &self,
+        ranges: &BTreeMap<String, Vec<FacetRange>>,
+    ) -> Result<BTreeMap<String, Vec<(FacetRange, u64)>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
+        Self::check_filterable_fields(
+            ranges.keys().map(String::as_str),
+            &filterable_attributes_rules,
+        )?;
+        let candidates = self.candidates_or_all_documents()?;
+
+        let mut counts = BTreeMap::new();
+        for (name, ranges) in ranges {
+            let fid = fields_ids_map.id(name);
+            let mut range_counts = Vec::with_capacity(ranges.len());
+            for &range in ranges {
+                let count = match fid {
+                    Some(fid) => self.count_in_range(fid, range, &candidates)?,
+                    None => 0,
+                };
+                range_counts.push((range, count));
+            }
+            counts.insert(name.to_string(), range_counts);
+        }
+
+        Ok(counts)
+    }
+
+    /// Count the candidates in fixed-size buckets going from the lowest to the highest value of the field.
+    ///
+    /// The buckets are aligned on multiples of the interval and empty buckets are returned too,
+    /// up to the maximum number of values per facet.
+    pub fn compute_histogram(
+        &self,
+        intervals: &BTreeMap<String, f64>,
+    ) -> Result<BTreeMap<String, Vec<(FacetRange, u64)>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
+        Self::check_filterable_fields(
+            intervals.keys().map(String::as_str),
+            &filterable_attributes_rules,
+        )?;
+        let candidates = self.candidates_or_all_documents()?;
+
+        let mut histograms = BTreeMap::new();
+        for (name, &interval) in intervals {
+            let mut buckets = Vec::new();
+            let fid = fields_ids_map.id(name).filter(|_| interval.is_finite() && interval > 0.0);
+            if let Some(fid) = fid {
+                let min = crate::search::facet::facet_min_value(
+                    self.index,
+                    self.rtxn,
+                    fid,
+                    candidates.clone(),
+                )?;
+                let max = crate::search::facet::facet_max_value(
+                    self.index,
+                    self.rtxn,
+                    fid,
+                    candidates.clone(),
+                )?;
+
+                if let (Some(min), Some(max)) = (min, max) {
+                    let first = (min / interval).floor() * interval;
+                    // computing each bound from the first one avoids accumulating rounding errors
+                    for i in 0..self.max_values_per_facet {
+                        let from = first + i as f64 * interval;
+                        if from > max {
+                            break;
+                        }
+                        let range = FacetRange { from: Some(from), to: Some(from + interval) };
+                        buckets.push((range, self.count_in_range(fid, range, &candidates)?));
+                    }
+                }
+            }
+            histograms.insert(name.to_string(), buckets);
+        }
+
+        Ok(histograms)
+    }
+
+    fn candidates_or_all_documents(&self) -> heed::Result<RoaringBitmap> {
+        match &self.candidates {
+            Some(candidates) => Ok(candidates.clone()),
+            None => self.index.documents_ids(self.rtxn),
+        }
+    }
+
+    fn count_in_range(
+        &self,
+        field_id: FieldId,
+        range: FacetRange,
+        candidates: &RoaringBitmap,
+    ) -> Result<u64> {
+        if let (Some(from), Some(to)) = (range.from, range.to) {
+            if from >= to {
+                return Ok(0);
+            }
+        }
+
+        let left = range.from.map_or(Bound::Unbounded, Bound::Included);
+        let right = range.to.map_or(Bound::Unbounded, Bound::Excluded);
+        let mut docids = RoaringBitmap::new();
+        find_docids_of_facet_within_bounds::<OrderedF64Codec>(
+            self.rtxn,
+            self.index.facet_id_f64_docids,
+            field_id,
+            &left,
+            &right,
+            Some(candidates),
+            &mut docids,
+        )?;
+
+        Ok(docids.intersection_len(candidates))
+    }
+
     pub fn execute(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
         let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
         let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
@@ -342,7 +547,12 @@ impl<'a> FacetDistribution<'a> {
                     .as_ref()
                     .and_then(|facets| facets.get(name).copied())
                     .unwrap_or(self.default_order_by);
-                let values = self.facet_values(fid, order_by)?;
+                let date_bucket = matching_features(name, &filterable_attributes_rules)
+                    .and_then(|(_, features)| features.date_bucket());
+                let values = match date_bucket {
+                    Some(bucket) => self.facet_date_values(fid, bucket, order_by)?,
+                    None => self.facet_values(fid, order_by)?,
+                };
                 distribution.insert(name.to_string(), values);
             }
         }
@@ -376,23 +586,35 @@ impl<'a> FacetDistribution<'a> {
     fn check_faceted_fields(
         &self,
         filterable_attributes_rules: &[FilterableAttributesRule],
+    ) -> Result<()> {
+        match &self.facets {
+            Some(facets) => Self::check_filterable_fields(
+                facets.keys().map(String::as_str),
+                filterable_attributes_rules,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Check if the given fields are valid filterable fields.
+    fn check_filterable_fields<'f>(
+        fields: impl IntoIterator<Item = &'f str>,
+        filterable_attributes_rules: &[FilterableAttributesRule],
     ) -> Result<()> {
         let mut invalid_facets = BTreeSet::new();
         let mut matching_rule_indices = HashMap::new();
 
-        if let Some(facets) = &self.facets {
-            for field in facets.keys() {
-                let matched_rule = matching_features(field, filterable_attributes_rules);
-                let is_filterable = matched_rule.is_some_and(|(_, f)| f.is_filterable());
+        for field in fields {
+            let matched_rule = matching_features(field, filterable_attributes_rules);
+            let is_filterable = matched_rule.is_some_and(|(_, f)| f.is_filterable());
 
-                if !is_filterable {
-                    invalid_facets.insert(field.to_string());
+            if !is_filterable {
+                invalid_facets.insert(field.to_string());
 
-                    // If the field matched a rule but that rule doesn't enable filtering,
-                    // store the rule index for better error messages
-                    if let Some((rule_index, _)) = matched_rule {
-                        matching_rule_indices.insert(field.to_string(), rule_index);
-                    }
+                // If the field matched a rule but that rule doesn't enable filtering,
+                // store the rule index for better error messages
+                if let Some((rule_index, _)) = matched_rule {
+                    matching_rule_indices.insert(field.to_string(), rule_index);
                 }
             }
         }
@@ -438,13 +660,14 @@ impl fmt::Debug for FacetDistribution<'_> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::iter;
 
     use big_s::S;
 
     use crate::documents::mmap_from_objects;
     use crate::index::tests::TempIndex;
-    use crate::{milli_snap, FacetDistribution, FilterableAttributesRule, OrderBy};
+    use crate::{milli_snap, FacetDistribution, FacetRange, FilterableAttributesRule, OrderBy};
 
     #[test]
     fn few_candidates_few_facet_values() {
@@ -886,4 +1109,67 @@ mod tests {
 
         milli_snap!(format!("{map:?}"), "candidates_217_777", @r###"{"colour": (218.0, 1776.0)}"###);
     }
+
+    #[test]
+    fn facet_ranges_and_histogram() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("price"))])
+            })
+            .unwrap();
+
+        let mut documents = vec![];
+        for i in 0..100 {
+            let document = serde_json::json!({ "id": i, "price": i });
+            documents.push(document.as_object().unwrap().clone());
+        }
+
+        let documents = mmap_from_objects(documents);
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let ranges = BTreeMap::from([(
+            S("price"),
+            vec![
+                FacetRange { from: None, to: Some(10.0) },
+                FacetRange { from: Some(10.0), to: Some(50.0) },
+                FacetRange { from: Some(50.0), to: None },
+            ],
+        )]);
+
+        let map = FacetDistribution::new(&txn, &index).compute_ranges(&ranges).unwrap();
+        let counts: Vec<_> = map["price"].iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, [10, 40, 50]);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .candidates((0..30).collect())
+            .compute_ranges(&ranges)
+            .unwrap();
+        let counts: Vec<_> = map["price"].iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, [10, 20, 0]);
+
+        let intervals = BTreeMap::from([(S("price"), 25.0)]);
+        let map = FacetDistribution::new(&txn, &index)
+            .candidates((5..60).collect())
+            .compute_histogram(&intervals)
+            .unwrap();
+        let buckets: Vec<_> = map["price"]
+            .iter()
+            .map(|(range, count)| (range.from.unwrap(), range.to.unwrap(), *count))
+            .collect();
+        assert_eq!(buckets, [(0.0, 25.0, 20), (25.0, 50.0, 25), (50.0, 75.0, 10)]);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .max_values_per_facet(2)
+            .compute_histogram(&intervals)
+            .unwrap();
+        assert_eq!(map["price"].len(), 2);
+
+        let ranges = BTreeMap::from([(S("id"), vec![FacetRange::default()])]);
+        let error = FacetDistribution::new(&txn, &index).compute_ranges(&ranges).unwrap_err();
+        assert!(error.to_string().contains("Invalid facet distribution"), "{error}");
+    }
 }
@@ -6,13 +6,17 @@ use either::Either;
 pub use filter_parser::{Condition, Error as FPError, FilterCondition, Token};
 use heed::types::LazyDecode;
 use memchr::memmem::Finder;
+use regex::RegexBuilder;
 use roaring::{MultiOps, RoaringBitmap};
+use rstar::AABB;
 use serde_json::Value;
 
 use super::facet_range_search;
 use crate::constants::RESERVED_GEO_FIELD_NAME;
 use crate::error::{Error, UserError};
+use crate::facet::parse_date_facet;
 use crate::filterable_attributes_rules::{filtered_matching_patterns, matching_features};
+use crate::geojson::{polygon_contains, GeoJsonGeometry};
 use crate::heed_codec::facet::{
     FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec, OrderedF64Codec,
 };
@@ -304,19 +308,29 @@ impl<'a> Filter<'a> {
                     rtxn, index, field_id, operator, features, rule_index,
                 ));
             }
+            Condition::Matches { .. } | Condition::Like { .. }
+                if !features.is_filterable_pattern() =>
+            {
+                return Err(generate_filter_error(
+                    rtxn, index, field_id, operator, features, rule_index,
+                ));
+            }
             Condition::GreaterThan(val) => {
-                (Excluded(val.parse_finite_float()?), Included(f64::MAX))
+                (Excluded(parse_facet_number(val, features)?), Included(f64::MAX))
             }
             Condition::GreaterThanOrEqual(val) => {
-                (Included(val.parse_finite_float()?), Included(f64::MAX))
+                (Included(parse_facet_number(val, features)?), Included(f64::MAX))
             }
-            Condition::LowerThan(val) => (Included(f64::MIN), Excluded(val.parse_finite_float()?)),
-            Condition::LowerThanOrEqual(val) => {
-                (Included(f64::MIN), Included(val.parse_finite_float()?))
+            Condition::LowerThan(val) => {
+                (Included(f64::MIN), Excluded(parse_facet_number(val, features)?))
             }
-            Condition::Between { from, to } => {
-                (Included(from.parse_finite_float()?), Included(to.parse_finite_float()?))
+            Condition::LowerThanOrEqual(val) => {
+                (Included(f64::MIN), Included(parse_facet_number(val, features)?))
             }
+            Condition::Between { from, to } => (
+                Included(parse_facet_number(from, features)?),
+                Included(parse_facet_number(to, features)?),
+            ),
             Condition::Null => {
                 let is_null = index.null_faceted_documents_ids(rtxn, field_id)?;
                 return Ok(is_null);
@@ -341,7 +355,7 @@ impl<'a> Filter<'a> {
                     )?
                     .map(|v| v.bitmap)
                     .unwrap_or_default();
-                let number = val.parse_finite_float().ok();
+                let number = parse_facet_number(val, features).ok();
                 let number_docids = match number {
                     Some(n) => numbers_db
                         .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: n })?
@@ -412,6 +426,29 @@ impl<'a> Filter<'a> {
 
                 return Ok(docids);
             }
+            Condition::Matches { keyword: _, pattern } => {
+                // The facet strings are stored normalized (lowercased), we therefore match
+                // the whole value case-insensitively instead of normalizing the regex itself.
+                let regex = build_pattern_regex(&format!("^(?:{})$", pattern.value()))
+                    .map_err(|e| pattern.as_external_error(e))?;
+
+                return Self::evaluate_facet_string_pattern(rtxn, index, field_id, "", |value| {
+                    regex.is_match(value)
+                });
+            }
+            Condition::Like { keyword: _, pattern } => {
+                let (prefix, regex) = like_pattern_to_regex(pattern.value());
+                let regex =
+                    build_pattern_regex(&regex).map_err(|e| pattern.as_external_error(e))?;
+
+                return Self::evaluate_facet_string_pattern(
+                    rtxn,
+                    index,
+                    field_id,
+                    &prefix,
+                    |value| regex.is_match(value),
+                );
+            }
         };
 
         let mut output = RoaringBitmap::new();
@@ -427,6 +464,43 @@ impl<'a> Filter<'a> {
         Ok(output)
     }
 
+    /// Aggregates the documents ids of the level 0 facet strings that start with `prefix`
+    /// and for which `is_match` returns `true`.
+    fn evaluate_facet_string_pattern(
+        rtxn: &heed::RoTxn<'_>,
+        index: &Index,
+        field_id: FieldId,
+        prefix: &str,
+        is_match: impl Fn(&str) -> bool,
+    ) -> Result<RoaringBitmap> {
+        let base = FacetGroupKey { field_id, level: 0, left_bound: prefix };
+        index
+            .facet_id_string_docids
+            .prefix_iter(rtxn, &base)?
+            .remap_data_type::<LazyDecode<FacetGroupValueCodec>>()
+            .filter_map(|result| -> Option<Result<RoaringBitmap>> {
+                match result {
+                    Ok((FacetGroupKey { left_bound, .. }, lazy_group_value)) => {
+                        if is_match(left_bound) {
+                            Some(lazy_group_value.decode().map(|gv| gv.bitmap).map_err(|_| {
+                                InternalError::from(SerializationError::Decoding {
+                                    db_name: Some(FACET_ID_STRING_DOCIDS),
+                                })
+                                .into()
+                            }))
+                        } else {
+                            None
+                        }
+                    }
+                    Err(_e) => Some(Err(InternalError::from(SerializationError::Decoding {
+                        db_name: Some(FACET_ID_STRING_DOCIDS),
+                    })
+                    .into())),
+                }
+            })
+            .union()
+    }
+
     /// Aggregates the documents ids that are part of the specified range automatically
     /// going deeper through the levels.
     fn explore_facet_number_levels(
@@ -578,7 +652,7 @@ impl<'a> Filter<'a> {
 
                     let xyz_base_point = lat_lng_to_xyz(&base_point);
 
-                    let result = rtree
+                    let mut result: RoaringBitmap = rtree
                         .nearest_neighbor_iter(&xyz_base_point)
                         .take_while(|point| {
                             distance_between_two_points(&base_point, &point.data.1)
@@ -587,6 +661,18 @@ impl<'a> Filter<'a> {
                         .map(|point| point.data.0)
                         .collect();
 
+                    result |= geojson_polygons_intersecting(
+                        rtxn,
+                        index,
+                        &circle_envelopes(&base_point, radius),
+                        |geometry| {
+                            geometry.contains(&base_point)
+                                || geometry.crosses_circle(&base_point, radius)
+                        },
+                        &result,
+                        universe,
+                    )?;
+
                     Ok(result)
                 } else {
                     Err(point[0].as_external_error(FilterError::AttributeNotFilterable {
@@ -632,101 +718,75 @@ impl<'a> Filter<'a> {
                         ))?;
                     }
 
-                    // Instead of writing a custom `GeoBoundingBox` filter we're simply going to re-use the range
-                    // filter to create the following filter;
-                    // `_geo.lat {top_right[0]} TO {bottom_left[0]} AND _geo.lng {top_right[1]} TO {bottom_left[1]}`
-                    // As we can see, we need to use a bunch of tokens that don't exist in the original filter,
-                    // thus we're going to create tokens that point to a random span but contain our text.
-
-                    let geo_lat_token = Token::new(
-                        top_right_point[0].original_span(),
-                        Some("_geo.lat".to_string()),
-                    );
-
-                    let condition_lat = FilterCondition::Condition {
-                        fid: geo_lat_token,
-                        op: Condition::Between {
-                            from: bottom_left_point[0].clone(),
-                            to: top_right_point[0].clone(),
-                        },
+                    // A document can have several points, we cannot rely on the `_geo.lat` and
+                    // `_geo.lng` facets as they would match a document having a point in the
+                    // latitude range and another one in the longitude range.
+                    let rtree = match index.geo_rtree(rtxn)? {
+                        Some(rtree) => rtree,
+                        None => return Ok(RoaringBitmap::new()),
                     };
 
-                    let selected_lat = Filter { condition: condition_lat }.inner_evaluate(
-                        rtxn,
-                        index,
-                        field_ids_map,
-                        filterable_attribute_rules,
-                        universe,
-                    )?;
-
-                    let geo_lng_token = Token::new(
-                        top_right_point[1].original_span(),
-                        Some("_geo.lng".to_string()),
-                    );
-                    let selected_lng = if top_right[1] < bottom_left[1] {
+                    let wraps_around = top_right[1] < bottom_left[1];
+                    let envelopes = if wraps_around {
                         // In this case the bounding box is wrapping around the earth (going from 180 to -180).
-                        // We need to update the lng part of the filter from;
-                        // `_geo.lng {top_right[1]} TO {bottom_left[1]}` to
-                        // `_geo.lng {bottom_left[1]} TO 180 AND _geo.lng -180 TO {top_right[1]}`
-
-                        let min_lng_token = Token::new(
-                            top_right_point[1].original_span(),
-                            Some("-180.0".to_string()),
-                        );
-                        let max_lng_token = Token::new(
-                            top_right_point[1].original_span(),
-                            Some("180.0".to_string()),
-                        );
-
-                        let condition_left = FilterCondition::Condition {
-                            fid: geo_lng_token.clone(),
-                            op: Condition::Between {
-                                from: bottom_left_point[1].clone(),
-                                to: max_lng_token,
-                            },
+                        vec![
+                            polygon_envelope(&[bottom_left, [top_right[0], 180.0]]),
+                            polygon_envelope(&[[bottom_left[0], -180.0], top_right]),
+                        ]
+                    } else {
+                        vec![polygon_envelope(&[bottom_left, top_right])]
+                    };
+                    let in_bounding_box = |[lat, lng]: [f64; 2]| {
+                        let lng_in_range = if wraps_around {
+                            lng >= bottom_left[1] || lng <= top_right[1]
+                        } else {
+                            (bottom_left[1]..=top_right[1]).contains(&lng)
                         };
-                        let left = Filter { condition: condition_left }.inner_evaluate(
-                            rtxn,
-                            index,
-                            field_ids_map,
-                            filterable_attribute_rules,
-                            universe,
-                        )?;
+                        (bottom_left[0]..=top_right[0]).contains(&lat) && lng_in_range
+                    };
 
-                        let condition_right = FilterCondition::Condition {
-                            fid: geo_lng_token,
-                            op: Condition::Between {
-                                from: min_lng_token,
-                                to: top_right_point[1].clone(),
-                            },
-                        };
-                        let right = Filter { condition: condition_right }.inner_evaluate(
-                            rtxn,
-                            index,
-                            field_ids_map,
-                            filterable_attribute_rules,
-                            universe,
-                        )?;
+                    let mut result = RoaringBitmap::new();
+                    for envelope in envelopes {
+                        result.extend(
+                            rtree
+                                .locate_in_envelope(&envelope)
+                                .filter(|point| in_bounding_box(point.data.1))
+                                .map(|point| point.data.0),
+                        );
+                    }
+                    if let Some(universe) = universe {
+                        result &= universe;
+                    }
 
-                        left | right
+                    let boxes = if wraps_around {
+                        vec![
+                            [bottom_left, [top_right[0], 180.0]],
+                            [[bottom_left[0], -180.0], top_right],
+                        ]
                     } else {
-                        let condition_lng = FilterCondition::Condition {
-                            fid: geo_lng_token,
-                            op: Condition::Between {
-                                from: bottom_left_point[1].clone(),
-                                to: top_right_point[1].clone(),
-                            },
-                        };
-                        Filter { condition: condition_lng }.inner_evaluate(
-                            rtxn,
-                            index,
-                            field_ids_map,
-                            filterable_attribute_rules,
-                            universe,
-                        )?
+                        vec![[bottom_left, top_right]]
                     };
+                    let boxes: Vec<_> = boxes
+                        .into_iter()
+                        .map(|[[bottom, left], [top, right]]| {
+                            [[bottom, left], [bottom, right], [top, right], [top, left]]
+                        })
+                        .collect();
+                    let envelopes: Vec<_> = boxes.iter().map(AABB::from_points).collect();
+                    result |= geojson_polygons_intersecting(
+                        rtxn,
+                        index,
+                        &envelopes,
+                        |geometry| {
+                            boxes.iter().any(|corners| {
+                                geometry.contains(&corners[0]) || geometry.crosses_polygon(corners)
+                            })
+                        },
+                        &result,
+                        universe,
+                    )?;
 
-                    Ok(selected_lat & selected_lng)
+                    Ok(result)
                 } else {
                     Err(top_right_point[0].as_external_error(
                         FilterError::AttributeNotFilterable {
@@ -739,10 +799,171 @@ impl<'a> Filter<'a> {
                     ))?
                 }
             }
+            FilterCondition::GeoPolygon { points } => {
+                if index.is_geo_filtering_enabled(rtxn)? {
+                    let mut polygon = Vec::with_capacity(points.len());
+                    for [lat_token, lng_token] in points {
+                        let point =
+                            [lat_token.parse_finite_float()?, lng_token.parse_finite_float()?];
+                        if !(-90.0..=90.0).contains(&point[0]) {
+                            return Err(lat_token.as_external_error(BadGeoError::Lat(point[0])))?;
+                        }
+                        if !(-180.0..=180.0).contains(&point[1]) {
+                            return Err(lng_token.as_external_error(BadGeoError::Lng(point[1])))?;
+                        }
+                        polygon.push(point);
+                    }
+
+                    let rtree = match index.geo_rtree(rtxn)? {
+                        Some(rtree) => rtree,
+                        None => return Ok(RoaringBitmap::new()),
+                    };
+
+                    // We first retrieve the points contained in the cartesian envelope of the
+                    // polygon and then only keep the ones that are really inside of it.
+                    let mut result: RoaringBitmap = rtree
+                        .locate_in_envelope(&polygon_envelope(&polygon))
+                        .filter(|point| polygon_contains(&polygon, &point.data.1))
+                        .map(|point| point.data.0)
+                        .collect();
+
+                    result |= geojson_polygons_intersecting(
+                        rtxn,
+                        index,
+                        &[AABB::from_points(&polygon)],
+                        |geometry| {
+                            geometry.contains(&polygon[0]) || geometry.crosses_polygon(&polygon)
+                        },
+                        &result,
+                        universe,
+                    )?;
+
+                    Ok(result)
+                } else {
+                    Err(points[0][0].as_external_error(FilterError::AttributeNotFilterable {
+                        attribute: RESERVED_GEO_FIELD_NAME,
+                        filterable_patterns: filtered_matching_patterns(
+                            filterable_attribute_rules,
+                            &|features| features.is_filterable(),
+                        ),
+                    }))?
+                }
+            }
         }
     }
 }
 
+/// Returns the documents whose `_geojson` geometry is a polygon intersecting the filtered area.
+///
+/// The polygons with a vertex in the area are already matched by the points rtree, the other ones
+/// either contain the area or have an edge crossing it. Only the polygons whose envelope
+/// intersects one of the `envelopes` of the area are fetched to be checked by `intersects`, the
+/// documents that already matched are skipped.
+fn geojson_polygons_intersecting(
+    rtxn: &heed::RoTxn<'_>,
+    index: &Index,
+    envelopes: &[AABB<[f64; 2]>],
+    intersects: impl Fn(&GeoJsonGeometry) -> bool,
+    matched: &RoaringBitmap,
+    universe: Option<&RoaringBitmap>,
+) -> Result<RoaringBitmap> {
+    let rtree = match index.geojson_rtree(rtxn)? {
+        Some(rtree) => rtree,
+        None => return Ok(RoaringBitmap::new()),
+    };
+
+    let mut candidates: RoaringBitmap = envelopes
+        .iter()
+        .flat_map(|envelope| rtree.locate_in_envelope_intersecting(envelope))
+        .map(|polygon| polygon.data)
+        .collect();
+    candidates -= matched;
+    if let Some(universe) = universe {
+        candidates &= universe;
+    }
+    if candidates.is_empty() {
+        return Ok(candidates);
+    }
+
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let mut result = RoaringBitmap::new();
+    for docid in candidates {
+        if let Some(geometry) = index.geojson_geometry(rtxn, &fields_ids_map, docid)? {
+            if intersects(&geometry) {
+                result.insert(docid);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the envelopes in the latitude and longitude plane containing every point at less than
+/// `radius` meters from the center, split in two when crossing the antimeridian.
+fn circle_envelopes(center: &[f64; 2], radius: f64) -> Vec<AABB<[f64; 2]>> {
+    const EARTH_RADIUS: f64 = 6_371_000.0;
+    // the haversine distance may slightly differ from the one on a perfect sphere
+    let angle = (radius / EARTH_RADIUS * 1.01).to_degrees();
+    let min_lat = center[0] - angle;
+    let max_lat = center[0] + angle;
+    if min_lat <= -90.0 || max_lat >= 90.0 || angle >= 90.0 {
+        // the circle contains a pole and thus every longitude
+        return vec![AABB::from_corners([min_lat.max(-90.0), -180.0], [max_lat.min(90.0), 180.0])];
+    }
+
+    // the circle is the widest on its latitude closest to a pole
+    let widest_lat = min_lat.abs().max(max_lat.abs()).to_radians();
+    let lng_angle = angle / widest_lat.cos();
+    let min_lng = center[1] - lng_angle;
+    let max_lng = center[1] + lng_angle;
+    if lng_angle >= 180.0 {
+        vec![AABB::from_corners([min_lat, -180.0], [max_lat, 180.0])]
+    } else if min_lng < -180.0 {
+        vec![
+            AABB::from_corners([min_lat, min_lng + 360.0], [max_lat, 180.0]),
+            AABB::from_corners([min_lat, -180.0], [max_lat, max_lng]),
+        ]
+    } else if max_lng > 180.0 {
+        vec![
+            AABB::from_corners([min_lat, min_lng], [max_lat, 180.0]),
+            AABB::from_corners([min_lat, -180.0], [max_lat, max_lng - 360.0]),
+        ]
+    } else {
+        vec![AABB::from_corners([min_lat, min_lng], [max_lat, max_lng])]
+    }
+}
+
+/// Returns the smallest cartesian box containing every point of the sphere whose latitude and
+/// longitude are in the ranges covered by the polygon.
+fn polygon_envelope(polygon: &[[f64; 2]]) -> AABB<[f64; 3]> {
+    let (min_lat, max_lat) = min_max(polygon.iter().map(|point| point[0]));
+    let (min_lng, max_lng) = min_max(polygon.iter().map(|point| point[1]));
+
+    // The extremums of the cartesian coordinates are reached either on the bounds of the
+    // ranges, on the equator, or on the meridians aligned with the x and y axes.
+    let lats = [min_lat, max_lat, 0.0];
+    let lngs = [min_lng, max_lng, -180.0, -90.0, 0.0, 90.0, 180.0];
+    let lats = lats.into_iter().filter(|lat| (min_lat..=max_lat).contains(lat));
+    let lngs = lngs.into_iter().filter(|lng| (min_lng..=max_lng).contains(lng));
+
+    let mut lower = [f64::INFINITY; 3];
+    let mut upper = [f64::NEG_INFINITY; 3];
+    for lat in lats {
+        for lng in lngs.clone() {
+            let xyz = lat_lng_to_xyz(&[lat, lng]);
+            for i in 0..3 {
+                lower[i] = lower[i].min(xyz[i] - f64::EPSILON);
+                upper[i] = upper[i].max(xyz[i] + f64::EPSILON);
+            }
+        }
+    }
+
+    AABB::from_corners(lower, upper)
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)))
+}
+
 fn generate_filter_error(
     rtxn: &heed::RoTxn<'_>,
     index: &Index,
@@ -765,6 +986,68 @@ fn generate_filter_error(
     }
 }
 
+/// Builds the regex of the `MATCHES` and `LIKE` operators, both must match the facet values
+/// with the same flags.
+///
+/// The facet strings are stored normalized (lowercased), the values are therefore matched
+/// case-insensitively, and `.` also matches the line breaks they may contain.
+fn build_pattern_regex(regex: &str) -> std::result::Result<regex::Regex, regex::Error> {
+    RegexBuilder::new(regex).case_insensitive(true).dot_matches_new_line(true).build()
+}
+
+/// Converts a SQL `LIKE` pattern into an anchored regex.
+///
+/// `%` matches any sequence of characters and `_` matches exactly one character,
+/// everything else is matched literally. A backslash makes the character following it literal,
+/// `\%`, `\_` and `\\` match a `%`, a `_` and a `\`. The normalized literal prefix of the
+/// pattern is returned alongside the regex so that the caller can restrict the facet values to scan.
+fn like_pattern_to_regex(pattern: &str) -> (String, String) {
+    let pattern = crate::normalize_facet(pattern);
+
+    let mut prefix = String::new();
+    let mut in_prefix = true;
+    let mut regex = String::from("^");
+    let mut literal = [0; 4];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        let c = match c {
+            '%' => {
+                in_prefix = false;
+                regex.push_str(".*");
+                continue;
+            }
+            '_' => {
+                in_prefix = false;
+                regex.push('.');
+                continue;
+            }
+            // A trailing backslash has nothing to escape and is matched literally.
+            '\\' => chars.next().unwrap_or('\\'),
+            c => c,
+        };
+        if in_prefix {
+            prefix.push(c);
+        }
+        regex.push_str(&regex::escape(c.encode_utf8(&mut literal)));
+    }
+    regex.push('$');
+
+    (prefix, regex)
+}
+
+/// Parses the value of a range filter as a number.
+///
+/// On date facets the value can also be an RFC 3339 date, compared against the indexed timestamps.
+fn parse_facet_number<'a>(
+    token: &Token<'a>,
+    features: &FilterableAttributesFeatures,
+) -> std::result::Result<f64, FPError<'a>> {
+    match parse_date_facet(token.value()) {
+        Some(timestamp) if features.is_date() => Ok(timestamp),
+        _ => token.parse_finite_float(),
+    }
+}
+
 impl<'a> From<FilterCondition<'a>> for Filter<'a> {
     fn from(fc: FilterCondition<'a>) -> Self {
         Self { condition: fc }
@@ -889,6 +1172,14 @@ mod tests {
         18:20 _geoBoundingBox([42, 150], [30, 10])
         "###);
 
+        let filter =
+            Filter::from_str("_geoPolygon([42, 150], [30, 10], [10, 30])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        snapshot!(error.to_string(), @r###"
+        Attribute `_geo` is not filterable. This index does not have configured filterable attributes.
+        14:16 _geoPolygon([42, 150], [30, 10], [10, 30])
+        "###);
+
         let filter = Filter::from_str("dog = \"bernese mountain\"").unwrap().unwrap();
         let error = filter.evaluate(&rtxn, &index).unwrap_err();
         snapshot!(error.to_string(), @r###"
@@ -1191,6 +1482,240 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn geo_polygon() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S(
+                    RESERVED_GEO_FIELD_NAME,
+                ))]);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+              { "id": 0, RESERVED_GEO_FIELD_NAME: { "lat": 1.0, "lng": 1.0 } },
+              { "id": 1, RESERVED_GEO_FIELD_NAME: { "lat": 1.0, "lng": 5.0 } },
+              { "id": 2, RESERVED_GEO_FIELD_NAME: { "lat": 9.0, "lng": 1.0 } },
+              { "id": 3, RESERVED_GEO_FIELD_NAME: { "lat": 9.0, "lng": 5.0 } },
+              { "id": 4, RESERVED_GEO_FIELD_NAME: { "lat": 5.0, "lng": 15.0 } },
+              { "id": 5, RESERVED_GEO_FIELD_NAME: { "lat": -45.0, "lng": -170.0 } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // a U shaped polygon, the document 3 is in the hole of the U
+        let filter = Filter::from_str(
+            "_geoPolygon([0, 0], [10, 0], [10, 2], [2, 2], [2, 8], [10, 8], [10, 10], [0, 10])",
+        )
+        .unwrap()
+        .unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let filter = Filter::from_str("_geoPolygon([-60, -180], [-30, -180], [-45, -160])")
+            .unwrap()
+            .unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![5]);
+
+        let filter = Filter::from_str("NOT _geoPolygon([0, 0], [0, 10], [10, 10], [10, 0])")
+            .unwrap()
+            .unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![4, 5]);
+
+        let filter =
+            Filter::from_str("_geoPolygon([0, 0], [0, 10], [90.000001, 10])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Bad latitude `90.000001`. Latitude must be contained between -90 and 90 degrees."
+        ));
+
+        let filter =
+            Filter::from_str("_geoPolygon([0, 0], [0, -180.000001], [10, 10])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Bad longitude `-180.000001`. Longitude must be contained between -180 and 180 degrees."
+        ));
+    }
+
+    #[test]
+    fn geojson() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S(
+                    RESERVED_GEO_FIELD_NAME,
+                ))]);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+              { "id": 0, RESERVED_GEO_FIELD_NAME: { "lat": 1.0, "lng": 1.0 } },
+              { "id": 1, "_geojson": { "type": "Point", "coordinates": [1.0, 1.0] } },
+              { "id": 2, "_geojson": { "type": "MultiPoint", "coordinates": [[50.0, 50.0], [1.5, 1.5]] } },
+              { "id": 3, "_geojson": {
+                  "type": "Polygon",
+                  "coordinates": [[[-10.0, -10.0], [10.0, -10.0], [10.0, 10.0], [-10.0, 10.0], [-10.0, -10.0]]]
+              } },
+              { "id": 4, "_geojson": { "type": "Point", "coordinates": [100.0, 40.0] } },
+              { "id": 5, "_geojson": {
+                  "type": "Polygon",
+                  "coordinates": [[[-50.0, 20.0], [50.0, 20.0], [50.0, 21.0], [-50.0, 21.0], [-50.0, 20.0]]]
+              } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // the polygon contains the center of the radius
+        let filter = Filter::from_str("_geoRadius(1, 1, 1000)").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![0, 1, 3]);
+
+        let filter = Filter::from_str("_geoBoundingBox([2, 2], [0, 0])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        let filter = Filter::from_str("_geoBoundingBox([60, 60], [45, 45])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![2]);
+
+        let filter = Filter::from_str("_geoPolygon([30, 90], [50, 90], [50, 110], [30, 110])")
+            .unwrap()
+            .unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![4]);
+
+        // the edges of the polygon cross the area without any vertex inside of it
+        let filter = Filter::from_str("_geoRadius(21.5, 0, 100000)").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![5]);
+
+        let filter = Filter::from_str("_geoRadius(25, 0, 100000)").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert!(result.is_empty());
+
+        let filter = Filter::from_str("_geoBoundingBox([22, 5], [19, 0])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![5]);
+
+        let filter = Filter::from_str("_geoBoundingBox([22, -175], [19, 175])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert!(result.is_empty());
+
+        let filter = Filter::from_str("_geoPolygon([19, 1], [22, 1], [22, 3])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![5]);
+        drop(rtxn);
+
+        // updating a geometry removes its old points from the rtree
+        index
+            .add_documents(documents!([
+              { "id": 2, "_geojson": { "type": "Point", "coordinates": [50.0, 50.0] } },
+              { "id": 3, "_geojson": null },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str("_geoBoundingBox([2, 2], [0, 0])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(
+            index.geojson_documents_ids(&rtxn).unwrap().into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 4, 5]
+        );
+        // only the envelopes of the polygons are indexed
+        let geojson_rtree = index.geojson_rtree(&rtxn).unwrap().unwrap();
+        assert_eq!(geojson_rtree.iter().map(|envelope| envelope.data).collect::<Vec<_>>(), vec![5]);
+    }
+
+    #[test]
+    fn geo_multiple_points() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S(
+                    RESERVED_GEO_FIELD_NAME,
+                ))]);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+              { "id": 0, RESERVED_GEO_FIELD_NAME: [{ "lat": 1.0, "lng": 50.0 }, { "lat": 50.0, "lng": 1.0 }] },
+              { "id": 1, RESERVED_GEO_FIELD_NAME: [{ "lat": 1.0, "lng": 1.0 }, { "lat": "50.0", "lng": 50.0 }] },
+              { "id": 2, RESERVED_GEO_FIELD_NAME: [{ "lat": -20.0, "lng": -20.0 }] },
+              { "id": 3, RESERVED_GEO_FIELD_NAME: [] },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        let filter = Filter::from_str("_geoRadius(50, 50, 1000)").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![1]);
+
+        // a document having a point in the latitude range and another one
+        // in the longitude range must not match the bounding box
+        let filter = Filter::from_str("_geoBoundingBox([2, 2], [0, 0])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![1]);
+
+        let filter = Filter::from_str("_geoBoundingBox([60, 60], [-30, -30])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let filter =
+            Filter::from_str("_geoPolygon([0, 49], [2, 49], [2, 51], [0, 51])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), vec![0]);
+        drop(rtxn);
+
+        // updating the points removes the old ones from the rtree
+        index
+            .add_documents(documents!([
+              { "id": 1, RESERVED_GEO_FIELD_NAME: { "lat": 50.0, "lng": 50.0 } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str("_geoBoundingBox([2, 2], [0, 0])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert!(result.is_empty());
+        assert_eq!(
+            index.geo_faceted_documents_ids(&rtxn).unwrap().into_iter().collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn invalid_geojson() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S(
+                    RESERVED_GEO_FIELD_NAME,
+                ))]);
+            })
+            .unwrap();
+
+        let error = index
+            .add_documents(documents!([
+              { "id": 0, "_geojson": { "type": "LineString", "coordinates": [[1.0, 1.0], [2.0, 2.0]] } },
+            ]))
+            .unwrap_err();
+        snapshot!(error, @r###"The `_geojson` field in the document with the id: `"0"` is not a valid GeoJSON geometry: unsupported geometry type `LineString`, expected `Point`, `MultiPoint` or `Polygon`."###);
+    }
+
     #[test]
     fn filter_depth() {
         // generates a big (2 MiB) filter with too much of ORs.
@@ -1357,4 +1882,92 @@ mod tests {
         let result = filter.evaluate(&rtxn, &index).unwrap();
         assert_eq!(result, RoaringBitmap::from_iter((0..100).filter(|x| x % 10 != 0)));
     }
+
+    #[test]
+    fn filter_pattern() {
+        let index = TempIndex::new();
+
+        let sku_rule: FilterableAttributesRule = serde_json::from_value(serde_json::json!({
+            "attributePatterns": ["sku"],
+            "features": { "filter": { "equality": true, "pattern": true } }
+        }))
+        .unwrap();
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key("id".to_owned());
+                settings.set_filterable_fields(vec![
+                    sku_rule,
+                    FilterableAttributesRule::Field("path".to_string()),
+                ]);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "sku": "SKU-1234-red", "path": "abc/def" },
+                { "id": 1, "sku": "SKU-12-blue", "path": "abc/xyz/def" },
+                { "id": 2, "sku": "sku-9876-green", "path": "xyz/def" },
+                { "id": 3, "sku": "ABC-1234-red", "path": "abc" },
+                { "id": 4, "sku": "50%_OFF", "path": "abc" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        let filter = Filter::from_str("sku MATCHES 'SKU-[0-9]{4}-.*'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0, 2]));
+
+        let filter = Filter::from_str("sku NOT MATCHES 'SKU-[0-9]{4}-.*'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([1, 3, 4]));
+
+        // the regex must match the whole value
+        let filter = Filter::from_str("sku MATCHES '1234'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert!(result.is_empty());
+
+        let filter = Filter::from_str("sku LIKE 'sku-____-%'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0, 2]));
+
+        let filter = Filter::from_str("sku LIKE '%-red'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0, 3]));
+
+        let filter = Filter::from_str("sku LIKE 'SKU-1234-red'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0]));
+
+        // the escaped wildcards only match themselves
+        let filter = Filter::from_str(r"sku LIKE '50\%\_off'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([4]));
+
+        let filter = Filter::from_str(r"sku LIKE '%\_%'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([4]));
+
+        let filter = Filter::from_str(r"sku LIKE 'SKU\%'").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert!(result.is_empty());
+
+        let filter = Filter::from_str("sku MATCHES 'SKU-(['").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(matches!(
+            error,
+            crate::Error::UserError(crate::error::UserError::InvalidFilter(_))
+        ));
+
+        // `path` uses the legacy rule which does not enable the pattern operators
+        let filter = Filter::from_str("path LIKE 'abc%def'").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        snapshot!(error.to_string(), @r###"
+        Filter operator `LIKE` is not allowed for the attribute `path`.
+          - Note: allowed operators: OR, AND, NOT, =, !=, IN, <, >, <=, >=, TO, IS EMPTY, IS NULL, EXISTS.
+          - Note: field `path` matched rule #1 in `filterableAttributes`
+          - Hint: enable pattern in rule #1 by modifying the features.filter object
+          - Hint: prepend another rule matching `path` with appropriate filter features before rule #1
+        "###);
+    }
 }
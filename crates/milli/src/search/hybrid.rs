@@ -1,11 +1,28 @@
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 
 use itertools::Itertools;
 use roaring::RoaringBitmap;
 
-use crate::score_details::{ScoreDetails, ScoreValue, ScoringStrategy};
+use super::new::ResolvedPinning;
+use super::Pinning;
+use crate::score_details::{self, ScoreDetails, ScoreValue, ScoringStrategy};
 use crate::search::SemanticSearch;
-use crate::{MatchingWords, Result, Search, SearchResult};
+use crate::{
+    filtered_universe, DocumentId, MatchingWords, Result, Search, SearchContext, SearchResult,
+};
+
+/// How the keyword and semantic results of a hybrid search are merged together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HybridFusion {
+    /// Compare the scores of the documents, weighted by the semantic ratio.
+    #[default]
+    Ratio,
+    /// Rank the documents by the sum of the reciprocals of their rank in the keyword and semantic results,
+    /// offset by the rank constant and weighted by the semantic ratio.
+    ReciprocalRank { rank_constant: u32 },
+}
 
 struct ScoreWithRatioResult {
     matching_words: MatchingWords,
@@ -53,6 +70,27 @@ fn compare_scores(
                     order => return order,
                 }
             }
+            (Some(ScoreValue::Expression(left)), Some(ScoreValue::Expression(right))) => {
+                match left.partial_cmp(right).unwrap() {
+                    Ordering::Equal => continue,
+                    order => return order,
+                }
+            }
+            (Some(ScoreValue::Boost(left)), Some(ScoreValue::Boost(right))) => {
+                match left.partial_cmp(right).unwrap() {
+                    Ordering::Equal => continue,
+                    order => return order,
+                }
+            }
+            (Some(ScoreValue::Pinned(left)), Some(ScoreValue::Pinned(right))) => {
+                match left.partial_cmp(right).unwrap() {
+                    Ordering::Equal => continue,
+                    order => return order,
+                }
+            }
+            // the pinned documents are always ranked first
+            (Some(ScoreValue::Pinned(_)), Some(_)) => return Ordering::Greater,
+            (Some(_), Some(ScoreValue::Pinned(_))) => return Ordering::Less,
             (Some(ScoreValue::Score(x)), Some(_)) => {
                 return if x == 0. { Ordering::Less } else { Ordering::Greater }
             }
@@ -64,6 +102,9 @@ fn compare_scores(
             | (Some(ScoreValue::Sort(_)), Some(ScoreValue::GeoSort(_))) => {
                 unreachable!("Unexpected geo and sort comparison")
             }
+            (Some(_), Some(_)) => {
+                unreachable!("Unexpected comparison between different ranking rules")
+            }
         }
     }
 }
@@ -151,14 +192,212 @@ impl ScoreWithRatioResult {
     }
 }
 
+/// A document of the results of the Reciprocal Rank Fusion.
+struct FusedDocument {
+    docid: DocumentId,
+    /// The position of the document if it is pinned by a pinning rule.
+    pinned: Option<usize>,
+    fusion: score_details::Fusion,
+    /// The scores of the document in the results where it is the best ranked.
+    scores: Vec<ScoreDetails>,
+    /// The rank of the document in the results where it is the best ranked.
+    best_rank: Option<u32>,
+    from_semantic: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FusionSource {
+    Keyword,
+    Semantic,
+    Sparse,
+}
+
+#[tracing::instrument(level = "trace", skip_all, target = "search::hybrid")]
+fn reciprocal_rank_fusion(
+    vector_results: SearchResult,
+    sparse_results: Option<SearchResult>,
+    keyword_results: SearchResult,
+    semantic_ratio: f32,
+    rank_constant: u32,
+    from: usize,
+    length: usize,
+) -> (SearchResult, u32) {
+    let with_sparse = sparse_results.is_some();
+    let mut candidates = vector_results.candidates | &keyword_results.candidates;
+    let mut degraded = vector_results.degraded | keyword_results.degraded;
+    let mut used_negative_operator =
+        vector_results.used_negative_operator | keyword_results.used_negative_operator;
+    let mut results = vec![
+        (FusionSource::Keyword, keyword_results.documents_ids, keyword_results.document_scores),
+        (FusionSource::Semantic, vector_results.documents_ids, vector_results.document_scores),
+    ];
+    if let Some(sparse_results) = sparse_results {
+        candidates |= sparse_results.candidates;
+        degraded |= sparse_results.degraded;
+        used_negative_operator |= sparse_results.used_negative_operator;
+        results.push((
+            FusionSource::Sparse,
+            sparse_results.documents_ids,
+            sparse_results.document_scores,
+        ));
+    }
+
+    let mut documents: Vec<FusedDocument> =
+        Vec::with_capacity(results.iter().map(|(_, documents_ids, _)| documents_ids.len()).sum());
+    let mut positions: HashMap<DocumentId, usize> = HashMap::with_capacity(documents.capacity());
+
+    for (source, documents_ids, document_scores) in results {
+        let mut rank = 0;
+        for (docid, scores) in documents_ids.into_iter().zip(document_scores) {
+            let pinned = match scores.first() {
+                Some(ScoreDetails::Pinned(pinned)) => Some(pinned.position),
+                _ => None,
+            };
+            // pinned documents are not ranked, they keep their position
+            let rank = if pinned.is_none() {
+                rank += 1;
+                Some(rank)
+            } else {
+                None
+            };
+
+            let document = match positions.entry(docid) {
+                Entry::Occupied(entry) => {
+                    let document = &mut documents[*entry.get()];
+                    let better_ranked = match (rank, document.best_rank) {
+                        (Some(rank), Some(best_rank)) => rank < best_rank,
+                        _ => false,
+                    };
+                    if better_ranked {
+                        document.scores = scores;
+                        document.best_rank = rank;
+                        document.from_semantic = true;
+                    }
+                    document
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(documents.len());
+                    documents.push(FusedDocument {
+                        docid,
+                        pinned,
+                        fusion: score_details::Fusion {
+                            keyword_rank: None,
+                            semantic_rank: None,
+                            sparse_rank: None,
+                            with_sparse,
+                            rank_constant,
+                            semantic_ratio,
+                        },
+                        scores,
+                        best_rank: rank,
+                        from_semantic: source != FusionSource::Keyword,
+                    });
+                    documents.last_mut().unwrap()
+                }
+            };
+            match source {
+                FusionSource::Keyword => document.fusion.keyword_rank = rank,
+                FusionSource::Semantic => document.fusion.semantic_rank = rank,
+                FusionSource::Sparse => document.fusion.sparse_rank = rank,
+            }
+        }
+    }
+
+    // the sort is stable, so that documents with the same fused score keep the keyword results order
+    documents.sort_by(|left, right| match (left.pinned, right.pinned) {
+        (Some(left), Some(right)) => left.cmp(&right),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => right.fusion.score().total_cmp(&left.fusion.score()),
+    });
+
+    let mut semantic_hit_count = 0;
+    let mut documents_ids = Vec::with_capacity(length);
+    let mut document_scores = Vec::with_capacity(length);
+    for FusedDocument { docid, pinned, fusion, scores, best_rank: _, from_semantic } in
+        documents.into_iter().skip(from).take(length)
+    {
+        if from_semantic {
+            semantic_hit_count += 1;
+        }
+        documents_ids.push(docid);
+        if pinned.is_some() {
+            document_scores.push(scores);
+        } else {
+            document_scores
+                .push(std::iter::once(ScoreDetails::Fusion(fusion)).chain(scores).collect());
+        }
+    }
+
+    (
+        SearchResult {
+            matching_words: keyword_results.matching_words,
+            candidates,
+            documents_ids,
+            document_scores,
+            degraded,
+            used_negative_operator,
+        },
+        semantic_hit_count,
+    )
+}
+
 impl Search<'_> {
     #[tracing::instrument(level = "trace", skip_all, target = "search::hybrid")]
-    pub fn execute_hybrid(&self, semantic_ratio: f32) -> Result<(SearchResult, Option<u32>)> {
+    pub fn execute_hybrid(
+        &self,
+        semantic_ratio: f32,
+        fusion: HybridFusion,
+    ) -> Result<(SearchResult, Option<u32>)> {
+        if let Some(rerank) = &self.rerank {
+            let (results, semantic_hit_count) =
+                self.reranking_window(rerank).execute_hybrid(semantic_ratio, fusion)?;
+            let results = self.rerank_and_paginate(rerank, results)?;
+            let semantic_hit_count = semantic_hit_count.map(|_| semantic_hit_count_of(&results));
+            return Ok((results, semantic_hit_count));
+        }
+
+        // the pinning rule is resolved once for the keyword and semantic searches,
+        // its pinned documents are inserted in the results once they are merged
+        let pinning = {
+            let ctx = SearchContext::new(self.index, self.rtxn)?;
+            let universe = filtered_universe(ctx.index, ctx.txn, &self.filter)?;
+            ResolvedPinning::new(&ctx, self.query.as_deref(), &universe, self.distinct.as_deref())?
+        };
+        let (results, semantic_hit_count) =
+            self.execute_hybrid_ranking(semantic_ratio, fusion, pinning.as_ref())?;
+        Ok(match pinning {
+            Some(pinning) => {
+                let results = pinning.apply_to_results(results, self.offset, self.limit);
+                let semantic_hit_count =
+                    semantic_hit_count.map(|_| semantic_hit_count_of(&results));
+                (results, semantic_hit_count)
+            }
+            None => (results, semantic_hit_count),
+        })
+    }
+
+    /// Executes the hybrid search without the documents of the pinning rule.
+    ///
+    /// When there is a pinning rule, the results start at the first hit so that
+    /// the pinned documents can be inserted before the pagination.
+    fn execute_hybrid_ranking(
+        &self,
+        semantic_ratio: f32,
+        fusion: HybridFusion,
+        pinning: Option<&ResolvedPinning>,
+    ) -> Result<(SearchResult, Option<u32>)> {
+        let (offset, limit) = match pinning {
+            Some(_) => (0, self.offset + self.limit),
+            None => (self.offset, self.limit),
+        };
+
         // TODO: find classier way to achieve that than to reset vector and query params
         // create separate keyword and semantic searches
         let mut search = Search {
             query: self.query.clone(),
             filter: self.filter.clone(),
+            boosts: self.boosts.clone(),
             offset: 0,
             limit: self.limit + self.offset,
             sort_criteria: self.sort_criteria.clone(),
@@ -172,26 +411,34 @@ impl Search<'_> {
             rtxn: self.rtxn,
             index: self.index,
             semantic: self.semantic.clone(),
+            sparse: self.sparse.clone(),
             time_budget: self.time_budget.clone(),
             ranking_score_threshold: self.ranking_score_threshold,
             locales: self.locales.clone(),
+            rerank: None,
         };
 
         let semantic = search.semantic.take();
-        let keyword_results = search.execute()?;
+        let sparse = search.sparse.take();
+        let keyword_results = search.execute_with_pinning(Pinning::Exclude(pinning))?;
 
-        // completely skip semantic search if the results of the keyword search are good enough
-        if self.results_good_enough(&keyword_results, semantic_ratio) {
-            return Ok(return_keyword_results(self.limit, self.offset, keyword_results));
+        // completely skip semantic search if the results of the keyword search are good enough.
+        // This doesn't apply to the reciprocal rank fusion, that doesn't depend on the scores
+        if fusion == HybridFusion::Ratio
+            && self.results_good_enough(&keyword_results, semantic_ratio)
+        {
+            return Ok(return_keyword_results(limit, offset, keyword_results));
         }
 
         // no vector search against placeholder search
         let Some(query) = search.query.take() else {
-            return Ok(return_keyword_results(self.limit, self.offset, keyword_results));
+            return Ok(return_keyword_results(limit, offset, keyword_results));
         };
         // no embedder, no semantic search
-        let Some(SemanticSearch { vector, embedder_name, embedder, quantized }) = semantic else {
-            return Ok(return_keyword_results(self.limit, self.offset, keyword_results));
+        let Some(SemanticSearch { vector, embedder_name, embedder, quantization, distance }) =
+            semantic
+        else {
+            return Ok(return_keyword_results(limit, offset, keyword_results));
         };
 
         let vector_query = match vector {
@@ -207,28 +454,59 @@ impl Search<'_> {
                     Ok(embedding) => embedding,
                     Err(error) => {
                         tracing::error!(error=%error, "Embedding failed");
-                        return Ok(return_keyword_results(
-                            self.limit,
-                            self.offset,
-                            keyword_results,
-                        ));
+                        return Ok(return_keyword_results(limit, offset, keyword_results));
                     }
                 }
             }
         };
 
-        search.semantic =
-            Some(SemanticSearch { vector: Some(vector_query), embedder_name, embedder, quantized });
+        search.semantic = Some(SemanticSearch {
+            vector: Some(vector_query),
+            embedder_name,
+            embedder,
+            quantization,
+            distance,
+        });
 
         // TODO: would be better to have two distinct functions at this point
-        let vector_results = search.execute()?;
+        let vector_results = search.execute_with_pinning(Pinning::Exclude(pinning))?;
 
-        let keyword_results = ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
-        let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
+        let sparse_results = match sparse {
+            Some(sparse) => sparse_search(&mut search, &query, sparse, pinning)?,
+            None => None,
+        };
 
-        let (merge_results, semantic_hit_count) =
-            ScoreWithRatioResult::merge(vector_results, keyword_results, self.offset, self.limit);
-        assert!(merge_results.documents_ids.len() <= self.limit);
+        let (merge_results, semantic_hit_count) = match fusion {
+            HybridFusion::Ratio => {
+                // the semantic and sparse results are compared by their scores like the keyword results
+                let vector_results = match sparse_results {
+                    Some(sparse_results) => {
+                        ScoreWithRatioResult::merge(
+                            ScoreWithRatioResult::new(vector_results, 1.0),
+                            ScoreWithRatioResult::new(sparse_results, 1.0),
+                            0,
+                            offset + limit,
+                        )
+                        .0
+                    }
+                    None => vector_results,
+                };
+                let keyword_results =
+                    ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
+                let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
+                ScoreWithRatioResult::merge(vector_results, keyword_results, offset, limit)
+            }
+            HybridFusion::ReciprocalRank { rank_constant } => reciprocal_rank_fusion(
+                vector_results,
+                sparse_results,
+                keyword_results,
+                semantic_ratio,
+                rank_constant,
+                offset,
+                limit,
+            ),
+        };
+        assert!(merge_results.documents_ids.len() <= limit);
         Ok((merge_results, Some(semantic_hit_count)))
     }
 
@@ -253,6 +531,52 @@ impl Search<'_> {
     }
 }
 
+/// Executes the sparse search of a hybrid search, embedding the query if needed.
+///
+/// Returns `None` when the query cannot be embedded, so that the other results are still returned.
+fn sparse_search(
+    search: &mut Search<'_>,
+    query: &str,
+    SemanticSearch { vector, embedder_name, embedder, quantization, distance }: SemanticSearch,
+    pinning: Option<&ResolvedPinning>,
+) -> Result<Option<SearchResult>> {
+    let vector = match vector {
+        Some(vector) => vector,
+        None => {
+            let span = tracing::trace_span!(target: "search::hybrid", "embed_sparse");
+            let _entered = span.enter();
+
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(3);
+
+            match embedder.embed_search(query, Some(deadline)) {
+                Ok(embedding) => embedding,
+                Err(error) => {
+                    tracing::error!(error=%error, "Sparse embedding failed");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    search.semantic = Some(SemanticSearch {
+        vector: Some(vector),
+        embedder_name,
+        embedder,
+        quantization,
+        distance,
+    });
+    search.execute_with_pinning(Pinning::Exclude(pinning)).map(Some)
+}
+
+/// The number of hits coming from the semantic search, the only ones with a vector score.
+fn semantic_hit_count_of(results: &SearchResult) -> u32 {
+    results
+        .document_scores
+        .iter()
+        .filter(|scores| scores.iter().any(|score| matches!(score, ScoreDetails::Vector(_))))
+        .count() as u32
+}
+
 fn return_keyword_results(
     limit: usize,
     offset: usize,
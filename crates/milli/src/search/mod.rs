@@ -6,12 +6,14 @@ use levenshtein_automata::{LevenshteinAutomatonBuilder as LevBuilder, DFA};
 use once_cell::sync::Lazy;
 use roaring::bitmap::RoaringBitmap;
 
-pub use self::facet::{FacetDistribution, Filter, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::facet::{FacetDistribution, FacetRange, Filter, OrderBy, DEFAULT_VALUES_PER_FACET};
 pub use self::new::matches::{FormatOptions, MatchBounds, MatcherBuilder, MatchingWords};
-use self::new::{execute_vector_search, PartialSearchResult};
+use self::new::{execute_vector_search, PartialSearchResult, ResolvedPinning};
+use self::rerank::RerankSearch;
 use crate::filterable_attributes_rules::{filtered_matching_patterns, matching_features};
+use crate::reranker::Reranker;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
-use crate::vector::Embedder;
+use crate::vector::{Embedder, VectorDistance, VectorQuantization};
 use crate::{
     execute_search, filtered_universe, AscDesc, DefaultSearchLogger, DocumentId, Error, Index,
     Result, SearchContext, TimeBudget, UserError,
@@ -26,20 +28,33 @@ pub mod facet;
 mod fst_utils;
 pub mod hybrid;
 pub mod new;
+mod rerank;
 pub mod similar;
 
+/// How a search applies the pinning rule matching its query.
+#[derive(Clone, Copy)]
+enum Pinning<'p> {
+    /// Resolves the rule and inserts its pinned documents in the results.
+    Resolve,
+    /// Only removes the documents of a rule resolved by the caller from the documents to rank,
+    /// the caller inserts the pinned documents in the results.
+    Exclude(Option<&'p ResolvedPinning>),
+}
+
 #[derive(Debug, Clone)]
 pub struct SemanticSearch {
     vector: Option<Vec<f32>>,
     embedder_name: String,
     embedder: Arc<Embedder>,
-    quantized: bool,
+    quantization: VectorQuantization,
+    distance: VectorDistance,
 }
 
 pub struct Search<'a> {
     query: Option<String>,
     // this should be linked to the String in the query
     filter: Option<Filter<'a>>,
+    boosts: Vec<Boost<'a>>,
     offset: usize,
     limit: usize,
     sort_criteria: Option<Vec<AscDesc>>,
@@ -53,9 +68,11 @@ pub struct Search<'a> {
     rtxn: &'a heed::RoTxn<'a>,
     index: &'a Index,
     semantic: Option<SemanticSearch>,
+    sparse: Option<SemanticSearch>,
     time_budget: TimeBudget,
     ranking_score_threshold: Option<f64>,
     locales: Option<Vec<Language>>,
+    rerank: Option<RerankSearch>,
 }
 
 impl<'a> Search<'a> {
@@ -63,6 +80,7 @@ impl<'a> Search<'a> {
         Search {
             query: None,
             filter: None,
+            boosts: Vec::new(),
             offset: 0,
             limit: 20,
             sort_criteria: None,
@@ -76,9 +94,11 @@ impl<'a> Search<'a> {
             rtxn,
             index,
             semantic: None,
+            sparse: None,
             locales: None,
             time_budget: TimeBudget::max(),
             ranking_score_threshold: None,
+            rerank: None,
         }
     }
 
@@ -91,10 +111,29 @@ impl<'a> Search<'a> {
         &mut self,
         embedder_name: String,
         embedder: Arc<Embedder>,
-        quantized: bool,
+        quantization: VectorQuantization,
+        distance: VectorDistance,
         vector: Option<Vec<f32>>,
     ) -> &mut Search<'a> {
-        self.semantic = Some(SemanticSearch { embedder_name, embedder, quantized, vector });
+        self.semantic =
+            Some(SemanticSearch { embedder_name, embedder, quantization, distance, vector });
+        self
+    }
+
+    /// Adds the results of a sparse embedder as a third signal of the hybrid search.
+    pub fn sparse(
+        &mut self,
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        vector: Option<Vec<f32>>,
+    ) -> &mut Search<'a> {
+        self.sparse = Some(SemanticSearch {
+            embedder_name,
+            embedder,
+            quantization: VectorQuantization::default(),
+            distance: VectorDistance::DotProduct,
+            vector,
+        });
         self
     }
 
@@ -143,6 +182,12 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Ranks the documents matching the filters of the boosts before the other documents.
+    pub fn boosts(&mut self, boosts: Vec<Boost<'a>>) -> &mut Search<'a> {
+        self.boosts = boosts;
+        self
+    }
+
     #[cfg(test)]
     pub fn geo_sort_strategy(&mut self, strategy: new::GeoSortStrategy) -> &mut Search<'a> {
         self.geo_strategy = strategy;
@@ -171,6 +216,12 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Reorders the `limit` first hits by the scores of the reranker before applying the pagination.
+    pub fn rerank(&mut self, reranker: Arc<Reranker>, limit: usize) -> &mut Search<'a> {
+        self.rerank = Some(RerankSearch { reranker, limit });
+        self
+    }
+
     pub fn execute_for_candidates(&self, has_vector_search: bool) -> Result<RoaringBitmap> {
         if has_vector_search {
             let ctx = SearchContext::new(self.index, self.rtxn)?;
@@ -181,6 +232,15 @@ impl<'a> Search<'a> {
     }
 
     pub fn execute(&self) -> Result<SearchResult> {
+        if let Some(rerank) = &self.rerank {
+            let results = self.reranking_window(rerank).execute()?;
+            return self.rerank_and_paginate(rerank, results);
+        }
+
+        self.execute_with_pinning(Pinning::Resolve)
+    }
+
+    fn execute_with_pinning(&self, pinning: Pinning<'_>) -> Result<SearchResult> {
         let mut ctx = SearchContext::new(self.index, self.rtxn)?;
 
         if let Some(searchable_attributes) = self.searchable_attributes {
@@ -215,7 +275,31 @@ impl<'a> Search<'a> {
             }
         }
 
-        let universe = filtered_universe(ctx.index, ctx.txn, &self.filter)?;
+        let mut universe = filtered_universe(ctx.index, ctx.txn, &self.filter)?;
+
+        // the pinned and hidden documents are removed from the documents to rank
+        let resolved_pinning = match pinning {
+            Pinning::Resolve => ResolvedPinning::new(
+                &ctx,
+                self.query.as_deref(),
+                &universe,
+                self.distinct.as_deref(),
+            )?,
+            Pinning::Exclude(_) => None,
+        };
+        let excluded = match pinning {
+            Pinning::Resolve => resolved_pinning.as_ref(),
+            Pinning::Exclude(pinning) => pinning,
+        };
+        if let Some(pinning) = excluded {
+            universe -= pinning.excluded();
+        }
+        // the pinned documents are inserted in the ranked documents before the pagination
+        let (from, length) = match resolved_pinning {
+            Some(_) => (0, self.offset.saturating_add(self.limit)),
+            None => (self.offset, self.limit),
+        };
+
         let PartialSearchResult {
             located_query_terms,
             candidates,
@@ -224,24 +308,30 @@ impl<'a> Search<'a> {
             degraded,
             used_negative_operator,
         } = match self.semantic.as_ref() {
-            Some(SemanticSearch { vector: Some(vector), embedder_name, embedder, quantized }) => {
-                execute_vector_search(
-                    &mut ctx,
-                    vector,
-                    self.scoring_strategy,
-                    universe,
-                    &self.sort_criteria,
-                    &self.distinct,
-                    self.geo_strategy,
-                    self.offset,
-                    self.limit,
-                    embedder_name,
-                    embedder,
-                    *quantized,
-                    self.time_budget.clone(),
-                    self.ranking_score_threshold,
-                )?
-            }
+            Some(SemanticSearch {
+                vector: Some(vector),
+                embedder_name,
+                embedder,
+                quantization,
+                distance,
+            }) => execute_vector_search(
+                &mut ctx,
+                vector,
+                self.scoring_strategy,
+                universe,
+                &self.sort_criteria,
+                &self.distinct,
+                &self.boosts,
+                self.geo_strategy,
+                from,
+                length,
+                embedder_name,
+                embedder,
+                *quantization,
+                *distance,
+                self.time_budget.clone(),
+                self.ranking_score_threshold,
+            )?,
             _ => execute_search(
                 &mut ctx,
                 self.query.as_deref(),
@@ -251,9 +341,10 @@ impl<'a> Search<'a> {
                 universe,
                 &self.sort_criteria,
                 &self.distinct,
+                &self.boosts,
                 self.geo_strategy,
-                self.offset,
-                self.limit,
+                from,
+                length,
                 Some(self.words_limit),
                 &mut DefaultSearchLogger,
                 &mut DefaultSearchLogger,
@@ -269,13 +360,18 @@ impl<'a> Search<'a> {
             None => MatchingWords::default(),
         };
 
-        Ok(SearchResult {
+        let results = SearchResult {
             matching_words,
             candidates,
             document_scores,
             documents_ids,
             degraded,
             used_negative_operator,
+        };
+
+        Ok(match resolved_pinning {
+            Some(pinning) => pinning.apply_to_results(results, self.offset, self.limit),
+            None => results,
         })
     }
 }
@@ -285,6 +381,7 @@ impl fmt::Debug for Search<'_> {
         let Search {
             query,
             filter,
+            boosts,
             offset,
             limit,
             sort_criteria,
@@ -298,14 +395,17 @@ impl fmt::Debug for Search<'_> {
             rtxn: _,
             index: _,
             semantic,
+            sparse,
             time_budget,
             ranking_score_threshold,
             locales,
+            rerank,
         } = self;
         f.debug_struct("Search")
             .field("query", query)
             .field("vector", &"[...]")
             .field("filter", filter)
+            .field("boosts", boosts)
             .field("offset", offset)
             .field("limit", limit)
             .field("sort_criteria", sort_criteria)
@@ -319,13 +419,25 @@ impl fmt::Debug for Search<'_> {
                 "semantic.embedder_name",
                 &semantic.as_ref().map(|semantic| &semantic.embedder_name),
             )
+            .field("sparse.embedder_name", &sparse.as_ref().map(|sparse| &sparse.embedder_name))
             .field("time_budget", time_budget)
             .field("ranking_score_threshold", ranking_score_threshold)
             .field("locales", locales)
+            .field("rerank.limit", &rerank.as_ref().map(|rerank| rerank.limit))
             .finish()
     }
 }
 
+/// Documents matching the filter of a boost are ranked higher than the other documents.
+///
+/// When several boosts are given, the documents are ranked by the sum of the weights of the
+/// boosts they match, a negative weight lowers the rank of the matching documents.
+#[derive(Debug, Clone)]
+pub struct Boost<'a> {
+    pub filter: Filter<'a>,
+    pub weight: f64,
+}
+
 #[derive(Default, Debug)]
 pub struct SearchResult {
     pub matching_words: MatchingWords,
@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use roaring::RoaringBitmap;
+
+use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
+use crate::score_details::{self, ScoreDetails};
+use crate::{Boost, Result, SearchContext, SearchLogger};
+
+/// Ranks the documents by the decreasing sum of the weights of the boosts they match.
+///
+/// This ranking rule is inserted before the ranking rules of the settings when
+/// boosts are given at search time.
+pub struct FilterBoost<Query> {
+    /// The documents matching the filter of each boost along with its weight.
+    boosts: Vec<(RoaringBitmap, f64)>,
+    query: Option<Query>,
+    buckets: VecDeque<(f64, RoaringBitmap)>,
+}
+
+impl<Query> FilterBoost<Query> {
+    pub fn new(ctx: &SearchContext<'_>, boosts: &[Boost<'_>]) -> Result<Self> {
+        let boosts = boosts
+            .iter()
+            .map(|Boost { filter, weight }| Ok((filter.evaluate(ctx.txn, ctx.index)?, *weight)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self { boosts, query: None, buckets: VecDeque::new() })
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for FilterBoost<Query> {
+    fn id(&self) -> String {
+        "boost".to_owned()
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn start_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+        query: &Query,
+    ) -> Result<()> {
+        // split the universe in groups of documents matching the same boosts
+        let mut groups = vec![(0.0, universe.clone())];
+        for (docids, weight) in &self.boosts {
+            groups = groups
+                .into_iter()
+                .flat_map(|(total, group)| {
+                    let matching = &group & docids;
+                    let not_matching = group - &matching;
+                    [(total + weight, matching), (total, not_matching)]
+                })
+                .filter(|(_, group)| !group.is_empty())
+                .collect();
+        }
+
+        // the higher the sum of the weights the better
+        groups.sort_by(|(left, _), (right, _)| right.total_cmp(left));
+        self.buckets.clear();
+        for (weight, group) in groups {
+            match self.buckets.back_mut() {
+                Some((last, docids)) if *last == weight => *docids |= group,
+                _ => self.buckets.push_back((weight, group)),
+            }
+        }
+        self.query = Some(query.clone());
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.query.as_ref().unwrap().clone();
+        while let Some((weight, mut candidates)) = self.buckets.pop_front() {
+            candidates &= universe;
+            if !candidates.is_empty() {
+                return Ok(Some(RankingRuleOutput {
+                    query,
+                    candidates,
+                    score: ScoreDetails::Boost(score_details::Boost { weight }),
+                }));
+            }
+        }
+
+        Ok(Some(RankingRuleOutput {
+            query,
+            candidates: universe.clone(),
+            score: ScoreDetails::Boost(score_details::Boost { weight: 0.0 }),
+        }))
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.query = None;
+        self.buckets.clear();
+    }
+}
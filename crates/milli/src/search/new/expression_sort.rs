@@ -0,0 +1,181 @@
+use std::collections::VecDeque;
+
+use heed::types::{Bytes, Unit};
+use heed::RoTxn;
+use roaring::RoaringBitmap;
+
+use super::geo_sort::facet_values_prefix_key;
+use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
+use crate::heed_codec::facet::{FieldDocIdFacetCodec, OrderedF64Codec};
+use crate::ranking_expression::RankingExpression;
+use crate::score_details::{self, ScoreDetails};
+use crate::{FieldId, Index, Result, SearchContext, SearchLogger};
+
+/// Sorts the documents by the decreasing value of a ranking expression.
+///
+/// The expression is computed with the numeric facet values of the documents.
+/// When a field has several values, the highest one is used.
+/// Documents for which the expression can't be computed are returned in the last bucket.
+///
+/// The expression is computed for every document of the universe the rule is started with,
+/// which costs one lookup per field of the expression and per document. Placed after other
+/// ranking rules, the rule is only started with the buckets these rules return.
+pub struct ExpressionSort<Query> {
+    source: String,
+    expression: RankingExpression,
+    /// The field ids of the fields of the expression, `None` if the field doesn't exist in the index.
+    fields: Vec<(String, Option<FieldId>)>,
+    query: Option<Query>,
+    buckets: VecDeque<(f64, RoaringBitmap)>,
+}
+
+impl<Query> ExpressionSort<Query> {
+    pub fn new(index: &Index, rtxn: &RoTxn<'_>, source: String) -> Result<Self> {
+        let expression = RankingExpression::parse(&source).map_err(|error| {
+            crate::CriterionError::InvalidExpression { expression: source.clone(), error }
+        })?;
+        let fields_ids_map = index.fields_ids_map(rtxn)?;
+        let fields = expression
+            .fields()
+            .into_iter()
+            .map(|name| (name.to_string(), fields_ids_map.id(name)))
+            .collect();
+
+        Ok(Self { source, expression, fields, query: None, buckets: VecDeque::new() })
+    }
+
+    /// Computes the expression for a document, `field_ids` and `values` are in the same
+    /// order as `self.fields` and `values` is only a buffer reused between the documents.
+    fn document_value(
+        &self,
+        ctx: &SearchContext<'_>,
+        field_ids: &[FieldId],
+        values: &mut Vec<f64>,
+        docid: u32,
+    ) -> Result<Option<f64>> {
+        values.clear();
+        for &field_id in field_ids {
+            match max_facet_number_value(ctx, field_id, docid)? {
+                Some(value) => values.push(value),
+                None => return Ok(None),
+            }
+        }
+
+        let value = self.expression.evaluate(&|name| {
+            let position = self.fields.iter().position(|(field, _)| field == name)?;
+            values.get(position).copied()
+        });
+        Ok(value)
+    }
+
+    fn score(&self, value: Option<f64>) -> ScoreDetails {
+        ScoreDetails::Expression(score_details::Expression {
+            expression: self.source.clone(),
+            value,
+        })
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for ExpressionSort<Query> {
+    fn id(&self) -> String {
+        format!("expression:{}", self.source)
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::expression_sort")]
+    fn start_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+        query: &Query,
+    ) -> Result<()> {
+        // The documents that miss a field of the expression are directly left for the last bucket.
+        let mut candidates = universe.clone();
+        let mut field_ids = Vec::with_capacity(self.fields.len());
+        for (_, field_id) in &self.fields {
+            match field_id {
+                Some(field_id) => {
+                    candidates &= ctx.index.exists_faceted_documents_ids(ctx.txn, *field_id)?;
+                    field_ids.push(*field_id);
+                }
+                None => candidates.clear(),
+            }
+        }
+
+        let mut values = Vec::new();
+        let mut buffer = Vec::with_capacity(field_ids.len());
+        for docid in &candidates {
+            if let Some(value) = self.document_value(ctx, &field_ids, &mut buffer, docid)? {
+                values.push((value, docid));
+            }
+        }
+        // the higher the value the better
+        values.sort_unstable_by(|(left, _), (right, _)| right.total_cmp(left));
+
+        self.buckets.clear();
+        for (value, docid) in values {
+            match self.buckets.back_mut() {
+                Some((last, docids)) if *last == value => {
+                    docids.insert(docid);
+                }
+                _ => self.buckets.push_back((value, RoaringBitmap::from_iter([docid]))),
+            }
+        }
+        self.query = Some(query.clone());
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::expression_sort")]
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.query.as_ref().unwrap().clone();
+        while let Some((value, mut candidates)) = self.buckets.pop_front() {
+            candidates &= universe;
+            if !candidates.is_empty() {
+                return Ok(Some(RankingRuleOutput {
+                    query,
+                    candidates,
+                    score: self.score(Some(value)),
+                }));
+            }
+        }
+
+        // the remaining documents don't have a value for the expression
+        Ok(Some(RankingRuleOutput { query, candidates: universe.clone(), score: self.score(None) }))
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::expression_sort")]
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.query = None;
+        self.buckets.clear();
+    }
+}
+
+/// Returns the highest number value of the field in the document.
+///
+/// The values are ordered in the database, the highest one is therefore the last one.
+fn max_facet_number_value(
+    ctx: &SearchContext<'_>,
+    field_id: FieldId,
+    docid: u32,
+) -> Result<Option<f64>> {
+    let key = facet_values_prefix_key(field_id, docid);
+    let mut iter = ctx
+        .index
+        .field_id_docid_facet_f64s
+        .remap_types::<Bytes, Unit>()
+        .rev_prefix_iter(ctx.txn, &key)?
+        .remap_key_type::<FieldDocIdFacetCodec<OrderedF64Codec>>();
+    match iter.next().transpose()? {
+        Some(((_, _, value), ())) => Ok(Some(value)),
+        None => Ok(None),
+    }
+}
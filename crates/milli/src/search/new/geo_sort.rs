@@ -1,4 +1,4 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::iter::FromIterator;
 
 use heed::types::{Bytes, Unit};
@@ -11,20 +11,20 @@ use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait
 use crate::heed_codec::facet::{FieldDocIdFacetCodec, OrderedF64Codec};
 use crate::score_details::{self, ScoreDetails};
 use crate::{
-    distance_between_two_points, lat_lng_to_xyz, GeoPoint, Index, Result, SearchContext,
-    SearchLogger,
+    distance_between_two_points, lat_lng_to_xyz, FieldsIdsMap, GeoPoint, Index, Result,
+    SearchContext, SearchLogger,
 };
 
 const FID_SIZE: usize = 2;
 const DOCID_SIZE: usize = 4;
 
 #[allow(clippy::drop_non_drop)]
-fn facet_values_prefix_key(distinct: u16, id: u32) -> [u8; FID_SIZE + DOCID_SIZE] {
+pub(super) fn facet_values_prefix_key(distinct: u16, id: u32) -> [u8; FID_SIZE + DOCID_SIZE] {
     concat_arrays::concat_arrays!(distinct.to_be_bytes(), id.to_be_bytes())
 }
 
 /// Return an iterator over each number value in the given field of the given document.
-fn facet_number_values<'a>(
+pub(super) fn facet_number_values<'a>(
     docid: u32,
     field_id: u16,
     index: &Index,
@@ -81,6 +81,8 @@ pub struct GeoSort<Q: RankingRuleQueryTrait> {
     point: [f64; 2],
     field_ids: Option<[u16; 2]>,
     rtree: Option<RTree<GeoPoint>>,
+    /// The number of points of the documents having several points in the rtree.
+    points_per_document: HashMap<u32, usize>,
 
     cached_sorted_docids: VecDeque<(u32, [f64; 2])>,
     geo_candidates: RoaringBitmap,
@@ -101,6 +103,7 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
             geo_candidates: geo_faceted_docids,
             field_ids: None,
             rtree: None,
+            points_per_document: HashMap::new(),
             cached_sorted_docids: VecDeque::new(),
         })
     }
@@ -112,7 +115,6 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
         ctx: &mut SearchContext<'_>,
         geo_candidates: &RoaringBitmap,
     ) -> Result<()> {
-        debug_assert!(self.field_ids.is_some(), "fill_buffer can't be called without the lat&lng");
         debug_assert!(self.cached_sorted_docids.is_empty());
 
         // lazily initialize the rtree if needed by the strategy, and cache it in `self.rtree`
@@ -122,6 +124,7 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
                 Some(rtree)
             } else {
                 let rtree = ctx.index.geo_rtree(ctx.txn)?.expect("geo candidates but no rtree");
+                self.points_per_document = points_per_document(&rtree);
                 // insert rtree in cache and returns it.
                 // Can't use `get_or_insert_with` because getting the rtree from the DB is a fallible operation.
                 Some(&*self.rtree.insert(rtree))
@@ -130,12 +133,15 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
             None
         };
 
+        // A document is ranked by its nearest point to the queried point, in both orders.
         let cache_size = self.strategy.cache_size();
         if let Some(rtree) = rtree {
             if self.ascending {
+                // the first point of a document we encounter is its nearest one
+                let mut seen = RoaringBitmap::new();
                 let point = lat_lng_to_xyz(&self.point);
                 for point in rtree.nearest_neighbor_iter(&point) {
-                    if geo_candidates.contains(point.data.0) {
+                    if geo_candidates.contains(point.data.0) && seen.insert(point.data.0) {
                         self.cached_sorted_docids.push_back(point.data);
                         if self.cached_sorted_docids.len() >= cache_size {
                             break;
@@ -144,64 +150,116 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
                 }
             } else {
                 // in the case of the desc geo sort we look for the closest point to the opposite of the queried point
-                // and we insert the points in reverse order they get reversed when emptying the cache later on
+                // and we insert the points in reverse order they get reversed when emptying the cache later on.
+                // The nearest point of a document to the queried point is the last of its points we encounter.
+                let mut encountered = HashMap::<u32, usize>::new();
                 let point = lat_lng_to_xyz(&opposite_of(self.point));
                 for point in rtree.nearest_neighbor_iter(&point) {
-                    if geo_candidates.contains(point.data.0) {
-                        self.cached_sorted_docids.push_front(point.data);
-                        if self.cached_sorted_docids.len() >= cache_size {
-                            break;
+                    let docid = point.data.0;
+                    if !geo_candidates.contains(docid) {
+                        continue;
+                    }
+                    if let Some(&count) = self.points_per_document.get(&docid) {
+                        let encountered = encountered.entry(docid).or_default();
+                        *encountered += 1;
+                        if *encountered < count {
+                            continue;
                         }
                     }
+                    self.cached_sorted_docids.push_front(point.data);
+                    if self.cached_sorted_docids.len() >= cache_size {
+                        break;
+                    }
                 }
             }
         } else {
             // the iterative version
-            let [lat, lng] = self.field_ids.unwrap();
+            let geojson_docids = ctx.index.geojson_documents_ids(ctx.txn)?;
+            let fields_ids_map = ctx.index.fields_ids_map(ctx.txn)?;
 
             let mut documents = geo_candidates
                 .iter()
-                .map(|id| -> Result<_> { Ok((id, geo_value(id, lat, lng, ctx.index, ctx.txn)?)) })
-                .collect::<Result<Vec<(u32, [f64; 2])>>>()?;
-            // computing the distance between two points is expensive thus we cache the result
-            documents
-                .sort_by_cached_key(|(_, p)| distance_between_two_points(&self.point, p) as usize);
-            self.cached_sorted_docids.extend(documents);
+                .map(|id| -> Result<_> {
+                    let mut points = Vec::new();
+                    if let Some([lat, lng]) = self.field_ids {
+                        points.extend(geo_points(
+                            id,
+                            lat,
+                            lng,
+                            ctx.index,
+                            ctx.txn,
+                            &fields_ids_map,
+                        )?);
+                    }
+                    if geojson_docids.contains(id) {
+                        if let Some(geometry) =
+                            ctx.index.geojson_geometry(ctx.txn, &fields_ids_map, id)?
+                        {
+                            points.extend(geometry.points());
+                        }
+                    }
+                    let (distance, point) = points
+                        .into_iter()
+                        .map(|point| (distance_between_two_points(&self.point, &point), point))
+                        .min_by(|(left, _), (right, _)| left.total_cmp(right))
+                        .expect("A geo faceted document doesn't contain any point");
+                    Ok((id, point, distance))
+                })
+                .collect::<Result<Vec<(u32, [f64; 2], f64)>>>()?;
+            // computing the distance between two points is expensive thus we computed it once above
+            documents.sort_by_key(|(_, _, distance)| *distance as usize);
+            self.cached_sorted_docids
+                .extend(documents.into_iter().map(|(id, point, _)| (id, point)));
         };
 
         Ok(())
     }
 }
 
+/// Counts the points of the documents having several points in the rtree.
+fn points_per_document(rtree: &RTree<GeoPoint>) -> HashMap<u32, usize> {
+    let mut counts = HashMap::<u32, usize>::new();
+    for point in rtree.iter() {
+        *counts.entry(point.data.0).or_default() += 1;
+    }
+    counts.retain(|_, count| *count > 1);
+    counts
+}
+
 /// Extracts the lat and long values from a single document.
 ///
 /// If it is not able to find it in the facet number index it will extract it
 /// from the facet string index and parse it as f64 (as the geo extraction behaves).
-fn geo_value(
+/// The latitudes and longitudes of a document with several points cannot be paired
+/// from the facet indexes, in this case the points are read from the document itself.
+fn geo_points(
     docid: u32,
     field_lat: u16,
     field_lng: u16,
     index: &Index,
     rtxn: &RoTxn<'_>,
-) -> Result<[f64; 2]> {
-    let extract_geo = |geo_field: u16| -> Result<f64> {
-        match facet_number_values(docid, geo_field, index, rtxn)?.next() {
-            Some(Ok(((_, _, geo), ()))) => Ok(geo),
-            Some(Err(e)) => Err(e.into()),
-            None => match facet_string_values(docid, geo_field, index, rtxn)?.next() {
-                Some(Ok((_, geo))) => {
-                    Ok(geo.parse::<f64>().expect("cannot parse geo field as f64"))
-                }
-                Some(Err(e)) => Err(e.into()),
-                None => panic!("A geo faceted document doesn't contain any lat or lng"),
-            },
+    fields_ids_map: &FieldsIdsMap,
+) -> Result<Vec<[f64; 2]>> {
+    let extract_geo = |geo_field: u16| -> Result<Vec<f64>> {
+        let mut values = Vec::new();
+        for result in facet_number_values(docid, geo_field, index, rtxn)? {
+            let ((_, _, geo), ()) = result?;
+            values.push(geo);
         }
+        if values.is_empty() {
+            for result in facet_string_values(docid, geo_field, index, rtxn)? {
+                let (_, geo) = result?;
+                values.push(geo.parse::<f64>().expect("cannot parse geo field as f64"));
+            }
+        }
+        Ok(values)
     };
 
-    let lat = extract_geo(field_lat)?;
-    let lng = extract_geo(field_lng)?;
-
-    Ok([lat, lng])
+    match (extract_geo(field_lat)?.as_slice(), extract_geo(field_lng)?.as_slice()) {
+        ([], _) | (_, []) => Ok(Vec::new()),
+        ([lat], [lng]) => Ok(vec![[*lat, *lng]]),
+        _ => index.geo_points(rtxn, fields_ids_map, docid),
+    }
 }
 
 impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
@@ -228,9 +286,10 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
         }
 
         let fid_map = ctx.index.fields_ids_map(ctx.txn)?;
-        let lat = fid_map.id("_geo.lat").expect("geo candidates but no fid for lat");
-        let lng = fid_map.id("_geo.lng").expect("geo candidates but no fid for lng");
-        self.field_ids = Some([lat, lng]);
+        // the documents may only have `_geojson` geometries
+        let lat = fid_map.id("_geo.lat");
+        let lng = fid_map.id("_geo.lng");
+        self.field_ids = lat.zip(lng).map(|(lat, lng)| [lat, lng]);
         self.fill_buffer(ctx, &geo_candidates)?;
         Ok(())
     }
@@ -513,6 +513,7 @@ mod tests {
                 universe,
                 &None,
                 &None,
+                &[],
                 crate::search::new::GeoSortStrategy::default(),
                 0,
                 100,
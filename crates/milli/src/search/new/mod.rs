@@ -1,3 +1,4 @@
+mod boost;
 mod bucket_sort;
 mod db_cache;
 mod distinct;
@@ -7,6 +8,7 @@ mod interner;
 mod limits;
 mod logger;
 pub mod matches;
+mod pinning;
 mod query_graph;
 mod query_term;
 mod ranking_rule_graph;
@@ -15,23 +17,27 @@ mod resolve_query_graph;
 mod small_bitmap;
 
 mod exact_attribute;
+mod expression_sort;
 mod sort;
 mod vector_sort;
 
 #[cfg(test)]
 mod tests;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use boost::FilterBoost;
 use bucket_sort::{bucket_sort, BucketSortOutput};
 use charabia::{Language, TokenizerBuilder};
 use db_cache::DatabaseCache;
 use exact_attribute::ExactAttribute;
+use expression_sort::ExpressionSort;
 use graph_based_ranking_rule::{Exactness, Fid, Position, Proximity, Typo};
 use heed::RoTxn;
 use interner::{DedupInterner, Interner};
 pub use logger::visual::VisualSearchLogger;
 pub use logger::{DefaultSearchLogger, SearchLogger};
+pub(crate) use pinning::ResolvedPinning;
 use query_graph::{QueryGraph, QueryNode};
 use query_term::{
     located_query_terms_from_tokens, ExtractedTokens, LocatedQueryTerm, Phrase, QueryTerm,
@@ -54,10 +60,10 @@ use crate::index::PrefixSearch;
 use crate::localized_attributes_rules::LocalizedFieldIds;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::search::new::distinct::apply_distinct_rule;
-use crate::vector::Embedder;
+use crate::vector::{Embedder, VectorDistance, VectorQuantization};
 use crate::{
-    AscDesc, DocumentId, FieldId, Filter, Index, Member, Result, TermsMatchingStrategy, TimeBudget,
-    UserError, Weight,
+    AscDesc, Boost, DocumentId, FieldId, Filter, Index, Member, Result, TermsMatchingStrategy,
+    TimeBudget, UserError, Weight,
 };
 
 /// A structure used throughout the execution of a search query.
@@ -71,6 +77,10 @@ pub struct SearchContext<'ctx> {
     pub phrase_docids: PhraseDocIdsCache,
     pub restricted_fids: Option<RestrictedFids>,
     pub prefix_search: PrefixSearch,
+    /// The locales of the query, they select the synonym sets that apply to it.
+    synonym_locales: Vec<Language>,
+    /// The synonyms that apply to the query, loaded the first time a query term needs them.
+    synonyms: Option<HashMap<Vec<String>, Vec<Vec<String>>>>,
 }
 
 impl<'ctx> SearchContext<'ctx> {
@@ -100,9 +110,24 @@ impl<'ctx> SearchContext<'ctx> {
             phrase_docids: <_>::default(),
             restricted_fids: None,
             prefix_search,
+            synonym_locales: Vec::new(),
+            synonyms: None,
         })
     }
 
+    /// Returns the synonyms of the given words.
+    ///
+    /// The synonyms are only loaded once, with the locales of the query, and only by
+    /// the searches that build query terms.
+    pub fn synonyms_of(&mut self, words: &[String]) -> Result<Vec<Vec<String>>> {
+        if self.synonyms.is_none() {
+            let synonyms = self.index.synonyms_for_locales(self.txn, &self.synonym_locales)?;
+            self.synonyms = Some(synonyms);
+        }
+        let synonyms = self.synonyms.as_ref().and_then(|synonyms| synonyms.get(words));
+        Ok(synonyms.cloned().unwrap_or_default())
+    }
+
     pub fn is_prefix_search_allowed(&self) -> bool {
         self.prefix_search != PrefixSearch::Disabled
     }
@@ -317,6 +342,9 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Expression(expression) => {
+                ranking_rules.push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression)?));
+            }
         }
     }
     Ok(ranking_rules)
@@ -331,7 +359,8 @@ fn get_ranking_rules_for_vector<'ctx>(
     target: &[f32],
     embedder_name: &str,
     embedder: &Embedder,
-    quantized: bool,
+    quantization: VectorQuantization,
+    distance: VectorDistance,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     // query graph search
 
@@ -359,7 +388,8 @@ fn get_ranking_rules_for_vector<'ctx>(
                         limit_plus_offset,
                         embedder_name,
                         embedder,
-                        quantized,
+                        quantization,
+                        distance,
                     )?;
                     ranking_rules.push(Box::new(vector_sort));
                     vector = true;
@@ -393,6 +423,9 @@ fn get_ranking_rules_for_vector<'ctx>(
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Expression(expression) => {
+                ranking_rules.push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression)?));
+            }
         }
     }
 
@@ -503,6 +536,9 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Expression(expression) => {
+                ranking_rules.push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression)?));
+            }
         }
     }
     Ok(ranking_rules)
@@ -584,12 +620,14 @@ pub fn execute_vector_search(
     universe: RoaringBitmap,
     sort_criteria: &Option<Vec<AscDesc>>,
     distinct: &Option<String>,
+    boosts: &[Boost<'_>],
     geo_strategy: geo_sort::Strategy,
     from: usize,
     length: usize,
     embedder_name: &str,
     embedder: &Embedder,
-    quantized: bool,
+    quantization: VectorQuantization,
+    distance: VectorDistance,
     time_budget: TimeBudget,
     ranking_score_threshold: Option<f64>,
 ) -> Result<PartialSearchResult> {
@@ -597,7 +635,7 @@ pub fn execute_vector_search(
 
     // FIXME: input universe = universe & documents_with_vectors
     // for now if we're computing embeddings for ALL documents, we can assume that this is just universe
-    let ranking_rules = get_ranking_rules_for_vector(
+    let mut ranking_rules = get_ranking_rules_for_vector(
         ctx,
         sort_criteria,
         geo_strategy,
@@ -605,8 +643,12 @@ pub fn execute_vector_search(
         vector,
         embedder_name,
         embedder,
-        quantized,
+        quantization,
+        distance,
     )?;
+    if !boosts.is_empty() {
+        ranking_rules.insert(0, Box::new(FilterBoost::new(ctx, boosts)?));
+    }
 
     let mut placeholder_search_logger = logger::DefaultSearchLogger;
     let placeholder_search_logger: &mut dyn SearchLogger<PlaceholderQuery> =
@@ -647,6 +689,7 @@ pub fn execute_search(
     mut universe: RoaringBitmap,
     sort_criteria: &Option<Vec<AscDesc>>,
     distinct: &Option<String>,
+    boosts: &[Boost<'_>],
     geo_strategy: geo_sort::Strategy,
     from: usize,
     length: usize,
@@ -693,6 +736,7 @@ pub fn execute_search(
                 if !locales.is_empty() {
                     tokbuilder.allow_list(locales);
                 }
+                ctx.synonym_locales = locales.to_vec();
             }
             None => {
                 // If no locales are specified, we use the locales specified in the localized attributes rules
@@ -723,6 +767,7 @@ pub fn execute_search(
                 if !db_locales.is_empty() {
                     tokbuilder.allow_list(&db_locales);
                 }
+                ctx.synonym_locales = db_locales.clone();
             }
         };
 
@@ -758,12 +803,15 @@ pub fn execute_search(
         let (graph, new_located_query_terms) = QueryGraph::from_query(ctx, &query_terms)?;
         located_query_terms = Some(new_located_query_terms);
 
-        let ranking_rules = get_ranking_rules_for_query_graph_search(
+        let mut ranking_rules = get_ranking_rules_for_query_graph_search(
             ctx,
             sort_criteria,
             geo_strategy,
             terms_matching_strategy,
         )?;
+        if !boosts.is_empty() {
+            ranking_rules.insert(0, Box::new(FilterBoost::new(ctx, boosts)?));
+        }
 
         universe &=
             resolve_universe(ctx, &universe, &graph, terms_matching_strategy, query_graph_logger)?;
@@ -782,8 +830,11 @@ pub fn execute_search(
             ranking_score_threshold,
         )?
     } else {
-        let ranking_rules =
+        let mut ranking_rules =
             get_ranking_rules_for_placeholder_search(ctx, sort_criteria, geo_strategy)?;
+        if !boosts.is_empty() {
+            ranking_rules.insert(0, Box::new(FilterBoost::new(ctx, boosts)?));
+        }
         bucket_sort(
             ctx,
             ranking_rules,
@@ -0,0 +1,135 @@
+use roaring::RoaringBitmap;
+
+use super::distinct::distinct_single_docid;
+use crate::pinning::matching_rule;
+use crate::score_details::{self, ScoreDetails};
+use crate::{Result, SearchContext, SearchResult};
+
+/// The documents pinned and hidden by the pinning rule matching the query of a search.
+pub struct ResolvedPinning {
+    /// The pinned documents along with their position, sorted by position.
+    pinned: Vec<(usize, u32)>,
+    /// The ids of the pinned documents.
+    pinned_docids: RoaringBitmap,
+    /// The documents that must not be ranked by the ranking rules, either because they are hidden,
+    /// pinned, or share their distinct value with a pinned document.
+    excluded: RoaringBitmap,
+}
+
+impl ResolvedPinning {
+    /// Resolves the pinning rule of the index matching the query, if any.
+    ///
+    /// Only the pinned documents that are part of the universe are kept, so that the pinned
+    /// documents still respect the filter of the search.
+    pub fn new(
+        ctx: &SearchContext<'_>,
+        query: Option<&str>,
+        universe: &RoaringBitmap,
+        distinct: Option<&str>,
+    ) -> Result<Option<Self>> {
+        let rules = ctx.index.pinning_rules(ctx.txn)?;
+        let Some(rule) = matching_rule(&rules, query.unwrap_or_default()) else {
+            return Ok(None);
+        };
+
+        let distinct_field = match distinct {
+            Some(distinct) => Some(distinct),
+            None => ctx.index.distinct_field(ctx.txn)?,
+        };
+        let distinct_fid = match distinct_field {
+            Some(field) => ctx.index.fields_ids_map(ctx.txn)?.id(field),
+            None => None,
+        };
+
+        let external_ids = ctx.index.external_documents_ids();
+        let mut excluded = RoaringBitmap::new();
+        for id in &rule.hide {
+            if let Some(docid) = external_ids.get(ctx.txn, id)? {
+                excluded.insert(docid);
+            }
+        }
+
+        let mut pin: Vec<_> = rule.pin.iter().collect();
+        pin.sort_by_key(|pinned| pinned.position);
+
+        let mut pinned = Vec::with_capacity(pin.len());
+        let mut pinned_docids = RoaringBitmap::new();
+        for document in pin {
+            let Some(docid) = external_ids.get(ctx.txn, &document.id)? else { continue };
+            if !universe.contains(docid) || excluded.contains(docid) {
+                continue;
+            }
+            if let Some(distinct_fid) = distinct_fid {
+                distinct_single_docid(ctx.index, ctx.txn, distinct_fid, docid, &mut excluded)?;
+            }
+            excluded.insert(docid);
+            pinned_docids.insert(docid);
+            pinned.push((document.position, docid));
+        }
+
+        Ok(Some(Self { pinned, pinned_docids, excluded }))
+    }
+
+    /// The documents that must be removed from the universe given to the ranking rules.
+    pub fn excluded(&self) -> &RoaringBitmap {
+        &self.excluded
+    }
+
+    /// The ids of the pinned documents, that must be counted in the candidates of the search.
+    pub fn pinned_docids(&self) -> &RoaringBitmap {
+        &self.pinned_docids
+    }
+
+    /// Inserts the pinned documents at their position in the ranked documents
+    /// and returns the requested page.
+    ///
+    /// The ranked documents must start at the first result, not at `from`.
+    /// When there aren't enough ranked documents to reach the position of a pinned document,
+    /// it is placed right after the last ranked document.
+    pub fn apply(
+        &self,
+        docids: Vec<u32>,
+        scores: Vec<Vec<ScoreDetails>>,
+        from: usize,
+        length: usize,
+    ) -> (Vec<u32>, Vec<Vec<ScoreDetails>>) {
+        let limit = from.saturating_add(length);
+        let mut ranked = docids.into_iter().zip(scores);
+        let mut pinned = self.pinned.iter().copied().peekable();
+        let mut results = Vec::new();
+
+        while results.len() < limit {
+            let next = match pinned.next_if(|(position, _)| *position <= results.len()) {
+                Some((position, docid)) => Some((docid, pinned_score(position))),
+                None => ranked.next().or_else(|| {
+                    pinned.next().map(|(position, docid)| (docid, pinned_score(position)))
+                }),
+            };
+            match next {
+                Some(result) => results.push(result),
+                None => break,
+            }
+        }
+
+        results.into_iter().skip(from).unzip()
+    }
+
+    /// Inserts the pinned documents in the results of a search ranked without them
+    /// and returns the requested page, counting the pinned documents in the candidates.
+    pub fn apply_to_results(
+        &self,
+        mut results: SearchResult,
+        from: usize,
+        length: usize,
+    ) -> SearchResult {
+        let docids = std::mem::take(&mut results.documents_ids);
+        let scores = std::mem::take(&mut results.document_scores);
+        (results.documents_ids, results.document_scores) = self.apply(docids, scores, from, length);
+        results.candidates |= &self.pinned_docids;
+        results
+    }
+}
+
+fn pinned_score(position: usize) -> Vec<ScoreDetails> {
+    vec![ScoreDetails::Pinned(score_details::Pinned { position })]
+}
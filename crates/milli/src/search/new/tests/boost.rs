@@ -0,0 +1,103 @@
+/*!
+This module tests the boosts given at search time:
+
+1. documents are ranked by the decreasing sum of the weights of the boosts they match
+2. documents matching a boost with a negative weight are ranked after the documents matching no boost
+3. boosts are applied before the ranking rules of the settings
+4. the sum of the weights is exposed in the score details
+*/
+
+use meili_snap::insta;
+
+use crate::index::tests::TempIndex;
+use crate::score_details::{ScoreDetails, ScoringStrategy};
+use crate::{
+    Boost, Criterion, Filter, FilterableAttributesRule, Search, SearchResult, TermsMatchingStrategy,
+};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_filterable_fields(vec![
+                FilterableAttributesRule::Field("brand".to_owned()),
+                FilterableAttributesRule::Field("color".to_owned()),
+            ]);
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "hello", "brand": "acme", "color": "red" },
+            { "id": 1, "text": "hello world", "brand": "other", "color": "blue" },
+            { "id": 2, "text": "hello", "brand": "acme", "color": "blue" },
+            { "id": 3, "text": "hello world", "brand": "other", "color": "red" },
+            { "id": 4, "text": "hello world", "brand": "other", "color": "green" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn boosts() -> Vec<Boost<'static>> {
+    [("brand = acme", 2.0), ("color = blue", 1.0), ("color = green", -1.0)]
+        .into_iter()
+        .map(|(filter, weight)| Boost {
+            filter: Filter::from_str(filter).unwrap().unwrap(),
+            weight,
+        })
+        .collect()
+}
+
+fn boost_weights(document_scores: &[Vec<ScoreDetails>]) -> String {
+    let weights: Vec<_> = document_scores
+        .iter()
+        .map(|details| {
+            details.iter().find_map(|detail| match detail {
+                ScoreDetails::Boost(boost) => Some(boost.weight),
+                _ => None,
+            })
+        })
+        .collect();
+    format!("{weights:?}")
+}
+
+#[test]
+fn boost_placeholder() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.boosts(boosts());
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 0, 1, 3, 4]");
+    insta::assert_snapshot!(boost_weights(&document_scores), @"[Some(3.0), Some(2.0), Some(1.0), Some(0.0), Some(-1.0)]");
+
+    let details = serde_json::Value::Object(ScoreDetails::to_json_map(document_scores[0].iter()));
+    insta::assert_snapshot!(details, @r###"{"boost":{"order":0,"weight":3.0}}"###);
+}
+
+#[test]
+fn boost_before_ranking_rules() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.query("hello world");
+    s.boosts(boosts());
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // the boosted documents only containing `hello` are ranked first
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 0, 1, 3, 4]");
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.query("hello world");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 3, 4, 0, 2]");
+}
@@ -0,0 +1,85 @@
+/*!
+This module tests the `expression` ranking rule:
+
+1. documents are sorted by the decreasing value of the expression
+2. if a field contains an array, its highest number is used
+3. documents for which the expression can't be computed (missing field, non-numeric value) appear at the end
+4. the computed value is exposed in the score details
+*/
+
+use meili_snap::insta;
+
+use crate::index::tests::TempIndex;
+use crate::score_details::{ScoreDetails, ScoringStrategy};
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![
+                Criterion::Words,
+                Criterion::Expression("sales * 0.5 + rating".to_owned()),
+            ]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "hello world", "sales": 2, "rating": 1 },
+            { "id": 1, "text": "hello world", "sales": 4, "rating": 0 },
+            { "id": 2, "text": "hello world", "sales": 0, "rating": 5 },
+            { "id": 3, "text": "hello world", "sales": [1, 6], "rating": 0 },
+            { "id": 4, "text": "hello world", "rating": 3 },
+            { "id": 5, "text": "hello world", "sales": "many", "rating": 1 },
+            { "id": 6, "text": "hello", "sales": 10, "rating": 0.5 },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn expression_values(document_scores: &[Vec<ScoreDetails>]) -> String {
+    let values: Vec<_> = document_scores
+        .iter()
+        .map(|details| {
+            details.iter().find_map(|detail| match detail {
+                ScoreDetails::Expression(expression) => Some(expression.value),
+                _ => None,
+            })
+        })
+        .collect();
+    format!("{values:?}")
+}
+
+#[test]
+fn expression_placeholder() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[6, 2, 3, 0, 1, 4, 5]");
+    insta::assert_snapshot!(expression_values(&document_scores), @"[Some(Some(5.5)), Some(Some(5.0)), Some(Some(3.0)), Some(Some(2.0)), Some(Some(2.0)), Some(None), Some(None)]");
+
+    let details = serde_json::Value::Object(ScoreDetails::to_json_map(document_scores[0].iter()));
+    insta::assert_snapshot!(details, @r###"{"expression:sales * 0.5 + rating":{"order":0,"value":5.5}}"###);
+}
+
+#[test]
+fn expression_after_words() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.query("hello world");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // the document that only matches `hello` is ranked last, despite its high value
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 3, 0, 1, 4, 5, 6]");
+}
@@ -308,3 +308,51 @@ fn geo_sort_without_any_geo_faceted_documents() {
     insta::assert_snapshot!(format!("{ids:?}"), @"[0, 2, 3]");
     insta::assert_snapshot!(format!("{scores:#?}"));
 }
+
+#[test]
+fn geo_sort_with_multiple_points_per_document() {
+    let index = create_index();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, RESERVED_GEO_FIELD_NAME: [{ "lat": 10, "lng": 10 }, { "lat": 1, "lng": 1 }] },
+            { "id": 1, RESERVED_GEO_FIELD_NAME: { "lat": 2, "lng": 2 } },
+            { "id": 2, RESERVED_GEO_FIELD_NAME: [
+                { "lat": -3, "lng": -3 },
+                { "lat": 0.5, "lng": 0.5 },
+                { "lat": 20, "lng": 20 },
+            ] },
+            { "id": 3, RESERVED_GEO_FIELD_NAME: [{ "lat": 5, "lng": 5 }] },
+            { "id": 4 },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&rtxn, &index);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+
+    // the documents are ranked by their nearest point, which is the one reported in the details
+    let matched_points = |scores: &[Vec<ScoreDetails>]| {
+        let points: Vec<_> = scores
+            .iter()
+            .map(|details| {
+                details.iter().find_map(|detail| match detail {
+                    ScoreDetails::GeoSort(geo_sort) => geo_sort.value,
+                    _ => None,
+                })
+            })
+            .collect();
+        format!("{points:?}")
+    };
+
+    s.sort_criteria(vec![AscDesc::Asc(Member::Geo([0., 0.]))]);
+    let (ids, scores) = execute_iterative_and_rtree_returns_the_same(&rtxn, &index, &mut s);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[2, 0, 1, 3, 4]");
+    insta::assert_snapshot!(matched_points(&scores), @"[Some([0.5, 0.5]), Some([1.0, 1.0]), Some([2.0, 2.0]), Some([5.0, 5.0]), None]");
+
+    s.sort_criteria(vec![AscDesc::Desc(Member::Geo([0., 0.]))]);
+    let (ids, scores) = execute_iterative_and_rtree_returns_the_same(&rtxn, &index, &mut s);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[3, 1, 0, 2, 4]");
+    insta::assert_snapshot!(matched_points(&scores), @"[Some([5.0, 5.0]), Some([2.0, 2.0]), Some([1.0, 1.0]), Some([0.5, 0.5]), None]");
+}
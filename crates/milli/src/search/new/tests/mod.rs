@@ -1,14 +1,17 @@
 pub mod attribute_fid;
 pub mod attribute_position;
+pub mod boost;
 pub mod cutoff;
 pub mod distinct;
 pub mod exactness;
+pub mod expression_sort;
 pub mod geo_sort;
 pub mod integration;
 #[cfg(feature = "all-tokenizations")]
 #[cfg(not(feature = "chinese-pinyin"))]
 pub mod language;
 pub mod ngram_split_words;
+pub mod pinning;
 pub mod proximity;
 pub mod proximity_typo;
 pub mod sort;
@@ -0,0 +1,144 @@
+/*!
+This module tests the pinning rules of the settings:
+
+1. the pinned documents are inserted at their position and the hidden documents are removed
+2. the rules only apply to the queries matching their query, regardless of the case and spacing
+3. the pinned documents must match the filter of the search
+4. the pinned documents are deduplicated with the distinct attribute
+5. the pinned documents are inserted before the pagination and counted in the candidates
+6. a pinned document whose position is after the last ranked document is placed after it
+7. the pinned documents are marked in the score details
+*/
+
+use big_s::S;
+use meili_snap::insta;
+
+use crate::index::tests::TempIndex;
+use crate::pinning::PinnedDocument;
+use crate::score_details::{ScoreDetails, ScoringStrategy};
+use crate::{Criterion, Filter, FilterableAttributesRule, PinningRule, Search, SearchResult};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_filterable_fields(vec![
+                FilterableAttributesRule::Field("brand".to_owned()),
+                FilterableAttributesRule::Field("color".to_owned()),
+            ]);
+            s.set_criteria(vec![Criterion::Words]);
+            s.set_pinning(vec![
+                PinningRule {
+                    query: S("Hello"),
+                    pin: vec![
+                        PinnedDocument { id: S("3"), position: 2 },
+                        PinnedDocument { id: S("4"), position: 0 },
+                        PinnedDocument { id: S("9"), position: 1 },
+                    ],
+                    hide: vec![S("1")],
+                },
+                PinningRule {
+                    query: S("hello world"),
+                    pin: vec![PinnedDocument { id: S("5"), position: 10 }],
+                    hide: vec![],
+                },
+            ]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "hello", "color": "red", "brand": "acme" },
+            { "id": 1, "text": "hello", "color": "red", "brand": "globex" },
+            { "id": 2, "text": "hello", "color": "red", "brand": "initech" },
+            { "id": 3, "text": "hello", "color": "blue", "brand": "umbrella" },
+            { "id": 4, "text": "hello", "color": "blue", "brand": "acme" },
+            { "id": 5, "text": "hello", "color": "red", "brand": "hooli" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+#[test]
+fn pinning_rule() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("hello");
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, candidates, .. } = s.execute().unwrap();
+    // `4` and `3` are pinned, `1` is hidden, and the unknown document `9` is ignored
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[4, 0, 3, 2, 5]");
+    insta::assert_snapshot!(format!("{candidates:?}"), @"RoaringBitmap<[0, 2, 3, 4, 5]>");
+
+    let details = serde_json::Value::Object(ScoreDetails::to_json_map(document_scores[2].iter()));
+    insta::assert_snapshot!(details, @r###"{"pinned":{"order":0,"position":2}}"###);
+
+    // the rule doesn't apply to a placeholder search
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2, 3, 4, 5]");
+}
+
+#[test]
+fn pinning_rule_with_pagination() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("  HELLO ");
+    s.offset(1);
+    s.limit(2);
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 3]");
+    insta::assert_snapshot!(format!("{}", candidates.len()), @"5");
+}
+
+#[test]
+fn pinning_rule_with_filter() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("hello");
+    s.filter(Filter::from_str("color = red").unwrap().unwrap());
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    // the pinned documents are blue
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 5]");
+    insta::assert_snapshot!(format!("{candidates:?}"), @"RoaringBitmap<[0, 2, 5]>");
+}
+
+#[test]
+fn pinning_rule_with_distinct() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("hello");
+    s.distinct(S("brand"));
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // `0` has the same brand as the pinned document `4`
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[4, 2, 3, 5]");
+}
+
+#[test]
+fn pinning_rule_after_the_last_document() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("hello world");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2, 3, 4, 5]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("hello world");
+    s.limit(3);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2]");
+}
@@ -4,18 +4,24 @@ use roaring::RoaringBitmap;
 
 use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
 use crate::score_details::{self, ScoreDetails};
-use crate::vector::{ArroyWrapper, DistributionShift, Embedder};
+use crate::vector::{
+    ArroyWrapper, DistributionShift, Embedder, VectorDistance, VectorQuantization,
+};
 use crate::{DocumentId, Result, SearchContext, SearchLogger};
 
 pub struct VectorSort<Q: RankingRuleQueryTrait> {
     query: Option<Q>,
     target: Vec<f32>,
     vector_candidates: RoaringBitmap,
-    cached_sorted_docids: std::vec::IntoIter<(DocumentId, f32)>,
+    cached_sorted_docids: std::vec::IntoIter<(DocumentId, f32, Option<u8>)>,
     limit: usize,
     distribution_shift: Option<DistributionShift>,
     embedder_index: u8,
-    quantized: bool,
+    quantization: VectorQuantization,
+    distance: VectorDistance,
+    sparse: bool,
+    /// The dimensions of each token embedding of the target, for late interaction embedders
+    late_interaction: Option<usize>,
 }
 
 impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
@@ -26,7 +32,8 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
         limit: usize,
         embedder_name: &str,
         embedder: &Embedder,
-        quantized: bool,
+        quantization: VectorQuantization,
+        distance: VectorDistance,
     ) -> Result<Self> {
         let embedder_index = ctx
             .index
@@ -42,7 +49,10 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
             limit,
             distribution_shift: embedder.distribution(),
             embedder_index,
-            quantized,
+            quantization,
+            distance,
+            sparse: embedder.is_sparse(),
+            late_interaction: embedder.is_late_interaction().then(|| embedder.dimensions()),
         })
     }
 
@@ -53,8 +63,32 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
     ) -> Result<()> {
         let target = &self.target;
 
-        let reader = ArroyWrapper::new(ctx.index.vector_arroy, self.embedder_index, self.quantized);
-        let results = reader.nns_by_vector(ctx.txn, target, self.limit, Some(vector_candidates))?;
+        let reader = ArroyWrapper::new(
+            ctx.index.vector_arroy,
+            ctx.index.vector_scalar_quantized,
+            self.sparse.then_some(ctx.index.vector_sparse),
+            self.embedder_index,
+            self.quantization,
+            self.distance,
+        );
+        let results: Vec<_> = match self.late_interaction {
+            Some(dimensions) => reader
+                .nns_by_late_interaction_vector(
+                    ctx.txn,
+                    target,
+                    dimensions,
+                    self.limit,
+                    Some(vector_candidates),
+                )?
+                .into_iter()
+                .map(|(docid, distance)| (docid, distance, None))
+                .collect(),
+            None => reader
+                .nns_by_vector_with_positions(ctx.txn, target, self.limit, Some(vector_candidates))?
+                .into_iter()
+                .map(|(docid, distance, position)| (docid, distance, Some(position)))
+                .collect(),
+        };
         self.cached_sorted_docids = results.into_iter();
 
         Ok(())
@@ -97,13 +131,16 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
             return Ok(Some(RankingRuleOutput {
                 query,
                 candidates: universe.clone(),
-                score: ScoreDetails::Vector(score_details::Vector { similarity: None }),
+                score: ScoreDetails::Vector(score_details::Vector {
+                    similarity: None,
+                    chunk: None,
+                }),
             }));
         }
 
-        for (docid, distance) in self.cached_sorted_docids.by_ref() {
+        for (docid, distance, position) in self.cached_sorted_docids.by_ref() {
             if vector_candidates.contains(docid) {
-                let score = 1.0 - distance;
+                let score = self.distance.similarity(distance);
                 let score = self
                     .distribution_shift
                     .map(|distribution| distribution.shift(score))
@@ -111,7 +148,10 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
                 return Ok(Some(RankingRuleOutput {
                     query,
                     candidates: RoaringBitmap::from_iter([docid]),
-                    score: ScoreDetails::Vector(score_details::Vector { similarity: Some(score) }),
+                    score: ScoreDetails::Vector(score_details::Vector {
+                        similarity: Some(score),
+                        chunk: position,
+                    }),
                 }));
             }
         }
@@ -127,7 +167,10 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
             return Ok(Some(RankingRuleOutput {
                 query,
                 candidates: universe.clone(),
-                score: ScoreDetails::Vector(score_details::Vector { similarity: None }),
+                score: ScoreDetails::Vector(score_details::Vector {
+                    similarity: None,
+                    chunk: None,
+                }),
             }));
         }
 
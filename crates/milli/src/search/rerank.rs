@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::reranker::Reranker;
+use crate::score_details::{self, ScoreDetails};
+use crate::{Result, Search, SearchResult};
+
+/// Reranks the first hits of a search with the reranker of the index.
+#[derive(Debug, Clone)]
+pub struct RerankSearch {
+    pub reranker: Arc<Reranker>,
+    /// The number of first hits sent to the reranker.
+    pub limit: usize,
+}
+
+impl<'a> Search<'a> {
+    /// The same search without the pagination, returning at least the hits to rerank.
+    pub(super) fn reranking_window(&self, rerank: &RerankSearch) -> Search<'a> {
+        Search {
+            query: self.query.clone(),
+            filter: self.filter.clone(),
+            boosts: self.boosts.clone(),
+            offset: 0,
+            limit: (self.offset + self.limit).max(rerank.limit),
+            sort_criteria: self.sort_criteria.clone(),
+            distinct: self.distinct.clone(),
+            searchable_attributes: self.searchable_attributes,
+            geo_strategy: self.geo_strategy,
+            terms_matching_strategy: self.terms_matching_strategy,
+            scoring_strategy: self.scoring_strategy,
+            words_limit: self.words_limit,
+            exhaustive_number_hits: self.exhaustive_number_hits,
+            rtxn: self.rtxn,
+            index: self.index,
+            semantic: self.semantic.clone(),
+            sparse: self.sparse.clone(),
+            time_budget: self.time_budget.clone(),
+            ranking_score_threshold: self.ranking_score_threshold,
+            locales: self.locales.clone(),
+            rerank: None,
+        }
+    }
+
+    /// Reorders the first hits of the results of the reranking window by the scores of the reranker,
+    /// then applies the pagination.
+    ///
+    /// The pinned documents keep their position, and the results are left in their order
+    /// when the reranker fails, as when the embedder fails in a hybrid search.
+    pub(super) fn rerank_and_paginate(
+        &self,
+        rerank: &RerankSearch,
+        mut results: SearchResult,
+    ) -> Result<SearchResult> {
+        // there is nothing to score the documents against in a placeholder search
+        if let Some(query) = self.query.as_deref().filter(|query| !query.trim().is_empty()) {
+            let window = rerank.limit.min(results.documents_ids.len());
+            let positions: Vec<usize> = (0..window)
+                .filter(|&position| {
+                    !matches!(
+                        results.document_scores[position].first(),
+                        Some(ScoreDetails::Pinned(_))
+                    )
+                })
+                .collect();
+            let docids: Vec<_> =
+                positions.iter().map(|&position| results.documents_ids[position]).collect();
+
+            let documents = rerank.reranker.render_documents(self.index, self.rtxn, &docids)?;
+            match rerank.reranker.rerank(query, &documents) {
+                Ok(scores) => {
+                    let mut reranked: Vec<_> = positions
+                        .iter()
+                        .zip(scores)
+                        .map(|(&position, score)| {
+                            let docid = results.documents_ids[position];
+                            let scores = std::mem::take(&mut results.document_scores[position]);
+                            (docid, scores, score)
+                        })
+                        .collect();
+                    // the sort is stable, so that documents with the same score keep their order
+                    reranked.sort_by(|(_, _, left), (_, _, right)| right.total_cmp(left));
+
+                    for (rank, (&position, (docid, scores, score))) in
+                        positions.iter().zip(reranked).enumerate()
+                    {
+                        let details = score_details::Rerank { rank: rank as u32 + 1, score };
+                        results.documents_ids[position] = docid;
+                        results.document_scores[position] =
+                            std::iter::once(ScoreDetails::Rerank(details)).chain(scores).collect();
+                    }
+                }
+                Err(error) => tracing::error!(error=%error, "Reranking failed"),
+            }
+        }
+
+        let SearchResult { documents_ids, document_scores, .. } = &mut results;
+        let offset = self.offset.min(documents_ids.len());
+        documents_ids.drain(..offset);
+        documents_ids.truncate(self.limit);
+        document_scores.drain(..offset);
+        document_scores.truncate(self.limit);
+        Ok(results)
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use roaring::RoaringBitmap;
 
 use crate::score_details::{self, ScoreDetails};
-use crate::vector::{ArroyWrapper, Embedder};
+use crate::vector::{ArroyWrapper, Embedder, VectorDistance, VectorQuantization};
 use crate::{filtered_universe, DocumentId, Filter, Index, Result, SearchResult};
 
 pub struct Similar<'a> {
@@ -17,7 +17,8 @@ pub struct Similar<'a> {
     embedder_name: String,
     embedder: Arc<Embedder>,
     ranking_score_threshold: Option<f64>,
-    quantized: bool,
+    quantization: VectorQuantization,
+    distance: VectorDistance,
 }
 
 impl<'a> Similar<'a> {
@@ -30,7 +31,8 @@ impl<'a> Similar<'a> {
         rtxn: &'a heed::RoTxn<'a>,
         embedder_name: String,
         embedder: Arc<Embedder>,
-        quantized: bool,
+        quantization: VectorQuantization,
+        distance: VectorDistance,
     ) -> Self {
         Self {
             id,
@@ -42,7 +44,8 @@ impl<'a> Similar<'a> {
             embedder_name,
             embedder,
             ranking_score_threshold: None,
-            quantized,
+            quantization,
+            distance,
         }
     }
 
@@ -69,13 +72,28 @@ impl<'a> Similar<'a> {
                 || crate::UserError::InvalidSimilarEmbedder(self.embedder_name.to_owned()),
             )?;
 
-        let reader = ArroyWrapper::new(self.index.vector_arroy, embedder_index, self.quantized);
-        let results = reader.nns_by_item(
-            self.rtxn,
-            self.id,
-            self.limit + self.offset + 1,
-            Some(&universe),
-        )?;
+        let reader = ArroyWrapper::new(
+            self.index.vector_arroy,
+            self.index.vector_scalar_quantized,
+            self.embedder.is_sparse().then_some(self.index.vector_sparse),
+            embedder_index,
+            self.quantization,
+            self.distance,
+        );
+        let limit = self.limit + self.offset + 1;
+        let results = if self.embedder.is_late_interaction() {
+            // the token embeddings of the document are the late interaction query
+            let query = reader.item_vectors(self.rtxn, self.id)?.concat();
+            reader.nns_by_late_interaction_vector(
+                self.rtxn,
+                &query,
+                self.embedder.dimensions(),
+                limit,
+                Some(&universe),
+            )?
+        } else {
+            reader.nns_by_item(self.rtxn, self.id, limit, Some(&universe))?
+        };
 
         let mut documents_ids = Vec::with_capacity(self.limit);
         let mut document_scores = Vec::with_capacity(self.limit);
@@ -94,15 +112,17 @@ impl<'a> Similar<'a> {
             // take **after** filter and skip so that we get exactly limit elements if available
             .take(self.limit)
         {
-            let score = 1.0 - distance;
+            let score = self.distance.similarity(distance);
             let score = self
                 .embedder
                 .distribution()
                 .map(|distribution| distribution.shift(score))
                 .unwrap_or(score);
 
-            let score_details =
-                vec![ScoreDetails::Vector(score_details::Vector { similarity: Some(score) })];
+            let score_details = vec![ScoreDetails::Vector(score_details::Vector {
+                similarity: Some(score),
+                chunk: None,
+            })];
 
             let score = ScoreDetails::global_score(score_details.iter());
 
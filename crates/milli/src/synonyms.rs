@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use charabia::Language;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A list of synonym rules, optionally restricted to the queries in some locales.
+///
+/// The rules are written in the Solr synonyms format:
+/// - `tv, television, telly` is an equivalence group, each term is a synonym of the others.
+/// - `phone => smartphone, mobile` is a one-way rule, the terms on the left are expanded with
+///   the terms on the right but not the other way around.
+///
+/// A set without any locale applies to every query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SynonymSet {
+    pub rules: Vec<String>,
+    #[schema(value_type = Vec<String>)]
+    pub locales: Vec<Language>,
+}
+
+impl SynonymSet {
+    pub fn new(rules: Vec<String>, locales: Vec<Language>) -> Self {
+        Self { rules, locales }
+    }
+
+    /// Returns `true` if the set applies to a query in any of the given locales.
+    pub fn matches_locales(&self, locales: &[Language]) -> bool {
+        matches_locales(&self.locales, locales)
+    }
+}
+
+/// A [`SynonymSet`] whose rules have been expanded and normalized with the tokenizer of the index.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct NormalizedSynonymSet {
+    pub locales: Vec<Language>,
+    pub synonyms: HashMap<Vec<String>, Vec<Vec<String>>>,
+}
+
+impl NormalizedSynonymSet {
+    /// Returns `true` if the set applies to a query in any of the given locales.
+    pub fn matches_locales(&self, locales: &[Language]) -> bool {
+        matches_locales(&self.locales, locales)
+    }
+}
+
+fn matches_locales(set_locales: &[Language], locales: &[Language]) -> bool {
+    set_locales.is_empty() || set_locales.iter().any(|locale| locales.contains(locale))
+}
+
+/// A single parsed synonym rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SynonymRule<'a> {
+    /// `a, b, c`: every term is a synonym of the others.
+    Equivalence(Vec<&'a str>),
+    /// `a, b => c, d`: `a` and `b` are expanded with `c` and `d`.
+    OneWay { words: Vec<&'a str>, synonyms: Vec<&'a str> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SynonymRuleError {
+    #[error("terms must be separated by a single comma and cannot be empty.")]
+    EmptyTerm,
+    #[error("a rule can contain at most one `=>`.")]
+    TooManyArrows,
+    #[error("an equivalence group must contain at least two terms.")]
+    NotEnoughTerms,
+}
+
+impl<'a> SynonymRule<'a> {
+    pub fn parse(rule: &'a str) -> Result<Self, SynonymRuleError> {
+        let mut sides = rule.split("=>");
+        let left = sides.next().unwrap_or_default();
+        match (sides.next(), sides.next()) {
+            (None, _) => {
+                let terms = parse_terms(left)?;
+                if terms.len() < 2 {
+                    return Err(SynonymRuleError::NotEnoughTerms);
+                }
+                Ok(SynonymRule::Equivalence(terms))
+            }
+            (Some(right), None) => {
+                Ok(SynonymRule::OneWay { words: parse_terms(left)?, synonyms: parse_terms(right)? })
+            }
+            (Some(_), Some(_)) => Err(SynonymRuleError::TooManyArrows),
+        }
+    }
+
+    /// Returns each word of the rule associated with its synonyms.
+    pub fn expand(&self) -> Vec<(&'a str, Vec<&'a str>)> {
+        match self {
+            SynonymRule::Equivalence(terms) => terms
+                .iter()
+                .map(|term| (*term, terms.iter().filter(|other| *other != term).copied().collect()))
+                .collect(),
+            SynonymRule::OneWay { words, synonyms } => {
+                words.iter().map(|word| (*word, synonyms.clone())).collect()
+            }
+        }
+    }
+}
+
+fn parse_terms(side: &str) -> Result<Vec<&str>, SynonymRuleError> {
+    side.split(',')
+        .map(str::trim)
+        .map(|term| if term.is_empty() { Err(SynonymRuleError::EmptyTerm) } else { Ok(term) })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rules() {
+        assert_eq!(
+            SynonymRule::parse("tv, television , telly").unwrap(),
+            SynonymRule::Equivalence(vec!["tv", "television", "telly"])
+        );
+        assert_eq!(
+            SynonymRule::parse("phone, cell => smartphone, mobile phone").unwrap(),
+            SynonymRule::OneWay {
+                words: vec!["phone", "cell"],
+                synonyms: vec!["smartphone", "mobile phone"]
+            }
+        );
+
+        assert_eq!(SynonymRule::parse("tv").unwrap_err(), SynonymRuleError::NotEnoughTerms);
+        assert_eq!(SynonymRule::parse("tv,, telly").unwrap_err(), SynonymRuleError::EmptyTerm);
+        assert_eq!(SynonymRule::parse("tv =>").unwrap_err(), SynonymRuleError::EmptyTerm);
+        assert_eq!(SynonymRule::parse("=> tv").unwrap_err(), SynonymRuleError::EmptyTerm);
+        assert_eq!(SynonymRule::parse("a => b => c").unwrap_err(), SynonymRuleError::TooManyArrows);
+    }
+
+    #[test]
+    fn expand_rules() {
+        let rule = SynonymRule::parse("tv, television, telly").unwrap();
+        assert_eq!(
+            rule.expand(),
+            vec![
+                ("tv", vec!["television", "telly"]),
+                ("television", vec!["tv", "telly"]),
+                ("telly", vec!["tv", "television"]),
+            ]
+        );
+
+        let rule = SynonymRule::parse("phone, cell => smartphone").unwrap();
+        assert_eq!(
+            rule.expand(),
+            vec![("phone", vec!["smartphone"]), ("cell", vec!["smartphone"])]
+        );
+    }
+}
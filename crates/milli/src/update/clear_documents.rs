@@ -46,6 +46,8 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_scalar_quantized,
+            vector_sparse,
             embedder_category_id: _,
             documents,
         } = self.index;
@@ -62,6 +64,8 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         self.index.put_field_distribution(self.wtxn, &FieldDistribution::default())?;
         self.index.delete_geo_rtree(self.wtxn)?;
         self.index.delete_geo_faceted_documents_ids(self.wtxn)?;
+        self.index.delete_geojson_documents_ids(self.wtxn)?;
+        self.index.delete_geojson_rtree(self.wtxn)?;
 
         // Remove all user-provided bits from the configs
         let mut configs = self.index.embedding_configs(self.wtxn)?;
@@ -93,6 +97,8 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         field_id_docid_facet_strings.clear(self.wtxn)?;
         // vector
         vector_arroy.clear(self.wtxn)?;
+        vector_scalar_quantized.clear(self.wtxn)?;
+        vector_sparse.clear(self.wtxn)?;
 
         documents.clear(self.wtxn)?;
 
@@ -136,6 +142,8 @@ mod tests {
         assert!(index.field_distribution(&rtxn).unwrap().is_empty());
         assert!(index.geo_rtree(&rtxn).unwrap().is_none());
         assert!(index.geo_faceted_documents_ids(&rtxn).unwrap().is_empty());
+        assert!(index.geojson_documents_ids(&rtxn).unwrap().is_empty());
+        assert!(index.geojson_rtree(&rtxn).unwrap().is_none());
 
         assert!(index.word_docids.is_empty(&rtxn).unwrap());
         assert!(index.word_prefix_docids.is_empty(&rtxn).unwrap());
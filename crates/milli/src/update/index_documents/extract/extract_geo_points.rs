@@ -1,19 +1,22 @@
 use std::fs::File;
 use std::io::{self, BufReader};
 
-use concat_arrays::concat_arrays;
 use serde_json::Value;
 
 use super::helpers::{create_writer, writer_into_reader, GrenadParameters};
 use crate::error::GeoError;
+use crate::geojson::GeoJsonGeometry;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::index_documents::extract_finite_float_from_value;
 use crate::update::settings::{InnerIndexSettings, InnerIndexSettingsDiff};
 use crate::{FieldId, InternalError, Result};
 
-/// Extracts the geographical coordinates contained in each document under the `_geo` field.
+/// Extracts the geographical coordinates contained in each document under the `_geo` and
+/// `_geojson` fields.
 ///
-/// Returns the generated grenad reader containing the docid as key associated to the (latitude, longitude)
+/// Returns the generated grenad reader containing the docid as key associated to a byte telling if
+/// the document has a `_geojson` geometry or polygon, the envelope of the polygon, and the list of
+/// (latitude, longitude).
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
 pub fn extract_geo_points<R: io::Read + io::Seek>(
     obkv_documents: grenad::Reader<R>,
@@ -40,20 +43,16 @@ pub fn extract_geo_points<R: io::Read + io::Seek>(
         };
 
         // extract old version
-        let del_lat_lng = extract_lat_lng(obkv, &settings_diff.old, DelAdd::Deletion, document_id)?;
+        let del_points = extract_points(obkv, &settings_diff.old, DelAdd::Deletion, document_id)?;
         // extract new version
-        let add_lat_lng = extract_lat_lng(obkv, &settings_diff.new, DelAdd::Addition, document_id)?;
+        let add_points = extract_points(obkv, &settings_diff.new, DelAdd::Addition, document_id)?;
 
-        if del_lat_lng != add_lat_lng {
+        if del_points != add_points {
             let mut obkv = KvWriterDelAdd::memory();
-            if let Some([lat, lng]) = del_lat_lng {
-                #[allow(clippy::drop_non_drop)]
-                let bytes: [u8; 16] = concat_arrays![lat.to_ne_bytes(), lng.to_ne_bytes()];
+            if let Some(bytes) = encode_points(&del_points) {
                 obkv.insert(DelAdd::Deletion, bytes)?;
             }
-            if let Some([lat, lng]) = add_lat_lng {
-                #[allow(clippy::drop_non_drop)]
-                let bytes: [u8; 16] = concat_arrays![lat.to_ne_bytes(), lng.to_ne_bytes()];
+            if let Some(bytes) = encode_points(&add_points) {
                 obkv.insert(DelAdd::Addition, bytes)?;
             }
             let bytes = obkv.into_inner()?;
@@ -64,13 +63,73 @@ pub fn extract_geo_points<R: io::Read + io::Seek>(
     writer_into_reader(writer)
 }
 
+/// Extracts the points of the `_geo` and `_geojson` fields, and the `_geojson` geometry.
+fn extract_points(
+    document: &obkv::KvReader<FieldId>,
+    settings: &InnerIndexSettings,
+    deladd: DelAdd,
+    document_id: impl Fn() -> Value,
+) -> Result<(Vec<[f64; 2]>, Option<GeoJsonGeometry>)> {
+    let mut points = extract_lat_lng(document, settings, deladd, &document_id)?;
+    let mut geojson = None;
+
+    let value = settings
+        .geojson_field_id
+        .and_then(|fid| document.get(fid))
+        .map(KvReaderDelAdd::from_slice)
+        .and_then(|r| r.get(deladd));
+    if let Some(value) = value {
+        let value: Value = serde_json::from_slice(value).map_err(InternalError::SerdeJson)?;
+        if !value.is_null() {
+            let geometry = GeoJsonGeometry::from_value(&value)
+                .map_err(|reason| GeoError::InvalidGeoJson { document_id: document_id(), reason })
+                .map_err(Box::new)?;
+            points.extend(geometry.points());
+            geojson = Some(geometry);
+        }
+    }
+
+    Ok((points, geojson))
+}
+
+/// Encodes the geojson flag, the envelope of a polygon and the points, returns `None` if there is
+/// no point.
+///
+/// The flag is `0` without `_geojson` geometry, `1` for a geometry which is not a polygon and `2`
+/// for a polygon, in which case it is followed by the lower and upper corners of its envelope.
+fn encode_points((points, geojson): &(Vec<[f64; 2]>, Option<GeoJsonGeometry>)) -> Option<Vec<u8>> {
+    if points.is_empty() {
+        return None;
+    }
+    let envelope = geojson.as_ref().and_then(GeoJsonGeometry::envelope);
+    let mut bytes = Vec::with_capacity(1 + 32 + points.len() * 16);
+    match envelope {
+        Some(corners) => {
+            bytes.push(2);
+            for [lat, lng] in corners {
+                bytes.extend_from_slice(&lat.to_ne_bytes());
+                bytes.extend_from_slice(&lng.to_ne_bytes());
+            }
+        }
+        None => bytes.push(geojson.is_some() as u8),
+    }
+    for [lat, lng] in points {
+        bytes.extend_from_slice(&lat.to_ne_bytes());
+        bytes.extend_from_slice(&lng.to_ne_bytes());
+    }
+    Some(bytes)
+}
+
 /// Extract the finite floats lat and lng from two bytes slices.
+///
+/// A document with several points has its `_geo.lat` and `_geo.lng` fields flattened into two
+/// arrays of the same length, the points are rebuilt by zipping them.
 fn extract_lat_lng(
     document: &obkv::KvReader<FieldId>,
     settings: &InnerIndexSettings,
     deladd: DelAdd,
     document_id: impl Fn() -> Value,
-) -> Result<Option<[f64; 2]>> {
+) -> Result<Vec<[f64; 2]>> {
     match settings.geo_fields_ids {
         Some((lat_fid, lng_fid)) => {
             let lat =
@@ -89,21 +148,36 @@ fn extract_lat_lng(
                         Box::new(GeoError::MissingLongitude { document_id: document_id() }).into()
                     )
                 }
-                (None, None) => return Ok(None),
+                (None, None) => return Ok(Vec::new()),
+            };
+            let lat: Value = serde_json::from_slice(lat).map_err(InternalError::SerdeJson)?;
+            let lng: Value = serde_json::from_slice(lng).map_err(InternalError::SerdeJson)?;
+            let lat_lngs = match (lat, lng) {
+                (Value::Array(lats), Value::Array(lngs)) if lats.len() == lngs.len() => {
+                    lats.into_iter().zip(lngs).collect()
+                }
+                (lat, lng) => vec![(lat, lng)],
             };
-            let lat = extract_finite_float_from_value(
-                serde_json::from_slice(lat).map_err(InternalError::SerdeJson)?,
-            )
-            .map_err(|lat| GeoError::BadLatitude { document_id: document_id(), value: lat })
-            .map_err(Box::new)?;
 
-            let lng = extract_finite_float_from_value(
-                serde_json::from_slice(lng).map_err(InternalError::SerdeJson)?,
-            )
-            .map_err(|lng| GeoError::BadLongitude { document_id: document_id(), value: lng })
-            .map_err(Box::new)?;
-            Ok(Some([lat, lng]))
+            lat_lngs
+                .into_iter()
+                .map(|(lat, lng)| {
+                    let lat = extract_finite_float_from_value(lat)
+                        .map_err(|lat| GeoError::BadLatitude {
+                            document_id: document_id(),
+                            value: lat,
+                        })
+                        .map_err(Box::new)?;
+                    let lng = extract_finite_float_from_value(lng)
+                        .map_err(|lng| GeoError::BadLongitude {
+                            document_id: document_id(),
+                            value: lng,
+                        })
+                        .map_err(Box::new)?;
+                    Ok([lat, lng])
+                })
+                .collect()
         }
-        None => Ok(None),
+        None => Ok(Vec::new()),
     }
 }
@@ -40,6 +40,7 @@ pub struct ExtractedVectorPoints {
     // embedder
     pub embedder_name: String,
     pub embedder: Arc<Embedder>,
+    pub prompt: Arc<Prompt>,
     pub add_to_user_provided: RoaringBitmap,
     pub remove_from_user_provided: RoaringBitmap,
 }
@@ -207,7 +208,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
     if reindex_vectors {
         for (name, action) in settings_diff.embedding_config_updates.iter() {
             if let Some(action) = action.reindex() {
-                let Some((embedder_name, (embedder, prompt, _quantized))) =
+                let Some((embedder_name, (embedder, prompt, _quantization, _distance))) =
                     configs.remove_entry(name)
                 else {
                     tracing::error!(embedder = name, "Requested embedder config not found");
@@ -238,7 +239,8 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                 let action = match action {
                     ReindexAction::FullReindex => ExtractionAction::SettingsFullReindex,
                     ReindexAction::RegeneratePrompts => {
-                        let Some((_, old_prompt, _quantized)) = old_configs.get(name) else {
+                        let Some((_, old_prompt, _quantization, _distance)) = old_configs.get(name)
+                        else {
                             tracing::error!(embedder = name, "Old embedder config not found");
                             continue;
                         };
@@ -264,7 +266,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
     } else {
         // document operation
 
-        for (embedder_name, (embedder, prompt, _quantized)) in configs.into_iter() {
+        for (embedder_name, (embedder, prompt, _quantization, _distance)) in configs.into_iter() {
             // (docid, _index) -> KvWriterDelAdd -> Vector
             let manual_vectors_writer = create_writer(
                 indexer.chunk_compression_type,
@@ -339,7 +341,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
             action,
         } in extractors.iter_mut()
         {
-            let embedder_is_manual = matches!(**embedder, Embedder::UserProvided(_));
+            let embedder_is_manual = embedder.is_user_provided();
 
             let (old, new) = parsed_vectors.remove(embedder_name);
             let delta = match action {
@@ -444,7 +446,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
     for EmbedderVectorExtractor {
         embedder_name,
         embedder,
-        prompt: _,
+        prompt,
         prompts_writer,
         remove_vectors_writer,
         action,
@@ -468,6 +470,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
             prompts: writer_into_reader(prompts_writer)?,
             embedder,
             embedder_name,
+            prompt,
             add_to_user_provided,
             remove_from_user_provided,
         })
@@ -681,6 +684,7 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
     indexer: GrenadParameters,
     embedder: Arc<Embedder>,
     embedder_name: &str,
+    prompt: &Prompt,
     possible_embedding_mistakes: &PossibleEmbeddingMistakes,
     unused_vectors_distribution: &UnusedVectorsDistribution,
     request_threads: &ThreadPoolNoAbort,
@@ -699,24 +703,28 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
     let mut current_chunk = Vec::with_capacity(n_vectors_per_chunk);
     let mut current_chunk_ids = Vec::with_capacity(n_vectors_per_chunk);
     let mut chunks_ids = Vec::with_capacity(n_chunks);
+    // the embeddings of the chunks of the last document, written once all of them are known
+    let mut pending_embeddings = None;
     let mut cursor = prompt_reader.into_cursor()?;
 
     while let Some((key, value)) = cursor.move_on_next()? {
         let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
         // SAFETY: precondition, the grenad value was saved from a string
-        let prompt = unsafe { std::str::from_utf8_unchecked(value) };
-        if current_chunk.len() == current_chunk.capacity() {
-            chunks.push(std::mem::replace(
-                &mut current_chunk,
-                Vec::with_capacity(n_vectors_per_chunk),
-            ));
-            chunks_ids.push(std::mem::replace(
-                &mut current_chunk_ids,
-                Vec::with_capacity(n_vectors_per_chunk),
-            ));
-        };
-        current_chunk.push(prompt.to_owned());
-        current_chunk_ids.push(docid);
+        let rendered = unsafe { std::str::from_utf8_unchecked(value) };
+        for text in prompt.split_into_chunks(rendered) {
+            if current_chunk.len() == current_chunk.capacity() {
+                chunks.push(std::mem::replace(
+                    &mut current_chunk,
+                    Vec::with_capacity(n_vectors_per_chunk),
+                ));
+                chunks_ids.push(std::mem::replace(
+                    &mut current_chunk_ids,
+                    Vec::with_capacity(n_vectors_per_chunk),
+                ));
+            };
+            current_chunk.push(text.to_owned());
+            current_chunk_ids.push(docid);
+        }
 
         if chunks.len() == chunks.capacity() {
             let chunked_embeds = embed_chunks(
@@ -733,7 +741,7 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
                 .flat_map(|docids| docids.iter())
                 .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
             {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings))?;
+                push_embedding(&mut state_writer, &mut pending_embeddings, *docid, embeddings)?;
             }
             chunks_ids.clear();
         }
@@ -754,7 +762,7 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
             .flat_map(|docids| docids.iter())
             .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
         {
-            state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings))?;
+            push_embedding(&mut state_writer, &mut pending_embeddings, *docid, embeddings)?;
         }
     }
 
@@ -770,14 +778,41 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
 
         if let Some(embeds) = embeds.first() {
             for (docid, embeddings) in current_chunk_ids.iter().zip(embeds.iter()) {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings))?;
+                push_embedding(&mut state_writer, &mut pending_embeddings, *docid, embeddings)?;
             }
         }
     }
 
+    if let Some((docid, embeddings)) = pending_embeddings {
+        state_writer.insert(docid.to_be_bytes(), cast_slice(&embeddings))?;
+    }
+
     writer_into_reader(state_writer)
 }
 
+/// Appends the embedding to the ones of its document,
+/// writing the embeddings of the previous document when the document changes.
+fn push_embedding(
+    state_writer: &mut Writer<BufWriter<File>>,
+    pending_embeddings: &mut Option<(DocumentId, Vec<f32>)>,
+    docid: DocumentId,
+    embedding: &[f32],
+) -> Result<()> {
+    match pending_embeddings {
+        Some((pending_docid, embeddings)) if *pending_docid == docid => {
+            embeddings.extend_from_slice(embedding)
+        }
+        _ => {
+            if let Some((previous_docid, embeddings)) =
+                pending_embeddings.replace((docid, embedding.to_vec()))
+            {
+                state_writer.insert(previous_docid.to_be_bytes(), cast_slice(&embeddings))?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn embed_chunks(
     embedder: &Embedder,
     text_chunks: Vec<Vec<String>>,
@@ -260,6 +260,7 @@ fn send_original_documents_data(
                         prompts,
                         embedder_name,
                         embedder,
+                        prompt,
                         add_to_user_provided,
                         remove_from_user_provided,
                     } in extracted_vectors
@@ -269,6 +270,7 @@ fn send_original_documents_data(
                             indexer,
                             embedder.clone(),
                             &embedder_name,
+                            &prompt,
                             &possible_embedding_mistakes,
                             &unused_vectors_distribution,
                             request_threads(),
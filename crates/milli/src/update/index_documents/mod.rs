@@ -486,18 +486,34 @@ where
         let number_of_documents = self.index.number_of_documents(self.wtxn)?;
         let mut rng = rand::rngs::StdRng::seed_from_u64(42);
 
-        // If an embedder wasn't used in the typedchunk but must be binary quantized
-        // we should insert it in `dimension`
+        // If an embedder wasn't used in the typedchunk but must be quantized
+        // or must change its distance, we should insert it in `dimension`
         for (name, action) in settings_diff.embedding_config_updates.iter() {
-            if action.is_being_quantized && !dimension.contains_key(name.as_str()) {
+            let old_config = settings_diff.old.embedding_configs.get(name);
+            let new_config = settings_diff.new.embedding_configs.get(name);
+            let is_changing_distance = old_config
+                .as_ref()
+                .zip(new_config)
+                .is_some_and(|(old_config, new_config)| old_config.3 != new_config.3);
+            if (action.is_being_quantized || is_changing_distance)
+                && !dimension.contains_key(name.as_str())
+            {
                 let index = self.index.embedder_category_id.get(self.wtxn, name)?.ok_or(
                     InternalError::DatabaseMissingEntry {
                         db_name: "embedder_category_id",
                         key: None,
                     },
                 )?;
-                let reader =
-                    ArroyWrapper::new(self.index.vector_arroy, index, action.was_quantized);
+                let distance = old_config.as_ref().map(|conf| conf.3).unwrap_or_default();
+                let sparse = old_config.is_some_and(|conf| conf.0.is_sparse());
+                let reader = ArroyWrapper::new(
+                    self.index.vector_arroy,
+                    self.index.vector_scalar_quantized,
+                    sparse.then_some(self.index.vector_sparse),
+                    index,
+                    action.old_quantization,
+                    distance,
+                );
                 let dim = reader.dimensions(self.wtxn)?;
                 dimension.insert(name.to_string(), dim);
             }
@@ -506,25 +522,38 @@ where
         for (embedder_name, dimension) in dimension {
             let wtxn = &mut *self.wtxn;
             let vector_arroy = self.index.vector_arroy;
+            let vector_scalar_quantized = self.index.vector_scalar_quantized;
+            let vector_sparse = self.index.vector_sparse;
             let cancel = &self.should_abort;
 
             let embedder_index = self.index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
             )?;
-            let embedder_config = settings_diff.embedding_config_updates.get(&embedder_name);
-            let was_quantized =
-                settings_diff.old.embedding_configs.get(&embedder_name).is_some_and(|conf| conf.2);
-            let is_quantizing = embedder_config.is_some_and(|action| action.is_being_quantized);
+            let old_config = settings_diff.old.embedding_configs.get(&embedder_name);
+            let new_config = settings_diff.new.embedding_configs.get(&embedder_name);
+            let old_quantization = old_config.as_ref().map(|conf| conf.2).unwrap_or_default();
+            let old_distance = old_config.as_ref().map(|conf| conf.3).unwrap_or_default();
+            let sparse = old_config.is_some_and(|conf| conf.0.is_sparse());
+            let new_quantization = new_config.as_ref().map(|conf| conf.2).unwrap_or_default();
+            let new_distance = new_config.map(|conf| conf.3).unwrap_or_default();
 
             pool.install(|| {
-                let mut writer = ArroyWrapper::new(vector_arroy, embedder_index, was_quantized);
+                let mut writer = ArroyWrapper::new(
+                    vector_arroy,
+                    vector_scalar_quantized,
+                    sparse.then_some(vector_sparse),
+                    embedder_index,
+                    old_quantization,
+                    old_distance,
+                );
+                writer.change_distance(wtxn, dimension, new_distance)?;
                 writer.build_and_quantize(
                     wtxn,
                     // In the settings we don't have any progress to share
                     &Progress::default(),
                     &mut rng,
                     dimension,
-                    is_quantizing,
+                    new_quantization,
                     self.indexer_config.max_memory,
                     cancel,
                 )?;
@@ -2774,14 +2803,19 @@ mod tests {
                         dimensions: Setting::Set(3),
                         document_template: Setting::NotSet,
                         document_template_max_bytes: Setting::NotSet,
+                        chunking: Setting::NotSet,
                         url: Setting::NotSet,
                         request: Setting::NotSet,
                         response: Setting::NotSet,
                         distribution: Setting::NotSet,
                         headers: Setting::NotSet,
+                        media: Setting::NotSet,
+                        late_interaction: Setting::NotSet,
                         search_embedder: Setting::NotSet,
                         indexing_embedder: Setting::NotSet,
                         binary_quantized: Setting::NotSet,
+                        quantization: Setting::NotSet,
+                        distance: Setting::NotSet,
                     }),
                 );
                 settings.set_embedder_settings(embedders);
@@ -2806,12 +2840,20 @@ mod tests {
             embedding_configs.pop().unwrap();
         insta::assert_snapshot!(embedder_name, @"manual");
         insta::assert_debug_snapshot!(user_provided, @"RoaringBitmap<[0, 1, 2]>");
+        let quantization = embedder.quantization();
+        let distance = embedder.distance();
         let embedder = std::sync::Arc::new(
-            crate::vector::Embedder::new(embedder.embedder_options, 0).unwrap(),
+            crate::vector::Embedder::new(embedder.embedder_options, 0, None).unwrap(),
         );
         let res = index
             .search(&rtxn)
-            .semantic(embedder_name, embedder, false, Some([0.0, 1.0, 2.0].to_vec()))
+            .semantic(
+                embedder_name,
+                embedder,
+                quantization,
+                distance,
+                Some([0.0, 1.0, 2.0].to_vec()),
+            )
             .execute()
             .unwrap();
         assert_eq!(res.documents_ids.len(), 3);
@@ -841,10 +841,16 @@ impl<'a, 'i> Transform<'a, 'i> {
                 if let Some(WriteBackToDocuments { embedder_id, user_provided }) =
                     action.write_back()
                 {
+                    let old_config = settings_diff.old.embedding_configs.get(name);
+                    let distance = old_config.as_ref().map(|conf| conf.3).unwrap_or_default();
+                    let sparse = old_config.is_some_and(|conf| conf.0.is_sparse());
                     let reader = ArroyWrapper::new(
                         self.index.vector_arroy,
+                        self.index.vector_scalar_quantized,
+                        sparse.then_some(self.index.vector_sparse),
                         *embedder_id,
-                        action.was_quantized,
+                        action.old_quantization,
+                        distance,
                     );
                     Some((name.as_str(), (reader, user_provided)))
                 } else {
@@ -896,9 +902,10 @@ impl<'a, 'i> Transform<'a, 'i> {
                             Ok(vectors) => Some(Ok((
                                 name.to_string(),
                                 serde_json::to_value(ExplicitVectors {
-                                    embeddings: Some(
-                                        VectorOrArrayOfVectors::from_array_of_vectors(vectors),
-                                    ),
+                                    embeddings: Some(VectorOrArrayOfVectors::from_stored(
+                                        vectors,
+                                        reader.is_sparse(),
+                                    )),
                                     regenerate: false,
                                 })
                                 .unwrap(),
@@ -17,6 +17,7 @@ use super::helpers::{
 };
 use crate::external_documents_ids::{DocumentOperation, DocumentOperationKind};
 use crate::facet::FacetType;
+use crate::geojson::{geojson_envelope, GeoJsonEnvelope};
 use crate::index::db_name::DOCUMENTS;
 use crate::index::IndexEmbeddingConfig;
 use crate::proximity::MAX_DISTANCE;
@@ -592,6 +593,8 @@ pub(crate) fn write_typed_chunk_into_index(
 
             let mut rtree = index.geo_rtree(wtxn)?.unwrap_or_default();
             let mut geo_faceted_docids = index.geo_faceted_documents_ids(wtxn)?;
+            let mut geojson_docids = index.geojson_documents_ids(wtxn)?;
+            let mut geojson_rtree = index.geojson_rtree(wtxn)?.unwrap_or_default();
 
             let mut iter = merger.into_stream_merger_iter()?;
             while let Some((key, value)) = iter.next()? {
@@ -600,18 +603,36 @@ pub(crate) fn write_typed_chunk_into_index(
 
                 let deladd_obkv = KvReaderDelAdd::from_slice(value);
                 if let Some(value) = deladd_obkv.get(DelAdd::Deletion) {
-                    let geopoint = extract_geo_point(value, docid);
-                    rtree.remove(&geopoint);
+                    let (geojson, envelope, geopoints) = extract_geo_points(value, docid);
+                    for geopoint in geopoints {
+                        rtree.remove(&geopoint);
+                    }
                     geo_faceted_docids.remove(docid);
+                    if geojson {
+                        geojson_docids.remove(docid);
+                    }
+                    if let Some(envelope) = envelope {
+                        geojson_rtree.remove(&envelope);
+                    }
                 }
                 if let Some(value) = deladd_obkv.get(DelAdd::Addition) {
-                    let geopoint = extract_geo_point(value, docid);
-                    rtree.insert(geopoint);
+                    let (geojson, envelope, geopoints) = extract_geo_points(value, docid);
+                    for geopoint in geopoints {
+                        rtree.insert(geopoint);
+                    }
                     geo_faceted_docids.insert(docid);
+                    if geojson {
+                        geojson_docids.insert(docid);
+                    }
+                    if let Some(envelope) = envelope {
+                        geojson_rtree.insert(envelope);
+                    }
                 }
             }
             index.put_geo_rtree(wtxn, &rtree)?;
             index.put_geo_faceted_documents_ids(wtxn, &geo_faceted_docids)?;
+            index.put_geojson_documents_ids(wtxn, &geojson_docids)?;
+            index.put_geojson_rtree(wtxn, &geojson_rtree)?;
         }
         TypedChunk::VectorPoints { .. } => {
             let span = tracing::trace_span!(target: "indexing::write_db", "vector_points");
@@ -664,10 +685,19 @@ pub(crate) fn write_typed_chunk_into_index(
             let embedder_index = index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
             )?;
-            let binary_quantized =
-                settings_diff.old.embedding_configs.get(&embedder_name).is_some_and(|conf| conf.2);
-            // FIXME: allow customizing distance
-            let writer = ArroyWrapper::new(index.vector_arroy, embedder_index, binary_quantized);
+            let old_config = settings_diff.old.embedding_configs.get(&embedder_name);
+            // the embeddings are converted to the new quantization and distance when building the trees
+            let quantization = old_config.as_ref().map(|conf| conf.2).unwrap_or_default();
+            let distance = old_config.as_ref().map(|conf| conf.3).unwrap_or_default();
+            let sparse = old_config.is_some_and(|conf| conf.0.is_sparse());
+            let writer = ArroyWrapper::new(
+                index.vector_arroy,
+                index.vector_scalar_quantized,
+                sparse.then_some(index.vector_sparse),
+                embedder_index,
+                quantization,
+                distance,
+            );
 
             // remove vectors for docids we want them removed
             let merger = remove_vectors_builder.build();
@@ -683,6 +713,12 @@ pub(crate) fn write_typed_chunk_into_index(
             while let Some((key, value)) = iter.next()? {
                 let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
                 let data = pod_collect_to_vec(value);
+                // the sparse embeddings of a document are merged, regardless of their length
+                if writer.is_sparse() {
+                    let embeddings = crate::vector::Embeddings::from_single_embedding(data);
+                    writer.add_items(wtxn, docid, &embeddings)?;
+                    continue;
+                }
                 // it is a code error to have embeddings and not expected_dimension
                 let embeddings = crate::vector::Embeddings::from_inner(data, expected_dimension)
                     // code error if we somehow got the wrong dimension
@@ -736,12 +772,33 @@ pub(crate) fn write_typed_chunk_into_index(
 }
 
 /// Converts the latitude and longitude back to an xyz GeoPoint.
-pub fn extract_geo_point(value: &[u8], docid: DocumentId) -> GeoPoint {
-    let (lat, tail) = helpers::try_split_array_at::<u8, 8>(value).unwrap();
-    let (lng, _) = helpers::try_split_array_at::<u8, 8>(tail).unwrap();
-    let point = [f64::from_ne_bytes(lat), f64::from_ne_bytes(lng)];
-    let xyz_point = lat_lng_to_xyz(&point);
-    GeoPoint::new(xyz_point, (docid, point))
+/// Decodes the geojson flag, the envelope of the polygon and the points encoded by the geo points
+/// extractor.
+pub fn extract_geo_points(
+    value: &[u8],
+    docid: DocumentId,
+) -> (bool, Option<GeoJsonEnvelope>, Vec<GeoPoint>) {
+    let decode_point = |bytes: &[u8]| {
+        let (lat, tail) = helpers::try_split_array_at::<u8, 8>(bytes).unwrap();
+        let (lng, _) = helpers::try_split_array_at::<u8, 8>(tail).unwrap();
+        [f64::from_ne_bytes(lat), f64::from_ne_bytes(lng)]
+    };
+
+    let (geojson, mut points) = value.split_first().unwrap();
+    let envelope = (*geojson == 2).then(|| {
+        let (corners, tail) = points.split_at(32);
+        points = tail;
+        geojson_envelope(docid, [decode_point(&corners[..16]), decode_point(&corners[16..])])
+    });
+    let points = points
+        .chunks_exact(16)
+        .map(|bytes| {
+            let point = decode_point(bytes);
+            let xyz_point = lat_lng_to_xyz(&point);
+            GeoPoint::new(xyz_point, (docid, point))
+        })
+        .collect();
+    (*geojson != 0, envelope, points)
 }
 
 fn merge_word_docids_reader_into_fst<MF>(
@@ -1,7 +1,10 @@
+use std::path::PathBuf;
+
 use grenad::CompressionType;
 
 use super::GrenadParameters;
 use crate::thread_pool_no_abort::ThreadPoolNoAbort;
+use crate::vector::persistent_cache::PersistentEmbeddingCache;
 
 #[derive(Debug)]
 pub struct IndexerConfig {
@@ -14,6 +17,10 @@ pub struct IndexerConfig {
     pub thread_pool: Option<ThreadPoolNoAbort>,
     pub max_positions_per_attributes: Option<u32>,
     pub skip_index_budget: bool,
+    /// The cache of the embeddings, shared by the embedders used when reindexing after a settings update.
+    pub embedding_cache: Option<PersistentEmbeddingCache>,
+    /// The directory from which the multimodal embedders read the media files referenced by the documents.
+    pub embedding_media_path: Option<PathBuf>,
 }
 
 impl IndexerConfig {
@@ -39,6 +46,8 @@ impl Default for IndexerConfig {
             thread_pool: None,
             max_positions_per_attributes: None,
             skip_index_budget: false,
+            embedding_cache: None,
+            embedding_media_path: None,
         }
     }
 }
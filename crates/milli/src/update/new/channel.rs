@@ -24,7 +24,9 @@ use super::thread_local::{FullySend, ThreadLocal};
 use super::StdResult;
 use crate::heed_codec::facet::{FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec};
 use crate::index::db_name;
-use crate::index::main_key::{GEO_FACETED_DOCUMENTS_IDS_KEY, GEO_RTREE_KEY};
+use crate::index::main_key::{
+    GEOJSON_DOCUMENTS_IDS_KEY, GEOJSON_RTREE_KEY, GEO_FACETED_DOCUMENTS_IDS_KEY, GEO_RTREE_KEY,
+};
 use crate::update::new::KvReaderFieldId;
 use crate::vector::Embedding;
 use crate::{CboRoaringBitmapCodec, DocumentId, Error, Index, InternalError};
@@ -172,6 +174,11 @@ impl LargeVectors {
     pub fn read_embeddings(&self, dimensions: usize) -> impl Iterator<Item = &[f32]> {
         self.embeddings.chunks_exact(dimensions).map(bytemuck::cast_slice)
     }
+
+    /// Reads the embeddings as a single flat vector, for the embeddings without fixed dimensions.
+    pub fn read_all_embeddings(&self) -> &[f32] {
+        bytemuck::cast_slice(&self.embeddings)
+    }
 }
 
 impl<'a> WriterBbqueueReceiver<'a> {
@@ -269,9 +276,9 @@ impl EntryHeader {
     }
 
     /// The `dimensions` corresponds to the number of `f32` in the embedding.
-    fn total_set_vectors_size(count: usize, dimensions: usize) -> usize {
-        let embedding_size = dimensions * mem::size_of::<f32>();
-        Self::variant_size() + mem::size_of::<ArroySetVectors>() + embedding_size * count
+    /// The size of the entry of embeddings made of `values` floats in total.
+    fn total_set_vectors_size(values: usize) -> usize {
+        Self::variant_size() + mem::size_of::<ArroySetVectors>() + values * mem::size_of::<f32>()
     }
 
     fn header_size(&self) -> usize {
@@ -518,13 +525,13 @@ impl<'b> ExtractorBbqueueSender<'b> {
         let refcell = self.producers.get().unwrap();
         let mut producer = refcell.0.borrow_mut_or_yield();
 
-        // If there are no vectors we specify the dimensions
-        // to zero to allocate no extra space at all
-        let dimensions = embeddings.first().map_or(0, |emb| emb.len());
+        // The embeddings of sparse embedders have different lengths,
+        // so we count all the values rather than the dimensions
+        let values = embeddings.iter().map(Vec::len).sum();
 
         let arroy_set_vector = ArroySetVectors { docid, embedder_id, _padding: [0; 3] };
         let payload_header = EntryHeader::ArroySetVectors(arroy_set_vector);
-        let total_length = EntryHeader::total_set_vectors_size(embeddings.len(), dimensions);
+        let total_length = EntryHeader::total_set_vectors_size(values);
         if total_length > max_grant {
             let mut value_file = tempfile::tempfile().map(BufWriter::new)?;
             for embedding in embeddings {
@@ -550,15 +557,14 @@ impl<'b> ExtractorBbqueueSender<'b> {
             &self.blocking_sent_messages_attempts,
             |grant| {
                 let header_size = payload_header.header_size();
-                let (header_bytes, remaining) = grant.split_at_mut(header_size);
+                let (header_bytes, mut remaining) = grant.split_at_mut(header_size);
                 payload_header.serialize_into(header_bytes);
 
-                if dimensions != 0 {
-                    let output_iter =
-                        remaining.chunks_exact_mut(dimensions * mem::size_of::<f32>());
-                    for (embedding, output) in embeddings.iter().zip(output_iter) {
-                        output.copy_from_slice(bytemuck::cast_slice(embedding));
-                    }
+                for embedding in embeddings {
+                    let (output, tail) =
+                        mem::take(&mut remaining).split_at_mut(mem::size_of_val(&embedding[..]));
+                    output.copy_from_slice(bytemuck::cast_slice(embedding));
+                    remaining = tail;
                 }
 
                 Ok(())
@@ -963,10 +969,29 @@ impl GeoSender<'_, '_> {
             .map_err(|_| SendError(()))
     }
 
+    pub fn set_geojson_rtree(&self, value: Mmap) -> StdResult<(), SendError<()>> {
+        self.0
+            .sender
+            .send(ReceiverAction::LargeEntry(LargeEntry {
+                database: Database::Main,
+                key: GEOJSON_RTREE_KEY.to_string().into_bytes().into_boxed_slice(),
+                value,
+            }))
+            .map_err(|_| SendError(()))
+    }
+
     pub fn set_geo_faceted(&self, bitmap: &RoaringBitmap) -> crate::Result<()> {
+        self.set_bitmap(GEO_FACETED_DOCUMENTS_IDS_KEY, bitmap)
+    }
+
+    pub fn set_geojson_documents_ids(&self, bitmap: &RoaringBitmap) -> crate::Result<()> {
+        self.set_bitmap(GEOJSON_DOCUMENTS_IDS_KEY, bitmap)
+    }
+
+    fn set_bitmap(&self, key: &str, bitmap: &RoaringBitmap) -> crate::Result<()> {
         let database = Database::Main;
         let value_length = bitmap.serialized_size();
-        let key = GEO_FACETED_DOCUMENTS_IDS_KEY.as_bytes();
+        let key = key.as_bytes();
         let key_length = key.len().try_into().ok().and_then(NonZeroU16::new).ok_or_else(|| {
             InternalError::StorePut {
                 database_name: database.database_name(),
@@ -10,6 +10,7 @@ use serde_json::Value;
 use super::super::cache::BalancedCaches;
 use super::facet_document::extract_document_facets;
 use super::FacetKind;
+use crate::facet::parse_date_facet;
 use crate::fields_ids_map::metadata::Metadata;
 use crate::filterable_attributes_rules::match_faceted_field;
 use crate::heed_codec::facet::OrderedF64Codec;
@@ -245,6 +246,20 @@ impl FacetedDocidsExtractor {
         buffer.extend_from_slice(&fid.to_be_bytes());
         cache_fn(cached_sorter, &buffer, docid)?;
 
+        // Dates are stored as numbers so that they can be filtered and sorted on.
+        // A string that is not a valid RFC 3339 date is kept as a string facet.
+        let date;
+        let value = match value {
+            Value::String(s) if features.is_date() => match parse_date_facet(s) {
+                Some(timestamp) => {
+                    date = Value::from(timestamp);
+                    &date
+                }
+                None => value,
+            },
+            value => value,
+        };
+
         match value {
             // Number
             // key: fid - level - orderedf64 - originalf64
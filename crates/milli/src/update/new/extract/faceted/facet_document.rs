@@ -104,14 +104,17 @@ pub fn extract_document_facets<'doc>(
 
     if is_geo_enabled {
         if let Some(geo_value) = document.geo_field()? {
-            if let Some([lat, lng]) = extract_geo_coordinates(external_document_id, geo_value)? {
+            let points = extract_geo_coordinates(external_document_id, geo_value)?;
+            if !points.is_empty() {
                 let ((lat_fid, lat_meta), (lng_fid, lng_meta)) = field_id_map
                     .id_with_metadata_or_insert("_geo.lat")
                     .zip(field_id_map.id_with_metadata_or_insert("_geo.lng"))
                     .ok_or(UserError::AttributeLimitReached)?;
 
-                facet_fn(lat_fid, lat_meta, perm_json_p::Depth::OnBaseKey, &lat.into())?;
-                facet_fn(lng_fid, lng_meta, perm_json_p::Depth::OnBaseKey, &lng.into())?;
+                for [lat, lng] in points {
+                    facet_fn(lat_fid, lat_meta, perm_json_p::Depth::OnBaseKey, &lat.into())?;
+                    facet_fn(lng_fid, lng_meta, perm_json_p::Depth::OnBaseKey, &lng.into())?;
+                }
             }
         }
     }
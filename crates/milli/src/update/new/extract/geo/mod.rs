@@ -6,10 +6,13 @@ use std::{iter, mem, result};
 use bumpalo::Bump;
 use bytemuck::{bytes_of, pod_read_unaligned, Pod, Zeroable};
 use heed::RoTxn;
+use roaring::RoaringBitmap;
 use serde_json::value::RawValue;
 use serde_json::Value;
 
+use crate::constants::RESERVED_GEOJSON_FIELD_NAME;
 use crate::error::GeoError;
+use crate::geojson::{geojson_envelope, GeoJsonEnvelope, GeoJsonGeometry};
 use crate::update::new::document::Document;
 use crate::update::new::indexer::document_changes::{DocumentChangeContext, Extractor};
 use crate::update::new::ref_cell_ext::RefCellExt as _;
@@ -64,15 +67,36 @@ pub struct GeoExtractorData<'extractor> {
     /// Contains a packed list of `ExtractedGeoPoint` of the inserted geo points
     /// data structures if we have spilled to disk.
     spilled_inserted: Option<BufWriter<File>>,
+    /// The documents ids whose removed geo points come from a `_geojson` field.
+    geojson_removed: RoaringBitmap,
+    /// The documents ids whose inserted geo points come from a `_geojson` field.
+    geojson_inserted: RoaringBitmap,
+    /// The envelopes of the removed `_geojson` polygons.
+    geojson_envelopes_removed: Vec<GeoJsonEnvelope>,
+    /// The envelopes of the inserted `_geojson` polygons.
+    geojson_envelopes_inserted: Vec<GeoJsonEnvelope>,
 }
 
 impl<'extractor> GeoExtractorData<'extractor> {
     pub fn freeze(self) -> Result<FrozenGeoExtractorData<'extractor>> {
-        let GeoExtractorData { removed, inserted, spilled_removed, spilled_inserted } = self;
+        let GeoExtractorData {
+            removed,
+            inserted,
+            spilled_removed,
+            spilled_inserted,
+            geojson_removed,
+            geojson_inserted,
+            geojson_envelopes_removed,
+            geojson_envelopes_inserted,
+        } = self;
 
         Ok(FrozenGeoExtractorData {
             removed: removed.into_bump_slice(),
             inserted: inserted.into_bump_slice(),
+            geojson_removed,
+            geojson_inserted,
+            geojson_envelopes_removed,
+            geojson_envelopes_inserted,
             spilled_removed: spilled_removed
                 .map(|bw| bw.into_inner().map(BufReader::new).map_err(|iie| iie.into_error()))
                 .transpose()?,
@@ -81,6 +105,40 @@ impl<'extractor> GeoExtractorData<'extractor> {
                 .transpose()?,
         })
     }
+
+    fn remove(&mut self, docid: DocumentId, points: DocumentGeoPoints) -> io::Result<()> {
+        for lat_lng in points.points {
+            let geopoint = ExtractedGeoPoint { docid, lat_lng };
+            match &mut self.spilled_removed {
+                Some(file) => file.write_all(bytes_of(&geopoint))?,
+                None => self.removed.push(geopoint),
+            }
+        }
+        if points.geojson {
+            self.geojson_removed.insert(docid);
+        }
+        if let Some(envelope) = points.envelope {
+            self.geojson_envelopes_removed.push(geojson_envelope(docid, envelope));
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, docid: DocumentId, points: DocumentGeoPoints) -> io::Result<()> {
+        for lat_lng in points.points {
+            let geopoint = ExtractedGeoPoint { docid, lat_lng };
+            match &mut self.spilled_inserted {
+                Some(file) => file.write_all(bytes_of(&geopoint))?,
+                None => self.inserted.push(geopoint),
+            }
+        }
+        if points.geojson {
+            self.geojson_inserted.insert(docid);
+        }
+        if let Some(envelope) = points.envelope {
+            self.geojson_envelopes_inserted.push(geojson_envelope(docid, envelope));
+        }
+        Ok(())
+    }
 }
 
 unsafe impl MostlySend for GeoExtractorData<'_> {}
@@ -90,6 +148,10 @@ pub struct FrozenGeoExtractorData<'extractor> {
     pub inserted: &'extractor [ExtractedGeoPoint],
     pub spilled_removed: Option<BufReader<File>>,
     pub spilled_inserted: Option<BufReader<File>>,
+    pub geojson_removed: RoaringBitmap,
+    pub geojson_inserted: RoaringBitmap,
+    pub geojson_envelopes_removed: Vec<GeoJsonEnvelope>,
+    pub geojson_envelopes_inserted: Vec<GeoJsonEnvelope>,
 }
 
 impl FrozenGeoExtractorData<'_> {
@@ -144,6 +206,10 @@ impl<'extractor> Extractor<'extractor> for GeoExtractor {
             inserted: bumpalo::collections::Vec::new_in(extractor_alloc),
             spilled_inserted: None,
             spilled_removed: None,
+            geojson_removed: RoaringBitmap::new(),
+            geojson_inserted: RoaringBitmap::new(),
+            geojson_envelopes_removed: Vec::new(),
+            geojson_envelopes_inserted: Vec::new(),
         }))
     }
 
@@ -172,73 +238,33 @@ impl<'extractor> Extractor<'extractor> for GeoExtractor {
                     let docid = deletion.docid();
                     let external_id = deletion.external_document_id();
                     let current = deletion.current(rtxn, index, db_fields_ids_map)?;
-                    let current_geo = current
-                        .geo_field()?
-                        .map(|geo| extract_geo_coordinates(external_id, geo))
-                        .transpose()?;
-
-                    if let Some(lat_lng) = current_geo.flatten() {
-                        let geopoint = ExtractedGeoPoint { docid, lat_lng };
-                        match &mut data_ref.spilled_removed {
-                            Some(file) => file.write_all(bytes_of(&geopoint))?,
-                            None => data_ref.removed.push(geopoint),
-                        }
-                    }
+                    let current_points = extract_document_geo_points(external_id, &current)?;
+                    data_ref.remove(docid, current_points)?;
                 }
                 DocumentChange::Update(update) => {
                     let current = update.current(rtxn, index, db_fields_ids_map)?;
                     let external_id = update.external_document_id();
                     let docid = update.docid();
 
-                    let current_geo = current
-                        .geo_field()?
-                        .map(|geo| extract_geo_coordinates(external_id, geo))
-                        .transpose()?;
-
-                    let updated_geo = update
-                        .merged(rtxn, index, db_fields_ids_map)?
-                        .geo_field()?
-                        .map(|geo| extract_geo_coordinates(external_id, geo))
-                        .transpose()?;
+                    let current_points = extract_document_geo_points(external_id, &current)?;
+                    let updated = update.merged(rtxn, index, db_fields_ids_map)?;
+                    let updated_points = extract_document_geo_points(external_id, &updated)?;
 
-                    if current_geo != updated_geo {
+                    if current_points != updated_points {
                         // If the current and new geo points are different it means that
-                        // we need to replace the current by the new point and therefore
-                        // delete the current point from the RTree.
-                        if let Some(lat_lng) = current_geo.flatten() {
-                            let geopoint = ExtractedGeoPoint { docid, lat_lng };
-                            match &mut data_ref.spilled_removed {
-                                Some(file) => file.write_all(bytes_of(&geopoint))?,
-                                None => data_ref.removed.push(geopoint),
-                            }
-                        }
-
-                        if let Some(lat_lng) = updated_geo.flatten() {
-                            let geopoint = ExtractedGeoPoint { docid, lat_lng };
-                            match &mut data_ref.spilled_inserted {
-                                Some(file) => file.write_all(bytes_of(&geopoint))?,
-                                None => data_ref.inserted.push(geopoint),
-                            }
-                        }
+                        // we need to replace the current by the new points and therefore
+                        // delete the current points from the RTree.
+                        data_ref.remove(docid, current_points)?;
+                        data_ref.insert(docid, updated_points)?;
                     }
                 }
                 DocumentChange::Insertion(insertion) => {
                     let external_id = insertion.external_document_id();
                     let docid = insertion.docid();
 
-                    let inserted_geo = insertion
-                        .inserted()
-                        .geo_field()?
-                        .map(|geo| extract_geo_coordinates(external_id, geo))
-                        .transpose()?;
-
-                    if let Some(lat_lng) = inserted_geo.flatten() {
-                        let geopoint = ExtractedGeoPoint { docid, lat_lng };
-                        match &mut data_ref.spilled_inserted {
-                            Some(file) => file.write_all(bytes_of(&geopoint))?,
-                            None => data_ref.inserted.push(geopoint),
-                        }
-                    }
+                    let inserted_points =
+                        extract_document_geo_points(external_id, &insertion.inserted())?;
+                    data_ref.insert(docid, inserted_points)?;
                 }
             }
         }
@@ -247,15 +273,75 @@ impl<'extractor> Extractor<'extractor> for GeoExtractor {
     }
 }
 
-/// Extracts and validates the latitude and latitude from a document geo field.
-///
-/// It can be of the form `{ "lat": 0.0, "lng": "1.0" }`.
-pub fn extract_geo_coordinates(
+/// The points of a document that must be indexed in the RTree.
+#[derive(Debug, Default, PartialEq)]
+struct DocumentGeoPoints {
+    points: Vec<[f64; 2]>,
+    /// Whether the document has a `_geojson` geometry.
+    geojson: bool,
+    /// The envelope of the `_geojson` geometry when it is a polygon.
+    envelope: Option<[[f64; 2]; 2]>,
+}
+
+/// Extracts the points of the `_geo` and `_geojson` fields of a document.
+fn extract_document_geo_points<'doc>(
+    external_id: &str,
+    document: &impl Document<'doc>,
+) -> Result<DocumentGeoPoints> {
+    let mut points = DocumentGeoPoints::default();
+
+    if let Some(geo) = document.geo_field()? {
+        points.points.extend(extract_geo_coordinates(external_id, geo)?);
+    }
+
+    if let Some(geojson) = document.top_level_field(RESERVED_GEOJSON_FIELD_NAME)? {
+        if let Some(geometry) = extract_geojson_geometry(external_id, geojson)? {
+            points.points.extend(geometry.points());
+            points.geojson = true;
+            points.envelope = geometry.envelope();
+        }
+    }
+
+    Ok(points)
+}
+
+/// Extracts and validates the GeoJSON geometry of a document `_geojson` field.
+pub fn extract_geojson_geometry(
     external_id: &str,
     raw_value: &RawValue,
-) -> Result<Option<[f64; 2]>> {
-    let mut geo = match serde_json::from_str(raw_value.get()).map_err(InternalError::SerdeJson)? {
-        Value::Null => return Ok(None),
+) -> Result<Option<GeoJsonGeometry>> {
+    let value = serde_json::from_str(raw_value.get()).map_err(InternalError::SerdeJson)?;
+    if value == Value::Null {
+        return Ok(None);
+    }
+
+    match GeoJsonGeometry::from_value(&value) {
+        Ok(geometry) => Ok(Some(geometry)),
+        Err(reason) => Err(Box::new(GeoError::InvalidGeoJson {
+            document_id: Value::from(external_id),
+            reason,
+        })
+        .into()),
+    }
+}
+
+/// Extracts and validates the latitudes and longitudes from a document geo field.
+///
+/// It can be of the form `{ "lat": 0.0, "lng": "1.0" }` or an array of such objects when the
+/// document has several points.
+pub fn extract_geo_coordinates(external_id: &str, raw_value: &RawValue) -> Result<Vec<[f64; 2]>> {
+    match serde_json::from_str(raw_value.get()).map_err(InternalError::SerdeJson)? {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(points) => {
+            points.into_iter().map(|point| extract_geo_point(external_id, point)).collect()
+        }
+        value => extract_geo_point(external_id, value).map(|point| vec![point]),
+    }
+}
+
+/// Extracts and validates the latitude and longitude of a single point.
+fn extract_geo_point(external_id: &str, value: Value) -> Result<[f64; 2]> {
+    let mut geo = match value {
         Value::Object(map) => map,
         value => {
             return Err(Box::new(GeoError::NotAnObject {
@@ -299,7 +385,7 @@ pub fn extract_geo_coordinates(
     };
 
     match (extract_finite_float_from_value(lat), extract_finite_float_from_value(lng)) {
-        (Ok(lat), Ok(lng)) => Ok(Some([lat, lng])),
+        (Ok(lat), Ok(lng)) => Ok([lat, lng]),
         (Ok(_), Err(value)) => {
             Err(Box::new(GeoError::BadLongitude { document_id: Value::from(external_id), value })
                 .into())
@@ -3,6 +3,7 @@ use std::cell::RefCell;
 use bumpalo::collections::Vec as BVec;
 use bumpalo::Bump;
 use hashbrown::{DefaultHashBuilder, HashMap};
+use itertools::Itertools;
 
 use super::cache::DelAddRoaringBitmap;
 use crate::error::FaultSource;
@@ -60,7 +61,7 @@ impl<'extractor> Extractor<'extractor> for EmbeddingExtractor<'_, '_> {
             UnusedVectorsDistributionBump::new_in(&context.doc_alloc);
 
         let mut all_chunks = BVec::with_capacity_in(embedders.len(), &context.doc_alloc);
-        for (embedder_name, (embedder, prompt, _is_quantized)) in embedders {
+        for (embedder_name, (embedder, prompt, _quantization, _distance)) in embedders {
             let embedder_id =
                 context.index.embedder_category_id.get(&context.rtxn, embedder_name)?.ok_or_else(
                     || InternalError::DatabaseMissingEntry {
@@ -319,29 +320,34 @@ impl<'a, 'b, 'extractor> Chunks<'a, 'b, 'extractor> {
         rendered: &'a str,
         unused_vectors_distribution: &UnusedVectorsDistributionBump,
     ) -> Result<()> {
-        let is_manual = matches!(&self.embedder, &Embedder::UserProvided(_));
+        let is_manual = self.embedder.is_user_provided();
         if is_manual {
             self.has_manual_generation.get_or_insert(external_docid);
         }
 
-        if self.texts.len() < self.texts.capacity() {
-            self.texts.push(rendered);
-            self.ids.push(docid);
-            return Ok(());
+        let chunks = self.prompt.split_into_chunks(rendered);
+
+        // all the chunks of a document must be embedded together, as their embeddings replace the previous ones
+        if !self.texts.is_empty() && self.texts.len() + chunks.len() > self.texts.capacity() {
+            Self::embed_chunks(
+                &mut self.texts,
+                &mut self.ids,
+                self.embedder,
+                self.embedder_id,
+                self.embedder_name,
+                self.possible_embedding_mistakes,
+                unused_vectors_distribution,
+                self.threads,
+                self.sender,
+                self.has_manual_generation.take(),
+            )?;
         }
 
-        Self::embed_chunks(
-            &mut self.texts,
-            &mut self.ids,
-            self.embedder,
-            self.embedder_id,
-            self.embedder_name,
-            self.possible_embedding_mistakes,
-            unused_vectors_distribution,
-            self.threads,
-            self.sender,
-            self.has_manual_generation.take(),
-        )
+        for chunk in chunks {
+            self.texts.push(chunk);
+            self.ids.push(docid);
+        }
+        Ok(())
     }
 
     pub fn drain(
@@ -418,8 +424,11 @@ impl<'a, 'b, 'extractor> Chunks<'a, 'b, 'extractor> {
 
         let res = match embedder.embed_index_ref(texts.as_slice(), threads) {
             Ok(embeddings) => {
-                for (docid, embedding) in ids.into_iter().zip(embeddings) {
-                    sender.set_vector(*docid, embedder_id, embedding).unwrap();
+                // the chunks of a document are consecutive
+                let embeddings_by_docid = ids.iter().zip(embeddings).chunk_by(|(docid, _)| **docid);
+                for (docid, embeddings) in &embeddings_by_docid {
+                    let embeddings = embeddings.map(|(_, embedding)| embedding).collect();
+                    sender.set_vectors(docid, embedder_id, embeddings).unwrap();
                 }
                 Ok(())
             }
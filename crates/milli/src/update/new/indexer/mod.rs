@@ -165,10 +165,12 @@ where
         let global_fields_ids_map = GlobalFieldsIdsMap::new(&new_fields_ids_map);
 
         let vector_arroy = index.vector_arroy;
+        let vector_scalar_quantized = index.vector_scalar_quantized;
+        let vector_sparse = index.vector_sparse;
         let arroy_writers: Result<HashMap<_, _>> = embedders
             .inner_as_ref()
             .iter()
-            .map(|(embedder_name, (embedder, _, was_quantized))| {
+            .map(|(embedder_name, (embedder, _, quantization, distance))| {
                 let embedder_index = index.embedder_category_id.get(wtxn, embedder_name)?.ok_or(
                     InternalError::DatabaseMissingEntry {
                         db_name: "embedder_category_id",
@@ -177,7 +179,14 @@ where
                 )?;
 
                 let dimensions = embedder.dimensions();
-                let writer = ArroyWrapper::new(vector_arroy, embedder_index, *was_quantized);
+                let writer = ArroyWrapper::new(
+                    vector_arroy,
+                    vector_scalar_quantized,
+                    embedder.is_sparse().then_some(vector_sparse),
+                    embedder_index,
+                    *quantization,
+                    *distance,
+                );
 
                 Ok((
                     embedder_index,
@@ -54,10 +54,15 @@ pub fn write_to_db(
                 let LargeVectors { docid, embedder_id, .. } = large_vectors;
                 let (_, _, writer, dimensions) =
                     arroy_writers.get(&embedder_id).expect("requested a missing embedder");
-                let mut embeddings = Embeddings::new(*dimensions);
-                for embedding in large_vectors.read_embeddings(*dimensions) {
-                    embeddings.push(embedding.to_vec()).unwrap();
-                }
+                let embeddings = if writer.is_sparse() {
+                    Embeddings::from_single_embedding(large_vectors.read_all_embeddings().to_vec())
+                } else {
+                    let mut embeddings = Embeddings::new(*dimensions);
+                    for embedding in large_vectors.read_embeddings(*dimensions) {
+                        embeddings.push(embedding.to_vec()).unwrap();
+                    }
+                    embeddings
+                };
                 writer.del_items(wtxn, *dimensions, docid)?;
                 writer.add_items(wtxn, docid, &embeddings)?;
             }
@@ -118,12 +123,13 @@ where
     let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
     for (_index, (_embedder_name, _embedder, writer, dimensions)) in arroy_writers {
         let dimensions = *dimensions;
+        let quantization = writer.quantization();
         writer.build_and_quantize(
             wtxn,
             progress,
             &mut rng,
             dimensions,
-            false,
+            quantization,
             arroy_memory,
             must_stop_processing,
         )?;
@@ -216,14 +222,20 @@ pub fn write_from_bbqueue(
                 let frame = frame_with_header.frame();
                 let (_, _, writer, dimensions) =
                     arroy_writers.get(&embedder_id).expect("requested a missing embedder");
-                let mut embeddings = Embeddings::new(*dimensions);
                 let all_embeddings = asvs.read_all_embeddings_into_vec(frame, aligned_embedding);
-                if embeddings.append(all_embeddings.to_vec()).is_err() {
-                    return Err(Error::UserError(UserError::InvalidVectorDimensions {
-                        expected: *dimensions,
-                        found: all_embeddings.len(),
-                    }));
-                }
+                let embeddings = if writer.is_sparse() {
+                    // the sparse embeddings of a document are merged, regardless of their length
+                    Embeddings::from_single_embedding(all_embeddings.to_vec())
+                } else {
+                    let mut embeddings = Embeddings::new(*dimensions);
+                    if embeddings.append(all_embeddings.to_vec()).is_err() {
+                        return Err(Error::UserError(UserError::InvalidVectorDimensions {
+                            expected: *dimensions,
+                            found: all_embeddings.len(),
+                        }));
+                    }
+                    embeddings
+                };
                 writer.del_items(wtxn, *dimensions, docid)?;
                 writer.add_items(wtxn, docid, &embeddings)?;
             }
@@ -28,6 +28,8 @@ where
 {
     let mut rtree = index.geo_rtree(rtxn)?.unwrap_or_default();
     let mut faceted = index.geo_faceted_documents_ids(rtxn)?;
+    let mut geojson = index.geojson_documents_ids(rtxn)?;
+    let mut geojson_rtree = index.geojson_rtree(rtxn)?.unwrap_or_default();
 
     for data in datastore {
         if must_stop_processing() {
@@ -39,15 +41,23 @@ where
             let extracted_geo_point = result?;
             let removed = rtree.remove(&GeoPoint::from(extracted_geo_point));
             debug_assert!(removed.is_some());
-            let removed = faceted.remove(extracted_geo_point.docid);
-            debug_assert!(removed);
+            // a document can have multiple points, the first one removes the docid
+            faceted.remove(extracted_geo_point.docid);
+        }
+        geojson -= &frozen.geojson_removed;
+        for envelope in &frozen.geojson_envelopes_removed {
+            let removed = geojson_rtree.remove(envelope);
+            debug_assert!(removed.is_some());
         }
 
         for result in frozen.iter_and_clear_inserted()? {
             let extracted_geo_point = result?;
             rtree.insert(GeoPoint::from(extracted_geo_point));
-            let inserted = faceted.insert(extracted_geo_point.docid);
-            debug_assert!(inserted);
+            faceted.insert(extracted_geo_point.docid);
+        }
+        geojson |= &frozen.geojson_inserted;
+        for envelope in frozen.geojson_envelopes_inserted.drain(..) {
+            geojson_rtree.insert(envelope);
         }
     }
 
@@ -57,7 +67,15 @@ where
 
     let rtree_mmap = unsafe { Mmap::map(&file)? };
     geo_sender.set_rtree(rtree_mmap).unwrap();
+
+    let mut file = tempfile::tempfile()?;
+    bincode::serialize_into(&mut file, &geojson_rtree).map_err(InternalError::BincodeError)?;
+    file.sync_all()?;
+
+    let geojson_rtree_mmap = unsafe { Mmap::map(&file)? };
+    geo_sender.set_geojson_rtree(geojson_rtree_mmap).unwrap();
     geo_sender.set_geo_faceted(&faceted)?;
+    geo_sender.set_geojson_documents_ids(&geojson)?;
 
     Ok(())
 }
@@ -119,8 +119,14 @@ impl<'t> VectorDocumentFromDb<'t> {
         embedder_id: u8,
         config: &IndexEmbeddingConfig,
     ) -> Result<VectorEntry<'t>> {
-        let reader =
-            ArroyWrapper::new(self.index.vector_arroy, embedder_id, config.config.quantized());
+        let reader = ArroyWrapper::new(
+            self.index.vector_arroy,
+            self.index.vector_scalar_quantized,
+            config.config.is_sparse().then_some(self.index.vector_sparse),
+            embedder_id,
+            config.config.quantization(),
+            config.config.distance(),
+        );
         let vectors = reader.item_vectors(self.rtxn, self.docid)?;
 
         Ok(VectorEntry {
@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryInto;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::result::Result as StdResult;
 use std::sync::Arc;
 
@@ -15,7 +16,7 @@ use super::del_add::{DelAdd, DelAddOperation};
 use super::index_documents::{IndexDocumentsConfig, Transform};
 use super::IndexerConfig;
 use crate::attribute_patterns::PatternMatch;
-use crate::constants::RESERVED_GEO_FIELD_NAME;
+use crate::constants::{RESERVED_GEOJSON_FIELD_NAME, RESERVED_GEO_FIELD_NAME};
 use crate::criterion::Criterion;
 use crate::error::UserError;
 use crate::fields_ids_map::metadata::{FieldIdMapWithMetadata, MetadataBuilder};
@@ -25,16 +26,24 @@ use crate::index::{
     DEFAULT_MIN_WORD_LEN_TWO_TYPOS,
 };
 use crate::order_by_map::OrderByMap;
-use crate::prompt::default_max_bytes;
+use crate::prompt::chunking::Chunking;
+use crate::prompt::{default_max_bytes, Prompt};
 use crate::proximity::ProximityPrecision;
+use crate::synonyms::{NormalizedSynonymSet, SynonymRule};
 use crate::update::index_documents::IndexDocumentsMethod;
 use crate::update::{IndexDocuments, UpdateIndexingStep};
+use crate::vector::persistent_cache::PersistentEmbeddingCache;
 use crate::vector::settings::{
     EmbedderAction, EmbedderSource, EmbeddingSettings, NestingContext, ReindexAction,
     SubEmbeddingSettings, WriteBackToDocuments,
 };
-use crate::vector::{Embedder, EmbeddingConfig, EmbeddingConfigs};
-use crate::{FieldId, FilterableAttributesRule, Index, LocalizedAttributesRule, Result};
+use crate::vector::{
+    Embedder, EmbeddingConfig, EmbeddingConfigs, VectorDistance, VectorQuantization,
+};
+use crate::{
+    FieldId, FilterableAttributesRule, Index, LocalizedAttributesRule, PinningRule, Reranker,
+    RerankerSettings, Result, SynonymSet,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum Setting<T> {
@@ -167,6 +176,7 @@ pub struct Settings<'a, 't, 'i> {
     dictionary: Setting<BTreeSet<String>>,
     distinct_field: Setting<String>,
     synonyms: Setting<BTreeMap<String, Vec<String>>>,
+    synonym_sets: Setting<Vec<SynonymSet>>,
     primary_key: Setting<String>,
     authorize_typos: Setting<bool>,
     min_word_len_two_typos: Setting<u8>,
@@ -183,6 +193,8 @@ pub struct Settings<'a, 't, 'i> {
     localized_attributes_rules: Setting<Vec<LocalizedAttributesRule>>,
     prefix_search: Setting<PrefixSearch>,
     facet_search: Setting<bool>,
+    pinning: Setting<Vec<PinningRule>>,
+    reranker: Setting<RerankerSettings>,
 }
 
 impl<'a, 't, 'i> Settings<'a, 't, 'i> {
@@ -205,6 +217,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             dictionary: Setting::NotSet,
             distinct_field: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_sets: Setting::NotSet,
             primary_key: Setting::NotSet,
             authorize_typos: Setting::NotSet,
             exact_words: Setting::NotSet,
@@ -220,6 +233,8 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             localized_attributes_rules: Setting::NotSet,
             prefix_search: Setting::NotSet,
             facet_search: Setting::NotSet,
+            pinning: Setting::NotSet,
+            reranker: Setting::NotSet,
             indexer_config,
         }
     }
@@ -322,6 +337,15 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.synonyms = if synonyms.is_empty() { Setting::Reset } else { Setting::Set(synonyms) }
     }
 
+    pub fn reset_synonym_sets(&mut self) {
+        self.synonym_sets = Setting::Reset;
+    }
+
+    pub fn set_synonym_sets(&mut self, synonym_sets: Vec<SynonymSet>) {
+        self.synonym_sets =
+            if synonym_sets.is_empty() { Setting::Reset } else { Setting::Set(synonym_sets) }
+    }
+
     pub fn reset_primary_key(&mut self) {
         self.primary_key = Setting::Reset;
     }
@@ -442,6 +466,22 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.facet_search = Setting::Reset;
     }
 
+    pub fn set_pinning(&mut self, rules: Vec<PinningRule>) {
+        self.pinning = if rules.is_empty() { Setting::Reset } else { Setting::Set(rules) }
+    }
+
+    pub fn reset_pinning(&mut self) {
+        self.pinning = Setting::Reset;
+    }
+
+    pub fn set_reranker(&mut self, reranker: RerankerSettings) {
+        self.reranker = Setting::Set(reranker);
+    }
+
+    pub fn reset_reranker(&mut self) {
+        self.reranker = Setting::Reset;
+    }
+
     #[tracing::instrument(
         level = "trace"
         skip(self, progress_callback, should_abort, settings_diff),
@@ -595,6 +635,9 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         if changes && self.synonyms == Setting::NotSet {
             self.synonyms = Setting::Set(self.index.user_defined_synonyms(self.wtxn)?);
         }
+        if changes && self.synonym_sets == Setting::NotSet {
+            self.synonym_sets = Setting::Set(self.index.user_defined_synonym_sets(self.wtxn)?);
+        }
 
         Ok(changes)
     }
@@ -620,6 +663,9 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         if changes && self.synonyms == Setting::NotSet {
             self.synonyms = Setting::Set(self.index.user_defined_synonyms(self.wtxn)?);
         }
+        if changes && self.synonym_sets == Setting::NotSet {
+            self.synonym_sets = Setting::Set(self.index.user_defined_synonym_sets(self.wtxn)?);
+        }
 
         Ok(changes)
     }
@@ -645,84 +691,137 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         if changes && self.synonyms == Setting::NotSet {
             self.synonyms = Setting::Set(self.index.user_defined_synonyms(self.wtxn)?);
         }
+        if changes && self.synonym_sets == Setting::NotSet {
+            self.synonym_sets = Setting::Set(self.index.user_defined_synonym_sets(self.wtxn)?);
+        }
 
         Ok(changes)
     }
 
     fn update_synonyms(&mut self) -> Result<bool> {
-        match self.synonyms {
-            Setting::Set(ref user_synonyms) => {
-                fn normalize(tokenizer: &Tokenizer<'_>, text: &str) -> Vec<String> {
-                    tokenizer
-                        .tokenize(text)
-                        .filter_map(|token| {
-                            if token.is_word() && !token.lemma().is_empty() {
-                                Some(token.lemma().to_string())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                }
+        if self.synonyms.is_not_set() && self.synonym_sets.is_not_set() {
+            return Ok(false);
+        }
 
-                let mut builder = TokenizerBuilder::new();
-                let stop_words = self.index.stop_words(self.wtxn)?;
-                if let Some(ref stop_words) = stop_words {
-                    builder.stop_words(stop_words);
-                }
+        fn normalize(tokenizer: &Tokenizer<'_>, text: &str) -> Vec<String> {
+            tokenizer
+                .tokenize(text)
+                .filter_map(|token| {
+                    if token.is_word() && !token.lemma().is_empty() {
+                        Some(token.lemma().to_string())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        }
 
-                let separators = self.index.allowed_separators(self.wtxn)?;
-                let separators: Option<Vec<_>> =
-                    separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
-                if let Some(ref separators) = separators {
-                    builder.separators(separators);
-                }
+        let mut builder = TokenizerBuilder::new();
+        let stop_words = self.index.stop_words(self.wtxn)?;
+        if let Some(ref stop_words) = stop_words {
+            builder.stop_words(stop_words);
+        }
 
-                let dictionary = self.index.dictionary(self.wtxn)?;
-                let dictionary: Option<Vec<_>> =
-                    dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
-                if let Some(ref dictionary) = dictionary {
-                    builder.words_dict(dictionary);
-                }
+        let separators = self.index.allowed_separators(self.wtxn)?;
+        let separators: Option<Vec<_>> =
+            separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref separators) = separators {
+            builder.separators(separators);
+        }
 
-                let tokenizer = builder.build();
+        let dictionary = self.index.dictionary(self.wtxn)?;
+        let dictionary: Option<Vec<_>> =
+            dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref dictionary) = dictionary {
+            builder.words_dict(dictionary);
+        }
+
+        let tokenizer = builder.build();
+
+        // Normalize both the words and associated synonyms and store the normalized synonyms
+        // under the normalized word, merging the possible duplicate words.
+        let normalize_synonyms = |synonyms: &mut HashMap<Vec<String>, Vec<Vec<String>>>,
+                                  word: &str,
+                                  word_synonyms: &[&str]| {
+            let normalized_word = normalize(&tokenizer, word);
+            let normalized_synonyms: Vec<_> = word_synonyms
+                .iter()
+                .map(|synonym| normalize(&tokenizer, synonym))
+                .filter(|synonym| !synonym.is_empty())
+                .collect();
+
+            if !normalized_word.is_empty() && !normalized_synonyms.is_empty() {
+                let entry = synonyms.entry(normalized_word).or_insert_with(Vec::new);
+                entry.extend(normalized_synonyms);
+            }
+        };
 
+        // Make sure that we don't have duplicate synonyms.
+        let dedup_synonyms = |synonyms: &mut HashMap<Vec<String>, Vec<Vec<String>>>| {
+            synonyms.iter_mut().for_each(|(_, synonyms)| {
+                synonyms.sort_unstable();
+                synonyms.dedup();
+            });
+        };
+
+        let synonyms_changed = match self.synonyms {
+            Setting::Set(ref user_synonyms) => {
                 let mut new_synonyms = HashMap::new();
                 for (word, synonyms) in user_synonyms {
-                    // Normalize both the word and associated synonyms.
-                    let normalized_word = normalize(&tokenizer, word);
-                    let normalized_synonyms: Vec<_> = synonyms
-                        .iter()
-                        .map(|synonym| normalize(&tokenizer, synonym))
-                        .filter(|synonym| !synonym.is_empty())
-                        .collect();
-
-                    // Store the normalized synonyms under the normalized word,
-                    // merging the possible duplicate words.
-                    if !normalized_word.is_empty() && !normalized_synonyms.is_empty() {
-                        let entry = new_synonyms.entry(normalized_word).or_insert_with(Vec::new);
-                        entry.extend(normalized_synonyms.into_iter());
-                    }
+                    let synonyms: Vec<_> = synonyms.iter().map(String::as_str).collect();
+                    normalize_synonyms(&mut new_synonyms, word, &synonyms);
                 }
-
-                // Make sure that we don't have duplicate synonyms.
-                new_synonyms.iter_mut().for_each(|(_, synonyms)| {
-                    synonyms.sort_unstable();
-                    synonyms.dedup();
-                });
+                dedup_synonyms(&mut new_synonyms);
 
                 let old_synonyms = self.index.synonyms(self.wtxn)?;
 
                 if new_synonyms != old_synonyms {
                     self.index.put_synonyms(self.wtxn, &new_synonyms, user_synonyms)?;
-                    Ok(true)
+                    true
                 } else {
-                    Ok(false)
+                    false
                 }
             }
-            Setting::Reset => Ok(self.index.delete_synonyms(self.wtxn)?),
-            Setting::NotSet => Ok(false),
-        }
+            Setting::Reset => self.index.delete_synonyms(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        let synonym_sets_changed = match self.synonym_sets {
+            Setting::Set(ref user_synonym_sets) => {
+                let mut new_synonym_sets = Vec::with_capacity(user_synonym_sets.len());
+                for set in user_synonym_sets {
+                    let mut synonyms = HashMap::new();
+                    for rule in &set.rules {
+                        let parsed = SynonymRule::parse(rule).map_err(|error| {
+                            UserError::InvalidSynonymRule { rule: rule.clone(), error }
+                        })?;
+                        for (word, word_synonyms) in parsed.expand() {
+                            normalize_synonyms(&mut synonyms, word, &word_synonyms);
+                        }
+                    }
+                    dedup_synonyms(&mut synonyms);
+                    new_synonym_sets
+                        .push(NormalizedSynonymSet { locales: set.locales.clone(), synonyms });
+                }
+
+                let old_synonym_sets = self.index.synonym_sets(self.wtxn)?;
+
+                if new_synonym_sets != old_synonym_sets {
+                    self.index.put_synonym_sets(
+                        self.wtxn,
+                        new_synonym_sets,
+                        user_synonym_sets.clone(),
+                    )?;
+                    true
+                } else {
+                    false
+                }
+            }
+            Setting::Reset => self.index.delete_synonym_sets(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(synonyms_changed || synonym_sets_changed)
     }
 
     fn update_exact_attributes(&mut self) -> Result<bool> {
@@ -1014,7 +1113,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                             name,
                             EmbedderAction::with_write_back(
                                 WriteBackToDocuments { embedder_id, user_provided },
-                                config.quantized(),
+                                config.quantization(),
                             ),
                         ))
                     })
@@ -1052,7 +1151,10 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             match joined {
                 // updated config
                 EitherOrBoth::Both((name, (old, user_provided)), (_, new)) => {
-                    let was_quantized = old.binary_quantized.set().unwrap_or_default();
+                    let old_quantization = VectorQuantization::from_options(
+                        old.quantization.set(),
+                        old.binary_quantized.set(),
+                    );
                     let settings_diff = SettingsDiff::from_settings(&name, old, new)?;
                     match settings_diff {
                         SettingsDiff::Remove => {
@@ -1074,7 +1176,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                                 name,
                                 EmbedderAction::with_write_back(
                                     WriteBackToDocuments { embedder_id, user_provided },
-                                    was_quantized,
+                                    old_quantization,
                                 ),
                             );
                         }
@@ -1087,7 +1189,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                             );
                             embedder_actions.insert(
                                 name.clone(),
-                                EmbedderAction::with_reindex(action, was_quantized)
+                                EmbedderAction::with_reindex(action, old_quantization)
                                     .with_is_being_quantized(quantize),
                             );
                             let new =
@@ -1193,6 +1295,44 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(changed)
     }
 
+    fn update_pinning(&mut self) -> Result<bool> {
+        let changed = match &self.pinning {
+            Setting::Set(new) => {
+                let old = self.index.pinning_rules(self.wtxn)?;
+                if &old == new {
+                    false
+                } else {
+                    self.index.put_pinning_rules(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_pinning_rules(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_reranker(&mut self) -> Result<bool> {
+        let changed = match &self.reranker {
+            Setting::Set(new) => {
+                // check that the reranker can be built before storing its settings
+                Reranker::new(new.clone())?;
+                let old = self.index.reranker(self.wtxn)?;
+                if old.as_ref() == Some(new) {
+                    false
+                } else {
+                    self.index.put_reranker(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_reranker(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
     fn update_localized_attributes_rules(&mut self) -> Result<()> {
         match &self.localized_attributes_rules {
             Setting::Set(new) => {
@@ -1217,7 +1357,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
     {
         self.index.set_updated_at(self.wtxn, &OffsetDateTime::now_utc())?;
 
-        let old_inner_settings = InnerIndexSettings::from_index(self.index, self.wtxn, None)?;
+        // the old embedders render the documents with their media, so that unchanged documents are not embedded again
+        let old_embedders = embedders(
+            self.index.embedding_configs(self.wtxn)?,
+            self.indexer_config.embedding_cache.as_ref(),
+            self.indexer_config.embedding_media_path.as_deref(),
+        )?;
+        let old_inner_settings =
+            InnerIndexSettings::from_index(self.index, self.wtxn, Some(old_embedders))?;
 
         // never trigger re-indexing
         self.update_displayed()?;
@@ -1231,6 +1378,8 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.update_sort_facet_values_by()?;
         self.update_pagination_max_total_hits()?;
         self.update_search_cutoff()?;
+        self.update_pinning()?;
+        self.update_reranker()?;
 
         // could trigger re-indexing
         self.update_filterable()?;
@@ -1249,7 +1398,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
 
         let embedding_config_updates = self.update_embedding_configs()?;
 
-        let mut new_inner_settings = InnerIndexSettings::from_index(self.index, self.wtxn, None)?;
+        // the documents are reindexed with the new embedders, that can use the embedding cache
+        let new_embedders = embedders(
+            self.index.embedding_configs(self.wtxn)?,
+            self.indexer_config.embedding_cache.as_ref(),
+            self.indexer_config.embedding_media_path.as_deref(),
+        )?;
+        let mut new_inner_settings =
+            InnerIndexSettings::from_index(self.index, self.wtxn, Some(new_embedders))?;
         new_inner_settings.recompute_searchables(self.wtxn, self.index)?;
 
         let primary_key_id = self
@@ -1336,11 +1492,14 @@ impl InnerIndexSettingsDiff {
 
         // if the user-defined searchables changed, then we need to reindex prompts.
         if cache_user_defined_searchables {
-            for (embedder_name, (config, _, _quantized)) in
+            for (embedder_name, (config, _, _quantization, _distance)) in
                 new_settings.embedding_configs.inner_as_ref()
             {
-                let was_quantized =
-                    old_settings.embedding_configs.get(embedder_name).is_some_and(|conf| conf.2);
+                let old_quantization = old_settings
+                    .embedding_configs
+                    .get(embedder_name)
+                    .map(|conf| conf.2)
+                    .unwrap_or_default();
                 // skip embedders that don't use document templates
                 if !config.uses_document_template() {
                     continue;
@@ -1352,12 +1511,12 @@ impl InnerIndexSettingsDiff {
                     std::collections::btree_map::Entry::Vacant(entry) => {
                         entry.insert(EmbedderAction::with_reindex(
                             ReindexAction::RegeneratePrompts,
-                            was_quantized,
+                            old_quantization,
                         ));
                     }
                     std::collections::btree_map::Entry::Occupied(entry) => {
                         let EmbedderAction {
-                            was_quantized: _,
+                            old_quantization: _,
                             is_being_quantized: _,
                             write_back: _, // We are deleting this embedder, so no point in regeneration
                             reindex: _,    // We are already fully reindexing
@@ -1470,6 +1629,12 @@ impl InnerIndexSettingsDiff {
                     if old_facet_level_database != new_facet_level_database {
                         return true;
                     }
+
+                    // Check if the field is parsed as a date in the old and new settings.
+                    // If there is a difference, the values must move between the number and string databases.
+                    if old_filterable_features.is_date() != new_filterable_features.is_date() {
+                        return true;
+                    }
                 }
             }
         }
@@ -1496,7 +1661,9 @@ impl InnerIndexSettingsDiff {
 
     pub fn run_geo_indexing(&self) -> bool {
         self.old.geo_fields_ids != self.new.geo_fields_ids
-            || (!self.settings_update_only && self.new.geo_fields_ids.is_some())
+            || self.old.geojson_field_id != self.new.geojson_field_id
+            || (!self.settings_update_only
+                && (self.new.geo_fields_ids.is_some() || self.new.geojson_field_id.is_some()))
     }
 }
 
@@ -1516,6 +1683,7 @@ pub(crate) struct InnerIndexSettings {
     pub proximity_precision: ProximityPrecision,
     pub embedding_configs: EmbeddingConfigs,
     pub geo_fields_ids: Option<(FieldId, FieldId)>,
+    pub geojson_field_id: Option<FieldId>,
     pub prefix_search: PrefixSearch,
     pub facet_search: bool,
 }
@@ -1535,7 +1703,7 @@ impl InnerIndexSettings {
         let proximity_precision = index.proximity_precision(rtxn)?.unwrap_or_default();
         let embedding_configs = match embedding_configs {
             Some(embedding_configs) => embedding_configs,
-            None => embedders(index.embedding_configs(rtxn)?)?,
+            None => embedders(index.embedding_configs(rtxn)?, None, None)?,
         };
         let prefix_search = index.prefix_search(rtxn)?.unwrap_or_default();
         let facet_search = index.facet_search(rtxn)?;
@@ -1550,6 +1718,10 @@ impl InnerIndexSettings {
             }
             _ => None,
         };
+        let geojson_field_id = match fields_ids_map.id(RESERVED_GEOJSON_FIELD_NAME) {
+            Some(field_id) if index.is_geo_enabled(rtxn)? => Some(field_id),
+            _ => None,
+        };
         let localized_attributes_rules =
             index.localized_attributes_rules(rtxn)?.unwrap_or_default();
         let filterable_attributes_rules = index.filterable_attributes_rules(rtxn)?;
@@ -1577,6 +1749,7 @@ impl InnerIndexSettings {
             proximity_precision,
             embedding_configs,
             geo_fields_ids,
+            geojson_field_id,
             prefix_search,
             facet_search,
         })
@@ -1592,6 +1765,13 @@ impl InnerIndexSettings {
         )
     }
 
+    /// Returns `true` if the string values of this field must be parsed as dates.
+    pub fn is_date_facet(&self, field_id: FieldId) -> bool {
+        self.fields_ids_map.metadata(field_id).is_some_and(|metadata| {
+            metadata.filterable_attributes_features(&self.filterable_attributes_rules).is_date()
+        })
+    }
+
     // find and insert the new field ids
     pub fn recompute_searchables(
         &mut self,
@@ -1616,30 +1796,62 @@ impl InnerIndexSettings {
     }
 }
 
-fn embedders(embedding_configs: Vec<IndexEmbeddingConfig>) -> Result<EmbeddingConfigs> {
+fn embedders(
+    embedding_configs: Vec<IndexEmbeddingConfig>,
+    persistent_cache: Option<&PersistentEmbeddingCache>,
+    media_path: Option<&Path>,
+) -> Result<EmbeddingConfigs> {
     let res: Result<_> = embedding_configs
         .into_iter()
         .map(
             |IndexEmbeddingConfig {
                  name,
-                 config: EmbeddingConfig { embedder_options, prompt, quantized },
+                 config:
+                     EmbeddingConfig { embedder_options, prompt, quantized, quantization, distance },
                  ..
              }| {
-                let prompt = Arc::new(prompt.try_into().map_err(crate::Error::from)?);
+                let prompt: Prompt = prompt.try_into().map_err(crate::Error::from)?;
+                let prompt = Arc::new(prompt.with_media(embedder_options.document_media(media_path)));
 
                 let embedder = Arc::new(
-                    // cache_cap: no cache needed for indexing purposes
-                    Embedder::new(embedder_options.clone(), 0)
+                    // cache_cap: no in-memory cache needed for indexing purposes
+                    Embedder::new(embedder_options.clone(), 0, persistent_cache)
                         .map_err(crate::vector::Error::from)
                         .map_err(crate::Error::from)?,
                 );
-                Ok((name, (embedder, prompt, quantized.unwrap_or_default())))
+                Ok((
+                    name,
+                    (
+                        embedder,
+                        prompt,
+                        VectorQuantization::from_options(quantization, quantized),
+                        distance.unwrap_or_default(),
+                    ),
+                ))
             },
         )
         .collect();
     res.map(EmbeddingConfigs::new)
 }
 
+fn validate_chunking(name: &str, chunking: &Setting<Chunking>) -> Result<()> {
+    let Setting::Set(chunking) = chunking else { return Ok(()) };
+    if chunking.size == Some(0) {
+        return Err(
+            UserError::InvalidSettingsChunkingSize { embedder_name: name.to_owned() }.into()
+        );
+    }
+    if chunking.overlap() >= chunking.size() {
+        return Err(UserError::InvalidSettingsChunkingOverlap {
+            embedder_name: name.to_owned(),
+            size: chunking.size(),
+            overlap: chunking.overlap(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 fn validate_prompt(
     name: &str,
     new_prompt: Setting<String>,
@@ -1685,6 +1897,7 @@ pub fn validate_embedding_settings(
         dimensions,
         document_template,
         document_template_max_bytes,
+        chunking,
         url,
         request,
         response,
@@ -1692,10 +1905,15 @@ pub fn validate_embedding_settings(
         mut indexing_embedder,
         distribution,
         headers,
+        media,
+        late_interaction,
         binary_quantized: binary_quantize,
+        quantization,
+        distance,
     } = settings;
 
     let document_template = validate_prompt(name, document_template, document_template_max_bytes)?;
+    validate_chunking(name, &chunking)?;
 
     if let Some(0) = dimensions.set() {
         return Err(crate::error::UserError::InvalidSettingsDimensions {
@@ -1721,6 +1939,21 @@ pub fn validate_embedding_settings(
         }
     }
 
+    if let Some(media) = media.as_ref().set() {
+        media.fragments().map_err(|error| {
+            crate::UserError::VectorEmbeddingError(
+                crate::vector::error::NewEmbedderError::rest_could_not_parse_template(error).into(),
+            )
+        })?;
+        // the rendered text is sent along with the media, it cannot be split into several chunks
+        if let Setting::Set(_) = chunking {
+            return Err(UserError::InvalidSettingsMediaWithChunking {
+                embedder_name: name.to_owned(),
+            }
+            .into());
+        }
+    }
+
     let Some(inferred_source) = source.set() else {
         // we are validating the fused settings, so we always have a source
         return Ok(Setting::Set(EmbeddingSettings {
@@ -1732,6 +1965,7 @@ pub fn validate_embedding_settings(
             dimensions,
             document_template,
             document_template_max_bytes,
+            chunking,
             url,
             request,
             response,
@@ -1739,7 +1973,11 @@ pub fn validate_embedding_settings(
             indexing_embedder,
             distribution,
             headers,
+            media,
+            late_interaction,
             binary_quantized: binary_quantize,
+            quantization,
+            distance,
         }));
     };
     EmbeddingSettings::check_settings(
@@ -1756,12 +1994,41 @@ pub fn validate_embedding_settings(
         &response,
         &document_template,
         &document_template_max_bytes,
+        &chunking,
         &headers,
+        &media,
+        &late_interaction,
         &search_embedder,
         &indexing_embedder,
         &binary_quantize,
+        &quantization,
+        &distance,
         &distribution,
     )?;
+
+    // the token embeddings are packed together in the response, so their dimensions cannot be inferred
+    if matches!(late_interaction, Setting::Set(true)) && !matches!(dimensions, Setting::Set(_)) {
+        return Err(UserError::InvalidSettingsEmbedder {
+            embedder_name: name.to_owned(),
+            message: "Missing field `dimensions`.\n  - note: `dimensions` must be set when `lateInteraction` is `true`".to_owned(),
+        }
+        .into());
+    }
+
+    let effective_quantization =
+        VectorQuantization::from_options(quantization.set(), binary_quantize.set());
+    if let (
+        VectorQuantization::Binary | VectorQuantization::Int8,
+        Setting::Set(distance @ VectorDistance::DotProduct),
+    ) = (effective_quantization, &distance)
+    {
+        return Err(UserError::InvalidQuantizationForDistance {
+            embedder_name: name.to_owned(),
+            quantization: effective_quantization,
+            distance: *distance,
+        }
+        .into());
+    }
     match inferred_source {
         EmbedderSource::OpenAi => {
             if let Setting::Set(model) = &model {
@@ -1797,7 +2064,29 @@ pub fn validate_embedding_settings(
         EmbedderSource::Ollama
         | EmbedderSource::HuggingFace
         | EmbedderSource::UserProvided
-        | EmbedderSource::Rest => {}
+        | EmbedderSource::Rest
+        | EmbedderSource::Onnx => {}
+        EmbedderSource::Sparse => {
+            // the embeddings are generated by a remote server only when its url is set
+            let missing_field = match (&url, &request, &response) {
+                (Setting::Set(_), Setting::Set(_), Setting::Set(_))
+                | (
+                    Setting::NotSet | Setting::Reset,
+                    Setting::NotSet | Setting::Reset,
+                    Setting::NotSet | Setting::Reset,
+                ) => None,
+                (Setting::NotSet | Setting::Reset, _, _) => Some("url"),
+                (_, Setting::NotSet | Setting::Reset, _) => Some("request"),
+                (_, _, Setting::NotSet | Setting::Reset) => Some("response"),
+            };
+            if let Some(field) = missing_field {
+                return Err(UserError::InvalidSettingsEmbedder {
+                    embedder_name: name.to_owned(),
+                    message: format!("Missing field `{field}`.\n  - note: `url`, `request` and `response` must be set together for source `sparse`"),
+                }
+                .into());
+            }
+        }
         EmbedderSource::Composite => {
             if let Setting::Set(embedder) = &search_embedder {
                 if let Some(source) = embedder.source.set() {
@@ -1834,10 +2123,15 @@ pub fn validate_embedding_settings(
                         &embedder.response,
                         &embedder.document_template,
                         &embedder.document_template_max_bytes,
+                        &embedder.chunking,
                         &embedder.headers,
+                        &embedder.media,
+                        &embedder.late_interaction,
                         &search_embedder,
                         &indexing_embedder,
                         &embedder.binary_quantized,
+                        &embedder.quantization,
+                        &embedder.distance,
                         &embedder.distribution,
                     )?;
                 } else {
@@ -1854,6 +2148,7 @@ pub fn validate_embedding_settings(
                     embedder.document_template,
                     embedder.document_template_max_bytes,
                 )?;
+                validate_chunking(name, &embedder.chunking)?;
 
                 if let Some(source) = embedder.source.set() {
                     let search_embedder = match embedder.search_embedder.clone() {
@@ -1889,10 +2184,15 @@ pub fn validate_embedding_settings(
                         &embedder.response,
                         &embedder.document_template,
                         &embedder.document_template_max_bytes,
+                        &embedder.chunking,
                         &embedder.headers,
+                        &embedder.media,
+                        &embedder.late_interaction,
                         &search_embedder,
                         &indexing_embedder,
                         &embedder.binary_quantized,
+                        &embedder.quantization,
+                        &embedder.distance,
                         &embedder.distribution,
                     )?;
                 } else {
@@ -1916,6 +2216,7 @@ pub fn validate_embedding_settings(
         dimensions,
         document_template,
         document_template_max_bytes,
+        chunking,
         url,
         request,
         response,
@@ -1923,7 +2224,11 @@ pub fn validate_embedding_settings(
         indexing_embedder,
         distribution,
         headers,
+        media,
+        late_interaction,
         binary_quantized: binary_quantize,
+        quantization,
+        distance,
     }))
 }
 
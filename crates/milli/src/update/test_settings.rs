@@ -1,4 +1,5 @@
 use big_s::S;
+use charabia::Language;
 use heed::types::Bytes;
 use maplit::{btreemap, btreeset};
 use meili_snap::snapshot;
@@ -7,7 +8,7 @@ use super::*;
 use crate::error::Error;
 use crate::index::tests::TempIndex;
 use crate::update::ClearDocuments;
-use crate::{db_snap, Criterion, Filter, SearchResult};
+use crate::{db_snap, Criterion, Filter, SearchResult, SynonymSet};
 
 #[test]
 fn set_and_reset_searchable_fields() {
@@ -620,6 +621,73 @@ fn thai_synonyms() {
     assert_eq!(result.documents_ids.len(), 2);
 }
 
+#[test]
+fn set_and_reset_synonym_sets() {
+    let index = TempIndex::new();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "name": "kevin", "maxim": "I watch the television" },
+            { "id": 1, "name": "kevina", "maxim": "My smartphone is broken" },
+            { "id": 2, "name": "benoit", "maxim": "Le portable est cassé" },
+        ]))
+        .unwrap();
+
+    index
+        .update_settings(|settings| {
+            settings.set_synonym_sets(vec![
+                SynonymSet::new(vec![S("tv, television, telly"), S("phone => smartphone")], vec![]),
+                SynonymSet::new(vec![S("téléphone => portable")], vec![Language::Fra]),
+            ]);
+        })
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.user_defined_synonym_sets(&rtxn).unwrap().len(), 2);
+
+    // Equivalence groups work in every direction.
+    let result = index.search(&rtxn).query("telly").execute().unwrap();
+    assert_eq!(result.documents_ids, vec![0]);
+    let result = index.search(&rtxn).query("tv").execute().unwrap();
+    assert_eq!(result.documents_ids, vec![0]);
+
+    // One-way rules only expand the left side.
+    let result = index.search(&rtxn).query("phone").execute().unwrap();
+    assert_eq!(result.documents_ids, vec![1]);
+    let mut search = index.search(&rtxn);
+    search.query("broken phone").terms_matching_strategy(crate::TermsMatchingStrategy::All);
+    assert_eq!(search.execute().unwrap().documents_ids, vec![1]);
+
+    // Scoped sets only apply to the queries in their locales.
+    let result = index.search(&rtxn).query("téléphone").execute().unwrap();
+    assert!(result.documents_ids.is_empty());
+    let result =
+        index.search(&rtxn).query("téléphone").locales(vec![Language::Fra]).execute().unwrap();
+    assert_eq!(result.documents_ids, vec![2]);
+    drop(rtxn);
+
+    // Invalid rules are rejected.
+    let error = index
+        .update_settings(|settings| {
+            settings.set_synonym_sets(vec![SynonymSet::new(vec![S("tv")], vec![])]);
+        })
+        .unwrap_err();
+    snapshot!(error, @"The synonym rule `tv` is invalid: an equivalence group must contain at least two terms.");
+
+    // Reset the synonym sets.
+    index
+        .update_settings(|settings| {
+            settings.reset_synonym_sets();
+        })
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    assert!(index.user_defined_synonym_sets(&rtxn).unwrap().is_empty());
+    assert!(index.synonym_sets(&rtxn).unwrap().is_empty());
+    let result = index.search(&rtxn).query("telly").execute().unwrap();
+    assert!(result.documents_ids.is_empty());
+}
+
 #[test]
 fn setting_searchable_recomputes_other_settings() {
     let index = TempIndex::new();
@@ -881,6 +949,7 @@ fn test_correct_settings_init() {
                 dictionary,
                 distinct_field,
                 synonyms,
+                synonym_sets,
                 primary_key,
                 authorize_typos,
                 min_word_len_two_typos,
@@ -896,6 +965,8 @@ fn test_correct_settings_init() {
                 localized_attributes_rules,
                 prefix_search,
                 facet_search,
+                pinning,
+                reranker,
             } = settings;
             assert!(matches!(searchable_fields, Setting::NotSet));
             assert!(matches!(displayed_fields, Setting::NotSet));
@@ -908,6 +979,7 @@ fn test_correct_settings_init() {
             assert!(matches!(dictionary, Setting::NotSet));
             assert!(matches!(distinct_field, Setting::NotSet));
             assert!(matches!(synonyms, Setting::NotSet));
+            assert!(matches!(synonym_sets, Setting::NotSet));
             assert!(matches!(primary_key, Setting::NotSet));
             assert!(matches!(authorize_typos, Setting::NotSet));
             assert!(matches!(min_word_len_two_typos, Setting::NotSet));
@@ -923,6 +995,8 @@ fn test_correct_settings_init() {
             assert!(matches!(localized_attributes_rules, Setting::NotSet));
             assert!(matches!(prefix_search, Setting::NotSet));
             assert!(matches!(facet_search, Setting::NotSet));
+            assert!(matches!(pinning, Setting::NotSet));
+            assert!(matches!(reranker, Setting::NotSet));
         })
         .unwrap();
 }
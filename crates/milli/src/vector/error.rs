@@ -3,6 +3,7 @@ use std::path::PathBuf;
 
 use bumpalo::Bump;
 use hf_hub::api::sync::ApiError;
+use tract_onnx::prelude::TractError;
 
 use super::parsed_vectors::ParsedVectorsDiff;
 use super::rest::ConfigurationSource;
@@ -99,6 +100,10 @@ pub enum EmbedErrorKind {
     UnexpectedDimension(usize, usize),
     #[error("no embedding was produced")]
     MissingEmbedding,
+    #[error("could not run ONNX model:\n  - {0}")]
+    OnnxRun(TractError),
+    #[error("unexpected shape `{0:?}` for the output of the ONNX model, expected `[1, tokens, dimensions]` or `[1, dimensions]`")]
+    OnnxOutputShape(Vec<usize>),
     #[error(transparent)]
     PanicInThreadPool(#[from] PanicCatched),
 }
@@ -210,6 +215,14 @@ impl EmbedError {
     pub(crate) fn rest_extraction_error(error: String) -> EmbedError {
         Self { kind: EmbedErrorKind::RestExtractionError(error), fault: FaultSource::Runtime }
     }
+
+    pub(crate) fn onnx_run(inner: TractError) -> EmbedError {
+        Self { kind: EmbedErrorKind::OnnxRun(inner), fault: FaultSource::Runtime }
+    }
+
+    pub(crate) fn onnx_output_shape(shape: Vec<usize>) -> EmbedError {
+        Self { kind: EmbedErrorKind::OnnxOutputShape(shape), fault: FaultSource::User }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -337,6 +350,17 @@ impl NewEmbedderError {
         Self { kind: NewEmbedderErrorKind::OllamaUnsupportedUrl(url), fault: FaultSource::User }
     }
 
+    pub(crate) fn load_onnx_model(filename: PathBuf, inner: TractError) -> NewEmbedderError {
+        Self {
+            kind: NewEmbedderErrorKind::LoadOnnxModel { filename, inner },
+            fault: FaultSource::User,
+        }
+    }
+
+    pub(crate) fn onnx_unsupported_input(name: String) -> NewEmbedderError {
+        Self { kind: NewEmbedderErrorKind::OnnxUnsupportedInput(name), fault: FaultSource::User }
+    }
+
     pub(crate) fn composite_dimensions_mismatch(
         search_dimensions: usize,
         index_dimensions: usize,
@@ -491,6 +515,10 @@ pub enum NewEmbedderErrorKind {
     CouldNotParseTemplate(String),
     #[error("unsupported Ollama URL.\n  - For `ollama` sources, the URL must end with `/api/embed` or `/api/embeddings`\n  - Got `{0}`")]
     OllamaUnsupportedUrl(String),
+    #[error("could not load ONNX model at {filename}:\n  - {inner}")]
+    LoadOnnxModel { filename: PathBuf, inner: TractError },
+    #[error("unsupported input `{0}` of the ONNX model.\n  - Note: only the `input_ids`, `attention_mask` and `token_type_ids` inputs are supported.")]
+    OnnxUnsupportedInput(String),
     #[error("error while generating test embeddings.\n  - the dimensions of embeddings produced at search time and at indexing time don't match.\n  - Search time dimensions: {search_dimensions}\n  - Indexing time dimensions: {index_dimensions}\n  - Note: Dimensions of embeddings produced by both embedders are required to match.")]
     CompositeDimensionsMismatch { search_dimensions: usize, index_dimensions: usize },
     #[error("error while generating test embeddings.\n  - could not generate test embedding with embedder at {failing_embedder} time.\n  - Embedding failed with {inner}")]
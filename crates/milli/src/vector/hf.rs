@@ -99,7 +99,7 @@ impl std::fmt::Debug for Embedder {
 }
 
 #[derive(Clone, Copy, serde::Deserialize)]
-struct PoolingConfig {
+pub(super) struct PoolingConfig {
     #[serde(default)]
     pub pooling_mode_cls_token: bool,
     #[serde(default)]
@@ -122,7 +122,7 @@ pub enum Pooling {
     LastToken,
 }
 impl Pooling {
-    fn override_with(&mut self, pooling: OverridePooling) {
+    pub(super) fn override_with(&mut self, pooling: OverridePooling) {
         match pooling {
             OverridePooling::UseModel => {}
             OverridePooling::ForceCls => *self = Pooling::Cls,
@@ -152,7 +152,7 @@ impl From<PoolingConfig> for Pooling {
 impl Embedder {
     pub fn new(
         options: EmbedderOptions,
-        cache_cap: usize,
+        cache: EmbeddingCache,
     ) -> std::result::Result<Self, NewEmbedderError> {
         let device = match candle_core::Device::cuda_if_available(0) {
             Ok(device) => device,
@@ -249,14 +249,7 @@ impl Embedder {
             tokenizer.with_padding(Some(pp));
         }
 
-        let mut this = Self {
-            model,
-            tokenizer,
-            options,
-            dimensions: 0,
-            pooling,
-            cache: EmbeddingCache::new(cache_cap),
-        };
+        let mut this = Self { model, tokenizer, options, dimensions: 0, pooling, cache };
 
         let embeddings = this
             .embed(vec!["test".into()])
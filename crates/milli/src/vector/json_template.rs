@@ -495,6 +495,21 @@ impl ValueTemplate {
         Ok(rendered)
     }
 
+    /// Render a single value into a value that was rendered from a template with the same structure.
+    ///
+    /// This allows filling several placeholders of the same JSON value, using one template per placeholder.
+    ///
+    /// # Panics
+    ///
+    /// - if the placeholder is repeated
+    /// - if `rendered` does not have the structure of the template
+    pub fn inject_into(&self, rendered: &mut Value, value: Value) {
+        match &self.value_kind {
+            ValueKind::Single(injection_path) => inject_value(rendered, injection_path, value),
+            ValueKind::Array(_) => panic!("cannot inject into a repeated placeholder"),
+        }
+    }
+
     /// Extract sub values from the template and a value.
     ///
     /// # Errors
@@ -878,6 +893,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn several_placeholders() {
+        let template = json!({
+            "text": "{{text}}",
+            "image": {"data": "{{media}}"}
+        });
+
+        let text = new_template(template.clone()).unwrap();
+        let media = ValueTemplate::new(template, "{{media}}", "{{..}}").unwrap();
+
+        let mut rendered = text.inject(["a doggo".into()]).unwrap();
+        media.inject_into(&mut rendered, "aGVsbG8=".into());
+        assert_eq!(
+            rendered,
+            json!({
+                "text": "a doggo",
+                "image": {"data": "aGVsbG8="}
+            })
+        );
+    }
+
     #[test]
     fn too_many_placeholders() {
         let template = json!({
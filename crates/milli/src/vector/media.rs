@@ -0,0 +1,246 @@
+//! Media, such as images, embedded alongside the text of documents and queries by the `rest` embedders.
+//!
+//! The media of a document is read from one of its fields, then the rendered text and the media are injected into a
+//! JSON fragment, that is itself injected into the request template of the embedder in place of the text.
+//!
+//! The embedders exchange the texts to embed as strings, so a text and its media are encoded as a single
+//! [`MediaInput`] string. This also makes the media part of the key of the embedding caches.
+
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine as _;
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use super::json_template::{TemplateParsingError, ValueTemplate};
+use super::rest::{REPEAT_PLACEHOLDER, REQUEST_PLACEHOLDER};
+
+pub const MEDIA_PLACEHOLDER: &str = "{{media}}";
+
+/// Describes where to find the media of the documents and how to send them to the embedder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct MediaOptions {
+    /// The document field containing the media.
+    ///
+    /// The field either contains the base64 encoded media,
+    /// or an object with a `path` to a file of the media directory of the instance.
+    pub document_field: String,
+    /// The JSON value injected in the request in place of `{{text}}` for each document.
+    ///
+    /// It must contain `{{media}}` and can contain `{{text}}`, replaced by the media and the rendered document template.
+    #[schema(value_type = Object)]
+    pub indexing_fragment: Value,
+    /// The JSON value injected in the request in place of `{{text}}` for search queries.
+    ///
+    /// It must contain `{{media}}` and can contain `{{text}}`, replaced by the `media` and the `q` of the query.
+    ///
+    /// Defaults to the indexing fragment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    #[schema(value_type = Option<Object>)]
+    pub search_fragment: Option<Value>,
+}
+
+impl MediaOptions {
+    /// Parses the indexing and search fragments, the search fragment defaulting to the indexing one.
+    pub fn fragments(&self) -> Result<(Fragment, Fragment), String> {
+        let indexing = Fragment::new(self.indexing_fragment.clone(), "indexingFragment")?;
+        let search = Fragment::new(
+            self.search_fragment.as_ref().unwrap_or(&self.indexing_fragment).clone(),
+            "searchFragment",
+        )?;
+        Ok((indexing, search))
+    }
+}
+
+/// A text to embed along with its media.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MediaInput {
+    pub text: String,
+    pub media: Value,
+}
+
+impl MediaInput {
+    pub fn new(text: Option<&str>, media: Option<Value>) -> Self {
+        Self { text: text.unwrap_or_default().to_owned(), media: media.unwrap_or_default() }
+    }
+
+    /// Encodes the text and its media into the string that is passed to the embedder.
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Decodes a string passed to the embedder, a string that was not encoded being a text without media.
+    pub fn decode(input: &str) -> Self {
+        serde_json::from_str(input)
+            .unwrap_or_else(|_| Self { text: input.to_owned(), media: Value::Null })
+    }
+}
+
+/// A JSON value in which the text and media of an input are injected.
+#[derive(Debug)]
+pub struct Fragment {
+    media: ValueTemplate,
+    text: Option<ValueTemplate>,
+}
+
+impl Fragment {
+    /// Parses a fragment, returning an error message prefixed by the name of the fragment in case of failure.
+    pub fn new(template: Value, name: &str) -> Result<Self, String> {
+        let parse = |placeholder: &str| match ValueTemplate::new(
+            template.clone(),
+            placeholder,
+            REPEAT_PLACEHOLDER,
+        ) {
+            Ok(template) if template.has_array_value() => {
+                Err(format!(r#"in `{name}`: "{REPEAT_PLACEHOLDER}" cannot be used in a fragment"#))
+            }
+            Ok(template) => Ok(Some(template)),
+            Err(TemplateParsingError::MissingPlaceholderString)
+                if placeholder == REQUEST_PLACEHOLDER =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error.error_message(name, placeholder, REPEAT_PLACEHOLDER)),
+        };
+
+        // unwrap: a missing media placeholder is an error
+        let media = parse(MEDIA_PLACEHOLDER)?.unwrap();
+        let text = parse(REQUEST_PLACEHOLDER)?;
+        Ok(Self { media, text })
+    }
+
+    pub fn render(&self, input: &str) -> Value {
+        let MediaInput { text, media } = MediaInput::decode(input);
+        // unwrap: the media placeholder is not repeated, so there is exactly one value to inject
+        let mut rendered = self.media.inject([media]).unwrap();
+        if let Some(template) = &self.text {
+            template.inject_into(&mut rendered, Value::String(text));
+        }
+        rendered
+    }
+}
+
+/// The field containing the media of the documents, and the directory from which media files can be read.
+#[derive(Debug, Clone)]
+pub struct DocumentMedia {
+    field: String,
+    directory: Option<PathBuf>,
+}
+
+impl DocumentMedia {
+    pub fn new(field: String, directory: Option<PathBuf>) -> Self {
+        Self { field, directory }
+    }
+
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Returns the media described by the value of the media field of a document.
+    ///
+    /// - a missing field or `null` has no media,
+    /// - a string is the base64 encoded media,
+    /// - an object with a `path` is the content of this file of the media directory, encoded in base64.
+    pub fn resolve(&self, value: Option<Value>) -> Result<Value, String> {
+        match value {
+            None | Some(Value::Null) => Ok(Value::Null),
+            Some(Value::String(media)) => Ok(Value::String(media)),
+            Some(Value::Object(object)) => match object.get("path") {
+                Some(Value::String(path)) if object.len() == 1 => {
+                    self.read_file(path).map(Value::String)
+                }
+                _ => Err(format!(
+                    "expected `{}` to only contain a `path` string, but found `{}`",
+                    self.field,
+                    Value::Object(object)
+                )),
+            },
+            Some(value) => Err(format!(
+                "expected `{}` to be a base64 string or an object with a `path`, but found `{value}`",
+                self.field
+            )),
+        }
+    }
+
+    fn read_file(&self, path: &str) -> Result<String, String> {
+        let Some(directory) = &self.directory else {
+            return Err(format!(
+                "cannot read the media file `{path}` as no media directory is configured"
+            ));
+        };
+        let relative = Path::new(path);
+        if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(format!(
+                "the media file `{path}` must be a relative path inside of the media directory"
+            ));
+        }
+
+        let read = || -> std::io::Result<Vec<u8>> {
+            let directory = directory.canonicalize()?;
+            let file = directory.join(relative).canonicalize()?;
+            // symbolic links must not escape the media directory
+            if !file.starts_with(&directory) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    "the file is outside of the media directory",
+                ));
+            }
+            std::fs::read(file)
+        };
+        let content =
+            read().map_err(|error| format!("could not read the media file `{path}`: {error}"))?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(content))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn render_fragment() {
+        let fragment =
+            Fragment::new(json!({"text": "{{text}}", "image": {"data": "{{media}}"}}), "fragment")
+                .unwrap();
+        let input = MediaInput::new(Some("a doggo"), Some(json!("aGVsbG8="))).encode();
+        assert_eq!(
+            fragment.render(&input),
+            json!({"text": "a doggo", "image": {"data": "aGVsbG8="}})
+        );
+        // texts that were not encoded have no media
+        assert_eq!(fragment.render("a doggo"), json!({"text": "a doggo", "image": {"data": null}}));
+
+        let media_only = Fragment::new(json!({"image": "{{media}}"}), "fragment").unwrap();
+        assert_eq!(media_only.render(&input), json!({"image": "aGVsbG8="}));
+
+        let error = Fragment::new(json!({"text": "{{text}}"}), "fragment").unwrap_err();
+        assert_eq!(error, r#"in `fragment`: "{{media}}" not found"#);
+        let error = Fragment::new(json!(["{{media}}", "{{..}}"]), "fragment").unwrap_err();
+        assert_eq!(error, r#"in `fragment`: "{{..}}" cannot be used in a fragment"#);
+    }
+
+    #[test]
+    fn resolve_media() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("doggo.png"), "hello").unwrap();
+        let media = DocumentMedia::new("image".into(), Some(dir.path().to_owned()));
+
+        assert_eq!(media.resolve(None).unwrap(), Value::Null);
+        assert_eq!(media.resolve(Some(json!("aGVsbG8="))).unwrap(), json!("aGVsbG8="));
+        assert_eq!(media.resolve(Some(json!({"path": "doggo.png"}))).unwrap(), json!("aGVsbG8="));
+        assert!(media.resolve(Some(json!({"path": "../doggo.png"}))).is_err());
+        assert!(media.resolve(Some(json!({"path": "missing.png"}))).is_err());
+        assert!(media.resolve(Some(json!(42))).is_err());
+
+        let without_directory = DocumentMedia::new("image".into(), None);
+        assert!(without_directory.resolve(Some(json!({"path": "doggo.png"}))).is_err());
+    }
+}
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
-use arroy::distances::{BinaryQuantizedCosine, Cosine};
+use arroy::distances::{
+    BinaryQuantizedCosine, BinaryQuantizedEuclidean, Cosine, DotProduct, Euclidean,
+};
 use arroy::ItemId;
 use deserr::{DeserializeError, Deserr};
+use heed::types::{Bytes, DecodeIgnore};
 use heed::{RoTxn, RwTxn, Unspecified};
 use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
@@ -13,6 +17,9 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use self::error::{EmbedError, NewEmbedderError};
+use self::persistent_cache::{EmbedderCache, PersistentEmbeddingCache};
+use self::scalar_quantized::{ScalarQuantizedKeyCodec, ScalarQuantizedVectorCodec};
+use self::sparse::{SparseVector, SparseVectorDatabase, SparseVectorStore};
 use crate::progress::Progress;
 use crate::prompt::{Prompt, PromptData};
 use crate::ThreadPoolNoAbort;
@@ -22,9 +29,14 @@ pub mod error;
 pub mod hf;
 pub mod json_template;
 pub mod manual;
+pub mod media;
+pub mod onnx;
 pub mod openai;
 pub mod parsed_vectors;
+pub mod persistent_cache;
+pub mod scalar_quantized;
 pub mod settings;
+pub mod sparse;
 
 pub mod ollama;
 pub mod rest;
@@ -35,26 +47,96 @@ pub type Embedding = Vec<f32>;
 
 pub const REQUEST_PARALLELISM: usize = 40;
 pub const MAX_COMPOSITE_DISTANCE: f32 = 0.01;
+/// How many more candidates are fetched from arroy before rescoring them with the `int8` embeddings.
+const SCALAR_QUANTIZED_OVERSAMPLING: usize = 4;
+
+/// Stores the `int8` quantized embeddings of the embedders using the `int8` quantization.
+pub type ScalarQuantizedDatabase =
+    heed::Database<ScalarQuantizedKeyCodec, ScalarQuantizedVectorCodec>;
 
 pub struct ArroyWrapper {
-    quantized: bool,
+    quantization: VectorQuantization,
+    distance: VectorDistance,
     embedder_index: u8,
     database: arroy::Database<Unspecified>,
+    scalar_database: ScalarQuantizedDatabase,
+    /// The embeddings of sparse embedders are stored in this inverted index rather than in arroy.
+    sparse_store: Option<SparseVectorStore>,
+}
+
+/// Evaluates `$body` with `$d` being the arroy distance used to store the embeddings
+/// of an embedder with the given distance and quantization.
+macro_rules! with_arroy_distance {
+    ($distance:expr, $quantized:expr, |$d:ident| $body:expr) => {
+        match ($distance, $quantized) {
+            (VectorDistance::Cosine, false) => {
+                type $d = Cosine;
+                $body
+            }
+            (VectorDistance::Cosine, true) => {
+                type $d = BinaryQuantizedCosine;
+                $body
+            }
+            // dot product embedders cannot be binary quantized, this is checked in the settings
+            (VectorDistance::DotProduct, _) => {
+                type $d = DotProduct;
+                $body
+            }
+            (VectorDistance::Euclidean, false) => {
+                type $d = Euclidean;
+                $body
+            }
+            (VectorDistance::Euclidean, true) => {
+                type $d = BinaryQuantizedEuclidean;
+                $body
+            }
+        }
+    };
 }
 
 impl ArroyWrapper {
     pub fn new(
         database: arroy::Database<Unspecified>,
+        scalar_database: ScalarQuantizedDatabase,
+        sparse_database: Option<SparseVectorDatabase>,
         embedder_index: u8,
-        quantized: bool,
+        quantization: VectorQuantization,
+        distance: VectorDistance,
     ) -> Self {
-        Self { database, embedder_index, quantized }
+        let sparse_store =
+            sparse_database.map(|database| SparseVectorStore::new(database, embedder_index));
+        Self { database, scalar_database, sparse_store, embedder_index, quantization, distance }
     }
 
     pub fn embedder_index(&self) -> u8 {
         self.embedder_index
     }
 
+    pub fn distance(&self) -> VectorDistance {
+        self.distance
+    }
+
+    pub fn quantization(&self) -> VectorQuantization {
+        self.quantization
+    }
+
+    /// Whether the embeddings are the sparse embeddings of a sparse embedder.
+    pub fn is_sparse(&self) -> bool {
+        self.sparse_store.is_some()
+    }
+
+    /// Whether arroy stores binary quantized embeddings.
+    ///
+    /// It is also the case of `int8` quantized embedders, whose `int8` embeddings are only used
+    /// to rescore the embeddings returned by arroy.
+    fn arroy_quantized(&self) -> bool {
+        self.quantization != VectorQuantization::None
+    }
+
+    fn scalar_quantized(&self) -> bool {
+        self.quantization == VectorQuantization::Int8
+    }
+
     fn readers<'a, D: arroy::Distance>(
         &'a self,
         rtxn: &'a RoTxn<'a>,
@@ -74,14 +156,55 @@ impl ArroyWrapper {
     }
 
     pub fn dimensions(&self, rtxn: &RoTxn) -> Result<usize, arroy::Error> {
+        if self.is_sparse() {
+            return Ok(0);
+        }
         let first_id = arroy_db_range_for_embedder(self.embedder_index).next().unwrap();
-        if self.quantized {
-            Ok(arroy::Reader::open(rtxn, first_id, self.quantized_db())?.dimensions())
-        } else {
-            Ok(arroy::Reader::open(rtxn, first_id, self.angular_db())?.dimensions())
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            Ok(arroy::Reader::open(rtxn, first_id, self.db::<D>())?.dimensions())
+        })
+    }
+
+    /// Converts the stored embeddings to the given distance, keeping their quantization.
+    ///
+    /// The trees are rebuilt with the new distance on the next call to `build_and_quantize`.
+    pub fn change_distance(
+        &mut self,
+        wtxn: &mut RwTxn,
+        dimension: usize,
+        distance: VectorDistance,
+    ) -> Result<(), arroy::Error> {
+        if self.distance == distance || self.is_sparse() {
+            return Ok(());
         }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            with_arroy_distance!(distance, self.arroy_quantized(), |ND| {
+                self._change_distance::<D, ND>(wtxn, dimension)
+            })
+        })?;
+        self.distance = distance;
+        Ok(())
     }
 
+    fn _change_distance<D: arroy::Distance, ND: arroy::Distance>(
+        &self,
+        wtxn: &mut RwTxn,
+        dimension: usize,
+    ) -> Result<(), arroy::Error> {
+        // Like when quantizing, we can't know if a database is empty but still contains
+        // the metadata of the old distance, so we must convert all of them.
+        for index in arroy_db_range_for_embedder(self.embedder_index) {
+            let writer = arroy::Writer::new(self.db::<D>(), index, dimension);
+            writer.prepare_changing_distance::<ND>(wtxn)?;
+        }
+        Ok(())
+    }
+
+    /// Builds the trees of the embedder, converting the stored embeddings to the given
+    /// quantization beforehand if it differs from the current one.
+    ///
+    /// Quantizing is a one-way operation, only the conversions allowed by
+    /// [`VectorQuantization::can_be_converted_to`] are supported.
     #[allow(clippy::too_many_arguments)]
     pub fn build_and_quantize<R: rand::Rng + rand::SeedableRng>(
         &mut self,
@@ -89,27 +212,78 @@ impl ArroyWrapper {
         progress: &Progress,
         rng: &mut R,
         dimension: usize,
+        quantization: VectorQuantization,
+        arroy_memory: Option<usize>,
+        cancel: &(impl Fn() -> bool + Sync + Send),
+    ) -> Result<(), arroy::Error> {
+        // the inverted index of sparse embeddings doesn't need to be built
+        if self.is_sparse() {
+            return Ok(());
+        }
+        match (self.quantization, quantization) {
+            (VectorQuantization::None, VectorQuantization::Int8) => {
+                with_arroy_distance!(self.distance, false, |D| {
+                    self.store_scalar_quantized(wtxn, self.db::<D>(), dimension)
+                })?;
+            }
+            (VectorQuantization::Int8, VectorQuantization::Binary) => {
+                self.clear_scalar_quantized(wtxn)?;
+            }
+            _ => (),
+        }
+
+        let quantizing = !self.arroy_quantized() && quantization != VectorQuantization::None;
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            with_arroy_distance!(self.distance, true, |Q| {
+                self._build_and_quantize::<D, Q, R>(
+                    wtxn,
+                    self.db(),
+                    progress,
+                    rng,
+                    dimension,
+                    quantizing,
+                    arroy_memory,
+                    cancel,
+                )
+            })
+        })?;
+        self.quantization = quantization;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn _build_and_quantize<
+        D: arroy::Distance,
+        Q: arroy::Distance,
+        R: rand::Rng + rand::SeedableRng,
+    >(
+        &self,
+        wtxn: &mut RwTxn,
+        db: arroy::Database<D>,
+        progress: &Progress,
+        rng: &mut R,
+        dimension: usize,
         quantizing: bool,
         arroy_memory: Option<usize>,
         cancel: &(impl Fn() -> bool + Sync + Send),
     ) -> Result<(), arroy::Error> {
         for index in arroy_db_range_for_embedder(self.embedder_index) {
-            if self.quantized {
-                let writer = arroy::Writer::new(self.quantized_db(), index, dimension);
+            if self.arroy_quantized() {
+                let writer = arroy::Writer::new(db, index, dimension);
                 if writer.need_build(wtxn)? {
                     writer.builder(rng).build(wtxn)?
                 } else if writer.is_empty(wtxn)? {
                     break;
                 }
             } else {
-                let writer = arroy::Writer::new(self.angular_db(), index, dimension);
+                let writer = arroy::Writer::new(db, index, dimension);
                 // If we are quantizing the databases, we can't know from meilisearch
                 // if the db was empty but still contained the wrong metadata, thus we need
                 // to quantize everything and can't stop early. Since this operation can
                 // only happens once in the life of an embedder, it's not very performances
                 // sensitive.
-                if quantizing && !self.quantized {
-                    let writer = writer.prepare_changing_distance::<BinaryQuantizedCosine>(wtxn)?;
+                if quantizing {
+                    let writer = writer.prepare_changing_distance::<Q>(wtxn)?;
                     writer
                         .builder(rng)
                         .available_memory(arroy_memory.unwrap_or(usize::MAX))
@@ -131,6 +305,45 @@ impl ArroyWrapper {
         Ok(())
     }
 
+    /// Stores the `int8` quantized version of all the embeddings stored in arroy.
+    fn store_scalar_quantized<D: arroy::Distance>(
+        &self,
+        wtxn: &mut RwTxn,
+        db: arroy::Database<D>,
+        dimension: usize,
+    ) -> Result<(), arroy::Error> {
+        for index in arroy_db_range_for_embedder(self.embedder_index) {
+            let item_ids = match arroy::Reader::open(wtxn, index, db) {
+                Ok(reader) => reader.item_ids().clone(),
+                Err(arroy::Error::MissingMetadata(_)) => break,
+                Err(e) => return Err(e),
+            };
+            if item_ids.is_empty() {
+                break;
+            }
+            let writer = arroy::Writer::new(db, index, dimension);
+            for item_id in item_ids {
+                if let Some(vector) = writer.item_vector(wtxn, item_id)? {
+                    self.scalar_database.put(wtxn, &(index, item_id), &vector)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all the `int8` quantized embeddings of this embedder.
+    fn clear_scalar_quantized(&self, wtxn: &mut RwTxn) -> Result<(), arroy::Error> {
+        let mut iter = self
+            .scalar_database
+            .remap_types::<Bytes, DecodeIgnore>()
+            .prefix_iter_mut(wtxn, &[self.embedder_index])?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the LMDB database.
+            unsafe { iter.del_current()? };
+        }
+        Ok(())
+    }
+
     /// Overwrite all the embeddings associated with the index and item ID.
     /// /!\ It won't remove embeddings after the last passed embedding, which can leave stale embeddings.
     ///     You should call `del_items` on the `item_id` before calling this method.
@@ -140,17 +353,31 @@ impl ArroyWrapper {
         wtxn: &mut RwTxn,
         item_id: arroy::ItemId,
         embeddings: &Embeddings<f32>,
+    ) -> Result<(), arroy::Error> {
+        if let Some(store) = &self.sparse_store {
+            // the sparse embeddings of a document are merged in a single one
+            let vector = SparseVector::unpack(embeddings.as_inner());
+            return Ok(store.put(wtxn, item_id, &vector)?);
+        }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._add_items(wtxn, self.db::<D>(), item_id, embeddings)
+        })
+    }
+
+    fn _add_items<D: arroy::Distance>(
+        &self,
+        wtxn: &mut RwTxn,
+        db: arroy::Database<D>,
+        item_id: arroy::ItemId,
+        embeddings: &Embeddings<f32>,
     ) -> Result<(), arroy::Error> {
         let dimension = embeddings.dimension();
         for (index, vector) in
             arroy_db_range_for_embedder(self.embedder_index).zip(embeddings.iter())
         {
-            if self.quantized {
-                arroy::Writer::new(self.quantized_db(), index, dimension)
-                    .add_item(wtxn, item_id, vector)?
-            } else {
-                arroy::Writer::new(self.angular_db(), index, dimension)
-                    .add_item(wtxn, item_id, vector)?
+            arroy::Writer::new(db, index, dimension).add_item(wtxn, item_id, vector)?;
+            if self.scalar_quantized() {
+                self.scalar_database.put(wtxn, &(index, item_id), vector)?;
             }
         }
         Ok(())
@@ -163,11 +390,15 @@ impl ArroyWrapper {
         item_id: arroy::ItemId,
         vector: &[f32],
     ) -> Result<(), arroy::Error> {
-        if self.quantized {
-            self._add_item(wtxn, self.quantized_db(), item_id, vector)
-        } else {
-            self._add_item(wtxn, self.angular_db(), item_id, vector)
+        if let Some(store) = &self.sparse_store {
+            let existing = store.get(wtxn, item_id)?.unwrap_or_default();
+            let vector =
+                SparseVector::new(existing.iter().chain(SparseVector::unpack(vector).iter()));
+            return Ok(store.put(wtxn, item_id, &vector)?);
         }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._add_item(wtxn, self.db::<D>(), item_id, vector)
+        })
     }
 
     fn _add_item<D: arroy::Distance>(
@@ -183,6 +414,9 @@ impl ArroyWrapper {
             let writer = arroy::Writer::new(db, index, dimension);
             if !writer.contains_item(wtxn, item_id)? {
                 writer.add_item(wtxn, item_id, vector)?;
+                if self.scalar_quantized() {
+                    self.scalar_database.put(wtxn, &(index, item_id), vector)?;
+                }
                 break;
             }
         }
@@ -195,18 +429,30 @@ impl ArroyWrapper {
         wtxn: &mut RwTxn,
         dimension: usize,
         item_id: arroy::ItemId,
+    ) -> Result<(), arroy::Error> {
+        if let Some(store) = &self.sparse_store {
+            store.delete(wtxn, item_id)?;
+            return Ok(());
+        }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._del_items(wtxn, self.db::<D>(), dimension, item_id)
+        })
+    }
+
+    fn _del_items<D: arroy::Distance>(
+        &self,
+        wtxn: &mut RwTxn,
+        db: arroy::Database<D>,
+        dimension: usize,
+        item_id: arroy::ItemId,
     ) -> Result<(), arroy::Error> {
         for index in arroy_db_range_for_embedder(self.embedder_index) {
-            if self.quantized {
-                let writer = arroy::Writer::new(self.quantized_db(), index, dimension);
-                if !writer.del_item(wtxn, item_id)? {
-                    break;
-                }
-            } else {
-                let writer = arroy::Writer::new(self.angular_db(), index, dimension);
-                if !writer.del_item(wtxn, item_id)? {
-                    break;
-                }
+            let writer = arroy::Writer::new(db, index, dimension);
+            if !writer.del_item(wtxn, item_id)? {
+                break;
+            }
+            if self.scalar_quantized() {
+                self.scalar_database.delete(wtxn, &(index, item_id))?;
             }
         }
 
@@ -220,11 +466,15 @@ impl ArroyWrapper {
         item_id: arroy::ItemId,
         vector: &[f32],
     ) -> Result<bool, arroy::Error> {
-        if self.quantized {
-            self._del_item(wtxn, self.quantized_db(), item_id, vector)
-        } else {
-            self._del_item(wtxn, self.angular_db(), item_id, vector)
+        if let Some(store) = &self.sparse_store {
+            if store.get(wtxn, item_id)? != Some(SparseVector::unpack(vector)) {
+                return Ok(false);
+            }
+            return Ok(store.delete(wtxn, item_id)?);
         }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._del_item(wtxn, self.db::<D>(), item_id, vector)
+        })
     }
 
     fn _del_item<D: arroy::Distance>(
@@ -236,6 +486,10 @@ impl ArroyWrapper {
     ) -> Result<bool, arroy::Error> {
         let dimension = vector.len();
         let mut deleted_index = None;
+        // the arroy embeddings are binary quantized, so we compare the `int8` embeddings instead
+        let scalar_quantized_vector =
+            self.scalar_quantized().then(|| scalar_quantized::quantize(vector));
+        let scalar_database = self.scalar_database.remap_data_type::<Bytes>();
 
         for index in arroy_db_range_for_embedder(self.embedder_index) {
             let writer = arroy::Writer::new(db, index, dimension);
@@ -243,8 +497,17 @@ impl ArroyWrapper {
                 // uses invariant: vectors are packed in the first writers.
                 break;
             };
-            if candidate == vector {
+            let is_same_vector = match &scalar_quantized_vector {
+                Some(quantized) => {
+                    scalar_database.get(wtxn, &(index, item_id))? == Some(quantized.as_slice())
+                }
+                None => candidate == vector,
+            };
+            if is_same_vector {
                 writer.del_item(wtxn, item_id)?;
+                if self.scalar_quantized() {
+                    scalar_database.delete(wtxn, &(index, item_id))?;
+                }
                 deleted_index = Some(index);
             }
         }
@@ -266,26 +529,45 @@ impl ArroyWrapper {
                 writer.del_item(wtxn, item_id)?;
                 let writer = arroy::Writer::new(db, deleted_index, dimension);
                 writer.add_item(wtxn, item_id, &vector)?;
+                if self.scalar_quantized() {
+                    let quantized = scalar_database
+                        .get(wtxn, &(last_index, item_id))?
+                        .map(|quantized| quantized.to_vec());
+                    if let Some(quantized) = quantized {
+                        scalar_database.delete(wtxn, &(last_index, item_id))?;
+                        scalar_database.put(wtxn, &(deleted_index, item_id), &quantized)?;
+                    }
+                }
             }
         }
         Ok(deleted_index.is_some())
     }
 
     pub fn clear(&self, wtxn: &mut RwTxn, dimension: usize) -> Result<(), arroy::Error> {
+        if let Some(store) = &self.sparse_store {
+            return Ok(store.clear(wtxn)?);
+        }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._clear(wtxn, self.db::<D>(), dimension)
+        })?;
+        if self.scalar_quantized() {
+            self.clear_scalar_quantized(wtxn)?;
+        }
+        Ok(())
+    }
+
+    fn _clear<D: arroy::Distance>(
+        &self,
+        wtxn: &mut RwTxn,
+        db: arroy::Database<D>,
+        dimension: usize,
+    ) -> Result<(), arroy::Error> {
         for index in arroy_db_range_for_embedder(self.embedder_index) {
-            if self.quantized {
-                let writer = arroy::Writer::new(self.quantized_db(), index, dimension);
-                if writer.is_empty(wtxn)? {
-                    break;
-                }
-                writer.clear(wtxn)?;
-            } else {
-                let writer = arroy::Writer::new(self.angular_db(), index, dimension);
-                if writer.is_empty(wtxn)? {
-                    break;
-                }
-                writer.clear(wtxn)?;
+            let writer = arroy::Writer::new(db, index, dimension);
+            if writer.is_empty(wtxn)? {
+                break;
             }
+            writer.clear(wtxn)?;
         }
         Ok(())
     }
@@ -295,23 +577,29 @@ impl ArroyWrapper {
         rtxn: &RoTxn,
         dimension: usize,
         item: arroy::ItemId,
+    ) -> Result<bool, arroy::Error> {
+        if let Some(store) = &self.sparse_store {
+            return Ok(store.get(rtxn, item)?.is_some());
+        }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._contains_item(rtxn, self.db::<D>(), dimension, item)
+        })
+    }
+
+    fn _contains_item<D: arroy::Distance>(
+        &self,
+        rtxn: &RoTxn,
+        db: arroy::Database<D>,
+        dimension: usize,
+        item: arroy::ItemId,
     ) -> Result<bool, arroy::Error> {
         for index in arroy_db_range_for_embedder(self.embedder_index) {
-            let contains = if self.quantized {
-                let writer = arroy::Writer::new(self.quantized_db(), index, dimension);
-                if writer.is_empty(rtxn)? {
-                    break;
-                }
-                writer.contains_item(rtxn, item)?
-            } else {
-                let writer = arroy::Writer::new(self.angular_db(), index, dimension);
-                if writer.is_empty(rtxn)? {
-                    break;
-                }
-                writer.contains_item(rtxn, item)?
-            };
-            if contains {
-                return Ok(contains);
+            let writer = arroy::Writer::new(db, index, dimension);
+            if writer.is_empty(rtxn)? {
+                break;
+            }
+            if writer.contains_item(rtxn, item)? {
+                return Ok(true);
             }
         }
         Ok(false)
@@ -324,11 +612,27 @@ impl ArroyWrapper {
         limit: usize,
         filter: Option<&RoaringBitmap>,
     ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
-        if self.quantized {
-            self._nns_by_item(rtxn, self.quantized_db(), item, limit, filter)
-        } else {
-            self._nns_by_item(rtxn, self.angular_db(), item, limit, filter)
+        if let Some(store) = &self.sparse_store {
+            let Some(vector) = store.get(rtxn, item)? else {
+                return Ok(Vec::new());
+            };
+            return Ok(store
+                .search(rtxn, &vector, limit, filter)?
+                .into_iter()
+                .map(|(item_id, score)| (item_id, -score))
+                .collect());
+        }
+        if self.scalar_quantized() {
+            let mut results = Vec::new();
+            for vector in self.item_vectors(rtxn, item)? {
+                results.append(&mut self.nns_by_vector(rtxn, &vector, limit, filter)?);
+            }
+            results.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+            return Ok(results);
         }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._nns_by_item(rtxn, self.db::<D>(), item, limit, filter)
+        })
     }
 
     fn _nns_by_item<D: arroy::Distance>(
@@ -365,11 +669,45 @@ impl ArroyWrapper {
         limit: usize,
         filter: Option<&RoaringBitmap>,
     ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
-        if self.quantized {
-            self._nns_by_vector(rtxn, self.quantized_db(), vector, limit, filter)
-        } else {
-            self._nns_by_vector(rtxn, self.angular_db(), vector, limit, filter)
+        Ok(self
+            .nns_by_vector_with_positions(rtxn, vector, limit, filter)?
+            .into_iter()
+            .map(|(item_id, distance, _position)| (item_id, distance))
+            .collect())
+    }
+
+    /// Like [`Self::nns_by_vector`], but also returns the position of the matching embedding
+    /// among the embeddings of the item, e.g. the chunk of the document that matched.
+    pub fn nns_by_vector_with_positions(
+        &self,
+        rtxn: &RoTxn,
+        vector: &[f32],
+        limit: usize,
+        filter: Option<&RoaringBitmap>,
+    ) -> Result<Vec<(ItemId, f32, u8)>, arroy::Error> {
+        if let Some(store) = &self.sparse_store {
+            // the distance of the dot product is the opposite of the score, like in arroy
+            return Ok(store
+                .search(rtxn, &SparseVector::unpack(vector), limit, filter)?
+                .into_iter()
+                .map(|(item_id, score)| (item_id, -score, 0))
+                .collect());
+        }
+        if self.scalar_quantized() {
+            let candidates = with_arroy_distance!(self.distance, true, |D| {
+                self._nns_by_vector(
+                    rtxn,
+                    self.db::<D>(),
+                    vector,
+                    limit * SCALAR_QUANTIZED_OVERSAMPLING,
+                    filter,
+                )
+            })?;
+            return self.rescore_scalar_quantized(rtxn, vector, candidates, limit);
         }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._nns_by_vector(rtxn, self.db::<D>(), vector, limit, filter)
+        })
     }
 
     fn _nns_by_vector<D: arroy::Distance>(
@@ -379,52 +717,144 @@ impl ArroyWrapper {
         vector: &[f32],
         limit: usize,
         filter: Option<&RoaringBitmap>,
-    ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
+    ) -> Result<Vec<(ItemId, f32, u8)>, arroy::Error> {
         let mut results = Vec::new();
 
-        for reader in self.readers(rtxn, db) {
+        // the readers are opened in the order of the positions of the embeddings
+        for (position, reader) in (0..=u8::MAX).zip(self.readers(rtxn, db)) {
             let reader = reader?;
             let mut searcher = reader.nns(limit);
             if let Some(filter) = filter {
                 searcher.candidates(filter);
             }
 
-            results.append(&mut searcher.by_vector(rtxn, vector)?);
+            results.extend(
+                searcher
+                    .by_vector(rtxn, vector)?
+                    .into_iter()
+                    .map(|(item_id, distance)| (item_id, distance, position)),
+            );
         }
 
-        results.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+        results.sort_unstable_by_key(|(_, distance, _)| OrderedFloat(*distance));
 
         Ok(results)
     }
 
-    pub fn item_vectors(&self, rtxn: &RoTxn, item_id: u32) -> Result<Vec<Vec<f32>>, arroy::Error> {
-        let mut vectors = Vec::new();
-
-        if self.quantized {
-            for reader in self.readers(rtxn, self.quantized_db()) {
-                if let Some(vec) = reader?.item_vector(rtxn, item_id)? {
-                    vectors.push(vec);
-                } else {
+    /// Computes the distances between the vector and the `int8` embeddings of the candidates,
+    /// which are more precise than the distances between the binary quantized embeddings.
+    fn rescore_scalar_quantized(
+        &self,
+        rtxn: &RoTxn,
+        vector: &[f32],
+        candidates: Vec<(ItemId, f32, u8)>,
+        limit: usize,
+    ) -> Result<Vec<(ItemId, f32, u8)>, arroy::Error> {
+        let mut seen = RoaringBitmap::new();
+        let mut results = Vec::new();
+        for (item_id, _, _) in candidates {
+            // a document with several embeddings can be returned several times by arroy
+            if !seen.insert(item_id) {
+                continue;
+            }
+            for (position, index) in
+                (0..=u8::MAX).zip(arroy_db_range_for_embedder(self.embedder_index))
+            {
+                let Some(candidate) = self.scalar_database.get(rtxn, &(index, item_id))? else {
                     break;
-                }
+                };
+                results.push((item_id, self.distance.distance(vector, &candidate), position));
             }
-        } else {
-            for reader in self.readers(rtxn, self.angular_db()) {
-                if let Some(vec) = reader?.item_vector(rtxn, item_id)? {
-                    vectors.push(vec);
-                } else {
+        }
+        results.sort_unstable_by_key(|(_, distance, _)| OrderedFloat(*distance));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Scores the items with MaxSim against a late interaction query,
+    /// made of several embeddings of `dimensions` packed together.
+    ///
+    /// The candidates are the nearest neighbors of each embedding of the query.
+    /// The distance of a candidate is the mean, over the embeddings of the query,
+    /// of the distance to its closest embedding of the candidate.
+    pub fn nns_by_late_interaction_vector(
+        &self,
+        rtxn: &RoTxn,
+        query: &[f32],
+        dimensions: usize,
+        limit: usize,
+        filter: Option<&RoaringBitmap>,
+    ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
+        let query: Vec<&[f32]> = query.chunks_exact(dimensions).collect();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut candidates = RoaringBitmap::new();
+        for vector in &query {
+            for (item_id, _distance) in self.nns_by_vector(rtxn, vector, limit, filter)? {
+                candidates.insert(item_id);
+            }
+        }
+
+        let mut results = Vec::with_capacity(candidates.len() as usize);
+        for item_id in candidates {
+            let item_vectors = self.item_vectors(rtxn, item_id)?;
+            let total_distance: f32 = query
+                .iter()
+                .filter_map(|vector| {
+                    item_vectors
+                        .iter()
+                        .map(|item_vector| {
+                            OrderedFloat(self.distance.distance(vector, item_vector))
+                        })
+                        .min()
+                })
+                .map(|OrderedFloat(distance)| distance)
+                .sum();
+            results.push((item_id, total_distance / query.len() as f32));
+        }
+        results.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    pub fn item_vectors(&self, rtxn: &RoTxn, item_id: u32) -> Result<Vec<Vec<f32>>, arroy::Error> {
+        if let Some(store) = &self.sparse_store {
+            return Ok(store.get(rtxn, item_id)?.map(|vector| vector.pack()).into_iter().collect());
+        }
+        if self.scalar_quantized() {
+            let mut vectors = Vec::new();
+            for index in arroy_db_range_for_embedder(self.embedder_index) {
+                let Some(vector) = self.scalar_database.get(rtxn, &(index, item_id))? else {
                     break;
-                }
+                };
+                vectors.push(vector);
             }
+            return Ok(vectors);
         }
-        Ok(vectors)
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._item_vectors(rtxn, self.db::<D>(), item_id)
+        })
     }
 
-    fn angular_db(&self) -> arroy::Database<Cosine> {
-        self.database.remap_data_type()
+    fn _item_vectors<D: arroy::Distance>(
+        &self,
+        rtxn: &RoTxn,
+        db: arroy::Database<D>,
+        item_id: u32,
+    ) -> Result<Vec<Vec<f32>>, arroy::Error> {
+        let mut vectors = Vec::new();
+        for reader in self.readers(rtxn, db) {
+            if let Some(vec) = reader?.item_vector(rtxn, item_id)? {
+                vectors.push(vec);
+            } else {
+                break;
+            }
+        }
+        Ok(vectors)
     }
 
-    fn quantized_db(&self) -> arroy::Database<BinaryQuantizedCosine> {
+    fn db<D: arroy::Distance>(&self) -> arroy::Database<D> {
         self.database.remap_data_type()
     }
 
@@ -433,26 +863,49 @@ impl ArroyWrapper {
         rtxn: &RoTxn,
         stats: &mut ArroyStats,
     ) -> Result<(), arroy::Error> {
-        if self.quantized {
-            for reader in self.readers(rtxn, self.quantized_db()) {
-                let reader = reader?;
-                let documents = reader.item_ids();
-                if documents.is_empty() {
-                    break;
-                }
-                stats.documents |= documents;
-                stats.number_of_embeddings += documents.len();
-            }
-        } else {
-            for reader in self.readers(rtxn, self.angular_db()) {
-                let reader = reader?;
-                let documents = reader.item_ids();
-                if documents.is_empty() {
-                    break;
-                }
-                stats.documents |= documents;
-                stats.number_of_embeddings += documents.len();
+        if let Some(store) = &self.sparse_store {
+            return Ok(store.aggregate_stats(rtxn, stats)?);
+        }
+        with_arroy_distance!(self.distance, self.arroy_quantized(), |D| {
+            self._aggregate_stats(rtxn, self.db::<D>(), stats)
+        })
+    }
+
+    /// The size taken by the embeddings of this embedder in the database, in bytes.
+    pub fn storage_size(&self, rtxn: &RoTxn) -> Result<u64, arroy::Error> {
+        if let Some(store) = &self.sparse_store {
+            return Ok(store.storage_size(rtxn)?);
+        }
+        // the keys of both databases are prefixed by the embedder index
+        let prefix = [self.embedder_index];
+        let mut size = 0;
+        for result in self.database.remap_types::<Bytes, Bytes>().prefix_iter(rtxn, &prefix)? {
+            let (key, value) = result?;
+            size += (key.len() + value.len()) as u64;
+        }
+        for result in
+            self.scalar_database.remap_types::<Bytes, Bytes>().prefix_iter(rtxn, &prefix)?
+        {
+            let (key, value) = result?;
+            size += (key.len() + value.len()) as u64;
+        }
+        Ok(size)
+    }
+
+    fn _aggregate_stats<D: arroy::Distance>(
+        &self,
+        rtxn: &RoTxn,
+        db: arroy::Database<D>,
+        stats: &mut ArroyStats,
+    ) -> Result<(), arroy::Error> {
+        for reader in self.readers(rtxn, db) {
+            let reader = reader?;
+            let documents = reader.item_ids();
+            if documents.is_empty() {
+                break;
             }
+            stats.documents |= documents;
+            stats.number_of_embeddings += documents.len();
         }
 
         Ok(())
@@ -555,34 +1008,50 @@ pub enum Embedder {
     Rest(rest::Embedder),
     /// An embedder composed of an embedder at search time and an embedder at indexing time.
     Composite(composite::Embedder),
+    /// An embedder based on running local ONNX models, read from a directory.
+    Onnx(onnx::Embedder),
+    /// An embedder producing sparse embeddings, provided by the user or by a REST embedding server.
+    Sparse(sparse::Embedder),
 }
 
+/// The embeddings of the search queries kept in memory, and of all the texts kept on disk when
+/// the [`PersistentEmbeddingCache`] is enabled.
 #[derive(Debug)]
-struct EmbeddingCache {
+pub struct EmbeddingCache {
     data: Option<Mutex<lru::LruCache<String, Embedding>>>,
+    persistent: Option<EmbedderCache>,
 }
 
 impl EmbeddingCache {
     const MAX_TEXT_LEN: usize = 2000;
 
-    pub fn new(cap: usize) -> Self {
+    pub fn new(cap: usize, persistent: Option<EmbedderCache>) -> Self {
         let data = NonZeroUsize::new(cap).map(lru::LruCache::new).map(Mutex::new);
-        Self { data }
+        Self { data, persistent }
     }
 
     /// Get the embedding corresponding to `text`, if any is present in the cache.
     pub fn get(&self, text: &str) -> Option<Embedding> {
-        let data = self.data.as_ref()?;
-        if text.len() > Self::MAX_TEXT_LEN {
-            return None;
+        if let Some(data) = self.data.as_ref().filter(|_| text.len() <= Self::MAX_TEXT_LEN) {
+            let mut cache = data.lock().unwrap();
+            if let Some(embedding) = cache.get(text) {
+                return Some(embedding.clone());
+            }
         }
-        let mut cache = data.lock().unwrap();
 
-        cache.get(text).cloned()
+        let embedding = self.persistent.as_ref()?.get_many(&[text]).pop().flatten()?;
+        if let Some(data) = self.data.as_ref().filter(|_| text.len() <= Self::MAX_TEXT_LEN) {
+            let mut cache = data.lock().unwrap();
+            cache.put(text.to_owned(), embedding.clone());
+        }
+        Some(embedding)
     }
 
     /// Puts a new embedding for the specified `text`
     pub fn put(&self, text: String, embedding: Embedding) {
+        if let Some(persistent) = &self.persistent {
+            persistent.put_many(&[(&text, &embedding)]);
+        }
         let Some(data) = self.data.as_ref() else {
             return;
         };
@@ -595,6 +1064,35 @@ impl EmbeddingCache {
 
         cache.put(text, embedding);
     }
+
+    /// Embeds with `embed` the texts that are missing from the persistent cache, then caches them.
+    fn embed_missing(
+        persistent: &EmbedderCache,
+        texts: &[&str],
+        embed: impl FnOnce(&[&str]) -> std::result::Result<Vec<Embedding>, EmbedError>,
+    ) -> std::result::Result<Vec<Embedding>, EmbedError> {
+        let cached = persistent.get_many(texts);
+        let missing: Vec<&str> = texts
+            .iter()
+            .zip(&cached)
+            .filter_map(|(text, cached)| cached.is_none().then_some(*text))
+            .collect();
+        if missing.is_empty() {
+            return Ok(cached.into_iter().flatten().collect());
+        }
+
+        let embeddings = embed(&missing)?;
+        let entries: Vec<_> = missing.iter().copied().zip(&embeddings).collect();
+        persistent.put_many(&entries);
+
+        let mut embeddings = embeddings.into_iter();
+        cached
+            .into_iter()
+            .map(|cached| {
+                cached.or_else(|| embeddings.next()).ok_or_else(EmbedError::missing_embedding)
+            })
+            .collect()
+    }
 }
 
 /// Configuration for an embedder.
@@ -606,12 +1104,156 @@ pub struct EmbeddingConfig {
     pub prompt: PromptData,
     /// If this embedder is binary quantized
     pub quantized: Option<bool>,
-    // TODO: add metrics and anything needed
+    /// The quantization of this embedder, takes precedence over `quantized` when set
+    pub quantization: Option<VectorQuantization>,
+    /// The distance used to compare the embeddings of this embedder
+    pub distance: Option<VectorDistance>,
 }
 
 impl EmbeddingConfig {
-    pub fn quantized(&self) -> bool {
-        self.quantized.unwrap_or_default()
+    pub fn quantization(&self) -> VectorQuantization {
+        VectorQuantization::from_options(self.quantization, self.quantized)
+    }
+
+    pub fn distance(&self) -> VectorDistance {
+        // sparse embeddings are always compared with the dot product
+        if self.is_sparse() {
+            return VectorDistance::DotProduct;
+        }
+        self.distance.unwrap_or_default()
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.embedder_options, EmbedderOptions::Sparse(_))
+    }
+}
+
+/// The metric used to compare the embeddings of an embedder.
+#[derive(
+    Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub enum VectorDistance {
+    /// The cosine of the angle between the embeddings, regardless of their norm.
+    #[default]
+    Cosine,
+    /// The inner product of the embeddings, for models trained for maximum inner product search.
+    DotProduct,
+    /// The euclidean (L2) distance between the embeddings.
+    Euclidean,
+}
+
+impl VectorDistance {
+    /// Converts a distance returned by arroy for this metric to a similarity in the `[0, 1]` range,
+    /// the most similar embeddings having the highest similarity.
+    pub fn similarity(&self, distance: f32) -> f32 {
+        match self {
+            // the cosine distance is in the `[0, 1]` range
+            VectorDistance::Cosine => 1.0 - distance,
+            // the distance is the opposite of the inner product, which is unbounded.
+            // It is mapped monotonically to `]0, 1[`, orthogonal embeddings having a similarity of 0.5.
+            VectorDistance::DotProduct => {
+                let inner_product = -distance;
+                if inner_product < 0.0 {
+                    0.5 / (1.0 - inner_product)
+                } else {
+                    1.0 - 0.5 / (1.0 + inner_product)
+                }
+            }
+            // the euclidean distance is in the `[0, +inf[` range
+            VectorDistance::Euclidean => 1.0 / (1.0 + distance),
+        }
+    }
+
+    /// Computes the distance between two embeddings, like arroy does for this metric.
+    pub fn distance(&self, left: &[f32], right: &[f32]) -> f32 {
+        fn dot_product(left: &[f32], right: &[f32]) -> f32 {
+            left.iter().zip(right).map(|(l, r)| l * r).sum()
+        }
+
+        match self {
+            VectorDistance::Cosine => {
+                let norms = (dot_product(left, left) * dot_product(right, right)).sqrt();
+                if norms > f32::EPSILON {
+                    let cos = (dot_product(left, right) / norms).clamp(-1.0, 1.0);
+                    (1.0 - cos) / 2.0
+                } else {
+                    0.0
+                }
+            }
+            VectorDistance::DotProduct => -dot_product(left, right),
+            VectorDistance::Euclidean => {
+                left.iter().zip(right).map(|(l, r)| (l - r) * (l - r)).sum::<f32>().sqrt()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for VectorDistance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            VectorDistance::Cosine => "cosine",
+            VectorDistance::DotProduct => "dotProduct",
+            VectorDistance::Euclidean => "euclidean",
+        };
+        f.write_str(s)
+    }
+}
+
+/// How the embeddings of an embedder are compressed in the vector store.
+#[derive(
+    Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub enum VectorQuantization {
+    /// The embeddings are stored as 32 bits floats.
+    #[default]
+    None,
+    /// Each dimension of the embeddings is reduced to its sign.
+    Binary,
+    /// Each dimension of the embeddings is scaled to a 8 bits integer.
+    ///
+    /// The search is performed on the binary quantized embeddings,
+    /// and the results are rescored using the 8 bits embeddings.
+    Int8,
+}
+
+impl VectorQuantization {
+    /// The quantization resulting from the `quantization` and `binaryQuantized` options,
+    /// `quantization` taking precedence when both are set.
+    pub fn from_options(quantization: Option<Self>, binary_quantized: Option<bool>) -> Self {
+        match (quantization, binary_quantized) {
+            (Some(quantization), _) => quantization,
+            (None, Some(true)) => VectorQuantization::Binary,
+            (None, _) => VectorQuantization::None,
+        }
+    }
+
+    /// Whether the embeddings quantized with `self` can be converted to `other`.
+    ///
+    /// Quantization is lossy, so embeddings can only be converted to a lossier quantization.
+    pub fn can_be_converted_to(self, other: Self) -> bool {
+        fn lossiness(quantization: VectorQuantization) -> u8 {
+            match quantization {
+                VectorQuantization::None => 0,
+                VectorQuantization::Int8 => 1,
+                VectorQuantization::Binary => 2,
+            }
+        }
+        lossiness(self) <= lossiness(other)
+    }
+}
+
+impl std::fmt::Display for VectorQuantization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            VectorQuantization::None => "none",
+            VectorQuantization::Binary => "binary",
+            VectorQuantization::Int8 => "int8",
+        };
+        f.write_str(s)
     }
 }
 
@@ -619,11 +1261,15 @@ impl EmbeddingConfig {
 ///
 /// Each configuration is mapped to a name.
 #[derive(Clone, Default)]
-pub struct EmbeddingConfigs(HashMap<String, (Arc<Embedder>, Arc<Prompt>, bool)>);
+pub struct EmbeddingConfigs(
+    HashMap<String, (Arc<Embedder>, Arc<Prompt>, VectorQuantization, VectorDistance)>,
+);
 
 impl EmbeddingConfigs {
     /// Create the map from its internal component.s
-    pub fn new(data: HashMap<String, (Arc<Embedder>, Arc<Prompt>, bool)>) -> Self {
+    pub fn new(
+        data: HashMap<String, (Arc<Embedder>, Arc<Prompt>, VectorQuantization, VectorDistance)>,
+    ) -> Self {
         Self(data)
     }
 
@@ -632,24 +1278,33 @@ impl EmbeddingConfigs {
     }
 
     /// Get an embedder configuration and template from its name.
-    pub fn get(&self, name: &str) -> Option<(Arc<Embedder>, Arc<Prompt>, bool)> {
+    pub fn get(
+        &self,
+        name: &str,
+    ) -> Option<(Arc<Embedder>, Arc<Prompt>, VectorQuantization, VectorDistance)> {
         self.0.get(name).cloned()
     }
 
-    pub fn inner_as_ref(&self) -> &HashMap<String, (Arc<Embedder>, Arc<Prompt>, bool)> {
+    pub fn inner_as_ref(
+        &self,
+    ) -> &HashMap<String, (Arc<Embedder>, Arc<Prompt>, VectorQuantization, VectorDistance)> {
         &self.0
     }
 
-    pub fn into_inner(self) -> HashMap<String, (Arc<Embedder>, Arc<Prompt>, bool)> {
+    pub fn into_inner(
+        self,
+    ) -> HashMap<String, (Arc<Embedder>, Arc<Prompt>, VectorQuantization, VectorDistance)> {
         self.0
     }
 }
 
 impl IntoIterator for EmbeddingConfigs {
-    type Item = (String, (Arc<Embedder>, Arc<Prompt>, bool));
+    type Item = (String, (Arc<Embedder>, Arc<Prompt>, VectorQuantization, VectorDistance));
 
-    type IntoIter =
-        std::collections::hash_map::IntoIter<String, (Arc<Embedder>, Arc<Prompt>, bool)>;
+    type IntoIter = std::collections::hash_map::IntoIter<
+        String,
+        (Arc<Embedder>, Arc<Prompt>, VectorQuantization, VectorDistance),
+    >;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
@@ -665,6 +1320,8 @@ pub enum EmbedderOptions {
     UserProvided(manual::EmbedderOptions),
     Rest(rest::EmbedderOptions),
     Composite(composite::EmbedderOptions),
+    Sparse(sparse::EmbedderOptions),
+    Onnx(onnx::EmbedderOptions),
 }
 
 impl Default for EmbedderOptions {
@@ -673,33 +1330,57 @@ impl Default for EmbedderOptions {
     }
 }
 
+impl EmbedderOptions {
+    /// The media sent along with the rendered documents, if the embedder is multimodal.
+    ///
+    /// Media files are read from `directory`.
+    pub fn document_media(&self, directory: Option<&Path>) -> Option<media::DocumentMedia> {
+        match self {
+            EmbedderOptions::Rest(options) => options.media.as_ref().map(|media| {
+                media::DocumentMedia::new(
+                    media.document_field.clone(),
+                    directory.map(Path::to_path_buf),
+                )
+            }),
+            _ => None,
+        }
+    }
+}
+
 impl Embedder {
     /// Spawns a new embedder built from its options.
+    ///
+    /// The embeddings are cached in `persistent_cache` when it is provided.
     pub fn new(
         options: EmbedderOptions,
         cache_cap: usize,
+        persistent_cache: Option<&PersistentEmbeddingCache>,
     ) -> std::result::Result<Self, NewEmbedderError> {
+        let persistent = persistent_cache.map(|cache| cache.for_embedder(&options));
+        let cache = || EmbeddingCache::new(cache_cap, persistent);
         Ok(match options {
             EmbedderOptions::HuggingFace(options) => {
-                Self::HuggingFace(hf::Embedder::new(options, cache_cap)?)
+                Self::HuggingFace(hf::Embedder::new(options, cache())?)
             }
             EmbedderOptions::OpenAi(options) => {
-                Self::OpenAi(openai::Embedder::new(options, cache_cap)?)
+                Self::OpenAi(openai::Embedder::new(options, cache())?)
             }
             EmbedderOptions::Ollama(options) => {
-                Self::Ollama(ollama::Embedder::new(options, cache_cap)?)
+                Self::Ollama(ollama::Embedder::new(options, cache())?)
             }
             EmbedderOptions::UserProvided(options) => {
                 Self::UserProvided(manual::Embedder::new(options))
             }
-            EmbedderOptions::Rest(options) => Self::Rest(rest::Embedder::new(
-                options,
-                cache_cap,
-                rest::ConfigurationSource::User,
-            )?),
+            EmbedderOptions::Rest(options) => {
+                Self::Rest(rest::Embedder::new(options, cache(), rest::ConfigurationSource::User)?)
+            }
             EmbedderOptions::Composite(options) => {
-                Self::Composite(composite::Embedder::new(options, cache_cap)?)
+                Self::Composite(composite::Embedder::new(options, cache_cap, persistent_cache)?)
+            }
+            EmbedderOptions::Sparse(options) => {
+                Self::Sparse(sparse::Embedder::new(options, cache())?)
             }
+            EmbedderOptions::Onnx(options) => Self::Onnx(onnx::Embedder::new(options, cache())?),
         })
     }
 
@@ -726,11 +1407,10 @@ impl Embedder {
                 embedder.embed(&[text], deadline)?.pop().ok_or_else(EmbedError::missing_embedding)
             }
             Embedder::UserProvided(embedder) => embedder.embed_one(text),
-            Embedder::Rest(embedder) => embedder
-                .embed_ref(&[text], deadline)?
-                .pop()
-                .ok_or_else(EmbedError::missing_embedding),
+            Embedder::Rest(embedder) => embedder.embed_search(text, deadline),
             Embedder::Composite(embedder) => embedder.search.embed_one(text, deadline),
+            Embedder::Sparse(embedder) => embedder.embed_search(text, deadline),
+            Embedder::Onnx(embedder) => embedder.embed_one(text),
         }?;
 
         if let Some(cache) = self.cache() {
@@ -748,6 +1428,18 @@ impl Embedder {
         text_chunks: Vec<Vec<String>>,
         threads: &ThreadPoolNoAbort,
     ) -> std::result::Result<Vec<Vec<Embedding>>, EmbedError> {
+        if let Some(persistent) = self.persistent_index_cache() {
+            let texts: Vec<&str> = text_chunks.iter().flatten().map(String::as_str).collect();
+            let mut embeddings = EmbeddingCache::embed_missing(persistent, &texts, |texts| {
+                self.embed_index_ref_uncached(texts, threads)
+            })?
+            .into_iter();
+            return Ok(text_chunks
+                .iter()
+                .map(|chunk| embeddings.by_ref().take(chunk.len()).collect())
+                .collect());
+        }
+
         match self {
             Embedder::HuggingFace(embedder) => embedder.embed_index(text_chunks),
             Embedder::OpenAi(embedder) => embedder.embed_index(text_chunks, threads),
@@ -755,6 +1447,8 @@ impl Embedder {
             Embedder::UserProvided(embedder) => embedder.embed_index(text_chunks),
             Embedder::Rest(embedder) => embedder.embed_index(text_chunks, threads),
             Embedder::Composite(embedder) => embedder.index.embed_index(text_chunks, threads),
+            Embedder::Sparse(embedder) => embedder.embed_index(text_chunks, threads),
+            Embedder::Onnx(embedder) => embedder.embed_index(text_chunks),
         }
     }
 
@@ -763,6 +1457,19 @@ impl Embedder {
         &self,
         texts: &[&str],
         threads: &ThreadPoolNoAbort,
+    ) -> std::result::Result<Vec<Embedding>, EmbedError> {
+        match self.persistent_index_cache() {
+            Some(persistent) => EmbeddingCache::embed_missing(persistent, texts, |texts| {
+                self.embed_index_ref_uncached(texts, threads)
+            }),
+            None => self.embed_index_ref_uncached(texts, threads),
+        }
+    }
+
+    fn embed_index_ref_uncached(
+        &self,
+        texts: &[&str],
+        threads: &ThreadPoolNoAbort,
     ) -> std::result::Result<Vec<Embedding>, EmbedError> {
         match self {
             Embedder::HuggingFace(embedder) => embedder.embed_index_ref(texts),
@@ -771,6 +1478,8 @@ impl Embedder {
             Embedder::UserProvided(embedder) => embedder.embed_index_ref(texts),
             Embedder::Rest(embedder) => embedder.embed_index_ref(texts, threads),
             Embedder::Composite(embedder) => embedder.index.embed_index_ref(texts, threads),
+            Embedder::Sparse(embedder) => embedder.embed_index_ref(texts, threads),
+            Embedder::Onnx(embedder) => embedder.embed_index_ref(texts),
         }
     }
 
@@ -783,6 +1492,8 @@ impl Embedder {
             Embedder::UserProvided(_) => 100,
             Embedder::Rest(embedder) => embedder.chunk_count_hint(),
             Embedder::Composite(embedder) => embedder.index.chunk_count_hint(),
+            Embedder::Sparse(embedder) => embedder.chunk_count_hint(),
+            Embedder::Onnx(embedder) => embedder.chunk_count_hint(),
         }
     }
 
@@ -795,10 +1506,14 @@ impl Embedder {
             Embedder::UserProvided(_) => 1,
             Embedder::Rest(embedder) => embedder.prompt_count_in_chunk_hint(),
             Embedder::Composite(embedder) => embedder.index.prompt_count_in_chunk_hint(),
+            Embedder::Sparse(embedder) => embedder.prompt_count_in_chunk_hint(),
+            Embedder::Onnx(embedder) => embedder.prompt_count_in_chunk_hint(),
         }
     }
 
     /// Indicates the dimensions of a single embedding produced by the embedder.
+    ///
+    /// Sparse embedders have no fixed dimensions, and return `0`.
     pub fn dimensions(&self) -> usize {
         match self {
             Embedder::HuggingFace(embedder) => embedder.dimensions(),
@@ -807,6 +1522,8 @@ impl Embedder {
             Embedder::UserProvided(embedder) => embedder.dimensions(),
             Embedder::Rest(embedder) => embedder.dimensions(),
             Embedder::Composite(embedder) => embedder.dimensions(),
+            Embedder::Sparse(_) => 0,
+            Embedder::Onnx(embedder) => embedder.dimensions(),
         }
     }
 
@@ -819,6 +1536,8 @@ impl Embedder {
             Embedder::UserProvided(embedder) => embedder.distribution(),
             Embedder::Rest(embedder) => embedder.distribution(),
             Embedder::Composite(embedder) => embedder.distribution(),
+            Embedder::Sparse(embedder) => embedder.distribution(),
+            Embedder::Onnx(embedder) => embedder.distribution(),
         }
     }
 
@@ -827,9 +1546,54 @@ impl Embedder {
             Embedder::HuggingFace(_)
             | Embedder::OpenAi(_)
             | Embedder::Ollama(_)
-            | Embedder::Rest(_) => true,
+            | Embedder::Rest(_)
+            | Embedder::Onnx(_) => true,
             Embedder::UserProvided(_) => false,
             Embedder::Composite(embedder) => embedder.index.uses_document_template(),
+            Embedder::Sparse(embedder) => embedder.uses_document_template(),
+        }
+    }
+
+    /// Whether the embeddings can only be provided by the user in the documents.
+    pub fn is_user_provided(&self) -> bool {
+        match self {
+            Embedder::UserProvided(_) => true,
+            Embedder::Sparse(embedder) => !embedder.uses_document_template(),
+            _ => false,
+        }
+    }
+
+    /// Whether the embedder produces sparse embeddings, see [`sparse`].
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, Embedder::Sparse(_))
+    }
+
+    /// Whether the embeddings are made of an embedding of [`Self::dimensions`] per token,
+    /// that are scored with MaxSim, see [`ArroyWrapper::nns_by_late_interaction_vector`].
+    pub fn is_late_interaction(&self) -> bool {
+        match self {
+            Embedder::Rest(embedder) => embedder.is_late_interaction(),
+            Embedder::HuggingFace(_)
+            | Embedder::OpenAi(_)
+            | Embedder::Ollama(_)
+            | Embedder::UserProvided(_)
+            | Embedder::Composite(_)
+            | Embedder::Sparse(_)
+            | Embedder::Onnx(_) => false,
+        }
+    }
+
+    /// Whether search queries can contain media, that are embedded along with the query.
+    pub fn supports_media(&self) -> bool {
+        match self {
+            Embedder::Rest(embedder) => embedder.supports_media(),
+            Embedder::HuggingFace(_)
+            | Embedder::OpenAi(_)
+            | Embedder::Ollama(_)
+            | Embedder::UserProvided(_)
+            | Embedder::Composite(_)
+            | Embedder::Sparse(_)
+            | Embedder::Onnx(_) => false,
         }
     }
 
@@ -841,8 +1605,19 @@ impl Embedder {
             Embedder::Ollama(embedder) => Some(embedder.cache()),
             Embedder::Rest(embedder) => Some(embedder.cache()),
             Embedder::Composite(embedder) => embedder.search.cache(),
+            Embedder::Sparse(embedder) => embedder.cache(),
+            Embedder::Onnx(embedder) => Some(embedder.cache()),
         }
     }
+
+    /// The persistent cache of the embeddings generated at indexing time, if enabled.
+    fn persistent_index_cache(&self) -> Option<&EmbedderCache> {
+        let cache = match self {
+            Embedder::Composite(embedder) => embedder.index.cache(),
+            _ => self.cache(),
+        };
+        cache?.persistent.as_ref()
+    }
 }
 
 /// Describes the mean and sigma of distribution of embedding similarity in the embedding space.
@@ -979,3 +1754,64 @@ pub fn arroy_db_range_for_embedder(embedder_id: u8) -> impl Iterator<Item = u16>
 
     (0..=u8::MAX).map(move |k| embedder_id | (k as u16))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn similarity(distance: VectorDistance, left: &[f32], right: &[f32]) -> f32 {
+        distance.similarity(distance.distance(left, right))
+    }
+
+    fn assert_close(left: f32, right: f32) {
+        assert!((left - right).abs() <= 1e-6, "{left} != {right}");
+    }
+
+    #[test]
+    fn cosine_similarity() {
+        let distance = VectorDistance::Cosine;
+        assert_close(similarity(distance, &[1.0, 0.0], &[2.0, 0.0]), 1.0);
+        assert_close(similarity(distance, &[1.0, 0.0], &[0.0, 3.0]), 0.5);
+        assert_close(similarity(distance, &[1.0, 0.0], &[-1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn dot_product_similarity() {
+        let distance = VectorDistance::DotProduct;
+        assert_close(similarity(distance, &[1.0, 0.0], &[0.0, 3.0]), 0.5);
+        assert_close(similarity(distance, &[1.0, 0.0], &[2.0, 0.0]), 1.0 - 0.5 / 3.0);
+        assert_close(similarity(distance, &[1.0, 0.0], &[-2.0, 0.0]), 0.5 / 3.0);
+
+        // the similarity is unbounded, but stays in the `]0, 1[` range
+        let high = similarity(distance, &[1000.0, 0.0], &[1000.0, 0.0]);
+        let low = similarity(distance, &[1000.0, 0.0], &[-1000.0, 0.0]);
+        assert!(high < 1.0 && high > similarity(distance, &[1.0, 0.0], &[2.0, 0.0]));
+        assert!(low > 0.0 && low < similarity(distance, &[1.0, 0.0], &[-2.0, 0.0]));
+    }
+
+    #[test]
+    fn euclidean_similarity() {
+        let distance = VectorDistance::Euclidean;
+        assert_close(similarity(distance, &[1.0, 2.0], &[1.0, 2.0]), 1.0);
+        assert_close(similarity(distance, &[0.0, 0.0], &[3.0, 4.0]), 1.0 / 6.0);
+    }
+
+    #[test]
+    fn ranking_depends_on_the_distance() {
+        let query = [1.0, 0.0];
+        let documents = [("same", [1.0, 0.0]), ("long", [3.0, 0.5]), ("orthogonal", [0.0, 1.0])];
+
+        let ranking = |distance: VectorDistance| {
+            let mut ranked: Vec<_> = documents
+                .iter()
+                .map(|(name, embedding)| (*name, similarity(distance, &query, embedding)))
+                .collect();
+            ranked.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+            ranked.into_iter().map(|(name, _)| name).collect::<Vec<_>>()
+        };
+
+        assert_eq!(ranking(VectorDistance::Cosine), ["same", "long", "orthogonal"]);
+        assert_eq!(ranking(VectorDistance::DotProduct), ["long", "same", "orthogonal"]);
+        assert_eq!(ranking(VectorDistance::Euclidean), ["same", "orthogonal", "long"]);
+    }
+}
@@ -0,0 +1,233 @@
+//! An embedder running an [ONNX](https://onnx.ai) model from a local directory, on CPU.
+//!
+//! The directory must contain the model as `model.onnx` and its tokenizer as `tokenizer.json`.
+//! When it contains the `1_Pooling/config.json` file of the Sentence Transformers models,
+//! it is used to determine the pooling of the embeddings of the tokens.
+//!
+//! Unlike the Hugging Face embedder, this embedder never fetches anything from the network.
+
+use std::path::PathBuf;
+
+use tokenizers::Tokenizer;
+use tract_onnx::prelude::{
+    Framework as _, InferenceModelExt as _, TValue, TVec, Tensor, TractResult, TypedModel,
+    TypedRunnableModel,
+};
+
+pub use super::error::{EmbedError, NewEmbedderError};
+use super::hf::{OverridePooling, Pooling, PoolingConfig};
+use super::{DistributionShift, Embedding, EmbeddingCache};
+
+/// The maximum number of tokens of a text, the remaining tokens are ignored.
+const MAX_TOKENS: usize = 512;
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct EmbedderOptions {
+    /// The path of the directory containing the model.
+    pub model: String,
+    pub distribution: Option<DistributionShift>,
+    pub pooling: OverridePooling,
+}
+
+/// The inputs of the model that the embedder knows how to fill.
+#[derive(Debug, Clone, Copy)]
+enum ModelInput {
+    InputIds,
+    AttentionMask,
+    TokenTypeIds,
+}
+
+/// Perform embedding of documents and queries
+pub struct Embedder {
+    model: TypedRunnableModel<TypedModel>,
+    inputs: Vec<ModelInput>,
+    tokenizer: Tokenizer,
+    options: EmbedderOptions,
+    dimensions: usize,
+    pooling: Pooling,
+    cache: EmbeddingCache,
+}
+
+impl std::fmt::Debug for Embedder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Embedder")
+            .field("model", &self.options.model)
+            .field("inputs", &self.inputs)
+            .field("tokenizer", &self.tokenizer)
+            .field("options", &self.options)
+            .field("pooling", &self.pooling)
+            .finish()
+    }
+}
+
+impl Embedder {
+    pub fn new(
+        options: EmbedderOptions,
+        cache: EmbeddingCache,
+    ) -> std::result::Result<Self, NewEmbedderError> {
+        let directory = PathBuf::from(&options.model);
+        let model_filename = directory.join("model.onnx");
+        let tokenizer_filename = directory.join("tokenizer.json");
+        let pooling_filename = directory.join("1_Pooling").join("config.json");
+
+        let mut pooling: Pooling = if pooling_filename.exists() {
+            let pooling = std::fs::read_to_string(&pooling_filename).map_err(|inner| {
+                NewEmbedderError::open_pooling_config(pooling_filename.clone(), inner)
+            })?;
+            let pooling: PoolingConfig = serde_json::from_str(&pooling).map_err(|inner| {
+                NewEmbedderError::deserialize_pooling_config(
+                    options.model.clone(),
+                    pooling_filename,
+                    inner,
+                )
+            })?;
+            pooling.into()
+        } else {
+            Pooling::default()
+        };
+        pooling.override_with(options.pooling);
+
+        let tokenizer = Tokenizer::from_file(&tokenizer_filename)
+            .map_err(|inner| NewEmbedderError::open_tokenizer(tokenizer_filename, inner))?;
+
+        let model = tract_onnx::onnx()
+            .model_for_path(&model_filename)
+            .and_then(|model| model.into_optimized())
+            .and_then(|model| model.into_runnable())
+            .map_err(|inner| NewEmbedderError::load_onnx_model(model_filename.clone(), inner))?;
+
+        let graph = model.model();
+        let input_outlets = graph
+            .input_outlets()
+            .map_err(|inner| NewEmbedderError::load_onnx_model(model_filename, inner))?;
+        let inputs = input_outlets
+            .iter()
+            .map(|outlet| match graph.node(outlet.node).name.as_str() {
+                "input_ids" => Ok(ModelInput::InputIds),
+                "attention_mask" => Ok(ModelInput::AttentionMask),
+                "token_type_ids" => Ok(ModelInput::TokenTypeIds),
+                name => Err(NewEmbedderError::onnx_unsupported_input(name.to_owned())),
+            })
+            .collect::<Result<_, _>>()?;
+
+        tracing::debug!(model = options.model, pooling=?pooling, inputs=?inputs, "model config");
+
+        let mut this = Self { model, inputs, tokenizer, options, dimensions: 0, pooling, cache };
+
+        let embeddings = this
+            .embed(vec!["test".into()])
+            .map_err(NewEmbedderError::could_not_determine_dimension)?;
+        this.dimensions = embeddings.first().unwrap().len();
+
+        Ok(this)
+    }
+
+    pub fn embed(&self, texts: Vec<String>) -> std::result::Result<Vec<Embedding>, EmbedError> {
+        texts.into_iter().map(|text| self.embed_one(&text)).collect()
+    }
+
+    pub fn embed_one(&self, text: &str) -> std::result::Result<Embedding, EmbedError> {
+        let tokens = self.tokenizer.encode(text, true).map_err(EmbedError::tokenize)?;
+        let token_ids = tokens.get_ids();
+        let token_ids =
+            if token_ids.len() > MAX_TOKENS { &token_ids[..MAX_TOKENS] } else { token_ids };
+        if token_ids.is_empty() {
+            return Err(EmbedError::missing_embedding());
+        }
+
+        let inputs: TVec<TValue> = self
+            .inputs
+            .iter()
+            .map(|input| {
+                let values: Vec<i64> = match input {
+                    ModelInput::InputIds => token_ids.iter().map(|&id| id.into()).collect(),
+                    ModelInput::AttentionMask => vec![1; token_ids.len()],
+                    ModelInput::TokenTypeIds => vec![0; token_ids.len()],
+                };
+                Tensor::from_shape(&[1, token_ids.len()], &values).map(TValue::from)
+            })
+            .collect::<TractResult<_>>()
+            .map_err(EmbedError::onnx_run)?;
+
+        let outputs = self.model.run(inputs).map_err(EmbedError::onnx_run)?;
+        let output = outputs
+            .first()
+            .ok_or_else(EmbedError::missing_embedding)?
+            .to_array_view::<f32>()
+            .map_err(EmbedError::onnx_run)?;
+
+        match *output.shape() {
+            // the model pools the embeddings of the tokens itself
+            [1, _dimensions] => Ok(output.iter().copied().collect()),
+            [1, token_count, dimensions] if token_count > 0 => {
+                let values: Vec<f32> = output.iter().copied().collect();
+                Ok(Self::pooling(values.chunks_exact(dimensions), self.pooling))
+            }
+            ref shape => Err(EmbedError::onnx_output_shape(shape.to_vec())),
+        }
+    }
+
+    /// Pools the embeddings of the tokens of a text in a single embedding.
+    ///
+    /// There must be at least one token.
+    fn pooling<'a>(
+        mut tokens: impl ExactSizeIterator<Item = &'a [f32]>,
+        pooling: Pooling,
+    ) -> Embedding {
+        let token_count = tokens.len() as f32;
+        let first = tokens.next().unwrap_or_default().to_vec();
+        match pooling {
+            Pooling::Cls => first,
+            Pooling::LastToken => tokens.last().map_or(first, <[f32]>::to_vec),
+            Pooling::Max => tokens.fold(first, |mut max, token| {
+                max.iter_mut().zip(token).for_each(|(max, value)| *max = max.max(*value));
+                max
+            }),
+            Pooling::Mean => {
+                Self::sum(first, tokens).into_iter().map(|v| v / token_count).collect()
+            }
+            Pooling::MeanSqrtLen => {
+                let divisor = token_count.sqrt();
+                Self::sum(first, tokens).into_iter().map(|v| v / divisor).collect()
+            }
+        }
+    }
+
+    fn sum<'a>(first: Embedding, tokens: impl Iterator<Item = &'a [f32]>) -> Embedding {
+        tokens.fold(first, |mut sum, token| {
+            sum.iter_mut().zip(token).for_each(|(sum, value)| *sum += value);
+            sum
+        })
+    }
+
+    pub fn embed_index(
+        &self,
+        text_chunks: Vec<Vec<String>>,
+    ) -> std::result::Result<Vec<Vec<Embedding>>, EmbedError> {
+        text_chunks.into_iter().map(|prompts| self.embed(prompts)).collect()
+    }
+
+    pub fn chunk_count_hint(&self) -> usize {
+        1
+    }
+
+    pub fn prompt_count_in_chunk_hint(&self) -> usize {
+        std::thread::available_parallelism().map(|x| x.get()).unwrap_or(8)
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn distribution(&self) -> Option<DistributionShift> {
+        self.options.distribution
+    }
+
+    pub(crate) fn embed_index_ref(&self, texts: &[&str]) -> Result<Vec<Embedding>, EmbedError> {
+        texts.iter().map(|text| self.embed_one(text)).collect()
+    }
+
+    pub(super) fn cache(&self) -> &EmbeddingCache {
+        &self.cache
+    }
+}
@@ -176,7 +176,7 @@ pub struct Embedder {
 }
 
 impl Embedder {
-    pub fn new(options: EmbedderOptions, cache_cap: usize) -> Result<Self, NewEmbedderError> {
+    pub fn new(options: EmbedderOptions, cache: EmbeddingCache) -> Result<Self, NewEmbedderError> {
         let mut inferred_api_key = Default::default();
         let api_key = options.api_key.as_ref().unwrap_or_else(|| {
             inferred_api_key = infer_api_key();
@@ -200,8 +200,10 @@ impl Embedder {
                     ]
                 }),
                 headers: Default::default(),
+                media: None,
+                late_interaction: None,
             },
-            cache_cap,
+            cache,
             super::rest::ConfigurationSource::OpenAi,
         )?;
 
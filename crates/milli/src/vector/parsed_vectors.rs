@@ -2,9 +2,11 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use deserr::{take_cf_content, DeserializeError, Deserr, Sequence};
 use obkv::KvReader;
+use serde::de::IgnoredAny;
 use serde_json::value::RawValue;
 use serde_json::{from_slice, Value};
 
+use super::sparse::{parse_token_id, SparseVector};
 use super::Embedding;
 use crate::index::IndexEmbeddingConfig;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd};
@@ -137,8 +139,25 @@ impl<'doc> serde::de::Visitor<'doc> for RawVectorsVisitor {
     {
         let mut regenerate = None;
         let mut embeddings = None;
+        let mut key = map.next_key::<&str>();
+        // a map of token ids to weights is the embedding of a sparse embedder
+        if let Ok(Some(first_key)) = key {
+            if parse_token_id(first_key).is_some() {
+                // must consume all entries or parsing fails
+                let consumed = map.next_value::<IgnoredAny>().and_then(|_| {
+                    while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+                    Ok(())
+                });
+                return Ok(match consumed {
+                    Ok(()) => Ok(RawVectorsVisitorValue::Implicit),
+                    Err(error) => {
+                        Err(RawVectorsError::DeserializeEmbeddings { error: error.to_string() })
+                    }
+                });
+            }
+        }
         loop {
-            match map.next_key::<&str>() {
+            match key {
                 Ok(Some("regenerate")) => {
                     let value: bool = match map.next_value() {
                         Ok(value) => value,
@@ -169,6 +188,7 @@ impl<'doc> serde::de::Visitor<'doc> for RawVectorsVisitor {
                     return Ok(Err(RawVectorsError::DeserializeKey { error: error.to_string() }))
                 }
             }
+            key = map.next_key::<&str>();
         }
         let Some(regenerate) = regenerate else {
             return Ok(Err(RawVectorsError::MissingRegenerate));
@@ -272,8 +292,15 @@ impl<E: DeserializeError> Deserr<E> for Vectors {
                     value, location,
                 )?))
             }
-            deserr::Value::Map(_) => {
-                Ok(Vectors::Explicit(ExplicitVectors::deserialize_from_value(value, location)?))
+            deserr::Value::Map(map) => {
+                let mut entries = deserr::Map::into_iter(map).peekable();
+                // a map of token ids to weights is the embedding of a sparse embedder
+                if entries.peek().is_some_and(|(key, _)| parse_token_id(key).is_some()) {
+                    let vector = SparseVector::deserialize_entries(entries, location)?;
+                    Ok(Vectors::ImplicitlyUserProvided(VectorOrArrayOfVectors::from_sparse(vector)))
+                } else {
+                    Ok(Vectors::Explicit(ExplicitVectors::deserialize_entries(entries, location)?))
+                }
             }
 
             value => Err(take_cf_content(E::error(
@@ -327,15 +354,72 @@ impl<'doc> RawVectors<'doc> {
     }
 }
 
-#[derive(serde::Serialize, Deserr, Debug)]
+#[derive(serde::Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ExplicitVectors {
     #[serde(default)]
-    #[deserr(default)]
     pub embeddings: Option<VectorOrArrayOfVectors>,
     pub regenerate: bool,
 }
 
+impl<E: DeserializeError> Deserr<E> for ExplicitVectors {
+    fn deserialize_from_value<V: deserr::IntoValue>(
+        value: deserr::Value<V>,
+        location: deserr::ValuePointerRef<'_>,
+    ) -> Result<Self, E> {
+        match value {
+            deserr::Value::Map(map) => {
+                Self::deserialize_entries(deserr::Map::into_iter(map), location)
+            }
+            value => Err(take_cf_content(E::error(
+                None,
+                deserr::ErrorKind::IncorrectValueKind {
+                    actual: value,
+                    accepted: &[deserr::ValueKind::Map],
+                },
+                location,
+            ))),
+        }
+    }
+}
+
+impl ExplicitVectors {
+    /// Deserializes the explicit vectors from the entries of a map, once it is known not to be
+    /// the embedding of a sparse embedder.
+    fn deserialize_entries<E: DeserializeError, V: deserr::IntoValue>(
+        entries: impl IntoIterator<Item = (String, V)>,
+        location: deserr::ValuePointerRef<'_>,
+    ) -> Result<Self, E> {
+        let mut embeddings = None;
+        let mut regenerate = None;
+        for (key, value) in entries {
+            match key.as_str() {
+                "embeddings" => {
+                    embeddings = Option::<VectorOrArrayOfVectors>::deserialize_from_value(
+                        value.into_value(),
+                        location.push_key("embeddings"),
+                    )?;
+                }
+                "regenerate" => {
+                    regenerate = Some(bool::deserialize_from_value(
+                        value.into_value(),
+                        location.push_key("regenerate"),
+                    )?);
+                }
+                _ => (),
+            }
+        }
+        let Some(regenerate) = regenerate else {
+            return Err(take_cf_content(E::error::<std::convert::Infallible>(
+                None,
+                deserr::ErrorKind::MissingField { field: "regenerate" },
+                location,
+            )));
+        };
+        Ok(Self { embeddings, regenerate })
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RawExplicitVectors<'doc> {
@@ -516,11 +600,29 @@ fn to_vector_map(
 }
 
 /// Represents either a vector or an array of multiple vectors.
-#[derive(serde::Serialize, Debug)]
-#[serde(transparent)]
+///
+/// The vectors of sparse embedders are maps of token ids to weights, packed as [`Embedding`]s.
+#[derive(Debug)]
 pub struct VectorOrArrayOfVectors {
-    #[serde(with = "either::serde_untagged_optional")]
     inner: Option<either::Either<Vec<Embedding>, Embedding>>,
+    sparse: bool,
+}
+
+impl serde::Serialize for VectorOrArrayOfVectors {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Serialize as _;
+        match (&self.inner, self.sparse) {
+            (None, _) => serializer.serialize_none(),
+            (Some(either::Either::Left(vectors)), false) => vectors.serialize(serializer),
+            (Some(either::Either::Right(vector)), false) => vector.serialize(serializer),
+            (Some(either::Either::Left(vectors)), true) => {
+                serializer.collect_seq(vectors.iter().map(|vector| SparseVector::unpack(vector)))
+            }
+            (Some(either::Either::Right(vector)), true) => {
+                SparseVector::unpack(vector).serialize(serializer)
+            }
+        }
+    }
 }
 
 impl<E: DeserializeError> Deserr<E> for VectorOrArrayOfVectors {
@@ -529,14 +631,33 @@ impl<E: DeserializeError> Deserr<E> for VectorOrArrayOfVectors {
         location: deserr::ValuePointerRef<'_>,
     ) -> Result<Self, E> {
         match value {
-            deserr::Value::Null => Ok(VectorOrArrayOfVectors { inner: None }),
+            deserr::Value::Null => Ok(VectorOrArrayOfVectors { inner: None, sparse: false }),
+            deserr::Value::Map(_) => {
+                Ok(Self::from_sparse(SparseVector::deserialize_from_value(value, location)?))
+            }
             deserr::Value::Sequence(seq) => {
                 let mut iter = seq.into_iter();
                 match iter.next().map(|v| v.into_value()) {
                     None => {
                         // With the strange way serde serialize the `Either`, we must send the left part
                         // otherwise it'll consider we returned [[]]
-                        Ok(VectorOrArrayOfVectors { inner: Some(either::Either::Left(Vec::new())) })
+                        Ok(VectorOrArrayOfVectors::from_array_of_vectors(Vec::new()))
+                    }
+                    Some(val @ deserr::Value::Map(_)) => {
+                        let first =
+                            SparseVector::deserialize_from_value(val, location.push_index(0))?;
+                        let mut collect = vec![first.pack()];
+                        for (i, v) in iter.enumerate() {
+                            let vector = SparseVector::deserialize_from_value(
+                                v.into_value(),
+                                location.push_index(i + 1),
+                            )?;
+                            collect.push(vector.pack());
+                        }
+                        Ok(VectorOrArrayOfVectors {
+                            inner: Some(either::Either::Left(collect)),
+                            sparse: true,
+                        })
                     }
                     Some(val @ deserr::Value::Sequence(_)) => {
                         let first = Embedding::deserialize_from_value(val, location.push_index(0))?;
@@ -552,7 +673,7 @@ impl<E: DeserializeError> Deserr<E> for VectorOrArrayOfVectors {
                             .collect::<Result<Vec<_>, _>>()?;
                         collect.append(&mut tail);
 
-                        Ok(VectorOrArrayOfVectors { inner: Some(either::Either::Left(collect)) })
+                        Ok(VectorOrArrayOfVectors::from_array_of_vectors(collect))
                     }
                     Some(
                         val @ deserr::Value::Integer(_)
@@ -570,13 +691,17 @@ impl<E: DeserializeError> Deserr<E> for VectorOrArrayOfVectors {
                             })
                             .collect::<Result<Vec<_>, _>>()?;
                         embedding.insert(0, first);
-                        Ok(VectorOrArrayOfVectors { inner: Some(either::Either::Right(embedding)) })
+                        Ok(VectorOrArrayOfVectors::from_vector(embedding))
                     }
                     Some(value) => Err(take_cf_content(E::error(
                         None,
                         deserr::ErrorKind::IncorrectValueKind {
                             actual: value,
-                            accepted: &[deserr::ValueKind::Sequence, deserr::ValueKind::Float],
+                            accepted: &[
+                                deserr::ValueKind::Sequence,
+                                deserr::ValueKind::Float,
+                                deserr::ValueKind::Map,
+                            ],
                         },
                         location.push_index(0),
                     ))),
@@ -586,7 +711,11 @@ impl<E: DeserializeError> Deserr<E> for VectorOrArrayOfVectors {
                 None,
                 deserr::ErrorKind::IncorrectValueKind {
                     actual: value,
-                    accepted: &[deserr::ValueKind::Sequence, deserr::ValueKind::Null],
+                    accepted: &[
+                        deserr::ValueKind::Sequence,
+                        deserr::ValueKind::Map,
+                        deserr::ValueKind::Null,
+                    ],
                 },
                 location,
             ))),
@@ -603,11 +732,25 @@ impl VectorOrArrayOfVectors {
     }
 
     pub fn from_array_of_vectors(array_of_vec: Vec<Embedding>) -> Self {
-        Self { inner: Some(either::Either::Left(array_of_vec)) }
+        Self { inner: Some(either::Either::Left(array_of_vec)), sparse: false }
     }
 
     pub fn from_vector(vec: Embedding) -> Self {
-        Self { inner: Some(either::Either::Right(vec)) }
+        Self { inner: Some(either::Either::Right(vec)), sparse: false }
+    }
+
+    pub fn from_sparse(vector: SparseVector) -> Self {
+        Self { inner: Some(either::Either::Right(vector.pack())), sparse: true }
+    }
+
+    /// Wraps the embeddings stored for a document, `sparse` when they come from a sparse embedder.
+    ///
+    /// The embeddings of a sparse embedder are merged in a single one, displayed as a map.
+    pub fn from_stored(embeddings: Vec<Embedding>, sparse: bool) -> Self {
+        match embeddings.as_slice() {
+            [vector] if sparse => Self::from_sparse(SparseVector::unpack(vector)),
+            _ => Self { inner: Some(either::Either::Left(embeddings)), sparse },
+        }
     }
 }
 
@@ -625,7 +768,7 @@ impl From<Vec<Embedding>> for VectorOrArrayOfVectors {
 
 #[cfg(test)]
 mod test {
-    use super::VectorOrArrayOfVectors;
+    use super::{RawVectors, VectorOrArrayOfVectors, Vectors};
 
     fn embedding_from_str(s: &str) -> Result<VectorOrArrayOfVectors, deserr::errors::JsonError> {
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
@@ -679,4 +822,58 @@ mod test {
         ]
         "###);
     }
+
+    #[test]
+    fn sparse_vectors() {
+        let one = embedding_from_str(r#"{ "12": 0.5, "3": 1.5 }"#).unwrap();
+        let two = embedding_from_str(r#"[{ "12": 0.5 }, { "3": 1.5 }]"#).unwrap();
+        insta::assert_json_snapshot!(one, @r###"
+        {
+          "3": 1.5,
+          "12": 0.5
+        }
+        "###);
+        insta::assert_json_snapshot!(one.into_array_of_vectors(), @r###"
+        [
+          [
+            3.0,
+            1.5,
+            12.0,
+            0.5
+          ]
+        ]
+        "###);
+        insta::assert_json_snapshot!(two, @r###"
+        [
+          {
+            "12": 0.5
+          },
+          {
+            "3": 1.5
+          }
+        ]
+        "###);
+
+        // a map of token ids is implicitly user provided, other maps are explicit vectors
+        let value = serde_json::json!({ "12": 0.5 });
+        let vectors: Vectors =
+            deserr::deserialize::<_, _, deserr::errors::JsonError>(value).unwrap();
+        assert!(matches!(vectors, Vectors::ImplicitlyUserProvided(_)));
+        let value = serde_json::json!({ "regenerate": false, "embeddings": { "12": 0.5 } });
+        let vectors: Vectors =
+            deserr::deserialize::<_, _, deserr::errors::JsonError>(value).unwrap();
+        assert!(matches!(vectors, Vectors::Explicit(_)));
+
+        let raw =
+            serde_json::value::RawValue::from_string(r#"{ "12": 0.5, "3": 1.5 }"#.to_string())
+                .unwrap();
+        assert!(matches!(
+            RawVectors::from_raw_value(&raw),
+            Ok(RawVectors::ImplicitlyUserProvided(Some(_)))
+        ));
+        let raw =
+            serde_json::value::RawValue::from_string(r#"{ "regenerate": false }"#.to_string())
+                .unwrap();
+        assert!(matches!(RawVectors::from_raw_value(&raw), Ok(RawVectors::Explicit(_))));
+    }
 }
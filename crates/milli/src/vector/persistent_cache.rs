@@ -0,0 +1,321 @@
+//! An embedding cache stored on disk, shared by all the embedders of the instance.
+//!
+//! The embeddings are keyed by a hash of the options of the embedder that generated them and by the
+//! embedded text, so that the same texts are not embedded again when reindexing, when changing the
+//! settings or after a restart.
+
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use heed::types::{Bytes, SerdeBincode};
+use heed::{Database, Env, EnvOpenOptions, RwTxn, WithoutTls};
+use serde::{Deserialize, Serialize};
+
+use super::Embedding;
+use crate::BEU64;
+
+mod db_name {
+    pub const EMBEDDINGS: &str = "embeddings";
+    pub const INSERTIONS: &str = "insertions";
+}
+
+/// The embeddings generated by the embedders of the instance, stored in their own LMDB environment.
+///
+/// The environment is only opened on first use, and the embeddings are not cached if it cannot be opened.
+#[derive(Clone)]
+pub struct PersistentEmbeddingCache {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    path: PathBuf,
+    map_size: usize,
+    max_entries: NonZeroUsize,
+    databases: OnceLock<Option<Databases>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct Databases {
+    env: Env<WithoutTls>,
+    /// Maps the (embedder options hash, text hash) key to the cached embedding.
+    embeddings: Database<Bytes, SerdeBincode<CachedEmbedding>>,
+    /// Maps the insertion number to the key of the embedding, so that the oldest entries are evicted first.
+    insertions: Database<BEU64, Bytes>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedEmbedding {
+    insertion: u64,
+    /// Stored to tell apart the texts whose hashes collide.
+    text: String,
+    embedding: Embedding,
+}
+
+impl PersistentEmbeddingCache {
+    pub fn new(path: PathBuf, map_size: usize, max_entries: NonZeroUsize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                path,
+                map_size,
+                max_entries,
+                databases: OnceLock::new(),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// The part of the cache dedicated to the embeddings generated by an embedder with these options.
+    pub fn for_embedder<O: Serialize>(&self, options: &O) -> EmbedderCache {
+        // the `Hash` implementation of the options is not stable across versions, their serialization is
+        let options = serde_json::to_vec(options).unwrap_or_default();
+        EmbedderCache { cache: self.clone(), prefix: fxhash::hash64(&options).to_be_bytes() }
+    }
+
+    /// The number of embeddings that were found in the cache since the start of the instance.
+    pub fn hits(&self) -> u64 {
+        self.inner.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of embeddings that were not found in the cache since the start of the instance.
+    pub fn misses(&self) -> u64 {
+        self.inner.misses.load(Ordering::Relaxed)
+    }
+
+    /// The number of embeddings stored in the cache.
+    pub fn entries(&self) -> heed::Result<u64> {
+        let Some(databases) = self.databases() else { return Ok(0) };
+        let rtxn = databases.env.read_txn()?;
+        databases.embeddings.len(&rtxn)
+    }
+
+    /// Removes all the embeddings from the cache, returning the number of removed embeddings.
+    pub fn clear(&self) -> heed::Result<u64> {
+        let Some(databases) = self.databases() else { return Ok(0) };
+        let mut wtxn = databases.env.write_txn()?;
+        let entries = databases.embeddings.len(&wtxn)?;
+        databases.embeddings.clear(&mut wtxn)?;
+        databases.insertions.clear(&mut wtxn)?;
+        wtxn.commit()?;
+        Ok(entries)
+    }
+
+    fn databases(&self) -> Option<&Databases> {
+        self.inner
+            .databases
+            .get_or_init(|| match Databases::open(&self.inner.path, self.inner.map_size) {
+                Ok(databases) => Some(databases),
+                Err(error) => {
+                    tracing::error!(%error, path = %self.inner.path.display(), "Could not open the embedding cache, embeddings will not be cached");
+                    None
+                }
+            })
+            .as_ref()
+    }
+}
+
+impl fmt::Debug for PersistentEmbeddingCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentEmbeddingCache")
+            .field("path", &self.inner.path)
+            .field("map_size", &self.inner.map_size)
+            .field("max_entries", &self.inner.max_entries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Databases {
+    fn open(path: &Path, map_size: usize) -> heed::Result<Self> {
+        std::fs::create_dir_all(path).map_err(heed::Error::Io)?;
+        let env = unsafe {
+            let env_options = EnvOpenOptions::new();
+            let mut env_options = env_options.read_txn_without_tls();
+            env_options.max_dbs(2).map_size(map_size).open(path)
+        }?;
+
+        let mut wtxn = env.write_txn()?;
+        let embeddings = env.create_database(&mut wtxn, Some(db_name::EMBEDDINGS))?;
+        let insertions = env.create_database(&mut wtxn, Some(db_name::INSERTIONS))?;
+        wtxn.commit()?;
+
+        Ok(Self { env, embeddings, insertions })
+    }
+
+    /// Removes the `count` oldest embeddings of the cache.
+    fn evict_oldest(&self, wtxn: &mut RwTxn, count: u64) -> heed::Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        let oldest = self
+            .insertions
+            .iter(wtxn)?
+            .take(count as usize)
+            .map(|entry| entry.map(|(insertion, key)| (insertion, key.to_vec())))
+            .collect::<heed::Result<Vec<_>>>()?;
+        for (insertion, key) in oldest {
+            self.insertions.delete(wtxn, &insertion)?;
+            self.embeddings.delete(wtxn, &key)?;
+        }
+        Ok(())
+    }
+}
+
+/// The part of the [`PersistentEmbeddingCache`] dedicated to the embeddings of one embedder configuration.
+///
+/// The cache is a best effort: failing to read or write it is logged and the embeddings are computed instead.
+#[derive(Debug, Clone)]
+pub struct EmbedderCache {
+    cache: PersistentEmbeddingCache,
+    prefix: [u8; 8],
+}
+
+impl EmbedderCache {
+    /// Returns the cached embedding of each text, `None` for the texts that are not in the cache.
+    pub fn get_many(&self, texts: &[&str]) -> Vec<Option<Embedding>> {
+        let embeddings =
+            match self.cache.databases().map(|databases| self.try_get_many(databases, texts)) {
+                Some(Ok(embeddings)) => embeddings,
+                Some(Err(error)) => {
+                    tracing::warn!(%error, "Could not read the embedding cache");
+                    vec![None; texts.len()]
+                }
+                None => vec![None; texts.len()],
+            };
+
+        let hits = embeddings.iter().filter(|embedding| embedding.is_some()).count() as u64;
+        self.cache.inner.hits.fetch_add(hits, Ordering::Relaxed);
+        self.cache.inner.misses.fetch_add(texts.len() as u64 - hits, Ordering::Relaxed);
+        embeddings
+    }
+
+    /// Stores the embeddings of the texts, evicting the oldest embeddings of the cache when it is full.
+    pub fn put_many(&self, entries: &[(&str, &Embedding)]) {
+        let Some(databases) = self.cache.databases() else { return };
+        if entries.is_empty() {
+            return;
+        }
+
+        let result = match self.try_put_many(databases, entries) {
+            Err(heed::Error::Mdb(heed::MdbError::MapFull)) => {
+                // make room by evicting a quarter of the cache, then try again
+                self.cache
+                    .entries()
+                    .and_then(|entries_count| {
+                        let mut wtxn = databases.env.write_txn()?;
+                        databases.evict_oldest(&mut wtxn, entries_count.div_ceil(4))?;
+                        wtxn.commit()
+                    })
+                    .and_then(|()| self.try_put_many(databases, entries))
+            }
+            result => result,
+        };
+
+        if let Err(error) = result {
+            tracing::warn!(%error, "Could not store the embeddings in the embedding cache");
+        }
+    }
+
+    fn try_get_many(
+        &self,
+        databases: &Databases,
+        texts: &[&str],
+    ) -> heed::Result<Vec<Option<Embedding>>> {
+        let rtxn = databases.env.read_txn()?;
+        texts
+            .iter()
+            .map(|text| {
+                let cached = databases.embeddings.get(&rtxn, &self.key(text))?;
+                Ok(cached.filter(|cached| cached.text == *text).map(|cached| cached.embedding))
+            })
+            .collect()
+    }
+
+    fn try_put_many(
+        &self,
+        databases: &Databases,
+        entries: &[(&str, &Embedding)],
+    ) -> heed::Result<()> {
+        let mut wtxn = databases.env.write_txn()?;
+        let mut insertion =
+            databases.insertions.last(&wtxn)?.map_or(0, |(insertion, _)| insertion + 1);
+
+        for (text, embedding) in entries {
+            let key = self.key(text);
+            if let Some(previous) = databases.embeddings.get(&wtxn, &key)? {
+                databases.insertions.delete(&mut wtxn, &previous.insertion)?;
+            }
+            let cached = CachedEmbedding {
+                insertion,
+                text: text.to_string(),
+                embedding: embedding.to_vec(),
+            };
+            databases.embeddings.put(&mut wtxn, &key, &cached)?;
+            databases.insertions.put(&mut wtxn, &insertion, &key)?;
+            insertion += 1;
+        }
+
+        let entries_count = databases.embeddings.len(&wtxn)?;
+        let max_entries = self.cache.inner.max_entries.get() as u64;
+        databases.evict_oldest(&mut wtxn, entries_count.saturating_sub(max_entries))?;
+
+        wtxn.commit()
+    }
+
+    fn key(&self, text: &str) -> [u8; 16] {
+        let mut key = [0; 16];
+        key[..8].copy_from_slice(&self.prefix);
+        key[8..].copy_from_slice(&fxhash::hash64(text).to_be_bytes());
+        key
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cache(max_entries: usize) -> (tempfile::TempDir, PersistentEmbeddingCache) {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PersistentEmbeddingCache::new(
+            dir.path().join("embedding_cache"),
+            10 * 1024 * 1024,
+            NonZeroUsize::new(max_entries).unwrap(),
+        );
+        (dir, cache)
+    }
+
+    #[test]
+    fn embeddings_are_scoped_by_embedder() {
+        let (_dir, cache) = cache(10);
+        let kefir = cache.for_embedder(&"kefir");
+        let intel = cache.for_embedder(&"intel");
+
+        kefir.put_many(&[("doggo", &vec![1.0, 0.0]), ("catto", &vec![0.0, 1.0])]);
+        assert_eq!(kefir.get_many(&["doggo", "birdo"]), vec![Some(vec![1.0, 0.0]), None]);
+        assert_eq!(intel.get_many(&["doggo"]), vec![None]);
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(kefir.get_many(&["doggo"]), vec![None]);
+    }
+
+    #[test]
+    fn oldest_embeddings_are_evicted() {
+        let (_dir, cache) = cache(2);
+        let embedder = cache.for_embedder(&"kefir");
+
+        embedder.put_many(&[("first", &vec![1.0]), ("second", &vec![2.0])]);
+        // putting an existing text again makes it the most recent one
+        embedder.put_many(&[("first", &vec![1.0])]);
+        embedder.put_many(&[("third", &vec![3.0])]);
+
+        assert_eq!(cache.entries().unwrap(), 2);
+        assert_eq!(
+            embedder.get_many(&["first", "second", "third"]),
+            vec![Some(vec![1.0]), None, Some(vec![3.0])]
+        );
+    }
+}
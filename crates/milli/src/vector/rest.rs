@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 
 use super::error::EmbedErrorKind;
 use super::json_template::ValueTemplate;
+use super::media::{Fragment, MediaOptions};
+use super::sparse::SparseVector;
 use super::{
     DistributionShift, EmbedError, Embedding, EmbeddingCache, NewEmbedderError, REQUEST_PARALLELISM,
 };
@@ -90,6 +92,14 @@ struct EmbedderData {
     request: Request,
     response: Response,
     configuration_source: ConfigurationSource,
+    /// Injected in the request in place of each text when indexing, to embed the texts along with their media
+    indexing_fragment: Option<Fragment>,
+    /// Injected in the request in place of the query when searching
+    search_fragment: Option<Fragment>,
+    /// Whether the server returns sparse embeddings, as maps of token ids to weights
+    sparse: bool,
+    /// Whether the server returns an embedding per token, that are packed in a single embedding
+    late_interaction: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -101,6 +111,10 @@ pub struct EmbedderOptions {
     pub request: serde_json::Value,
     pub response: serde_json::Value,
     pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<MediaOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub late_interaction: Option<bool>,
 }
 
 impl std::hash::Hash for EmbedderOptions {
@@ -109,7 +123,8 @@ impl std::hash::Hash for EmbedderOptions {
         self.distribution.hash(state);
         self.dimensions.hash(state);
         self.url.hash(state);
-        // skip hashing the request and response
+        self.late_interaction.hash(state);
+        // skip hashing the request, response and media
         // collisions in regular usage should be minimal,
         // and the list is limited to 256 values anyway
     }
@@ -126,8 +141,26 @@ enum InputType {
 impl Embedder {
     pub fn new(
         options: EmbedderOptions,
-        cache_cap: usize,
+        cache: EmbeddingCache,
         configuration_source: ConfigurationSource,
+    ) -> Result<Self, NewEmbedderError> {
+        Self::new_inner(options, cache, configuration_source, false)
+    }
+
+    /// Spawns an embedder whose server returns sparse embeddings, packed as described in
+    /// [`super::sparse`].
+    pub fn new_sparse(
+        options: EmbedderOptions,
+        cache: EmbeddingCache,
+    ) -> Result<Self, NewEmbedderError> {
+        Self::new_inner(options, cache, ConfigurationSource::User, true)
+    }
+
+    fn new_inner(
+        options: EmbedderOptions,
+        cache: EmbeddingCache,
+        configuration_source: ConfigurationSource,
+        sparse: bool,
     ) -> Result<Self, NewEmbedderError> {
         let bearer = options.api_key.as_deref().map(|api_key| format!("Bearer {api_key}"));
 
@@ -139,6 +172,14 @@ impl Embedder {
 
         let request = Request::new(options.request)?;
         let response = Response::new(options.response, &request)?;
+        let (indexing_fragment, search_fragment) = match &options.media {
+            Some(media) => {
+                let (indexing, search) =
+                    media.fragments().map_err(NewEmbedderError::rest_could_not_parse_template)?;
+                (Some(indexing), Some(search))
+            }
+            None => (None, None),
+        };
 
         let data = EmbedderData {
             client,
@@ -148,20 +189,24 @@ impl Embedder {
             response,
             configuration_source,
             headers: options.headers,
+            indexing_fragment,
+            search_fragment,
+            sparse,
+            late_interaction: options.late_interaction.unwrap_or_default(),
         };
 
-        let dimensions = if let Some(dimensions) = options.dimensions {
+        // sparse embeddings have a variable number of dimensions
+        let dimensions = if sparse {
+            0
+        } else if let Some(dimensions) = options.dimensions {
             dimensions
         } else {
+            // late interaction embedders always have their dimensions set by the settings,
+            // as the length of the packed token embeddings cannot tell them
             infer_dimensions(&data)?
         };
 
-        Ok(Self {
-            data,
-            dimensions,
-            distribution: options.distribution,
-            cache: EmbeddingCache::new(cache_cap),
-        })
+        Ok(Self { data, dimensions, distribution: options.distribution, cache })
     }
 
     pub fn embed(
@@ -169,7 +214,7 @@ impl Embedder {
         texts: Vec<String>,
         deadline: Option<Instant>,
     ) -> Result<Vec<Embedding>, EmbedError> {
-        embed(&self.data, texts.as_slice(), texts.len(), Some(self.dimensions), deadline)
+        self.embed_ref(texts.as_slice(), deadline)
     }
 
     pub fn embed_ref<S>(
@@ -180,7 +225,54 @@ impl Embedder {
     where
         S: AsRef<str> + Serialize,
     {
-        embed(&self.data, texts, texts.len(), Some(self.dimensions), deadline)
+        self.embed_with_fragment(self.data.indexing_fragment.as_ref(), texts, deadline)
+    }
+
+    /// Embeds a search query, along with its media when the embedder has a search fragment.
+    pub fn embed_search(
+        &self,
+        query: &str,
+        deadline: Option<Instant>,
+    ) -> Result<Embedding, EmbedError> {
+        let mut embeddings =
+            self.embed_with_fragment(self.data.search_fragment.as_ref(), &[query], deadline)?;
+        // unwrap: guaranteed that embeddings.len() == 1, otherwise the previous line terminated in error
+        Ok(embeddings.pop().unwrap())
+    }
+
+    pub fn supports_media(&self) -> bool {
+        self.data.search_fragment.is_some()
+    }
+
+    /// Whether the embeddings are made of an embedding of [`Self::dimensions`] per token,
+    /// to be scored with MaxSim.
+    pub fn is_late_interaction(&self) -> bool {
+        self.data.late_interaction
+    }
+
+    fn embed_with_fragment<S>(
+        &self,
+        fragment: Option<&Fragment>,
+        texts: &[S],
+        deadline: Option<Instant>,
+    ) -> Result<Vec<Embedding>, EmbedError>
+    where
+        S: AsRef<str> + Serialize,
+    {
+        match fragment {
+            Some(fragment) => {
+                let inputs: Vec<_> =
+                    texts.iter().map(|text| fragment.render(text.as_ref())).collect();
+                embed(
+                    &self.data,
+                    inputs.as_slice(),
+                    texts.len(),
+                    self.expected_dimensions(),
+                    deadline,
+                )
+            }
+            None => embed(&self.data, texts, texts.len(), self.expected_dimensions(), deadline),
+        }
     }
 
     pub fn embed_tokens(
@@ -188,7 +280,7 @@ impl Embedder {
         tokens: &[u32],
         deadline: Option<Instant>,
     ) -> Result<Embedding, EmbedError> {
-        let mut embeddings = embed(&self.data, tokens, 1, Some(self.dimensions), deadline)?;
+        let mut embeddings = embed(&self.data, tokens, 1, self.expected_dimensions(), deadline)?;
         // unwrap: guaranteed that embeddings.len() == 1, otherwise the previous line terminated in error
         Ok(embeddings.pop().unwrap())
     }
@@ -262,6 +354,11 @@ impl Embedder {
         self.dimensions
     }
 
+    /// The dimensions every embedding returned by the server must have, if they are dense.
+    fn expected_dimensions(&self) -> Option<usize> {
+        (!self.data.sparse).then_some(self.dimensions)
+    }
+
     pub fn distribution(&self) -> Option<DistributionShift> {
         self.distribution
     }
@@ -272,8 +369,11 @@ impl Embedder {
 }
 
 fn infer_dimensions(data: &EmbedderData) -> Result<usize, NewEmbedderError> {
-    let v = embed(data, ["test"].as_slice(), 1, None, None)
-        .map_err(NewEmbedderError::could_not_determine_dimension)?;
+    let v = match &data.indexing_fragment {
+        Some(fragment) => embed(data, [fragment.render("test")].as_slice(), 1, None, None),
+        None => embed(data, ["test"].as_slice(), 1, None, None),
+    }
+    .map_err(NewEmbedderError::could_not_determine_dimension)?;
     // unwrap: guaranteed that v.len() == 1, otherwise the previous line terminated in error
     Ok(v.first().unwrap().len())
 }
@@ -388,7 +488,14 @@ fn response_to_embedding(
         .map_err(EmbedError::rest_response_deserialization)
         .map_err(Retry::retry_later)?;
 
-    let embeddings = data.response.extract_embeddings(response).map_err(Retry::give_up)?;
+    let embeddings = if data.sparse {
+        data.response.extract_sparse_embeddings(response)
+    } else if data.late_interaction {
+        data.response.extract_late_interaction_embeddings(response, expected_dimensions)
+    } else {
+        data.response.extract_embeddings(response)
+    }
+    .map_err(Retry::give_up)?;
 
     if embeddings.len() != expected_count {
         return Err(Retry::give_up(EmbedError::rest_response_embedding_count(
@@ -397,7 +504,7 @@ fn response_to_embedding(
         )));
     }
 
-    if let Some(dimensions) = expected_dimensions {
+    if let Some(dimensions) = expected_dimensions.filter(|_| !data.late_interaction) {
         for embedding in &embeddings {
             if embedding.len() != dimensions {
                 return Err(Retry::give_up(EmbedError::rest_unexpected_dimension(
@@ -415,6 +522,9 @@ pub(super) const REQUEST_PLACEHOLDER: &str = "{{text}}";
 pub(super) const RESPONSE_PLACEHOLDER: &str = "{{embedding}}";
 pub(super) const REPEAT_PLACEHOLDER: &str = "{{..}}";
 
+/// Each embedding of a document is stored in its own arroy tree, and a document can have at most 255 of them.
+pub const MAX_LATE_INTERACTION_EMBEDDINGS: usize = u8::MAX as usize;
+
 #[derive(Debug)]
 pub struct Request {
     template: ValueTemplate,
@@ -490,4 +600,69 @@ impl Response {
 
         Ok(embeddings)
     }
+
+    /// Like [`Self::extract_embeddings`], for the maps of token ids to weights of a sparse embedder.
+    pub fn extract_sparse_embeddings(
+        &self,
+        response: serde_json::Value,
+    ) -> Result<Vec<Embedding>, EmbedError> {
+        let extracted_values: Vec<SparseVector> = match self.template.extract(response) {
+            Ok(extracted_values) => extracted_values,
+            Err(error) => {
+                let error_message = error.error_message(
+                    "response",
+                    "{{embedding}}",
+                    "a map of token ids to weights",
+                );
+                return Err(EmbedError::rest_extraction_error(error_message));
+            }
+        };
+
+        Ok(extracted_values.iter().map(SparseVector::pack).collect())
+    }
+
+    /// Like [`Self::extract_embeddings`], for the arrays of token embeddings of a late interaction embedder.
+    ///
+    /// The first [`MAX_LATE_INTERACTION_EMBEDDINGS`] token embeddings of each text are packed in a single embedding.
+    pub fn extract_late_interaction_embeddings(
+        &self,
+        response: serde_json::Value,
+        expected_dimensions: Option<usize>,
+    ) -> Result<Vec<Embedding>, EmbedError> {
+        let extracted_values: Vec<Vec<Embedding>> = match self.template.extract(response) {
+            Ok(extracted_values) => extracted_values,
+            Err(error) => {
+                let error_message = error.error_message(
+                    "response",
+                    "{{embedding}}",
+                    "an array of arrays of numbers",
+                );
+                return Err(EmbedError::rest_extraction_error(error_message));
+            }
+        };
+
+        let mut embeddings = Vec::with_capacity(extracted_values.len());
+        for token_embeddings in extracted_values {
+            let mut embedding = Vec::new();
+            for token_embedding in
+                token_embeddings.into_iter().take(MAX_LATE_INTERACTION_EMBEDDINGS)
+            {
+                if let Some(dimensions) = expected_dimensions {
+                    if token_embedding.len() != dimensions {
+                        return Err(EmbedError::rest_unexpected_dimension(
+                            dimensions,
+                            token_embedding.len(),
+                        ));
+                    }
+                }
+                embedding.extend(token_embedding);
+            }
+            if embedding.is_empty() {
+                return Err(EmbedError::missing_embedding());
+            }
+            embeddings.push(embedding);
+        }
+
+        Ok(embeddings)
+    }
 }
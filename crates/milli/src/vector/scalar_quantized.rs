@@ -0,0 +1,269 @@
+//! Storage of the `int8` scalar quantized embeddings.
+//!
+//! Each embedding is stored as a `f32` scale followed by one `i8` per dimension,
+//! the original value of a dimension being approximated by `scale * value`.
+
+use std::borrow::Cow;
+
+use heed::BoxedError;
+
+use crate::heed_codec::SliceTooShortError;
+
+/// The largest absolute value a quantized dimension can take.
+const MAX_QUANTIZED_VALUE: f32 = i8::MAX as f32;
+
+/// Encodes the arroy index and the item id of a scalar quantized embedding.
+pub struct ScalarQuantizedKeyCodec;
+
+impl<'a> heed::BytesEncode<'a> for ScalarQuantizedKeyCodec {
+    type EItem = (u16, arroy::ItemId);
+
+    fn bytes_encode((index, item_id): &Self::EItem) -> Result<Cow<'a, [u8]>, BoxedError> {
+        Ok(Cow::Owned(key(*index, *item_id).to_vec()))
+    }
+}
+
+impl<'a> heed::BytesDecode<'a> for ScalarQuantizedKeyCodec {
+    type DItem = (u16, arroy::ItemId);
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        let index = bytes.get(..2).ok_or(SliceTooShortError)?;
+        let item_id = bytes.get(2..6).ok_or(SliceTooShortError)?;
+        Ok((u16::from_be_bytes(index.try_into()?), u32::from_be_bytes(item_id.try_into()?)))
+    }
+}
+
+/// Quantizes the embeddings to `i8` when encoding them, and dequantizes them when decoding.
+pub struct ScalarQuantizedVectorCodec;
+
+impl<'a> heed::BytesEncode<'a> for ScalarQuantizedVectorCodec {
+    type EItem = [f32];
+
+    fn bytes_encode(vector: &Self::EItem) -> Result<Cow<'a, [u8]>, BoxedError> {
+        Ok(Cow::Owned(quantize(vector)))
+    }
+}
+
+impl<'a> heed::BytesDecode<'a> for ScalarQuantizedVectorCodec {
+    type DItem = Vec<f32>;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        let (scale, values) = bytes.split_first_chunk::<4>().ok_or(SliceTooShortError)?;
+        let scale = f32::from_le_bytes(*scale);
+        Ok(values.iter().map(|&value| value as i8 as f32 * scale).collect())
+    }
+}
+
+/// The key of an embedding in the scalar quantized database.
+///
+/// The first byte is the embedder index, so that all the embeddings of an embedder share a prefix.
+pub fn key(index: u16, item_id: arroy::ItemId) -> [u8; 6] {
+    let mut key = [0; 6];
+    key[..2].copy_from_slice(&index.to_be_bytes());
+    key[2..].copy_from_slice(&item_id.to_be_bytes());
+    key
+}
+
+/// Symmetrically quantizes the vector, mapping its largest absolute value to `i8::MAX`.
+pub fn quantize(vector: &[f32]) -> Vec<u8> {
+    let max = vector.iter().fold(0.0f32, |max, value| max.max(value.abs()));
+    let scale = if max > 0.0 { max / MAX_QUANTIZED_VALUE } else { 1.0 };
+
+    let mut bytes = Vec::with_capacity(4 + vector.len());
+    bytes.extend_from_slice(&scale.to_le_bytes());
+    bytes.extend(vector.iter().map(|value| {
+        (value / scale).round().clamp(-MAX_QUANTIZED_VALUE, MAX_QUANTIZED_VALUE) as i8 as u8
+    }));
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use big_s::S;
+    use heed::{BytesDecode, BytesEncode};
+    use maplit::btreemap;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+    use crate::update::Setting;
+    use crate::vector::settings::{EmbedderSource, EmbeddingSettings};
+    use crate::vector::{ArroyWrapper, VectorDistance, VectorQuantization};
+
+    #[test]
+    fn quantize_roundtrip() {
+        let vector = [0.5, -1.0, 0.25, 0.0, 0.999];
+        let bytes = ScalarQuantizedVectorCodec::bytes_encode(&vector[..]).unwrap();
+        assert_eq!(bytes.len(), 4 + vector.len());
+
+        let dequantized = ScalarQuantizedVectorCodec::bytes_decode(&bytes).unwrap();
+        for (original, dequantized) in vector.iter().zip(dequantized) {
+            assert!((original - dequantized).abs() <= 1.0 / MAX_QUANTIZED_VALUE);
+        }
+    }
+
+    #[test]
+    fn quantize_zero_vector() {
+        let vector = [0.0; 3];
+        let bytes = ScalarQuantizedVectorCodec::bytes_encode(&vector[..]).unwrap();
+        let dequantized = ScalarQuantizedVectorCodec::bytes_decode(&bytes).unwrap();
+        assert_eq!(dequantized, vec![0.0; 3]);
+    }
+
+    fn user_provided(
+        dimensions: usize,
+        quantization: VectorQuantization,
+    ) -> Setting<EmbeddingSettings> {
+        Setting::Set(EmbeddingSettings {
+            source: Setting::Set(EmbedderSource::UserProvided),
+            dimensions: Setting::Set(dimensions),
+            quantization: Setting::Set(quantization),
+            ..EmbeddingSettings::default()
+        })
+    }
+
+    fn quantization_of(index: &TempIndex, embedder: &str) -> VectorQuantization {
+        let rtxn = index.read_txn().unwrap();
+        let configs = index.embedding_configs(&rtxn).unwrap();
+        configs.into_iter().find(|config| config.name == embedder).unwrap().config.quantization()
+    }
+
+    #[test]
+    fn quantize_from_none_to_int8_to_binary() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => user_provided(2, VectorQuantization::None),
+                });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "manual": [1.0, 0.1] } },
+                { "id": 1, "_vectors": { "manual": [[1.0, 0.9], [0.5, 0.5]] } },
+                { "id": 2, "_vectors": { "manual": [0.2, 1.0] } },
+            ]))
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.vector_scalar_quantized.is_empty(&rtxn).unwrap());
+        drop(rtxn);
+
+        // the int8 embeddings are computed from the embeddings stored in arroy
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => user_provided(2, VectorQuantization::Int8),
+                });
+            })
+            .unwrap();
+        assert_eq!(quantization_of(&index, "manual"), VectorQuantization::Int8);
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.vector_scalar_quantized.len(&rtxn).unwrap(), 4);
+        let int8_size = index.embedder_storage_sizes(&rtxn).unwrap()["manual"];
+        drop(rtxn);
+
+        // the binary quantization doesn't need the int8 embeddings anymore
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => user_provided(2, VectorQuantization::Binary),
+                });
+            })
+            .unwrap();
+        assert_eq!(quantization_of(&index, "manual"), VectorQuantization::Binary);
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.vector_scalar_quantized.is_empty(&rtxn).unwrap());
+        let binary_size = index.embedder_storage_sizes(&rtxn).unwrap()["manual"];
+        assert!(binary_size > 0 && binary_size < int8_size, "{binary_size} >= {int8_size}");
+
+        // the embeddings are still returned
+        let embedder_id = index.embedder_category_id.get(&rtxn, "manual").unwrap().unwrap();
+        let reader = ArroyWrapper::new(
+            index.vector_arroy,
+            index.vector_scalar_quantized,
+            None,
+            embedder_id,
+            VectorQuantization::Binary,
+            VectorDistance::Cosine,
+        );
+        let results = reader.nns_by_vector(&rtxn, &[1.0, 0.0], 10, None).unwrap();
+        let mut docids: Vec<_> = results.into_iter().map(|(docid, _)| docid).collect();
+        docids.sort_unstable();
+        docids.dedup();
+        assert_eq!(docids, [0, 1, 2]);
+    }
+
+    #[test]
+    fn int8_rescoring_order() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => user_provided(2, VectorQuantization::Int8),
+                });
+            })
+            .unwrap();
+        // all these embeddings have the same binary quantized embedding,
+        // only the int8 embeddings can rank them
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "manual": [0.2, 1.0] } },
+                { "id": 1, "_vectors": { "manual": [1.0, 0.1] } },
+                { "id": 2, "_vectors": { "manual": [1.0, 0.9] } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let embedder_id = index.embedder_category_id.get(&rtxn, "manual").unwrap().unwrap();
+        let reader = ArroyWrapper::new(
+            index.vector_arroy,
+            index.vector_scalar_quantized,
+            None,
+            embedder_id,
+            VectorQuantization::Int8,
+            VectorDistance::Cosine,
+        );
+        let query = [1.0, 0.0];
+        let results = reader.nns_by_vector(&rtxn, &query, 3, None).unwrap();
+        let docids: Vec<_> = results.iter().map(|(docid, _)| *docid).collect();
+        assert_eq!(docids, [1, 2, 0]);
+
+        // the distances are computed with the int8 embeddings, close to the exact ones
+        for ((_, distance), exact) in results.iter().zip([[1.0, 0.1], [1.0, 0.9], [0.2, 1.0]]) {
+            let exact = VectorDistance::Cosine.distance(&query, &exact);
+            assert!((distance - exact).abs() < 0.01, "{distance} != {exact}");
+        }
+
+        // only the requested number of results are returned after the rescoring
+        let results = reader.nns_by_vector(&rtxn, &query, 1, None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, 1);
+    }
+
+    #[test]
+    fn storage_size_per_embedder() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("float") => user_provided(64, VectorQuantization::None),
+                    S("int8") => user_provided(64, VectorQuantization::Int8),
+                });
+            })
+            .unwrap();
+
+        let embedding: Vec<f32> = (0..64).map(|i| (i as f32 - 32.0) / 32.0).collect();
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "float": embedding, "int8": embedding } },
+                { "id": 1, "_vectors": { "float": embedding, "int8": embedding } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let sizes = index.embedder_storage_sizes(&rtxn).unwrap();
+        assert_eq!(sizes.keys().collect::<Vec<_>>(), ["float", "int8"]);
+        // an int8 embedding takes a byte per dimension and a bit in arroy, instead of 4 bytes
+        assert!(sizes["int8"] > 0 && sizes["int8"] < sizes["float"], "{sizes:?}");
+    }
+}
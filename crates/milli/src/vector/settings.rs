@@ -8,7 +8,11 @@ use utoipa::ToSchema;
 
 use super::composite::SubEmbedderOptions;
 use super::hf::OverridePooling;
-use super::{ollama, openai, DistributionShift, EmbedderOptions};
+use super::media::MediaOptions;
+use super::{
+    ollama, openai, DistributionShift, EmbedderOptions, VectorDistance, VectorQuantization,
+};
+use crate::prompt::chunking::Chunking;
 use crate::prompt::{default_max_bytes, PromptData};
 use crate::update::Setting;
 use crate::vector::EmbeddingConfig;
@@ -38,13 +42,15 @@ pub struct EmbeddingSettings {
     #[schema(value_type = Option<String>)]
     /// The name of the model to use.
     ///
+    /// For source `onnx`, the path of the directory containing the model.
+    ///
     /// # Mandatory
     ///
-    /// - This parameter is mandatory for source `ollama`
+    /// - This parameter is mandatory for sources `ollama`, `onnx`
     ///
     /// # Availability
     ///
-    /// - This parameter is available for sources `openAi`, `huggingFace`, `ollama`
+    /// - This parameter is available for sources `openAi`, `huggingFace`, `ollama`, `onnx`
     ///
     /// # 🔄 Reindexing
     ///
@@ -82,7 +88,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `huggingFace`
+    /// - This parameter is available for sources `huggingFace`, `onnx`
     ///
     /// # 🔄 Reindexing
     ///
@@ -103,7 +109,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `openAi`, `ollama`, `rest`
+    /// - This parameter is available for source `openAi`, `ollama`, `rest`, `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -169,6 +175,59 @@ pub struct EmbeddingSettings {
     pub binary_quantized: Setting<bool>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
+    #[schema(value_type = Option<VectorQuantization>)]
+    /// How to compress the embeddings of this embedder.
+    ///
+    /// - `none` stores the embeddings as they are.
+    /// - `binary` reduces each dimension to its sign, like `binaryQuantized`.
+    /// - `int8` reduces each dimension to a 8 bits integer. The search is performed on the binary quantized
+    ///   embeddings and its results are rescored using the 8 bits embeddings, which retains more relevancy than
+    ///   `binary` at the cost of more disk usage.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for all embedders
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🏗️ When changed, embeddings are not regenerated, but they are quantized, which takes time.
+    ///
+    /// # Defaults
+    ///
+    /// - Defaults to `binary` if `binaryQuantized` is `true`, to `none` otherwise
+    ///
+    /// # Note
+    ///
+    /// As quantization is a destructive operation, it is only possible to change this setting from `none` to
+    /// `int8` or `binary`, and from `int8` to `binary`.
+    /// When both this parameter and `binaryQuantized` are set, this parameter takes precedence.
+    pub quantization: Setting<VectorQuantization>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<VectorDistance>)]
+    /// The metric used to compare the embeddings of this embedder.
+    ///
+    /// Use the metric the model was trained for: `dotProduct` for models trained for maximum inner product search,
+    /// `euclidean` for models trained for L2 similarity, and `cosine` otherwise.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for all embedders
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🏗️ Changing the value of this parameter always regenerates embeddings
+    ///
+    /// # Defaults
+    ///
+    /// - Defaults to `cosine`
+    ///
+    /// # Note
+    ///
+    /// Quantization is not available for the `dotProduct` metric.
+    pub distance: Setting<VectorDistance>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
     #[schema(value_type = Option<bool>)]
     /// A liquid template used to render documents to a text that can be embedded.
     ///
@@ -177,7 +236,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama` and `rest
+    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama`, `rest`, `sparse` and `onnx`
     ///
     /// # 🔄 Reindexing
     ///
@@ -190,7 +249,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama` and `rest`
+    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama`, `rest`, `sparse` and `onnx`
     ///
     /// # 🔄 Reindexing
     ///
@@ -203,6 +262,31 @@ pub struct EmbeddingSettings {
     pub document_template_max_bytes: Setting<usize>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
+    #[schema(value_type = Option<Chunking>)]
+    /// How to split rendered texts into several chunks that are embedded separately.
+    ///
+    /// - `paragraph` splits the text on empty lines.
+    /// - `tokens` splits the text on whitespace.
+    ///
+    /// Each chunk groups `size` paragraphs or tokens, and consecutive chunks share `overlap` of them.
+    /// A document is then as relevant as its most relevant chunk.
+    /// When chunking, each chunk is truncated to `documentTemplateMaxBytes` rather than the whole text.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama`, `rest`, `sparse` and `onnx`
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🏗️ Changing the value of this parameter always regenerates embeddings
+    ///
+    /// # Defaults
+    ///
+    /// - Defaults to no chunking, the rendered text is embedded as a whole
+    /// - `size` defaults to 1 paragraph or 256 tokens, `overlap` defaults to 0
+    pub chunking: Setting<Chunking>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
     #[schema(value_type = Option<String>)]
     /// URL to reach the remote embedder.
     ///
@@ -212,12 +296,17 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `openAi`, `ollama` and `rest`
+    /// - This parameter is available for source `openAi`, `ollama`, `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
     /// - 🌱 When modified for source `openAi`, embeddings are never regenerated
-    /// - 🏗️ When modified for sources `ollama` and `rest`, embeddings are always regenerated
+    /// - 🏗️ When modified for sources `ollama`, `rest` and `sparse`, embeddings are always regenerated
+    ///
+    /// # Note
+    ///
+    /// - For source `sparse`, the sparse embeddings must be provided in `_vectors` when this parameter is not set.
+    ///   `request` and `response` are then mandatory when this parameter is set.
     pub url: Setting<String>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
@@ -230,7 +319,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `rest`
+    /// - This parameter is available for sources `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -247,7 +336,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `rest`
+    /// - This parameter is available for sources `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -260,12 +349,49 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `rest`
+    /// - This parameter is available for sources `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
     /// - 🌱 Changing the value of this parameter never regenerates embeddings
     pub headers: Setting<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<MediaOptions>)]
+    /// Media to embed alongside the rendered document template, such as images.
+    ///
+    /// The media of each document is read from its `documentField`, either as a base64 string or as an object with
+    /// the `path` of a file of the media directory of the instance.
+    /// The media and the rendered document template are injected into the `indexingFragment`,
+    /// that is in turn injected into the `request` in place of `{{text}}`.
+    /// Search queries are embedded with the `searchFragment`, replacing the media and text with the `media` and `q` of the query.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for source `rest`
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🏗️ Changing the value of this parameter always regenerates embeddings
+    pub media: Setting<MediaOptions>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<bool>)]
+    /// Whether the remote embedder returns one embedding per token rather than one embedding per text.
+    ///
+    /// When enabled, the `{{embedding}}` of the `response` is an array of embeddings of `dimensions` numbers,
+    /// the first 255 of which are stored for each document.
+    /// Queries are embedded the same way, and the documents are scored with MaxSim:
+    /// each query embedding is matched with its most similar document embedding.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for source `rest`
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🏗️ Changing the value of this parameter always regenerates embeddings
+    pub late_interaction: Setting<bool>,
 
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
@@ -316,13 +442,15 @@ pub struct SubEmbeddingSettings {
     #[schema(value_type = Option<String>)]
     /// The name of the model to use.
     ///
+    /// For source `onnx`, the path of the directory containing the model.
+    ///
     /// # Mandatory
     ///
-    /// - This parameter is mandatory for source `ollama`
+    /// - This parameter is mandatory for sources `ollama`, `onnx`
     ///
     /// # Availability
     ///
-    /// - This parameter is available for sources `openAi`, `huggingFace`, `ollama`
+    /// - This parameter is available for sources `openAi`, `huggingFace`, `ollama`, `onnx`
     ///
     /// # 🔄 Reindexing
     ///
@@ -360,7 +488,7 @@ pub struct SubEmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `huggingFace`
+    /// - This parameter is available for sources `huggingFace`, `onnx`
     ///
     /// # 🔄 Reindexing
     ///
@@ -455,6 +583,23 @@ pub struct SubEmbeddingSettings {
     pub document_template_max_bytes: Setting<usize>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
+    #[schema(value_type = Option<Chunking>)]
+    /// How to split rendered texts into several chunks that are embedded separately.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama` and `rest`
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🏗️ Changing the value of this parameter always regenerates embeddings
+    ///
+    /// # Defaults
+    ///
+    /// - Defaults to no chunking, the rendered text is embedded as a whole
+    pub chunking: Setting<Chunking>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
     #[schema(value_type = Option<String>)]
     /// URL to reach the remote embedder.
     ///
@@ -518,6 +663,24 @@ pub struct SubEmbeddingSettings {
     ///
     /// - 🌱 Changing the value of this parameter never regenerates embeddings
     pub headers: Setting<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<MediaOptions>)]
+    /// Media to embed alongside the rendered document template, such as images.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is never available for a sub-embedder
+    pub media: Setting<MediaOptions>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<bool>)]
+    /// Whether the remote embedder returns one embedding per token rather than one embedding per text.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is never available for a sub-embedder
+    pub late_interaction: Setting<bool>,
 
     // The following fields are provided for the sake of improving error handling
     // They should always be set to `NotSet`, otherwise an error will be returned
@@ -531,6 +694,16 @@ pub struct SubEmbeddingSettings {
     #[schema(ignore)]
     pub binary_quantized: Setting<bool>,
 
+    #[serde(default, skip_serializing)]
+    #[deserr(default)]
+    #[schema(ignore)]
+    pub quantization: Setting<VectorQuantization>,
+
+    #[serde(default, skip_serializing)]
+    #[deserr(default)]
+    #[schema(ignore)]
+    pub distance: Setting<VectorDistance>,
+
     #[serde(default, skip_serializing)]
     #[deserr(default)]
     #[schema(ignore)]
@@ -561,7 +734,7 @@ pub enum SettingsDiff {
 
 #[derive(Default, Debug)]
 pub struct EmbedderAction {
-    pub was_quantized: bool,
+    pub old_quantization: VectorQuantization,
     pub is_being_quantized: bool,
     pub write_back: Option<WriteBackToDocuments>,
     pub reindex: Option<ReindexAction>,
@@ -585,17 +758,25 @@ impl EmbedderAction {
         self
     }
 
-    pub fn with_write_back(write_back: WriteBackToDocuments, was_quantized: bool) -> Self {
+    pub fn with_write_back(
+        write_back: WriteBackToDocuments,
+        old_quantization: VectorQuantization,
+    ) -> Self {
         Self {
-            was_quantized,
+            old_quantization,
             is_being_quantized: false,
             write_back: Some(write_back),
             reindex: None,
         }
     }
 
-    pub fn with_reindex(reindex: ReindexAction, was_quantized: bool) -> Self {
-        Self { was_quantized, is_being_quantized: false, write_back: None, reindex: Some(reindex) }
+    pub fn with_reindex(reindex: ReindexAction, old_quantization: VectorQuantization) -> Self {
+        Self {
+            old_quantization,
+            is_being_quantized: false,
+            write_back: None,
+            reindex: Some(reindex),
+        }
     }
 }
 
@@ -628,8 +809,13 @@ impl SettingsDiff {
                     mut indexing_embedder,
                     mut distribution,
                     mut headers,
+                    mut media,
+                    mut late_interaction,
                     mut document_template_max_bytes,
+                    mut chunking,
                     binary_quantized: mut binary_quantize,
+                    mut quantization,
+                    mut distance,
                 } = old;
 
                 let EmbeddingSettings {
@@ -647,8 +833,13 @@ impl SettingsDiff {
                     indexing_embedder: new_indexing_embedder,
                     distribution: new_distribution,
                     headers: new_headers,
+                    media: new_media,
+                    late_interaction: new_late_interaction,
                     document_template_max_bytes: new_document_template_max_bytes,
+                    chunking: new_chunking,
                     binary_quantized: new_binary_quantize,
+                    quantization: new_quantization,
+                    distance: new_distance,
                 } = new;
 
                 if matches!(binary_quantize, Setting::Set(true))
@@ -659,6 +850,23 @@ impl SettingsDiff {
                     });
                 }
 
+                // sparse embeddings are not stored alongside the dense ones
+                let new_source_value = match new_source {
+                    Setting::Set(new_source) => Some(new_source),
+                    Setting::Reset => Some(EmbedderSource::default()),
+                    Setting::NotSet => None,
+                };
+                if let (Setting::Set(old_source), Some(new_source)) = (source, new_source_value) {
+                    if (old_source == EmbedderSource::Sparse)
+                        != (new_source == EmbedderSource::Sparse)
+                    {
+                        return Err(UserError::InvalidSettingsEmbedder {
+                            embedder_name: embedder_name.to_string(),
+                            message: format!("Cannot change the source from `{old_source}` to `{new_source}`.\n - Hint: Add a new embedder that uses the `{new_source}` source and remove this one."),
+                        });
+                    }
+                }
+
                 let mut reindex_action = None;
 
                 Self::apply_and_diff(
@@ -671,10 +879,13 @@ impl SettingsDiff {
                     &mut dimensions,
                     &mut document_template,
                     &mut document_template_max_bytes,
+                    &mut chunking,
                     &mut url,
                     &mut request,
                     &mut response,
                     &mut headers,
+                    &mut media,
+                    &mut late_interaction,
                     new_source,
                     new_model,
                     new_revision,
@@ -683,13 +894,33 @@ impl SettingsDiff {
                     new_dimensions,
                     new_document_template,
                     new_document_template_max_bytes,
+                    new_chunking,
                     new_url,
                     new_request,
                     new_response,
                     new_headers,
+                    new_media,
+                    new_late_interaction,
                 );
 
-                let binary_quantize_changed = binary_quantize.apply(new_binary_quantize);
+                let old_quantization =
+                    VectorQuantization::from_options(quantization.set(), binary_quantize.set());
+                binary_quantize.apply(new_binary_quantize);
+                quantization.apply(new_quantization);
+                let new_quantization =
+                    VectorQuantization::from_options(quantization.set(), binary_quantize.set());
+                if !old_quantization.can_be_converted_to(new_quantization) {
+                    return Err(UserError::InvalidQuantizationChange {
+                        embedder_name: embedder_name.to_string(),
+                        old_quantization,
+                        new_quantization,
+                    });
+                }
+                let quantization_changed = old_quantization != new_quantization;
+
+                if distance.apply(new_distance) {
+                    ReindexAction::push_action(&mut reindex_action, ReindexAction::FullReindex);
+                }
 
                 // changes to the *search* embedder never triggers any reindexing
                 search_embedder.apply(new_search_embedder);
@@ -716,19 +947,22 @@ impl SettingsDiff {
                     indexing_embedder,
                     distribution,
                     headers,
+                    media,
+                    late_interaction,
                     document_template_max_bytes,
+                    chunking,
                     binary_quantized: binary_quantize,
+                    quantization,
+                    distance,
                 };
 
                 match reindex_action {
-                    Some(action) => Self::Reindex {
-                        action,
-                        updated_settings,
-                        quantize: binary_quantize_changed,
-                    },
+                    Some(action) => {
+                        Self::Reindex { action, updated_settings, quantize: quantization_changed }
+                    }
                     None => Self::UpdateWithoutReindex {
                         updated_settings,
-                        quantize: binary_quantize_changed,
+                        quantize: quantization_changed,
                     },
                 }
             }
@@ -756,13 +990,18 @@ impl SettingsDiff {
                     mut dimensions,
                     mut document_template,
                     mut document_template_max_bytes,
+                    mut chunking,
                     mut url,
                     mut request,
                     mut response,
                     mut headers,
+                    mut media,
+                    mut late_interaction,
                     // phony settings
                     mut distribution,
                     mut binary_quantized,
+                    mut quantization,
+                    mut distance,
                     mut search_embedder,
                     mut indexing_embedder,
                 }) = sub_embedder
@@ -781,12 +1020,17 @@ impl SettingsDiff {
                     dimensions: new_dimensions,
                     document_template: new_document_template,
                     document_template_max_bytes: new_document_template_max_bytes,
+                    chunking: new_chunking,
                     url: new_url,
                     request: new_request,
                     response: new_response,
                     headers: new_headers,
+                    media: new_media,
+                    late_interaction: new_late_interaction,
                     distribution: new_distribution,
                     binary_quantized: new_binary_quantized,
+                    quantization: new_quantization,
+                    distance: new_distance,
                     search_embedder: new_search_embedder,
                     indexing_embedder: new_indexing_embedder,
                 } = new_sub_embedder;
@@ -801,10 +1045,13 @@ impl SettingsDiff {
                     &mut dimensions,
                     &mut document_template,
                     &mut document_template_max_bytes,
+                    &mut chunking,
                     &mut url,
                     &mut request,
                     &mut response,
                     &mut headers,
+                    &mut media,
+                    &mut late_interaction,
                     new_source,
                     new_model,
                     new_revision,
@@ -813,15 +1060,20 @@ impl SettingsDiff {
                     new_dimensions,
                     new_document_template,
                     new_document_template_max_bytes,
+                    new_chunking,
                     new_url,
                     new_request,
                     new_response,
                     new_headers,
+                    new_media,
+                    new_late_interaction,
                 );
 
                 // update phony settings, it is always an error to have them set.
                 distribution.apply(new_distribution);
                 binary_quantized.apply(new_binary_quantized);
+                quantization.apply(new_quantization);
+                distance.apply(new_distance);
                 search_embedder.apply(new_search_embedder);
                 indexing_embedder.apply(new_indexing_embedder);
 
@@ -837,9 +1089,14 @@ impl SettingsDiff {
                     request,
                     response,
                     headers,
+                    media,
+                    late_interaction,
                     document_template_max_bytes,
+                    chunking,
                     distribution,
                     binary_quantized,
+                    quantization,
+                    distance,
                     search_embedder,
                     indexing_embedder,
                 };
@@ -862,10 +1119,13 @@ impl SettingsDiff {
         dimensions: &mut Setting<usize>,
         document_template: &mut Setting<String>,
         document_template_max_bytes: &mut Setting<usize>,
+        chunking: &mut Setting<Chunking>,
         url: &mut Setting<String>,
         request: &mut Setting<serde_json::Value>,
         response: &mut Setting<serde_json::Value>,
         headers: &mut Setting<BTreeMap<String, String>>,
+        media: &mut Setting<MediaOptions>,
+        late_interaction: &mut Setting<bool>,
         new_source: Setting<EmbedderSource>,
         new_model: Setting<String>,
         new_revision: Setting<String>,
@@ -874,10 +1134,13 @@ impl SettingsDiff {
         new_dimensions: Setting<usize>,
         new_document_template: Setting<String>,
         new_document_template_max_bytes: Setting<usize>,
+        new_chunking: Setting<Chunking>,
         new_url: Setting<String>,
         new_request: Setting<serde_json::Value>,
         new_response: Setting<serde_json::Value>,
         new_headers: Setting<BTreeMap<String, String>>,
+        new_media: Setting<MediaOptions>,
+        new_late_interaction: Setting<bool>,
     ) {
         // **Warning**: do not use short-circuiting || here, we want all these operations applied
         if source.apply(new_source) {
@@ -894,7 +1157,10 @@ impl SettingsDiff {
                 response,
                 document_template,
                 document_template_max_bytes,
+                chunking,
                 headers,
+                media,
+                late_interaction,
                 // send dummy values, the source cannot recursively be composite
                 &mut Setting::NotSet,
                 &mut Setting::NotSet,
@@ -953,6 +1219,16 @@ impl SettingsDiff {
             }
         }
 
+        if chunking.apply(new_chunking) {
+            ReindexAction::push_action(reindex_action, ReindexAction::FullReindex);
+        }
+        if media.apply(new_media) {
+            ReindexAction::push_action(reindex_action, ReindexAction::FullReindex);
+        }
+        if late_interaction.apply(new_late_interaction) {
+            ReindexAction::push_action(reindex_action, ReindexAction::FullReindex);
+        }
+
         api_key.apply(new_api_key);
         headers.apply(new_headers);
     }
@@ -980,7 +1256,10 @@ fn apply_default_for_source(
     response: &mut Setting<serde_json::Value>,
     document_template: &mut Setting<String>,
     document_template_max_bytes: &mut Setting<usize>,
+    chunking: &mut Setting<Chunking>,
     headers: &mut Setting<BTreeMap<String, String>>,
+    media: &mut Setting<MediaOptions>,
+    late_interaction: &mut Setting<bool>,
     search_embedder: &mut Setting<SubEmbeddingSettings>,
     indexing_embedder: &mut Setting<SubEmbeddingSettings>,
 ) {
@@ -994,6 +1273,8 @@ fn apply_default_for_source(
             *request = Setting::NotSet;
             *response = Setting::NotSet;
             *headers = Setting::NotSet;
+            *media = Setting::NotSet;
+            *late_interaction = Setting::NotSet;
             *search_embedder = Setting::NotSet;
             *indexing_embedder = Setting::NotSet;
         }
@@ -1006,6 +1287,8 @@ fn apply_default_for_source(
             *request = Setting::NotSet;
             *response = Setting::NotSet;
             *headers = Setting::NotSet;
+            *media = Setting::NotSet;
+            *late_interaction = Setting::NotSet;
             *search_embedder = Setting::NotSet;
             *indexing_embedder = Setting::NotSet;
         }
@@ -1018,6 +1301,8 @@ fn apply_default_for_source(
             *request = Setting::NotSet;
             *response = Setting::NotSet;
             *headers = Setting::NotSet;
+            *media = Setting::NotSet;
+            *late_interaction = Setting::NotSet;
             *search_embedder = Setting::NotSet;
             *indexing_embedder = Setting::NotSet;
         }
@@ -1030,6 +1315,8 @@ fn apply_default_for_source(
             *request = Setting::Reset;
             *response = Setting::Reset;
             *headers = Setting::Reset;
+            *media = Setting::Reset;
+            *late_interaction = Setting::Reset;
             *search_embedder = Setting::NotSet;
             *indexing_embedder = Setting::NotSet;
         }
@@ -1043,7 +1330,38 @@ fn apply_default_for_source(
             *response = Setting::NotSet;
             *document_template = Setting::NotSet;
             *document_template_max_bytes = Setting::NotSet;
+            *chunking = Setting::NotSet;
             *headers = Setting::NotSet;
+            *media = Setting::NotSet;
+            *late_interaction = Setting::NotSet;
+            *search_embedder = Setting::NotSet;
+            *indexing_embedder = Setting::NotSet;
+        }
+        Setting::Set(EmbedderSource::Sparse) => {
+            *model = Setting::NotSet;
+            *revision = Setting::NotSet;
+            *pooling = Setting::NotSet;
+            *dimensions = Setting::NotSet;
+            *url = Setting::Reset;
+            *request = Setting::Reset;
+            *response = Setting::Reset;
+            *headers = Setting::Reset;
+            *media = Setting::NotSet;
+            *late_interaction = Setting::NotSet;
+            *search_embedder = Setting::NotSet;
+            *indexing_embedder = Setting::NotSet;
+        }
+        Setting::Set(EmbedderSource::Onnx) => {
+            *model = Setting::Reset;
+            *revision = Setting::NotSet;
+            *pooling = Setting::Reset;
+            *dimensions = Setting::NotSet;
+            *url = Setting::NotSet;
+            *request = Setting::NotSet;
+            *response = Setting::NotSet;
+            *headers = Setting::NotSet;
+            *media = Setting::NotSet;
+            *late_interaction = Setting::NotSet;
             *search_embedder = Setting::NotSet;
             *indexing_embedder = Setting::NotSet;
         }
@@ -1057,7 +1375,10 @@ fn apply_default_for_source(
             *response = Setting::NotSet;
             *document_template = Setting::NotSet;
             *document_template_max_bytes = Setting::NotSet;
+            *chunking = Setting::NotSet;
             *headers = Setting::NotSet;
+            *media = Setting::NotSet;
+            *late_interaction = Setting::NotSet;
             *search_embedder = Setting::Reset;
             *indexing_embedder = Setting::Reset;
         }
@@ -1118,14 +1439,19 @@ pub enum MetaEmbeddingSetting {
     Dimensions,
     DocumentTemplate,
     DocumentTemplateMaxBytes,
+    Chunking,
     Url,
     Request,
     Response,
     Headers,
+    Media,
+    LateInteraction,
     SearchEmbedder,
     IndexingEmbedder,
     Distribution,
     BinaryQuantized,
+    Quantization,
+    Distance,
 }
 
 impl MetaEmbeddingSetting {
@@ -1140,14 +1466,19 @@ impl MetaEmbeddingSetting {
             Dimensions => "dimensions",
             DocumentTemplate => "documentTemplate",
             DocumentTemplateMaxBytes => "documentTemplateMaxBytes",
+            Chunking => "chunking",
             Url => "url",
             Request => "request",
             Response => "response",
             Headers => "headers",
+            Media => "media",
+            LateInteraction => "lateInteraction",
             SearchEmbedder => "searchEmbedder",
             IndexingEmbedder => "indexingEmbedder",
             Distribution => "distribution",
             BinaryQuantized => "binaryQuantized",
+            Quantization => "quantization",
+            Distance => "distance",
         }
     }
 }
@@ -1168,10 +1499,15 @@ impl EmbeddingSettings {
         response: &Setting<serde_json::Value>,
         document_template: &Setting<String>,
         document_template_max_bytes: &Setting<usize>,
+        chunking: &Setting<Chunking>,
         headers: &Setting<BTreeMap<String, String>>,
+        media: &Setting<MediaOptions>,
+        late_interaction: &Setting<bool>,
         search_embedder: &Setting<SubEmbeddingSettings>,
         indexing_embedder: &Setting<SubEmbeddingSettings>,
         binary_quantized: &Setting<bool>,
+        quantization: &Setting<VectorQuantization>,
+        distance: &Setting<VectorDistance>,
         distribution: &Setting<DistributionShift>,
     ) -> Result<(), UserError> {
         Self::check_setting(embedder_name, source, MetaEmbeddingSetting::Model, context, model)?;
@@ -1226,6 +1562,13 @@ impl EmbeddingSettings {
             context,
             document_template_max_bytes,
         )?;
+        Self::check_setting(
+            embedder_name,
+            source,
+            MetaEmbeddingSetting::Chunking,
+            context,
+            chunking,
+        )?;
         Self::check_setting(
             embedder_name,
             source,
@@ -1233,6 +1576,14 @@ impl EmbeddingSettings {
             context,
             headers,
         )?;
+        Self::check_setting(embedder_name, source, MetaEmbeddingSetting::Media, context, media)?;
+        Self::check_setting(
+            embedder_name,
+            source,
+            MetaEmbeddingSetting::LateInteraction,
+            context,
+            late_interaction,
+        )?;
         Self::check_setting(
             embedder_name,
             source,
@@ -1254,6 +1605,20 @@ impl EmbeddingSettings {
             context,
             binary_quantized,
         )?;
+        Self::check_setting(
+            embedder_name,
+            source,
+            MetaEmbeddingSetting::Quantization,
+            context,
+            quantization,
+        )?;
+        Self::check_setting(
+            embedder_name,
+            source,
+            MetaEmbeddingSetting::Distance,
+            context,
+            distance,
+        )?;
         Self::check_setting(
             embedder_name,
             source,
@@ -1320,9 +1685,19 @@ impl EmbeddingSettings {
         use MetaEmbeddingSetting::*;
         use NestingContext::*;
         match (source, field, context) {
-            (_, Distribution | BinaryQuantized, NotNested) => FieldStatus::Allowed,
-            (_, Distribution | BinaryQuantized, _) => FieldStatus::Disallowed,
-            (_, DocumentTemplate | DocumentTemplateMaxBytes, Search) => FieldStatus::Disallowed,
+            // sparse embedders cannot be nested
+            (Sparse, _, Search | Indexing) => FieldStatus::Disallowed,
+            (Sparse, BinaryQuantized | Quantization | Distance, _) => FieldStatus::Disallowed,
+            (_, Distribution | BinaryQuantized | Quantization | Distance, NotNested) => {
+                FieldStatus::Allowed
+            }
+            (_, Distribution | BinaryQuantized | Quantization | Distance, _) => {
+                FieldStatus::Disallowed
+            }
+            (_, DocumentTemplate | DocumentTemplateMaxBytes | Chunking, Search) => {
+                FieldStatus::Disallowed
+            }
+            (_, Media | LateInteraction, Search | Indexing) => FieldStatus::Disallowed,
             (
                 OpenAi,
                 Source
@@ -1330,37 +1705,50 @@ impl EmbeddingSettings {
                 | ApiKey
                 | DocumentTemplate
                 | DocumentTemplateMaxBytes
+                | Chunking
                 | Dimensions
                 | Url,
                 _,
             ) => FieldStatus::Allowed,
             (
                 OpenAi,
-                Revision | Pooling | Request | Response | Headers | SearchEmbedder
-                | IndexingEmbedder,
+                Revision | Pooling | Request | Response | Headers | Media | LateInteraction
+                | SearchEmbedder | IndexingEmbedder,
                 _,
             ) => FieldStatus::Disallowed,
             (
                 HuggingFace,
-                Source | Model | Revision | Pooling | DocumentTemplate | DocumentTemplateMaxBytes,
+                Source
+                | Model
+                | Revision
+                | Pooling
+                | DocumentTemplate
+                | DocumentTemplateMaxBytes
+                | Chunking,
                 _,
             ) => FieldStatus::Allowed,
             (
                 HuggingFace,
-                ApiKey | Dimensions | Url | Request | Response | Headers | SearchEmbedder
-                | IndexingEmbedder,
+                ApiKey | Dimensions | Url | Request | Response | Headers | Media | LateInteraction
+                | SearchEmbedder | IndexingEmbedder,
                 _,
             ) => FieldStatus::Disallowed,
             (Ollama, Model, _) => FieldStatus::Mandatory,
             (
                 Ollama,
-                Source | DocumentTemplate | DocumentTemplateMaxBytes | Url | ApiKey | Dimensions,
+                Source
+                | DocumentTemplate
+                | DocumentTemplateMaxBytes
+                | Chunking
+                | Url
+                | ApiKey
+                | Dimensions,
                 _,
             ) => FieldStatus::Allowed,
             (
                 Ollama,
-                Revision | Pooling | Request | Response | Headers | SearchEmbedder
-                | IndexingEmbedder,
+                Revision | Pooling | Request | Response | Headers | Media | LateInteraction
+                | SearchEmbedder | IndexingEmbedder,
                 _,
             ) => FieldStatus::Disallowed,
             (UserProvided, Dimensions, _) => FieldStatus::Mandatory,
@@ -1373,10 +1761,13 @@ impl EmbeddingSettings {
                 | ApiKey
                 | DocumentTemplate
                 | DocumentTemplateMaxBytes
+                | Chunking
                 | Url
                 | Request
                 | Response
                 | Headers
+                | Media
+                | LateInteraction
                 | SearchEmbedder
                 | IndexingEmbedder,
                 _,
@@ -1389,12 +1780,46 @@ impl EmbeddingSettings {
                 | Dimensions
                 | DocumentTemplate
                 | DocumentTemplateMaxBytes
-                | Headers,
+                | Chunking
+                | Headers
+                | Media
+                | LateInteraction,
                 _,
             ) => FieldStatus::Allowed,
             (Rest, Model | Revision | Pooling | SearchEmbedder | IndexingEmbedder, _) => {
                 FieldStatus::Disallowed
             }
+            (
+                Sparse,
+                Source
+                | ApiKey
+                | DocumentTemplate
+                | DocumentTemplateMaxBytes
+                | Chunking
+                | Url
+                | Request
+                | Response
+                | Headers,
+                _,
+            ) => FieldStatus::Allowed,
+            (
+                Sparse,
+                Model | Revision | Pooling | Dimensions | Media | LateInteraction | SearchEmbedder
+                | IndexingEmbedder,
+                _,
+            ) => FieldStatus::Disallowed,
+            (Onnx, Model, _) => FieldStatus::Mandatory,
+            (
+                Onnx,
+                Source | Pooling | DocumentTemplate | DocumentTemplateMaxBytes | Chunking,
+                _,
+            ) => FieldStatus::Allowed,
+            (
+                Onnx,
+                Revision | ApiKey | Dimensions | Url | Request | Response | Headers | Media
+                | LateInteraction | SearchEmbedder | IndexingEmbedder,
+                _,
+            ) => FieldStatus::Disallowed,
             (Composite, SearchEmbedder | IndexingEmbedder, _) => FieldStatus::Mandatory,
             (Composite, Source, _) => FieldStatus::Allowed,
             (
@@ -1406,10 +1831,13 @@ impl EmbeddingSettings {
                 | Dimensions
                 | DocumentTemplate
                 | DocumentTemplateMaxBytes
+                | Chunking
                 | Url
                 | Request
                 | Response
-                | Headers,
+                | Headers
+                | Media
+                | LateInteraction,
                 _,
             ) => FieldStatus::Disallowed,
         }
@@ -1445,7 +1873,7 @@ impl EmbeddingSettings {
             (NestingContext::NotNested, _) => Ok(()),
             (
                 NestingContext::Search | NestingContext::Indexing,
-                EmbedderSource::Composite | EmbedderSource::UserProvided,
+                EmbedderSource::Composite | EmbedderSource::UserProvided | EmbedderSource::Sparse,
             ) => Err(UserError::InvalidSourceForNested {
                 embedder_name: context.embedder_name_with_context(embedder_name),
                 source_: source,
@@ -1455,7 +1883,8 @@ impl EmbeddingSettings {
                 EmbedderSource::OpenAi
                 | EmbedderSource::HuggingFace
                 | EmbedderSource::Ollama
-                | EmbedderSource::Rest,
+                | EmbedderSource::Rest
+                | EmbedderSource::Onnx,
             ) => Ok(()),
         }
     }
@@ -1484,6 +1913,8 @@ pub enum EmbedderSource {
     UserProvided,
     Rest,
     Composite,
+    Sparse,
+    Onnx,
 }
 
 impl std::fmt::Display for EmbedderSource {
@@ -1495,6 +1926,8 @@ impl std::fmt::Display for EmbedderSource {
             EmbedderSource::Ollama => "ollama",
             EmbedderSource::Rest => "rest",
             EmbedderSource::Composite => "composite",
+            EmbedderSource::Sparse => "sparse",
+            EmbedderSource::Onnx => "onnx",
         };
         f.write_str(s)
     }
@@ -1510,7 +1943,10 @@ impl EmbeddingSettings {
     }: super::hf::EmbedderOptions,
         document_template: Setting<String>,
         document_template_max_bytes: Setting<usize>,
+        chunking: Setting<Chunking>,
         quantized: Option<bool>,
+        quantization: Option<VectorQuantization>,
+        distance: Option<VectorDistance>,
     ) -> Self {
         Self {
             source: Setting::Set(EmbedderSource::HuggingFace),
@@ -1521,14 +1957,53 @@ impl EmbeddingSettings {
             dimensions: Setting::NotSet,
             document_template,
             document_template_max_bytes,
+            chunking,
+            url: Setting::NotSet,
+            request: Setting::NotSet,
+            response: Setting::NotSet,
+            headers: Setting::NotSet,
+            media: Setting::NotSet,
+            late_interaction: Setting::NotSet,
+            search_embedder: Setting::NotSet,
+            indexing_embedder: Setting::NotSet,
+            distribution: Setting::some_or_not_set(distribution),
+            binary_quantized: Setting::some_or_not_set(quantized),
+            quantization: Setting::some_or_not_set(quantization),
+            distance: Setting::some_or_not_set(distance),
+        }
+    }
+
+    fn from_onnx(
+        super::onnx::EmbedderOptions { model, distribution, pooling }: super::onnx::EmbedderOptions,
+        document_template: Setting<String>,
+        document_template_max_bytes: Setting<usize>,
+        chunking: Setting<Chunking>,
+        quantized: Option<bool>,
+        quantization: Option<VectorQuantization>,
+        distance: Option<VectorDistance>,
+    ) -> Self {
+        Self {
+            source: Setting::Set(EmbedderSource::Onnx),
+            model: Setting::Set(model),
+            revision: Setting::NotSet,
+            pooling: Setting::Set(pooling),
+            api_key: Setting::NotSet,
+            dimensions: Setting::NotSet,
+            document_template,
+            document_template_max_bytes,
+            chunking,
             url: Setting::NotSet,
             request: Setting::NotSet,
             response: Setting::NotSet,
             headers: Setting::NotSet,
+            media: Setting::NotSet,
+            late_interaction: Setting::NotSet,
             search_embedder: Setting::NotSet,
             indexing_embedder: Setting::NotSet,
             distribution: Setting::some_or_not_set(distribution),
             binary_quantized: Setting::some_or_not_set(quantized),
+            quantization: Setting::some_or_not_set(quantization),
+            distance: Setting::some_or_not_set(distance),
         }
     }
 
@@ -1542,7 +2017,10 @@ impl EmbeddingSettings {
         }: super::openai::EmbedderOptions,
         document_template: Setting<String>,
         document_template_max_bytes: Setting<usize>,
+        chunking: Setting<Chunking>,
         quantized: Option<bool>,
+        quantization: Option<VectorQuantization>,
+        distance: Option<VectorDistance>,
     ) -> Self {
         Self {
             source: Setting::Set(EmbedderSource::OpenAi),
@@ -1553,14 +2031,19 @@ impl EmbeddingSettings {
             dimensions: Setting::some_or_not_set(dimensions),
             document_template,
             document_template_max_bytes,
+            chunking,
             url: Setting::some_or_not_set(url),
             request: Setting::NotSet,
             response: Setting::NotSet,
             headers: Setting::NotSet,
+            media: Setting::NotSet,
+            late_interaction: Setting::NotSet,
             search_embedder: Setting::NotSet,
             indexing_embedder: Setting::NotSet,
             distribution: Setting::some_or_not_set(distribution),
             binary_quantized: Setting::some_or_not_set(quantized),
+            quantization: Setting::some_or_not_set(quantization),
+            distance: Setting::some_or_not_set(distance),
         }
     }
 
@@ -1574,7 +2057,10 @@ impl EmbeddingSettings {
         }: super::ollama::EmbedderOptions,
         document_template: Setting<String>,
         document_template_max_bytes: Setting<usize>,
+        chunking: Setting<Chunking>,
         quantized: Option<bool>,
+        quantization: Option<VectorQuantization>,
+        distance: Option<VectorDistance>,
     ) -> Self {
         Self {
             source: Setting::Set(EmbedderSource::Ollama),
@@ -1585,20 +2071,27 @@ impl EmbeddingSettings {
             dimensions: Setting::some_or_not_set(dimensions),
             document_template,
             document_template_max_bytes,
+            chunking,
             url: Setting::some_or_not_set(url),
             request: Setting::NotSet,
             response: Setting::NotSet,
             headers: Setting::NotSet,
+            media: Setting::NotSet,
+            late_interaction: Setting::NotSet,
             search_embedder: Setting::NotSet,
             indexing_embedder: Setting::NotSet,
             distribution: Setting::some_or_not_set(distribution),
             binary_quantized: Setting::some_or_not_set(quantized),
+            quantization: Setting::some_or_not_set(quantization),
+            distance: Setting::some_or_not_set(distance),
         }
     }
 
     fn from_user_provided(
         super::manual::EmbedderOptions { dimensions, distribution }: super::manual::EmbedderOptions,
         quantized: Option<bool>,
+        quantization: Option<VectorQuantization>,
+        distance: Option<VectorDistance>,
     ) -> Self {
         Self {
             source: Setting::Set(EmbedderSource::UserProvided),
@@ -1609,14 +2102,19 @@ impl EmbeddingSettings {
             dimensions: Setting::Set(dimensions),
             document_template: Setting::NotSet,
             document_template_max_bytes: Setting::NotSet,
+            chunking: Setting::NotSet,
             url: Setting::NotSet,
             request: Setting::NotSet,
             response: Setting::NotSet,
             headers: Setting::NotSet,
+            media: Setting::NotSet,
+            late_interaction: Setting::NotSet,
             search_embedder: Setting::NotSet,
             indexing_embedder: Setting::NotSet,
             distribution: Setting::some_or_not_set(distribution),
             binary_quantized: Setting::some_or_not_set(quantized),
+            quantization: Setting::some_or_not_set(quantization),
+            distance: Setting::some_or_not_set(distance),
         }
     }
 
@@ -1629,10 +2127,15 @@ impl EmbeddingSettings {
             response,
             distribution,
             headers,
+            media,
+            late_interaction,
         }: super::rest::EmbedderOptions,
         document_template: Setting<String>,
         document_template_max_bytes: Setting<usize>,
+        chunking: Setting<Chunking>,
         quantized: Option<bool>,
+        quantization: Option<VectorQuantization>,
+        distance: Option<VectorDistance>,
     ) -> Self {
         Self {
             source: Setting::Set(EmbedderSource::Rest),
@@ -1643,50 +2146,131 @@ impl EmbeddingSettings {
             dimensions: Setting::some_or_not_set(dimensions),
             document_template,
             document_template_max_bytes,
+            chunking,
             url: Setting::Set(url),
             request: Setting::Set(request),
             response: Setting::Set(response),
             distribution: Setting::some_or_not_set(distribution),
             headers: Setting::Set(headers),
+            media: Setting::some_or_not_set(media),
+            late_interaction: Setting::some_or_not_set(late_interaction),
             search_embedder: Setting::NotSet,
             indexing_embedder: Setting::NotSet,
             binary_quantized: Setting::some_or_not_set(quantized),
+            quantization: Setting::some_or_not_set(quantization),
+            distance: Setting::some_or_not_set(distance),
+        }
+    }
+
+    fn from_sparse(
+        super::sparse::EmbedderOptions { rest, distribution }: super::sparse::EmbedderOptions,
+        document_template: Setting<String>,
+        document_template_max_bytes: Setting<usize>,
+        chunking: Setting<Chunking>,
+    ) -> Self {
+        let source = Setting::Set(EmbedderSource::Sparse);
+        let distribution = Setting::some_or_not_set(distribution);
+        let Some(rest) = rest else {
+            return Self {
+                source,
+                model: Setting::NotSet,
+                revision: Setting::NotSet,
+                pooling: Setting::NotSet,
+                api_key: Setting::NotSet,
+                dimensions: Setting::NotSet,
+                document_template: Setting::NotSet,
+                document_template_max_bytes: Setting::NotSet,
+                chunking: Setting::NotSet,
+                url: Setting::NotSet,
+                request: Setting::NotSet,
+                response: Setting::NotSet,
+                headers: Setting::NotSet,
+                media: Setting::NotSet,
+                late_interaction: Setting::NotSet,
+                search_embedder: Setting::NotSet,
+                indexing_embedder: Setting::NotSet,
+                distribution,
+                binary_quantized: Setting::NotSet,
+                quantization: Setting::NotSet,
+                distance: Setting::NotSet,
+            };
+        };
+        Self {
+            source,
+            distribution,
+            ..Self::from_rest(
+                rest,
+                document_template,
+                document_template_max_bytes,
+                chunking,
+                None,
+                None,
+                None,
+            )
         }
     }
 }
 
 impl From<EmbeddingConfig> for EmbeddingSettings {
     fn from(value: EmbeddingConfig) -> Self {
-        let EmbeddingConfig { embedder_options, prompt, quantized } = value;
+        let EmbeddingConfig { embedder_options, prompt, quantized, quantization, distance } = value;
         let document_template_max_bytes =
             Setting::Set(prompt.max_bytes.unwrap_or(default_max_bytes()).get());
+        let chunking = Setting::some_or_not_set(prompt.chunking);
         match embedder_options {
             super::EmbedderOptions::HuggingFace(options) => Self::from_hugging_face(
                 options,
                 Setting::Set(prompt.template),
                 document_template_max_bytes,
+                chunking,
                 quantized,
+                quantization,
+                distance,
             ),
             super::EmbedderOptions::OpenAi(options) => Self::from_openai(
                 options,
                 Setting::Set(prompt.template),
                 document_template_max_bytes,
+                chunking,
                 quantized,
+                quantization,
+                distance,
             ),
             super::EmbedderOptions::Ollama(options) => Self::from_ollama(
                 options,
                 Setting::Set(prompt.template),
                 document_template_max_bytes,
+                chunking,
                 quantized,
+                quantization,
+                distance,
             ),
             super::EmbedderOptions::UserProvided(options) => {
-                Self::from_user_provided(options, quantized)
+                Self::from_user_provided(options, quantized, quantization, distance)
             }
             super::EmbedderOptions::Rest(options) => Self::from_rest(
                 options,
                 Setting::Set(prompt.template),
                 document_template_max_bytes,
+                chunking,
+                quantized,
+                quantization,
+                distance,
+            ),
+            super::EmbedderOptions::Onnx(options) => Self::from_onnx(
+                options,
+                Setting::Set(prompt.template),
+                document_template_max_bytes,
+                chunking,
                 quantized,
+                quantization,
+                distance,
+            ),
+            super::EmbedderOptions::Sparse(options) => Self::from_sparse(
+                options,
+                Setting::Set(prompt.template),
+                document_template_max_bytes,
+                chunking,
             ),
             super::EmbedderOptions::Composite(super::composite::EmbedderOptions {
                 search,
@@ -1699,22 +2283,29 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 api_key: Setting::NotSet,
                 dimensions: Setting::NotSet,
                 binary_quantized: Setting::some_or_not_set(quantized),
+                quantization: Setting::some_or_not_set(quantization),
+                distance: Setting::some_or_not_set(distance),
                 document_template: Setting::NotSet,
                 document_template_max_bytes: Setting::NotSet,
+                chunking: Setting::NotSet,
                 url: Setting::NotSet,
                 request: Setting::NotSet,
                 response: Setting::NotSet,
                 headers: Setting::NotSet,
+                media: Setting::NotSet,
+                late_interaction: Setting::NotSet,
                 distribution: Setting::some_or_not_set(search.distribution()),
                 search_embedder: Setting::Set(SubEmbeddingSettings::from_options(
                     search,
                     Setting::NotSet,
                     Setting::NotSet,
+                    Setting::NotSet,
                 )),
                 indexing_embedder: Setting::Set(SubEmbeddingSettings::from_options(
                     index,
                     Setting::Set(prompt.template),
                     document_template_max_bytes,
+                    chunking,
                 )),
             },
         }
@@ -1726,6 +2317,7 @@ impl SubEmbeddingSettings {
         options: SubEmbedderOptions,
         document_template: Setting<String>,
         document_template_max_bytes: Setting<usize>,
+        chunking: Setting<Chunking>,
     ) -> Self {
         let settings = match options {
             SubEmbedderOptions::HuggingFace(embedder_options) => {
@@ -1733,6 +2325,9 @@ impl SubEmbeddingSettings {
                     embedder_options,
                     document_template,
                     document_template_max_bytes,
+                    chunking,
+                    None,
+                    None,
                     None,
                 )
             }
@@ -1740,21 +2335,39 @@ impl SubEmbeddingSettings {
                 embedder_options,
                 document_template,
                 document_template_max_bytes,
+                chunking,
+                None,
+                None,
                 None,
             ),
             SubEmbedderOptions::Ollama(embedder_options) => EmbeddingSettings::from_ollama(
                 embedder_options,
                 document_template,
                 document_template_max_bytes,
+                chunking,
+                None,
+                None,
                 None,
             ),
             SubEmbedderOptions::UserProvided(embedder_options) => {
-                EmbeddingSettings::from_user_provided(embedder_options, None)
+                EmbeddingSettings::from_user_provided(embedder_options, None, None, None)
             }
             SubEmbedderOptions::Rest(embedder_options) => EmbeddingSettings::from_rest(
                 embedder_options,
                 document_template,
                 document_template_max_bytes,
+                chunking,
+                None,
+                None,
+                None,
+            ),
+            SubEmbedderOptions::Onnx(embedder_options) => EmbeddingSettings::from_onnx(
+                embedder_options,
+                document_template,
+                document_template_max_bytes,
+                chunking,
+                None,
+                None,
                 None,
             ),
         };
@@ -1773,11 +2386,16 @@ impl From<EmbeddingSettings> for SubEmbeddingSettings {
             dimensions,
             document_template,
             document_template_max_bytes,
+            chunking,
             url,
             request,
             response,
             headers,
+            media,
+            late_interaction,
             binary_quantized: _,
+            quantization: _,
+            distance: _,
             search_embedder: _,
             indexing_embedder: _,
             distribution: _,
@@ -1791,12 +2409,17 @@ impl From<EmbeddingSettings> for SubEmbeddingSettings {
             dimensions,
             document_template,
             document_template_max_bytes,
+            chunking,
             url,
             request,
             response,
             headers,
+            media,
+            late_interaction,
             distribution: Setting::NotSet,
             binary_quantized: Setting::NotSet,
+            quantization: Setting::NotSet,
+            distance: Setting::NotSet,
             search_embedder: Setting::NotSet,
             indexing_embedder: Setting::NotSet,
         }
@@ -1815,29 +2438,43 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
             dimensions,
             document_template,
             document_template_max_bytes,
+            chunking,
             url,
             request,
             response,
             distribution,
             headers,
+            media,
+            late_interaction,
             binary_quantized,
+            quantization,
+            distance,
             search_embedder,
             mut indexing_embedder,
         } = value;
 
         this.quantized = binary_quantized.set();
-        if let Some((template, document_template_max_bytes)) =
+        this.quantization = quantization.set();
+        this.distance = distance.set();
+        if let Some((template, document_template_max_bytes, chunking)) =
             match (document_template, &mut indexing_embedder) {
-                (Setting::Set(template), _) => Some((template, document_template_max_bytes)),
+                (Setting::Set(template), _) => {
+                    Some((template, document_template_max_bytes, chunking))
+                }
                 // retrieve the prompt from the indexing embedder in case of a composite embedder
                 (
                     _,
                     Setting::Set(SubEmbeddingSettings {
                         document_template: Setting::Set(document_template),
                         document_template_max_bytes,
+                        chunking,
                         ..
                     }),
-                ) => Some((std::mem::take(document_template), *document_template_max_bytes)),
+                ) => Some((
+                    std::mem::take(document_template),
+                    *document_template_max_bytes,
+                    *chunking,
+                )),
                 _ => None,
             }
         {
@@ -1846,7 +2483,8 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                 .and_then(NonZeroUsize::new)
                 .unwrap_or(default_max_bytes());
 
-            this.prompt = PromptData { template, max_bytes: Some(max_bytes) }
+            this.prompt =
+                PromptData { template, max_bytes: Some(max_bytes), chunking: chunking.set() }
         }
 
         if let Some(source) = source.set() {
@@ -1870,10 +2508,31 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                     request.set().unwrap(),
                     response.set().unwrap(),
                     headers,
+                    media,
+                    late_interaction,
                     dimensions,
                     distribution,
                 )
                 .into(),
+                EmbedderSource::Onnx => {
+                    SubEmbedderOptions::onnx(model.set().unwrap(), pooling, distribution).into()
+                }
+                EmbedderSource::Sparse => {
+                    super::EmbedderOptions::Sparse(super::sparse::EmbedderOptions {
+                        rest: url.set().map(|url| super::rest::EmbedderOptions {
+                            api_key: api_key.set(),
+                            distribution: None,
+                            dimensions: None,
+                            url,
+                            request: request.set().unwrap(),
+                            response: response.set().unwrap(),
+                            headers: headers.set().unwrap_or_default(),
+                            media: None,
+                            late_interaction: None,
+                        }),
+                        distribution: distribution.set(),
+                    })
+                }
                 EmbedderSource::Composite => {
                     super::EmbedderOptions::Composite(super::composite::EmbedderOptions {
                         // it is important to give the distribution to the search here, as this is from where we'll retrieve it
@@ -1909,13 +2568,18 @@ impl SubEmbedderOptions {
             // retrieved by the EmbeddingConfig
             document_template: _,
             document_template_max_bytes: _,
+            chunking: _,
             url,
             request,
             response,
             headers,
+            media,
+            late_interaction,
             // phony parameters
             distribution: _,
             binary_quantized: _,
+            quantization: _,
+            distance: _,
             search_embedder: _,
             indexing_embedder: _,
         } = settings;
@@ -1935,10 +2599,14 @@ impl SubEmbedderOptions {
                 request.set().unwrap(),
                 response.set().unwrap(),
                 headers,
+                media,
+                late_interaction,
                 dimensions,
                 distribution,
             ),
+            EmbedderSource::Onnx => Self::onnx(model.set().unwrap(), pooling, distribution),
             EmbedderSource::Composite => panic!("nested composite embedders"),
+            EmbedderSource::Sparse => panic!("nested sparse embedders"),
         }
     }
 
@@ -1992,6 +2660,17 @@ impl SubEmbedderOptions {
         options.distribution = distribution.set();
         SubEmbedderOptions::HuggingFace(options)
     }
+    fn onnx(
+        model: String,
+        pooling: Setting<OverridePooling>,
+        distribution: Setting<DistributionShift>,
+    ) -> Self {
+        Self::Onnx(super::onnx::EmbedderOptions {
+            model,
+            distribution: distribution.set(),
+            pooling: pooling.set().unwrap_or(OverridePooling::UseModel),
+        })
+    }
     fn user_provided(dimensions: usize, distribution: Setting<DistributionShift>) -> Self {
         Self::UserProvided(super::manual::EmbedderOptions {
             dimensions,
@@ -2004,6 +2683,8 @@ impl SubEmbedderOptions {
         request: serde_json::Value,
         response: serde_json::Value,
         headers: Setting<BTreeMap<String, String>>,
+        media: Setting<MediaOptions>,
+        late_interaction: Setting<bool>,
         dimensions: Setting<usize>,
         distribution: Setting<DistributionShift>,
     ) -> Self {
@@ -2015,6 +2696,8 @@ impl SubEmbedderOptions {
             response,
             distribution: distribution.set(),
             headers: headers.set().unwrap_or_default(),
+            media: media.set(),
+            late_interaction: late_interaction.set(),
         })
     }
     fn ollama(
@@ -2051,6 +2734,7 @@ impl From<SubEmbedderOptions> for EmbedderOptions {
                 Self::UserProvided(embedder_options)
             }
             SubEmbedderOptions::Rest(embedder_options) => Self::Rest(embedder_options),
+            SubEmbedderOptions::Onnx(embedder_options) => Self::Onnx(embedder_options),
         }
     }
 }
@@ -0,0 +1,438 @@
+//! Sparse embedders and the storage of their embeddings.
+//!
+//! A sparse embedding associates a weight to a few token ids of a large vocabulary, like the
+//! embeddings generated by SPLADE models. They are passed around as regular [`Embedding`]s,
+//! packed as consecutive `(token id, weight)` pairs, and stored in an inverted index in which
+//! the documents are retrieved by dot product with the query.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use deserr::{take_cf_content, DeserializeError, Deserr, IntoValue, ValuePointerRef};
+use heed::types::{Bytes, DecodeIgnore};
+use heed::{RoTxn, RwTxn};
+use ordered_float::OrderedFloat;
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+
+use super::error::EmbedError;
+use super::{rest, ArroyStats, DistributionShift, Embedding, EmbeddingCache, NewEmbedderError};
+use crate::{DocumentId, ThreadPoolNoAbort};
+
+/// Token ids are packed as `f32` in the embeddings, which represent exactly the integers below `2^24`.
+pub const MAX_TOKEN_ID: u32 = 1 << 24;
+
+/// Stores the embeddings of the sparse embedders, see [`SparseVectorStore`] for the layout.
+pub type SparseVectorDatabase = heed::Database<Bytes, Bytes>;
+
+/// A sparse embedding, as a list of token ids sorted in ascending order and their weights.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SparseVector {
+    tokens: Vec<u32>,
+    weights: Vec<f32>,
+}
+
+impl SparseVector {
+    /// Builds a sparse vector from `(token id, weight)` pairs in any order.
+    ///
+    /// When a token appears several times, its highest weight is kept,
+    /// and the tokens with a null weight are removed.
+    pub fn new(pairs: impl IntoIterator<Item = (u32, f32)>) -> Self {
+        let mut pairs: Vec<_> = pairs.into_iter().collect();
+        pairs.sort_unstable_by_key(|&(token, weight)| (token, Reverse(OrderedFloat(weight))));
+        pairs.dedup_by_key(|(token, _)| *token);
+        pairs.retain(|(_, weight)| *weight != 0.0);
+        let (tokens, weights) = pairs.into_iter().unzip();
+        Self { tokens, weights }
+    }
+
+    /// Reads the sparse vector packed in an embedding by [`Self::pack`].
+    ///
+    /// The concatenation of several packed vectors is read as their union.
+    pub fn unpack(embedding: &[f32]) -> Self {
+        Self::new(embedding.chunks_exact(2).map(|pair| (pair[0] as u32, pair[1])))
+    }
+
+    /// Packs the sparse vector in an embedding of consecutive `(token id, weight)` pairs.
+    pub fn pack(&self) -> Embedding {
+        self.iter().flat_map(|(token, weight)| [token as f32, weight]).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, f32)> + '_ {
+        self.tokens.iter().copied().zip(self.weights.iter().copied())
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The dot product of the two sparse vectors, only the tokens they share contribute to it.
+    pub fn dot(&self, other: &Self) -> f32 {
+        let (mut left, mut right) = (0, 0);
+        let mut dot = 0.0;
+        while left < self.len() && right < other.len() {
+            match self.tokens[left].cmp(&other.tokens[right]) {
+                std::cmp::Ordering::Less => left += 1,
+                std::cmp::Ordering::Greater => right += 1,
+                std::cmp::Ordering::Equal => {
+                    dot += self.weights[left] * other.weights[right];
+                    left += 1;
+                    right += 1;
+                }
+            }
+        }
+        dot
+    }
+
+    /// Deserializes a sparse vector from the entries of a map of token ids to weights.
+    pub fn deserialize_entries<E: DeserializeError, V: IntoValue>(
+        entries: impl IntoIterator<Item = (String, V)>,
+        location: ValuePointerRef<'_>,
+    ) -> Result<Self, E> {
+        let mut pairs = Vec::new();
+        for (key, value) in entries {
+            let location = location.push_key(&key);
+            let Some(token) = parse_token_id(&key) else {
+                return Err(take_cf_content(E::error::<std::convert::Infallible>(
+                    None,
+                    deserr::ErrorKind::Unexpected {
+                        msg: format!(
+                            "expected a token id between 0 and {}, got `{key}`",
+                            MAX_TOKEN_ID - 1
+                        ),
+                    },
+                    location,
+                )));
+            };
+            let weight = f32::deserialize_from_value(value.into_value(), location)?;
+            pairs.push((token, weight));
+        }
+        Ok(Self::new(pairs))
+    }
+}
+
+/// Parses the key of a sparse vector map, returns `None` if it is not a valid token id.
+pub fn parse_token_id(key: &str) -> Option<u32> {
+    key.parse().ok().filter(|token| *token < MAX_TOKEN_ID)
+}
+
+impl<E: DeserializeError> Deserr<E> for SparseVector {
+    fn deserialize_from_value<V: IntoValue>(
+        value: deserr::Value<V>,
+        location: ValuePointerRef<'_>,
+    ) -> Result<Self, E> {
+        match value {
+            deserr::Value::Map(map) => {
+                Self::deserialize_entries(deserr::Map::into_iter(map), location)
+            }
+            value => Err(take_cf_content(E::error(
+                None,
+                deserr::ErrorKind::IncorrectValueKind {
+                    actual: value,
+                    accepted: &[deserr::ValueKind::Map],
+                },
+                location,
+            ))),
+        }
+    }
+}
+
+impl Serialize for SparseVector {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter().map(|(token, weight)| (token.to_string(), weight)))
+    }
+}
+
+impl<'de> Deserialize<'de> for SparseVector {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = HashMap::<String, f32>::deserialize(deserializer)?;
+        let pairs = map.into_iter().map(|(key, weight)| match parse_token_id(&key) {
+            Some(token) => Ok((token, weight)),
+            None => Err(serde::de::Error::custom(format!(
+                "expected a token id between 0 and {}, got `{key}`",
+                MAX_TOKEN_ID - 1
+            ))),
+        });
+        pairs.collect::<Result<Vec<_>, _>>().map(Self::new)
+    }
+}
+
+/// The sparse embeddings of an embedder, stored in the [`SparseVectorDatabase`].
+///
+/// All the keys start with the embedder index, followed by either:
+/// - `0` and the document id, mapped to the whole sparse embedding of the document,
+/// - `1`, a token id and a document id, mapped to the weight of the token in the document.
+///
+/// The latter keys form the posting lists of the tokens, which are scanned to search the embedder.
+#[derive(Debug, Clone, Copy)]
+pub struct SparseVectorStore {
+    database: SparseVectorDatabase,
+    embedder_index: u8,
+}
+
+const DOCUMENT_KEY_TAG: u8 = 0;
+const POSTING_KEY_TAG: u8 = 1;
+
+impl SparseVectorStore {
+    pub fn new(database: SparseVectorDatabase, embedder_index: u8) -> Self {
+        Self { database, embedder_index }
+    }
+
+    fn document_key(&self, docid: DocumentId) -> [u8; 6] {
+        let mut key = [self.embedder_index, DOCUMENT_KEY_TAG, 0, 0, 0, 0];
+        key[2..].copy_from_slice(&docid.to_be_bytes());
+        key
+    }
+
+    fn postings_prefix(&self, token: u32) -> [u8; 6] {
+        let mut key = [self.embedder_index, POSTING_KEY_TAG, 0, 0, 0, 0];
+        key[2..].copy_from_slice(&token.to_be_bytes());
+        key
+    }
+
+    fn posting_key(&self, token: u32, docid: DocumentId) -> [u8; 10] {
+        let mut key = [0; 10];
+        key[..6].copy_from_slice(&self.postings_prefix(token));
+        key[6..].copy_from_slice(&docid.to_be_bytes());
+        key
+    }
+
+    /// Replaces the embedding of the document, an empty vector removes it.
+    pub fn put(
+        &self,
+        wtxn: &mut RwTxn,
+        docid: DocumentId,
+        vector: &SparseVector,
+    ) -> heed::Result<()> {
+        self.delete(wtxn, docid)?;
+        if vector.is_empty() {
+            return Ok(());
+        }
+        let mut bytes = Vec::with_capacity(vector.len() * 8);
+        for (token, weight) in vector.iter() {
+            bytes.extend_from_slice(&token.to_le_bytes());
+            bytes.extend_from_slice(&weight.to_le_bytes());
+            self.database.put(wtxn, &self.posting_key(token, docid), &weight.to_le_bytes())?;
+        }
+        self.database.put(wtxn, &self.document_key(docid), &bytes)
+    }
+
+    pub fn get(&self, rtxn: &RoTxn, docid: DocumentId) -> heed::Result<Option<SparseVector>> {
+        let Some(bytes) = self.database.get(rtxn, &self.document_key(docid))? else {
+            return Ok(None);
+        };
+        Ok(Some(SparseVector::new(bytes.chunks_exact(8).map(|pair| {
+            let (token, weight) = pair.split_at(4);
+            (
+                u32::from_le_bytes(token.try_into().unwrap()),
+                f32::from_le_bytes(weight.try_into().unwrap()),
+            )
+        }))))
+    }
+
+    /// Removes the embedding of the document, returns whether it had one.
+    pub fn delete(&self, wtxn: &mut RwTxn, docid: DocumentId) -> heed::Result<bool> {
+        let Some(vector) = self.get(wtxn, docid)? else {
+            return Ok(false);
+        };
+        for (token, _) in vector.iter() {
+            self.database.delete(wtxn, &self.posting_key(token, docid))?;
+        }
+        self.database.delete(wtxn, &self.document_key(docid))
+    }
+
+    /// Removes all the embeddings of the embedder.
+    pub fn clear(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        let mut iter = self
+            .database
+            .remap_data_type::<DecodeIgnore>()
+            .prefix_iter_mut(wtxn, &[self.embedder_index])?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the LMDB database.
+            unsafe { iter.del_current()? };
+        }
+        Ok(())
+    }
+
+    /// Returns the `limit` documents with the highest dot product with the query,
+    /// along with this dot product, in decreasing order.
+    ///
+    /// Only the documents of `filter` are returned when it is specified.
+    pub fn search(
+        &self,
+        rtxn: &RoTxn,
+        query: &SparseVector,
+        limit: usize,
+        filter: Option<&RoaringBitmap>,
+    ) -> heed::Result<Vec<(DocumentId, f32)>> {
+        let mut scores: HashMap<DocumentId, f32> = HashMap::new();
+        for (token, query_weight) in query.iter() {
+            for result in self.database.prefix_iter(rtxn, &self.postings_prefix(token))? {
+                let (key, weight) = result?;
+                let docid = u32::from_be_bytes(key[6..].try_into().unwrap());
+                if filter.is_some_and(|filter| !filter.contains(docid)) {
+                    continue;
+                }
+                let weight = f32::from_le_bytes(weight.try_into().unwrap());
+                *scores.entry(docid).or_default() += query_weight * weight;
+            }
+        }
+
+        let mut results: Vec<_> = scores.into_iter().collect();
+        results.sort_unstable_by_key(|&(docid, score)| (Reverse(OrderedFloat(score)), docid));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    pub fn aggregate_stats(&self, rtxn: &RoTxn, stats: &mut ArroyStats) -> heed::Result<()> {
+        let prefix = [self.embedder_index, DOCUMENT_KEY_TAG];
+        for result in self.database.remap_data_type::<DecodeIgnore>().prefix_iter(rtxn, &prefix)? {
+            let (key, ()) = result?;
+            stats.documents.insert(u32::from_be_bytes(key[2..].try_into().unwrap()));
+            stats.number_of_embeddings += 1;
+        }
+        Ok(())
+    }
+
+    /// The size taken by the embeddings and the posting lists of the embedder, in bytes.
+    pub fn storage_size(&self, rtxn: &RoTxn) -> heed::Result<u64> {
+        let mut size = 0;
+        for result in self.database.prefix_iter(rtxn, &[self.embedder_index])? {
+            let (key, value) = result?;
+            size += (key.len() + value.len()) as u64;
+        }
+        Ok(size)
+    }
+}
+
+/// An embedder generating sparse embeddings, either provided by the user in the documents
+/// or computed by a REST embedding server.
+#[derive(Debug)]
+pub struct Embedder {
+    rest: Option<rest::Embedder>,
+    distribution: Option<DistributionShift>,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EmbedderOptions {
+    /// The server computing the sparse embeddings, `None` when they are provided by the user.
+    pub rest: Option<rest::EmbedderOptions>,
+    pub distribution: Option<DistributionShift>,
+}
+
+impl Embedder {
+    pub fn new(options: EmbedderOptions, cache: EmbeddingCache) -> Result<Self, NewEmbedderError> {
+        let rest = options.rest.map(|rest| rest::Embedder::new_sparse(rest, cache)).transpose()?;
+        Ok(Self { rest, distribution: options.distribution })
+    }
+
+    fn rest_embedder(&self, text: &str) -> Result<&rest::Embedder, EmbedError> {
+        self.rest
+            .as_ref()
+            .ok_or_else(|| EmbedError::embed_on_manual_embedder(text.chars().take(250).collect()))
+    }
+
+    pub fn embed_search(
+        &self,
+        text: &str,
+        deadline: Option<Instant>,
+    ) -> Result<Embedding, EmbedError> {
+        self.rest_embedder(text)?.embed_search(text, deadline)
+    }
+
+    pub fn embed_index(
+        &self,
+        text_chunks: Vec<Vec<String>>,
+        threads: &ThreadPoolNoAbort,
+    ) -> Result<Vec<Vec<Embedding>>, EmbedError> {
+        let Some(first) = text_chunks.iter().flatten().next() else {
+            return Ok(vec![Vec::new(); text_chunks.len()]);
+        };
+        self.rest_embedder(first)?.embed_index(text_chunks, threads)
+    }
+
+    pub(crate) fn embed_index_ref(
+        &self,
+        texts: &[&str],
+        threads: &ThreadPoolNoAbort,
+    ) -> Result<Vec<Embedding>, EmbedError> {
+        let Some(first) = texts.first() else {
+            return Ok(Vec::new());
+        };
+        self.rest_embedder(first)?.embed_index_ref(texts, threads)
+    }
+
+    pub fn chunk_count_hint(&self) -> usize {
+        self.rest.as_ref().map_or(100, |rest| rest.chunk_count_hint())
+    }
+
+    pub fn prompt_count_in_chunk_hint(&self) -> usize {
+        self.rest.as_ref().map_or(1, |rest| rest.prompt_count_in_chunk_hint())
+    }
+
+    pub fn distribution(&self) -> Option<DistributionShift> {
+        self.distribution
+    }
+
+    /// Whether the embeddings are computed from the documents rather than provided by the user.
+    pub fn uses_document_template(&self) -> bool {
+        self.rest.is_some()
+    }
+
+    pub(super) fn cache(&self) -> Option<&EmbeddingCache> {
+        self.rest.as_ref().map(|rest| rest.cache())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sparse_from_str(s: &str) -> Result<SparseVector, deserr::errors::JsonError> {
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        deserr::deserialize(value)
+    }
+
+    #[test]
+    fn pack_roundtrip() {
+        let vector = SparseVector::new([(30_000, 0.5), (3, 1.25), (MAX_TOKEN_ID - 1, 0.125)]);
+        let packed = vector.pack();
+        assert_eq!(packed, vec![3.0, 1.25, 30_000.0, 0.5, (MAX_TOKEN_ID - 1) as f32, 0.125]);
+        assert_eq!(SparseVector::unpack(&packed), vector);
+    }
+
+    #[test]
+    fn union_keeps_highest_weight() {
+        let mut packed = SparseVector::new([(1, 0.5), (2, 0.25)]).pack();
+        packed.extend(SparseVector::new([(2, 0.75), (3, 0.0), (4, 1.0)]).pack());
+        let union = SparseVector::unpack(&packed);
+        assert_eq!(union.iter().collect::<Vec<_>>(), vec![(1, 0.5), (2, 0.75), (4, 1.0)]);
+    }
+
+    #[test]
+    fn dot_product() {
+        let left = SparseVector::new([(1, 2.0), (5, 1.0), (9, 0.5)]);
+        let right = SparseVector::new([(0, 3.0), (5, 4.0), (9, 2.0), (12, 1.0)]);
+        assert_eq!(left.dot(&right), 5.0);
+        assert_eq!(right.dot(&left), 5.0);
+        assert_eq!(left.dot(&SparseVector::default()), 0.0);
+    }
+
+    #[test]
+    fn deserialize() {
+        let vector = sparse_from_str(r#"{ "12": 0.5, "3": 1, "40000": -0.25 }"#).unwrap();
+        assert_eq!(vector.iter().collect::<Vec<_>>(), vec![(3, 1.0), (12, 0.5), (40_000, -0.25)]);
+        assert_eq!(serde_json::to_string(&vector).unwrap(), r#"{"3":1.0,"12":0.5,"40000":-0.25}"#);
+
+        let error = sparse_from_str(r#"{ "hello": 0.5 }"#).unwrap_err();
+        assert!(error.to_string().contains("expected a token id between 0 and 16777215"));
+        let error = sparse_from_str(r#"{ "16777216": 0.5 }"#).unwrap_err();
+        assert!(error.to_string().contains("expected a token id between 0 and 16777215"));
+        assert!(sparse_from_str("[0.5]").is_err());
+    }
+}